@@ -13,7 +13,7 @@ use anyhow::{Context, Result};
 use schemars::{
   schema::{
     ArrayValidation, InstanceType, Metadata, ObjectValidation, RootSchema, Schema, SchemaObject,
-    SubschemaValidation,
+    SingleOrVec, SubschemaValidation,
   },
   schema_for,
 };
@@ -21,8 +21,9 @@ use tauri_utils::{
   acl::{
     capability::{Capability, CapabilityFile},
     manifest::Manifest,
-    APP_ACL_KEY,
+    APP_ACL_KEY, CAPABILITIES_SCHEMA_FILE_NAME as PROJECT_CAPABILITIES_SCHEMA_FILE_NAME,
   },
+  config::Config,
   platform::Target,
 };
 
@@ -102,7 +103,10 @@ impl AppManifest {
   }
 }
 
-fn capabilities_schema(acl_manifests: &BTreeMap<String, Manifest>) -> RootSchema {
+fn capabilities_schema(
+  acl_manifests: &BTreeMap<String, Manifest>,
+  window_labels: &[String],
+) -> RootSchema {
   let mut schema = schema_for!(CapabilityFile);
 
   fn schema_from(key: &str, id: &str, description: Option<&str>) -> Schema {
@@ -164,6 +168,19 @@ fn capabilities_schema(acl_manifests: &BTreeMap<String, Manifest>) -> RootSchema
     }));
   }
 
+  if !window_labels.is_empty() {
+    if let Some(Schema::Object(obj)) = schema.definitions.get_mut("Capability") {
+      if let Some(Schema::Object(windows)) = obj.object().properties.get_mut("windows") {
+        windows
+          .array()
+          .items
+          .replace(SingleOrVec::Single(Box::new(window_label_schema(
+            window_labels,
+          ))));
+      }
+    }
+  }
+
   let mut definitions = Vec::new();
 
   if let Some(Schema::Object(obj)) = schema.definitions.get_mut("PermissionEntry") {
@@ -263,12 +280,68 @@ fn capabilities_schema(acl_manifests: &BTreeMap<String, Manifest>) -> RootSchema
   schema
 }
 
-pub fn generate_schema(acl_manifests: &BTreeMap<String, Manifest>, target: Target) -> Result<()> {
-  let schema = capabilities_schema(acl_manifests);
+/// Suggests the window labels defined in the configuration while still allowing any label or glob pattern.
+fn window_label_schema(window_labels: &[String]) -> Schema {
+  Schema::Object(SchemaObject {
+    subschemas: Some(Box::new(SubschemaValidation {
+      any_of: Some(vec![
+        Schema::Object(SchemaObject {
+          metadata: Some(Box::new(Metadata {
+            description: Some("Window label defined in the Tauri configuration".into()),
+            ..Default::default()
+          })),
+          instance_type: Some(InstanceType::String.into()),
+          enum_values: Some(
+            window_labels
+              .iter()
+              .map(|label| serde_json::Value::String(label.clone()))
+              .collect(),
+          ),
+          ..Default::default()
+        }),
+        Schema::Object(SchemaObject {
+          metadata: Some(Box::new(Metadata {
+            description: Some("Window label or glob pattern".into()),
+            ..Default::default()
+          })),
+          instance_type: Some(InstanceType::String.into()),
+          ..Default::default()
+        }),
+      ]),
+      ..Default::default()
+    })),
+    ..Default::default()
+  })
+}
+
+/// Labels of the windows defined in the configuration, sorted so the generated schema is deterministic.
+fn window_labels(config: &Config) -> Vec<String> {
+  config
+    .app
+    .windows
+    .iter()
+    .map(|window| window.label.clone())
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect()
+}
+
+pub fn generate_schema(
+  acl_manifests: &BTreeMap<String, Manifest>,
+  config: &Config,
+  target: Target,
+) -> Result<()> {
+  let schema = capabilities_schema(acl_manifests, &window_labels(config));
   let schema_str = serde_json::to_string_pretty(&schema).unwrap();
   let out_dir = PathBuf::from(CAPABILITIES_SCHEMA_FOLDER_PATH);
   create_dir_all(&out_dir).context("unable to create schema output directory")?;
 
+  // target agnostic file name, referenced by capability files created with the CLI
+  let project_schema_path = out_dir.join(PROJECT_CAPABILITIES_SCHEMA_FILE_NAME);
+  if schema_str != read_to_string(&project_schema_path).unwrap_or_default() {
+    write(&project_schema_path, &schema_str)?;
+  }
+
   let schema_path = out_dir.join(format!("{target}-{CAPABILITIES_SCHEMA_FILE_NAME}"));
   if schema_str != read_to_string(&schema_path).unwrap_or_default() {
     write(&schema_path, schema_str)?;
@@ -521,3 +594,151 @@ pub fn validate_capabilities(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::BTreeMap, fs::read_to_string, path::Path};
+
+  use serde_json::{json, Value};
+  use tauri_utils::{
+    acl::manifest::{Manifest, PermissionFile},
+    config::Config,
+  };
+
+  use super::{capabilities_schema, window_labels};
+
+  fn fixture_path() -> &'static Path {
+    Path::new(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/tests/fixtures/capabilities-schema"
+    ))
+  }
+
+  fn permission_file(plugin: &str, name: &str) -> PermissionFile {
+    toml::from_str(&read_to_string(fixture_path().join(plugin).join(name)).unwrap()).unwrap()
+  }
+
+  /// Loads the fixture plugin set, with the permission files read in the given order.
+  fn manifests(fs_files: &[&str]) -> BTreeMap<String, Manifest> {
+    let fs_scope: Value =
+      serde_json::from_str(&read_to_string(fixture_path().join("fs/scope.json")).unwrap()).unwrap();
+
+    let mut manifests = BTreeMap::new();
+    manifests.insert(
+      "window".to_string(),
+      Manifest::new(vec![permission_file("window", "commands.toml")], None),
+    );
+    manifests.insert(
+      "fs".to_string(),
+      Manifest::new(
+        fs_files
+          .iter()
+          .map(|name| permission_file("fs", name))
+          .collect(),
+        Some(fs_scope),
+      ),
+    );
+    manifests
+  }
+
+  fn schema(fs_files: &[&str]) -> Value {
+    let config: Config =
+      serde_json::from_str(&read_to_string(fixture_path().join("tauri.conf.json")).unwrap())
+        .unwrap();
+    serde_json::to_value(capabilities_schema(
+      &manifests(fs_files),
+      &window_labels(&config),
+    ))
+    .unwrap()
+  }
+
+  #[test]
+  fn enumerates_permission_identifiers() {
+    let schema = schema(&["default.toml", "commands.toml"]);
+
+    let identifiers = schema["definitions"]["Identifier"]["oneOf"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|s| {
+        (
+          s["enum"][0].as_str().unwrap(),
+          s["description"].as_str().unwrap(),
+        )
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      identifiers,
+      vec![
+        (
+          "fs:read-all",
+          "fs:read-all -> Allows reading and listing files."
+        ),
+        ("fs:default", "fs:default -> Allows reading files."),
+        (
+          "fs:allow-read-dir",
+          "fs:allow-read-dir -> Enables the read_dir command."
+        ),
+        (
+          "fs:allow-read-file",
+          "fs:allow-read-file -> Enables the read_file command."
+        ),
+        (
+          "fs:deny-read-file",
+          "fs:deny-read-file -> Denies the read_file command."
+        ),
+        (
+          "window:default",
+          "window:default -> Allows closing windows."
+        ),
+        (
+          "window:allow-close",
+          "window:allow-close -> Enables the close command."
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn suggests_configured_window_labels() {
+    let schema = schema(&["default.toml", "commands.toml"]);
+    let windows = &schema["definitions"]["Capability"]["properties"]["windows"];
+    assert_eq!(
+      windows["items"]["anyOf"][0]["enum"],
+      json!(["main", "settings"])
+    );
+    // glob patterns and windows created at runtime are still allowed
+    assert_eq!(windows["items"]["anyOf"][1]["type"], "string");
+  }
+
+  #[test]
+  fn describes_plugin_scopes() {
+    let schema = schema(&["default.toml", "commands.toml"]);
+    let scoped_entries = schema["definitions"]["PermissionEntry"]["anyOf"]
+      .as_array()
+      .unwrap()
+      .last()
+      .unwrap()["oneOf"]
+      .as_array()
+      .unwrap();
+
+    // only the fs plugin declares a global scope schema
+    assert_eq!(scoped_entries.len(), 1);
+    let properties = &scoped_entries[0]["properties"];
+    assert_eq!(
+      properties["identifier"]["oneOf"][0]["enum"],
+      json!(["fs:default"])
+    );
+    for key in ["allow", "deny"] {
+      assert_eq!(properties[key]["items"]["required"], json!(["path"]));
+    }
+  }
+
+  #[test]
+  fn deterministic() {
+    let schema_str = |files: &[&str]| serde_json::to_string_pretty(&schema(files)).unwrap();
+    let expected = schema_str(&["default.toml", "commands.toml"]);
+    assert_eq!(expected, schema_str(&["default.toml", "commands.toml"]));
+    assert_eq!(expected, schema_str(&["commands.toml", "default.toml"]));
+  }
+}
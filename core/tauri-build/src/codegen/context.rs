@@ -84,7 +84,14 @@ impl CodegenContext {
     let (config, config_parent) = tauri_codegen::get_config(&self.config_path)?;
 
     // rerun if changed
-    match &config.build.frontend_dist {
+    // the frontend is served from the dev server instead of being embedded in development
+    let embeds_frontend = !(crate::is_dev() && config.build.dev_url.is_some());
+    match config
+      .build
+      .frontend_dist
+      .as_ref()
+      .filter(|_| embeds_frontend)
+    {
       Some(FrontendDist::Directory(p)) => {
         let dist_path = config_parent.join(p);
         if dist_path.exists() {
@@ -131,6 +138,7 @@ impl CodegenContext {
       root: quote::quote!(::tauri),
       capabilities: self.capabilities,
       assets: None,
+      stub_assets: false,
       test: false,
     })?;
 
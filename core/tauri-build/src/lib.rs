@@ -17,13 +17,16 @@ pub use anyhow::Result;
 use cargo_toml::Manifest;
 
 use tauri_utils::{
-  acl::{build::parse_capabilities, APP_ACL_KEY},
-  config::{BundleResources, Config, WebviewInstallMode},
-  resources::{external_binaries, ResourcePaths},
+  acl::{
+    build::{capability_files, parse_capabilities},
+    APP_ACL_KEY,
+  },
+  config::{BundleResources, Config, ResourceCollision, WebviewInstallMode},
+  resources::{external_binaries, resolve_collisions, ResourcePaths},
 };
 
 use std::{
-  collections::HashMap,
+  collections::{BTreeMap, HashMap},
   env::var_os,
   fs::copy,
   path::{Path, PathBuf},
@@ -44,6 +47,7 @@ pub use acl::{AppManifest, InlinedPlugin};
 
 const ACL_MANIFESTS_FILE_NAME: &str = "acl-manifests.json";
 const CAPABILITIES_FILE_NAME: &str = "capabilities.json";
+const DEV_RESOURCES_FILE_NAME: &str = "dev-resources.json";
 
 fn copy_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<()> {
   let from = from.as_ref();
@@ -91,22 +95,29 @@ fn copy_binaries(
   Ok(())
 }
 
-/// Copies resources to a path.
-fn copy_resources(resources: ResourcePaths<'_>, path: &Path) -> Result<()> {
+/// Copies resources to a path, the resources bundled at the same target being resolved with `collision`.
+///
+/// Returns the map of each resource target to its canonicalized source path.
+fn copy_resources(
+  resources: ResourcePaths<'_>,
+  collision: ResourceCollision,
+  path: &Path,
+) -> Result<BTreeMap<PathBuf, PathBuf>> {
   let path = path.canonicalize()?;
-  for resource in resources.iter() {
-    let resource = resource?;
-
+  let resources = resources.iter().collect::<tauri_utils::Result<Vec<_>>>()?;
+  let mut resource_map = BTreeMap::new();
+  for resource in resolve_collisions(resources, collision)? {
     println!("cargo:rerun-if-changed={}", resource.path().display());
 
     // avoid copying the resource if target is the same as source
     let src = resource.path().canonicalize()?;
     let target = path.join(resource.target());
     if src != target {
-      copy_file(src, target)?;
+      copy_file(&src, target)?;
     }
+    resource_map.insert(resource.target().to_path_buf(), src);
   }
-  Ok(())
+  Ok(resource_map)
 }
 
 #[cfg(unix)]
@@ -356,7 +367,10 @@ impl Attributes {
 
   /// Set the glob pattern to be used to find the capabilities.
   ///
-  /// **Note:** You must emit [rerun-if-changed] instructions for your capabilities directory.
+  /// Each capability file matching the pattern is tracked with a [rerun-if-changed] instruction.
+  ///
+  /// **Note:** You must emit a [rerun-if-changed] instruction for your capabilities directory
+  /// so capability files added later are detected.
   ///
   /// [rerun-if-changed]: https://doc.rust-lang.org/cargo/reference/build-scripts.html#rerun-if-changed
   #[must_use]
@@ -435,12 +449,6 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
   use anyhow::anyhow;
 
   println!("cargo:rerun-if-env-changed=TAURI_CONFIG");
-  #[cfg(feature = "config-json")]
-  println!("cargo:rerun-if-changed=tauri.conf.json");
-  #[cfg(feature = "config-json5")]
-  println!("cargo:rerun-if-changed=tauri.conf.json5");
-  #[cfg(feature = "config-toml")]
-  println!("cargo:rerun-if-changed=Tauri.toml");
 
   let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
   let mobile = target_os == "ios" || target_os == "android";
@@ -450,6 +458,13 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
   let target_triple = std::env::var("TARGET").unwrap();
   let target = tauri_utils::platform::Target::from_triple(&target_triple);
 
+  // only the existing configuration files are tracked since cargo reruns the build script on every build while a tracked path is missing,
+  // creating an optional one is detected through the configuration directory tracked at the end of the build
+  let config_dir = std::env::current_dir()?;
+  for config_file in tauri_utils::config::parse::configuration_files(target, &config_dir) {
+    println!("cargo:rerun-if-changed={}", config_file.display());
+  }
+
   let mut config = serde_json::from_value(tauri_utils::config::parse::read_from(
     target,
     std::env::current_dir().unwrap(),
@@ -458,6 +473,21 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     let merge_config: serde_json::Value = serde_json::from_str(&env)?;
     json_patch::merge(&mut config, &merge_config);
   }
+  // the version can be read from a file with a build number from an environment variable
+  if let Some(version) = config.get("version").and_then(|v| v.as_object()) {
+    if let Some(build_number_env) = version
+      .get("buildNumberEnv")
+      .or_else(|| version.get("build-number-env"))
+      .and_then(|v| v.as_str())
+    {
+      println!("cargo:rerun-if-env-changed={build_number_env}");
+    }
+    let version_file = version
+      .get("file")
+      .and_then(|f| f.as_str())
+      .unwrap_or("Cargo.toml");
+    println!("cargo:rerun-if-changed={version_file}");
+  }
   let config: Config = serde_json::from_value(config)?;
 
   let s = config.identifier.split('.');
@@ -517,13 +547,18 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     serde_json::to_string(&acl_manifests)?,
   )?;
 
-  let capabilities = if let Some(pattern) = attributes.capabilities_path_pattern {
-    parse_capabilities(pattern)?
-  } else {
+  let capabilities_path_pattern = attributes
+    .capabilities_path_pattern
+    .unwrap_or("./capabilities/**/*");
+  for capability_file in capability_files(capabilities_path_pattern)? {
+    println!("cargo:rerun-if-changed={}", capability_file.display());
+  }
+  // also track the default capabilities directory so added and removed files are detected
+  if attributes.capabilities_path_pattern.is_none() {
     println!("cargo:rerun-if-changed=capabilities");
-    parse_capabilities("./capabilities/**/*")?
-  };
-  acl::generate_schema(&acl_manifests, target)?;
+  }
+  let capabilities = parse_capabilities(capabilities_path_pattern)?;
+  acl::generate_schema(&acl_manifests, &config, target)?;
   acl::validate_capabilities(&acl_manifests, &capabilities)?;
 
   let capabilities_path = acl::save_capabilities(&capabilities)?;
@@ -574,12 +609,22 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
       resources.push(fixed_webview2_runtime_path.display().to_string());
     }
   }
-  match resources {
-    BundleResources::List(res) => {
-      copy_resources(ResourcePaths::new(res.as_slice(), true), target_dir)?
+  let collision = config.bundle.resource_collision;
+  let resource_map = match resources {
+    BundleResources::List(res) => copy_resources(
+      ResourcePaths::new(res.as_slice(), true),
+      collision,
+      target_dir,
+    )?,
+    BundleResources::Map(map) => {
+      copy_resources(ResourcePaths::from_map(&map, true), collision, target_dir)?
     }
-    BundleResources::Map(map) => copy_resources(ResourcePaths::from_map(&map, true), target_dir)?,
-  }
+  };
+  // record where each resource comes from so the app resolves them from their source in development
+  std::fs::write(
+    out_dir.join(DEV_RESOURCES_FILE_NAME),
+    serde_json::to_string(&resource_map)?,
+  )?;
 
   if target_triple.contains("darwin") {
     if let Some(frameworks) = &config.bundle.macos.frameworks {
@@ -638,7 +683,9 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
 
     if let Some(version_str) = &config.version {
       if let Ok(v) = Version::parse(version_str) {
-        let version = v.major << 48 | v.minor << 32 | v.patch << 16;
+        // the build number is the fourth field, e.g. `1.2.3.456` for `1.2.3+456`
+        let build = tauri_utils::config::version::build_number(&v, "Windows", 65535)?;
+        let version = v.major << 48 | v.minor << 32 | v.patch << 16 | build.unwrap_or_default();
         res.set_version_info(VersionInfo::FILEVERSION, version);
         res.set_version_info(VersionInfo::PRODUCTVERSION, version);
       }
@@ -657,6 +704,7 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     }
 
     if window_icon_path.exists() {
+      println!("cargo:rerun-if-changed={}", window_icon_path.display());
       res.set_icon_with_id(&window_icon_path.display().to_string(), "32512");
     } else {
       return Err(anyhow!(format!(
@@ -710,6 +758,9 @@ pub fn try_build(attributes: Attributes) -> Result<()> {
     codegen.try_build()?;
   }
 
+  // tracked last so cargo reports the exact files above when they change
+  println!("cargo:rerun-if-changed={}", config_dir.display());
+
   Ok(())
 }
 
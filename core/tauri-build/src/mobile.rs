@@ -5,8 +5,7 @@
 use std::{fs::write, path::PathBuf};
 
 use anyhow::{Context, Result};
-use semver::Version;
-use tauri_utils::config::Config;
+use tauri_utils::config::{version, Config};
 
 use crate::is_dev;
 
@@ -54,12 +53,17 @@ dependencies {"
 
   app_build_gradle.push_str("\n}");
 
-  if let Some(version) = config.version.as_ref() {
+  // the crate version, which cargo resolves from the workspace if it is inherited
+  let version = config
+    .version
+    .clone()
+    .or_else(|| std::env::var("CARGO_PKG_VERSION").ok());
+  if let Some(version) = version {
     app_tauri_properties.push(format!("tauri.android.versionName={version}"));
     if let Some(version_code) = config.bundle.android.version_code.as_ref() {
       app_tauri_properties.push(format!("tauri.android.versionCode={version_code}"));
-    } else if let Ok(version) = Version::parse(version) {
-      let mut version_code = version.major * 1000000 + version.minor * 1000 + version.patch;
+    } else {
+      let mut version_code = version::android_version_code(&version)?;
 
       if is_dev() {
         version_code = version_code.clamp(1, 2100000000);
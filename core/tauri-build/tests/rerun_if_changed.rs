@@ -0,0 +1,159 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Builds the `fixtures/rerun-if-changed` app and checks which units cargo considers dirty
+//! after editing the files consumed by `tauri-build`.
+
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+  thread::sleep,
+  time::Duration,
+};
+
+const FIXTURE_PACKAGE: &str = "rerun-if-changed-fixture";
+
+/// Copies the fixture to a temporary directory, generating its manifest.
+fn setup_fixture() -> PathBuf {
+  let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+  let fixture = manifest_dir.join("tests/fixtures/rerun-if-changed");
+  let app_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("rerun-if-changed-app");
+  let _ = std::fs::remove_dir_all(&app_dir);
+
+  for entry in walkdir::WalkDir::new(&fixture) {
+    let entry = entry.unwrap();
+    let dest = app_dir.join(entry.path().strip_prefix(&fixture).unwrap());
+    if entry.file_type().is_dir() {
+      std::fs::create_dir_all(dest).unwrap();
+    } else {
+      std::fs::copy(entry.path(), dest).unwrap();
+    }
+  }
+
+  std::fs::write(
+    app_dir.join("Cargo.toml"),
+    format!(
+      r#"[package]
+name = "{FIXTURE_PACKAGE}"
+version = "0.1.0"
+edition = "2021"
+
+[build-dependencies]
+tauri-build = {{ path = {:?} }}
+
+[workspace]
+"#,
+      manifest_dir.display().to_string()
+    ),
+  )
+  .unwrap();
+
+  app_dir
+}
+
+/// Builds the app, returning the `Dirty` lines cargo printed for the fingerprints that changed.
+fn build(app_dir: &Path) -> Vec<String> {
+  let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+    .args(["build", "--verbose", "--target-dir"])
+    .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("rerun-if-changed-target"))
+    .current_dir(app_dir)
+    // the fixture does not depend on the tauri crate that usually defines it
+    .env("DEP_TAURI_DEV", "false")
+    .output()
+    .unwrap();
+
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(output.status.success(), "failed to build fixture: {stderr}");
+
+  stderr
+    .lines()
+    .map(str::trim)
+    .filter(|line| line.starts_with("Dirty "))
+    .map(ToString::to_string)
+    .collect()
+}
+
+/// Rewrites the file so its modification time is newer than the last build.
+fn touch(path: &Path) {
+  // account for file systems with a coarse modification time resolution
+  sleep(Duration::from_secs(1));
+  let contents = std::fs::read(path).unwrap();
+  std::fs::write(path, contents).unwrap();
+}
+
+fn assert_only_fixture_dirty(dirty: &[String], file: &str) {
+  assert_eq!(dirty.len(), 1, "expected a single dirty unit: {dirty:?}");
+  assert!(
+    dirty[0].starts_with(&format!("Dirty {FIXTURE_PACKAGE} ")),
+    "unexpected dirty unit: {}",
+    dirty[0]
+  );
+  assert!(
+    dirty[0].contains(file),
+    "expected `{file}` to be reported as changed: {}",
+    dirty[0]
+  );
+}
+
+#[test]
+fn rerun_if_changed() {
+  let app_dir = setup_fixture();
+  let app_dir_name = app_dir.file_name().unwrap().to_str().unwrap();
+
+  build(&app_dir);
+  // the capability schemas generated by the first build are written to the tracked configuration directory
+  assert_only_fixture_dirty(&build(&app_dir), app_dir_name);
+
+  // every tracked path exists and the generated files are only written when they change,
+  // otherwise cargo would always consider the build script stale
+  let dirty = build(&app_dir);
+  assert!(dirty.is_empty(), "expected a fresh build: {dirty:?}");
+
+  // the whole configuration directory is tracked
+  touch(&app_dir.join("README.md"));
+  assert_only_fixture_dirty(&build(&app_dir), app_dir_name);
+  assert!(build(&app_dir).is_empty());
+
+  let capability = app_dir.join("capabilities").join("secondary.json");
+  touch(&capability);
+  assert_only_fixture_dirty(&build(&app_dir), "secondary.json");
+  assert!(build(&app_dir).is_empty());
+
+  touch(&app_dir.join("tauri.conf.json"));
+  assert_only_fixture_dirty(&build(&app_dir), "tauri.conf.json");
+
+  // new capability files are detected through the capabilities directory
+  sleep(Duration::from_secs(1));
+  std::fs::write(
+    app_dir.join("capabilities").join("new.json"),
+    r#"{ "identifier": "new", "windows": ["new"], "permissions": [] }"#,
+  )
+  .unwrap();
+  assert_only_fixture_dirty(&build(&app_dir), "capabilities");
+  assert!(build(&app_dir).is_empty());
+
+  // missing optional platform configuration files are not tracked,
+  // creating one is detected through the configuration directory
+  let platform_config_name = format!("tauri.{}.conf.json", host_platform());
+  let platform_config = app_dir.join(&platform_config_name);
+  let platform_config_contents = std::fs::read(&platform_config).unwrap();
+  std::fs::remove_file(&platform_config).unwrap();
+  assert_only_fixture_dirty(&build(&app_dir), &platform_config_name);
+  assert!(build(&app_dir).is_empty());
+  sleep(Duration::from_secs(1));
+  std::fs::write(&platform_config, platform_config_contents).unwrap();
+  assert_only_fixture_dirty(&build(&app_dir), app_dir_name);
+  assert!(build(&app_dir).is_empty());
+}
+
+/// The platform name of the platform-specific configuration files read on the host.
+fn host_platform() -> &'static str {
+  if cfg!(target_os = "macos") {
+    "macos"
+  } else if cfg!(windows) {
+    "windows"
+  } else {
+    "linux"
+  }
+}
@@ -9,7 +9,7 @@ use std::{ffi::OsStr, str::FromStr};
 
 use crate::{
   embedded_assets::{
-    ensure_out_dir, AssetOptions, CspHashes, EmbeddedAssets, EmbeddedAssetsResult,
+    ensure_cache_dir, AssetOptions, CspHashes, EmbeddedAssets, EmbeddedAssetsResult, StubAssets,
   },
   image::CachedIcon,
 };
@@ -32,6 +32,7 @@ use tauri_utils::{
 
 const ACL_MANIFESTS_FILE_NAME: &str = "acl-manifests.json";
 const CAPABILITIES_FILE_NAME: &str = "capabilities.json";
+const DEV_RESOURCES_FILE_NAME: &str = "dev-resources.json";
 
 /// Necessary data needed by [`context_codegen`] to generate code for a Tauri application context.
 pub struct ContextData {
@@ -43,6 +44,8 @@ pub struct ContextData {
   pub capabilities: Option<Vec<PathBuf>>,
   /// The custom assets implementation
   pub assets: Option<Expr>,
+  /// Serve a blank `index.html` instead of embedding the frontend, see [`StubAssets`].
+  pub stub_assets: bool,
   /// Skip runtime-only types generation for tests (e.g. embed-plist usage).
   pub test: bool,
 }
@@ -142,6 +145,7 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
     root,
     capabilities: additional_capabilities,
     assets,
+    stub_assets,
     test,
   } = data;
 
@@ -178,6 +182,9 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
 
   let assets = if let Some(assets) = assets {
     quote!(#assets)
+  } else if stub_assets {
+    let assets = StubAssets;
+    quote!(#assets)
   } else if dev && config.build.dev_url.is_some() {
     let assets = EmbeddedAssets::default();
     quote!(#assets)
@@ -209,7 +216,14 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
     quote!(#assets)
   };
 
-  let out_dir = ensure_out_dir()?;
+  let cache_dir = ensure_cache_dir()?;
+  // files generated by `tauri-build`, missing when the crate has no build script
+  let out_dir_file = |name: &str| {
+    cache_dir
+      .out_dir()
+      .map(|dir| dir.join(name))
+      .filter(|path| path.exists())
+  };
 
   let default_window_icon = {
     if target == Target::Windows {
@@ -290,12 +304,41 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
   );
 
   let with_tray_icon_code = if target.is_desktop() {
-    if let Some(tray) = &config.app.tray_icon {
+    let mut ids = BTreeMap::new();
+    for tray in config.app.all_tray_icons() {
+      if let Some(previous) = ids.insert(tray.id_or_default(), &tray.icon_path) {
+        panic!(
+          "The tray icons with the icons `{}` and `{}` have the same id `{}`, set a unique `id` on each of them",
+          previous.display(),
+          tray.icon_path.display(),
+          tray.id_or_default()
+        );
+      }
+    }
+
+    let tray_icon = if let Some(tray) = &config.app.tray_icon {
       let tray_icon_icon_path = config_parent.join(&tray.icon_path);
       let icon = CachedIcon::new(&root, &tray_icon_icon_path)?;
       quote!(context.set_tray_icon(::std::option::Option::Some(#icon));)
     } else {
       quote!()
+    };
+
+    let tray_icons = if config.app.tray_icons.is_empty() {
+      quote!()
+    } else {
+      let mut icons = Vec::new();
+      for tray in &config.app.tray_icons {
+        let id = tray.id_or_default();
+        let icon = CachedIcon::new(&root, &config_parent.join(&tray.icon_path))?;
+        icons.push(quote!((#id.to_string(), #icon)));
+      }
+      quote!(context.set_tray_icons(::std::collections::HashMap::from([#(#icons),*]));)
+    };
+
+    quote! {
+      #tray_icon
+      #tray_icons
     }
   } else {
     quote!()
@@ -315,9 +358,22 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
       if let Some(product_name) = &config.product_name {
         plist.insert("CFBundleName".into(), product_name.clone().into());
       }
-      if let Some(version) = &config.version {
-        plist.insert("CFBundleShortVersionString".into(), version.clone().into());
-        plist.insert("CFBundleVersion".into(), version.clone().into());
+      // the crate version, which cargo resolves from the workspace if it is inherited
+      let version = config
+        .version
+        .clone()
+        .or_else(|| std::env::var("CARGO_PKG_VERSION").ok());
+      if let Some(version) = version {
+        let (short_version, bundle_version) =
+          tauri_utils::config::version::apple_bundle_versions(&version, "macOS")?;
+        plist.insert(
+          "CFBundleShortVersionString".into(),
+          short_version.clone().into(),
+        );
+        plist.insert(
+          "CFBundleVersion".into(),
+          bundle_version.unwrap_or(short_version).into(),
+        );
       }
     }
 
@@ -379,24 +435,23 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
     }
   };
 
-  let acl_file_path = out_dir.join(ACL_MANIFESTS_FILE_NAME);
-  let acl: BTreeMap<String, Manifest> = if acl_file_path.exists() {
-    let acl_file =
-      std::fs::read_to_string(acl_file_path).expect("failed to read plugin manifest map");
-    serde_json::from_str(&acl_file).expect("failed to parse plugin manifest map")
-  } else {
-    Default::default()
-  };
+  let acl: BTreeMap<String, Manifest> =
+    if let Some(acl_file_path) = out_dir_file(ACL_MANIFESTS_FILE_NAME) {
+      let acl_file =
+        std::fs::read_to_string(acl_file_path).expect("failed to read plugin manifest map");
+      serde_json::from_str(&acl_file).expect("failed to parse plugin manifest map")
+    } else {
+      Default::default()
+    };
 
-  let capabilities_file_path = out_dir.join(CAPABILITIES_FILE_NAME);
-  let mut capabilities_from_files: BTreeMap<String, Capability> = if capabilities_file_path.exists()
-  {
-    let capabilities_file =
-      std::fs::read_to_string(capabilities_file_path).expect("failed to read capabilities");
-    serde_json::from_str(&capabilities_file).expect("failed to parse capabilities")
-  } else {
-    Default::default()
-  };
+  let mut capabilities_from_files: BTreeMap<String, Capability> =
+    if let Some(capabilities_file_path) = out_dir_file(CAPABILITIES_FILE_NAME) {
+      let capabilities_file =
+        std::fs::read_to_string(capabilities_file_path).expect("failed to read capabilities");
+      serde_json::from_str(&capabilities_file).expect("failed to parse capabilities")
+    } else {
+      Default::default()
+    };
 
   let mut capabilities = if config.app.security.capabilities.is_empty() {
     capabilities_from_files
@@ -450,28 +505,30 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
   let resolved = Resolved::resolve(&acl, capabilities, target).expect("failed to resolve ACL");
   let runtime_authority = quote!(#root::ipc::RuntimeAuthority::new(#acl_tokens, #resolved));
 
-  let plugin_global_api_script_file_list_path = out_dir.join(GLOBAL_API_SCRIPT_FILE_LIST_PATH);
-  let plugin_global_api_script =
-    if config.app.with_global_tauri && plugin_global_api_script_file_list_path.exists() {
-      let file_list_str = std::fs::read_to_string(plugin_global_api_script_file_list_path)
-        .expect("failed to read plugin global API script paths");
-      let file_list = serde_json::from_str::<Vec<PathBuf>>(&file_list_str)
-        .expect("failed to parse plugin global API script paths");
-
-      let mut plugins = Vec::new();
-      for path in file_list {
-        plugins.push(std::fs::read_to_string(&path).unwrap_or_else(|e| {
-          panic!(
-            "failed to read plugin global API script {}: {e}",
-            path.display()
-          )
-        }));
-      }
+  let plugin_global_api_script_file_list_path =
+    out_dir_file(GLOBAL_API_SCRIPT_FILE_LIST_PATH).filter(|_| config.app.with_global_tauri);
+  let plugin_global_api_script = if let Some(plugin_global_api_script_file_list_path) =
+    plugin_global_api_script_file_list_path
+  {
+    let file_list_str = std::fs::read_to_string(plugin_global_api_script_file_list_path)
+      .expect("failed to read plugin global API script paths");
+    let file_list = serde_json::from_str::<Vec<PathBuf>>(&file_list_str)
+      .expect("failed to parse plugin global API script paths");
+
+    let mut plugins = Vec::new();
+    for path in file_list {
+      plugins.push(std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+          "failed to read plugin global API script {}: {e}",
+          path.display()
+        )
+      }));
+    }
 
-      Some(plugins)
-    } else {
-      None
-    };
+    Some(plugins)
+  } else {
+    None
+  };
 
   let plugin_global_api_script = if let Some(scripts) = plugin_global_api_script {
     let scripts = scripts.into_iter().map(|s| quote!(#s));
@@ -489,6 +546,24 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
     quote!()
   };
 
+  let dev_resources_file_path = out_dir_file(DEV_RESOURCES_FILE_NAME).filter(|_| dev);
+  let maybe_dev_resources_setter = if let Some(dev_resources_file_path) = dev_resources_file_path {
+    let dev_resources_file = std::fs::read_to_string(dev_resources_file_path)
+      .expect("failed to read development resource map");
+    let dev_resources: BTreeMap<PathBuf, PathBuf> =
+      serde_json::from_str(&dev_resources_file).expect("failed to parse development resource map");
+    let dev_resources = dev_resources.iter().map(|(target, src)| {
+      let target = target.to_string_lossy();
+      let src = src.to_string_lossy();
+      quote!((#target, #src))
+    });
+    quote!({
+      context.with_dev_resources(&[#(#dev_resources),*]);
+    })
+  } else {
+    quote!()
+  };
+
   Ok(quote!({
     #[allow(unused_mut, clippy::let_and_return)]
     let mut context = #root::Context::new(
@@ -505,6 +580,7 @@ pub fn context_codegen(data: ContextData) -> EmbeddedAssetsResult<TokenStream> {
 
     #with_tray_icon_code
     #maybe_config_parent_setter
+    #maybe_dev_resources_setter
 
     context
   }))
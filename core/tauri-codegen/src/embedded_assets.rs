@@ -22,6 +22,9 @@ use brotli::enc::backward_references::BrotliEncoderParams;
 /// The subdirectory inside the target directory we want to place assets.
 const TARGET_PATH: &str = "tauri-codegen-assets";
 
+/// The subdirectory inside the temporary directory used when `$OUT_DIR` is not set.
+const SHARED_CACHE_PATH: &str = "tauri-codegen-cache";
+
 /// (key, (original filepath, compressed bytes))
 type Asset = (AssetKey, (PathBuf, PathBuf));
 
@@ -61,6 +64,9 @@ pub enum EmbeddedAssetsError {
 
   #[error("version error: {0}")]
   Version(#[from] semver::Error),
+
+  #[error(transparent)]
+  AppVersion(#[from] tauri_utils::config::version::VersionError),
 }
 
 pub type EmbeddedAssetsResult<T> = Result<T, EmbeddedAssetsError>;
@@ -432,13 +438,81 @@ impl ToTokens for EmbeddedAssets {
   }
 }
 
-pub(crate) fn ensure_out_dir() -> EmbeddedAssetsResult<PathBuf> {
-  let out_dir = std::env::var("OUT_DIR")
-    .map_err(|_| EmbeddedAssetsError::OutDir)
-    .map(PathBuf::from)
-    .and_then(|p| p.canonicalize().map_err(|_| EmbeddedAssetsError::OutDir))?;
+/// The `index.html` served by [`StubAssets`].
+const STUB_INDEX_HTML: &[u8] =
+  b"<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head><body></body></html>";
+
+/// Assets serving a blank `index.html`, generated instead of embedding the frontend for tests.
+pub struct StubAssets;
+
+impl ToTokens for StubAssets {
+  fn to_tokens(&self, tokens: &mut TokenStream) {
+    #[cfg(not(feature = "compression"))]
+    let index = STUB_INDEX_HTML.to_vec();
+
+    #[cfg(feature = "compression")]
+    let index = {
+      let mut index = Vec::new();
+      brotli::BrotliCompress(
+        &mut std::io::Cursor::new(STUB_INDEX_HTML),
+        &mut index,
+        &EmbeddedAssets::compression_settings(),
+      )
+      .expect("failed to compress the stub index.html");
+      index
+    };
+
+    let index = proc_macro2::Literal::byte_string(&index);
+    tokens.append_all(quote! {{
+        #[allow(unused_imports)]
+        use ::tauri::utils::assets::{CspHash, EmbeddedAssets, phf, phf::phf_map};
+        EmbeddedAssets::new(phf_map! { "/index.html" => #index }, &[], phf_map! {})
+    }});
+  }
+}
+
+/// The directory generated files are cached to.
+pub(crate) enum CacheDir {
+  /// `$OUT_DIR`, available when the crate has a build script.
+  OutDir(PathBuf),
+  /// A directory shared by all crates, used when generating a context without a build script (e.g. in integration tests).
+  ///
+  /// The files are named after their checksum so crates generating the same content reuse them.
+  Shared(PathBuf),
+}
+
+impl CacheDir {
+  pub(crate) fn path(&self) -> &Path {
+    match self {
+      Self::OutDir(path) | Self::Shared(path) => path,
+    }
+  }
+
+  /// The build script output directory, where `tauri-build` writes the ACL and resource files.
+  pub(crate) fn out_dir(&self) -> Option<&Path> {
+    match self {
+      Self::OutDir(path) => Some(path),
+      Self::Shared(_) => None,
+    }
+  }
+}
+
+/// Returns the directory to cache generated files to, making sure it exists.
+///
+/// Falls back to [`CacheDir::Shared`] when `$OUT_DIR` is not set.
+pub(crate) fn ensure_cache_dir() -> EmbeddedAssetsResult<CacheDir> {
+  let dir = match std::env::var_os("OUT_DIR") {
+    Some(out_dir) => PathBuf::from(out_dir)
+      .canonicalize()
+      .map(CacheDir::OutDir)
+      .map_err(|_| EmbeddedAssetsError::OutDir)?,
+    None => CacheDir::Shared(std::env::temp_dir().join(SHARED_CACHE_PATH)),
+  };
 
   // make sure that our output directory is created
-  std::fs::create_dir_all(&out_dir).map_err(|_| EmbeddedAssetsError::OutDir)?;
-  Ok(out_dir)
+  std::fs::create_dir_all(dir.path()).map_err(|error| EmbeddedAssetsError::AssetWrite {
+    path: dir.path().to_path_buf(),
+    error,
+  })?;
+  Ok(dir)
 }
@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: MIT
 
 use crate::{
-  embedded_assets::{EmbeddedAssetsError, EmbeddedAssetsResult},
-  Cached,
+  checksum,
+  embedded_assets::{ensure_cache_dir, EmbeddedAssetsError, EmbeddedAssetsResult},
+  write_shared, Cached,
 };
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
@@ -49,52 +50,81 @@ impl CachedIcon {
 
   /// Cache an ICO icon as RGBA data, see [`ImageFormat::Image`].
   pub fn new_ico(root: &TokenStream, icon: &Path) -> EmbeddedAssetsResult<Self> {
-    let buf = Self::open(icon);
+    Self::new_rgba(root, icon, |buf| {
+      let icon_dir = ico::IconDir::read(Cursor::new(buf))
+        .unwrap_or_else(|e| panic!("failed to parse icon {}: {}", icon.display(), e));
+
+      let entry = &icon_dir.entries()[0];
+      let rgba = entry
+        .decode()
+        .unwrap_or_else(|e| panic!("failed to decode icon {}: {}", icon.display(), e))
+        .rgba_data()
+        .to_vec();
+
+      (rgba, entry.width(), entry.height())
+    })
+  }
 
-    let icon_dir = ico::IconDir::read(Cursor::new(&buf))
-      .unwrap_or_else(|e| panic!("failed to parse icon {}: {}", icon.display(), e));
+  /// Cache a PNG icon as RGBA data, see [`ImageFormat::Image`].
+  pub fn new_png(root: &TokenStream, icon: &Path) -> EmbeddedAssetsResult<Self> {
+    Self::new_rgba(root, icon, |buf| {
+      let decoder = png::Decoder::new(Cursor::new(buf));
+      let mut reader = decoder
+        .read_info()
+        .unwrap_or_else(|e| panic!("failed to read icon {}: {}", icon.display(), e));
+
+      if reader.output_color_type().0 != png::ColorType::Rgba {
+        panic!("icon {} is not RGBA", icon.display());
+      }
 
-    let entry = &icon_dir.entries()[0];
-    let rgba = entry
-      .decode()
-      .unwrap_or_else(|e| panic!("failed to decode icon {}: {}", icon.display(), e))
-      .rgba_data()
-      .to_vec();
+      let mut rgba = Vec::with_capacity(reader.output_buffer_size());
+      while let Ok(Some(row)) = reader.next_row() {
+        rgba.extend(row.data());
+      }
 
-    Cached::try_from(rgba).map(|cache| Self {
-      cache,
-      root: root.clone(),
-      format: IconFormat::Image {
-        width: entry.width(),
-        height: entry.height(),
-      },
+      (rgba, reader.info().width, reader.info().height)
     })
   }
 
-  /// Cache a PNG icon as RGBA data, see [`ImageFormat::Image`].
-  pub fn new_png(root: &TokenStream, icon: &Path) -> EmbeddedAssetsResult<Self> {
+  /// Cache the RGBA data returned by `decode`, see [`ImageFormat::Image`].
+  ///
+  /// The decoded data is memoized by the checksum of the icon file,
+  /// so crates sharing an icon (e.g. multiple integration tests of a workspace) only decode it once.
+  fn new_rgba(
+    root: &TokenStream,
+    icon: &Path,
+    decode: impl FnOnce(&[u8]) -> (Vec<u8>, u32, u32),
+  ) -> EmbeddedAssetsResult<Self> {
     let buf = Self::open(icon);
-    let decoder = png::Decoder::new(Cursor::new(&buf));
-    let mut reader = decoder
-      .read_info()
-      .unwrap_or_else(|e| panic!("failed to read icon {}: {}", icon.display(), e));
-
-    if reader.output_color_type().0 != png::ColorType::Rgba {
-      panic!("icon {} is not RGBA", icon.display());
+    let dir = ensure_cache_dir()?;
+    let index_path = dir.path().join(format!(
+      "{}.rgba",
+      checksum(&buf).map_err(EmbeddedAssetsError::Hex)?
+    ));
+
+    if let Some((checksum, width, height)) = read_rgba_index(&index_path) {
+      if dir.path().join(&checksum).exists() {
+        return Ok(Self {
+          cache: Cached { checksum, dir },
+          root: root.clone(),
+          format: IconFormat::Image { width, height },
+        });
+      }
     }
 
-    let mut rgba = Vec::with_capacity(reader.output_buffer_size());
-    while let Ok(Some(row)) = reader.next_row() {
-      rgba.extend(row.data());
-    }
+    let (rgba, width, height) = decode(&buf);
+    let cache = Cached::try_from(rgba)?;
+
+    // the index is only an optimization, the icon is decoded again if it can't be read
+    let _ = write_shared(
+      &index_path,
+      format!("{} {width} {height}", cache.checksum).as_bytes(),
+    );
 
-    Cached::try_from(rgba).map(|cache| Self {
+    Ok(Self {
       cache,
       root: root.clone(),
-      format: IconFormat::Image {
-        width: reader.info().width,
-        height: reader.info().height,
-      },
+      format: IconFormat::Image { width, height },
     })
   }
 
@@ -103,6 +133,16 @@ impl CachedIcon {
   }
 }
 
+/// Reads the `<rgba checksum> <width> <height>` index written by [`CachedIcon::new_rgba`].
+fn read_rgba_index(path: &Path) -> Option<(String, u32, u32)> {
+  let index = std::fs::read_to_string(path).ok()?;
+  let mut parts = index.split(' ');
+  let checksum = parts.next()?.to_string();
+  let width = parts.next()?.parse().ok()?;
+  let height = parts.next()?.parse().ok()?;
+  Some((checksum, width, height))
+}
+
 impl ToTokens for CachedIcon {
   fn to_tokens(&self, tokens: &mut TokenStream) {
     let root = &self.root;
@@ -13,7 +13,7 @@
 )]
 
 pub use self::context::{context_codegen, ContextData};
-use crate::embedded_assets::{ensure_out_dir, EmbeddedAssetsError};
+use crate::embedded_assets::{ensure_cache_dir, CacheDir, EmbeddedAssetsError};
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
 use std::{
@@ -121,8 +121,12 @@ fn checksum(bytes: &[u8]) -> Result<String, fmt::Error> {
 ///
 /// Due to using a checksum as the filename, an existing file should be the exact same content
 /// as the data being checked.
+///
+/// Without a build script the data is cached to a directory shared by all crates instead,
+/// see [`CacheDir::Shared`].
 struct Cached {
   checksum: String,
+  dir: CacheDir,
 }
 
 impl TryFrom<String> for Cached {
@@ -138,17 +142,44 @@ impl TryFrom<Vec<u8>> for Cached {
 
   fn try_from(content: Vec<u8>) -> Result<Self, Self::Error> {
     let checksum = checksum(content.as_ref()).map_err(EmbeddedAssetsError::Hex)?;
-    let path = ensure_out_dir()?.join(&checksum);
+    let dir = ensure_cache_dir()?;
+    let path = dir.path().join(&checksum);
+
+    match &dir {
+      CacheDir::OutDir(_) => write_if_changed(&path, &content),
+      CacheDir::Shared(_) => write_shared(&path, &content),
+    }
+    .map(|_| Self { checksum, dir })
+    .map_err(|error| EmbeddedAssetsError::AssetWrite { path, error })
+  }
+}
 
-    write_if_changed(&path, &content)
-      .map(|_| Self { checksum })
-      .map_err(|error| EmbeddedAssetsError::AssetWrite { path, error })
+/// Writes a file whose name identifies its content (e.g. a checksum) if it does not exist yet.
+///
+/// The content is written to a temporary file first so other crates being compiled concurrently
+/// never include a partially written file.
+fn write_shared(path: &Path, content: &[u8]) -> std::io::Result<()> {
+  if path.exists() {
+    return Ok(());
   }
+
+  let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+  std::fs::write(&tmp_path, content)?;
+  std::fs::rename(&tmp_path, path).map_err(|error| {
+    let _ = std::fs::remove_file(&tmp_path);
+    error
+  })
 }
 
 impl ToTokens for Cached {
   fn to_tokens(&self, tokens: &mut TokenStream) {
-    let path = &self.checksum;
-    tokens.append_all(quote!(::std::concat!(::std::env!("OUT_DIR"), "/", #path)))
+    let checksum = &self.checksum;
+    tokens.append_all(match &self.dir {
+      CacheDir::OutDir(_) => quote!(::std::concat!(::std::env!("OUT_DIR"), "/", #checksum)),
+      CacheDir::Shared(dir) => {
+        let path = dir.join(checksum).display().to_string();
+        quote!(#path)
+      }
+    })
   }
 }
@@ -27,6 +27,7 @@ pub struct Handler {
   command_defs: Vec<CommandDef>,
   commands: Vec<Ident>,
   wrappers: Vec<Path>,
+  states: Vec<Path>,
 }
 
 impl Parse for Handler {
@@ -34,36 +35,62 @@ impl Parse for Handler {
     let command_defs = input.parse_terminated(CommandDef::parse, Token![,])?;
 
     // parse the command names and wrappers from the passed paths
-    let (commands, wrappers) = command_defs
-      .iter()
-      .map(|command_def| {
-        let mut wrapper = command_def.path.clone();
-        let last = super::path_to_command(&mut wrapper);
+    let mut commands = Vec::new();
+    let mut wrappers = Vec::new();
+    let mut states = Vec::new();
+    for command_def in &command_defs {
+      let mut wrapper = command_def.path.clone();
+      let last = super::path_to_command(&mut wrapper);
 
-        // the name of the actual command function
-        let command = last.ident.clone();
+      // the name of the actual command function
+      let command = last.ident.clone();
 
-        // set the path to the command function wrapper
-        last.ident = super::format_command_wrapper(&command);
+      // set the path to the command function wrapper
+      last.ident = super::format_command_wrapper(&command);
 
-        (command, wrapper)
-      })
-      .unzip();
+      // set the path to the function returning the command state types
+      let mut state = command_def.path.clone();
+      super::path_to_command(&mut state).ident = super::format_command_states(&command);
+
+      commands.push(command);
+      wrappers.push(wrapper);
+      states.push(state);
+    }
 
     Ok(Self {
       command_defs: command_defs.into_iter().collect(), // remove punctuation separators
       commands,
       wrappers,
+      states,
     })
   }
 }
 
+impl Handler {
+  /// The [`State`] types requested by the commands, returned by [`generate_command_states!`](crate::generate_command_states).
+  ///
+  /// [`State`]: https://docs.rs/tauri/*/tauri/struct.State.html
+  pub fn command_states(self) -> proc_macro::TokenStream {
+    let command_states = format_ident!("__tauri_command_states__");
+    let attrs = self.command_defs.into_iter().map(|def| def.attrs);
+    let states = self.states;
+    quote::quote!({
+      #[allow(unused_mut)]
+      let mut #command_states = ::std::vec::Vec::new();
+      #(#(#attrs)* #command_states.extend(#states());)*
+      #command_states
+    })
+    .into()
+  }
+}
+
 impl From<Handler> for proc_macro::TokenStream {
   fn from(
     Handler {
       command_defs,
       commands,
       wrappers,
+      ..
     }: Handler,
   ) -> Self {
     let cmd = format_ident!("__tauri_cmd__");
@@ -15,6 +15,11 @@ fn format_command_wrapper(function: &Ident) -> Ident {
   quote::format_ident!("__cmd__{}", function)
 }
 
+/// The autogenerated function returning the managed state types requested by the command.
+fn format_command_states(function: &Ident) -> Ident {
+  quote::format_ident!("__cmd_states__{}", function)
+}
+
 /// This function will panic if the passed [`syn::Path`] does not have any segments.
 fn path_to_command(path: &mut Path) -> &mut PathSegment {
   path
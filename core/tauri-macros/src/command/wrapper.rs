@@ -6,15 +6,16 @@ use std::env::var;
 
 use heck::{ToLowerCamelCase, ToSnakeCase};
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
-use quote::{format_ident, quote, quote_spanned};
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
   ext::IdentExt,
   parse::{Parse, ParseStream},
   parse_macro_input,
   punctuated::Punctuated,
   spanned::Spanned,
-  Expr, ExprLit, FnArg, ItemFn, Lit, Meta, Pat, Token, Visibility,
+  Expr, ExprLit, FnArg, GenericArgument, GenericParam, ItemFn, Lit, Meta, Pat, PathArguments,
+  Token, Type, Visibility,
 };
 
 enum WrapperAttributeKind {
@@ -36,6 +37,8 @@ impl Parse for WrapperAttributeKind {
 
 struct WrapperAttributes {
   root: TokenStream2,
+  /// The root used outside of the wrapper macro, where `$crate` is not available.
+  item_root: TokenStream2,
   execution_context: ExecutionContext,
   argument_case: ArgumentCase,
 }
@@ -44,6 +47,7 @@ impl Parse for WrapperAttributes {
   fn parse(input: ParseStream) -> syn::Result<Self> {
     let mut wrapper_attributes = WrapperAttributes {
       root: quote!(::tauri),
+      item_root: quote!(::tauri),
       execution_context: ExecutionContext::Blocking,
       argument_case: ArgumentCase::Camel,
     };
@@ -80,11 +84,11 @@ impl Parse for WrapperAttributes {
             {
               let lit = s.value();
 
-              wrapper_attributes.root = if lit == "crate" {
-                quote!($crate)
+              (wrapper_attributes.root, wrapper_attributes.item_root) = if lit == "crate" {
+                (quote!($crate), quote!(crate))
               } else {
                 let ident = Ident::new(&lit, Span::call_site());
-                quote!(#ident)
+                (quote!(#ident), quote!(#ident))
               };
             }
           }
@@ -218,6 +222,8 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
       .unwrap_or_else(syn::Error::into_compile_error),
   };
 
+  let states = command_states(&plugin_name, &function, &attrs);
+
   let Invoke {
     message,
     resolver,
@@ -278,10 +284,101 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
     // allow the macro to be resolved with the same path as the command function
     #[allow(unused_imports)]
     #visibility use #wrapper;
+
+    #states
   )
   .into()
 }
 
+/// Generates the function returning the [`State`] types requested by the command,
+/// used by `generate_command_states!` to warn about unmanaged states in debug builds.
+///
+/// State types referencing the generic parameters of the command are skipped.
+///
+/// [`State`]: https://docs.rs/tauri/*/tauri/struct.State.html
+fn command_states(
+  plugin_name: &TokenStream2,
+  function: &ItemFn,
+  attributes: &WrapperAttributes,
+) -> TokenStream2 {
+  let command = &function.sig.ident;
+  let states_fn = super::format_command_states(command);
+  let visibility = &function.vis;
+  let root = &attributes.item_root;
+
+  let generics = function
+    .sig
+    .generics
+    .params
+    .iter()
+    .filter_map(|param| match param {
+      GenericParam::Type(ty) => Some(ty.ident.to_string()),
+      GenericParam::Const(c) => Some(c.ident.to_string()),
+      GenericParam::Lifetime(_) => None,
+    })
+    .collect::<Vec<_>>();
+
+  let states = function.sig.inputs.iter().filter_map(|arg| {
+    let FnArg::Typed(arg) = arg else {
+      return None;
+    };
+    let Pat::Ident(ident) = &*arg.pat else {
+      return None;
+    };
+    let Type::Path(ty) = &*arg.ty else {
+      return None;
+    };
+    let last = ty.path.segments.last()?;
+    if last.ident != "State" {
+      return None;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+      return None;
+    };
+    let state = args.args.iter().rev().find_map(|arg| match arg {
+      GenericArgument::Type(ty) => Some(ty),
+      _ => None,
+    })?;
+    if references_any(state.to_token_stream(), &generics) {
+      return None;
+    }
+
+    let key = ident.ident.unraw().to_string();
+    let key = match attributes.argument_case {
+      ArgumentCase::Camel => key.to_lower_camel_case(),
+      ArgumentCase::Snake => key.to_snake_case(),
+    };
+
+    Some(quote!(
+      #root::ipc::CommandState::new::<#state>(#plugin_name, stringify!(#command), #key)
+    ))
+  });
+
+  // keep the function conditionally compiled with the command
+  let cfg_attrs = function
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("cfg"));
+
+  quote!(
+    #(#cfg_attrs)*
+    #[doc(hidden)]
+    #[allow(dead_code, non_snake_case)]
+    #visibility fn #states_fn() -> ::std::vec::Vec<#root::ipc::CommandState> {
+      ::std::vec![#(#states),*]
+    }
+  )
+}
+
+/// Whether the tokens contain any of the given identifiers.
+fn references_any(tokens: TokenStream2, idents: &[String]) -> bool {
+  tokens.into_iter().any(|token| match token {
+    TokenTree::Ident(ident) => idents.iter().any(|i| ident == i),
+    TokenTree::Group(group) => references_any(group.stream(), idents),
+    _ => false,
+  })
+}
+
 /// Generates an asynchronous command response from the arguments and return value of a function.
 ///
 /// See the [`tauri::command`] module for all the items and traits that make this possible.
@@ -18,6 +18,7 @@ pub(crate) struct ContextItems {
   root: syn::Path,
   capabilities: Option<Vec<PathBuf>>,
   assets: Option<Expr>,
+  stub_assets: bool,
   test: bool,
 }
 
@@ -32,27 +33,11 @@ impl Parse for ContextItems {
     let mut root = None;
     let mut capabilities = None;
     let mut assets = None;
+    let mut stub_assets = false;
     let mut test = false;
-    let config_file = input.parse::<LitStr>().ok().map(|raw| {
+    let mut config_file = input.parse::<LitStr>().ok().map(|raw| {
       let _ = input.parse::<Token![,]>();
-      let path = PathBuf::from(raw.value());
-      if path.is_relative() {
-        std::env::var("CARGO_MANIFEST_DIR")
-          .map(|m| PathBuf::from(m).join(path))
-          .map_err(|e| e.to_string())
-      } else {
-        Ok(path)
-      }
-      .and_then(|path| {
-        if does_supported_file_name_exist(target, &path) {
-          Ok(path)
-        } else {
-          Err(format!(
-            "no file at path {} exists, expected tauri config file",
-            path.display()
-          ))
-        }
-      })
+      resolve_config_path(target, &raw)
     });
 
     while let Ok(meta) = input.parse::<Meta>() {
@@ -92,9 +77,29 @@ impl Parse for ContextItems {
                 ));
               }
             }
-            "assets" => {
-              assets.replace(v.value);
+            "config_path" => {
+              if let Expr::Lit(ExprLit {
+                lit: Lit::Str(raw), ..
+              }) = v.value
+              {
+                config_file.replace(resolve_config_path(target, &raw));
+              } else {
+                return Err(syn::Error::new(
+                  input.span(),
+                  "unexpected value for config_path",
+                ));
+              }
             }
+            "assets" => match v.value {
+              // `assets = test` serves a stub instead of embedding the frontend
+              Expr::Path(path) if path.path.is_ident("test") => {
+                stub_assets = true;
+                test = true;
+              }
+              value => {
+                assets.replace(value);
+              }
+            },
             "test" => {
               if let Expr::Lit(ExprLit {
                 lit: Lit::Bool(LitBool { value, .. }),
@@ -143,11 +148,34 @@ impl Parse for ContextItems {
       }),
       capabilities,
       assets,
+      stub_assets,
       test,
     })
   }
 }
 
+/// Resolves the config file path, relative to the Cargo manifest directory of the compiling crate.
+fn resolve_config_path(target: Target, raw: &LitStr) -> Result<PathBuf, String> {
+  let path = PathBuf::from(raw.value());
+  if path.is_relative() {
+    std::env::var("CARGO_MANIFEST_DIR")
+      .map(|m| PathBuf::from(m).join(path))
+      .map_err(|e| e.to_string())
+  } else {
+    Ok(path)
+  }
+  .and_then(|path| {
+    if does_supported_file_name_exist(target, &path) {
+      Ok(path)
+    } else {
+      Err(format!(
+        "no file at path {} exists, expected tauri config file",
+        path.display()
+      ))
+    }
+  })
+}
+
 pub(crate) fn generate_context(context: ContextItems) -> TokenStream {
   let context = get_config(&context.config_file)
     .map_err(|e| e.to_string())
@@ -158,6 +186,7 @@ pub(crate) fn generate_context(context: ContextItems) -> TokenStream {
       root: context.root.to_token_stream(),
       capabilities: context.capabilities,
       assets: context.assets,
+      stub_assets: context.stub_assets,
       test: context.test,
     })
     .and_then(|data| context_codegen(data).map_err(|e| e.to_string()));
@@ -69,6 +69,30 @@ pub fn generate_handler(item: TokenStream) -> TokenStream {
   parse_macro_input!(item as command::Handler).into()
 }
 
+/// Accepts the same list of command functions as [`generate_handler!`].
+/// Creates the list of managed state types requested by the commands, checked at startup in debug builds.
+///
+/// # Examples
+/// ```rust,ignore
+/// use tauri_macros::{command, generate_command_states};
+/// struct Counter;
+/// #[command]
+/// fn command_one(counter: tauri::State<'_, Counter>) {
+///   println!("command one called");
+/// }
+/// fn main() {
+///   let _states = generate_command_states![command_one];
+/// }
+/// ```
+/// # Stability
+/// The output of this macro is managed internally by Tauri,
+/// and should not be accessed directly on normal applications.
+/// It may have breaking changes in the future.
+#[proc_macro]
+pub fn generate_command_states(item: TokenStream) -> TokenStream {
+  parse_macro_input!(item as command::Handler).command_states()
+}
+
 /// Reads a Tauri config file and generates a `::tauri::Context` based on the content.
 ///
 /// # Stability
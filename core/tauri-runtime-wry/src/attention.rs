@@ -0,0 +1,107 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! System sounds and user attention requests, must be used on the main thread.
+
+use tao::window::Window;
+use tauri_runtime::SystemSound;
+
+/// Plays a sound of the system.
+pub fn play_system_sound(sound: SystemSound) {
+  #[cfg(windows)]
+  {
+    use windows::Win32::UI::WindowsAndMessaging::{
+      MessageBeep, MB_ICONERROR, MB_ICONINFORMATION, MB_ICONQUESTION, MB_ICONWARNING, MB_OK,
+    };
+    let style = match sound {
+      SystemSound::Alert => MB_OK,
+      SystemSound::Information => MB_ICONINFORMATION,
+      SystemSound::Warning => MB_ICONWARNING,
+      SystemSound::Error => MB_ICONERROR,
+      SystemSound::Question => MB_ICONQUESTION,
+    };
+    if let Err(e) = unsafe { MessageBeep(style) } {
+      log::error!("failed to play the {sound:?} system sound: {e}");
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    let _ = sound;
+    unsafe { NSBeep() };
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    let _ = sound;
+    if let Some(display) = gtk::gdk::Display::default() {
+      display.beep();
+    }
+  }
+
+  #[cfg(any(target_os = "android", target_os = "ios"))]
+  let _ = sound;
+}
+
+/// Plays the alert sound of the system for `window`.
+pub fn beep(window: &Window) {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::WidgetExt;
+    use tao::platform::unix::WindowExtUnix;
+
+    // rings the bell of the window, which desktops may show as a visual bell instead
+    if let Some(gdk_window) = window.gtk_window().window() {
+      gdk_window.beep();
+      return;
+    }
+  }
+
+  let _ = window;
+  play_system_sound(SystemSound::Alert);
+}
+
+/// Requests the activation of `window` on Wayland, where the urgency hint set by
+/// [`Window::request_user_attention`] is ignored.
+///
+/// GTK requests an `xdg-activation` token for the window, which the compositor turns into an attention request,
+/// e.g. a notification or a highlighted taskbar entry, instead of focusing the window when the user is using another application.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+pub fn request_activation(window: &Window) {
+  use gtk::{glib::prelude::ObjectExt, prelude::GtkWindowExt, prelude::WidgetExt};
+  use tao::platform::unix::WindowExtUnix;
+
+  let is_wayland = gtk::gdk::Display::default().map_or(false, |display| {
+    display.type_().name() == "GdkWaylandDisplay"
+  });
+  let gtk_window = window.gtk_window();
+  // presenting a hidden window would show it
+  if is_wayland && gtk_window.is_visible() && !gtk_window.is_active() {
+    gtk_window.present_with_time(gtk::gdk::CURRENT_TIME);
+  }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+  fn NSBeep();
+}
@@ -0,0 +1,386 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Find-in-page with the WebKitGTK find controller,
+//! or with an injected script on the platforms whose webview has no find API exposing the matches.
+
+use serde::Serialize;
+use tauri_runtime::{
+  webview::{FindOptions, FindResult},
+  Result,
+};
+
+/// Receives the result of a find request.
+pub type FindResultHandler = Box<dyn Fn(FindResult) + Send>;
+
+/// A find-in-page request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FindRequest {
+  Find {
+    text: String,
+    #[serde(flatten)]
+    options: FindOptions,
+  },
+  Next,
+  Previous,
+  #[serde(rename_all = "camelCase")]
+  Stop {
+    clear_selection: bool,
+  },
+}
+
+/// The matches of the current search, for the platforms that do not track the active match.
+#[derive(Debug, Default)]
+#[cfg_attr(
+  not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )),
+  allow(dead_code)
+)]
+pub struct FindState {
+  options: FindOptions,
+  matches: u32,
+  active_index: Option<u32>,
+}
+
+#[cfg_attr(
+  not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )),
+  allow(dead_code)
+)]
+impl FindState {
+  /// Starts a search that found the given number of matches, highlighting the first one.
+  fn start(&mut self, options: FindOptions, matches: u32) -> FindResult {
+    self.options = options;
+    self.matches = matches;
+    self.active_index = None;
+    self.advance(true)
+  }
+
+  /// Moves to the next match in the search direction, or to the previous one.
+  ///
+  /// This is the navigation implemented by the injected find script.
+  fn advance(&mut self, next: bool) -> FindResult {
+    if self.matches > 0 {
+      let forward = self.options.forward == next;
+      let last = self.matches - 1;
+      self.active_index = Some(match self.active_index {
+        None if forward => 0,
+        None => last,
+        Some(index) if forward && index < last => index + 1,
+        Some(index) if !forward && index > 0 => index - 1,
+        Some(_) if self.options.wrap => {
+          if forward {
+            0
+          } else {
+            last
+          }
+        }
+        Some(index) => index,
+      });
+    }
+    self.result()
+  }
+
+  fn stop(&mut self) -> FindResult {
+    self.matches = 0;
+    self.active_index = None;
+    self.result()
+  }
+
+  fn result(&self) -> FindResult {
+    FindResult {
+      matches: self.matches,
+      active_index: self.active_index,
+    }
+  }
+}
+
+/// The per webview find session.
+#[derive(Default)]
+pub struct FindSession {
+  #[cfg_attr(
+    not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )),
+    allow(dead_code)
+  )]
+  state: FindState,
+  #[cfg_attr(
+    not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )),
+    allow(dead_code)
+  )]
+  handler: Option<FindResultHandler>,
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  counted_matches_connected: bool,
+}
+
+/// Runs the find request on the webview, `on_result` receiving its result once the platform reports it.
+pub fn find(
+  webview: &wry::WebView,
+  session: &std::rc::Rc<std::cell::RefCell<FindSession>>,
+  request: FindRequest,
+  on_result: FindResultHandler,
+) -> Result<()> {
+  imp::find(webview, session, request, on_result)
+}
+
+/// The script running the request for the platforms without a usable native find API.
+#[allow(dead_code)]
+fn find_script(request: &FindRequest) -> String {
+  include_str!("./scripts/find.js").replace(
+    "__REQUEST__",
+    &serde_json::to_string(request).expect("failed to serialize find request"),
+  )
+}
+
+/// Parses the value returned by the find script, which some platforms serialize twice.
+#[allow(dead_code)]
+fn parse_script_result(json: &str) -> Option<FindResult> {
+  serde_json::from_str(json).ok().or_else(|| {
+    serde_json::from_str::<String>(json)
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+  })
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+mod imp {
+  use super::*;
+  use std::{cell::RefCell, rc::Rc};
+  use tauri_runtime::Error;
+  use webkit2gtk::{FindControllerExt, FindOptions as WebKitFindOptions, WebViewExt};
+  use wry::WebViewExtUnix;
+
+  /// WebKitGTK highlights every match so the search is limited to keep large pages responsive.
+  const MAX_MATCHES: u32 = 1000;
+
+  pub(super) fn find_flags(options: &FindOptions) -> WebKitFindOptions {
+    let mut flags = WebKitFindOptions::empty();
+    if !options.match_case {
+      flags |= WebKitFindOptions::CASE_INSENSITIVE;
+    }
+    if !options.forward {
+      flags |= WebKitFindOptions::BACKWARDS;
+    }
+    if options.wrap {
+      flags |= WebKitFindOptions::WRAP_AROUND;
+    }
+    flags
+  }
+
+  pub fn find(
+    webview: &wry::WebView,
+    session: &Rc<RefCell<FindSession>>,
+    request: FindRequest,
+    on_result: FindResultHandler,
+  ) -> Result<()> {
+    let controller = webview
+      .webview()
+      .find_controller()
+      .ok_or(Error::FailedToSendMessage)?;
+
+    let result = match request {
+      FindRequest::Find { text, options } => {
+        // the result is sent once the matches are counted
+        let connect = {
+          let mut session_ = session.borrow_mut();
+          session_.state.options = options;
+          session_.handler.replace(on_result);
+          !std::mem::replace(&mut session_.counted_matches_connected, true)
+        };
+        if connect {
+          let session = session.clone();
+          controller.connect_counted_matches(move |_controller, matches| {
+            let mut session = session.borrow_mut();
+            let options = session.state.options;
+            let result = session.state.start(options, matches);
+            if let Some(handler) = &session.handler {
+              handler(result);
+            }
+          });
+        }
+
+        let flags = find_flags(&options).bits();
+        controller.count_matches(&text, flags, MAX_MATCHES);
+        controller.search(&text, flags, MAX_MATCHES);
+        return Ok(());
+      }
+      FindRequest::Next => {
+        controller.search_next();
+        session.borrow_mut().state.advance(true)
+      }
+      FindRequest::Previous => {
+        controller.search_previous();
+        session.borrow_mut().state.advance(false)
+      }
+      // WebKitGTK always removes the highlight of the active match
+      FindRequest::Stop { .. } => {
+        controller.search_finish();
+        session.borrow_mut().state.stop()
+      }
+    };
+    on_result(result);
+
+    Ok(())
+  }
+}
+
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+)))]
+mod imp {
+  use super::*;
+  use std::{cell::RefCell, rc::Rc};
+
+  // WebView2 does not expose its find API in the SDK version we use
+  // and the WKWebView find API does not report the matches, so the injected script is used instead
+  pub fn find(
+    webview: &wry::WebView,
+    _session: &Rc<RefCell<FindSession>>,
+    request: FindRequest,
+    on_result: FindResultHandler,
+  ) -> Result<()> {
+    webview
+      .evaluate_script_with_callback(
+        &find_script(&request),
+        move |json| match parse_script_result(&json) {
+          Some(result) => on_result(result),
+          None => log::error!("unexpected find-in-page result: {json}"),
+        },
+      )
+      .map_err(|_| tauri_runtime::Error::FailedToSendMessage)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{find_script, parse_script_result, FindRequest, FindState};
+  use tauri_runtime::webview::{FindOptions, FindResult};
+
+  fn result(matches: u32, active_index: Option<u32>) -> FindResult {
+    FindResult {
+      matches,
+      active_index,
+    }
+  }
+
+  #[test]
+  fn navigates_matches() {
+    let mut state = FindState::default();
+    assert_eq!(state.start(FindOptions::default(), 3), result(3, Some(0)));
+    assert_eq!(state.advance(true), result(3, Some(1)));
+    assert_eq!(state.advance(true), result(3, Some(2)));
+    assert_eq!(state.advance(true), result(3, Some(0)));
+    assert_eq!(state.advance(false), result(3, Some(2)));
+    assert_eq!(state.stop(), result(0, None));
+
+    let backwards_no_wrap = FindOptions {
+      forward: false,
+      wrap: false,
+      ..Default::default()
+    };
+    assert_eq!(state.start(backwards_no_wrap, 2), result(2, Some(1)));
+    assert_eq!(state.advance(true), result(2, Some(0)));
+    assert_eq!(state.advance(true), result(2, Some(0)));
+    assert_eq!(state.advance(false), result(2, Some(1)));
+    assert_eq!(state.advance(false), result(2, Some(1)));
+
+    assert_eq!(state.start(FindOptions::default(), 0), result(0, None));
+    assert_eq!(state.advance(true), result(0, None));
+  }
+
+  #[test]
+  fn serializes_requests() {
+    let script = find_script(&FindRequest::Find {
+      text: "it's \"quoted\"".into(),
+      options: FindOptions {
+        match_case: true,
+        ..Default::default()
+      },
+    });
+    assert!(script.ends_with(
+      r#"})({"kind":"find","text":"it's \"quoted\"","forward":true,"matchCase":true,"wrap":true})
+"#
+    ));
+    assert!(find_script(&FindRequest::Stop {
+      clear_selection: false
+    })
+    .contains(r#"({"kind":"stop","clearSelection":false})"#));
+  }
+
+  #[test]
+  fn parses_script_results() {
+    assert_eq!(
+      parse_script_result(r#"{"matches":2,"activeIndex":1}"#),
+      Some(result(2, Some(1)))
+    );
+    assert_eq!(
+      parse_script_result(r#""{\"matches\":0,\"activeIndex\":null}""#),
+      Some(result(0, None))
+    );
+    assert_eq!(parse_script_result("null"), None);
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  #[test]
+  fn maps_webkit_find_options() {
+    use webkit2gtk::FindOptions as WebKitFindOptions;
+
+    assert_eq!(
+      super::imp::find_flags(&FindOptions::default()),
+      WebKitFindOptions::CASE_INSENSITIVE | WebKitFindOptions::WRAP_AROUND
+    );
+    assert_eq!(
+      super::imp::find_flags(&FindOptions {
+        forward: false,
+        match_case: true,
+        wrap: false,
+      }),
+      WebKitFindOptions::BACKWARDS
+    );
+  }
+}
@@ -20,14 +20,17 @@ use raw_window_handle::{DisplayHandle, HasDisplayHandle, HasWindowHandle};
 use tauri_runtime::{
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
   monitor::Monitor,
-  webview::{DetachedWebview, DownloadEvent, PendingWebview, WebviewIpcHandler},
+  webview::{
+    DetachedWebview, DownloadEvent, PendingWebview, UnsupportedWebviewSetting,
+    UriSchemeResponseBody, WebviewIpcHandler,
+  },
   window::{
     CursorIcon, DetachedWindow, DragDropEvent, PendingWindow, RawWindow, WebviewEvent,
     WindowBuilder, WindowBuilderBase, WindowEvent, WindowId, WindowSizeConstraints,
   },
   DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, ProgressBarState,
-  ProgressBarStatus, Result, RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType,
-  UserEvent, WebviewDispatch, WebviewEventId, WindowDispatch, WindowEventId,
+  ProgressBarStatus, Result, RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, SystemSound,
+  UserAttentionType, UserEvent, WebviewDispatch, WebviewEventId, WindowDispatch, WindowEventId,
 };
 
 #[cfg(target_os = "macos")]
@@ -63,7 +66,10 @@ use tao::{
 };
 #[cfg(target_os = "macos")]
 use tauri_utils::TitleBarStyle;
-use tauri_utils::{config::WindowConfig, Theme};
+use tauri_utils::{
+  config::{AutoplayPolicy, CookiePersistence, WebviewSettingsConfig, WindowConfig},
+  Theme,
+};
 use url::Url;
 use wry::{
   DragDropEvent as WryDragDropEvent, ProxyConfig, ProxyEndpoint, WebContext, WebView,
@@ -128,9 +134,20 @@ type IpcHandler = dyn Fn(Request<String>) + 'static;
 ))]
 mod undecorated_resizing;
 
+mod attention;
+
+mod find;
+
 mod webview;
 pub use webview::Webview;
 
+mod webview_data;
+use webview_data::WebsiteData;
+
+mod webview_process;
+
+mod webview_settings;
+
 pub type WebContextStore = Arc<Mutex<HashMap<Option<PathBuf>, WebContext>>>;
 // window
 pub type WindowEventHandler = Box<dyn Fn(&WindowEvent) + Send>;
@@ -1165,6 +1182,7 @@ pub enum WindowMessage {
   // Setters
   Center,
   RequestUserAttention(Option<UserAttentionTypeWrapper>),
+  Beep,
   SetResizable(bool),
   SetMaximizable(bool),
   SetMinimizable(bool),
@@ -1239,11 +1257,16 @@ pub enum WebviewMessage {
   Reparent(WindowId, Sender<Result<()>>),
   SetAutoResize(bool),
   SetZoom(f64),
+  Find(find::FindRequest, find::FindResultHandler),
+  ClearWebsiteData(WebsiteData, Option<Url>, webview_data::ClearedHandler),
   // Getters
   Url(Sender<Result<String>>),
   Bounds(Sender<Result<tauri_runtime::Rect>>),
   Position(Sender<Result<PhysicalPosition<i32>>>),
   Size(Sender<Result<PhysicalSize<u32>>>),
+  ProcessId(Sender<Option<u32>>),
+  WebviewSettings(Sender<WebviewSettingsConfig>),
+  UnsupportedWebviewSettings(Sender<Vec<UnsupportedWebviewSetting>>),
   WithWebview(Box<dyn FnOnce(Webview) + Send>),
   // Devtools
   #[cfg(any(debug_assertions, feature = "devtools"))]
@@ -1264,6 +1287,7 @@ pub enum Message<T: 'static> {
   #[cfg(target_os = "macos")]
   SetActivationPolicy(ActivationPolicy),
   RequestExit(i32),
+  PlaySystemSound(SystemSound),
   #[cfg(target_os = "macos")]
   Application(ApplicationMessage),
   Window(WindowId, WindowMessage),
@@ -1371,6 +1395,18 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
     webview_getter!(self, WebviewMessage::Size)?
   }
 
+  fn process_id(&self) -> Result<Option<u32>> {
+    webview_getter!(self, WebviewMessage::ProcessId)
+  }
+
+  fn webview_settings(&self) -> Result<WebviewSettingsConfig> {
+    webview_getter!(self, WebviewMessage::WebviewSettings)
+  }
+
+  fn unsupported_webview_settings(&self) -> Result<Vec<UnsupportedWebviewSetting>> {
+    webview_getter!(self, WebviewMessage::UnsupportedWebviewSettings)
+  }
+
   // Setters
 
   fn navigate(&self, url: Url) -> Result<()> {
@@ -1516,6 +1552,93 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
       ),
     )
   }
+
+  fn clear_cache(&self) -> Result<()> {
+    clear_website_data(self, WebsiteData::Cache, None, Box::new(log_clear_error))
+  }
+
+  fn unregister_service_workers<F: FnOnce(Result<()>) + Send + 'static>(
+    &self,
+    on_complete: F,
+  ) -> Result<()> {
+    clear_website_data(
+      self,
+      WebsiteData::ServiceWorkers,
+      None,
+      Box::new(on_complete),
+    )
+  }
+
+  fn clear_all_site_data_for_origin(&self, origin: Url) -> Result<()> {
+    clear_website_data(
+      self,
+      WebsiteData::All,
+      Some(origin),
+      Box::new(log_clear_error),
+    )
+  }
+
+  fn find(&self, text: String, options: tauri_runtime::webview::FindOptions) -> Result<()> {
+    find(self, find::FindRequest::Find { text, options })
+  }
+
+  fn find_next(&self) -> Result<()> {
+    find(self, find::FindRequest::Next)
+  }
+
+  fn find_previous(&self) -> Result<()> {
+    find(self, find::FindRequest::Previous)
+  }
+
+  fn stop_find(&self, clear_selection: bool) -> Result<()> {
+    find(self, find::FindRequest::Stop { clear_selection })
+  }
+}
+
+fn find<T: UserEvent>(
+  dispatcher: &WryWebviewDispatcher<T>,
+  request: find::FindRequest,
+) -> Result<()> {
+  let proxy = dispatcher.context.proxy.clone();
+  let window_id = dispatcher.window_id.clone();
+  let webview_id = dispatcher.webview_id;
+  let on_result: find::FindResultHandler = Box::new(move |result| {
+    let _ = proxy.send_event(Message::Webview(
+      *window_id.lock().unwrap(),
+      webview_id,
+      WebviewMessage::WebviewEvent(WebviewEvent::FindResult(result)),
+    ));
+  });
+  send_user_message(
+    &dispatcher.context,
+    Message::Webview(
+      *dispatcher.window_id.lock().unwrap(),
+      dispatcher.webview_id,
+      WebviewMessage::Find(request, on_result),
+    ),
+  )
+}
+
+fn clear_website_data<T: UserEvent>(
+  dispatcher: &WryWebviewDispatcher<T>,
+  data: WebsiteData,
+  origin: Option<Url>,
+  on_cleared: webview_data::ClearedHandler,
+) -> Result<()> {
+  send_user_message(
+    &dispatcher.context,
+    Message::Webview(
+      *dispatcher.window_id.lock().unwrap(),
+      dispatcher.webview_id,
+      WebviewMessage::ClearWebsiteData(data, origin, on_cleared),
+    ),
+  )
+}
+
+fn log_clear_error(result: Result<()>) {
+  if let Err(e) = result {
+    log::error!("failed to clear webview data: {e}");
+  }
 }
 
 /// The Tauri [`WindowDispatch`] for [`Wry`].
@@ -1708,6 +1831,13 @@ impl<T: UserEvent> WindowDispatch<T> for WryWindowDispatcher<T> {
     )
   }
 
+  fn beep(&self) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(self.window_id, WindowMessage::Beep),
+    )
+  }
+
   // Creates a window by dispatching a message to the event loop.
   // Note that this must be called from a separate thread, otherwise the channel will introduce a deadlock.
   fn create_window<F: Fn(RawWindow) + Send + 'static>(
@@ -2022,6 +2152,12 @@ pub struct WebviewWrapper {
   // the key of the WebContext if it's not shared
   context_key: Option<PathBuf>,
   bounds: Arc<Mutex<Option<WebviewBounds>>>,
+  // the webview engine settings in effect and the ones the platform does not support
+  settings: WebviewSettingsConfig,
+  unsupported_settings: Vec<UnsupportedWebviewSetting>,
+  find_session: Rc<RefCell<find::FindSession>>,
+  // dropped with the webview, cancelling its streamed custom protocol responses
+  _streamed_responses_token: Arc<()>,
 }
 
 impl Deref for WebviewWrapper {
@@ -2195,6 +2331,10 @@ impl<T: UserEvent> RuntimeHandle<T> for WryHandle<T> {
       .map_err(|_| Error::FailedToSendMessage)
   }
 
+  fn play_system_sound(&self, sound: SystemSound) -> Result<()> {
+    send_user_message(&self.context, Message::PlaySystemSound(sound))
+  }
+
   // Creates a window by dispatching a message to the event loop.
   // Note that this must be called from a separate thread, otherwise the channel will introduce a deadlock.
   fn create_window<F: Fn(RawWindow) + Send + 'static>(
@@ -2695,6 +2835,7 @@ fn handle_user_message<T: UserEvent>(
       event_loop.set_activation_policy_at_runtime(tao_activation_policy(activation_policy))
     }
     Message::RequestExit(_code) => panic!("cannot handle RequestExit on the main thread"),
+    Message::PlaySystemSound(sound) => attention::play_system_sound(sound),
     #[cfg(target_os = "macos")]
     Message::Application(application_message) => match application_message {
       ApplicationMessage::Show => {
@@ -2826,8 +2967,21 @@ fn handle_user_message<T: UserEvent>(
             }
           }
           WindowMessage::RequestUserAttention(request_type) => {
+            #[allow(unused_variables)]
+            let requested = request_type.is_some();
             window.request_user_attention(request_type.map(|r| r.0));
+            #[cfg(any(
+              target_os = "linux",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "netbsd",
+              target_os = "openbsd"
+            ))]
+            if requested {
+              attention::request_activation(&window);
+            }
           }
+          WindowMessage::Beep => attention::beep(&window),
           WindowMessage::SetResizable(resizable) => {
             window.set_resizable(resizable);
             #[cfg(windows)]
@@ -3158,6 +3312,14 @@ fn handle_user_message<T: UserEvent>(
               log::error!("failed to set webview zoom: {e}");
             }
           }
+          WebviewMessage::Find(request, on_result) => {
+            if let Err(e) = find::find(&webview, &webview.find_session, request, on_result) {
+              log::error!("failed to find in page: {e}");
+            }
+          }
+          WebviewMessage::ClearWebsiteData(data, origin, on_cleared) => {
+            webview_data::clear(&webview, data, origin.as_ref(), on_cleared);
+          }
           // Getters
           WebviewMessage::Url(tx) => {
             tx.send(
@@ -3198,6 +3360,15 @@ fn handle_user_message<T: UserEvent>(
             )
             .unwrap();
           }
+          WebviewMessage::ProcessId(tx) => {
+            tx.send(webview_process::process_id(&webview)).unwrap();
+          }
+          WebviewMessage::WebviewSettings(tx) => {
+            tx.send(webview.settings.clone()).unwrap();
+          }
+          WebviewMessage::UnsupportedWebviewSettings(tx) => {
+            tx.send(webview.unsupported_settings.clone()).unwrap();
+          }
           WebviewMessage::SetFocus => {
             if let Err(e) = webview.focus() {
               log::error!("failed to focus webview: {e}");
@@ -3860,6 +4031,65 @@ struct WebviewBounds {
   height_rate: f32,
 }
 
+/// The maximum length of a streamed custom protocol response body, see [`respond_streaming`].
+const MAX_STREAMED_BODY_LEN: usize = 512 * 1024 * 1024;
+
+/// The length of the chunks read from a streamed custom protocol response body.
+const STREAMED_BODY_CHUNK_LEN: usize = 64 * 1024;
+
+/// Reads a streamed custom protocol response body chunk by chunk on a separate thread and hands it to the webview.
+///
+/// The custom protocol APIs exposed by wry take the complete body, so the chunks are collected up to [`MAX_STREAMED_BODY_LEN`]
+/// and the request fails with a `500 Internal Server Error` response past it.
+/// The body is dropped once the webview is destroyed or the limit is reached,
+/// which cancels the request and fails the writes of the protocol handler.
+fn respond_streaming(
+  responder: wry::RequestAsyncResponder,
+  parts: http::response::Parts,
+  mut body: Box<dyn std::io::Read + Send>,
+  webview: Weak<()>,
+) {
+  let fail = |responder: wry::RequestAsyncResponder| {
+    responder.respond(
+      http::Response::builder()
+        .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+        .body(Vec::new())
+        .unwrap(),
+    )
+  };
+
+  std::thread::spawn(move || {
+    let mut buf = Vec::new();
+    let mut chunk = vec![0; STREAMED_BODY_CHUNK_LEN];
+    loop {
+      if webview.strong_count() == 0 {
+        log::debug!("cancelled the custom protocol response of a destroyed webview");
+        return;
+      }
+      match body.read(&mut chunk) {
+        Ok(0) => break,
+        Ok(len) if buf.len() + len > MAX_STREAMED_BODY_LEN => {
+          log::error!(
+            "the custom protocol response body is larger than {MAX_STREAMED_BODY_LEN} bytes"
+          );
+          drop(body);
+          fail(responder);
+          return;
+        }
+        Ok(len) => buf.extend_from_slice(&chunk[..len]),
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+        Err(e) => {
+          log::error!("failed to read the custom protocol response body: {e}");
+          drop(body);
+          fail(responder);
+          return;
+        }
+      }
+    }
+    responder.respond(http::Response::from_parts(parts, buf))
+  });
+}
+
 fn create_webview<T: UserEvent>(
   kind: WebviewKind,
   window: &Window,
@@ -3877,6 +4107,7 @@ fn create_webview<T: UserEvent>(
     url,
     ..
   } = pending;
+  let cookie_persistence = webview_attributes.effective_cookie_persistence();
 
   let builder = match kind {
     #[cfg(not(any(
@@ -3926,6 +4157,19 @@ fn create_webview<T: UserEvent>(
     .with_accept_first_mouse(webview_attributes.accept_first_mouse)
     .with_hotkeys_zoom(webview_attributes.zoom_hotkeys_enabled);
 
+  let unsupported_settings = webview_settings::unsupported(&webview_attributes.webview_settings);
+  for setting in &unsupported_settings {
+    log::warn!("{setting}, ignoring it for webview `{label}`");
+  }
+  let settings = webview_settings::without_unsupported(
+    webview_attributes.webview_settings.clone(),
+    &unsupported_settings,
+  );
+
+  if let Some(autoplay) = settings.autoplay {
+    webview_builder = webview_builder.with_autoplay(autoplay == AutoplayPolicy::Allow);
+  }
+
   if webview_attributes.drag_drop_handler_enabled {
     let proxy = context.proxy.clone();
     let window_id_ = window_id.clone();
@@ -4057,7 +4301,19 @@ fn create_webview<T: UserEvent>(
 
   #[cfg(windows)]
   {
-    if let Some(additional_browser_args) = webview_attributes.additional_browser_args {
+    let mut additional_browser_args = webview_attributes.additional_browser_args;
+    let settings_browser_args = webview_settings::browser_args(&settings);
+    if !settings_browser_args.is_empty() {
+      // keep wry's default arguments unless they were overridden
+      let args = additional_browser_args
+        .get_or_insert_with(|| webview_settings::DEFAULT_BROWSER_ARGS.to_string());
+      for arg in settings_browser_args {
+        args.push(' ');
+        args.push_str(arg);
+      }
+    }
+
+    if let Some(additional_browser_args) = additional_browser_args {
       webview_builder = webview_builder.with_additional_browser_args(&additional_browser_args);
     }
 
@@ -4082,12 +4338,26 @@ fn create_webview<T: UserEvent>(
     ipc_handler,
   ));
 
+  // held by the webview wrapper, so the streamed responses are cancelled once the webview is destroyed
+  let streamed_responses_token = Arc::new(());
   for (scheme, protocol) in uri_scheme_protocols {
+    let webview = Arc::downgrade(&streamed_responses_token);
     webview_builder =
       webview_builder.with_asynchronous_custom_protocol(scheme, move |request, responder| {
+        let webview = webview.clone();
         protocol(
           request,
-          Box::new(move |response| responder.respond(response)),
+          Box::new(move |response| {
+            let (parts, body) = response.into_parts();
+            match body {
+              UriSchemeResponseBody::Full(body) => {
+                responder.respond(http::Response::from_parts(parts, body))
+              }
+              UriSchemeResponseBody::Stream(body) => {
+                respond_streaming(responder, parts, body, webview)
+              }
+            }
+          }),
         )
       });
   }
@@ -4130,7 +4400,8 @@ fn create_webview<T: UserEvent>(
     webview_builder.attrs.clipboard = true;
   }
 
-  if webview_attributes.incognito {
+  // wry's incognito mode uses an ephemeral data store on every platform but Android
+  if cookie_persistence == CookiePersistence::Session {
     webview_builder.attrs.incognito = true;
   }
 
@@ -4157,6 +4428,8 @@ fn create_webview<T: UserEvent>(
     .build()
     .map_err(|e| Error::CreateWebview(Box::new(e)))?;
 
+  webview_settings::apply(&webview, &settings);
+
   if kind == WebviewKind::WindowContent {
     #[cfg(any(
       target_os = "linux",
@@ -4221,6 +4494,10 @@ fn create_webview<T: UserEvent>(
       web_context_key
     },
     bounds: Arc::new(Mutex::new(webview_bounds)),
+    settings,
+    unsupported_settings,
+    find_session: Default::default(),
+    _streamed_responses_token: streamed_responses_token,
   })
 }
 
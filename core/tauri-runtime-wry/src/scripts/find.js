@@ -0,0 +1,110 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// Find-in-page for the webviews without a native find API.
+// Matches are searched in the visible text nodes and the active one is highlighted with the selection.
+;(function (request) {
+  const state = (window.__TAURI_FIND__ = window.__TAURI_FIND__ || {
+    ranges: [],
+    active: -1,
+    forward: true,
+    wrap: true
+  })
+
+  function search(text, matchCase) {
+    const ranges = []
+    if (!text || !document.body) {
+      return ranges
+    }
+    const needle = matchCase ? text : text.toLowerCase()
+    const walker = document.createTreeWalker(
+      document.body,
+      NodeFilter.SHOW_TEXT,
+      {
+        acceptNode(node) {
+          const parent = node.parentElement
+          if (
+            !parent ||
+            ['SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE'].includes(parent.tagName) ||
+            parent.getClientRects().length === 0
+          ) {
+            return NodeFilter.FILTER_REJECT
+          }
+          return NodeFilter.FILTER_ACCEPT
+        }
+      }
+    )
+    while (walker.nextNode()) {
+      const node = walker.currentNode
+      const haystack = matchCase ? node.data : node.data.toLowerCase()
+      let index = haystack.indexOf(needle)
+      while (index !== -1) {
+        const range = document.createRange()
+        range.setStart(node, index)
+        range.setEnd(node, index + needle.length)
+        ranges.push(range)
+        index = haystack.indexOf(needle, index + needle.length)
+      }
+    }
+    return ranges
+  }
+
+  function select(index) {
+    state.active = index
+    const range = state.ranges[index]
+    const selection = window.getSelection()
+    selection.removeAllRanges()
+    selection.addRange(range)
+    const element = range.startContainer.parentElement
+    if (element) {
+      element.scrollIntoView({ block: 'center', inline: 'nearest' })
+    }
+  }
+
+  // same navigation as `FindState::advance`
+  function advance(next) {
+    const count = state.ranges.length
+    if (count === 0) {
+      return
+    }
+    const forward = state.forward === next
+    let index
+    if (state.active === -1) {
+      index = forward ? 0 : count - 1
+    } else if (forward) {
+      index = state.active + 1 < count ? state.active + 1 : state.wrap ? 0 : state.active
+    } else {
+      index = state.active > 0 ? state.active - 1 : state.wrap ? count - 1 : state.active
+    }
+    select(index)
+  }
+
+  switch (request.kind) {
+    case 'find':
+      state.ranges = search(request.text, request.matchCase)
+      state.active = -1
+      state.forward = request.forward
+      state.wrap = request.wrap
+      advance(true)
+      break
+    case 'next':
+      advance(true)
+      break
+    case 'previous':
+      advance(false)
+      break
+    case 'stop':
+      if (request.clearSelection && state.active !== -1) {
+        window.getSelection().removeAllRanges()
+      }
+      state.ranges = []
+      state.active = -1
+      break
+  }
+
+  return {
+    matches: state.ranges.length,
+    activeIndex: state.active === -1 ? null : state.active
+  }
+})(__REQUEST__)
@@ -0,0 +1,337 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Clearing of the website data stored by the platform webviews.
+
+use tauri_runtime::{Error, Result};
+use url::Url;
+
+/// The website data to clear.
+#[derive(Debug, Clone, Copy)]
+pub enum WebsiteData {
+  /// HTTP disk and memory cache.
+  Cache,
+  /// Service worker registrations.
+  ServiceWorkers,
+  /// Every kind of website data.
+  All,
+}
+
+/// The handler called with the result of a website data clear once the platform completes it.
+pub type ClearedHandler = Box<dyn FnOnce(Result<()>) + Send>;
+
+/// Whether a website data record belongs to the given origin, comparing the scheme, host and port exactly.
+///
+/// Records that are not identified by an origin, such as the bare domains some platforms group the data by, never match.
+#[allow(dead_code)]
+fn record_matches_origin(record: &str, origin: &Url) -> bool {
+  Url::parse(record)
+    .map(|record| record.origin() == origin.origin())
+    .unwrap_or(false)
+}
+
+/// Clears the website data of the given webview, optionally only for the given origin.
+///
+/// The platform APIs are asynchronous so this function returns as soon as the request is dispatched,
+/// `on_cleared` receiving the result once the data is cleared or the request fails.
+pub fn clear(
+  webview: &wry::WebView,
+  data: WebsiteData,
+  origin: Option<&Url>,
+  on_cleared: ClearedHandler,
+) {
+  imp::clear(webview, data, origin, on_cleared)
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+mod imp {
+  use super::*;
+  use gtk::{gio::Cancellable, glib::TimeSpan};
+  use webkit2gtk::{WebViewExt, WebsiteDataManagerExt, WebsiteDataTypes};
+  use wry::WebViewExtUnix;
+
+  fn map_err(e: gtk::glib::Error) -> Error {
+    Error::ClearWebviewData(e.to_string())
+  }
+
+  pub fn clear(
+    webview: &wry::WebView,
+    data: WebsiteData,
+    origin: Option<&Url>,
+    on_cleared: ClearedHandler,
+  ) {
+    let Some(manager) = webview.webview().website_data_manager() else {
+      on_cleared(Err(Error::ClearWebviewData(
+        "website data manager not available".into(),
+      )));
+      return;
+    };
+
+    let types = match data {
+      WebsiteData::Cache => WebsiteDataTypes::DISK_CACHE | WebsiteDataTypes::MEMORY_CACHE,
+      WebsiteData::ServiceWorkers => WebsiteDataTypes::SERVICE_WORKER_REGISTRATIONS,
+      WebsiteData::All => WebsiteDataTypes::ALL,
+    };
+
+    match origin {
+      None => manager.clear(types, TimeSpan(0), None::<&Cancellable>, move |result| {
+        on_cleared(result.map_err(map_err))
+      }),
+      Some(origin) => {
+        let origin = origin.clone();
+        let manager_ = manager.clone();
+        manager.fetch(types, None::<&Cancellable>, move |records| match records {
+          Ok(records) => {
+            let records = records
+              .into_iter()
+              .filter(|record| {
+                record
+                  .name()
+                  .map(|name| record_matches_origin(&name, &origin))
+                  .unwrap_or(false)
+              })
+              .collect::<Vec<_>>();
+            manager_.remove(types, &records, None::<&Cancellable>, move |result| {
+              on_cleared(result.map_err(map_err))
+            });
+          }
+          Err(e) => on_cleared(Err(map_err(e))),
+        });
+      }
+    }
+  }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod imp {
+  use super::*;
+  use block::ConcreteBlock;
+  use cocoa::{
+    base::{id, nil},
+    foundation::{NSArray, NSString},
+  };
+  use objc::{class, msg_send, sel, sel_impl};
+  #[cfg(target_os = "ios")]
+  use wry::WebViewExtIOS;
+  #[cfg(target_os = "macos")]
+  use wry::WebViewExtMacOS;
+
+  unsafe fn data_types(data: WebsiteData) -> id {
+    let types = match data {
+      WebsiteData::Cache => vec!["WKWebsiteDataTypeDiskCache", "WKWebsiteDataTypeMemoryCache"],
+      WebsiteData::ServiceWorkers => vec!["WKWebsiteDataTypeServiceWorkerRegistrations"],
+      WebsiteData::All => return msg_send![class!(WKWebsiteDataStore), allWebsiteDataTypes],
+    };
+    let types = types
+      .into_iter()
+      .map(|t| NSString::alloc(nil).init_str(t))
+      .collect::<Vec<id>>();
+    let array = NSArray::arrayWithObjects(nil, &types);
+    msg_send![class!(NSSet), setWithArray: array]
+  }
+
+  /// Wraps the handler so it can be moved into a completion block, which may be called more than once.
+  fn once(on_cleared: ClearedHandler) -> impl Fn() {
+    let on_cleared = std::cell::Cell::new(Some(on_cleared));
+    move || {
+      if let Some(on_cleared) = on_cleared.take() {
+        on_cleared(Ok(()));
+      }
+    }
+  }
+
+  pub fn clear(
+    webview: &wry::WebView,
+    data: WebsiteData,
+    origin: Option<&Url>,
+    on_cleared: ClearedHandler,
+  ) {
+    unsafe {
+      let configuration: id = msg_send![webview.webview(), configuration];
+      let store: id = msg_send![configuration, websiteDataStore];
+      let types = data_types(data);
+
+      match origin {
+        None => {
+          let date: id = msg_send![class!(NSDate), dateWithTimeIntervalSince1970: 0.0f64];
+          let handler = ConcreteBlock::new(once(on_cleared)).copy();
+          let _: () = msg_send![store, removeDataOfTypes: types modifiedSince: date completionHandler: &*handler];
+        }
+        Some(origin) => {
+          let origin = origin.clone();
+          // keep the types alive until the records are fetched
+          let _: id = msg_send![types, retain];
+          let on_cleared = std::cell::Cell::new(Some(on_cleared));
+          let handler = ConcreteBlock::new(move |records: id| {
+            let Some(on_cleared) = on_cleared.take() else {
+              return;
+            };
+            let count: usize = msg_send![records, count];
+            let matching: id = msg_send![class!(NSMutableArray), array];
+            for i in 0..count {
+              let record: id = msg_send![records, objectAtIndex: i];
+              let name: id = msg_send![record, displayName];
+              let name = std::ffi::CStr::from_ptr(NSString::UTF8String(name))
+                .to_string_lossy()
+                .into_owned();
+              if record_matches_origin(&name, &origin) {
+                let _: () = msg_send![matching, addObject: record];
+              }
+            }
+            let done = ConcreteBlock::new(once(on_cleared)).copy();
+            let _: () = msg_send![store, removeDataOfTypes: types forDataRecords: matching completionHandler: &*done];
+            let _: () = msg_send![types, release];
+          })
+          .copy();
+          let _: () = msg_send![store, fetchDataRecordsOfTypes: types completionHandler: &*handler];
+        }
+      }
+    }
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use super::*;
+  use webview2_com::{
+    CallDevToolsProtocolMethodCompletedHandler, ClearBrowsingDataCompletedHandler,
+    Microsoft::Web::WebView2::Win32::{
+      ICoreWebView2Profile2, ICoreWebView2_13, COREWEBVIEW2_BROWSING_DATA_KINDS_ALL_PROFILE,
+      COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE,
+      COREWEBVIEW2_BROWSING_DATA_KINDS_SERVICE_WORKERS,
+    },
+  };
+  use windows::core::{Interface, HSTRING};
+  use wry::WebViewExtWindows;
+
+  fn map_err(e: windows::core::Error) -> Error {
+    Error::ClearWebviewData(e.to_string())
+  }
+
+  pub fn clear(
+    webview: &wry::WebView,
+    data: WebsiteData,
+    origin: Option<&Url>,
+    on_cleared: ClearedHandler,
+  ) {
+    // the platform completion handlers are not called when the request fails to dispatch
+    let on_cleared = std::sync::Arc::new(std::sync::Mutex::new(Some(on_cleared)));
+    let complete = move |result: Result<()>| {
+      if let Some(on_cleared) = on_cleared.lock().unwrap().take() {
+        on_cleared(result);
+      }
+    };
+    let complete_ = complete.clone();
+    if let Err(e) = dispatch(webview, data, origin, complete_) {
+      complete(Err(e));
+    }
+  }
+
+  fn dispatch(
+    webview: &wry::WebView,
+    data: WebsiteData,
+    origin: Option<&Url>,
+    complete: impl FnOnce(Result<()>) + 'static,
+  ) -> Result<()> {
+    unsafe {
+      let core_webview = webview.controller().CoreWebView2().map_err(map_err)?;
+
+      match origin {
+        None => {
+          let kinds = match data {
+            WebsiteData::Cache => COREWEBVIEW2_BROWSING_DATA_KINDS_DISK_CACHE,
+            WebsiteData::ServiceWorkers => COREWEBVIEW2_BROWSING_DATA_KINDS_SERVICE_WORKERS,
+            WebsiteData::All => COREWEBVIEW2_BROWSING_DATA_KINDS_ALL_PROFILE,
+          };
+          let profile = core_webview
+            .cast::<ICoreWebView2_13>()
+            .and_then(|webview| webview.Profile())
+            .and_then(|profile| profile.cast::<ICoreWebView2Profile2>())
+            .map_err(map_err)?;
+          profile
+            .ClearBrowsingData(
+              kinds,
+              &ClearBrowsingDataCompletedHandler::create(Box::new(move |result| {
+                complete(result.map_err(map_err));
+                Ok(())
+              })),
+            )
+            .map_err(map_err)?;
+        }
+        Some(origin) => {
+          let storage_types = match data {
+            WebsiteData::Cache => "cache_storage",
+            WebsiteData::ServiceWorkers => "service_workers",
+            WebsiteData::All => "all",
+          };
+          let params = format!(
+            r#"{{"origin":"{}","storageTypes":"{storage_types}"}}"#,
+            origin.origin().ascii_serialization()
+          );
+          core_webview
+            .CallDevToolsProtocolMethod(
+              &HSTRING::from("Storage.clearDataForOrigin"),
+              &HSTRING::from(params),
+              &CallDevToolsProtocolMethodCompletedHandler::create(Box::new(move |result, _| {
+                complete(result.map_err(map_err));
+                Ok(())
+              })),
+            )
+            .map_err(map_err)?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(target_os = "android")]
+mod imp {
+  use super::*;
+
+  pub fn clear(
+    _webview: &wry::WebView,
+    _data: WebsiteData,
+    _origin: Option<&Url>,
+    on_cleared: ClearedHandler,
+  ) {
+    on_cleared(Err(Error::ClearWebviewData(
+      "clearing webview data is not supported on Android".into(),
+    )))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::record_matches_origin;
+
+  #[test]
+  fn matches_exact_origin() {
+    let origin = "https://app.example.com/index.html".parse().unwrap();
+    assert!(record_matches_origin("https://app.example.com", &origin));
+    assert!(record_matches_origin(
+      "https://app.example.com:443",
+      &origin
+    ));
+    assert!(!record_matches_origin("http://app.example.com", &origin));
+    assert!(!record_matches_origin(
+      "https://app.example.com:8443",
+      &origin
+    ));
+    assert!(!record_matches_origin("https://example.com", &origin));
+    assert!(!record_matches_origin(
+      "https://sub.app.example.com",
+      &origin
+    ));
+    assert!(!record_matches_origin("app.example.com", &origin));
+    assert!(!record_matches_origin("example.com", &origin));
+  }
+}
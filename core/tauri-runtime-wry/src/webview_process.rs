@@ -0,0 +1,56 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Identification of the OS process rendering the webview content.
+
+/// Returns the identifier of the process rendering the given webview, if the platform exposes it.
+pub fn process_id(webview: &wry::WebView) -> Option<u32> {
+  imp::process_id(webview)
+}
+
+#[cfg(windows)]
+mod imp {
+  use wry::WebViewExtWindows;
+
+  /// The WebView2 browser process, shared by the webviews using the same user data folder.
+  pub fn process_id(webview: &wry::WebView) -> Option<u32> {
+    let mut pid = 0;
+    unsafe {
+      webview
+        .controller()
+        .CoreWebView2()
+        .and_then(|webview| webview.BrowserProcessId(&mut pid))
+        .ok()?;
+    }
+    Some(pid)
+  }
+}
+
+#[cfg(all(target_os = "macos", feature = "macos-private-api"))]
+mod imp {
+  use cocoa::base::{id, BOOL, NO};
+  use objc::{msg_send, sel, sel_impl};
+  use wry::WebViewExtMacOS;
+
+  /// The WebKit web content process, only exposed by a private API.
+  pub fn process_id(webview: &wry::WebView) -> Option<u32> {
+    unsafe {
+      let webview: id = webview.webview();
+      let responds: BOOL = msg_send![webview, respondsToSelector: sel!(_webProcessIdentifier)];
+      if responds == NO {
+        return None;
+      }
+      let pid: i32 = msg_send![webview, _webProcessIdentifier];
+      u32::try_from(pid).ok().filter(|pid| *pid != 0)
+    }
+  }
+}
+
+// WebKitGTK and Android do not expose the web process
+#[cfg(not(any(windows, all(target_os = "macos", feature = "macos-private-api"))))]
+mod imp {
+  pub fn process_id(_webview: &wry::WebView) -> Option<u32> {
+    None
+  }
+}
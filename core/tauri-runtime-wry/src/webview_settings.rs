@@ -0,0 +1,261 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Webview engine settings applied through the platform webviews.
+
+use tauri_runtime::webview::UnsupportedWebviewSetting;
+use tauri_utils::config::{BackgroundThrottlingPolicy, WebviewSettingsConfig};
+
+/// The default browser arguments passed by wry to WebView2,
+/// kept when the settings require additional arguments.
+#[cfg(windows)]
+pub const DEFAULT_BROWSER_ARGS: &str =
+  "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+/// Returns the settings the platform webview does not support.
+pub fn unsupported(settings: &WebviewSettingsConfig) -> Vec<UnsupportedWebviewSetting> {
+  unsupported_with(settings, imp::unsupported_reason)
+}
+
+/// Returns an [`UnsupportedWebviewSetting`] for each setting that is set
+/// and for which `unsupported_reason` returns a reason.
+fn unsupported_with(
+  settings: &WebviewSettingsConfig,
+  unsupported_reason: impl Fn(&'static str) -> Option<String>,
+) -> Vec<UnsupportedWebviewSetting> {
+  [
+    ("autoplay", settings.autoplay.is_some()),
+    (
+      "backgroundThrottling",
+      settings.background_throttling.is_some(),
+    ),
+    ("smoothScrolling", settings.smooth_scrolling.is_some()),
+  ]
+  .into_iter()
+  .filter(|(_, set)| *set)
+  .filter_map(|(key, _)| {
+    unsupported_reason(key).map(|reason| UnsupportedWebviewSetting { key, reason })
+  })
+  .collect()
+}
+
+/// Unsets the given unsupported settings, returning the settings in effect.
+pub fn without_unsupported(
+  mut settings: WebviewSettingsConfig,
+  unsupported: &[UnsupportedWebviewSetting],
+) -> WebviewSettingsConfig {
+  for setting in unsupported {
+    match setting.key {
+      "autoplay" => settings.autoplay = None,
+      "backgroundThrottling" => settings.background_throttling = None,
+      "smoothScrolling" => settings.smooth_scrolling = None,
+      _ => (),
+    }
+  }
+  settings
+}
+
+/// Returns the WebView2 browser arguments needed by the settings.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn browser_args(settings: &WebviewSettingsConfig) -> Vec<&'static str> {
+  let mut args = Vec::new();
+  if settings.background_throttling == Some(BackgroundThrottlingPolicy::Disabled) {
+    args.extend([
+      "--disable-background-timer-throttling",
+      "--disable-backgrounding-occluded-windows",
+      "--disable-renderer-backgrounding",
+    ]);
+  }
+  if settings.smooth_scrolling == Some(false) {
+    args.push("--disable-smooth-scrolling");
+  }
+  args
+}
+
+/// Applies the settings that can only be set once the webview is created.
+///
+/// The media autoplay policy is set on the [`wry::WebViewBuilder`] and the WebView2 settings are browser arguments.
+pub fn apply(webview: &wry::WebView, settings: &WebviewSettingsConfig) {
+  imp::apply(webview, settings)
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+mod imp {
+  use super::*;
+  use webkit2gtk::{SettingsExt, WebViewExt};
+  use wry::WebViewExtUnix;
+
+  pub fn unsupported_reason(key: &str) -> Option<String> {
+    match key {
+      "backgroundThrottling" => Some("WebKitGTK does not expose its background throttling".into()),
+      _ => None,
+    }
+  }
+
+  pub fn apply(webview: &wry::WebView, settings: &WebviewSettingsConfig) {
+    if let Some(smooth_scrolling) = settings.smooth_scrolling {
+      if let Some(webkit_settings) = WebViewExt::settings(&webview.webview()) {
+        webkit_settings.set_enable_smooth_scrolling(smooth_scrolling);
+      }
+    }
+  }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod imp {
+  use super::*;
+  use cocoa::base::{id, BOOL, YES};
+  use objc::{class, msg_send, sel, sel_impl};
+  #[cfg(target_os = "ios")]
+  use wry::WebViewExtIOS;
+  #[cfg(target_os = "macos")]
+  use wry::WebViewExtMacOS;
+
+  /// `WKInactiveSchedulingPolicyNone`
+  const INACTIVE_SCHEDULING_POLICY_NONE: isize = 2;
+
+  fn supports_inactive_scheduling_policy() -> bool {
+    unsafe {
+      let responds: BOOL = msg_send![
+        class!(WKPreferences),
+        instancesRespondToSelector: sel!(setInactiveSchedulingPolicy:)
+      ];
+      responds == YES
+    }
+  }
+
+  pub fn unsupported_reason(key: &str) -> Option<String> {
+    match key {
+      "backgroundThrottling" if !supports_inactive_scheduling_policy() => {
+        Some("requires macOS 14 / iOS 17 or newer".into())
+      }
+      "smoothScrolling" => Some("WKWebView does not expose its smooth scrolling".into()),
+      _ => None,
+    }
+  }
+
+  pub fn apply(webview: &wry::WebView, settings: &WebviewSettingsConfig) {
+    // the default policy is left to the engine
+    if settings.background_throttling == Some(BackgroundThrottlingPolicy::Disabled) {
+      unsafe {
+        let configuration: id = msg_send![webview.webview(), configuration];
+        let preferences: id = msg_send![configuration, preferences];
+        let _: () =
+          msg_send![preferences, setInactiveSchedulingPolicy: INACTIVE_SCHEDULING_POLICY_NONE];
+      }
+    }
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use super::*;
+
+  pub fn unsupported_reason(_key: &str) -> Option<String> {
+    None
+  }
+
+  pub fn apply(_webview: &wry::WebView, _settings: &WebviewSettingsConfig) {}
+}
+
+#[cfg(target_os = "android")]
+mod imp {
+  use super::*;
+
+  pub fn unsupported_reason(key: &str) -> Option<String> {
+    match key {
+      "backgroundThrottling" | "smoothScrolling" => {
+        Some("the Android webview does not expose it".into())
+      }
+      _ => None,
+    }
+  }
+
+  pub fn apply(_webview: &wry::WebView, _settings: &WebviewSettingsConfig) {}
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{browser_args, unsupported_with, without_unsupported};
+  use tauri_utils::config::{AutoplayPolicy, BackgroundThrottlingPolicy, WebviewSettingsConfig};
+
+  #[test]
+  fn unsupported_settings_accumulate() {
+    let settings = WebviewSettingsConfig {
+      autoplay: Some(AutoplayPolicy::Allow),
+      background_throttling: Some(BackgroundThrottlingPolicy::Disabled),
+      smooth_scrolling: Some(false),
+    };
+
+    let unsupported = unsupported_with(&settings, |key| {
+      (key != "autoplay").then(|| format!("{key} is unsupported"))
+    });
+    assert_eq!(
+      unsupported
+        .iter()
+        .map(|setting| (setting.key, setting.reason.as_str()))
+        .collect::<Vec<_>>(),
+      [
+        (
+          "backgroundThrottling",
+          "backgroundThrottling is unsupported"
+        ),
+        ("smoothScrolling", "smoothScrolling is unsupported"),
+      ]
+    );
+
+    assert_eq!(
+      without_unsupported(settings.clone(), &unsupported),
+      WebviewSettingsConfig {
+        autoplay: Some(AutoplayPolicy::Allow),
+        ..Default::default()
+      }
+    );
+
+    assert!(unsupported_with(&settings, |_| None).is_empty());
+  }
+
+  #[test]
+  fn unset_settings_are_not_reported() {
+    let settings = WebviewSettingsConfig {
+      smooth_scrolling: Some(true),
+      ..Default::default()
+    };
+    let unsupported = unsupported_with(&settings, |_| Some("unsupported".into()));
+    assert_eq!(
+      unsupported.iter().map(|s| s.key).collect::<Vec<_>>(),
+      ["smoothScrolling"]
+    );
+  }
+
+  #[test]
+  fn webview2_browser_args() {
+    assert!(browser_args(&WebviewSettingsConfig::default()).is_empty());
+    assert!(browser_args(&WebviewSettingsConfig {
+      background_throttling: Some(BackgroundThrottlingPolicy::Default),
+      smooth_scrolling: Some(true),
+      ..Default::default()
+    })
+    .is_empty());
+    assert_eq!(
+      browser_args(&WebviewSettingsConfig {
+        background_throttling: Some(BackgroundThrottlingPolicy::Disabled),
+        smooth_scrolling: Some(false),
+        ..Default::default()
+      }),
+      [
+        "--disable-background-timer-throttling",
+        "--disable-backgrounding-occluded-windows",
+        "--disable-renderer-backgrounding",
+        "--disable-smooth-scrolling",
+      ]
+    );
+  }
+}
@@ -100,13 +100,41 @@ pub enum UserAttentionType {
   /// ## Platform-specific
   /// - **macOS:** Bounces the dock icon until the application is in focus.
   /// - **Windows:** Flashes both the window and the taskbar button until the application is in focus.
+  /// - **Linux (X11):** Sets the urgency hint, usually highlighting the taskbar entry until the window is focused.
+  /// - **Linux (Wayland):** Requests the activation of the window with the `xdg-activation` protocol,
+  ///   which the compositor shows as a notification or a highlighted taskbar entry when the window is not focused.
   Critical,
   /// ## Platform-specific
   /// - **macOS:** Bounces the dock icon once.
   /// - **Windows:** Flashes the taskbar button until the application is in focus.
+  /// - **Linux (X11):** Sets the urgency hint, usually highlighting the taskbar entry until the window is focused.
+  /// - **Linux (Wayland):** Requests the activation of the window with the `xdg-activation` protocol,
+  ///   which the compositor shows as a notification or a highlighted taskbar entry when the window is not focused.
   Informational,
 }
 
+/// A sound of the system, played with [`RuntimeHandle::play_system_sound`].
+///
+/// ## Platform-specific
+///
+/// - **macOS:** All the sounds play the alert sound selected in the system settings.
+/// - **Linux:** All the sounds ring the display bell, which desktops usually map to their alert sound.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SystemSound {
+  /// The default alert sound.
+  #[default]
+  Alert,
+  /// The sound of informational messages.
+  Information,
+  /// The sound of warnings.
+  Warning,
+  /// The sound of errors.
+  Error,
+  /// The sound of questions.
+  Question,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(tag = "type")]
 pub enum DeviceEventFilter {
@@ -183,6 +211,9 @@ pub enum Error {
   InvalidProxyUrl,
   #[error("window not found")]
   WindowNotFound,
+  /// Failed to clear the webview data.
+  #[error("failed to clear webview data: {0}")]
+  ClearWebviewData(String),
 }
 
 /// Result type.
@@ -286,6 +317,9 @@ pub trait RuntimeHandle<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 'st
   /// Requests an exit of the event loop.
   fn request_exit(&self, code: i32) -> Result<()>;
 
+  /// Plays a sound of the system.
+  fn play_system_sound(&self, sound: SystemSound) -> Result<()>;
+
   /// Create a new window.
   fn create_window<F: Fn(RawWindow) + Send + 'static>(
     &self,
@@ -478,6 +512,16 @@ pub trait WebviewDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + '
   /// Returns the physical size of the webviews's client area.
   fn size(&self) -> Result<PhysicalSize<u32>>;
 
+  /// Returns the identifier of the OS process rendering the webview content,
+  /// or `None` if the platform does not expose it.
+  fn process_id(&self) -> Result<Option<u32>>;
+
+  /// Returns the webview engine settings in effect, without the settings the platform does not support.
+  fn webview_settings(&self) -> Result<tauri_utils::config::WebviewSettingsConfig>;
+
+  /// Returns the webview engine settings that were ignored because the platform does not support them.
+  fn unsupported_webview_settings(&self) -> Result<Vec<webview::UnsupportedWebviewSetting>>;
+
   // SETTER
 
   /// Navigate to the given URL.
@@ -512,6 +556,34 @@ pub trait WebviewDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + '
 
   /// Set the webview zoom level
   fn set_zoom(&self, scale_factor: f64) -> Result<()>;
+
+  /// Clears the webview HTTP disk and memory cache.
+  fn clear_cache(&self) -> Result<()>;
+
+  /// Unregisters all service workers registered in the webview.
+  ///
+  /// Returns once the request is dispatched, `on_complete` receiving the result once the platform unregisters them.
+  fn unregister_service_workers<F: FnOnce(Result<()>) + Send + 'static>(
+    &self,
+    on_complete: F,
+  ) -> Result<()>;
+
+  /// Clears all website data (cache, cookies, storage and service workers) stored for the given origin.
+  fn clear_all_site_data_for_origin(&self, origin: Url) -> Result<()>;
+
+  /// Searches the page for the text and highlights the first match.
+  ///
+  /// The result is emitted as [`WebviewEvent::FindResult`], as are the results of the other find methods.
+  fn find(&self, text: String, options: webview::FindOptions) -> Result<()>;
+
+  /// Highlights the next match of the current search.
+  fn find_next(&self) -> Result<()>;
+
+  /// Highlights the previous match of the current search.
+  fn find_previous(&self) -> Result<()>;
+
+  /// Ends the current search, clearing the highlighted match if `clear_selection` is `true`.
+  fn stop_find(&self, clear_selection: bool) -> Result<()>;
 }
 
 /// Window dispatcher. A thread-safe handle to the window APIs.
@@ -647,6 +719,9 @@ pub trait WindowDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 's
   /// Providing `None` will unset the request for user attention.
   fn request_user_attention(&self, request_type: Option<UserAttentionType>) -> Result<()>;
 
+  /// Plays the alert sound of the system for the window.
+  fn beep(&self) -> Result<()>;
+
   /// Create a new window.
   fn create_window<F: Fn(RawWindow) + Send + 'static>(
     &mut self,
@@ -7,21 +7,47 @@
 use crate::{window::is_label_valid, Rect, Runtime, UserEvent};
 
 use http::Request;
-use tauri_utils::config::{WebviewUrl, WindowConfig, WindowEffectsConfig};
+use serde::{Deserialize, Serialize};
+use tauri_utils::config::{
+  CookiePersistence, WebviewSettingsConfig, WebviewUrl, WindowConfig, WindowEffectsConfig,
+};
 use url::Url;
 
 use std::{
   borrow::Cow,
   collections::HashMap,
   hash::{Hash, Hasher},
+  io::Read,
   path::PathBuf,
   sync::Arc,
 };
 
-type UriSchemeProtocol = dyn Fn(http::Request<Vec<u8>>, Box<dyn FnOnce(http::Response<Cow<'static, [u8]>>) + Send>)
-  + Send
-  + Sync
-  + 'static;
+/// A custom protocol handler, responding to the request with the given responder.
+pub type UriSchemeProtocol =
+  dyn Fn(http::Request<Vec<u8>>, UriSchemeResponder) + Send + Sync + 'static;
+
+/// Responds to a custom protocol request.
+pub type UriSchemeResponder = Box<dyn FnOnce(http::Response<UriSchemeResponseBody>) + Send>;
+
+/// The body of a custom protocol response.
+pub enum UriSchemeResponseBody {
+  /// The complete body.
+  Full(Cow<'static, [u8]>),
+  /// A body read in chunks as it is produced, ending when the reader returns `Ok(0)`.
+  ///
+  /// The request fails if the reader returns an error.
+  /// The runtime drops the reader when the request is cancelled, so the body is no longer produced.
+  Stream(Box<dyn Read + Send>),
+}
+
+impl std::fmt::Debug for UriSchemeResponseBody {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Full(body) => f.debug_tuple("Full").field(body).finish(),
+      Self::Stream(_) => f.write_str("Stream(..)"),
+    }
+  }
+}
 
 type WebResourceRequestHandler =
   dyn Fn(http::Request<Vec<u8>>, &mut http::Response<Cow<'static, [u8]>>) + Send + Sync;
@@ -129,10 +155,7 @@ impl<T: UserEvent, R: Runtime<T>> PendingWebview<T, R> {
 
   pub fn register_uri_scheme_protocol<
     N: Into<String>,
-    H: Fn(http::Request<Vec<u8>>, Box<dyn FnOnce(http::Response<Cow<'static, [u8]>>) + Send>)
-      + Send
-      + Sync
-      + 'static,
+    H: Fn(http::Request<Vec<u8>>, UriSchemeResponder) + Send + Sync + 'static,
   >(
     &mut self,
     uri_scheme: N,
@@ -203,17 +226,21 @@ pub struct WebviewAttributes {
   pub additional_browser_args: Option<String>,
   pub window_effects: Option<WindowEffectsConfig>,
   pub incognito: bool,
+  pub cookie_persistence: CookiePersistence,
   pub transparent: bool,
   pub bounds: Option<Rect>,
   pub auto_resize: bool,
   pub proxy_url: Option<Url>,
   pub zoom_hotkeys_enabled: bool,
+  pub clear_service_workers_on_version_change: bool,
+  pub webview_settings: WebviewSettingsConfig,
 }
 
 impl From<&WindowConfig> for WebviewAttributes {
   fn from(config: &WindowConfig) -> Self {
     let mut builder = Self::new(config.url.clone());
     builder = builder.incognito(config.incognito);
+    builder = builder.cookie_persistence(config.cookie_persistence);
     #[cfg(any(not(target_os = "macos"), feature = "macos-private-api"))]
     {
       builder = builder.transparent(config.transparent);
@@ -235,6 +262,9 @@ impl From<&WindowConfig> for WebviewAttributes {
       builder = builder.proxy_url(url.to_owned());
     }
     builder = builder.zoom_hotkeys_enabled(config.zoom_hotkeys_enabled);
+    builder = builder
+      .clear_service_workers_on_version_change(config.clear_service_workers_on_version_change);
+    builder = builder.webview_settings(config.webview_settings.clone());
     builder
   }
 }
@@ -253,11 +283,14 @@ impl WebviewAttributes {
       additional_browser_args: None,
       window_effects: None,
       incognito: false,
+      cookie_persistence: CookiePersistence::Persistent,
       transparent: false,
       bounds: None,
       auto_resize: false,
       proxy_url: None,
       zoom_hotkeys_enabled: false,
+      clear_service_workers_on_version_change: false,
+      webview_settings: Default::default(),
     }
   }
 
@@ -327,6 +360,25 @@ impl WebviewAttributes {
     self
   }
 
+  /// Sets whether the webview cookies and website data are kept between runs.
+  #[must_use]
+  pub fn cookie_persistence(mut self, cookie_persistence: CookiePersistence) -> Self {
+    self.cookie_persistence = cookie_persistence;
+    self
+  }
+
+  /// The cookie persistence the webview is created with.
+  ///
+  /// Incognito webviews always use [`CookiePersistence::Session`]
+  /// and Android webviews always use [`CookiePersistence::Persistent`].
+  pub fn effective_cookie_persistence(&self) -> CookiePersistence {
+    effective_cookie_persistence(
+      self.incognito,
+      self.cookie_persistence,
+      cfg!(target_os = "android"),
+    )
+  }
+
   /// Enable or disable transparency for the WebView.
   #[cfg(any(not(target_os = "macos"), feature = "macos-private-api"))]
   #[must_use]
@@ -363,7 +415,165 @@ impl WebviewAttributes {
     self.zoom_hotkeys_enabled = enabled;
     self
   }
+
+  /// Whether to unregister the service workers of the webview when the app version changes.
+  #[must_use]
+  pub fn clear_service_workers_on_version_change(mut self, enabled: bool) -> Self {
+    self.clear_service_workers_on_version_change = enabled;
+    self
+  }
+
+  /// Sets the webview engine settings such as the media autoplay policy, the background throttling and smooth scrolling.
+  #[must_use]
+  pub fn webview_settings(mut self, settings: WebviewSettingsConfig) -> Self {
+    self.webview_settings = settings;
+    self
+  }
+}
+
+/// A webview setting that the platform webview does not support, ignored when creating the webview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedWebviewSetting {
+  /// The setting key, as written in the `webviewSettings` configuration e.g. `backgroundThrottling`.
+  pub key: &'static str,
+  /// Why the setting could not be applied.
+  pub reason: String,
+}
+
+impl std::fmt::Display for UnsupportedWebviewSetting {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "webview setting `{}` is not supported: {}",
+      self.key, self.reason
+    )
+  }
+}
+
+/// The options of a find-in-page search, see [`crate::WebviewDispatch::find`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindOptions {
+  /// Search towards the end of the page, which is also the direction [`crate::WebviewDispatch::find_next`] moves in.
+  ///
+  /// The default is `true`.
+  pub forward: bool,
+  /// Only find the text with the same case.
+  ///
+  /// The default is `false`.
+  pub match_case: bool,
+  /// Continue from the other end of the page after the last match.
+  ///
+  /// The default is `true`.
+  pub wrap: bool,
+}
+
+impl Default for FindOptions {
+  fn default() -> Self {
+    Self {
+      forward: true,
+      match_case: false,
+      wrap: true,
+    }
+  }
+}
+
+/// The result of a find-in-page search, emitted as [`crate::window::WebviewEvent::FindResult`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindResult {
+  /// The number of matches in the page.
+  pub matches: u32,
+  /// The index of the highlighted match, `None` when nothing is highlighted.
+  pub active_index: Option<u32>,
 }
 
 /// IPC handler.
 pub type WebviewIpcHandler<T, R> = Box<dyn Fn(DetachedWebview<T, R>, Request<String>) + Send>;
+
+fn effective_cookie_persistence(
+  incognito: bool,
+  cookie_persistence: CookiePersistence,
+  android: bool,
+) -> CookiePersistence {
+  if android {
+    CookiePersistence::Persistent
+  } else if incognito {
+    CookiePersistence::Session
+  } else {
+    cookie_persistence
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{effective_cookie_persistence, FindOptions, FindResult, WebviewAttributes};
+  use tauri_utils::config::{CookiePersistence, WindowConfig};
+
+  #[test]
+  fn find_serialization() {
+    assert_eq!(
+      serde_json::from_str::<FindOptions>(r#"{ "matchCase": true }"#).unwrap(),
+      FindOptions {
+        forward: true,
+        match_case: true,
+        wrap: true,
+      }
+    );
+    assert_eq!(
+      serde_json::to_value(FindResult {
+        matches: 3,
+        active_index: Some(1),
+      })
+      .unwrap(),
+      serde_json::json!({ "matches": 3, "activeIndex": 1 })
+    );
+    assert_eq!(
+      serde_json::from_str::<FindResult>(r#"{ "matches": 0, "activeIndex": null }"#).unwrap(),
+      FindResult {
+        matches: 0,
+        active_index: None,
+      }
+    );
+  }
+
+  #[test]
+  fn cookie_persistence_mapping() {
+    use CookiePersistence::{Persistent, Session};
+
+    for (incognito, cookie_persistence, android, expected) in [
+      (false, Persistent, false, Persistent),
+      (false, Session, false, Session),
+      (true, Persistent, false, Session),
+      (true, Session, false, Session),
+      (false, Session, true, Persistent),
+      (true, Persistent, true, Persistent),
+    ] {
+      assert_eq!(
+        effective_cookie_persistence(incognito, cookie_persistence, android),
+        expected,
+        "incognito: {incognito}, cookie persistence: {cookie_persistence:?}, android: {android}"
+      );
+    }
+  }
+
+  #[test]
+  fn cookie_persistence_from_config() {
+    let config: WindowConfig = serde_json::from_value(serde_json::json!({
+      "cookiePersistence": "session"
+    }))
+    .unwrap();
+    let attributes = WebviewAttributes::from(&config);
+    assert_eq!(attributes.cookie_persistence, CookiePersistence::Session);
+    #[cfg(not(target_os = "android"))]
+    assert_eq!(
+      attributes.effective_cookie_persistence(),
+      CookiePersistence::Session
+    );
+
+    assert_eq!(
+      WebviewAttributes::from(&WindowConfig::default()).cookie_persistence,
+      CookiePersistence::Persistent
+    );
+  }
+}
@@ -66,6 +66,8 @@ pub enum WindowEvent {
 pub enum WebviewEvent {
   /// An event associated with the drag and drop action.
   DragDrop(DragDropEvent),
+  /// The result of a find-in-page search, see [`crate::WebviewDispatch::find`].
+  FindResult(crate::webview::FindResult),
 }
 
 /// The drag drop event payload.
@@ -115,24 +115,31 @@ pub fn define_global_scope_schema(
   Ok(())
 }
 
+/// Lists the capability files matching the given glob pattern.
+pub fn capability_files(capabilities_path_pattern: &str) -> Result<Vec<PathBuf>, Error> {
+  Ok(
+    glob::glob(capabilities_path_pattern)?
+      .flatten() // filter extension
+      .filter(|p| {
+        p.extension()
+          .and_then(|e| e.to_str())
+          .map(|e| CAPABILITY_FILE_EXTENSIONS.contains(&e))
+          .unwrap_or_default()
+      })
+      // filter schema files
+      // TODO: remove this before stable
+      .filter(|p| p.parent().unwrap().file_name().unwrap() != CAPABILITIES_SCHEMA_FOLDER_NAME)
+      .collect(),
+  )
+}
+
 /// Parses all capability files with the given glob pattern.
 pub fn parse_capabilities(
   capabilities_path_pattern: &str,
 ) -> Result<BTreeMap<String, Capability>, Error> {
   let mut capabilities_map = BTreeMap::new();
 
-  for path in glob::glob(capabilities_path_pattern)?
-    .flatten() // filter extension
-    .filter(|p| {
-      p.extension()
-        .and_then(|e| e.to_str())
-        .map(|e| CAPABILITY_FILE_EXTENSIONS.contains(&e))
-        .unwrap_or_default()
-    })
-    // filter schema files
-    // TODO: remove this before stable
-    .filter(|p| p.parent().unwrap().file_name().unwrap() != CAPABILITIES_SCHEMA_FOLDER_NAME)
-  {
+  for path in capability_files(capabilities_path_pattern)? {
     match CapabilityFile::load(&path)? {
       CapabilityFile::Capability(capability) => {
         capabilities_map.insert(capability.identifier.clone(), capability);
@@ -305,6 +312,8 @@ pub fn generate_docs(
 }
 
 /// Read all permissions listed from the defined cargo cfg key value.
+///
+/// Emits a `rerun-if-changed` instruction for the list and for each permission file.
 pub fn read_permissions() -> Result<HashMap<String, Vec<PermissionFile>>, Error> {
   let mut permissions_map = HashMap::new();
 
@@ -321,8 +330,12 @@ pub fn read_permissions() -> Result<HashMap<String, Vec<PermissionFile>>, Error>
       })
     {
       let permissions_path = PathBuf::from(value);
+      println!("cargo:rerun-if-changed={}", permissions_path.display());
       let permissions_str = std::fs::read_to_string(&permissions_path).map_err(Error::ReadFile)?;
       let permissions: Vec<PathBuf> = serde_json::from_str(&permissions_str)?;
+      for path in &permissions {
+        println!("cargo:rerun-if-changed={}", path.display());
+      }
       let permissions = parse_permissions(permissions)?;
 
       let plugin_crate_name = plugin_crate_name_var.to_lowercase().replace('_', "-");
@@ -340,6 +353,8 @@ pub fn read_permissions() -> Result<HashMap<String, Vec<PermissionFile>>, Error>
 }
 
 /// Read all global scope schemas listed from the defined cargo cfg key value.
+///
+/// Emits a `rerun-if-changed` instruction for each schema file.
 pub fn read_global_scope_schemas() -> Result<HashMap<String, serde_json::Value>, Error> {
   let mut permissions_map = HashMap::new();
 
@@ -356,6 +371,7 @@ pub fn read_global_scope_schemas() -> Result<HashMap<String, serde_json::Value>,
       })
     {
       let path = PathBuf::from(value);
+      println!("cargo:rerun-if-changed={}", path.display());
       let json = std::fs::read_to_string(&path).map_err(Error::ReadFile)?;
       let schema: serde_json::Value = serde_json::from_str(&json)?;
 
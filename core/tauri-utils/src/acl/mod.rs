@@ -32,6 +32,10 @@ pub use self::{identifier::*, value::*};
 
 /// Known filename of the permission schema JSON file
 pub const PERMISSION_SCHEMA_FILE_NAME: &str = "schema.json";
+/// Known filename of the capabilities JSON schema generated in the `gen/schemas` folder of the project.
+///
+/// It describes the permissions of the core, the app and its plugins for the current target.
+pub const CAPABILITIES_SCHEMA_FILE_NAME: &str = "capabilities.schema.json";
 /// Known ACL key for the app permissions.
 pub const APP_ACL_KEY: &str = "__app-acl__";
 
@@ -41,6 +45,8 @@ pub mod capability;
 pub mod identifier;
 pub mod manifest;
 pub mod resolved;
+#[cfg(feature = "test-helpers")]
+pub mod test;
 pub mod value;
 
 /// Possible errors while processing ACL files.
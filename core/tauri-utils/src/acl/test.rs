@@ -0,0 +1,323 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Helpers to unit test permissions and scopes without running an application.
+//!
+//! The [`AclBuilder`] resolves the ACL from in-memory permission and capability definitions,
+//! and [`Resolved::authorize`] checks a command invocation the same way the runtime authority does.
+//!
+//! # Examples
+//!
+//! A plugin can check its permission files in its tests:
+//!
+//! ```
+//! use tauri_utils::acl::test::{AclBuilder, Denial, Origin};
+//!
+//! #[derive(Debug, PartialEq, serde::Deserialize)]
+//! struct Entry {
+//!   path: String,
+//! }
+//!
+//! let resolved = AclBuilder::new()
+//!   // usually `include_str!("../permissions/read.toml")`
+//!   .plugin(
+//!     "fs",
+//!     r#"
+//! [[permission]]
+//! identifier = "allow-read"
+//! commands.allow = ["read"]
+//!
+//! [[permission.scope.allow]]
+//! path = "$APPDATA/*"
+//! "#,
+//!   )
+//!   .unwrap()
+//!   .capability(
+//!     r#"{ "identifier": "main", "windows": ["main"], "permissions": ["fs:allow-read"] }"#,
+//!   )
+//!   .unwrap()
+//!   .resolve()
+//!   .unwrap();
+//!
+//! let authorization = resolved.authorize("plugin:fs|read", "main", &Origin::Local);
+//! let scope = authorization.scope().expect("read is allowed");
+//! let (allow, deny) = scope.deserialize::<Entry>().unwrap();
+//! assert_eq!(allow, vec![Entry { path: "$APPDATA/*".into() }]);
+//! assert!(deny.is_empty());
+//!
+//! assert_eq!(
+//!   resolved.authorize("plugin:fs|write", "main", &Origin::Local).denial(),
+//!   Some(Denial::NotAllowed)
+//! );
+//! assert_eq!(
+//!   resolved.authorize("plugin:fs|read", "other", &Origin::Local).denial(),
+//!   Some(Denial::Label)
+//! );
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use crate::platform::Target;
+
+use super::{
+  capability::{Capability, CapabilityFile},
+  manifest::{Manifest, PermissionFile},
+  resolved::{Resolved, ResolvedCommand, ResolvedScope},
+  Error, ExecutionContext, APP_ACL_KEY,
+};
+
+/// Builds a [`Resolved`] ACL from in-memory permission and capability definitions.
+#[derive(Debug)]
+pub struct AclBuilder {
+  permission_files: BTreeMap<String, Vec<PermissionFile>>,
+  capabilities: BTreeMap<String, Capability>,
+  target: Target,
+}
+
+impl Default for AclBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl AclBuilder {
+  /// Creates a builder resolving the ACL for the current target.
+  pub fn new() -> Self {
+    Self {
+      permission_files: BTreeMap::new(),
+      capabilities: BTreeMap::new(),
+      target: Target::current(),
+    }
+  }
+
+  /// Sets the target the ACL is resolved for, which filters platform-specific permissions and capabilities.
+  pub fn target(mut self, target: Target) -> Self {
+    self.target = target;
+    self
+  }
+
+  /// Adds a TOML permission file to the given plugin, e.g. `fs`.
+  ///
+  /// Can be called multiple times for the same plugin to add all of its permission files.
+  pub fn plugin(mut self, plugin: impl Into<String>, permissions: &str) -> Result<Self, Error> {
+    let permission_file: PermissionFile = toml::from_str(permissions)?;
+    self
+      .permission_files
+      .entry(plugin.into())
+      .or_default()
+      .push(permission_file);
+    Ok(self)
+  }
+
+  /// Adds a TOML permission file defining application commands permissions.
+  pub fn app_permissions(self, permissions: &str) -> Result<Self, Error> {
+    self.plugin(APP_ACL_KEY, permissions)
+  }
+
+  /// Adds the capabilities of a JSON or TOML capability file.
+  pub fn capability(mut self, capability: &str) -> Result<Self, Error> {
+    let capabilities = match capability.parse::<CapabilityFile>()? {
+      CapabilityFile::Capability(capability) => vec![capability],
+      CapabilityFile::List(capabilities) | CapabilityFile::NamedList { capabilities } => {
+        capabilities
+      }
+    };
+    for capability in capabilities {
+      self
+        .capabilities
+        .insert(capability.identifier.clone(), capability);
+    }
+    Ok(self)
+  }
+
+  /// Resolves the ACL.
+  pub fn resolve(self) -> Result<Resolved, Error> {
+    let manifests = self
+      .permission_files
+      .into_iter()
+      .map(|(plugin, permission_files)| (plugin, Manifest::new(permission_files, None)))
+      .collect();
+    Resolved::resolve(&manifests, self.capabilities, self.target)
+  }
+}
+
+/// The origin of a command invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+  /// The application URL.
+  Local,
+  /// A remote URL.
+  Remote {
+    /// The URL invoking the command.
+    url: Url,
+  },
+}
+
+impl Origin {
+  fn matches(&self, context: &ExecutionContext) -> bool {
+    match (self, context) {
+      (Self::Local, ExecutionContext::Local) => true,
+      (Self::Remote { url }, ExecutionContext::Remote { url: url_pattern }) => {
+        url_pattern.test(url)
+      }
+      _ => false,
+    }
+  }
+}
+
+/// The reason a command invocation is denied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Denial {
+  /// A permission denies the command for this origin.
+  Denied,
+  /// No permission allows the command.
+  NotAllowed,
+  /// The command is allowed, but not for this origin.
+  Origin,
+  /// The command is allowed, but not for this window or webview label.
+  Label,
+}
+
+/// The result of [`Resolved::authorize`].
+#[derive(Debug)]
+pub enum Authorization {
+  /// The command is allowed.
+  Allowed {
+    /// The capabilities and permissions allowing the command.
+    #[cfg(debug_assertions)]
+    referenced_by: Vec<(String, String)>,
+    /// The command scope, merged from all the permissions allowing the command.
+    scope: ResolvedScope,
+    /// The global scope of the plugin.
+    global_scope: ResolvedScope,
+  },
+  /// The command is denied.
+  Denied(Denial),
+}
+
+impl Authorization {
+  /// Whether the command is allowed or not.
+  pub fn is_allowed(&self) -> bool {
+    matches!(self, Self::Allowed { .. })
+  }
+
+  /// The command scope, if the command is allowed.
+  pub fn scope(&self) -> Option<&ResolvedScope> {
+    match self {
+      Self::Allowed { scope, .. } => Some(scope),
+      Self::Denied(_) => None,
+    }
+  }
+
+  /// The global scope of the plugin, if the command is allowed.
+  pub fn global_scope(&self) -> Option<&ResolvedScope> {
+    match self {
+      Self::Allowed { global_scope, .. } => Some(global_scope),
+      Self::Denied(_) => None,
+    }
+  }
+
+  /// The reason the command is denied, if it is.
+  pub fn denial(&self) -> Option<Denial> {
+    match self {
+      Self::Allowed { .. } => None,
+      Self::Denied(denial) => Some(*denial),
+    }
+  }
+}
+
+impl ResolvedScope {
+  /// Deserializes the allowed and denied scope entries, as the command receives them.
+  pub fn deserialize<T: DeserializeOwned>(&self) -> Result<(Vec<T>, Vec<T>), serde_json::Error> {
+    let deserialize = |values: &[super::Value]| {
+      values
+        .iter()
+        .map(|value| serde_json::from_value(value.clone().into()))
+        .collect::<Result<Vec<T>, _>>()
+    };
+    Ok((deserialize(&self.allow)?, deserialize(&self.deny)?))
+  }
+}
+
+impl Resolved {
+  /// Checks whether `command` can be invoked by the window or webview with the given `label` from `origin`.
+  ///
+  /// Plugin commands are named `plugin:<plugin>|<command>`, e.g. `plugin:fs|read`,
+  /// while application commands use their name directly.
+  pub fn authorize(&self, command: &str, label: &str, origin: &Origin) -> Authorization {
+    if self
+      .denied_commands
+      .get(command)
+      .is_some_and(|denied| denied.iter().any(|cmd| origin.matches(&cmd.context)))
+    {
+      return Authorization::Denied(Denial::Denied);
+    }
+
+    let Some(allowed) = self.allowed_commands.get(command) else {
+      return Authorization::Denied(Denial::NotAllowed);
+    };
+
+    let allowed = allowed
+      .iter()
+      .filter(|cmd| origin.matches(&cmd.context))
+      .collect::<Vec<&ResolvedCommand>>();
+    if allowed.is_empty() {
+      return Authorization::Denied(Denial::Origin);
+    }
+
+    let allowed = allowed
+      .into_iter()
+      .filter(|cmd| {
+        cmd.windows.iter().any(|w| w.matches(label))
+          || cmd.webviews.iter().any(|w| w.matches(label))
+      })
+      .collect::<Vec<_>>();
+    if allowed.is_empty() {
+      return Authorization::Denied(Denial::Label);
+    }
+
+    let mut scope = ResolvedScope::default();
+    for scope_id in allowed
+      .iter()
+      .filter_map(|cmd| cmd.scope_id)
+      .collect::<BTreeSet<_>>()
+    {
+      if let Some(command_scope) = self.command_scope.get(&scope_id) {
+        scope.allow.extend(command_scope.allow.iter().cloned());
+        scope.deny.extend(command_scope.deny.iter().cloned());
+      }
+    }
+
+    let global_scope = match command
+      .strip_prefix("plugin:")
+      .and_then(|command| command.split_once('|'))
+    {
+      Some((plugin, _)) => self
+        .global_scope
+        .get(plugin)
+        .or_else(|| self.global_scope.get(&format!("core:{plugin}"))),
+      None => self.global_scope.get(APP_ACL_KEY),
+    }
+    .cloned()
+    .unwrap_or_default();
+
+    Authorization::Allowed {
+      #[cfg(debug_assertions)]
+      referenced_by: allowed
+        .iter()
+        .map(|cmd| {
+          (
+            cmd.referenced_by.capability.clone(),
+            cmd.referenced_by.permission.clone(),
+          )
+        })
+        .collect(),
+      scope,
+      global_scope,
+    }
+  }
+}
@@ -27,7 +27,7 @@
 use schemars::JsonSchema;
 use semver::Version;
 use serde::{
-  de::{Deserializer, Error as DeError, Visitor},
+  de::{value::MapAccessDeserializer, Deserializer, Error as DeError, MapAccess, Visitor},
   Deserialize, Serialize, Serializer,
 };
 use serde_json::Value as JsonValue;
@@ -38,13 +38,13 @@ use url::Url;
 use std::{
   collections::HashMap,
   fmt::{self, Display},
-  fs::read_to_string,
   path::PathBuf,
   str::FromStr,
 };
 
 /// Items to help with parsing content into a [`Config`].
 pub mod parse;
+pub mod version;
 
 use crate::{acl::capability::Capability, TitleBarStyle, WindowEffect, WindowEffectState};
 
@@ -129,15 +129,29 @@ pub enum BundleType {
   App,
   /// The Apple Disk Image bundle (.dmg).
   Dmg,
+  /// The macOS installer package (.pkg).
+  Pkg,
+  /// The Flatpak single-file bundle (.flatpak).
+  Flatpak,
+  /// The Snap package (.snap).
+  Snap,
+  /// The pacman package (.pkg.tar.zst).
+  Pacman,
+  /// The portable archive (.tar.gz or .zip).
+  Archive,
 }
 
 impl BundleType {
   /// All bundle types.
+  ///
+  /// Flatpak and Snap are not included since they require `flatpak-builder` and `snapcraft`
+  /// and must be requested explicitly. The portable archive and the macOS installer package must also be requested explicitly.
   fn all() -> &'static [Self] {
     &[
       BundleType::Deb,
       BundleType::Rpm,
       BundleType::AppImage,
+      BundleType::Pacman,
       BundleType::Msi,
       BundleType::Nsis,
       BundleType::App,
@@ -159,6 +173,11 @@ impl Display for BundleType {
         Self::Nsis => "nsis",
         Self::App => "app",
         Self::Dmg => "dmg",
+        Self::Pkg => "pkg",
+        Self::Flatpak => "flatpak",
+        Self::Snap => "snap",
+        Self::Pacman => "pacman",
+        Self::Archive => "archive",
       }
     )
   }
@@ -187,6 +206,11 @@ impl<'de> Deserialize<'de> for BundleType {
       "nsis" => Ok(Self::Nsis),
       "app" => Ok(Self::App),
       "dmg" => Ok(Self::Dmg),
+      "pkg" => Ok(Self::Pkg),
+      "flatpak" => Ok(Self::Flatpak),
+      "snap" => Ok(Self::Snap),
+      "pacman" => Ok(Self::Pacman),
+      "archive" => Ok(Self::Archive),
       _ => Err(DeError::custom(format!("unknown bundle target '{s}'"))),
     }
   }
@@ -308,6 +332,7 @@ impl BundleTarget {
 /// Configuration for AppImage bundles.
 ///
 /// See more: <https://tauri.app/v1/api/config#appimageconfig>
+#[skip_serializing_none]
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
@@ -317,8 +342,18 @@ pub struct AppImageConfig {
   #[serde(default, alias = "bundle-media-framework")]
   pub bundle_media_framework: bool,
   /// The files to include in the Appimage Binary.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.so`.
   #[serde(default)]
   pub files: HashMap<PathBuf, PathBuf>,
+  /// The update information embedded in the AppImage for delta updates with AppImageUpdate,
+  /// e.g. `gh-releases-zsync|owner|repo|latest|*x86_64.AppImage.zsync`.
+  ///
+  /// When set, a `.zsync` file is also generated next to the AppImage if `zsyncmake` is installed.
+  ///
+  /// See <https://github.com/AppImage/AppImageSpec/blob/master/draft.md#update-information>.
+  #[serde(alias = "update-information")]
+  pub update_information: Option<String>,
 }
 
 /// Configuration for Debian (.deb) bundles.
@@ -338,6 +373,8 @@ pub struct DebConfig {
   /// The list of package replaces.
   pub replaces: Option<Vec<String>>,
   /// The files to include on the package.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.so`.
   #[serde(default)]
   pub files: HashMap<PathBuf, PathBuf>,
   /// Define the section in Debian Control file. See : https://www.debian.org/doc/debian-policy/ch-archive.html#s-subsections
@@ -347,7 +384,14 @@ pub struct DebConfig {
   pub priority: Option<String>,
   /// Path of the uncompressed Changelog file, to be stored at /usr/share/doc/package-name/changelog.gz. See
   /// <https://www.debian.org/doc/debian-policy/ch-docs.html#changelog-files-and-release-notes>
+  ///
+  /// It must use the Debian changelog format and its latest entry must match the package version.
   pub changelog: Option<PathBuf>,
+  /// Do not register the [`Self::files`] installed under `/etc` as conffiles.
+  ///
+  /// By default they are, so the changes made by the user are kept when the package is upgraded.
+  #[serde(default, alias = "skip-conffiles")]
+  pub skip_conffiles: bool,
   /// Path to a custom desktop file Handlebars template.
   ///
   /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
@@ -369,6 +413,188 @@ pub struct DebConfig {
   /// <https://www.debian.org/doc/debian-policy/ch-maintainerscripts.html>
   #[serde(alias = "post-remove-script")]
   pub post_remove_script: Option<PathBuf>,
+  /// Maintainer scripts to include in the package, keyed by their name: `preinst`, `postinst`, `prerm` or `postrm`. See
+  /// <https://www.debian.org/doc/debian-policy/ch-maintainerscripts.html>
+  ///
+  /// A script cannot also be set with its dedicated option, e.g. `postinst` and [`Self::post_install_script`].
+  #[serde(default, alias = "maintainer-scripts")]
+  pub maintainer_scripts: HashMap<String, PathBuf>,
+  /// The compression algorithm of the `control.tar` and `data.tar` members of the package. Defaults to `gzip`.
+  #[serde(default)]
+  pub compression: DebCompression,
+  /// The GPG key ID used to sign the package with `gpg`, embedding the signature in the package as a `_gpgorigin` member
+  /// that can be verified with `debsig-verify`.
+  ///
+  /// The key passphrase is read from the `TAURI_SIGNING_DEB_KEY_PASSPHRASE` environment variable if set.
+  #[serde(alias = "sign-key")]
+  pub sign_key: Option<String>,
+}
+
+/// Compression algorithms used in the Debian package archives.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum DebCompression {
+  /// Gzip (default) is supported by every `dpkg` version.
+  #[default]
+  Gzip,
+  /// XZ gives better compression ratios than gzip at a lower compression speed. Supported since `dpkg` 1.15.6.
+  Xz,
+  /// Zstandard gives compression ratios close to XZ with a much faster compression and decompression.
+  /// Supported since `dpkg` 1.21.18, i.e. Debian 12 and Ubuntu 21.10.
+  Zstd,
+}
+
+/// Configuration for Flatpak bundles.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FlatpakConfig {
+  /// The Flatpak application id, e.g. `org.example.MyApp`. Defaults to the bundle identifier.
+  ///
+  /// It must contain at least three dot separated elements made of ASCII letters, digits, `_` and `-`,
+  /// see <https://docs.flatpak.org/en/latest/conventions.html#application-ids>.
+  #[serde(alias = "app-id")]
+  pub app_id: Option<String>,
+  /// The Flatpak runtime the application runs on. Defaults to `org.gnome.Platform`.
+  #[serde(default = "default_flatpak_runtime")]
+  pub runtime: String,
+  /// The version of the runtime. Defaults to `46`.
+  #[serde(default = "default_flatpak_runtime_version", alias = "runtime-version")]
+  pub runtime_version: String,
+  /// The SDK used to build the application. Defaults to `org.gnome.Sdk`.
+  #[serde(default = "default_flatpak_sdk")]
+  pub sdk: String,
+  /// The sandbox permissions of the application, passed as `finish-args` to `flatpak-builder`.
+  ///
+  /// Defaults to access to the display server, GPU and IPC: `--share=ipc`, `--socket=fallback-x11`, `--socket=wayland` and `--device=dri`.
+  #[serde(default = "default_flatpak_finish_args", alias = "finish-args")]
+  pub finish_args: Vec<String>,
+  /// Additional `flatpak-builder` modules built before the application, either inline module objects
+  /// or paths to module manifest files.
+  #[serde(default)]
+  pub modules: Vec<JsonValue>,
+  /// The files to include on the package, relative to the `/app` prefix.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.so`.
+  #[serde(default)]
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Path to a custom desktop file Handlebars template.
+  ///
+  /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
+  #[serde(alias = "desktop-template")]
+  pub desktop_template: Option<PathBuf>,
+}
+
+impl Default for FlatpakConfig {
+  fn default() -> Self {
+    Self {
+      app_id: None,
+      runtime: default_flatpak_runtime(),
+      runtime_version: default_flatpak_runtime_version(),
+      sdk: default_flatpak_sdk(),
+      finish_args: default_flatpak_finish_args(),
+      modules: Default::default(),
+      files: Default::default(),
+      desktop_template: None,
+    }
+  }
+}
+
+fn default_flatpak_runtime() -> String {
+  "org.gnome.Platform".into()
+}
+
+fn default_flatpak_runtime_version() -> String {
+  "46".into()
+}
+
+fn default_flatpak_sdk() -> String {
+  "org.gnome.Sdk".into()
+}
+
+fn default_flatpak_finish_args() -> Vec<String> {
+  vec![
+    "--share=ipc".into(),
+    "--socket=fallback-x11".into(),
+    "--socket=wayland".into(),
+    "--device=dri".into(),
+  ]
+}
+
+/// The confinement level of a Snap package.
+///
+/// See <https://snapcraft.io/docs/snap-confinement>
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum SnapConfinement {
+  /// The application runs isolated, only accessing the resources granted by its plugs.
+  Strict,
+  /// The application has the same access to the system as a traditionally packaged application.
+  ///
+  /// Classic snaps must be reviewed before they can be published to the Snap Store.
+  Classic,
+}
+
+impl Default for SnapConfinement {
+  fn default() -> Self {
+    Self::Strict
+  }
+}
+
+/// The quality grade of a Snap package.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum SnapGrade {
+  /// A release ready to be published to the `stable` and `candidate` channels.
+  Stable,
+  /// A development build, which can only be published to the `edge` and `beta` channels.
+  Devel,
+}
+
+impl Default for SnapGrade {
+  fn default() -> Self {
+    Self::Stable
+  }
+}
+
+/// Configuration for Snap packages.
+///
+/// See more: <https://snapcraft.io/docs/snapcraft-yaml-reference>
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SnapConfig {
+  /// The confinement level of the snap. Defaults to `strict`.
+  #[serde(default)]
+  pub confinement: SnapConfinement,
+  /// The quality grade of the snap. Defaults to `stable`.
+  #[serde(default)]
+  pub grade: SnapGrade,
+  /// The base snap providing the run-time environment, e.g. `core24`. Defaults to `core22`.
+  ///
+  /// The GNOME extension providing WebKitGTK to strictly confined snaps is picked according to the base.
+  pub base: Option<String>,
+  /// Additional interfaces the application plugs into, on top of
+  /// `network`, `desktop`, `desktop-legacy`, `wayland`, `x11`, `opengl` and `home`.
+  ///
+  /// Ignored when using the `classic` confinement.
+  #[serde(default)]
+  pub plugs: Vec<String>,
+  /// The files to include on the package, relative to the root of the snap.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.so`.
+  #[serde(default)]
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Path to a custom desktop file Handlebars template.
+  ///
+  /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
+  #[serde(alias = "desktop-template")]
+  pub desktop_template: Option<PathBuf>,
 }
 
 /// Configuration for Linux bundles.
@@ -388,6 +614,19 @@ pub struct LinuxConfig {
   /// Configuration for the RPM bundle.
   #[serde(default)]
   pub rpm: RpmConfig,
+  /// Configuration for the Flatpak bundle.
+  #[serde(default)]
+  pub flatpak: FlatpakConfig,
+  /// Configuration for the Snap package.
+  #[serde(default)]
+  pub snap: SnapConfig,
+  /// Configuration for the pacman package.
+  #[serde(default)]
+  pub pacman: PacmanConfig,
+  /// Whether glob patterns in the `files` maps that do not match any file are ignored
+  /// instead of failing the bundle.
+  #[serde(default, alias = "allow-empty-file-globs")]
+  pub allow_empty_file_globs: bool,
 }
 
 /// Configuration for RPM bundles.
@@ -397,6 +636,8 @@ pub struct LinuxConfig {
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct RpmConfig {
   /// The list of RPM dependencies your application relies on.
+  ///
+  /// Each dependency is a package name optionally followed by a version constraint, e.g. `libfoo >= 2.0`.
   pub depends: Option<Vec<String>>,
   /// The list of RPM dependencies your application provides.
   pub provides: Option<Vec<String>>,
@@ -413,6 +654,8 @@ pub struct RpmConfig {
   #[serde(default)]
   pub epoch: u32,
   /// The files to include on the package.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.so`.
   #[serde(default)]
   pub files: HashMap<PathBuf, PathBuf>,
   /// Path to a custom desktop file Handlebars template.
@@ -436,6 +679,23 @@ pub struct RpmConfig {
   /// <http://ftp.rpm.org/max-rpm/s1-rpm-inside-scripts.html>
   #[serde(alias = "post-remove-script")]
   pub post_remove_script: Option<PathBuf>,
+  /// Paths on the package of the [`Self::files`] to mark as `%config(noreplace)`,
+  /// so the changes made by the user are kept when the package is upgraded.
+  ///
+  /// A directory marks all of its files.
+  #[serde(default, alias = "config-files")]
+  pub config_files: Vec<PathBuf>,
+  /// Paths to systemd unit files installed to `/usr/lib/systemd/system`.
+  ///
+  /// The units are enabled according to the system presets on install,
+  /// and disabled and stopped on removal, like the `%systemd_post` and `%systemd_preun` macros do.
+  #[serde(default, alias = "systemd-units")]
+  pub systemd_units: Vec<PathBuf>,
+  /// The GPG key ID used to sign the package with `rpm --addsign`, which replaces the `TAURI_SIGNING_RPM_KEY` signature.
+  ///
+  /// The key passphrase is read from the `TAURI_SIGNING_RPM_KEY_PASSPHRASE` environment variable if set.
+  #[serde(alias = "sign-key")]
+  pub sign_key: Option<String>,
 }
 
 impl Default for RpmConfig {
@@ -453,6 +713,9 @@ impl Default for RpmConfig {
       post_install_script: None,
       pre_remove_script: None,
       post_remove_script: None,
+      config_files: Vec::new(),
+      systemd_units: Vec::new(),
+      sign_key: None,
     }
   }
 }
@@ -461,6 +724,75 @@ fn default_release() -> String {
   "1".into()
 }
 
+/// Configuration for pacman packages, used by Arch Linux and its derivatives.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PacmanConfig {
+  /// The list of pacman dependencies your application relies on.
+  ///
+  /// Defaults to the Debian dependencies, converting their version constraints to the pacman syntax.
+  pub depends: Option<Vec<String>>,
+  /// The list of virtual packages your application provides.
+  pub provides: Option<Vec<String>>,
+  /// The list of packages your application conflicts with.
+  pub conflicts: Option<Vec<String>>,
+  /// The list of packages your application replaces.
+  pub replaces: Option<Vec<String>>,
+  /// The package release number, appended to the version.
+  #[serde(default = "default_release")]
+  pub release: String,
+  /// The files to include on the package.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.so`.
+  #[serde(default)]
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Path to a custom desktop file Handlebars template.
+  ///
+  /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
+  #[serde(alias = "desktop-template")]
+  pub desktop_template: Option<PathBuf>,
+  /// Path to script that will be executed before the package is installed,
+  /// as the body of the `pre_install` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  #[serde(alias = "pre-install-script")]
+  pub pre_install_script: Option<PathBuf>,
+  /// Path to script that will be executed after the package is installed,
+  /// as the body of the `post_install` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  #[serde(alias = "post-install-script")]
+  pub post_install_script: Option<PathBuf>,
+  /// Path to script that will be executed before the package is removed,
+  /// as the body of the `pre_remove` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  #[serde(alias = "pre-remove-script")]
+  pub pre_remove_script: Option<PathBuf>,
+  /// Path to script that will be executed after the package is removed,
+  /// as the body of the `post_remove` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  #[serde(alias = "post-remove-script")]
+  pub post_remove_script: Option<PathBuf>,
+}
+
+impl Default for PacmanConfig {
+  fn default() -> Self {
+    Self {
+      depends: None,
+      provides: None,
+      conflicts: None,
+      replaces: None,
+      release: default_release(),
+      files: Default::default(),
+      desktop_template: None,
+      pre_install_script: None,
+      post_install_script: None,
+      pre_remove_script: None,
+      post_remove_script: None,
+    }
+  }
+}
+
 /// Position coordinates struct.
 #[derive(Default, Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
@@ -507,6 +839,16 @@ pub struct DmgConfig {
     alias = "application-folder-position"
   )]
   pub application_folder_position: Position,
+  /// License agreement shown before the dmg file is mounted. Accepted formats: `txt`/`rtf`.
+  ///
+  /// Defaults to `bundle > licenseFile`.
+  #[serde(alias = "license-path")]
+  pub license_path: Option<PathBuf>,
+  /// Icon of the mounted volume, copied to the volume root as `.VolumeIcon.icns`. Accepted format: `icns`.
+  ///
+  /// Defaults to the app icon.
+  #[serde(alias = "volume-icon")]
+  pub volume_icon: Option<PathBuf>,
 }
 
 impl Default for DmgConfig {
@@ -517,6 +859,8 @@ impl Default for DmgConfig {
       window_size: dmg_window_size(),
       app_position: dmg_app_position(),
       application_folder_position: dmg_application_folder_position(),
+      license_path: None,
+      volume_icon: None,
     }
   }
 }
@@ -536,6 +880,46 @@ fn dmg_application_folder_position() -> Position {
   Position { x: 480, y: 170 }
 }
 
+/// Configuration for macOS installer packages (.pkg), built from the app bundle with `pkgbuild` and `productbuild`.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PkgConfig {
+  /// The directory the app is installed to. Defaults to `/Applications`.
+  #[serde(default = "pkg_install_location", alias = "install-location")]
+  pub install_location: String,
+  /// The package identifier recorded in the installer receipts. Defaults to the app identifier.
+  pub identifier: Option<String>,
+  /// Path to a script run before the app is installed.
+  #[serde(alias = "preinstall-script")]
+  pub preinstall_script: Option<PathBuf>,
+  /// Path to a script run after the app is installed.
+  #[serde(alias = "postinstall-script")]
+  pub postinstall_script: Option<PathBuf>,
+  /// The installer signing identity used by `productsign`, e.g. `Developer ID Installer: Name (TEAMID)`.
+  ///
+  /// The package is not signed when it is not set.
+  #[serde(alias = "signing-identity")]
+  pub signing_identity: Option<String>,
+}
+
+impl Default for PkgConfig {
+  fn default() -> Self {
+    Self {
+      install_location: pkg_install_location(),
+      identifier: None,
+      preinstall_script: None,
+      postinstall_script: None,
+      signing_identity: None,
+    }
+  }
+}
+
+fn pkg_install_location() -> String {
+  "/Applications".into()
+}
+
 fn de_macos_minimum_system_version<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
   D: Deserializer<'de>,
@@ -547,6 +931,33 @@ where
   }
 }
 
+/// The tool used to sign and notarize the macOS bundles.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MacSigningBackend {
+  /// Apple's `codesign` and `notarytool`, which require macOS and Xcode.
+  Codesign,
+  /// [rcodesign](https://github.com/indygreg/apple-platform-rs/tree/main/apple-codesign), which does not require Xcode.
+  ///
+  /// Requires the `rcodesign` binary in `PATH` and the certificate in the `APPLE_CERTIFICATE` and `APPLE_CERTIFICATE_PASSWORD` environment variables.
+  /// Notarization requires an App Store Connect API key.
+  Rcodesign,
+}
+
+/// The archive format of the macOS updater artifact.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum MacUpdaterArtifact {
+  /// A `.app.tar.gz` archive.
+  #[default]
+  TarGz,
+  /// A `.app.zip` archive created with `ditto`, which preserves the symbolic links and extended attributes
+  /// the code signature of the app depends on.
+  Zip,
+}
+
 /// Configuration for the macOS bundles.
 ///
 /// See more: <https://tauri.app/v1/api/config#macconfig>
@@ -560,6 +971,8 @@ pub struct MacConfig {
   /// If a name is used, ".framework" must be omitted and it will look for standard install locations. You may also use a path to a specific framework.
   pub frameworks: Option<Vec<String>>,
   /// The files to include in the application relative to the Contents directory.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.dylib`.
   #[serde(default)]
   pub files: HashMap<PathBuf, PathBuf>,
   /// A version string indicating the minimum macOS X version that the bundled application supports. Defaults to `10.13`.
@@ -586,14 +999,28 @@ pub struct MacConfig {
   /// [hardened runtime]: <https://developer.apple.com/documentation/security/hardened_runtime>
   #[serde(alias = "hardened-runtime", default = "default_true")]
   pub hardened_runtime: bool,
+  /// The tool used to sign and notarize the bundles.
+  ///
+  /// Defaults to `codesign`, or to `rcodesign` when `codesign` is not installed and the `APPLE_CERTIFICATE` environment variable is set.
+  #[serde(alias = "signing-backend")]
+  pub signing_backend: Option<MacSigningBackend>,
   /// Provider short name for notarization.
   #[serde(alias = "provider-short-name")]
   pub provider_short_name: Option<String>,
   /// Path to the entitlements file.
+  ///
+  /// The entitlements are used to sign the app, its frameworks and sidecars, e.g. to allow JIT compilation with the hardened runtime.
+  /// The file must be a property list.
   pub entitlements: Option<String>,
   /// DMG-specific settings.
   #[serde(default)]
   pub dmg: DmgConfig,
+  /// Installer package (.pkg) specific settings.
+  #[serde(default)]
+  pub pkg: PkgConfig,
+  /// The archive format of the updater artifact. Defaults to `tarGz`.
+  #[serde(default, alias = "updater-artifact")]
+  pub updater_artifact: MacUpdaterArtifact,
 }
 
 impl Default for MacConfig {
@@ -605,9 +1032,12 @@ impl Default for MacConfig {
       exception_domain: None,
       signing_identity: None,
       hardened_runtime: true,
+      signing_backend: None,
       provider_short_name: None,
       entitlements: None,
       dmg: Default::default(),
+      pkg: Default::default(),
+      updater_artifact: Default::default(),
     }
   }
 }
@@ -651,6 +1081,39 @@ impl Default for WixLanguage {
   }
 }
 
+/// Install modes for the MSI installer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum WixInstallMode {
+  /// Default mode for the installer.
+  ///
+  /// Install the app in the `Program Files` folder, which requires Administrator access.
+  ///
+  /// Installer metadata will be saved under the `HKLM` registry path.
+  PerMachine,
+  /// Install the app in the `%LOCALAPPDATA%` folder, which doesn't require Administrator access.
+  ///
+  /// Installer metadata will be saved under the `HKCU` registry path.
+  /// The installation fails if the app is already installed per machine.
+  #[serde(alias = "currentUser")]
+  PerUser,
+  /// Combines both modes and allows the user to choose at install time
+  /// whether to install for the current user or per machine.
+  /// The per machine installation is selected by default;
+  /// pass `MSIINSTALLPERUSER=1` to `msiexec` to install for the current user without a user interface.
+  ///
+  /// Installer metadata will be saved under the `HKLM` or `HKCU` registry path based on the user's choice.
+  /// Requires Windows Installer 5.0 (Windows 7 or later).
+  Both,
+}
+
+impl Default for WixInstallMode {
+  fn default() -> Self {
+    Self::PerMachine
+  }
+}
+
 /// Configuration for the MSI bundle using WiX.
 ///
 /// See more: <https://tauri.app/v1/api/config#wixconfig>
@@ -696,6 +1159,12 @@ pub struct WixConfig {
   /// The required dimensions are 493px × 312px.
   #[serde(alias = "dialog-image-path")]
   pub dialog_image_path: Option<PathBuf>,
+  /// Whether the installation will be for all users or just the current user.
+  ///
+  /// The install scope also defines the location of the shortcuts
+  /// and the registry root of the deep link protocols.
+  #[serde(default, alias = "install-mode")]
+  pub install_mode: WixInstallMode,
 }
 
 /// Compression algorithms used in the NSIS installer.
@@ -777,6 +1246,7 @@ pub struct NsisConfig {
   /// A list of installer languages.
   /// By default the OS language is used. If the OS language is not in the list of languages, the first language will be used.
   /// To allow the user to select the language, set `display_language_selector` to `true`.
+  /// When the list is empty, only English is used.
   ///
   /// See <https://github.com/kichik/nsis/tree/9465c08046f00ccb6eda985abbdbf52c275c6c4d/Contrib/Language%20files> for the complete list of languages.
   pub languages: Option<Vec<String>>,
@@ -809,6 +1279,10 @@ pub struct NsisConfig {
   /// A path to a `.nsh` file that contains special NSIS macros to be hooked into the
   /// main installer.nsi script.
   ///
+  /// The path is resolved from the directory containing the Tauri configuration file,
+  /// and the bundle fails if the file does not exist.
+  /// Hooks that are not defined by the file are skipped.
+  ///
   /// Supported hooks are:
   /// - `NSIS_HOOK_PREINSTALL`: This hook runs before copying files, setting registry key values and creating shortcuts.
   /// - `NSIS_HOOK_POSTINSTALL`: This hook runs after the installer has finished copying all files, setting the registry keys and created shortcuts.
@@ -836,7 +1310,7 @@ pub struct NsisConfig {
   /// !macroend
   ///
   /// ```
-  #[serde(alias = "installer-hooks")]
+  #[serde(alias = "installer-hooks", alias = "hooks")]
   pub installer_hooks: Option<PathBuf>,
 }
 
@@ -900,12 +1374,17 @@ impl Default for WebviewInstallMode {
 pub struct WindowsConfig {
   /// Specifies the file digest algorithm to use for creating file signatures.
   /// Required for code signing. SHA-256 is recommended.
+  ///
+  /// Use a list like `["sha1", "sha256"]` to dual sign the files for older Windows versions,
+  /// a signature is appended for each algorithm after the first one.
   #[serde(alias = "digest-algorithm")]
-  pub digest_algorithm: Option<String>,
+  pub digest_algorithm: Option<WindowsDigestAlgorithms>,
   /// Specifies the SHA1 hash of the signing certificate.
   #[serde(alias = "certificate-thumbprint")]
   pub certificate_thumbprint: Option<String>,
   /// Server to use during timestamping.
+  ///
+  /// Each signature is timestamped with this server, using the digest algorithm of the signature when `tsp` is enabled.
   #[serde(alias = "timestamp-url")]
   pub timestamp_url: Option<String>,
   /// Whether to use Time-Stamp Protocol (TSP, a.k.a. RFC 3161) for the timestamp server. Your code signing provider may
@@ -934,9 +1413,18 @@ pub struct WindowsConfig {
   pub wix: Option<WixConfig>,
   /// Configuration for the installer generated with NSIS.
   pub nsis: Option<NsisConfig>,
-  /// Specify a custom command to sign the binaries.
-  /// This command needs to have a `%1` in it which is just a placeholder for the binary path,
-  /// which we will detect and replace before calling the command.
+  /// The files to include in the installation directory.
+  ///
+  /// The source paths can be glob patterns, e.g. `plugins/*.dll`.
+  #[serde(default)]
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Specify a custom command to sign the binaries, used instead of `signtool.exe`.
+  ///
+  /// The command signs the main executable, the sidecars, the MSI and NSIS installers and the NSIS uninstaller,
+  /// and the bundling fails if it exits with a non-zero status.
+  /// Every `%1` in its arguments is replaced with the path of the file to sign before running it,
+  /// `%2` with the digest algorithm and `%3` with the timestamp server URL.
+  /// The command runs once for each algorithm of `digestAlgorithm`, the runs after the first one must append their signature.
   ///
   /// Example:
   /// ```text
@@ -947,7 +1435,80 @@ pub struct WindowsConfig {
   /// if you are on another platform and want to cross-compile and sign you will
   /// need to use another tool like `osslsigncode`.
   #[serde(alias = "sign-command")]
-  pub sign_command: Option<String>,
+  pub sign_command: Option<CustomSignCommandConfig>,
+}
+
+/// A file digest algorithm of the Windows code signatures.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DigestAlgorithm {
+  /// SHA-1, only needed for the signatures checked by Windows Vista and Windows 7 without SHA-2 support.
+  #[serde(alias = "SHA1")]
+  Sha1,
+  /// SHA-256.
+  #[serde(alias = "SHA256")]
+  Sha256,
+  /// SHA-384.
+  #[serde(alias = "SHA384")]
+  Sha384,
+  /// SHA-512.
+  #[serde(alias = "SHA512")]
+  Sha512,
+}
+
+impl Display for DigestAlgorithm {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Sha1 => "sha1",
+        Self::Sha256 => "sha256",
+        Self::Sha384 => "sha384",
+        Self::Sha512 => "sha512",
+      }
+    )
+  }
+}
+
+/// The file digest algorithms of the Windows code signatures.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(untagged)]
+pub enum WindowsDigestAlgorithms {
+  /// A single signature with this digest algorithm.
+  One(DigestAlgorithm),
+  /// A signature for each digest algorithm, in order.
+  List(Vec<DigestAlgorithm>),
+}
+
+impl WindowsDigestAlgorithms {
+  /// The digest algorithms of the signatures, in order.
+  pub fn to_vec(&self) -> Vec<DigestAlgorithm> {
+    match self {
+      Self::One(algorithm) => vec![*algorithm],
+      Self::List(algorithms) => algorithms.clone(),
+    }
+  }
+}
+
+/// A custom command to sign a binary.
+///
+/// Every `%1` in the arguments is replaced with the path of the file to sign,
+/// so it can be a standalone argument like `%1` or a part of one like `--file=%1`.
+/// `%2` is replaced with the digest algorithm and `%3` with the timestamp server URL.
+/// The program itself is never expanded.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(untagged)]
+pub enum CustomSignCommandConfig {
+  /// The command as a string, split on whitespace into the program and its arguments.
+  ///
+  /// Use the array notation if the program or an argument contains whitespace.
+  Command(String),
+  /// The program followed by its arguments.
+  CommandWithArgs(Vec<String>),
 }
 
 impl Default for WindowsConfig {
@@ -962,6 +1523,7 @@ impl Default for WindowsConfig {
       allow_downgrades: true,
       wix: None,
       nsis: None,
+      files: Default::default(),
       sign_command: None,
     }
   }
@@ -1065,6 +1627,25 @@ pub enum BundleResources {
   Map(HashMap<String, String>),
 }
 
+/// How the resources whose distinct sources are bundled at the same path are handled.
+///
+/// The `../` and absolute resource paths are bundled in `_up_` and `_root_` directories,
+/// so `../assets/a.png` collides with a project directory literally named `_up_`, e.g. `_up_/assets/a.png`.
+/// Resources with the same content are not collisions and are bundled once.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum ResourceCollision {
+  /// Fail the build.
+  #[default]
+  Error,
+  /// Log a warning and bundle the last of the colliding resources.
+  Warn,
+  /// Bundle each colliding resource in a directory named after a short hash of its content,
+  /// e.g. `_up_/assets/3f2a9c1b/a.png`, so the app must resolve the hashed paths.
+  HashSubdir,
+}
+
 impl BundleResources {
   /// Adds a path to the resource collection.
   pub fn push(&mut self, path: impl Into<String>) {
@@ -1105,6 +1686,47 @@ pub enum V1Compatible {
   V1Compatible,
 }
 
+/// A digest algorithm of the checksum files written next to the bundle artifacts.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumKind {
+  /// SHA-256, written to `<artifact>.sha256`.
+  Sha256,
+  /// SHA-512, written to `<artifact>.sha512`.
+  Sha512,
+}
+
+/// The format of the portable archive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+  /// A gzip compressed tarball (.tar.gz), which stores the symbolic links and Unix permissions.
+  #[serde(rename = "tar.gz", alias = "targz")]
+  TarGz,
+  /// A deflate compressed zip archive (.zip).
+  Zip,
+}
+
+/// Configuration for the portable archive bundle.
+///
+/// The archive contains a `<productName>_<version>_<arch>` directory with the main binary,
+/// the sidecars, the resources and the icons, laid out so the app resolves its resources at runtime.
+/// On Linux this is the `usr/bin` and `usr/lib` layout of the Debian package, and on macOS the `MacOS` and `Resources` layout of the app bundle.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ArchiveConfig {
+  /// The archive formats to produce. Defaults to `["zip"]` on Windows and `["tar.gz"]` on Linux and macOS.
+  #[serde(default)]
+  pub formats: Vec<ArchiveFormat>,
+  /// The compression level, from `0` (no compression) to `9` (best compression). Defaults to `6`.
+  #[serde(alias = "compression-level")]
+  pub compression_level: Option<u32>,
+}
+
 /// Configuration for tauri-bundler.
 ///
 /// See more: <https://tauri.app/v1/api/config#bundleconfig>
@@ -1116,7 +1738,7 @@ pub struct BundleConfig {
   /// Whether Tauri should bundle your application or just output the executable.
   #[serde(default)]
   pub active: bool,
-  /// The bundle targets, currently supports ["deb", "rpm", "appimage", "nsis", "msi", "app", "dmg"] or "all".
+  /// The bundle targets, currently supports ["deb", "rpm", "appimage", "flatpak", "snap", "pacman", "nsis", "msi", "app", "dmg", "pkg", "archive"] or "all".
   #[serde(default)]
   pub targets: BundleTarget,
   #[serde(default)]
@@ -1137,6 +1759,9 @@ pub struct BundleConfig {
   /// Each resource is a path to a file or directory.
   /// Glob patterns are supported.
   pub resources: Option<BundleResources>,
+  /// How the resources whose distinct sources are bundled at the same path are handled.
+  #[serde(default, alias = "resource-collision")]
+  pub resource_collision: ResourceCollision,
   /// A copyright string associated with your application.
   pub copyright: Option<String>,
   /// The package's license identifier to be included in the appropriate bundles.
@@ -1158,6 +1783,17 @@ pub struct BundleConfig {
   /// A longer, multi-line description of the application.
   #[serde(alias = "long-description")]
   pub long_description: Option<String>,
+  /// Localized product names, keyed by language tag, e.g. `{ "zh-CN": "我的应用", "ja": "私のアプリ" }`.
+  ///
+  /// A name is used for a locale when its tag matches exactly or matches the locale primary language (`ja` for `ja-JP`).
+  ///
+  /// - **macOS**: `CFBundleDisplayName` in the `InfoPlist.strings` of each `.lproj` directory.
+  /// - **Windows**: shortcut and Add/Remove Programs names for each installer language.
+  /// - **Linux**: `Name[locale]` keys of the `.desktop` file.
+  ///
+  /// The bundle identifier and binary names are not localized.
+  #[serde(default, alias = "display-names")]
+  pub display_names: HashMap<String, String>,
   /// A list of—either absolute or relative—paths to binaries to embed with your application.
   ///
   /// Note that Tauri will look for system-specific binaries following the pattern "binary-name{-target-triple}{.system-extension}".
@@ -1171,6 +1807,45 @@ pub struct BundleConfig {
   /// so don't forget to provide binaries for all targeted platforms.
   #[serde(alias = "external-bin")]
   pub external_bin: Option<Vec<String>>,
+  /// The directory where the final bundle artifacts are placed.
+  ///
+  /// By default each bundle type is written to its own `target/<profile>/bundle/<type>` directory.
+  /// When set, all artifacts are moved to this directory instead.
+  /// Relative paths are resolved from the directory containing the Tauri configuration file.
+  #[serde(alias = "output-dir")]
+  pub output_dir: Option<PathBuf>,
+  /// A template for the file name of the final bundle artifacts, e.g. `{{productName}}-Setup-{{version}}-{{arch}}.{{ext}}`.
+  ///
+  /// Available variables: `productName`, `version`, `arch`, `targetTriple`, `packageType` and `ext`.
+  ///
+  /// The bundler fails if two artifacts resolve to the same file name.
+  #[serde(alias = "artifact-name")]
+  pub artifact_name: Option<String>,
+  /// The checksum files to write next to each bundle artifact, e.g. `["sha256", "sha512"]`.
+  ///
+  /// Each file uses the `sha256sum` format and can be verified with `sha256sum --check` (or `sha512sum`).
+  /// Directory artifacts such as the macOS `.app` bundle are skipped.
+  #[serde(default)]
+  pub checksums: Vec<ChecksumKind>,
+  /// Makes the bundles byte-for-byte reproducible.
+  ///
+  /// The archive entries are sorted and owned by root, and their modification times and the package build dates
+  /// are pinned to the `SOURCE_DATE_EPOCH` environment variable, or to `1980-01-01T00:00:00Z` when it is not set.
+  /// Without this flag, `SOURCE_DATE_EPOCH` only clamps the modification times.
+  #[serde(default)]
+  pub reproducible: bool,
+  /// Strips the debug symbols of the app binaries before they are bundled.
+  ///
+  /// The binaries are stripped with `strip --strip-debug`, or `strip -x` on macOS to keep the symbols needed for code signing,
+  /// and the `STRIP` environment variable can point to the tool to use when cross compiling.
+  /// The bundles keep the debug symbols with a warning when the tool is not installed.
+  ///
+  /// - **Windows**: the MSVC binaries do not contain their debug symbols, so the `.pdb` files are copied to the `bundle/symbols` directory instead.
+  #[serde(default)]
+  pub strip: bool,
+  /// Configuration for the portable archive bundle.
+  #[serde(default)]
+  pub archive: ArchiveConfig,
   /// Configuration for the Windows bundles.
   #[serde(default)]
   pub windows: WindowsConfig,
@@ -1218,6 +1893,87 @@ pub struct WindowEffectsConfig {
   pub color: Option<Color>,
 }
 
+/// Autoplay policy of the webview media.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoplayPolicy {
+  /// Media can play automatically, without a user gesture.
+  Allow,
+  /// Media with sound only plays after a user gesture.
+  RequireGesture,
+}
+
+/// Throttling policy of the webview timers and rendering when it is in the background.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum BackgroundThrottlingPolicy {
+  /// The webview keeps running at full speed when hidden, minimized or occluded.
+  Disabled,
+  /// The webview engine default, which throttles the webview when it is in the background.
+  Default,
+}
+
+/// Whether the webview cookies and website data outlive the webview.
+///
+/// Persistent data is stored in the webview data directory, which defaults to `$LOCALDATA/<identifier>`
+/// on Linux and Windows and to the default `WKWebsiteDataStore` of the app on macOS.
+///
+/// ## Platform-specific
+///
+/// [`Self::Session`] uses an ephemeral `WebsiteDataManager` on Linux, a `nonPersistent` `WKWebsiteDataStore`
+/// on macOS and iOS and an InPrivate WebView2 profile on Windows.
+///
+/// - **Android**: Unsupported, data is always persisted.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum CookiePersistence {
+  /// Cookies and website data are stored in the webview data directory and kept between runs.
+  #[default]
+  Persistent,
+  /// Cookies and website data are kept in memory and discarded when the webview is closed.
+  Session,
+}
+
+/// Webview engine settings.
+///
+/// Settings left unset keep the platform default.
+/// A setting the platform webview does not support is ignored with a warning instead of failing the window creation.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WebviewSettingsConfig {
+  /// The media autoplay policy.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Sets the `--autoplay-policy` browser argument.
+  /// - **Linux**: Controls the `media-playback-requires-user-gesture` WebKitSettings property.
+  /// - **macOS / iOS**: Controls the `mediaTypesRequiringUserActionForPlayback` WKWebViewConfiguration property.
+  pub autoplay: Option<AutoplayPolicy>,
+  /// The throttling policy when the webview is in the background.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Sets the browser arguments disabling the background timer throttling and renderer backgrounding.
+  /// - **macOS / iOS**: Controls the `inactiveSchedulingPolicy` WKPreferences property. Requires macOS 14+ / iOS 17+.
+  /// - **Linux / Android**: Unsupported.
+  #[serde(alias = "background-throttling")]
+  pub background_throttling: Option<BackgroundThrottlingPolicy>,
+  /// Whether scrolling is animated.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Sets the `--disable-smooth-scrolling` browser argument when `false`.
+  /// - **Linux**: Controls the `enable-smooth-scrolling` WebKitSettings property.
+  /// - **macOS / iOS / Android**: Unsupported.
+  #[serde(alias = "smooth-scrolling")]
+  pub smooth_scrolling: Option<bool>,
+}
+
 /// The window configuration object.
 ///
 /// See more: <https://tauri.app/v1/api/config#windowconfig>
@@ -1387,6 +2143,11 @@ pub struct WindowConfig {
   ///  - **Android**: Unsupported.
   #[serde(default)]
   pub incognito: bool,
+  /// Whether the webview cookies and website data are kept between runs, see [`CookiePersistence`].
+  ///
+  /// Launching the webview in incognito mode always uses [`CookiePersistence::Session`].
+  #[serde(default, alias = "cookie-persistence")]
+  pub cookie_persistence: CookiePersistence,
   /// Sets the window associated with this label to be the parent of the window to be created.
   ///
   /// ## Platform-specific
@@ -1418,6 +2179,16 @@ pub struct WindowConfig {
   /// - **Android / iOS**: Unsupported.
   #[serde(default)]
   pub zoom_hotkeys_enabled: bool,
+  /// Whether to unregister the service workers of the webview when the app version changes.
+  ///
+  /// The app version is recorded in the webview data directory, or the app local data directory if it is not set,
+  /// and compared when the webview is created. When it changed, the webview loads a blank page until its service workers are unregistered,
+  /// making sure a new frontend version is not served by stale service workers from a previous version.
+  #[serde(default, alias = "clear-service-workers-on-version-change")]
+  pub clear_service_workers_on_version_change: bool,
+  /// Webview engine settings such as the media autoplay policy, the background throttling and smooth scrolling.
+  #[serde(default, alias = "webview-settings")]
+  pub webview_settings: WebviewSettingsConfig,
 }
 
 impl Default for WindowConfig {
@@ -1461,9 +2232,12 @@ impl Default for WindowConfig {
       shadow: true,
       window_effects: None,
       incognito: false,
+      cookie_persistence: Default::default(),
       parent: None,
       proxy_url: None,
       zoom_hotkeys_enabled: false,
+      clear_service_workers_on_version_change: false,
+      webview_settings: Default::default(),
     }
   }
 }
@@ -1814,6 +2588,15 @@ pub struct AppConfig {
   /// Configuration for app tray icon.
   #[serde(alias = "tray-icon")]
   pub tray_icon: Option<TrayIconConfig>,
+  /// Configuration for additional app tray icons, created along with the `trayIcon` one.
+  ///
+  /// Each tray icon must have a unique `id`, used to retrieve it with `app.tray_by_id(id)`
+  /// and available in the events it triggers.
+  #[serde(default, alias = "tray-icons")]
+  pub tray_icons: Vec<TrayIconConfig>,
+  /// Configuration for the app menus.
+  #[serde(default)]
+  pub menu: MenuConfig,
   /// MacOS private API configuration. Enables the transparent background API and sets the `fullScreenEnabled` preference to `true`.
   #[serde(rename = "macOSPrivateApi", alias = "macos-private-api", default)]
   pub macos_private_api: bool,
@@ -1823,6 +2606,12 @@ pub struct AppConfig {
   /// If set to true "identifier" will be set as GTK app ID (on systems that use GTK).
   #[serde(rename = "enableGTKAppId", alias = "enable-gtk-app-id", default)]
   pub enable_gtk_app_id: bool,
+  /// Interval in milliseconds at which the process metrics of all webviews are emitted
+  /// to the frontend with the `tauri://metrics` event. Disabled by default.
+  ///
+  /// Intervals shorter than 100 milliseconds are raised to 100 milliseconds.
+  #[serde(alias = "webview-metrics-interval")]
+  pub webview_metrics_interval: Option<u64>,
 }
 
 impl AppConfig {
@@ -1839,7 +2628,7 @@ impl AppConfig {
   /// Returns the enabled Cargo features.
   pub fn features(&self) -> Vec<&str> {
     let mut features = Vec::new();
-    if self.tray_icon.is_some() {
+    if self.tray_icon.is_some() || !self.tray_icons.is_empty() {
       features.push("tray-icon");
     }
     if self.macos_private_api {
@@ -1856,8 +2645,16 @@ impl AppConfig {
     features.sort_unstable();
     features
   }
+
+  /// Returns the configuration of all the tray icons, [`Self::tray_icon`] first.
+  pub fn all_tray_icons(&self) -> impl Iterator<Item = &TrayIconConfig> {
+    self.tray_icon.iter().chain(&self.tray_icons)
+  }
 }
 
+/// The id of a tray icon defined in the configuration without an `id`.
+pub const DEFAULT_TRAY_ICON_ID: &str = "main";
+
 /// Configuration for application tray icon.
 ///
 /// See more: <https://tauri.app/v1/api/config#trayiconconfig>
@@ -1868,6 +2665,8 @@ impl AppConfig {
 pub struct TrayIconConfig {
   /// Set an id for this tray icon so you can reference it later, defaults to `main`.
   pub id: Option<String>,
+  /// The id of the menu to show on the tray icon, added with `tauri::Builder::tray_menu`.
+  pub menu: Option<String>,
   /// Path to the default icon to use for the tray icon.
   ///
   /// Note: this stores the image in raw pixels to the final binary,
@@ -1887,6 +2686,79 @@ pub struct TrayIconConfig {
   pub tooltip: Option<String>,
 }
 
+impl TrayIconConfig {
+  /// The id of the tray icon, [`DEFAULT_TRAY_ICON_ID`] if not set.
+  pub fn id_or_default(&self) -> &str {
+    self.id.as_deref().unwrap_or(DEFAULT_TRAY_ICON_ID)
+  }
+}
+
+/// A predefined menu item role or a submenu of the default app menu.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum MenuRole {
+  /// The `Copy` menu item.
+  Copy,
+  /// The `Cut` menu item.
+  Cut,
+  /// The `Paste` menu item.
+  Paste,
+  /// The `Select All` menu item.
+  SelectAll,
+  /// The `Undo` menu item.
+  Undo,
+  /// The `Redo` menu item.
+  Redo,
+  /// The `Minimize` menu item.
+  Minimize,
+  /// The `Maximize` menu item.
+  Maximize,
+  /// The `Toggle Full Screen` menu item.
+  Fullscreen,
+  /// The `Hide` menu item.
+  Hide,
+  /// The `Hide Others` menu item.
+  HideOthers,
+  /// The `Show All` menu item.
+  ShowAll,
+  /// The `Close Window` menu item.
+  CloseWindow,
+  /// The `Quit` menu item.
+  Quit,
+  /// The `About` menu item.
+  About,
+  /// The `Services` menu item.
+  Services,
+  /// The `File` submenu of the default menu.
+  FileMenu,
+  /// The `Edit` submenu of the default menu.
+  EditMenu,
+  /// The `View` submenu of the default menu.
+  ViewMenu,
+  /// The `Window` submenu of the default menu.
+  WindowMenu,
+  /// The `Help` submenu of the default menu.
+  HelpMenu,
+}
+
+/// Configuration for the app menus.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MenuConfig {
+  /// The locale used to translate the text of the predefined menu items and the default menu, e.g. `de` or `pt-BR`.
+  ///
+  /// Falls back to the language without its region, then to English.
+  pub locale: Option<String>,
+  /// Text overrides for the predefined menu items and the default menu submenus, keyed by role.
+  ///
+  /// These take precedence over the built-in translations.
+  #[serde(default, alias = "role-text")]
+  pub role_text: HashMap<MenuRole, String>,
+}
+
 /// General configuration for the iOS target.
 #[skip_serializing_none]
 #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
@@ -1931,9 +2803,16 @@ pub struct AndroidConfig {
   ///
   /// By default we use your configured version and perform the following math:
   /// versionCode = version.major * 1000000 + version.minor * 1000 + version.patch
+  ///
+  /// The APKs and AABs built with `--split-per-abi` use `versionCode * 10 + n`, `n` being the index of their ABI starting at 1.
   #[serde(alias = "version-code")]
   #[cfg_attr(feature = "schema", validate(range(min = 1, max = 2_100_000_000)))]
   pub version_code: Option<u32>,
+
+  /// The keystore used to sign the release APKs and AABs built with `tauri android build`.
+  ///
+  /// When it is not set, the release artifacts are unsigned.
+  pub signing: Option<AndroidSigningConfig>,
 }
 
 impl Default for AndroidConfig {
@@ -1941,6 +2820,7 @@ impl Default for AndroidConfig {
     Self {
       min_sdk_version: default_min_sdk_version(),
       version_code: None,
+      signing: None,
     }
   }
 }
@@ -1949,6 +2829,42 @@ fn default_min_sdk_version() -> u32 {
   24
 }
 
+/// The keystore signing the Android release builds.
+///
+/// The passwords are read from environment variables when running `tauri android build`
+/// and handed to Gradle without being written to the Android project.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AndroidSigningConfig {
+  /// Path to the keystore, relative to the directory of the Tauri configuration file.
+  #[serde(alias = "keystore-path")]
+  pub keystore_path: PathBuf,
+  /// The alias of the signing key in the keystore.
+  #[serde(alias = "key-alias")]
+  pub key_alias: String,
+  /// The environment variable holding the keystore password. Defaults to `TAURI_ANDROID_KEYSTORE_PASSWORD`.
+  #[serde(
+    alias = "store-password-env",
+    default = "default_android_store_password_env"
+  )]
+  pub store_password_env: String,
+  /// The environment variable holding the key password. Defaults to `TAURI_ANDROID_KEY_PASSWORD`.
+  #[serde(
+    alias = "key-password-env",
+    default = "default_android_key_password_env"
+  )]
+  pub key_password_env: String,
+}
+
+fn default_android_store_password_env() -> String {
+  "TAURI_ANDROID_KEYSTORE_PASSWORD".into()
+}
+
+fn default_android_key_password_env() -> String {
+  "TAURI_ANDROID_KEY_PASSWORD".into()
+}
+
 /// Defines the URL or assets to embed in the application.
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
@@ -2027,6 +2943,21 @@ pub struct BuildConfig {
   /// and point to a web assets directory, and Tauri CLI will run its built-in dev server and provide a simple hot-reload experience.
   #[serde(alias = "dev-url")]
   pub dev_url: Option<Url>,
+  /// A path of [`devUrl`](BuildConfig::dev_url) requested by `tauri dev` to check that the dev server is ready, e.g. `/health`.
+  ///
+  /// The dev server is ready once the path responds with a 2xx or 3xx status,
+  /// which is useful when a proxy accepts connections before the dev server is up.
+  /// TLS certificates are not verified, so self-signed certificates are accepted.
+  ///
+  /// Defaults to waiting for `devUrl` to accept TCP connections.
+  #[serde(alias = "dev-url-health-path")]
+  pub dev_url_health_path: Option<String>,
+  /// The time in milliseconds `tauri dev` waits for the dev server to be ready before failing. Defaults to 180000.
+  #[serde(alias = "dev-url-timeout")]
+  pub dev_url_timeout: Option<u64>,
+  /// The time in milliseconds between two checks of the dev server. Defaults to 2000.
+  #[serde(alias = "dev-url-poll-interval")]
+  pub dev_url_poll_interval: Option<u64>,
   /// The path to the application assets (usually the `dist` folder of your javascript bundler)
   /// or a URL that could be either a custom protocol registered in the tauri app (for example: `myprotocol://`)
   /// or a remote URL (for example: `https://site.com/app`).
@@ -2059,6 +2990,90 @@ pub struct BuildConfig {
   pub before_bundle_command: Option<HookCommand>,
   /// Features passed to `cargo` commands.
   pub features: Option<Vec<String>>,
+  /// The cargo profile used by `tauri build`, e.g. a `release-lto` profile defined in the workspace `Cargo.toml`.
+  ///
+  /// Defaults to `release`, or `dev` with `--debug`.
+  pub profile: Option<String>,
+  /// The cargo profile used by `tauri dev`, e.g. a `dev-opt` profile defined in the workspace `Cargo.toml`.
+  ///
+  /// Defaults to `dev`, or `release` with `--release`.
+  pub dev_profile: Option<String>,
+  /// Configuration for the file watcher of `tauri dev`.
+  pub watch: Option<WatchConfig>,
+}
+
+/// Configuration for the file watcher of `tauri dev`, applied on top of the `.taurignore` files.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WatchConfig {
+  /// Additional directories or files to watch, relative to the configuration file.
+  ///
+  /// The Tauri crate and the Cargo workspace members are always watched.
+  #[serde(default)]
+  pub paths: Vec<PathBuf>,
+  /// Gitignore-style patterns of paths whose changes must not rebuild the application.
+  ///
+  /// Patterns containing a `/` are relative to the configuration file, e.g. `../shared/generated/`,
+  /// while patterns without it match anywhere, e.g. `*.sqlite`.
+  #[serde(default)]
+  pub ignore: Vec<String>,
+  /// The time in milliseconds to wait for further changes before rebuilding the application. Defaults to 1000.
+  pub debounce: Option<u64>,
+}
+
+/// The app version, see [`Config::version`].
+///
+/// It is resolved to a version string when the configuration is parsed,
+/// reading the files relative to the configuration file.
+#[cfg(feature = "schema")]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum PackageVersionConfig {
+  /// A semver version number or a path to a `package.json`, `Cargo.toml` or `VERSION` file containing the version.
+  Version(String),
+  /// The version read from a file, with an optional build number.
+  Source(VersionSourceConfig),
+}
+
+/// The app version read from a file, with an optional build number appended as its build metadata, e.g. `1.2.3+456`.
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct VersionSourceConfig {
+  /// The `package.json`, `Cargo.toml` or `VERSION` file containing the version. Defaults to the `Cargo.toml` file,
+  /// inheriting the version of its workspace.
+  file: Option<PathBuf>,
+  /// The environment variable containing the build number, e.g. `CI_BUILD_NUMBER`.
+  ///
+  /// The build number is not appended if the variable is not set, e.g. for local builds.
+  #[serde(alias = "build-number-env")]
+  build_number_env: Option<String>,
+}
+
+impl VersionSourceConfig {
+  fn resolve(self) -> Result<String, version::VersionError> {
+    self.resolve_with_env(|var| std::env::var(var).ok())
+  }
+
+  /// Resolves the version, reading the build number environment variable with `env`.
+  fn resolve_with_env(
+    self,
+    env: impl Fn(&str) -> Option<String>,
+  ) -> Result<String, version::VersionError> {
+    let file = self.file.unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+    let app_version = version::read_version_file(&file)?;
+    let build_number = self
+      .build_number_env
+      .and_then(|var| env(&var))
+      .filter(|build_number| !build_number.trim().is_empty());
+    match build_number {
+      Some(build_number) => version::with_build_number(&app_version, &build_number),
+      None => Ok(app_version),
+    }
+  }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -2074,35 +3089,16 @@ impl<'d> serde::Deserialize<'d> for PackageVersion {
       fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
           formatter,
-          "a semver string or a path to a package.json file"
+          "a semver string, a path to a file containing the version or a version source object"
         )
       }
 
       fn visit_str<E: DeError>(self, value: &str) -> Result<PackageVersion, E> {
         let path = PathBuf::from(value);
         if path.exists() {
-          let json_str = read_to_string(&path)
-            .map_err(|e| DeError::custom(format!("failed to read version JSON file: {e}")))?;
-          let package_json: serde_json::Value = serde_json::from_str(&json_str)
-            .map_err(|e| DeError::custom(format!("failed to read version JSON file: {e}")))?;
-          if let Some(obj) = package_json.as_object() {
-            let version = obj
-              .get("version")
-              .ok_or_else(|| DeError::custom("JSON must contain a `version` field"))?
-              .as_str()
-              .ok_or_else(|| {
-                DeError::custom(format!("`{} > version` must be a string", path.display()))
-              })?;
-            Ok(PackageVersion(
-              Version::from_str(version)
-                .map_err(|_| DeError::custom("`package > version` must be a semver string"))?
-                .to_string(),
-            ))
-          } else {
-            Err(DeError::custom(
-              "`package > version` value is not a path to a JSON object",
-            ))
-          }
+          version::read_version_file(&path)
+            .map(PackageVersion)
+            .map_err(DeError::custom)
         } else {
           Ok(PackageVersion(
             Version::from_str(value)
@@ -2111,9 +3107,16 @@ impl<'d> serde::Deserialize<'d> for PackageVersion {
           ))
         }
       }
+
+      fn visit_map<A: MapAccess<'d>>(self, map: A) -> Result<PackageVersion, A::Error> {
+        VersionSourceConfig::deserialize(MapAccessDeserializer::new(map))?
+          .resolve()
+          .map(PackageVersion)
+          .map_err(DeError::custom)
+      }
     }
 
-    deserializer.deserialize_string(PackageVersionVisitor {})
+    deserializer.deserialize_any(PackageVersionVisitor {})
   }
 }
 
@@ -2199,10 +3202,14 @@ pub struct Config {
   #[serde(alias = "product-name")]
   #[cfg_attr(feature = "schema", validate(regex(pattern = "^[^/\\:*?\"<>|]+$")))]
   pub product_name: Option<String>,
-  /// App version. It is a semver version number or a path to a `package.json` file containing the `version` field. If removed the version number from `Cargo.toml` is used.
+  /// App version. It is a semver version number or a path to a `package.json`, `Cargo.toml` or `VERSION` file containing the version.
+  /// If removed the version number from `Cargo.toml` is used, inheriting the version of its workspace.
   ///
-  /// By default version 1.0 is used on Android.
+  /// Use `{ "file": "VERSION", "buildNumberEnv": "CI_BUILD_NUMBER" }` to append a build number read from an environment variable,
+  /// e.g. `1.2.3+456`, to the version of a file or the `Cargo.toml` version if `file` is not set.
+  /// The build number is the fourth field of the Windows versions, the Android `versionCode` and part of the Apple `CFBundleVersion`.
   #[serde(deserialize_with = "version_deserializer", default)]
+  #[cfg_attr(feature = "schema", schemars(with = "Option<PackageVersionConfig>"))]
   pub version: Option<String>,
   /// The application identifier in reverse domain name notation (e.g. `com.tauri.example`).
   /// This string must be unique across applications since it is used in system configurations like
@@ -2236,11 +3243,17 @@ fn default_build() -> BuildConfig {
   BuildConfig {
     runner: None,
     dev_url: None,
+    dev_url_health_path: None,
+    dev_url_timeout: None,
+    dev_url_poll_interval: None,
     frontend_dist: None,
     before_dev_command: None,
     before_build_command: None,
     before_bundle_command: None,
     features: None,
+    profile: None,
+    dev_profile: None,
+    watch: None,
   }
 }
 
@@ -2313,6 +3326,55 @@ mod build {
     }
   }
 
+  impl ToTokens for AutoplayPolicy {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::AutoplayPolicy };
+
+      tokens.append_all(match self {
+        Self::Allow => quote! { #prefix::Allow },
+        Self::RequireGesture => quote! { #prefix::RequireGesture },
+      })
+    }
+  }
+
+  impl ToTokens for BackgroundThrottlingPolicy {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::BackgroundThrottlingPolicy };
+
+      tokens.append_all(match self {
+        Self::Disabled => quote! { #prefix::Disabled },
+        Self::Default => quote! { #prefix::Default },
+      })
+    }
+  }
+
+  impl ToTokens for CookiePersistence {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::CookiePersistence };
+
+      tokens.append_all(match self {
+        Self::Persistent => quote! { #prefix::Persistent },
+        Self::Session => quote! { #prefix::Session },
+      })
+    }
+  }
+
+  impl ToTokens for WebviewSettingsConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let autoplay = opt_lit(self.autoplay.as_ref());
+      let background_throttling = opt_lit(self.background_throttling.as_ref());
+      let smooth_scrolling = opt_lit(self.smooth_scrolling.as_ref());
+
+      literal_struct!(
+        tokens,
+        ::tauri::utils::config::WebviewSettingsConfig,
+        autoplay,
+        background_throttling,
+        smooth_scrolling
+      )
+    }
+  }
+
   impl ToTokens for crate::TitleBarStyle {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let prefix = quote! { ::tauri::utils::TitleBarStyle };
@@ -2416,8 +3478,11 @@ mod build {
       let shadow = self.shadow;
       let window_effects = opt_lit(self.window_effects.as_ref());
       let incognito = self.incognito;
+      let cookie_persistence = &self.cookie_persistence;
       let parent = opt_str_lit(self.parent.as_ref());
       let zoom_hotkeys_enabled = self.zoom_hotkeys_enabled;
+      let clear_service_workers_on_version_change = self.clear_service_workers_on_version_change;
+      let webview_settings = &self.webview_settings;
 
       literal_struct!(
         tokens,
@@ -2461,8 +3526,11 @@ mod build {
         shadow,
         window_effects,
         incognito,
+        cookie_persistence,
         parent,
-        zoom_hotkeys_enabled
+        zoom_hotkeys_enabled,
+        clear_service_workers_on_version_change,
+        webview_settings
       );
     }
   }
@@ -2533,12 +3601,20 @@ mod build {
       let targets = quote!(Default::default());
       let create_updater_artifacts = quote!(Default::default());
       let resources = quote!(None);
+      let resource_collision = quote!(Default::default());
       let copyright = quote!(None);
       let category = quote!(None);
       let file_associations = quote!(None);
       let short_description = quote!(None);
       let long_description = quote!(None);
+      let display_names = quote!(Default::default());
       let external_bin = opt_vec_lit(self.external_bin.as_ref(), str_lit);
+      let output_dir = quote!(None);
+      let artifact_name = quote!(None);
+      let checksums = quote!(Default::default());
+      let reproducible = false;
+      let strip = false;
+      let archive = quote!(Default::default());
       let windows = &self.windows;
       let license = opt_str_lit(self.license.as_ref());
       let license_file = opt_lit(self.license_file.as_ref().map(path_buf_lit).as_ref());
@@ -2557,6 +3633,7 @@ mod build {
         targets,
         create_updater_artifacts,
         resources,
+        resource_collision,
         copyright,
         category,
         license,
@@ -2564,7 +3641,14 @@ mod build {
         file_associations,
         short_description,
         long_description,
+        display_names,
         external_bin,
+        output_dir,
+        artifact_name,
+        checksums,
+        reproducible,
+        strip,
+        archive,
         windows,
         linux,
         macos,
@@ -2599,22 +3683,34 @@ mod build {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let dev_url = opt_lit(self.dev_url.as_ref().map(url_lit).as_ref());
       let frontend_dist = opt_lit(self.frontend_dist.as_ref());
+      let dev_url_health_path = quote!(None);
+      let dev_url_timeout = quote!(None);
+      let dev_url_poll_interval = quote!(None);
       let runner = quote!(None);
       let before_dev_command = quote!(None);
       let before_build_command = quote!(None);
       let before_bundle_command = quote!(None);
       let features = quote!(None);
+      let profile = quote!(None);
+      let dev_profile = quote!(None);
+      let watch = quote!(None);
 
       literal_struct!(
         tokens,
         ::tauri::utils::config::BuildConfig,
         runner,
         dev_url,
+        dev_url_health_path,
+        dev_url_timeout,
+        dev_url_poll_interval,
         frontend_dist,
         before_dev_command,
         before_build_command,
         before_bundle_command,
-        features
+        features,
+        profile,
+        dev_profile,
+        watch
       );
     }
   }
@@ -2717,6 +3813,7 @@ mod build {
   impl ToTokens for TrayIconConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let id = opt_str_lit(self.id.as_ref());
+      let menu = opt_str_lit(self.menu.as_ref());
       let icon_as_template = self.icon_as_template;
       let menu_on_left_click = self.menu_on_left_click;
       let icon_path = path_buf_lit(&self.icon_path);
@@ -2726,6 +3823,7 @@ mod build {
         tokens,
         ::tauri::utils::config::TrayIconConfig,
         id,
+        menu,
         icon_path,
         icon_as_template,
         menu_on_left_click,
@@ -2735,6 +3833,55 @@ mod build {
     }
   }
 
+  impl ToTokens for MenuRole {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::MenuRole };
+
+      tokens.append_all(match self {
+        Self::Copy => quote! { #prefix::Copy },
+        Self::Cut => quote! { #prefix::Cut },
+        Self::Paste => quote! { #prefix::Paste },
+        Self::SelectAll => quote! { #prefix::SelectAll },
+        Self::Undo => quote! { #prefix::Undo },
+        Self::Redo => quote! { #prefix::Redo },
+        Self::Minimize => quote! { #prefix::Minimize },
+        Self::Maximize => quote! { #prefix::Maximize },
+        Self::Fullscreen => quote! { #prefix::Fullscreen },
+        Self::Hide => quote! { #prefix::Hide },
+        Self::HideOthers => quote! { #prefix::HideOthers },
+        Self::ShowAll => quote! { #prefix::ShowAll },
+        Self::CloseWindow => quote! { #prefix::CloseWindow },
+        Self::Quit => quote! { #prefix::Quit },
+        Self::About => quote! { #prefix::About },
+        Self::Services => quote! { #prefix::Services },
+        Self::FileMenu => quote! { #prefix::FileMenu },
+        Self::EditMenu => quote! { #prefix::EditMenu },
+        Self::ViewMenu => quote! { #prefix::ViewMenu },
+        Self::WindowMenu => quote! { #prefix::WindowMenu },
+        Self::HelpMenu => quote! { #prefix::HelpMenu },
+      })
+    }
+  }
+
+  impl ToTokens for MenuConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let locale = opt_str_lit(self.locale.as_ref());
+      let role_text = map_lit(
+        quote! { ::std::collections::HashMap },
+        &self.role_text,
+        identity,
+        str_lit,
+      );
+
+      literal_struct!(
+        tokens,
+        ::tauri::utils::config::MenuConfig,
+        locale,
+        role_text
+      );
+    }
+  }
+
   impl ToTokens for FsScope {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let prefix = quote! { ::tauri::utils::config::FsScope };
@@ -2766,9 +3913,12 @@ mod build {
       let windows = vec_lit(&self.windows, identity);
       let security = &self.security;
       let tray_icon = opt_lit(self.tray_icon.as_ref());
+      let tray_icons = vec_lit(&self.tray_icons, identity);
+      let menu = &self.menu;
       let macos_private_api = self.macos_private_api;
       let with_global_tauri = self.with_global_tauri;
       let enable_gtk_app_id = self.enable_gtk_app_id;
+      let webview_metrics_interval = opt_lit(self.webview_metrics_interval.as_ref());
 
       literal_struct!(
         tokens,
@@ -2776,9 +3926,12 @@ mod build {
         windows,
         security,
         tray_icon,
+        tray_icons,
+        menu,
         macos_private_api,
         with_global_tauri,
-        enable_gtk_app_id
+        enable_gtk_app_id,
+        webview_metrics_interval
       );
     }
   }
@@ -2853,20 +4006,29 @@ mod test {
         capabilities: Vec::new(),
       },
       tray_icon: None,
+      tray_icons: Vec::new(),
+      menu: Default::default(),
       macos_private_api: false,
       with_global_tauri: false,
       enable_gtk_app_id: false,
+      webview_metrics_interval: None,
     };
 
     // create a build config
     let build = BuildConfig {
       runner: None,
       dev_url: None,
+      dev_url_health_path: None,
+      dev_url_timeout: None,
+      dev_url_poll_interval: None,
       frontend_dist: None,
       before_dev_command: None,
       before_build_command: None,
       before_bundle_command: None,
       features: None,
+      profile: None,
+      dev_profile: None,
+      watch: None,
     };
 
     // create a bundle config
@@ -2878,16 +4040,24 @@ mod test {
       homepage: None,
       icon: Vec::new(),
       resources: None,
+      resource_collision: Default::default(),
       copyright: None,
       category: None,
       file_associations: None,
       short_description: None,
       long_description: None,
+      display_names: Default::default(),
       license: None,
       license_file: None,
       linux: Default::default(),
       macos: Default::default(),
       external_bin: None,
+      output_dir: None,
+      artifact_name: None,
+      checksums: Vec::new(),
+      reproducible: false,
+      strip: false,
+      archive: Default::default(),
       windows: Default::default(),
       ios: Default::default(),
       android: Default::default(),
@@ -2899,4 +4069,150 @@ mod test {
     assert_eq!(d_bundle, bundle);
     assert_eq!(d_windows, app.windows);
   }
+
+  #[test]
+  fn parse_webview_settings() {
+    let window: WindowConfig = serde_json::from_value(serde_json::json!({
+      "webviewSettings": {
+        "autoplay": "allow",
+        "backgroundThrottling": "disabled",
+        "smoothScrolling": false
+      }
+    }))
+    .unwrap();
+    assert_eq!(
+      window.webview_settings,
+      WebviewSettingsConfig {
+        autoplay: Some(AutoplayPolicy::Allow),
+        background_throttling: Some(BackgroundThrottlingPolicy::Disabled),
+        smooth_scrolling: Some(false),
+      }
+    );
+
+    let window: WindowConfig = serde_json::from_value(serde_json::json!({
+      "webviewSettings": { "autoplay": "require-gesture" }
+    }))
+    .unwrap();
+    assert_eq!(
+      window.webview_settings,
+      WebviewSettingsConfig {
+        autoplay: Some(AutoplayPolicy::RequireGesture),
+        ..Default::default()
+      }
+    );
+
+    let window: WindowConfig = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(window.webview_settings, WebviewSettingsConfig::default());
+
+    assert!(serde_json::from_value::<WindowConfig>(serde_json::json!({
+      "webviewSettings": { "autoplay": "always" }
+    }))
+    .is_err());
+    assert!(serde_json::from_value::<WindowConfig>(serde_json::json!({
+      "webviewSettings": { "unknown": true }
+    }))
+    .is_err());
+  }
+
+  #[test]
+  fn parse_windows_digest_algorithm() {
+    let windows: WindowsConfig =
+      serde_json::from_value(serde_json::json!({ "digestAlgorithm": "SHA256" })).unwrap();
+    assert_eq!(
+      windows.digest_algorithm.unwrap().to_vec(),
+      [DigestAlgorithm::Sha256]
+    );
+
+    let windows: WindowsConfig =
+      serde_json::from_value(serde_json::json!({ "digestAlgorithm": ["sha1", "sha256"] })).unwrap();
+    let algorithms = windows.digest_algorithm.unwrap().to_vec();
+    assert_eq!(algorithms, [DigestAlgorithm::Sha1, DigestAlgorithm::Sha256]);
+    assert_eq!(algorithms[0].to_string(), "sha1");
+
+    assert!(serde_json::from_value::<WindowsConfig>(
+      serde_json::json!({ "digestAlgorithm": "md5" })
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn parse_tray_icons() {
+    let app: AppConfig = serde_json::from_value(serde_json::json!({
+      "trayIcon": { "iconPath": "icons/status.png", "tooltip": "Status" },
+      "trayIcons": [
+        { "id": "capture", "iconPath": "icons/capture.png", "menu": "capture-menu" }
+      ]
+    }))
+    .unwrap();
+    let trays = app.all_tray_icons().collect::<Vec<_>>();
+    assert_eq!(
+      trays.iter().map(|t| t.id_or_default()).collect::<Vec<_>>(),
+      ["main", "capture"]
+    );
+    assert_eq!(trays[0].menu, None);
+    assert_eq!(trays[1].menu.as_deref(), Some("capture-menu"));
+    assert!(trays[1].menu_on_left_click);
+    assert_eq!(app.features(), ["tray-icon"]);
+
+    let app: AppConfig = serde_json::from_value(serde_json::json!({
+      "trayIcons": [{ "id": "capture", "iconPath": "icons/capture.png" }]
+    }))
+    .unwrap();
+    assert!(app.tray_icon.is_none());
+    assert_eq!(app.features(), ["tray-icon"]);
+
+    assert!(serde_json::from_value::<AppConfig>(serde_json::json!({
+      "trayIcons": [{ "id": "capture" }]
+    }))
+    .is_err());
+  }
+
+  #[test]
+  fn parse_version() {
+    let parse = |version: serde_json::Value| {
+      serde_json::from_value::<Config>(serde_json::json!({ "version": version }))
+        .map(|config| config.version)
+    };
+
+    assert_eq!(
+      parse(serde_json::json!("1.2.3")).unwrap().as_deref(),
+      Some("1.2.3")
+    );
+    assert!(parse(serde_json::json!("one")).is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let version_file = dir.path().join("VERSION");
+    std::fs::write(&version_file, "1.2.3\n").unwrap();
+
+    assert_eq!(
+      parse(serde_json::json!(version_file)).unwrap().as_deref(),
+      Some("1.2.3")
+    );
+    assert_eq!(
+      parse(serde_json::json!({ "file": version_file }))
+        .unwrap()
+        .as_deref(),
+      Some("1.2.3")
+    );
+
+    let resolve = |build_number: Option<&str>| {
+      serde_json::from_value::<VersionSourceConfig>(serde_json::json!({
+        "file": version_file,
+        "buildNumberEnv": "CI_BUILD_NUMBER"
+      }))
+      .unwrap()
+      .resolve_with_env(|var| {
+        assert_eq!(var, "CI_BUILD_NUMBER");
+        build_number.map(Into::into)
+      })
+      .unwrap()
+    };
+    assert_eq!(resolve(Some("456")), "1.2.3+456");
+    // local builds without a build number
+    assert_eq!(resolve(None), "1.2.3");
+    assert_eq!(resolve(Some(" ")), "1.2.3");
+
+    assert!(parse(serde_json::json!({ "file": dir.path().join("MISSING") })).is_err());
+    assert!(parse(serde_json::json!({ "path": version_file })).is_err());
+  }
 }
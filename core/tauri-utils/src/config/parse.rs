@@ -163,6 +163,22 @@ pub fn is_configuration_file(target: Target, path: &Path) -> bool {
     .unwrap_or_default()
 }
 
+/// Lists the existing configuration files of the enabled formats that [`read_from`] may read from the given folder,
+/// including the platform-specific ones.
+pub fn configuration_files(target: Target, folder: &Path) -> Vec<PathBuf> {
+  ENABLED_FORMATS
+    .iter()
+    .flat_map(|format| {
+      [
+        format.into_file_name(),
+        format.into_platform_file_name(target),
+      ]
+    })
+    .map(|file_name| folder.join(file_name))
+    .filter(|path| path.is_file())
+    .collect()
+}
+
 /// Reads the configuration from the given root directory.
 ///
 /// It first looks for a `tauri.conf.json[5]` or `Tauri.toml` file on the given directory. The file must exist.
@@ -0,0 +1,323 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Reading the app version and mapping it to the version formats of the targets, see [`Config::version`](super::Config::version).
+
+use semver::Version;
+use thiserror::Error;
+
+use std::{
+  fs::read_to_string,
+  path::{Path, PathBuf},
+  str::FromStr,
+};
+
+/// Represents all the errors that can happen while reading or mapping the app version.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum VersionError {
+  /// Failed to read the version from a file.
+  #[error("failed to read the app version from {path}: {reason}")]
+  Read {
+    /// The file the version is read from.
+    path: PathBuf,
+    /// Why the version could not be read.
+    reason: String,
+  },
+  /// The version is not a semver version.
+  #[error("app version `{0}` must be a semver version")]
+  NotSemver(String),
+  /// A build number cannot be appended to a version that already has build metadata.
+  #[error("cannot append a build number to app version `{0}`, which already has build metadata")]
+  HasBuildMetadata(String),
+  /// The version cannot be used for a target.
+  #[error("app version `{version}` is invalid for the {target} target: {reason}")]
+  InvalidForTarget {
+    /// The app version.
+    version: String,
+    /// The name of the target, e.g. `msi` or `Android`.
+    target: &'static str,
+    /// Why the version cannot be used.
+    reason: String,
+  },
+}
+
+/// Parses `version` as a semver version.
+pub fn parse(version: &str) -> Result<Version, VersionError> {
+  Version::from_str(version).map_err(|_| VersionError::NotSemver(version.into()))
+}
+
+/// Reads the app version from `path`, which is either:
+///
+/// - a JSON file such as `package.json`, reading its `version` field;
+/// - a `Cargo.toml` file, reading its `package.version`, see [`cargo_package_version`];
+/// - a text file such as `VERSION`, containing only the version.
+pub fn read_version_file(path: &Path) -> Result<String, VersionError> {
+  let read_error = |reason: String| VersionError::Read {
+    path: path.to_path_buf(),
+    reason,
+  };
+
+  if path.file_name().map_or(false, |name| name == "Cargo.toml") {
+    return cargo_package_version(path);
+  }
+
+  let version = if path.extension().map_or(false, |ext| ext == "json") {
+    let json_str = read_to_string(path).map_err(|e| read_error(e.to_string()))?;
+    let json: serde_json::Value =
+      serde_json::from_str(&json_str).map_err(|e| read_error(e.to_string()))?;
+    json
+      .as_object()
+      .ok_or_else(|| read_error("the file is not a JSON object".into()))?
+      .get("version")
+      .ok_or_else(|| read_error("the JSON object must contain a `version` field".into()))?
+      .as_str()
+      .ok_or_else(|| read_error("the `version` field must be a string".into()))?
+      .to_string()
+  } else {
+    read_to_string(path)
+      .map_err(|e| read_error(e.to_string()))?
+      .trim()
+      .to_string()
+  };
+
+  parse(&version).map(|v| v.to_string())
+}
+
+/// Reads the `package.version` of the `Cargo.toml` file at `manifest_path`.
+///
+/// A `version.workspace = true` version is read from the `workspace.package.version` of the closest ancestor workspace manifest.
+pub fn cargo_package_version(manifest_path: &Path) -> Result<String, VersionError> {
+  let manifest = read_manifest(manifest_path)?;
+  let version = manifest
+    .get("package")
+    .and_then(|package| package.get("version"))
+    .ok_or_else(|| VersionError::Read {
+      path: manifest_path.to_path_buf(),
+      reason: "the manifest does not have a `package.version` field".into(),
+    })?;
+
+  let version = match version {
+    toml::Value::String(version) => version.clone(),
+    toml::Value::Table(table) if table.get("workspace").and_then(|w| w.as_bool()) == Some(true) => {
+      workspace_package_version(manifest_path)?
+    }
+    _ => {
+      return Err(VersionError::Read {
+        path: manifest_path.to_path_buf(),
+        reason: "`package.version` must be a string or `{ workspace = true }`".into(),
+      })
+    }
+  };
+
+  parse(&version).map(|v| v.to_string())
+}
+
+fn workspace_package_version(manifest_path: &Path) -> Result<String, VersionError> {
+  let manifest_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+  for dir in manifest_dir.ancestors() {
+    let workspace_manifest_path = dir.join("Cargo.toml");
+    if !workspace_manifest_path.is_file() {
+      continue;
+    }
+    let workspace_manifest = read_manifest(&workspace_manifest_path)?;
+    if let Some(workspace) = workspace_manifest.get("workspace") {
+      return workspace
+        .get("package")
+        .and_then(|package| package.get("version"))
+        .and_then(|version| version.as_str())
+        .map(ToString::to_string)
+        .ok_or_else(|| VersionError::Read {
+          path: workspace_manifest_path,
+          reason: "the workspace manifest does not have a `workspace.package.version` field".into(),
+        });
+    }
+  }
+
+  Err(VersionError::Read {
+    path: manifest_path.to_path_buf(),
+    reason: "`package.version` is inherited from a workspace but no workspace manifest was found"
+      .into(),
+  })
+}
+
+fn read_manifest(path: &Path) -> Result<toml::Table, VersionError> {
+  let read_error = |reason: String| VersionError::Read {
+    path: path.to_path_buf(),
+    reason,
+  };
+  let manifest = read_to_string(path).map_err(|e| read_error(e.to_string()))?;
+  toml::from_str(&manifest).map_err(|e| read_error(e.to_string()))
+}
+
+/// Appends `build_number` to `version` as its build metadata, e.g. `1.2.3+456`.
+pub fn with_build_number(version: &str, build_number: &str) -> Result<String, VersionError> {
+  let mut version = parse(version)?;
+  if !version.build.is_empty() {
+    return Err(VersionError::HasBuildMetadata(version.to_string()));
+  }
+  version.build = semver::BuildMetadata::new(build_number.trim())
+    .map_err(|_| VersionError::NotSemver(format!("{version}+{}", build_number.trim())))?;
+  Ok(version.to_string())
+}
+
+/// The build number of `version`, its numeric build metadata, e.g. `456` for `1.2.3+456`.
+///
+/// Fails with an error naming `target` if the build metadata is not a number lower than or equal to `max`.
+pub fn build_number(
+  version: &Version,
+  target: &'static str,
+  max: u64,
+) -> Result<Option<u64>, VersionError> {
+  if version.build.is_empty() {
+    return Ok(None);
+  }
+  match version.build.parse::<u64>() {
+    Ok(build) if build <= max => Ok(Some(build)),
+    _ => Err(VersionError::InvalidForTarget {
+      version: version.to_string(),
+      target,
+      reason: format!("the build metadata must be a number lower than or equal to {max}"),
+    }),
+  }
+}
+
+/// The Android `versionCode` derived from `version`.
+///
+/// It is the build number of the version if it has one, since it increases with every build,
+/// otherwise `major * 1000000 + minor * 1000 + patch`.
+pub fn android_version_code(version: &str) -> Result<u64, VersionError> {
+  let version = parse(version)?;
+  match build_number(&version, "Android", 2100000000)? {
+    Some(build) => Ok(build),
+    None => Ok(version.major * 1000000 + version.minor * 1000 + version.patch),
+  }
+}
+
+/// The Apple `CFBundleShortVersionString` and `CFBundleVersion` of `version`.
+///
+/// The short version is `major.minor.patch`, and the bundle version `major.minor.patch.build`
+/// if the version has a build number, or `None` otherwise.
+/// `target` is the name of the Apple target, `macOS` or `iOS`.
+pub fn apple_bundle_versions(
+  version: &str,
+  target: &'static str,
+) -> Result<(String, Option<String>), VersionError> {
+  let version = parse(version)?;
+  let short_version = format!("{}.{}.{}", version.major, version.minor, version.patch);
+  let bundle_version = build_number(&version, target, u32::MAX.into())?
+    .map(|build| format!("{short_version}.{build}"));
+  Ok((short_version, bundle_version))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_version_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+
+    std::fs::write(dir.join("VERSION"), "1.2.3\n").unwrap();
+    assert_eq!(read_version_file(&dir.join("VERSION")).unwrap(), "1.2.3");
+
+    std::fs::write(dir.join("package.json"), r#"{ "version": "2.0.0-beta.1" }"#).unwrap();
+    assert_eq!(
+      read_version_file(&dir.join("package.json")).unwrap(),
+      "2.0.0-beta.1"
+    );
+
+    std::fs::write(dir.join("INVALID"), "one").unwrap();
+    assert!(matches!(
+      read_version_file(&dir.join("INVALID")),
+      Err(VersionError::NotSemver(v)) if v == "one"
+    ));
+    assert!(matches!(
+      read_version_file(&dir.join("MISSING")),
+      Err(VersionError::Read { .. })
+    ));
+  }
+
+  #[test]
+  fn reads_cargo_workspace_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir = dir.path();
+    let crate_dir = dir.join("crates/app");
+    std::fs::create_dir_all(&crate_dir).unwrap();
+    std::fs::write(
+      dir.join("Cargo.toml"),
+      "[workspace]\nmembers = [\"crates/app\"]\n\n[workspace.package]\nversion = \"3.1.4\"\n",
+    )
+    .unwrap();
+
+    std::fs::write(
+      crate_dir.join("Cargo.toml"),
+      "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+      read_version_file(&crate_dir.join("Cargo.toml")).unwrap(),
+      "0.1.0"
+    );
+
+    std::fs::write(
+      crate_dir.join("Cargo.toml"),
+      "[package]\nname = \"app\"\nversion.workspace = true\n",
+    )
+    .unwrap();
+    assert_eq!(
+      read_version_file(&crate_dir.join("Cargo.toml")).unwrap(),
+      "3.1.4"
+    );
+  }
+
+  #[test]
+  fn appends_build_number() {
+    assert_eq!(with_build_number("1.2.3", "456").unwrap(), "1.2.3+456");
+    assert_eq!(
+      with_build_number("1.2.3-rc.1", "456").unwrap(),
+      "1.2.3-rc.1+456"
+    );
+    assert!(matches!(
+      with_build_number("1.2.3+1", "456"),
+      Err(VersionError::HasBuildMetadata(v)) if v == "1.2.3+1"
+    ));
+    assert!(with_build_number("1.2.3", "4 56").is_err());
+  }
+
+  #[test]
+  fn maps_version_per_target() {
+    assert_eq!(android_version_code("1.2.3").unwrap(), 1002003);
+    assert_eq!(android_version_code("1.2.3+456").unwrap(), 456);
+    assert!(matches!(
+      android_version_code("1.2.3+abc"),
+      Err(VersionError::InvalidForTarget {
+        target: "Android",
+        ..
+      })
+    ));
+
+    assert_eq!(
+      apple_bundle_versions("1.2.3", "macOS").unwrap(),
+      ("1.2.3".into(), None)
+    );
+    assert_eq!(
+      apple_bundle_versions("1.2.3-beta+456", "iOS").unwrap(),
+      ("1.2.3".into(), Some("1.2.3.456".into()))
+    );
+    let error = apple_bundle_versions("1.2.3+abc", "iOS").unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "app version `1.2.3+abc` is invalid for the iOS target: the build metadata must be a number lower than or equal to 4294967295"
+    );
+
+    let version = parse("1.2.3+70000").unwrap();
+    assert_eq!(
+      build_number(&version, "nsis", u64::MAX).unwrap(),
+      Some(70000)
+    );
+    assert!(build_number(&version, "msi", 65535).is_err());
+  }
+}
@@ -220,7 +220,7 @@ impl Display for TitleBarStyle {
 }
 
 /// System theme.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[non_exhaustive]
 pub enum Theme {
@@ -372,6 +372,19 @@ pub enum Error {
   #[cfg(feature = "resources")]
   #[error("could not walk directory `{0}`, try changing `allow_walk` to true on the `ResourcePaths` constructor.")]
   NotAllowedToWalkDir(std::path::PathBuf),
+  /// Distinct resources are bundled at the same path.
+  #[cfg(feature = "resources")]
+  #[error(
+    "resources {} are all bundled at `{}`, rename them or change `bundle > resourceCollision`",
+    .sources.iter().map(|s| format!("`{}`", s.display())).collect::<Vec<_>>().join(", "),
+    .target.display()
+  )]
+  ResourceCollision {
+    /// The path the resources are bundled at.
+    target: std::path::PathBuf,
+    /// The colliding resources.
+    sources: Vec<std::path::PathBuf>,
+  },
 }
 
 /// Reconstructs a path from its components using the platform separator then converts it to String and removes UNC prefixes on Windows if it exists.
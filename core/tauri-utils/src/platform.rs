@@ -251,7 +251,7 @@ fn is_cargo_output_directory(path: &Path) -> bool {
 /// On Linux, when running in an AppImage the `APPDIR` variable will be set to
 /// the mounted location of the app, and the resource dir will be
 /// `${APPDIR}/usr/lib/${exe_name}`. If not running in an AppImage, the path is
-/// `/usr/lib/${exe_name}`.  When running the app from
+/// `/usr/lib/${exe_name}`, or `${SNAP}/usr/lib/${exe_name}` in a Snap package.  When running the app from
 /// `src-tauri/target/(debug|release)/`, the path is
 /// `${exe_dir}/../lib/${exe_name}`.
 ///
@@ -296,6 +296,15 @@ fn resource_dir_from<P: AsRef<Path>>(
         .join(format!("../lib/{}", package_info.crate_name))
         .canonicalize()
         .map_err(Into::into)
+    } else if curr_dir == "/app/bin" {
+      // running from a Flatpak sandbox
+      Ok(PathBuf::from(format!(
+        "/app/lib/{}",
+        package_info.crate_name
+      )))
+    } else if curr_dir.starts_with("/snap/") && curr_dir.ends_with("/usr/bin") {
+      // running from a Snap package, mounted at `/snap/<name>/<revision>`
+      Ok(exe_dir.with_file_name("lib").join(package_info.crate_name))
     } else if let Some(appdir) = &env.appdir {
       let appdir: &std::path::Path = appdir.as_ref();
       Ok(PathBuf::from(format!(
@@ -303,6 +312,17 @@ fn resource_dir_from<P: AsRef<Path>>(
         appdir.display(),
         package_info.crate_name
       )))
+    } else if curr_dir != "/usr/bin"
+      && curr_dir.ends_with("/usr/bin")
+      && exe_dir
+        .join(format!("../lib/{}", package_info.crate_name))
+        .is_dir()
+    {
+      // running from an extracted portable archive
+      exe_dir
+        .join(format!("../lib/{}", package_info.crate_name))
+        .canonicalize()
+        .map_err(Into::into)
     } else {
       // running bundle
       Ok(PathBuf::from(format!(
@@ -382,5 +402,30 @@ mod tests {
     assert_eq!(resource_dir.unwrap(), PathBuf::from("/usr/lib/my-app"));
     #[cfg(windows)]
     assert_eq!(resource_dir.unwrap(), path.parent().unwrap());
+
+    #[cfg(target_os = "linux")]
+    {
+      let path = PathBuf::from("/app/bin/my-app");
+      let resource_dir = super::resource_dir_from(&path, &package_info, &env).unwrap();
+      assert_eq!(resource_dir, PathBuf::from("/app/lib/my-app"));
+
+      let path = PathBuf::from("/snap/my-app/x1/usr/bin/my-app");
+      let resource_dir = super::resource_dir_from(&path, &package_info, &env).unwrap();
+      assert_eq!(
+        resource_dir,
+        PathBuf::from("/snap/my-app/x1/usr/lib/my-app")
+      );
+
+      let archive_dir = std::env::temp_dir().join("tauri-resource-dir-test/MyApp_1.0.0_amd64");
+      std::fs::create_dir_all(archive_dir.join("usr/bin")).unwrap();
+      std::fs::create_dir_all(archive_dir.join("usr/lib/my-app")).unwrap();
+      let path = archive_dir.join("usr/bin/my-app");
+      let resource_dir = super::resource_dir_from(&path, &package_info, &env).unwrap();
+      assert_eq!(
+        resource_dir,
+        archive_dir.join("usr/lib/my-app").canonicalize().unwrap()
+      );
+      std::fs::remove_dir_all(archive_dir.parent().unwrap()).unwrap();
+    }
   }
 }
@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: MIT
 
 use std::{
-  collections::HashMap,
+  collections::{BTreeMap, HashMap},
   path::{Component, Path, PathBuf},
 };
 
+use crate::config::ResourceCollision;
+
 /// Given a path (absolute or relative) to a resource file, returns the
 /// relative path from the bundle resources directory where that resource
 /// should be stored.
@@ -109,12 +111,21 @@ pub struct ResourcePathsIter<'a> {
 }
 
 /// Information for a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Resource {
   path: PathBuf,
   target: PathBuf,
 }
 
 impl Resource {
+  /// Creates a resource bundled from `path` to `target`, relative to the resources directory.
+  pub fn new(path: impl Into<PathBuf>, target: impl Into<PathBuf>) -> Self {
+    Self {
+      path: path.into(),
+      target: target.into(),
+    }
+  }
+
   /// The path of the resource.
   pub fn path(&self) -> &Path {
     &self.path
@@ -247,3 +258,121 @@ impl<'a> Iterator for ResourcePathsIter<'a> {
     }
   }
 }
+
+/// Resolves the resources whose distinct sources are bundled at the same target with the given [`ResourceCollision`] strategy.
+///
+/// Resources listed more than once, and resources with the same target and content, are only kept once.
+/// The order of the resources is preserved.
+pub fn resolve_collisions(
+  resources: Vec<Resource>,
+  strategy: ResourceCollision,
+) -> crate::Result<Vec<Resource>> {
+  let mut targets: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+  for (index, resource) in resources.iter().enumerate() {
+    targets
+      .entry(resource.target.clone())
+      .or_default()
+      .push(index);
+  }
+
+  let mut keep = vec![true; resources.len()];
+  let mut hashed_targets = HashMap::new();
+  for (target, indices) in targets {
+    if indices.len() == 1 {
+      continue;
+    }
+
+    // the same source may be matched by several patterns
+    let mut sources: Vec<usize> = Vec::new();
+    for index in indices {
+      if sources
+        .iter()
+        .any(|&source| resources[source].path == resources[index].path)
+      {
+        keep[index] = false;
+      } else {
+        sources.push(index);
+      }
+    }
+
+    // sources with the same content are bundled once
+    let mut contents: Vec<(usize, u64)> = Vec::new();
+    for index in sources {
+      let hash = content_hash(&resources[index].path)?;
+      if contents.iter().any(|(_, h)| *h == hash) {
+        keep[index] = false;
+      } else {
+        contents.push((index, hash));
+      }
+    }
+    if contents.len() == 1 {
+      continue;
+    }
+
+    match strategy {
+      ResourceCollision::Error => {
+        return Err(crate::Error::ResourceCollision {
+          target,
+          sources: contents
+            .iter()
+            .map(|(index, _)| resources[*index].path.clone())
+            .collect(),
+        });
+      }
+      ResourceCollision::Warn => {
+        let (last, _) = contents.pop().unwrap();
+        log::warn!(
+          "resources {} are all bundled at `{}`, bundling `{}`",
+          contents
+            .iter()
+            .map(|(index, _)| format!("`{}`", resources[*index].path.display()))
+            .collect::<Vec<_>>()
+            .join(", "),
+          target.display(),
+          resources[last].path.display()
+        );
+        for (index, _) in contents {
+          keep[index] = false;
+        }
+      }
+      ResourceCollision::HashSubdir => {
+        for (index, hash) in contents {
+          hashed_targets.insert(index, hashed_target(&target, hash));
+        }
+      }
+    }
+  }
+
+  Ok(
+    resources
+      .into_iter()
+      .enumerate()
+      .filter(|(index, _)| keep[*index])
+      .map(|(index, mut resource)| {
+        if let Some(target) = hashed_targets.remove(&index) {
+          resource.target = target;
+        }
+        resource
+      })
+      .collect(),
+  )
+}
+
+/// `parent/<hash>/file_name`, the hash being the first 8 hex digits of the content hash.
+fn hashed_target(target: &Path, hash: u64) -> PathBuf {
+  let hash_dir = format!("{hash:016x}")[..8].to_string();
+  let mut hashed = target.parent().map(Path::to_path_buf).unwrap_or_default();
+  hashed.push(hash_dir);
+  if let Some(file_name) = target.file_name() {
+    hashed.push(file_name);
+  }
+  hashed
+}
+
+/// The 64-bit FNV-1a hash of the file content, which unlike the std hashers is stable across Rust versions.
+fn content_hash(path: &Path) -> crate::Result<u64> {
+  let content = std::fs::read(path)?;
+  Ok(content.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+  }))
+}
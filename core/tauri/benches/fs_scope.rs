@@ -0,0 +1,115 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Compares the filesystem scope checks with matching every pattern of the scope.
+//!
+//! Run with `cargo bench --bench fs_scope --features test`.
+
+use std::{
+  hint::black_box,
+  path::{Path, PathBuf},
+  time::{Duration, Instant},
+};
+
+use tauri::{scope::fs::Scope, utils::config::FsScope};
+
+const DIRECTORIES: usize = 200;
+const PATHS: usize = 10_000;
+const ITERATIONS: u32 = 10;
+
+fn options() -> glob::MatchOptions {
+  glob::MatchOptions {
+    require_literal_separator: true,
+    require_literal_leading_dot: cfg!(unix),
+    ..Default::default()
+  }
+}
+
+/// The scope check matching every pattern, the behavior before the patterns were indexed.
+fn is_allowed_linear(scope: &Scope, path: &Path) -> bool {
+  let options = options();
+  !scope
+    .forbidden_patterns()
+    .iter()
+    .any(|p| p.matches_path_with(path, options))
+    && scope
+      .allowed_patterns()
+      .iter()
+      .any(|p| p.matches_path_with(path, options))
+}
+
+fn bench<F: FnMut() -> usize>(name: &str, mut f: F) -> Duration {
+  let start = Instant::now();
+  let mut allowed = 0;
+  for _ in 0..ITERATIONS {
+    allowed = black_box(f());
+  }
+  let elapsed = start.elapsed() / ITERATIONS;
+  println!("{name:<24} {elapsed:>12.2?} per {PATHS} paths ({allowed} allowed)");
+  elapsed
+}
+
+fn main() {
+  let app = tauri::test::mock_app();
+  // a directory that does not exist so the paths are not canonicalized
+  let root = std::env::temp_dir()
+    .join("tauri-fs-scope-bench")
+    .join("data");
+
+  let scope = Scope::new(
+    &app,
+    &FsScope::Scope {
+      allow: vec![root.join("projects").join("**")],
+      deny: vec![root.join("projects").join("secret").join("**")],
+      require_literal_leading_dot: None,
+    },
+  )
+  .unwrap();
+  for i in 0..DIRECTORIES {
+    scope
+      .allow_directory(root.join(format!("user-{i}")), i % 2 == 0)
+      .unwrap();
+  }
+
+  let paths = (0..PATHS)
+    .map(|i| match i % 4 {
+      0 => root.join("projects").join(format!("file-{i}")),
+      1 => root.join(format!("user-{}", i % DIRECTORIES)).join("file"),
+      2 => root
+        .join(format!("user-{}", i % DIRECTORIES))
+        .join("dir")
+        .join("file"),
+      _ => root.join("other").join(format!("file-{i}")),
+    })
+    .collect::<Vec<PathBuf>>();
+
+  println!(
+    "{} allowed and {} forbidden patterns",
+    scope.allowed_patterns().len(),
+    scope.forbidden_patterns().len()
+  );
+
+  let linear = bench("linear", || {
+    paths
+      .iter()
+      .filter(|path| is_allowed_linear(&scope, path))
+      .count()
+  });
+  let indexed = bench("is_allowed", || {
+    paths.iter().filter(|path| scope.is_allowed(path)).count()
+  });
+  let batch = bench("is_allowed_batch", || {
+    scope
+      .is_allowed_batch(&paths)
+      .into_iter()
+      .filter(|allowed| *allowed)
+      .count()
+  });
+
+  println!(
+    "is_allowed is {:.1}x and is_allowed_batch {:.1}x faster than the linear scan",
+    linear.as_secs_f64() / indexed.as_secs_f64(),
+    linear.as_secs_f64() / batch.as_secs_f64()
+  );
+}
@@ -71,6 +71,7 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       // setters
       ("center", false),
       ("request_user_attention", false),
+      ("beep", false),
       ("set_resizable", false),
       ("set_maximizable", false),
       ("set_minimizable", false),
@@ -122,12 +123,20 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       // getters
       ("webview_position", true),
       ("webview_size", true),
+      ("webview_process_metrics", false),
       // setters
       ("webview_close", false),
       ("set_webview_size", false),
       ("set_webview_position", false),
       ("set_webview_focus", false),
       ("set_webview_zoom", false),
+      ("clear_webview_cache", false),
+      ("unregister_webview_service_workers", false),
+      ("clear_webview_site_data_for_origin", false),
+      ("webview_find", false),
+      ("webview_find_next", false),
+      ("webview_find_previous", false),
+      ("webview_stop_find", false),
       ("print", false),
       ("reparent", false),
       // internal
@@ -143,6 +152,9 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       ("app_show", false),
       ("app_hide", false),
       ("default_window_icon", false),
+      ("all_webview_metrics", false),
+      ("system_preferences", true),
+      ("play_system_sound", false),
     ],
   ),
   (
@@ -190,6 +202,7 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       ("get_by_id", true),
       ("remove_by_id", true),
       ("set_icon", true),
+      ("set_icon_for_theme", true),
       ("set_menu", true),
       ("set_tooltip", true),
       ("set_title", true),
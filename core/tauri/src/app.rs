@@ -5,8 +5,8 @@
 use crate::{
   image::Image,
   ipc::{
-    channel::ChannelDataIpcQueue, CallbackFn, CommandArg, CommandItem, Invoke, InvokeError,
-    InvokeHandler, InvokeResponder, InvokeResponse,
+    channel::ChannelDataIpcQueue, router::CommandRouter, CallbackFn, CommandArg, CommandItem,
+    CommandState, Invoke, InvokeError, InvokeHandler, InvokeResponder, InvokeResponse,
   },
   manager::{
     webview::{UriSchemeProtocol, WebviewLabelDef},
@@ -21,13 +21,13 @@ use crate::{
   sealed::{ManagerBase, RuntimeOrDispatch},
   utils::config::Config,
   utils::Env,
-  webview::PageLoadPayload,
+  webview::{PageLoadPayload, WebviewMetrics},
   Context, DeviceEventFilter, Emitter, EventLoopMessage, Listener, Manager, Monitor, Result,
-  Runtime, Scopes, StateManager, Theme, Webview, WebviewWindowBuilder, Window,
+  Runtime, Scopes, StateManager, SystemSound, Theme, Webview, WebviewWindowBuilder, Window,
 };
 
 #[cfg(desktop)]
-use crate::menu::{Menu, MenuEvent};
+use crate::menu::{Menu, MenuEvent, PredefinedMenuEvent};
 #[cfg(all(desktop, feature = "tray-icon"))]
 use crate::tray::{TrayIcon, TrayIconBuilder, TrayIconEvent, TrayIconId};
 use raw_window_handle::HasDisplayHandle;
@@ -37,6 +37,7 @@ use tauri_macros::default_runtime;
 use tauri_runtime::EventLoopProxy;
 use tauri_runtime::{
   dpi::{PhysicalPosition, PhysicalSize},
+  webview::{FindResult, UriSchemeResponseBody},
   window::DragDropEvent,
   RuntimeInitArgs,
 };
@@ -47,7 +48,12 @@ use std::{
   borrow::Cow,
   collections::HashMap,
   fmt,
-  sync::{mpsc::Sender, Arc, MutexGuard},
+  io::{self, Read, Write},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender, SyncSender},
+    Arc, MutexGuard,
+  },
 };
 
 use crate::{event::EventId, runtime::RuntimeHandle, Event, EventTarget};
@@ -56,15 +62,22 @@ use crate::{event::EventId, runtime::RuntimeHandle, Event, EventTarget};
 use crate::ActivationPolicy;
 
 pub(crate) mod plugin;
+pub(crate) mod system_preferences;
+
+pub use system_preferences::SystemPreferences;
 
 #[cfg(desktop)]
 pub(crate) type GlobalMenuEventListener<T> = Box<dyn Fn(&T, crate::menu::MenuEvent) + Send + Sync>;
+#[cfg(desktop)]
+pub(crate) type PredefinedMenuEventListener<T> =
+  Box<dyn Fn(&T, &crate::menu::PredefinedMenuEvent) + Send + Sync>;
 #[cfg(all(desktop, feature = "tray-icon"))]
 pub(crate) type GlobalTrayIconEventListener<T> =
   Box<dyn Fn(&T, crate::tray::TrayIconEvent) + Send + Sync>;
-pub(crate) type GlobalWindowEventListener<R> = Box<dyn Fn(&Window<R>, &WindowEvent) + Send + Sync>;
+pub(crate) type GlobalWindowEventListener<R> =
+  Box<dyn Fn(&Window<R>, &WindowEvent) -> EventAction + Send + Sync>;
 pub(crate) type GlobalWebviewEventListener<R> =
-  Box<dyn Fn(&Webview<R>, &WebviewEvent) + Send + Sync>;
+  Box<dyn Fn(&Webview<R>, &WebviewEvent) -> EventAction + Send + Sync>;
 /// A closure that is run when the Tauri application is setting up.
 pub type SetupHook<R> =
   Box<dyn FnOnce(&mut App<R>) -> std::result::Result<(), Box<dyn std::error::Error>> + Send>;
@@ -173,12 +186,35 @@ impl From<RuntimeWindowEvent> for WindowEvent {
 pub enum WebviewEvent {
   /// An event associated with the drag and drop action.
   DragDrop(DragDropEvent),
+  /// The result of a find-in-page search, see [`Webview::find`](crate::webview::Webview::find).
+  FindResult(FindResult),
+}
+
+/// What a window or webview event handler wants to happen to the handlers registered after it.
+///
+/// Handlers that return `()` continue the propagation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EventAction {
+  /// Run the next handlers. This is the default.
+  #[default]
+  Continue,
+  /// Do not run the handlers registered after this one.
+  ///
+  /// The event is still emitted to the frontend listeners.
+  StopPropagation,
+}
+
+impl From<()> for EventAction {
+  fn from(_: ()) -> Self {
+    Self::Continue
+  }
 }
 
 impl From<RuntimeWebviewEvent> for WebviewEvent {
   fn from(event: RuntimeWebviewEvent) -> Self {
     match event {
       RuntimeWebviewEvent::DragDrop(e) => Self::DragDrop(e),
+      RuntimeWebviewEvent::FindResult(result) => Self::FindResult(result),
     }
   }
 }
@@ -446,6 +482,57 @@ impl<R: Runtime> AppHandle<R> {
     self.manager().plugins.lock().unwrap().unregister(plugin)
   }
 
+  /// Samples the memory and CPU usage of the processes rendering all webviews, keyed by webview label.
+  ///
+  /// Webviews that cannot be sampled, e.g. because they are being closed, are logged and left out of the map.
+  /// See [`Webview::process_metrics`] for more information.
+  pub fn all_webview_metrics(&self) -> HashMap<String, WebviewMetrics> {
+    self
+      .manager()
+      .webviews()
+      .into_iter()
+      .filter_map(|(label, webview)| match webview.process_metrics() {
+        Ok(metrics) => Some((label, metrics)),
+        Err(e) => {
+          log::warn!("failed to sample the metrics of webview `{label}`: {e}");
+          None
+        }
+      })
+      .collect()
+  }
+
+  /// Reads the accessibility and appearance preferences of the system.
+  ///
+  /// The `tauri://system-preferences-changed` event is emitted with the new preferences when they change.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Read from the GTK settings. High contrast is detected from the theme name
+  ///   and the accent color is not supported.
+  ///   WebKitGTK does not reflect the high contrast mode in the `prefers-contrast` media query,
+  ///   so the `tauri-reduced-motion`, `tauri-high-contrast` and `tauri-dark` classes are toggled on the document element instead.
+  /// - **Android / iOS**: Not supported, the default preferences are returned.
+  pub fn system_preferences(&self) -> crate::Result<SystemPreferences> {
+    system_preferences::query(self)
+  }
+
+  /// Plays a sound of the system, e.g. to notify the user that a background task finished.
+  ///
+  /// ## Platform-specific
+  ///
+  /// | Platform | Behavior |
+  /// |----------|----------|
+  /// | Windows  | Plays the sound configured for the matching system event with `MessageBeep`. |
+  /// | macOS    | Plays the alert sound selected in the system settings for all the sounds. |
+  /// | Linux    | Rings the display bell for all the sounds, which desktops usually map to their alert sound. |
+  /// | Android / iOS | Unsupported, no sound is played. |
+  pub fn play_system_sound(&self, sound: SystemSound) -> crate::Result<()> {
+    self
+      .runtime_handle
+      .play_system_sound(sound)
+      .map_err(Into::into)
+  }
+
   /// Exits the app by triggering [`RunEvent::ExitRequested`] and [`RunEvent::Exit`].
   pub fn exit(&self, exit_code: i32) {
     if let Err(e) = self.runtime_handle.request_exit(exit_code) {
@@ -588,6 +675,35 @@ macro_rules! shared_app_impl {
         self.manager.menu.on_menu_event(handler)
       }
 
+      /// Registers a global listener for the activation of the predefined menu items
+      /// whose behavior is implemented by this crate, see [`PredefinedMenuAction`](crate::menu::PredefinedMenuAction).
+      ///
+      /// The listeners run after the [`Self::on_menu_event`] listeners,
+      /// and the default action is skipped if any of them calls [`PredefinedMenuEvent::prevent_default`].
+      ///
+      /// # Examples
+      /// ```
+      /// tauri::Builder::default()
+      ///   .setup(|app| {
+      ///     app.on_predefined_menu_event(|app, event| {
+      ///       if event.action() == tauri::menu::PredefinedMenuAction::Quit {
+      ///         // save the app state before exiting
+      ///         event.prevent_default();
+      ///       }
+      ///     });
+      ///     Ok(())
+      ///   });
+      /// ```
+      #[cfg(desktop)]
+      pub fn on_predefined_menu_event<
+        F: Fn(&AppHandle<R>, &PredefinedMenuEvent) + Send + Sync + 'static,
+      >(
+        &self,
+        handler: F,
+      ) {
+        self.manager.menu.on_predefined_menu_event(handler)
+      }
+
       /// Registers a global tray icon menu event listener.
       #[cfg(all(desktop, feature = "tray-icon"))]
       #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
@@ -845,6 +961,9 @@ macro_rules! shared_app_impl {
       /// Runs necessary cleanup tasks before exiting the process.
       /// **You should always exit the tauri app immediately after this function returns and not use any tauri-related APIs.**
       pub fn cleanup_before_exit(&self) {
+        if let Some(emitter) = self.manager.metrics_emitter.lock().unwrap().take() {
+          emitter.stop();
+        }
         #[cfg(all(desktop, feature = "tray-icon"))]
         self.manager.tray.icons.lock().unwrap().clear();
         self.manager.resources_table().clear();
@@ -883,6 +1002,23 @@ macro_rules! shared_app_impl {
         self.manager.listen(event.into(), EventTarget::App, handler)
       }
 
+      /// Listen to an event on this app with the given priority.
+      ///
+      /// See [`Listener::listen_with_priority`] for more information.
+      fn listen_with_priority<F>(
+        &self,
+        event: impl Into<String>,
+        priority: i32,
+        handler: F,
+      ) -> EventId
+      where
+        F: Fn(Event) + Send + 'static,
+      {
+        self
+          .manager
+          .listen_with_priority(event.into(), EventTarget::App, priority, handler)
+      }
+
       /// Listen to an event on this app only once.
       ///
       /// See [`Self::listen`] for more information.
@@ -1172,6 +1308,10 @@ pub struct Builder<R: Runtime> {
   /// The JS message handler.
   invoke_handler: Box<InvokeHandler<R>>,
 
+  /// The state types requested by the commands of the JS message handler.
+  #[cfg(debug_assertions)]
+  command_states: Vec<CommandState>,
+
   /// The JS message responder.
   invoke_responder: Option<Arc<InvokeResponder<R>>>,
 
@@ -1197,6 +1337,10 @@ pub struct Builder<R: Runtime> {
   #[cfg(desktop)]
   menu: Option<Box<dyn FnOnce(&AppHandle<R>) -> crate::Result<Menu<R>> + Send>>,
 
+  /// Closures that return the menus referenced by the tray icons of the configuration.
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  tray_menus: Vec<Box<dyn FnOnce(&AppHandle<R>) -> crate::Result<Menu<R>> + Send>>,
+
   /// Enable macOS default menu creation.
   #[allow(unused)]
   enable_macos_default_menu: bool,
@@ -1211,6 +1355,9 @@ pub struct Builder<R: Runtime> {
   device_event_filter: DeviceEventFilter,
 
   pub(crate) invoke_key: String,
+
+  /// The command aliases and hidden commands.
+  command_router: CommandRouter,
 }
 
 #[derive(Template)]
@@ -1252,6 +1399,8 @@ impl<R: Runtime> Builder<R> {
       runtime_any_thread: false,
       setup: Box::new(|_| Ok(())),
       invoke_handler: Box::new(|_| false),
+      #[cfg(debug_assertions)]
+      command_states: Vec::new(),
       invoke_responder: None,
       invoke_initialization_script: InvokeInitializationScript {
         process_ipc_message_fn: crate::manager::webview::PROCESS_IPC_MESSAGE_FN,
@@ -1269,11 +1418,14 @@ impl<R: Runtime> Builder<R> {
       state: StateManager::new(),
       #[cfg(desktop)]
       menu: None,
+      #[cfg(all(desktop, feature = "tray-icon"))]
+      tray_menus: Vec::new(),
       enable_macos_default_menu: true,
       window_event_listeners: Vec::new(),
       webview_event_listeners: Vec::new(),
       device_event_filter: Default::default(),
       invoke_key,
+      command_router: Default::default(),
     }
   }
 }
@@ -1315,6 +1467,30 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Declares the managed state types requested by the commands of the invoke handler,
+  /// logging a warning at startup for each state that is not managed after the app setup in debug builds.
+  ///
+  /// # Examples
+  /// ```
+  /// #[tauri::command]
+  /// fn count(counter: tauri::State<'_, std::sync::atomic::AtomicUsize>) -> usize {
+  ///   counter.load(std::sync::atomic::Ordering::Relaxed)
+  /// }
+  /// tauri::Builder::default()
+  ///   .invoke_handler(tauri::generate_handler![count])
+  ///   .command_states(tauri::generate_command_states![count]);
+  /// ```
+  #[must_use]
+  pub fn command_states(mut self, command_states: Vec<CommandState>) -> Self {
+    #[cfg(debug_assertions)]
+    {
+      self.command_states = command_states;
+    }
+    #[cfg(not(debug_assertions))]
+    drop(command_states);
+    self
+  }
+
   /// Defines a custom JS message system.
   ///
   /// The `responder` is a function that will be called when a command has been executed and must send a response to the JS layer.
@@ -1331,6 +1507,45 @@ impl<R: Runtime> Builder<R> {
     self
   }
 
+  /// Exposes a command to the webviews under another name.
+  ///
+  /// The alias is resolved before the ACL is checked, so invoking it requires the permissions of the aliased command.
+  /// An alias keeps working when the aliased command is hidden with [`Self::hide_commands`].
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   // `invoke('public:save')` runs the `persist` command of the `storage` plugin
+  ///   .command_alias("public:save", "plugin:storage|persist");
+  /// ```
+  #[must_use]
+  pub fn command_alias(mut self, alias: impl Into<String>, command: impl Into<String>) -> Self {
+    self.command_router.alias(alias.into(), command.into());
+    self
+  }
+
+  /// Prevents the webviews from invoking the given commands.
+  ///
+  /// A hidden command is rejected with the same error as a command that does not exist.
+  /// Plugin commands are identified by their `plugin:<plugin-name>|<command-name>` name.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .hide_commands(["plugin:storage|debug_dump"]);
+  /// ```
+  #[must_use]
+  pub fn hide_commands<I, S>(mut self, commands: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    for command in commands {
+      self.command_router.hide(command.into());
+    }
+    self
+  }
+
   /// Defines the setup hook.
   ///
   /// # Examples
@@ -1535,6 +1750,33 @@ tauri::Builder::default()
     self
   }
 
+  /// Adds a menu that the tray icons of the configuration can show by setting their `menu` to its id.
+  ///
+  /// The menus are created before the tray icons, when the app is built.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::menu::{Menu, MenuItem};
+  ///
+  /// // tauri.conf.json:
+  /// // "trayIcons": [{ "id": "capture", "iconPath": "icons/capture.png", "menu": "capture-menu" }]
+  /// tauri::Builder::default()
+  ///   .tray_menu(|handle| Menu::with_id_and_items(handle, "capture-menu", &[
+  ///     &MenuItem::with_id(handle, "start", "Start capture", true, None::<&str>)?,
+  ///     &MenuItem::with_id(handle, "stop", "Stop capture", true, None::<&str>)?,
+  ///   ]));
+  /// ```
+  #[must_use]
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
+  pub fn tray_menu<F: FnOnce(&AppHandle<R>) -> crate::Result<Menu<R>> + Send + 'static>(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.tray_menus.push(Box::new(f));
+    self
+  }
+
   /// Enable or disable the default menu on macOS. Enabled by default.
   ///
   /// # Examples
@@ -1550,6 +1792,9 @@ tauri::Builder::default()
 
   /// Registers a window event handler for all windows.
   ///
+  /// Handlers accumulate and run in registration order, after the plugin handlers.
+  /// A handler can return [`EventAction::StopPropagation`] to skip the handlers registered after it.
+  ///
   /// # Examples
   /// ```
   /// tauri::Builder::default()
@@ -1564,16 +1809,24 @@ tauri::Builder::default()
   ///   });
   /// ```
   #[must_use]
-  pub fn on_window_event<F: Fn(&Window<R>, &WindowEvent) + Send + Sync + 'static>(
+  pub fn on_window_event<
+    F: Fn(&Window<R>, &WindowEvent) -> A + Send + Sync + 'static,
+    A: Into<EventAction>,
+  >(
     mut self,
     handler: F,
   ) -> Self {
-    self.window_event_listeners.push(Box::new(handler));
+    self
+      .window_event_listeners
+      .push(Box::new(move |window, event| handler(window, event).into()));
     self
   }
 
   /// Registers a webview event handler for all webviews.
   ///
+  /// Handlers accumulate and run in registration order, after the plugin handlers.
+  /// A handler can return [`EventAction::StopPropagation`] to skip the handlers registered after it.
+  ///
   /// # Examples
   /// ```
   /// tauri::Builder::default()
@@ -1585,11 +1838,18 @@ tauri::Builder::default()
   ///   });
   /// ```
   #[must_use]
-  pub fn on_webview_event<F: Fn(&Webview<R>, &WebviewEvent) + Send + Sync + 'static>(
+  pub fn on_webview_event<
+    F: Fn(&Webview<R>, &WebviewEvent) -> A + Send + Sync + 'static,
+    A: Into<EventAction>,
+  >(
     mut self,
     handler: F,
   ) -> Self {
-    self.webview_event_listeners.push(Box::new(handler));
+    self
+      .webview_event_listeners
+      .push(Box::new(move |webview, event| {
+        handler(webview, event).into()
+      }));
     self
   }
 
@@ -1645,6 +1905,8 @@ tauri::Builder::default()
   /// Similar to [`Self::register_uri_scheme_protocol`] but with an asynchronous responder that allows you
   /// to process the request in a separate thread and respond asynchronously.
   ///
+  /// Large bodies can be written in chunks with [`UriSchemeResponder::body_writer`] or [`UriSchemeResponder::respond_streaming`].
+  ///
   /// # Arguments
   ///
   /// * `uri_scheme` The URI scheme to register, such as `example`.
@@ -1742,8 +2004,16 @@ tauri::Builder::default()
       HashMap::new(),
       (self.invoke_responder, self.invoke_initialization_script),
       self.invoke_key,
+      self.command_router,
     ));
 
+    #[cfg(debug_assertions)]
+    manager
+      .command_states
+      .lock()
+      .unwrap()
+      .extend(self.command_states);
+
     let app_id = if manager.config.app.enable_gtk_app_id {
       Some(manager.config.identifier.clone())
     } else {
@@ -1876,16 +2146,27 @@ tauri::Builder::default()
 
     let handle = app.handle();
 
-    // initialize default tray icon if defined
+    // initialize the tray icons defined in the config
     #[cfg(all(desktop, feature = "tray-icon"))]
     {
+      let menus = self
+        .tray_menus
+        .into_iter()
+        .map(|menu| menu(handle))
+        .collect::<crate::Result<Vec<_>>>()?;
+
       let config = app.config();
-      if let Some(tray_config) = &config.app.tray_icon {
-        let mut tray =
-          TrayIconBuilder::with_id(tray_config.id.clone().unwrap_or_else(|| "main".into()))
-            .icon_as_template(tray_config.icon_as_template)
-            .menu_on_left_click(tray_config.menu_on_left_click);
-        if let Some(icon) = &app.manager.tray.icon {
+      for tray_config in config.app.all_tray_icons() {
+        let id = tray_config.id_or_default();
+        let mut tray = TrayIconBuilder::with_id(id)
+          .icon_as_template(tray_config.icon_as_template)
+          .menu_on_left_click(tray_config.menu_on_left_click);
+        // the `trayIcon` icon is stored apart from the `trayIcons` ones
+        let icon = match &config.app.tray_icon {
+          Some(main) if std::ptr::eq(main, tray_config) => app.manager.tray.icon.as_ref(),
+          _ => app.manager.tray.config_icons.get(id),
+        };
+        if let Some(icon) = icon {
           tray = tray.icon(icon.clone());
         }
         if let Some(title) = &tray_config.title {
@@ -1894,6 +2175,16 @@ tauri::Builder::default()
         if let Some(tooltip) = &tray_config.tooltip {
           tray = tray.tooltip(tooltip);
         }
+        if let Some(menu_id) = &tray_config.menu {
+          let menu = menus
+            .iter()
+            .find(|menu| menu.id().as_ref() == menu_id.as_str())
+            .ok_or_else(|| crate::Error::UnknownTrayMenu {
+              tray: id.into(),
+              menu: menu_id.clone(),
+            })?;
+          tray = tray.menu(menu);
+        }
         tray.build(handle)?;
       }
     }
@@ -1910,16 +2201,195 @@ tauri::Builder::default()
   }
 }
 
-pub(crate) type UriSchemeResponderFn = Box<dyn FnOnce(http::Response<Cow<'static, [u8]>>) + Send>;
+pub(crate) type UriSchemeResponderFn = tauri_runtime::webview::UriSchemeResponder;
+
+/// The number of chunks a [`UriSchemeBodyWriter`] holds before its writes wait for the runtime to read them.
+const BODY_WRITER_BUFFERED_CHUNKS: usize = 16;
 
 /// Async uri scheme protocol responder.
-pub struct UriSchemeResponder(pub(crate) UriSchemeResponderFn);
+pub struct UriSchemeResponder {
+  respond: UriSchemeResponderFn,
+  /// Set once the webview that made the request is closed.
+  webview_closed: Arc<AtomicBool>,
+}
 
 impl UriSchemeResponder {
+  pub(crate) fn new(respond: UriSchemeResponderFn, webview_closed: Arc<AtomicBool>) -> Self {
+    Self {
+      respond,
+      webview_closed,
+    }
+  }
+
   /// Resolves the request with the given response.
   pub fn respond<T: Into<Cow<'static, [u8]>>>(self, response: http::Response<T>) {
     let (parts, body) = response.into_parts();
-    (self.0)(http::Response::from_parts(parts, body.into()))
+    (self.respond)(http::Response::from_parts(
+      parts,
+      UriSchemeResponseBody::Full(body.into()),
+    ))
+  }
+
+  /// Resolves the request with the status and headers of the given response
+  /// and a body written in chunks with the returned [`UriSchemeBodyWriter`].
+  ///
+  /// The response is handed to the runtime right away and each chunk as it is written,
+  /// so the body is never held in memory as a whole by Tauri.
+  /// The writes fail once the request is cancelled or the webview that made the request is closed,
+  /// so the handler can stop generating the body.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **All**: The custom protocol APIs of wry take the complete body, so the wry runtime collects the chunks,
+  ///   up to 512 MiB, before handing the response to the webview. It cancels the request past that limit
+  ///   and once the webview is destroyed.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use std::io::Write;
+  ///
+  /// tauri::Builder::default()
+  ///   .register_asynchronous_uri_scheme_protocol("export", |_app, _request, responder| {
+  ///     std::thread::spawn(move || {
+  ///       let mut writer = responder.body_writer(
+  ///         http::Response::builder()
+  ///           .header(http::header::CONTENT_TYPE, "text/csv")
+  ///           .body(())
+  ///           .unwrap(),
+  ///       );
+  ///       for i in 0..1_000_000 {
+  ///         // the request was cancelled
+  ///         if writeln!(writer, "{i},{}", i * 2).is_err() {
+  ///           return;
+  ///         }
+  ///       }
+  ///       writer.finish();
+  ///     });
+  ///   });
+  /// ```
+  pub fn body_writer(self, response: http::Response<()>) -> UriSchemeBodyWriter {
+    let (parts, ()) = response.into_parts();
+    let (chunks, chunks_rx) = mpsc::sync_channel(BODY_WRITER_BUFFERED_CHUNKS);
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let body = UriSchemeBodyReader {
+      chunks: chunks_rx,
+      chunk: io::Cursor::new(Vec::new()),
+      cancelled: cancelled.clone(),
+    };
+    (self.respond)(http::Response::from_parts(
+      parts,
+      UriSchemeResponseBody::Stream(Box::new(body)),
+    ));
+    UriSchemeBodyWriter {
+      chunks: Some(chunks),
+      cancelled,
+      webview_closed: self.webview_closed,
+    }
+  }
+
+  /// Resolves the request with the given response, whose body is read
+  /// on a blocking thread of the async runtime and written to a [`UriSchemeBodyWriter`], see [`Self::body_writer`].
+  ///
+  /// The request fails if the body cannot be read, and the body is no longer read once the request is cancelled.
+  pub fn respond_streaming<B: Read + Send + 'static>(self, response: http::Response<B>) {
+    let (parts, mut body) = response.into_parts();
+    let mut writer = self.body_writer(http::Response::from_parts(parts, ()));
+    crate::async_runtime::spawn_blocking(move || match io::copy(&mut body, &mut writer) {
+      Ok(_) => writer.finish(),
+      Err(e) if writer.is_closed() => log::debug!("stopped streaming the response: {e}"),
+      Err(e) => log::error!("failed to read the response body: {e}"),
+    });
+  }
+}
+
+/// The body of a custom protocol response, see [`UriSchemeResponder::body_writer`].
+///
+/// Dropping the writer without calling [`Self::finish`] fails the request.
+pub struct UriSchemeBodyWriter {
+  chunks: Option<SyncSender<io::Result<Vec<u8>>>>,
+  /// Set once the runtime drops the body, cancelling the request.
+  cancelled: Arc<AtomicBool>,
+  /// Set once the webview that made the request is closed.
+  webview_closed: Arc<AtomicBool>,
+}
+
+impl UriSchemeBodyWriter {
+  /// Whether the request was cancelled or the webview that made the request was closed, in which case the writes fail.
+  pub fn is_closed(&self) -> bool {
+    self.cancelled.load(Ordering::Relaxed) || self.webview_closed.load(Ordering::Relaxed)
+  }
+
+  /// Ends the body, completing the request.
+  pub fn finish(mut self) {
+    self.chunks.take();
+  }
+}
+
+impl Write for UriSchemeBodyWriter {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    if self.webview_closed.load(Ordering::Relaxed) {
+      return Err(io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "the webview that made the request was closed",
+      ));
+    }
+    if buf.is_empty() {
+      return Ok(0);
+    }
+    match &self.chunks {
+      // waits for the runtime to read the previous chunks if the buffer is full
+      Some(chunks) if chunks.send(Ok(buf.to_vec())).is_ok() => Ok(buf.len()),
+      _ => Err(io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "the request was cancelled",
+      )),
+    }
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+impl Drop for UriSchemeBodyWriter {
+  fn drop(&mut self) {
+    if let Some(chunks) = self.chunks.take() {
+      let _ = chunks.send(Err(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "the response body writer was dropped before it was finished",
+      )));
+    }
+  }
+}
+
+/// The body handed to the runtime by a [`UriSchemeBodyWriter`], reading the chunks as they are written.
+struct UriSchemeBodyReader {
+  chunks: Receiver<io::Result<Vec<u8>>>,
+  /// The chunk being read.
+  chunk: io::Cursor<Vec<u8>>,
+  cancelled: Arc<AtomicBool>,
+}
+
+impl Read for UriSchemeBodyReader {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+      let read = self.chunk.read(buf)?;
+      if read > 0 || buf.is_empty() {
+        return Ok(read);
+      }
+      match self.chunks.recv() {
+        Ok(chunk) => self.chunk = io::Cursor::new(chunk?),
+        // the writer was finished
+        Err(_) => return Ok(0),
+      }
+    }
+  }
+}
+
+impl Drop for UriSchemeBodyReader {
+  fn drop(&mut self) {
+    self.cancelled.store(true, Ordering::Relaxed);
   }
 }
 
@@ -1961,6 +2431,13 @@ impl<R: Runtime> HasDisplayHandle for App<R> {
 fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
   app.ran_setup = true;
 
+  // read before creating the windows so their webviews start with the current preferences
+  #[cfg(desktop)]
+  {
+    *app.manager.system_preferences.lock().unwrap() = app.handle().system_preferences().ok();
+    system_preferences::spawn_watcher(app.handle().clone());
+  }
+
   let window_labels = app
     .config()
     .app
@@ -1983,10 +2460,26 @@ fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
 
   app.manager.assets.setup(app);
 
+  if let Some(interval) = app.config().app.webview_metrics_interval {
+    match crate::webview::metrics::spawn_emitter(
+      app.handle().clone(),
+      std::time::Duration::from_millis(interval),
+    ) {
+      Ok(emitter) => *app.manager.metrics_emitter.lock().unwrap() = Some(emitter),
+      Err(e) => log::error!("failed to spawn the webview metrics thread: {e}"),
+    }
+  }
+
   if let Some(setup) = app.setup.take() {
     (setup)(app).map_err(|e| crate::Error::Setup(e.into()))?;
   }
 
+  #[cfg(debug_assertions)]
+  crate::state::warn_unmanaged_states(
+    &app.manager.command_states.lock().unwrap(),
+    &app.manager.state,
+  );
+
   Ok(())
 }
 
@@ -2046,40 +2539,11 @@ fn on_event_loop_event<R: Runtime>(
       match t {
         #[cfg(desktop)]
         EventLoopMessage::MenuEvent(ref e) => {
-          for listener in &*app_handle
-            .manager
-            .menu
-            .global_event_listeners
-            .lock()
-            .unwrap()
-          {
-            listener(app_handle, e.clone());
-          }
-          for (label, listener) in &*app_handle.manager.menu.event_listeners.lock().unwrap() {
-            if let Some(w) = app_handle.manager().get_window(label) {
-              listener(&w, e.clone());
-            }
-          }
+          app_handle.manager.menu.dispatch_event(app_handle, e);
         }
         #[cfg(all(desktop, feature = "tray-icon"))]
         EventLoopMessage::TrayIconEvent(ref e) => {
-          for listener in &*app_handle
-            .manager
-            .tray
-            .global_event_listeners
-            .lock()
-            .unwrap()
-          {
-            listener(app_handle, e.clone());
-          }
-
-          for (id, listener) in &*app_handle.manager.tray.event_listeners.lock().unwrap() {
-            if e.id() == id {
-              if let Some(tray) = app_handle.tray_by_id(id) {
-                listener(&tray, e.clone());
-              }
-            }
-          }
+          app_handle.manager.tray.dispatch_event(app_handle, e);
         }
       }
 
@@ -2108,6 +2572,15 @@ fn on_event_loop_event<R: Runtime>(
 
 #[cfg(test)]
 mod tests {
+  use std::{
+    io::{Read, Write},
+    sync::{mpsc, Mutex},
+  };
+
+  use tauri_runtime::webview::UriSchemeResponseBody;
+
+  use crate::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+
   #[test]
   fn is_send_sync() {
     crate::test_utils::assert_send::<super::AppHandle>();
@@ -2119,4 +2592,133 @@ mod tests {
       crate::test_utils::assert_sync::<super::AssetResolver<crate::Wry>>();
     }
   }
+
+  /// Sends a request to the `export` protocol of a mock webview, handled by `handler`.
+  fn request_export(
+    handler: impl Fn(super::UriSchemeResponder) + Send + Sync + 'static,
+  ) -> (
+    super::App<MockRuntime>,
+    http::Response<UriSchemeResponseBody>,
+  ) {
+    let app = mock_builder()
+      .register_asynchronous_uri_scheme_protocol("export", move |_app, _request, responder| {
+        handler(responder)
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let response = webview
+      .webview
+      .webview
+      .dispatcher
+      .uri_scheme_request(
+        http::Request::get("export://localhost/data.csv")
+          .body(Vec::new())
+          .unwrap(),
+      )
+      .recv()
+      .unwrap();
+    (app, response)
+  }
+
+  /// Sends a request to the `export` protocol of a mock webview, whose body is written with a [`super::UriSchemeBodyWriter`].
+  fn request_export_writer() -> (
+    super::App<MockRuntime>,
+    http::Response<Box<dyn Read + Send>>,
+    super::UriSchemeBodyWriter,
+  ) {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let (app, response) = request_export(move |responder| {
+      let writer = responder.body_writer(
+        http::Response::builder()
+          .header(http::header::CONTENT_TYPE, "text/csv")
+          .body(())
+          .unwrap(),
+      );
+      tx.lock().unwrap().send(writer).unwrap();
+    });
+    let writer = rx.recv().unwrap();
+    (app, response.map(stream), writer)
+  }
+
+  fn stream(body: UriSchemeResponseBody) -> Box<dyn Read + Send> {
+    match body {
+      UriSchemeResponseBody::Stream(body) => body,
+      UriSchemeResponseBody::Full(_) => panic!("expected a streamed body"),
+    }
+  }
+
+  #[test]
+  fn responds_with_full_body() {
+    let (_app, response) = request_export(|responder| {
+      responder.respond(http::Response::new(b"1,2\n".to_vec()));
+    });
+    match response.body() {
+      UriSchemeResponseBody::Full(body) => assert_eq!(body.as_ref(), b"1,2\n"),
+      UriSchemeResponseBody::Stream(_) => panic!("expected a full body"),
+    }
+  }
+
+  #[test]
+  fn writes_response_body_in_chunks() {
+    let (_app, response, mut writer) = request_export_writer();
+    // the headers are received before the body is written
+    assert_eq!(response.status(), http::StatusCode::OK);
+    assert_eq!(response.headers()[http::header::CONTENT_TYPE], "text/csv");
+    let mut body = response.into_body();
+
+    writer.write_all(b"1,2\n").unwrap();
+    let mut chunk = [0; 4];
+    body.read_exact(&mut chunk).unwrap();
+    assert_eq!(&chunk, b"1,2\n");
+
+    writer.write_all(b"3,4\n").unwrap();
+    writer.write_all(b"5,6\n").unwrap();
+    writer.finish();
+    let mut rest = String::new();
+    body.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "3,4\n5,6\n");
+  }
+
+  #[test]
+  fn cancelled_request_closes_body_writer() {
+    let (_app, response, mut writer) = request_export_writer();
+    writer.write_all(b"1,2\n").unwrap();
+    assert!(!writer.is_closed());
+
+    drop(response);
+    assert!(writer.is_closed());
+    assert_eq!(
+      writer.write_all(b"3,4\n").unwrap_err().kind(),
+      std::io::ErrorKind::BrokenPipe
+    );
+  }
+
+  #[test]
+  fn dropped_body_writer_fails_request() {
+    let (_app, response, mut writer) = request_export_writer();
+    writer.write_all(b"partial").unwrap();
+    drop(writer);
+
+    let mut body = Vec::new();
+    let error = response.into_body().read_to_end(&mut body).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    assert_eq!(body, b"partial");
+  }
+
+  #[test]
+  fn streams_response_body() {
+    let data = vec![7; 1024 * 1024];
+    let data_ = data.clone();
+    let (_app, response) = request_export(move |responder| {
+      responder.respond_streaming(http::Response::new(std::io::Cursor::new(data_.clone())));
+    });
+
+    let mut body = Vec::new();
+    stream(response.into_body()).read_to_end(&mut body).unwrap();
+    assert_eq!(body, data);
+  }
 }
@@ -2,10 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use std::collections::HashMap;
+
 use crate::{
   command,
   plugin::{Builder, TauriPlugin},
-  AppHandle, Manager, ResourceId, Runtime, Webview,
+  webview::WebviewMetrics,
+  AppHandle, Manager, ResourceId, Runtime, SystemPreferences, SystemSound, Webview,
 };
 
 #[command(root = "crate")]
@@ -50,6 +53,24 @@ pub fn default_window_icon<R: Runtime>(
   })
 }
 
+#[command(root = "crate")]
+pub fn system_preferences<R: Runtime>(app: AppHandle<R>) -> crate::Result<SystemPreferences> {
+  app.system_preferences()
+}
+
+#[command(root = "crate")]
+pub fn all_webview_metrics<R: Runtime>(app: AppHandle<R>) -> HashMap<String, WebviewMetrics> {
+  app.all_webview_metrics()
+}
+
+#[command(root = "crate")]
+pub fn play_system_sound<R: Runtime>(
+  app: AppHandle<R>,
+  sound: Option<SystemSound>,
+) -> crate::Result<()> {
+  app.play_system_sound(sound.unwrap_or_default())
+}
+
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
   Builder::new("app")
     .invoke_handler(crate::generate_handler![
@@ -59,6 +80,9 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
       app_show,
       app_hide,
       default_window_icon,
+      all_webview_metrics,
+      system_preferences,
+      play_system_sound,
     ])
     .build()
 }
@@ -0,0 +1,459 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Accessibility and appearance preferences of the system.
+
+#[cfg(desktop)]
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{window::Color, AppHandle, Runtime, Theme};
+#[cfg(desktop)]
+use crate::{Emitter, Manager};
+
+/// The event emitted with the new [`SystemPreferences`] when they change.
+#[cfg(desktop)]
+pub(crate) const SYSTEM_PREFERENCES_CHANGED_EVENT: &str = "tauri://system-preferences-changed";
+
+/// How often the preferences are checked for changes.
+#[cfg(desktop)]
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Whether the webview engine does not reflect all the preferences in the CSS media queries,
+/// in which case [`initialization_script`] exposes them as classes on the document element.
+///
+/// WebKitGTK does not implement `prefers-contrast`.
+const NEEDS_BRIDGE: bool = cfg!(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+));
+
+/// Accessibility and appearance preferences of the system.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemPreferences {
+  /// Whether the user asked to minimize non-essential motion, e.g. by disabling animations.
+  pub reduced_motion: bool,
+  /// Whether a high contrast mode is enabled.
+  pub high_contrast: bool,
+  /// The system color scheme.
+  pub color_scheme: Theme,
+  /// The system accent color, if the platform has one.
+  pub accent_color: Option<Color>,
+}
+
+impl Default for SystemPreferences {
+  fn default() -> Self {
+    Self {
+      reduced_motion: false,
+      high_contrast: false,
+      color_scheme: Theme::Light,
+      accent_color: None,
+    }
+  }
+}
+
+/// Reads the preferences from the platform APIs.
+pub(crate) fn query<R: Runtime>(app: &AppHandle<R>) -> crate::Result<SystemPreferences> {
+  // the GTK settings can only be read on the main thread
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  if !gtk::is_initialized_main_thread() {
+    return crate::run_main_thread!(app, imp::system_preferences);
+  }
+
+  let _ = app;
+  Ok(imp::system_preferences())
+}
+
+/// Checks the preferences for changes, emitting the [`SYSTEM_PREFERENCES_CHANGED_EVENT`]
+/// and updating the document of the webviews using the bridge when they do.
+#[cfg(desktop)]
+pub(crate) fn spawn_watcher<R: Runtime>(app: AppHandle<R>) {
+  let spawned = std::thread::Builder::new()
+    .name("tauri-system-preferences".into())
+    .spawn(move || loop {
+      std::thread::sleep(POLL_INTERVAL);
+      let preferences = match app.system_preferences() {
+        Ok(preferences) => preferences,
+        Err(e) => {
+          log::error!("failed to read the system preferences: {e}");
+          continue;
+        }
+      };
+
+      let previous = app
+        .manager()
+        .system_preferences
+        .lock()
+        .unwrap()
+        .replace(preferences.clone());
+      if previous.as_ref() == Some(&preferences) {
+        continue;
+      }
+
+      if NEEDS_BRIDGE {
+        match apply_script(&preferences) {
+          Ok(script) => {
+            for webview in app.manager().webviews().values() {
+              if let Err(e) = webview.eval(&script) {
+                log::error!("failed to update the system preferences of the webview: {e}");
+              }
+            }
+          }
+          Err(e) => log::error!("failed to serialize the system preferences: {e}"),
+        }
+      }
+      if let Err(e) = app.emit(SYSTEM_PREFERENCES_CHANGED_EVENT, preferences) {
+        log::error!("failed to emit the system preferences change: {e}");
+      }
+    });
+  if let Err(e) = spawned {
+    log::error!("failed to spawn the system preferences thread: {e}");
+  }
+}
+
+/// The script exposing the preferences to the webview CSS, if the webview engine does not.
+///
+/// The `tauri-reduced-motion`, `tauri-high-contrast` and `tauri-dark` classes are toggled on the document element
+/// and the `--tauri-accent-color` custom property is set to the accent color.
+pub(crate) fn initialization_script(
+  preferences: &SystemPreferences,
+) -> crate::Result<Option<String>> {
+  bridge_script(NEEDS_BRIDGE, preferences)
+}
+
+fn bridge_script(
+  needs_bridge: bool,
+  preferences: &SystemPreferences,
+) -> crate::Result<Option<String>> {
+  if !needs_bridge {
+    return Ok(None);
+  }
+
+  let script = format!(
+    r#"
+      Object.defineProperty(window.__TAURI_INTERNALS__, 'applySystemPreferences', {{
+        value: function (preferences) {{
+          function apply() {{
+            var root = document.documentElement
+            root.classList.toggle('tauri-reduced-motion', preferences.reducedMotion)
+            root.classList.toggle('tauri-high-contrast', preferences.highContrast)
+            root.classList.toggle('tauri-dark', preferences.colorScheme === 'dark')
+            var color = preferences.accentColor
+            if (color) {{
+              root.style.setProperty(
+                '--tauri-accent-color',
+                'rgba(' + color[0] + ', ' + color[1] + ', ' + color[2] + ', ' + color[3] / 255 + ')'
+              )
+            }} else {{
+              root.style.removeProperty('--tauri-accent-color')
+            }}
+          }}
+
+          if (document.documentElement) {{
+            apply()
+          }} else {{
+            document.addEventListener('DOMContentLoaded', apply, {{ once: true }})
+          }}
+        }}
+      }})
+      {apply}
+    "#,
+    apply = apply_script(preferences)?
+  );
+  Ok(Some(script))
+}
+
+fn apply_script(preferences: &SystemPreferences) -> crate::Result<String> {
+  Ok(format!(
+    "window.__TAURI_INTERNALS__.applySystemPreferences({})",
+    serde_json::to_string(preferences)?
+  ))
+}
+
+/// GTK does not have an accessibility setting for high contrast, it is a theme instead.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+  test
+))]
+fn is_high_contrast_theme(theme_name: &str) -> bool {
+  let theme_name = theme_name.to_lowercase();
+  theme_name.contains("highcontrast") || theme_name.contains("high-contrast")
+}
+
+/// Converts a `0xAARRGGBB` DWM colorization color.
+#[cfg(any(windows, test))]
+fn argb_color(argb: u32) -> Color {
+  let [alpha, red, green, blue] = argb.to_be_bytes();
+  Color(red, green, blue, alpha)
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+mod imp {
+  use super::*;
+  use gtk::prelude::*;
+
+  pub fn system_preferences() -> SystemPreferences {
+    // not initialized when running with the mock runtime
+    let Some(settings) = gtk::is_initialized().then(gtk::Settings::default).flatten() else {
+      return SystemPreferences::default();
+    };
+
+    let theme_name = settings
+      .property::<Option<String>>("gtk-theme-name")
+      .unwrap_or_default();
+    let dark = settings.property::<bool>("gtk-application-prefer-dark-theme")
+      || theme_name.to_lowercase().ends_with("-dark")
+      || theme_name.to_lowercase().ends_with("inverse");
+
+    SystemPreferences {
+      reduced_motion: !settings.property::<bool>("gtk-enable-animations"),
+      high_contrast: is_high_contrast_theme(&theme_name),
+      color_scheme: if dark { Theme::Dark } else { Theme::Light },
+      accent_color: None,
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use super::*;
+  use cocoa::{
+    appkit::CGFloat,
+    base::{id, nil, BOOL, NO},
+    foundation::NSString,
+  };
+  use objc::{class, msg_send, sel, sel_impl};
+
+  pub fn system_preferences() -> SystemPreferences {
+    unsafe {
+      let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+      let reduced_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+      let high_contrast: BOOL = msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+
+      // `AppleInterfaceStyle` is only set in dark mode
+      let defaults: id = msg_send![class!(NSUserDefaults), standardUserDefaults];
+      let key = NSString::alloc(nil).init_str("AppleInterfaceStyle");
+      let style: id = msg_send![defaults, stringForKey: key];
+      let _: () = msg_send![key, release];
+      let dark = style != nil
+        && std::ffi::CStr::from_ptr(style.UTF8String())
+          .to_str()
+          .is_ok_and(|style| style.eq_ignore_ascii_case("dark"));
+
+      SystemPreferences {
+        reduced_motion: reduced_motion != NO,
+        high_contrast: high_contrast != NO,
+        color_scheme: if dark { Theme::Dark } else { Theme::Light },
+        accent_color: accent_color(),
+      }
+    }
+  }
+
+  unsafe fn accent_color() -> Option<Color> {
+    let color: id = msg_send![class!(NSColor), controlAccentColor];
+    let color_space: id = msg_send![class!(NSColorSpace), sRGBColorSpace];
+    let color: id = msg_send![color, colorUsingColorSpace: color_space];
+    if color == nil {
+      return None;
+    }
+
+    let component = |value: CGFloat| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let red: CGFloat = msg_send![color, redComponent];
+    let green: CGFloat = msg_send![color, greenComponent];
+    let blue: CGFloat = msg_send![color, blueComponent];
+    let alpha: CGFloat = msg_send![color, alphaComponent];
+    Some(Color(
+      component(red),
+      component(green),
+      component(blue),
+      component(alpha),
+    ))
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use super::*;
+  use windows::{
+    core::w,
+    Win32::{
+      Foundation::{BOOL, ERROR_SUCCESS},
+      Graphics::Dwm::DwmGetColorizationColor,
+      System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+      UI::{
+        Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+        WindowsAndMessaging::{
+          SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST,
+          SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+        },
+      },
+    },
+  };
+
+  pub fn system_preferences() -> SystemPreferences {
+    unsafe {
+      let mut animations = BOOL(1);
+      let animations = SystemParametersInfoW(
+        SPI_GETCLIENTAREAANIMATION,
+        0,
+        Some(&mut animations as *mut _ as _),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      )
+      .map(|_| animations.as_bool())
+      .unwrap_or(true);
+
+      let mut high_contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        ..Default::default()
+      };
+      let high_contrast = SystemParametersInfoW(
+        SPI_GETHIGHCONTRAST,
+        high_contrast.cbSize,
+        Some(&mut high_contrast as *mut _ as _),
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+      )
+      .map(|_| high_contrast.dwFlags.contains(HCF_HIGHCONTRASTON))
+      .unwrap_or_default();
+
+      // `AppsUseLightTheme` is `0` in dark mode
+      let mut light_theme = 1u32;
+      let mut size = std::mem::size_of::<u32>() as u32;
+      let dark = RegGetValueW(
+        HKEY_CURRENT_USER,
+        w!(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize"),
+        w!("AppsUseLightTheme"),
+        RRF_RT_REG_DWORD,
+        None,
+        Some(&mut light_theme as *mut _ as _),
+        Some(&mut size),
+      ) == ERROR_SUCCESS
+        && light_theme == 0;
+
+      let mut colorization = 0u32;
+      let mut opaque_blend = BOOL(0);
+      let accent_color = DwmGetColorizationColor(&mut colorization, &mut opaque_blend)
+        .ok()
+        .map(|_| argb_color(colorization));
+
+      SystemPreferences {
+        reduced_motion: !animations,
+        high_contrast,
+        color_scheme: if dark { Theme::Dark } else { Theme::Light },
+        accent_color,
+      }
+    }
+  }
+}
+
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+  target_os = "macos",
+  windows
+)))]
+mod imp {
+  use super::*;
+
+  pub fn system_preferences() -> SystemPreferences {
+    SystemPreferences::default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serialize_preferences() {
+    let preferences = SystemPreferences {
+      reduced_motion: true,
+      high_contrast: false,
+      color_scheme: Theme::Dark,
+      accent_color: Some(Color(0, 120, 215, 255)),
+    };
+    let json = serde_json::to_value(&preferences).unwrap();
+    assert_eq!(
+      json,
+      serde_json::json!({
+        "reducedMotion": true,
+        "highContrast": false,
+        "colorScheme": "dark",
+        "accentColor": [0, 120, 215, 255],
+      })
+    );
+    assert_eq!(
+      serde_json::from_value::<SystemPreferences>(json).unwrap(),
+      preferences
+    );
+
+    assert_eq!(
+      serde_json::to_value(SystemPreferences::default()).unwrap(),
+      serde_json::json!({
+        "reducedMotion": false,
+        "highContrast": false,
+        "colorScheme": "light",
+        "accentColor": null,
+      })
+    );
+  }
+
+  #[test]
+  fn platform_values() {
+    assert!(is_high_contrast_theme("HighContrast"));
+    assert!(is_high_contrast_theme("HighContrastInverse"));
+    assert!(is_high_contrast_theme("Adwaita-high-contrast"));
+    assert!(!is_high_contrast_theme("Adwaita-dark"));
+
+    assert_eq!(argb_color(0xC40078D7), Color(0x00, 0x78, 0xD7, 0xC4));
+  }
+
+  #[test]
+  fn bridge_is_only_injected_when_needed() {
+    let preferences = SystemPreferences {
+      high_contrast: true,
+      ..Default::default()
+    };
+    assert_eq!(bridge_script(false, &preferences).unwrap(), None);
+
+    let script = bridge_script(true, &preferences).unwrap().unwrap();
+    assert!(script.contains("'applySystemPreferences'"));
+    assert!(
+      script.contains("root.classList.toggle('tauri-high-contrast', preferences.highContrast)")
+    );
+    // the current preferences are applied right away
+    assert!(script
+      .trim_end()
+      .ends_with(&apply_script(&preferences).unwrap()));
+    assert!(script.contains(r#""highContrast":true"#));
+
+    assert_eq!(
+      initialization_script(&preferences).unwrap().is_some(),
+      NEEDS_BRIDGE
+    );
+  }
+}
@@ -113,6 +113,16 @@ pub enum Error {
   #[cfg(all(desktop, feature = "tray-icon"))]
   #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
   BadTrayIcon(#[from] tray_icon::BadIcon),
+  /// A tray icon of the configuration references a menu that was not added with [`crate::Builder::tray_menu`].
+  #[error("tray icon `{tray}` references the unknown menu `{menu}`, add it with `tauri::Builder::tray_menu`")]
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
+  UnknownTrayMenu {
+    /// The tray icon id.
+    tray: String,
+    /// The menu id.
+    menu: String,
+  },
   /// Path does not have a parent.
   #[error("path does not have a parent")]
   NoParent,
@@ -129,6 +139,10 @@ pub enum Error {
   #[cfg(not(target_os = "android"))]
   #[error("unknown path")]
   UnknownPath,
+  /// The requested resource is not declared in the bundle resources.
+  #[cfg(dev)]
+  #[error("resource `{0}` is not declared in `bundle > resources`")]
+  UndeclaredResource(std::path::PathBuf),
   /// Failed to invoke mobile plugin.
   #[cfg(target_os = "android")]
   #[error(transparent)]
@@ -160,6 +174,19 @@ pub enum Error {
   /// Bad `__TAURI_INVOKE_KEY__` value received in ipc message.
   #[error("bad __TAURI_INVOKE_KEY__ value received in ipc message")]
   InvokeKey,
+  /// Failed to sample the metrics of a process.
+  #[error("failed to sample process metrics: {0}")]
+  ProcessMetrics(std::io::Error),
+  /// A command requested a [`State`](crate::State) whose type is not managed.
+  #[error("state not managed for field `{key}` on command `{command}`: `{type_name}`, did you forget to call `.manage()`?")]
+  StateNotManaged {
+    /// The command name.
+    command: String,
+    /// The argument key.
+    key: String,
+    /// The name of the state type.
+    type_name: &'static str,
+  },
 }
 
 impl From<getrandom::Error> for Error {
@@ -8,8 +8,8 @@ use super::{EmitArgs, Event, EventId, EventTarget};
 
 use std::{
   boxed::Box,
-  cell::Cell,
-  collections::{HashMap, HashSet},
+  cmp::Reverse,
+  collections::{BTreeMap, HashMap, HashSet},
   sync::{
     atomic::{AtomicU32, Ordering},
     Arc, Mutex,
@@ -30,13 +30,15 @@ enum Pending {
 /// Stored in [`Listeners`] to be called upon, when the event that stored it, is triggered.
 struct Handler {
   target: EventTarget,
+  priority: i32,
   callback: Box<dyn Fn(Event) + Send>,
 }
 
 impl Handler {
-  fn new<F: Fn(Event) + Send + 'static>(target: EventTarget, callback: F) -> Self {
+  fn new<F: Fn(Event) + Send + 'static>(target: EventTarget, priority: i32, callback: F) -> Self {
     Self {
       target,
+      priority,
       callback: Box::new(callback),
     }
   }
@@ -60,7 +62,10 @@ type EventName = String;
 /// Holds event handlers and pending event handlers, along with the salts associating them.
 struct InnerListeners {
   pending: Mutex<Vec<Pending>>,
-  handlers: Mutex<HashMap<EventName, HashMap<EventId, Handler>>>,
+  /// Handlers of each event, ordered by their id which is also their registration order.
+  handlers: Mutex<HashMap<EventName, BTreeMap<EventId, Handler>>>,
+  /// Number of handlers of each event, readable while the handlers are being called.
+  handler_counts: Mutex<HashMap<EventName, usize>>,
   js_event_listeners: Mutex<HashMap<WebviewLabel, HashMap<EventName, HashSet<JsHandler>>>>,
   function_name: &'static str,
   listeners_object_name: &'static str,
@@ -79,6 +84,7 @@ impl Default for Listeners {
       inner: Arc::new(InnerListeners {
         pending: Mutex::default(),
         handlers: Mutex::default(),
+        handler_counts: Mutex::default(),
         js_event_listeners: Mutex::default(),
         function_name: "__internal_unstable_listeners_function_id__",
         listeners_object_name: "__internal_unstable_listeners_object_id__",
@@ -141,6 +147,13 @@ impl Listeners {
     match self.inner.handlers.try_lock() {
       Err(_) => self.insert_pending(Pending::Listen { id, event, handler }),
       Ok(mut lock) => {
+        *self
+          .inner
+          .handler_counts
+          .lock()
+          .unwrap()
+          .entry(event.clone())
+          .or_default() += 1;
         lock.entry(event).or_default().insert(id, handler);
       }
     }
@@ -152,9 +165,22 @@ impl Listeners {
     event: String,
     target: EventTarget,
     handler: F,
+  ) -> EventId {
+    self.listen_with_priority(event, target, 0, handler)
+  }
+
+  /// Adds an event listener called before the listeners with a lower priority.
+  ///
+  /// Listeners with the same priority are called in registration order.
+  pub(crate) fn listen_with_priority<F: Fn(Event) + Send + 'static>(
+    &self,
+    event: String,
+    target: EventTarget,
+    priority: i32,
+    handler: F,
   ) -> EventId {
     let id = self.next_event_id();
-    let handler = Handler::new(target, handler);
+    let handler = Handler::new(target, priority, handler);
     self.listen_with_id(id, event, handler);
     id
   }
@@ -167,15 +193,17 @@ impl Listeners {
     handler: F,
   ) -> EventId {
     let self_ = self.clone();
-    let handler = Cell::new(Some(handler));
+    // an emit queued while the handlers were locked can reach this listener
+    // before its pending removal is applied, so the handler is taken atomically
+    let handler = Mutex::new(Some(handler));
 
     self.listen(event, target, move |event| {
       let id = event.id;
-      let handler = handler
-        .take()
-        .expect("attempted to call handler more than once");
-      handler(event);
-      self_.unlisten(id);
+      let handler = handler.lock().unwrap().take();
+      if let Some(handler) = handler {
+        handler(event);
+        self_.unlisten(id);
+      }
     })
   }
 
@@ -183,12 +211,37 @@ impl Listeners {
   pub(crate) fn unlisten(&self, id: EventId) {
     match self.inner.handlers.try_lock() {
       Err(_) => self.insert_pending(Pending::Unlisten(id)),
-      Ok(mut lock) => lock.values_mut().for_each(|handler| {
-        handler.remove(&id);
-      }),
+      Ok(mut lock) => {
+        let mut counts = self.inner.handler_counts.lock().unwrap();
+        lock.retain(|event, handlers| {
+          if handlers.remove(&id).is_some() {
+            if let Some(count) = counts.get_mut(event) {
+              *count -= 1;
+              if *count == 0 {
+                counts.remove(event);
+              }
+            }
+          }
+          !handlers.is_empty()
+        });
+      }
     }
   }
 
+  /// Returns the number of listeners registered for the given event.
+  ///
+  /// Listeners added or removed while the event handlers are running are only accounted once they are applied.
+  pub(crate) fn listeners_count(&self, event: &str) -> usize {
+    self
+      .inner
+      .handler_counts
+      .lock()
+      .unwrap()
+      .get(event)
+      .copied()
+      .unwrap_or_default()
+  }
+
   /// Emits the given event with its payload based on a filter.
   pub(crate) fn emit_filter<F>(&self, emit_args: EmitArgs, filter: Option<F>) -> crate::Result<()>
   where
@@ -200,8 +253,12 @@ impl Listeners {
       Err(_) => self.insert_pending(Pending::Emit(emit_args)),
       Ok(lock) => {
         if let Some(handlers) = lock.get(&emit_args.event_name) {
-          let handlers = handlers.iter();
-          let handlers = handlers.filter(|(_, h)| match_any_or_filter(&h.target, &filter));
+          let mut handlers = handlers
+            .iter()
+            .filter(|(_, h)| match_any_or_filter(&h.target, &filter))
+            .collect::<Vec<_>>();
+          // stable sort: handlers with the same priority keep their registration order
+          handlers.sort_by_key(|(_, h)| Reverse(h.priority));
           for (&id, Handler { callback, .. }) in handlers {
             maybe_pending = true;
             (callback)(Event::new(id, emit_args.payload.clone()))
@@ -396,4 +453,99 @@ mod test {
       assert!(l.contains_key(&key));
     }
   }
+
+  fn emit_args(event: &str) -> EmitArgs {
+    EmitArgs {
+      event_name: event.into(),
+      event: serde_json::to_string(event).unwrap(),
+      payload: "null".into(),
+    }
+  }
+
+  #[test]
+  fn listeners_called_by_priority() {
+    let listeners: Listeners = Default::default();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    for (name, priority) in [
+      ("default-1", 0),
+      ("audit-1", 10),
+      ("late", -5),
+      ("audit-2", 10),
+      ("default-2", 0),
+    ] {
+      let calls = calls.clone();
+      listeners.listen_with_priority("event".into(), EventTarget::Any, priority, move |_| {
+        calls.lock().unwrap().push(name)
+      });
+    }
+    let calls_ = calls.clone();
+    listeners.listen("event".into(), EventTarget::Any, move |_| {
+      calls_.lock().unwrap().push("default-3")
+    });
+
+    listeners.emit(emit_args("event")).unwrap();
+    listeners.emit(emit_args("event")).unwrap();
+
+    let expected = [
+      "audit-1",
+      "audit-2",
+      "default-1",
+      "default-2",
+      "default-3",
+      "late",
+    ];
+    assert_eq!(*calls.lock().unwrap(), [expected, expected].concat());
+  }
+
+  #[test]
+  fn once_fires_once_with_concurrent_emits() {
+    for _ in 0..50 {
+      let listeners: Listeners = Default::default();
+      let calls = Arc::new(AtomicU32::new(0));
+
+      let calls_ = calls.clone();
+      listeners.once("event".into(), EventTarget::Any, move |_| {
+        calls_.fetch_add(1, Ordering::SeqCst);
+      });
+
+      let threads = (0..8)
+        .map(|_| {
+          let listeners = listeners.clone();
+          std::thread::spawn(move || {
+            for _ in 0..100 {
+              listeners.emit(emit_args("event")).unwrap();
+            }
+          })
+        })
+        .collect::<Vec<_>>();
+      for thread in threads {
+        thread.join().unwrap();
+      }
+      listeners.emit(emit_args("event")).unwrap();
+
+      assert_eq!(calls.load(Ordering::SeqCst), 1);
+      assert_eq!(listeners.listeners_count("event"), 0);
+    }
+  }
+
+  #[test]
+  fn listeners_count() {
+    let listeners: Listeners = Default::default();
+    assert_eq!(listeners.listeners_count("event"), 0);
+
+    let id = listeners.listen("event".into(), EventTarget::Any, event_fn);
+    listeners.listen_with_priority("event".into(), EventTarget::App, 1, event_fn);
+    listeners.once("event".into(), EventTarget::Any, event_fn);
+    listeners.listen("other".into(), EventTarget::Any, event_fn);
+    assert_eq!(listeners.listeners_count("event"), 3);
+    assert_eq!(listeners.listeners_count("other"), 1);
+
+    // the `once` listener removes itself
+    listeners.emit(emit_args("event")).unwrap();
+    assert_eq!(listeners.listeners_count("event"), 2);
+
+    listeners.unlisten(id);
+    assert_eq!(listeners.listeners_count("event"), 1);
+  }
 }
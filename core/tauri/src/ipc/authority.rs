@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display};
 use std::sync::Arc;
 
@@ -356,31 +356,6 @@ impl RuntimeAuthority {
         .join(" || ")
     }
 
-    fn has_permissions_allowing_command(
-      manifest: &crate::utils::acl::manifest::Manifest,
-      set: &crate::utils::acl::PermissionSet,
-      command: &str,
-    ) -> bool {
-      for permission_id in &set.permissions {
-        if permission_id == "default" {
-          if let Some(default) = &manifest.default_permission {
-            if has_permissions_allowing_command(manifest, default, command) {
-              return true;
-            }
-          }
-        } else if let Some(ref_set) = manifest.permission_sets.get(permission_id) {
-          if has_permissions_allowing_command(manifest, ref_set, command) {
-            return true;
-          }
-        } else if let Some(permission) = manifest.permissions.get(permission_id) {
-          if permission.commands.allow.contains(&command.into()) {
-            return true;
-          }
-        }
-      }
-      false
-    }
-
     let command = if key == APP_ACL_KEY {
       command_name.to_string()
     } else {
@@ -426,46 +401,7 @@ impl RuntimeAuthority {
           )
         }
       } else {
-        let permission_error_detail = if let Some(manifest) = self
-          .acl
-          .get(key)
-          .or_else(|| self.acl.get(&format!("core:{key}")))
-        {
-          let mut permissions_referencing_command = Vec::new();
-
-          if let Some(default) = &manifest.default_permission {
-            if has_permissions_allowing_command(manifest, default, command_name) {
-              permissions_referencing_command.push("default".into());
-            }
-          }
-          for set in manifest.permission_sets.values() {
-            if has_permissions_allowing_command(manifest, set, command_name) {
-              permissions_referencing_command.push(set.identifier.clone());
-            }
-          }
-          for permission in manifest.permissions.values() {
-            if permission.commands.allow.contains(&command_name.into()) {
-              permissions_referencing_command.push(permission.identifier.clone());
-            }
-          }
-
-          permissions_referencing_command.sort();
-
-          format!(
-            "Permissions associated with this command: {}",
-            permissions_referencing_command
-              .iter()
-              .map(|p| if key == APP_ACL_KEY {
-                p.to_string()
-              } else {
-                format!("{key}:{p}")
-              })
-              .collect::<Vec<_>>()
-              .join(", ")
-          )
-        } else {
-          "Plugin did not define its manifest".to_string()
-        };
+        let permission_error_detail = self.permission_error_detail(key, command_name, true);
 
         if let Some(resolved_cmds) = command_matches {
           format!(
@@ -494,6 +430,93 @@ impl RuntimeAuthority {
     }
   }
 
+  /// The access message of a command that must be indistinguishable from an unknown command.
+  #[cfg(debug_assertions)]
+  pub(crate) fn unknown_command_access_message(&self, key: &str, command_name: &str) -> String {
+    let command_pretty_name = if key == APP_ACL_KEY {
+      command_name.to_string()
+    } else {
+      format!("{key}.{command_name}")
+    };
+
+    format!(
+      "{command_pretty_name} not allowed. {}",
+      self.permission_error_detail(key, command_name, false)
+    )
+  }
+
+  #[cfg(debug_assertions)]
+  fn permission_error_detail(&self, key: &str, command_name: &str, referenced: bool) -> String {
+    fn has_permissions_allowing_command(
+      manifest: &crate::utils::acl::manifest::Manifest,
+      set: &crate::utils::acl::PermissionSet,
+      command: &str,
+    ) -> bool {
+      for permission_id in &set.permissions {
+        if permission_id == "default" {
+          if let Some(default) = &manifest.default_permission {
+            if has_permissions_allowing_command(manifest, default, command) {
+              return true;
+            }
+          }
+        } else if let Some(ref_set) = manifest.permission_sets.get(permission_id) {
+          if has_permissions_allowing_command(manifest, ref_set, command) {
+            return true;
+          }
+        } else if let Some(permission) = manifest.permissions.get(permission_id) {
+          if permission.commands.allow.contains(&command.into()) {
+            return true;
+          }
+        }
+      }
+      false
+    }
+
+    if let Some(manifest) = self
+      .acl
+      .get(key)
+      .or_else(|| self.acl.get(&format!("core:{key}")))
+    {
+      let mut permissions_referencing_command = Vec::new();
+
+      // commands hidden from the webviews must not reveal the permissions referencing them
+      if referenced {
+        if let Some(default) = &manifest.default_permission {
+          if has_permissions_allowing_command(manifest, default, command_name) {
+            permissions_referencing_command.push("default".into());
+          }
+        }
+        for set in manifest.permission_sets.values() {
+          if has_permissions_allowing_command(manifest, set, command_name) {
+            permissions_referencing_command.push(set.identifier.clone());
+          }
+        }
+        for permission in manifest.permissions.values() {
+          if permission.commands.allow.contains(&command_name.into()) {
+            permissions_referencing_command.push(permission.identifier.clone());
+          }
+        }
+      }
+
+      permissions_referencing_command.sort();
+
+      format!(
+        "Permissions associated with this command: {}",
+        permissions_referencing_command
+          .iter()
+          .map(|p| if key == APP_ACL_KEY {
+            p.to_string()
+          } else {
+            format!("{key}:{p}")
+          })
+          .collect::<Vec<_>>()
+          .join(", ")
+      )
+    } else {
+      "Plugin did not define its manifest".to_string()
+    }
+  }
+
   /// Checks if the given IPC execution is allowed and returns the [`ResolvedCommand`] if it is.
   pub fn resolve_access(
     &self,
@@ -528,6 +551,25 @@ impl RuntimeAuthority {
       })
     }
   }
+
+  /// The commands allowed on the given window and webview for the given origin, see [`Self::resolve_access`].
+  pub(crate) fn allowed_commands_for(
+    &self,
+    window: &str,
+    webview: &str,
+    origin: &Origin,
+  ) -> BTreeSet<String> {
+    self
+      .allowed_commands
+      .keys()
+      .filter(|command| {
+        self
+          .resolve_access(command, window, webview, origin)
+          .is_some()
+      })
+      .cloned()
+      .collect()
+  }
 }
 
 /// List of allowed and denied objects that match either the command-specific or plugin global scope criteria.
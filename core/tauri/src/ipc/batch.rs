@@ -0,0 +1,396 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::sync::oneshot;
+
+use super::{
+  channel::IPC_PAYLOAD_PREFIX, CallbackFn, InvokeBody, InvokeError, InvokeResolver, InvokeResponse,
+};
+use crate::{webview::InvokeRequest, Runtime, Webview};
+
+/// The command that executes several commands in a single IPC round trip,
+/// used by `invokeBatch` from `@tauri-apps/api/core`.
+///
+/// It is not checked against the ACL, each of its commands is instead.
+pub(crate) const INVOKE_BATCH_COMMAND: &str = "__TAURI_INVOKE_BATCH__";
+
+#[derive(Deserialize)]
+struct Batch {
+  entries: Vec<BatchEntry>,
+  /// Whether the commands are executed concurrently instead of one after the other.
+  #[serde(default)]
+  concurrent: bool,
+}
+
+#[derive(Deserialize)]
+struct BatchEntry {
+  cmd: String,
+  #[serde(default = "default_args")]
+  args: JsonValue,
+}
+
+fn default_args() -> JsonValue {
+  JsonValue::Object(Default::default())
+}
+
+/// The outcome of a batched command.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+enum BatchResult {
+  Ok { data: JsonValue },
+  Error { error: JsonValue },
+}
+
+impl BatchResult {
+  fn error(error: impl Into<String>) -> Self {
+    Self::Error {
+      error: JsonValue::String(error.into()),
+    }
+  }
+}
+
+impl From<InvokeResponse> for BatchResult {
+  fn from(response: InvokeResponse) -> Self {
+    match response {
+      InvokeResponse::Ok(InvokeBody::Json(data)) => Self::Ok { data },
+      InvokeResponse::Ok(InvokeBody::Raw(_)) => {
+        Self::error("raw binary responses cannot be batched")
+      }
+      InvokeResponse::Err(InvokeError(error)) => Self::Error { error },
+    }
+  }
+}
+
+/// Whether the value references a [`Channel`](super::Channel), which cannot be batched.
+fn has_channel(value: &JsonValue) -> bool {
+  match value {
+    JsonValue::String(s) => s.starts_with(IPC_PAYLOAD_PREFIX),
+    JsonValue::Array(values) => values.iter().any(has_channel),
+    JsonValue::Object(map) => map.values().any(has_channel),
+    _ => false,
+  }
+}
+
+/// Executes the commands of an [`INVOKE_BATCH_COMMAND`] request,
+/// resolving it with the result of each command, in order.
+///
+/// A failing command does not fail the batch.
+pub(crate) fn invoke_batch<R: Runtime>(
+  webview: Webview<R>,
+  mut request: InvokeRequest,
+  resolver: InvokeResolver<R>,
+) {
+  let batch: Batch = match std::mem::take(&mut request.body) {
+    InvokeBody::Json(payload) => match serde_json::from_value(payload) {
+      Ok(batch) => batch,
+      Err(e) => {
+        resolver.reject(format!("invalid batch: {e}"));
+        return;
+      }
+    },
+    InvokeBody::Raw(_) => {
+      resolver.reject("raw binary requests cannot be batched");
+      return;
+    }
+  };
+
+  crate::async_runtime::spawn(async move {
+    let mut results = Vec::with_capacity(batch.entries.len());
+    if batch.concurrent {
+      let receivers = batch
+        .entries
+        .into_iter()
+        .map(|entry| dispatch(&webview, &request, entry))
+        .collect::<Vec<_>>();
+      for receiver in receivers {
+        results.push(receive(receiver).await);
+      }
+    } else {
+      for entry in batch.entries {
+        results.push(receive(dispatch(&webview, &request, entry)).await);
+      }
+    }
+    resolver.resolve(results);
+  });
+}
+
+/// Executes the command of a batch entry, returning a receiver for its result.
+fn dispatch<R: Runtime>(
+  webview: &Webview<R>,
+  request: &InvokeRequest,
+  entry: BatchEntry,
+) -> oneshot::Receiver<BatchResult> {
+  let (tx, rx) = oneshot::channel();
+
+  if entry.cmd == INVOKE_BATCH_COMMAND {
+    let _ = tx.send(BatchResult::error("batches cannot be nested"));
+  } else if has_channel(&entry.args) {
+    let _ = tx.send(BatchResult::error(format!(
+      "Command {} cannot be batched because its arguments include a channel",
+      entry.cmd
+    )));
+  } else {
+    let resolver = InvokeResolver::new(
+      webview.clone(),
+      Arc::new(Mutex::new(Some(Box::new(
+        move |_webview: Webview<R>,
+              _cmd: String,
+              response: InvokeResponse,
+              _callback: CallbackFn,
+              _error: CallbackFn| {
+          let _ = tx.send(response.into());
+        },
+      )))),
+      entry.cmd.clone(),
+      request.callback,
+      request.error,
+    );
+    webview.clone().handle_invoke(
+      InvokeRequest {
+        cmd: entry.cmd,
+        callback: request.callback,
+        error: request.error,
+        url: request.url.clone(),
+        body: InvokeBody::Json(entry.args),
+        // commands reading the request headers behave the same as when they are invoked directly
+        headers: request.headers.clone(),
+        invoke_key: request.invoke_key.clone(),
+      },
+      resolver,
+    );
+  }
+
+  rx
+}
+
+async fn receive(receiver: oneshot::Receiver<BatchResult>) -> BatchResult {
+  receiver
+    .await
+    .unwrap_or_else(|_| BatchResult::error("the command did not respond"))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use serde_json::{json, Value as JsonValue};
+  use tauri_utils::acl::ExecutionContext;
+  use tokio::sync::Notify;
+
+  use super::INVOKE_BATCH_COMMAND;
+  use crate::{
+    command,
+    ipc::{CallbackFn, InvokeBody, Request, Response},
+    test::{get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime, INVOKE_KEY},
+    webview::InvokeRequest,
+    App, State, WebviewWindow, WebviewWindowBuilder,
+  };
+
+  #[command(root = "crate")]
+  fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+  }
+
+  #[command(root = "crate")]
+  fn fail() -> Result<(), String> {
+    Err("failed".into())
+  }
+
+  #[command(root = "crate")]
+  fn raw() -> Response {
+    Response::new(vec![1, 2, 3])
+  }
+
+  #[command(root = "crate")]
+  async fn wait(notify: State<'_, Notify>) -> Result<(), String> {
+    tokio::time::timeout(Duration::from_millis(500), notify.notified())
+      .await
+      .map_err(|_| "timed out".to_string())
+  }
+
+  #[command(root = "crate")]
+  fn notify(notify: State<'_, Notify>) {
+    notify.notify_one();
+  }
+
+  #[command(root = "crate")]
+  fn authorization(request: Request<'_>) -> Option<String> {
+    request
+      .headers()
+      .get("Authorization")
+      .and_then(|value| value.to_str().ok())
+      .map(ToString::to_string)
+  }
+
+  #[command(root = "crate")]
+  fn persist() -> &'static str {
+    "persisted"
+  }
+
+  #[command(root = "crate")]
+  fn debug_dump() -> &'static str {
+    "dumped"
+  }
+
+  /// Builds an app with a `storage` plugin, where only its `persist` command is allowed.
+  fn app() -> (App<MockRuntime>, WebviewWindow<MockRuntime>) {
+    let mut context = mock_context(noop_assets());
+    context
+      .runtime_authority_mut()
+      .__allow_command("plugin:storage|persist".into(), ExecutionContext::Local);
+
+    let app = mock_builder()
+      .manage(Notify::new())
+      .invoke_handler(crate::generate_handler![
+        greet,
+        fail,
+        raw,
+        wait,
+        notify,
+        authorization
+      ])
+      .plugin(
+        crate::plugin::Builder::new("storage")
+          .invoke_handler(crate::generate_handler![persist, debug_dump])
+          .build(),
+      )
+      .build(context)
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    (app, webview)
+  }
+
+  fn invoke_batch(webview: &WebviewWindow<MockRuntime>, batch: JsonValue) -> Vec<JsonValue> {
+    invoke_batch_with_headers(webview, batch, Default::default())
+  }
+
+  fn invoke_batch_with_headers(
+    webview: &WebviewWindow<MockRuntime>,
+    batch: JsonValue,
+    headers: http::HeaderMap,
+  ) -> Vec<JsonValue> {
+    get_ipc_response(
+      webview,
+      InvokeRequest {
+        cmd: INVOKE_BATCH_COMMAND.into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        url: "http://tauri.localhost".parse().unwrap(),
+        body: InvokeBody::Json(batch),
+        headers,
+        invoke_key: INVOKE_KEY.to_string(),
+      },
+    )
+    .unwrap()
+    .deserialize()
+    .unwrap()
+  }
+
+  #[test]
+  fn mixed_success_and_failure() {
+    let (_app, webview) = app();
+    let results = invoke_batch(
+      &webview,
+      json!({
+        "entries": [
+          { "cmd": "greet", "args": { "name": "Tauri" } },
+          { "cmd": "fail" },
+          { "cmd": "plugin:storage|persist" },
+          { "cmd": "plugin:storage|debug_dump" },
+          { "cmd": "unknown" },
+          { "cmd": "raw" },
+          { "cmd": "greet", "args": { "name": "__CHANNEL__:1" } },
+          { "cmd": INVOKE_BATCH_COMMAND, "args": { "entries": [] } },
+        ]
+      }),
+    );
+
+    assert_eq!(results.len(), 8);
+    assert_eq!(
+      results[0],
+      json!({ "status": "ok", "data": "Hello, Tauri!" })
+    );
+    assert_eq!(results[1], json!({ "status": "error", "error": "failed" }));
+    assert_eq!(results[2], json!({ "status": "ok", "data": "persisted" }));
+    // each command is checked against the ACL
+    assert_eq!(results[3]["status"], "error");
+    assert_eq!(
+      results[4],
+      json!({ "status": "error", "error": "Command unknown not found" })
+    );
+    assert_eq!(
+      results[5],
+      json!({ "status": "error", "error": "raw binary responses cannot be batched" })
+    );
+    assert_eq!(
+      results[6],
+      json!({
+        "status": "error",
+        "error": "Command greet cannot be batched because its arguments include a channel"
+      })
+    );
+    assert_eq!(
+      results[7],
+      json!({ "status": "error", "error": "batches cannot be nested" })
+    );
+  }
+
+  #[test]
+  fn forwards_request_headers() {
+    let (_app, webview) = app();
+    let mut headers = http::HeaderMap::new();
+    headers.insert("Authorization", "Bearer token".parse().unwrap());
+    let results = invoke_batch_with_headers(
+      &webview,
+      json!({ "entries": [{ "cmd": "authorization" }, { "cmd": "authorization" }], "concurrent": true }),
+      headers,
+    );
+    assert_eq!(
+      results,
+      vec![
+        json!({ "status": "ok", "data": "Bearer token" }),
+        json!({ "status": "ok", "data": "Bearer token" }),
+      ]
+    );
+  }
+
+  #[test]
+  fn sequential_batch() {
+    let (_app, webview) = app();
+    // `wait` only completes once `notify` runs
+    let results = invoke_batch(
+      &webview,
+      json!({ "entries": [{ "cmd": "wait" }, { "cmd": "notify" }] }),
+    );
+    assert_eq!(
+      results,
+      vec![
+        json!({ "status": "error", "error": "timed out" }),
+        json!({ "status": "ok", "data": null }),
+      ]
+    );
+  }
+
+  #[test]
+  fn concurrent_batch() {
+    let (_app, webview) = app();
+    let results = invoke_batch(
+      &webview,
+      json!({ "entries": [{ "cmd": "wait" }, { "cmd": "notify" }], "concurrent": true }),
+    );
+    assert_eq!(
+      results,
+      vec![
+        json!({ "status": "ok", "data": null }),
+        json!({ "status": "ok", "data": null }),
+      ]
+    );
+  }
+}
@@ -177,6 +177,34 @@ impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
   pass!(deserialize_ignored_any, visitor: V);
 }
 
+/// A [`State`](crate::State) argument of a command, generated by [`generate_command_states!`](crate::generate_command_states)
+/// to warn about the state types that are not managed at startup in debug builds.
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+pub struct CommandState {
+  pub(crate) plugin: Option<&'static str>,
+  pub(crate) command: &'static str,
+  pub(crate) key: &'static str,
+  pub(crate) type_name: &'static str,
+  pub(crate) is_managed: fn(&crate::StateManager) -> bool,
+}
+
+impl CommandState {
+  #[doc(hidden)]
+  pub fn new<T: Send + Sync + 'static>(
+    plugin: Option<&'static str>,
+    command: &'static str,
+    key: &'static str,
+  ) -> Self {
+    Self {
+      plugin,
+      command,
+      key,
+      type_name: std::any::type_name::<T>(),
+      is_managed: |state| state.try_get::<T>().is_some(),
+    }
+  }
+}
+
 /// [Autoref-based stable specialization](https://github.com/dtolnay/case-studies/blob/master/autoref-specialization/README.md)
 ///
 /// Nothing in this module is considered stable.
@@ -22,17 +22,19 @@ use tauri_utils::acl::resolved::ResolvedCommand;
 use crate::{webview::Webview, Runtime, StateManager};
 
 mod authority;
+pub(crate) mod batch;
 pub(crate) mod channel;
 mod command;
 pub(crate) mod format_callback;
 pub(crate) mod protocol;
+pub(crate) mod router;
 
 pub use authority::{
   CapabilityBuilder, CommandScope, GlobalScope, Origin, RuntimeAuthority, RuntimeCapability,
   ScopeObject, ScopeValue,
 };
 pub use channel::{Channel, JavaScriptChannelId};
-pub use command::{private, CommandArg, CommandItem};
+pub use command::{private, CommandArg, CommandItem, CommandState};
 
 /// A closure that is run every time Tauri receives a message it doesn't explicitly handle.
 pub type InvokeHandler<R> = dyn Fn(Invoke<R>) -> bool + Send + Sync + 'static;
@@ -96,6 +98,15 @@ impl InvokeBody {
       InvokeBody::Raw(v) => T::deserialize(v.into_deserializer()),
     }
   }
+
+  /// The size in bytes of the serialized body, recorded by the IPC spans instead of the body itself.
+  #[cfg(feature = "tracing")]
+  pub(crate) fn size(&self) -> usize {
+    match self {
+      Self::Json(v) => serde_json::to_vec(v).map(|v| v.len()).unwrap_or_default(),
+      Self::Raw(v) => v.len(),
+    }
+  }
 }
 
 /// The IPC request.
@@ -218,6 +229,17 @@ pub enum InvokeResponse {
   Err(InvokeError),
 }
 
+impl InvokeResponse {
+  /// The outcome of the response recorded by the IPC spans.
+  #[cfg(feature = "tracing")]
+  pub(crate) fn outcome(&self) -> &'static str {
+    match self {
+      Self::Ok(_) => "ok",
+      Self::Err(_) => "error",
+    }
+  }
+}
+
 impl Serialize for InvokeResponse {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
@@ -234,6 +256,9 @@ impl Serialize for InvokeResponse {
 impl<T: IpcResponse, E: Into<InvokeError>> From<Result<T, E>> for InvokeResponse {
   #[inline]
   fn from(result: Result<T, E>) -> Self {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("ipc::request::serialize").entered();
+
     match result {
       Ok(ok) => match ok.body() {
         Ok(value) => Self::Ok(value),
@@ -30,10 +30,11 @@ pub fn message_handler<R: Runtime>(
 pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeProtocolHandler {
   Box::new(move |request, responder| {
     #[cfg(feature = "tracing")]
-    let span = tracing::trace_span!(
+    let _span = tracing::trace_span!(
       "ipc::request",
       kind = "custom-protocol",
-      request = tracing::field::Empty
+      webview = label,
+      payload_size = request.body().len()
     )
     .entered();
 
@@ -52,14 +53,6 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
         if let Some(webview) = manager.get_webview(&label) {
           match parse_invoke_request(&manager, request) {
             Ok(request) => {
-              #[cfg(feature = "tracing")]
-              span.record(
-                "request",
-                match &request.body {
-                  InvokeBody::Json(j) => serde_json::to_string(j).unwrap(),
-                  InvokeBody::Raw(b) => serde_json::to_string(b).unwrap(),
-                },
-              );
               #[cfg(feature = "tracing")]
               let request_span = tracing::trace_span!("ipc::request::handle", cmd = request.cmd);
 
@@ -76,7 +69,7 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
                   #[cfg(feature = "tracing")]
                   let response_span = tracing::trace_span!(
                     "ipc::request::response",
-                    response = serde_json::to_string(&response).unwrap(),
+                    outcome = response.outcome(),
                     mime_type = tracing::field::Empty
                   )
                   .entered();
@@ -167,8 +160,8 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
     let _span = tracing::trace_span!(
       "ipc::request",
       kind = "post-message",
-      uri = request.uri().to_string(),
-      request = request.body()
+      webview = label,
+      payload_size = request.body().len()
     )
     .entered();
 
@@ -313,7 +306,7 @@ fn handle_ipc_message<R: Runtime>(request: Request<String>, manager: &AppManager
               #[cfg(feature = "tracing")]
               let _response_span = tracing::trace_span!(
                 "ipc::request::response",
-                response = serde_json::to_string(&response).unwrap(),
+                outcome = response.outcome(),
                 mime_type = match &response {
                   InvokeResponse::Ok(InvokeBody::Json(_)) => mime::APPLICATION_JSON,
                   InvokeResponse::Ok(InvokeBody::Raw(_)) => mime::APPLICATION_OCTET_STREAM,
@@ -518,6 +511,7 @@ mod tests {
       Default::default(),
       (None, "".into()),
       crate::generate_invoke_key().unwrap(),
+      Default::default(),
     );
 
     let cmd = "write_something";
@@ -631,6 +625,7 @@ mod tests {
       Default::default(),
       (None, "".into()),
       crate::generate_invoke_key().unwrap(),
+      Default::default(),
     );
 
     let cmd = "write_something";
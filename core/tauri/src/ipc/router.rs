@@ -0,0 +1,235 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Remaps and hides the commands invoked by the webviews before they are authorized and executed.
+///
+/// See [`crate::Builder::command_alias`] and [`crate::Builder::hide_commands`].
+#[derive(Debug, Default)]
+pub(crate) struct CommandRouter {
+  aliases: HashMap<String, String>,
+  hidden: HashSet<String>,
+}
+
+/// The command to execute for an invoked command.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Route<'a> {
+  /// The command is executed as is.
+  Direct(&'a str),
+  /// The command is an alias of the given command.
+  Alias(&'a str),
+  /// The command is hidden and must behave as an unknown command.
+  Hidden,
+}
+
+impl CommandRouter {
+  pub(crate) fn alias(&mut self, alias: String, command: String) {
+    self.aliases.insert(alias, command);
+  }
+
+  pub(crate) fn hide(&mut self, command: String) {
+    self.hidden.insert(command);
+  }
+
+  /// Resolves the command invoked by a webview.
+  ///
+  /// Aliases take precedence over hidden commands.
+  pub(crate) fn route<'a>(&'a self, command: &'a str) -> Route<'a> {
+    if let Some(target) = self.aliases.get(command) {
+      Route::Alias(target)
+    } else if self.hidden.contains(command) {
+      Route::Hidden
+    } else {
+      Route::Direct(command)
+    }
+  }
+
+  /// Lists the `allowed` commands the way the webviews invoke them.
+  ///
+  /// Aliased commands are listed under their aliases instead of their own name, and hidden commands are left out.
+  pub(crate) fn visible_commands(&self, allowed: &BTreeSet<String>) -> Vec<String> {
+    let aliased = self.aliases.values().collect::<HashSet<_>>();
+    let mut commands = allowed
+      .iter()
+      .filter(|command| {
+        matches!(self.route(command), Route::Direct(_)) && !aliased.contains(command)
+      })
+      .chain(
+        self
+          .aliases
+          .iter()
+          .filter(|(_, command)| allowed.contains(*command))
+          .map(|(alias, _)| alias),
+      )
+      .cloned()
+      .collect::<Vec<_>>();
+    commands.sort();
+    commands
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{CommandRouter, Route};
+  use crate::{
+    command,
+    ipc::{CallbackFn, InvokeBody},
+    test::{get_ipc_response, mock_builder, mock_context, noop_assets, MockRuntime, INVOKE_KEY},
+    webview::InvokeRequest,
+    App, WebviewWindow, WebviewWindowBuilder,
+  };
+  use serde_json::Value as JsonValue;
+  use tauri_utils::acl::ExecutionContext;
+
+  #[command(root = "crate")]
+  fn persist() -> &'static str {
+    "persisted"
+  }
+
+  #[command(root = "crate")]
+  fn debug_dump() -> &'static str {
+    "dumped"
+  }
+
+  #[command(root = "crate")]
+  fn secret() -> &'static str {
+    "secret"
+  }
+
+  /// Builds an app with a `storage` plugin and a `secret` app command,
+  /// where the given commands are allowed by the ACL.
+  fn app(allowed_commands: &[&str]) -> (App<MockRuntime>, WebviewWindow<MockRuntime>) {
+    let mut context = mock_context(noop_assets());
+    for command in allowed_commands {
+      context
+        .runtime_authority_mut()
+        .__allow_command(command.to_string(), ExecutionContext::Local);
+    }
+
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![secret])
+      .plugin(
+        crate::plugin::Builder::new("storage")
+          .invoke_handler(crate::generate_handler![persist, debug_dump])
+          .build(),
+      )
+      .command_alias("public:save", "plugin:storage|persist")
+      .hide_commands(["plugin:storage|debug_dump", "secret"])
+      .build(context)
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    (app, webview)
+  }
+
+  fn invoke(webview: &WebviewWindow<MockRuntime>, cmd: &str) -> Result<JsonValue, JsonValue> {
+    get_ipc_response(
+      webview,
+      InvokeRequest {
+        cmd: cmd.into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        url: "http://tauri.localhost".parse().unwrap(),
+        body: InvokeBody::default(),
+        headers: Default::default(),
+        invoke_key: INVOKE_KEY.to_string(),
+      },
+    )
+    .map(|body| body.deserialize().unwrap())
+  }
+
+  #[test]
+  fn allowed_commands_list_aliases_instead_of_internal_commands() {
+    let (_app, webview) = app(&[
+      "plugin:storage|persist",
+      "plugin:storage|debug_dump",
+      "plugin:storage|load",
+      "secret",
+      // allowing the alias itself does not allow the aliased command
+      "public:save",
+    ]);
+    assert_eq!(
+      webview.as_ref().allowed_commands().unwrap(),
+      ["plugin:storage|load", "public:save"]
+    );
+
+    let (_app, webview) = app(&["plugin:storage|debug_dump", "public:save"]);
+    assert!(webview.as_ref().allowed_commands().unwrap().is_empty());
+  }
+
+  #[test]
+  fn alias_runs_aliased_command() {
+    let (_app, webview) = app(&["plugin:storage|persist"]);
+    assert_eq!(invoke(&webview, "public:save"), Ok("persisted".into()));
+    assert_eq!(
+      invoke(&webview, "plugin:storage|persist"),
+      Ok("persisted".into())
+    );
+  }
+
+  #[test]
+  fn alias_requires_aliased_command_permissions() {
+    // allowing the alias itself does not grant access to the aliased command
+    let (_app, webview) = app(&["public:save"]);
+    let error = invoke(&webview, "public:save").unwrap_err();
+    let error = error.as_str().unwrap();
+    assert!(error.contains("public:save"), "{error}");
+    assert!(!error.contains("storage"), "{error}");
+    assert!(!error.contains("persist"), "{error}");
+  }
+
+  #[test]
+  fn hidden_command_is_indistinguishable_from_unknown_command() {
+    let (_app, webview) = app(&["plugin:storage|debug_dump"]);
+
+    let hidden = invoke(&webview, "plugin:storage|debug_dump").unwrap_err();
+    let unknown = invoke(&webview, "plugin:storage|not_a_command").unwrap_err();
+    assert_eq!(
+      hidden
+        .as_str()
+        .unwrap()
+        .replace("debug_dump", "not_a_command"),
+      unknown.as_str().unwrap()
+    );
+
+    assert_eq!(
+      invoke(&webview, "secret"),
+      Err("Command secret not found".into())
+    );
+    assert_eq!(
+      invoke(&webview, "not_a_command"),
+      Err("Command not_a_command not found".into())
+    );
+  }
+
+  #[test]
+  fn routes_commands() {
+    let mut router = CommandRouter::default();
+    router.alias("public:save".into(), "plugin:storage|persist".into());
+    router.hide("plugin:storage|persist".into());
+    router.hide("plugin:storage|debug_dump".into());
+
+    assert_eq!(
+      router.route("public:save"),
+      Route::Alias("plugin:storage|persist")
+    );
+    assert_eq!(router.route("plugin:storage|persist"), Route::Hidden);
+    assert_eq!(router.route("plugin:storage|debug_dump"), Route::Hidden);
+    assert_eq!(
+      router.route("plugin:storage|load"),
+      Route::Direct("plugin:storage|load")
+    );
+  }
+
+  #[test]
+  fn aliases_take_precedence_over_hidden_commands() {
+    let mut router = CommandRouter::default();
+    router.hide("ping".into());
+    router.alias("ping".into(), "pong".into());
+
+    assert_eq!(router.route("ping"), Route::Alias("pong"));
+  }
+}
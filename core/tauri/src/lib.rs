@@ -17,6 +17,7 @@
 //! - **unstable**: Enables unstable features. Be careful, it might introduce breaking changes in future minor releases.
 //! - **tracing**: Enables [`tracing`](https://docs.rs/tracing/latest/tracing) for window startup, plugins, `Window::eval`, events, IPC, updater and custom protocol request handlers.
 //! - **test**: Enables the [`mod@test`] module exposing unit test helpers.
+//! - **test-webview**: Enables [`test::WebviewHarness`] which drives a real webview in integration tests. Implies **test** and **wry**.
 //! - **objc-exception**: Wrap each msg_send! in a @try/@catch and panics if an exception is caught, preventing Objective-C from unwinding into Rust.
 //! - **linux-ipc-protocol**: Use custom protocol for faster IPC on Linux. Requires webkit2gtk v2.40 or above.
 //! - **linux-libxdo**: Enables linking to libxdo which enables Cut, Copy, Paste and SelectAll menu items to work on Linux.
@@ -78,7 +79,7 @@ pub use swift_rs;
 pub use tauri_macros::include_image;
 #[cfg(mobile)]
 pub use tauri_macros::mobile_entry_point;
-pub use tauri_macros::{command, generate_handler};
+pub use tauri_macros::{command, generate_command_states, generate_handler};
 
 pub use url::Url;
 
@@ -216,15 +217,15 @@ pub use self::utils::TitleBarStyle;
 pub use self::event::{Event, EventId, EventTarget};
 pub use {
   self::app::{
-    App, AppHandle, AssetResolver, Builder, CloseRequestApi, RunEvent, UriSchemeResponder,
-    WebviewEvent, WindowEvent,
+    App, AppHandle, AssetResolver, Builder, CloseRequestApi, EventAction, RunEvent,
+    SystemPreferences, UriSchemeBodyWriter, UriSchemeResponder, WebviewEvent, WindowEvent,
   },
   self::manager::Asset,
   self::runtime::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Pixel, Position, Size},
     webview::WebviewAttributes,
     window::{CursorIcon, DragDropEvent, WindowSizeConstraints},
-    DeviceEventFilter, Rect, UserAttentionType,
+    DeviceEventFilter, Rect, SystemSound, UserAttentionType,
   },
   self::state::{State, StateManager},
   self::utils::{
@@ -309,7 +310,24 @@ impl<R: runtime::RuntimeHandle<EventLoopMessage>> RuntimeHandle for R {}
 ///
 /// You may pass a string literal to this macro to specify a custom path for the Tauri config file.
 /// If the path is relative, it will be search for relative to the Cargo manifest of the compiling
-/// crate.
+/// crate. The path can also be set with the `config_path = "..."` option.
+///
+/// # Integration Tests
+///
+/// The `assets = test` option serves a blank `index.html` instead of embedding the `frontendDist`,
+/// so the context can be generated from a test crate and used with `tauri::test::mock_builder`:
+///
+/// ```rust,ignore
+/// let app = tauri::test::mock_builder()
+///   .build(tauri::generate_context!(
+///     config_path = "../src-tauri/tauri.conf.json",
+///     assets = test
+///   ))
+///   .unwrap();
+/// ```
+///
+/// Without a build script, the icons are cached to a directory shared by all crates,
+/// so multiple test crates using the same icons only process them once.
 ///
 /// # Note
 ///
@@ -382,12 +400,16 @@ pub struct Context<R: Runtime> {
   pub(crate) config: Config,
   #[cfg(dev)]
   pub(crate) config_parent: Option<std::path::PathBuf>,
+  #[cfg(dev)]
+  pub(crate) dev_resources: Option<HashMap<std::path::PathBuf, std::path::PathBuf>>,
   /// Asset provider.
   pub assets: Box<dyn Assets<R>>,
   pub(crate) default_window_icon: Option<image::Image<'static>>,
   pub(crate) app_icon: Option<Vec<u8>>,
   #[cfg(all(desktop, feature = "tray-icon"))]
   pub(crate) tray_icon: Option<image::Image<'static>>,
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  pub(crate) tray_icons: HashMap<String, image::Image<'static>>,
   pub(crate) package_info: PackageInfo,
   pub(crate) _info_plist: (),
   pub(crate) pattern: Pattern,
@@ -406,7 +428,8 @@ impl<R: Runtime> fmt::Debug for Context<R> {
       .field("plugin_global_api_scripts", &self.plugin_global_api_scripts);
 
     #[cfg(all(desktop, feature = "tray-icon"))]
-    d.field("tray_icon", &self.tray_icon);
+    d.field("tray_icon", &self.tray_icon)
+      .field("tray_icons", &self.tray_icons);
 
     d.finish()
   }
@@ -465,6 +488,22 @@ impl<R: Runtime> Context<R> {
     self.tray_icon = icon;
   }
 
+  /// The icons of the tray icons defined in `app > trayIcons`, keyed by tray icon id.
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
+  #[inline(always)]
+  pub fn tray_icons(&self) -> &HashMap<String, image::Image<'static>> {
+    &self.tray_icons
+  }
+
+  /// Set the icons of the tray icons defined in `app > trayIcons`, keyed by tray icon id.
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
+  #[inline(always)]
+  pub fn set_tray_icons(&mut self, icons: HashMap<String, image::Image<'static>>) {
+    self.tray_icons = icons;
+  }
+
   /// Package information.
   #[inline(always)]
   pub fn package_info(&self) -> &PackageInfo {
@@ -512,11 +551,15 @@ impl<R: Runtime> Context<R> {
       config,
       #[cfg(dev)]
       config_parent: None,
+      #[cfg(dev)]
+      dev_resources: None,
       assets,
       default_window_icon,
       app_icon,
       #[cfg(all(desktop, feature = "tray-icon"))]
       tray_icon: None,
+      #[cfg(all(desktop, feature = "tray-icon"))]
+      tray_icons: HashMap::new(),
       package_info,
       _info_plist: info_plist,
       pattern,
@@ -532,6 +575,17 @@ impl<R: Runtime> Context<R> {
       .config_parent
       .replace(config_parent.as_ref().to_owned());
   }
+
+  #[cfg(dev)]
+  #[doc(hidden)]
+  pub fn with_dev_resources(&mut self, resources: &[(&str, &str)]) {
+    self.dev_resources.replace(
+      resources
+        .iter()
+        .map(|(target, src)| (target.into(), src.into()))
+        .collect(),
+    );
+  }
 }
 
 // TODO: expand these docs
@@ -801,8 +855,35 @@ pub trait Listener<R: Runtime>: sealed::ManagerBase<R> {
   where
     F: Fn(Event) + Send + 'static;
 
+  /// Listen to an emitted event on this manager with the given priority.
+  ///
+  /// Listeners with a higher priority are called first, [`Self::listen`] registers listeners with priority `0`.
+  /// Listeners with the same priority are called in the order they were registered.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::Listener;
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     // validate the payload before any other listener runs
+  ///     app.listen_with_priority("order-placed", 100, |event| {
+  ///       println!("auditing {}", event.payload());
+  ///     });
+  ///     app.listen("order-placed", |event| {
+  ///       println!("order placed");
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  fn listen_with_priority<F>(&self, event: impl Into<String>, priority: i32, handler: F) -> EventId
+  where
+    F: Fn(Event) + Send + 'static;
+
   /// Listen to an event on this manager only once.
   ///
+  /// The handler is called at most once, even if the event is emitted concurrently from multiple threads.
+  ///
   /// See [`Self::listen`] for more information.
   fn once<F>(&self, event: impl Into<String>, handler: F) -> EventId
   where
@@ -821,7 +902,7 @@ pub trait Listener<R: Runtime>: sealed::ManagerBase<R> {
   ///       println!("app is ready");
   ///
   ///       // we no longer need to listen to the event
-  ///       // we also could have used `app.once_global` instead
+  ///       // we also could have used `app.once_any` instead
   ///       handle.unlisten(event.id());
   ///     });
   ///
@@ -873,6 +954,13 @@ pub trait Listener<R: Runtime>: sealed::ManagerBase<R> {
   {
     self.manager().once(event.into(), EventTarget::Any, handler)
   }
+
+  /// Returns the number of Rust listeners registered for the given event, regardless of their target.
+  ///
+  /// Useful for diagnostics, e.g. detecting listeners that are never removed.
+  fn listeners_count(&self, event: &str) -> usize {
+    self.manager().listeners_count(event)
+  }
 }
 
 /// Emit events.
@@ -1081,6 +1169,99 @@ mod test_utils {
     }
   }
 
+  /// A span recorded by the [`CapturingSubscriber`].
+  #[cfg(feature = "tracing")]
+  #[derive(Debug)]
+  pub struct CapturedSpan {
+    pub name: &'static str,
+    /// Index of the parent span in the captured spans.
+    pub parent: Option<usize>,
+    pub fields: std::collections::BTreeMap<&'static str, String>,
+  }
+
+  #[cfg(feature = "tracing")]
+  struct FieldVisitor<'a>(&'a mut std::collections::BTreeMap<&'static str, String>);
+
+  #[cfg(feature = "tracing")]
+  impl tracing::field::Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+      self.0.insert(field.name(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+      self.0.insert(field.name(), format!("{value:?}"));
+    }
+  }
+
+  /// A [`tracing::Subscriber`] recording every span with its parent and fields.
+  #[cfg(feature = "tracing")]
+  #[derive(Clone, Default)]
+  pub struct CapturingSubscriber {
+    pub spans: std::sync::Arc<std::sync::Mutex<Vec<CapturedSpan>>>,
+    stack: std::sync::Arc<std::sync::Mutex<Vec<usize>>>,
+  }
+
+  #[cfg(feature = "tracing")]
+  impl tracing::Subscriber for CapturingSubscriber {
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+      true
+    }
+
+    fn new_span(&self, attrs: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+      let mut fields = Default::default();
+      attrs.record(&mut FieldVisitor(&mut fields));
+      let parent = match attrs.parent() {
+        Some(parent) => Some(parent.into_u64() as usize - 1),
+        None if attrs.is_contextual() => self.stack.lock().unwrap().last().copied(),
+        None => None,
+      };
+
+      let mut spans = self.spans.lock().unwrap();
+      spans.push(CapturedSpan {
+        name: attrs.metadata().name(),
+        parent,
+        fields,
+      });
+      tracing::span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+      let mut spans = self.spans.lock().unwrap();
+      values.record(&mut FieldVisitor(
+        &mut spans[span.into_u64() as usize - 1].fields,
+      ));
+    }
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, _event: &tracing::Event<'_>) {}
+
+    fn enter(&self, span: &tracing::span::Id) {
+      self
+        .stack
+        .lock()
+        .unwrap()
+        .push(span.into_u64() as usize - 1);
+    }
+
+    fn exit(&self, _span: &tracing::span::Id) {
+      self.stack.lock().unwrap().pop();
+    }
+  }
+
+  #[cfg(feature = "tracing")]
+  impl CapturingSubscriber {
+    /// Finds the index of the first captured span with the given name and parent.
+    pub fn find(&self, name: &str, parent: Option<usize>) -> Option<usize> {
+      self
+        .spans
+        .lock()
+        .unwrap()
+        .iter()
+        .position(|span| span.name == name && span.parent == parent)
+    }
+  }
+
   proptest! {
     #![proptest_config(ProptestConfig::with_cases(10000))]
     #[test]
@@ -8,7 +8,8 @@ use std::{
 };
 
 use crate::{
-  menu::{Menu, MenuEvent, MenuId},
+  app::{GlobalMenuEventListener, PredefinedMenuEventListener},
+  menu::{Menu, MenuEvent, MenuId, PredefinedMenuAction, PredefinedMenuEvent},
   AppHandle, Runtime, Window,
 };
 
@@ -22,9 +23,13 @@ pub struct MenuManager<R: Runtime> {
   /// The menu set to all windows.
   pub menu: Mutex<Option<Menu<R>>>,
   /// Menu event listeners to all windows.
-  pub global_event_listeners: Mutex<Vec<crate::app::GlobalMenuEventListener<AppHandle<R>>>>,
+  pub global_event_listeners: Mutex<Vec<GlobalMenuEventListener<AppHandle<R>>>>,
   /// Menu event listeners to specific windows.
-  pub event_listeners: Mutex<HashMap<String, crate::app::GlobalMenuEventListener<Window<R>>>>,
+  pub event_listeners: Mutex<HashMap<String, GlobalMenuEventListener<Window<R>>>>,
+  /// The actions of the predefined menu items implemented by this crate, keyed by menu item id.
+  pub(crate) predefined_actions: Mutex<HashMap<MenuId, PredefinedMenuAction>>,
+  /// Listeners that can prevent the predefined menu actions.
+  pub(crate) predefined_event_listeners: Mutex<Vec<PredefinedMenuEventListener<AppHandle<R>>>>,
 }
 
 impl<R: Runtime> MenuManager<R> {
@@ -101,4 +106,152 @@ impl<R: Runtime> MenuManager<R> {
       .unwrap()
       .push(Box::new(handler));
   }
+
+  pub fn on_predefined_menu_event<
+    F: Fn(&AppHandle<R>, &PredefinedMenuEvent) + Send + Sync + 'static,
+  >(
+    &self,
+    handler: F,
+  ) {
+    self
+      .predefined_event_listeners
+      .lock()
+      .unwrap()
+      .push(Box::new(handler));
+  }
+
+  pub(crate) fn set_predefined_action(&self, id: &MenuId, action: PredefinedMenuAction) {
+    self
+      .predefined_actions
+      .lock()
+      .unwrap()
+      .insert(id.clone(), action);
+  }
+
+  pub(crate) fn predefined_action(&self, id: &MenuId) -> Option<PredefinedMenuAction> {
+    self.predefined_actions.lock().unwrap().get(id).copied()
+  }
+
+  /// Runs the global listeners and the window listeners,
+  /// then the action of the predefined menu item that triggered the event unless a listener prevents it.
+  pub(crate) fn dispatch_event(&self, app_handle: &AppHandle<R>, event: &MenuEvent) {
+    for listener in &*self.global_event_listeners.lock().unwrap() {
+      listener(app_handle, event.clone());
+    }
+    for (label, listener) in &*self.event_listeners.lock().unwrap() {
+      if let Some(w) = app_handle.manager.get_window(label) {
+        listener(&w, event.clone());
+      }
+    }
+
+    let Some(action) = self.predefined_action(event.id()) else {
+      return;
+    };
+    let predefined_event = PredefinedMenuEvent::new(event.id().clone(), action);
+    for listener in &*self.predefined_event_listeners.lock().unwrap() {
+      listener(app_handle, &predefined_event);
+    }
+    if predefined_event.is_default_prevented() {
+      return;
+    }
+
+    match action {
+      PredefinedMenuAction::Quit => app_handle.exit(0),
+      PredefinedMenuAction::CloseWindow => {
+        let windows = app_handle
+          .manager
+          .window
+          .windows_lock()
+          .values()
+          .cloned()
+          .collect::<Vec<_>>();
+        if let Some(window) = windows
+          .into_iter()
+          .find(|w| w.is_focused().unwrap_or(false))
+        {
+          if let Err(e) = window.close() {
+            log::error!("failed to close window {}: {e}", window.label());
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+  };
+
+  use crate::{
+    menu::{MenuEvent, PredefinedMenuAction, PredefinedMenuItem},
+    test::mock_app,
+    Manager, RunEvent, WebviewWindowBuilder,
+  };
+
+  /// Activates the quit menu item once the app is running, then closes the window,
+  /// returning the exit codes of the [`RunEvent::ExitRequested`] events and the actions seen by the listener.
+  fn run_quit(prevent: bool) -> (Vec<Option<i32>>, Vec<PredefinedMenuAction>) {
+    let app = mock_app();
+    let window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let quit = PredefinedMenuItem::quit(&app, None).unwrap();
+    assert_eq!(quit.action(), Some(PredefinedMenuAction::Quit));
+
+    let actions = Arc::new(Mutex::new(Vec::new()));
+    let actions_ = actions.clone();
+    app.on_predefined_menu_event(move |_app, event| {
+      actions_.lock().unwrap().push(event.action());
+      if prevent {
+        event.prevent_default();
+      }
+    });
+
+    let handle = app.handle().clone();
+    let id = quit.id().clone();
+    std::thread::spawn(move || {
+      std::thread::sleep(Duration::from_secs(1));
+      let handle_ = handle.clone();
+      handle
+        .run_on_main_thread(move || {
+          handle_
+            .manager
+            .menu
+            .dispatch_event(&handle_, &MenuEvent { id });
+        })
+        .unwrap();
+      std::thread::sleep(Duration::from_secs(2));
+      let _ = window.close();
+    });
+
+    let exit_codes = Arc::new(Mutex::new(Vec::new()));
+    let exit_codes_ = exit_codes.clone();
+    app.run(move |_app, event| {
+      if let RunEvent::ExitRequested { code, .. } = event {
+        exit_codes_.lock().unwrap().push(code);
+      }
+    });
+
+    let exit_codes = exit_codes.lock().unwrap().clone();
+    let actions = actions.lock().unwrap().clone();
+    (exit_codes, actions)
+  }
+
+  #[test]
+  fn quit_requests_exit() {
+    let (exit_codes, actions) = run_quit(false);
+    assert_eq!(actions, [PredefinedMenuAction::Quit]);
+    assert_eq!(exit_codes, [Some(0)]);
+  }
+
+  #[test]
+  fn prevented_quit_keeps_running() {
+    let (exit_codes, actions) = run_quit(true);
+    assert_eq!(actions, [PredefinedMenuAction::Quit]);
+    // only the exit requested by closing the last window
+    assert_eq!(exit_codes, [None]);
+  }
 }
@@ -22,7 +22,7 @@ use tauri_utils::{
 use crate::{
   app::{AppHandle, GlobalWebviewEventListener, GlobalWindowEventListener, OnPageLoad},
   event::{assert_event_name_is_valid, Event, EventId, EventTarget, Listeners},
-  ipc::{Invoke, InvokeHandler, InvokeResponder, RuntimeAuthority},
+  ipc::{router::CommandRouter, Invoke, InvokeHandler, InvokeResponder, RuntimeAuthority},
   plugin::PluginStore,
   utils::{config::Config, PackageInfo},
   Assets, Context, Pattern, Runtime, StateManager, Window,
@@ -199,6 +199,9 @@ pub struct AppManager<R: Runtime> {
   pub config: Config,
   #[cfg(dev)]
   pub config_parent: Option<std::path::PathBuf>,
+  /// Resource targets mapped to their source paths, resolved in place of the copied resources.
+  #[cfg(dev)]
+  pub dev_resources: Option<HashMap<std::path::PathBuf, std::path::PathBuf>>,
   pub assets: Box<dyn Assets<R>>,
 
   pub app_icon: Option<Vec<u8>>,
@@ -216,6 +219,19 @@ pub struct AppManager<R: Runtime> {
 
   /// Runtime-generated invoke key.
   pub(crate) invoke_key: String,
+
+  /// Keeps track of the CPU time of the webview processes between samples.
+  pub(crate) metrics_sampler: crate::webview::metrics::MetricsSampler,
+
+  /// The thread emitting the webview metrics when `app > webviewMetricsInterval` is set, stopped on exit.
+  pub(crate) metrics_emitter: Mutex<Option<crate::webview::metrics::MetricsEmitter>>,
+
+  /// The last system preferences read, compared with the current ones to detect changes.
+  pub(crate) system_preferences: Mutex<Option<crate::SystemPreferences>>,
+
+  /// The state types requested by the registered commands, checked after the app setup.
+  #[cfg(debug_assertions)]
+  pub(crate) command_states: Mutex<Vec<crate::ipc::CommandState>>,
 }
 
 impl<R: Runtime> fmt::Debug for AppManager<R> {
@@ -256,6 +272,7 @@ impl<R: Runtime> AppManager<R> {
     >,
     (invoke_responder, invoke_initialization_script): (Option<Arc<InvokeResponder<R>>>, String),
     invoke_key: String,
+    command_router: CommandRouter,
   ) -> Self {
     // generate a random isolation key at runtime
     #[cfg(feature = "isolation")]
@@ -275,17 +292,24 @@ impl<R: Runtime> AppManager<R> {
         invoke_handler,
         on_page_load,
         uri_scheme_protocols: Mutex::new(uri_scheme_protocols),
+        webview_closed: Mutex::default(),
+        #[cfg(desktop)]
+        service_workers_clears: Mutex::default(),
         event_listeners: Arc::new(webiew_event_listeners),
         invoke_responder,
         invoke_initialization_script,
         invoke_key: invoke_key.clone(),
+        command_router,
       },
       #[cfg(all(desktop, feature = "tray-icon"))]
       tray: tray::TrayManager {
         icon: context.tray_icon,
+        config_icons: context.tray_icons,
         icons: Default::default(),
         global_event_listeners: Default::default(),
         event_listeners: Default::default(),
+        theme: Default::default(),
+        themed_icons: Default::default(),
       },
       #[cfg(desktop)]
       menu: menu::MenuManager {
@@ -293,6 +317,8 @@ impl<R: Runtime> AppManager<R> {
         menu: Default::default(),
         global_event_listeners: Default::default(),
         event_listeners: Mutex::new(window_menu_event_listeners),
+        predefined_actions: Default::default(),
+        predefined_event_listeners: Default::default(),
       },
       plugins: Mutex::new(plugins),
       listeners: Listeners::default(),
@@ -300,6 +326,8 @@ impl<R: Runtime> AppManager<R> {
       config: context.config,
       #[cfg(dev)]
       config_parent: context.config_parent,
+      #[cfg(dev)]
+      dev_resources: context.dev_resources,
       assets: context.assets,
       app_icon: context.app_icon,
       package_info: context.package_info,
@@ -307,6 +335,11 @@ impl<R: Runtime> AppManager<R> {
       plugin_global_api_scripts: Arc::new(context.plugin_global_api_scripts),
       resources_table: Arc::default(),
       invoke_key,
+      metrics_sampler: Default::default(),
+      metrics_emitter: Default::default(),
+      system_preferences: Default::default(),
+      #[cfg(debug_assertions)]
+      command_states: Default::default(),
     }
   }
 
@@ -499,6 +532,19 @@ impl<R: Runtime> AppManager<R> {
     self.listeners().listen(event, target, handler)
   }
 
+  pub fn listen_with_priority<F: Fn(Event) + Send + 'static>(
+    &self,
+    event: String,
+    target: EventTarget,
+    priority: i32,
+    handler: F,
+  ) -> EventId {
+    assert_event_name_is_valid(&event);
+    self
+      .listeners()
+      .listen_with_priority(event, target, priority, handler)
+  }
+
   pub fn once<F: FnOnce(Event) + Send + 'static>(
     &self,
     event: String,
@@ -513,6 +559,10 @@ impl<R: Runtime> AppManager<R> {
     self.listeners().unlisten(id)
   }
 
+  pub fn listeners_count(&self, event: &str) -> usize {
+    self.listeners().listeners_count(event)
+  }
+
   #[cfg_attr(
     feature = "tracing",
     tracing::instrument("app::emit", skip(self, payload))
@@ -521,11 +571,22 @@ impl<R: Runtime> AppManager<R> {
     assert_event_name_is_valid(event);
 
     #[cfg(feature = "tracing")]
-    let _span = tracing::debug_span!("emit::run").entered();
+    let span = tracing::debug_span!(
+      "emit::run",
+      payload_size = tracing::field::Empty,
+      listeners = tracing::field::Empty
+    )
+    .entered();
     let emit_args = EmitArgs::new(event, payload)?;
 
     let listeners = self.listeners();
 
+    #[cfg(feature = "tracing")]
+    {
+      span.record("payload_size", emit_args.payload.len());
+      span.record("listeners", listeners.listeners_count(event));
+    }
+
     listeners.emit_js(self.webview.webviews_lock().values(), event, &emit_args)?;
     listeners.emit(emit_args)?;
 
@@ -544,11 +605,22 @@ impl<R: Runtime> AppManager<R> {
     assert_event_name_is_valid(event);
 
     #[cfg(feature = "tracing")]
-    let _span = tracing::debug_span!("emit::run").entered();
+    let span = tracing::debug_span!(
+      "emit::run",
+      payload_size = tracing::field::Empty,
+      listeners = tracing::field::Empty
+    )
+    .entered();
     let emit_args = EmitArgs::new(event, payload)?;
 
     let listeners = self.listeners();
 
+    #[cfg(feature = "tracing")]
+    {
+      span.record("payload_size", emit_args.payload.len());
+      span.record("listeners", listeners.listeners_count(event));
+    }
+
     listeners.emit_js_filter(
       self.webview.webviews_lock().values(),
       event,
@@ -610,13 +682,13 @@ impl<R: Runtime> AppManager<R> {
     let window = self.window.windows_lock().remove(label);
     if let Some(window) = window {
       for webview in window.webviews() {
-        self.webview.webviews_lock().remove(webview.label());
+        self.webview.remove_webview(webview.label());
       }
     }
   }
 
   pub(crate) fn on_webview_close(&self, label: &str) {
-    self.webview.webviews_lock().remove(label);
+    self.webview.remove_webview(label);
 
     if let Ok(webview_labels_array) = serde_json::to_string(&self.webview.labels()) {
       let _ = self.webview.eval_script_all(format!(
@@ -685,6 +757,37 @@ mod tests {
       assert_eq!(replace_with_callback(src, pattern, replacement), result);
     }
   }
+
+  #[cfg(feature = "tracing")]
+  #[test]
+  fn emit_spans() {
+    use crate::{test_utils::CapturingSubscriber, Emitter, Listener};
+
+    let app = crate::test::mock_app();
+    app.listen_any("event", |_| {});
+
+    let subscriber = CapturingSubscriber::default();
+    tracing::subscriber::with_default(subscriber.clone(), || {
+      app.emit("event", "secret payload").unwrap();
+    });
+
+    let emit = subscriber
+      .find("app::emit", None)
+      .expect("missing app::emit span");
+    let run = subscriber
+      .find("emit::run", Some(emit))
+      .expect("missing emit::run span");
+    let spans = subscriber.spans.lock().unwrap();
+    assert_eq!(spans[emit].fields["event"].trim_matches('"'), "event");
+    assert_eq!(
+      spans[run].fields["payload_size"],
+      "\"secret payload\"".len().to_string()
+    );
+    assert_eq!(spans[run].fields["listeners"], "1");
+    assert!(spans
+      .iter()
+      .all(|span| span.fields.values().all(|v| !v.contains("secret"))));
+  }
 }
 
 #[cfg(test)]
@@ -732,6 +835,7 @@ mod test {
       Default::default(),
       (None, "".into()),
       crate::generate_invoke_key().unwrap(),
+      Default::default(),
     );
 
     #[cfg(custom_protocol)]
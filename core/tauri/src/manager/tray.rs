@@ -7,24 +7,31 @@ use std::{collections::HashMap, fmt, sync::Mutex};
 use crate::{
   app::GlobalTrayIconEventListener,
   image::Image,
-  tray::{TrayIcon, TrayIconEvent, TrayIconId},
-  AppHandle, Runtime,
+  tray::{ThemedIcons, TrayIcon, TrayIconEvent, TrayIconId},
+  AppHandle, Runtime, Theme,
 };
 
 pub struct TrayManager<R: Runtime> {
   pub(crate) icon: Option<Image<'static>>,
+  /// Icons of the tray icons defined in `app > trayIcons`, keyed by id.
+  pub(crate) config_icons: HashMap<String, Image<'static>>,
   /// Tray icons
   pub(crate) icons: Mutex<Vec<TrayIcon<R>>>,
   /// Global Tray icon event listeners.
   pub(crate) global_event_listeners: Mutex<Vec<GlobalTrayIconEventListener<AppHandle<R>>>>,
   /// Tray icon event listeners.
   pub(crate) event_listeners: Mutex<HashMap<TrayIconId, GlobalTrayIconEventListener<TrayIcon<R>>>>,
+  /// The last system theme reported by the windows.
+  pub(crate) theme: Mutex<Option<Theme>>,
+  /// Icons of the tray icons that follow the system theme.
+  pub(crate) themed_icons: Mutex<HashMap<TrayIconId, ThemedIcons>>,
 }
 
 impl<R: Runtime> fmt::Debug for TrayManager<R> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     f.debug_struct("TrayManager")
       .field("icon", &self.icon)
+      .field("config_icons", &self.config_icons)
       .finish()
   }
 }
@@ -63,8 +70,160 @@ impl<R: Runtime> TrayManager<R> {
     let mut icons = self.icons.lock().unwrap();
     let idx = icons.iter().position(|t| t.id() == &id);
     if let Some(idx) = idx {
-      return Some(icons.swap_remove(idx));
+      let icon = icons.swap_remove(idx);
+      self.themed_icons.lock().unwrap().remove(icon.id());
+      self.event_listeners.lock().unwrap().remove(icon.id());
+      return Some(icon);
     }
     None
   }
+
+  /// Runs the global listeners and the listener of the tray icon that triggered the event.
+  pub(crate) fn dispatch_event(&self, app_handle: &AppHandle<R>, event: &TrayIconEvent) {
+    for listener in &*self.global_event_listeners.lock().unwrap() {
+      listener(app_handle, event.clone());
+    }
+
+    for (id, listener) in &*self.event_listeners.lock().unwrap() {
+      if event.id() == id {
+        if let Some(tray) = self.tray_by_id(id) {
+          listener(&tray, event.clone());
+        }
+      }
+    }
+  }
+
+  /// The system theme, read from the windows if none of them reported a theme change yet.
+  pub(crate) fn current_theme(&self, app_handle: &AppHandle<R>) -> Option<Theme> {
+    let mut theme = self.theme.lock().unwrap();
+    if theme.is_none() {
+      let windows = app_handle
+        .manager
+        .window
+        .windows_lock()
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+      *theme = windows.iter().find_map(|w| w.theme().ok());
+    }
+    *theme
+  }
+
+  /// Records the system theme, returning the icons to swap in, sorted by tray icon id.
+  ///
+  /// Every window reports the theme change so nothing is returned if the theme did not change.
+  pub(crate) fn set_theme(&self, theme: Theme) -> Vec<(TrayIconId, Image<'static>)> {
+    if self.theme.lock().unwrap().replace(theme) == Some(theme) {
+      return Vec::new();
+    }
+
+    let mut icons = self
+      .themed_icons
+      .lock()
+      .unwrap()
+      .iter()
+      .filter_map(|(id, icons)| Some((id.clone(), icons.icon_for(Some(theme))?.clone())))
+      .collect::<Vec<_>>();
+    icons.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+    icons
+  }
+
+  /// Swaps the icons of the tray icons following the system theme,
+  /// then emits [`TrayIconEvent::ThemeChanged`] so dynamic icons can be rendered again.
+  pub(crate) fn on_theme_changed(&self, app_handle: &AppHandle<R>, theme: Theme) {
+    for (id, icon) in self.set_theme(theme) {
+      let Some(tray) = self.tray_by_id(&id) else {
+        continue;
+      };
+      if let Err(e) = tray.set_icon(Some(icon)) {
+        log::error!(
+          "failed to set the {theme} icon of tray icon {}: {e}",
+          id.as_ref()
+        );
+        continue;
+      }
+      self.dispatch_event(app_handle, &TrayIconEvent::ThemeChanged { id, theme });
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::{
+    image::Image,
+    test::mock_app,
+    tray::{ThemedIcons, TrayIconId},
+    Manager, Theme, WebviewWindowBuilder,
+  };
+
+  fn image(value: u8) -> Image<'static> {
+    Image::new_owned(vec![value; 4], 1, 1)
+  }
+
+  #[test]
+  fn swaps_themed_icons() {
+    let app = mock_app();
+    let tray = &app.manager().tray;
+    tray.themed_icons.lock().unwrap().extend([
+      (
+        TrayIconId::new("a"),
+        ThemedIcons {
+          fallback: Some(image(0)),
+          icons: HashMap::from([(Theme::Dark, image(1))]),
+        },
+      ),
+      (
+        TrayIconId::new("b"),
+        ThemedIcons {
+          fallback: None,
+          icons: HashMap::from([(Theme::Light, image(2)), (Theme::Dark, image(3))]),
+        },
+      ),
+      (
+        TrayIconId::new("c"),
+        ThemedIcons {
+          fallback: None,
+          icons: HashMap::from([(Theme::Dark, image(4))]),
+        },
+      ),
+    ]);
+
+    let swaps = |theme| {
+      tray
+        .set_theme(theme)
+        .into_iter()
+        .map(|(id, icon)| (id.as_ref().to_string(), icon.rgba()[0]))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      swaps(Theme::Dark),
+      [("a".into(), 1), ("b".into(), 3), ("c".into(), 4)]
+    );
+    // every window reports the change
+    assert!(swaps(Theme::Dark).is_empty());
+    // `c` has no light icon so it keeps the dark one
+    assert_eq!(swaps(Theme::Light), [("a".into(), 0), ("b".into(), 2)]);
+  }
+
+  #[test]
+  fn reads_theme_from_windows() {
+    let app = mock_app();
+    assert_eq!(app.manager().tray.current_theme(app.handle()), None);
+
+    WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    assert_eq!(
+      app.manager().tray.current_theme(app.handle()),
+      Some(Theme::Light)
+    );
+    // the theme is only read from the windows until they report a change
+    app.manager().tray.set_theme(Theme::Dark);
+    assert_eq!(
+      app.manager().tray.current_theme(app.handle()),
+      Some(Theme::Dark)
+    );
+  }
 }
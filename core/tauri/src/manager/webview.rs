@@ -7,7 +7,10 @@ use std::{
   collections::{HashMap, HashSet},
   fmt,
   fs::create_dir_all,
-  sync::{Arc, Mutex, MutexGuard},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, MutexGuard,
+  },
 };
 
 use serde::Serialize;
@@ -16,16 +19,17 @@ use tauri_runtime::{
   webview::{DetachedWebview, PendingWebview},
   window::DragDropEvent,
 };
-use tauri_utils::config::WebviewUrl;
+use tauri_utils::config::{CookiePersistence, WebviewUrl};
 use url::Url;
 
 use crate::{
   app::{GlobalWebviewEventListener, OnPageLoad, UriSchemeResponder, WebviewEvent},
-  ipc::{InvokeHandler, InvokeResponder},
+  ipc::{router::CommandRouter, InvokeHandler, InvokeResponder},
   pattern::PatternJavascript,
   sealed::ManagerBase,
   webview::PageLoadPayload,
-  AppHandle, Emitter, EventLoopMessage, EventTarget, Manager, Runtime, Scopes, Webview, Window,
+  AppHandle, Emitter, EventAction, EventLoopMessage, EventTarget, Manager, Runtime, Scopes,
+  Webview, Window,
 };
 
 use super::{
@@ -39,6 +43,12 @@ use super::{
 // must also keep in sync with the `let mut response` assignment in prepare_uri_scheme_protocol
 pub(crate) const PROXY_DEV_SERVER: bool = cfg!(all(dev, mobile));
 
+const FIND_RESULT_EVENT: &str = "tauri://find-result";
+
+/// File in the webview data directory recording the app version the service workers were registered with.
+#[cfg(desktop)]
+const SERVICE_WORKERS_VERSION_FILE_NAME: &str = ".service-workers-version";
+
 pub(crate) const PROCESS_IPC_MESSAGE_FN: &str =
   include_str!("../../scripts/process-ipc-message-fn.js");
 
@@ -79,6 +89,11 @@ pub struct WebviewManager<R: Runtime> {
   pub on_page_load: Option<Arc<OnPageLoad<R>>>,
   /// The webview protocols available to all webviews.
   pub uri_scheme_protocols: Mutex<HashMap<String, Arc<UriSchemeProtocol<R>>>>,
+  /// The flags set when a webview is closed, shared with the responders of its custom protocol requests.
+  pub(crate) webview_closed: Mutex<HashMap<String, Arc<AtomicBool>>>,
+  /// The service workers to unregister before the webviews load their URL, see [`WebviewAttributes::clear_service_workers_on_version_change`](tauri_runtime::webview::WebviewAttributes::clear_service_workers_on_version_change).
+  #[cfg(desktop)]
+  pub(crate) service_workers_clears: Mutex<HashMap<String, ServiceWorkersClear>>,
   /// Webview event listeners to all webviews.
   pub event_listeners: Arc<Vec<GlobalWebviewEventListener<R>>>,
 
@@ -89,6 +104,9 @@ pub struct WebviewManager<R: Runtime> {
 
   /// A runtime generated invoke key.
   pub(crate) invoke_key: String,
+
+  /// The command aliases and hidden commands.
+  pub(crate) command_router: CommandRouter,
 }
 
 impl<R: Runtime> fmt::Debug for WebviewManager<R> {
@@ -122,6 +140,14 @@ impl<R: Runtime> WebviewManager<R> {
     self.webviews.lock().expect("poisoned webview manager")
   }
 
+  /// Removes the webview, failing the body writers of its custom protocol requests.
+  pub(crate) fn remove_webview(&self, label: &str) {
+    self.webviews_lock().remove(label);
+    if let Some(closed) = self.webview_closed.lock().unwrap().remove(label) {
+      closed.store(true, Ordering::Relaxed);
+    }
+  }
+
   fn prepare_pending_webview<M: Manager<R>>(
     &self,
     mut pending: PendingWebview<EventLoopMessage, R>,
@@ -203,6 +229,19 @@ impl<R: Runtime> WebviewManager<R> {
         is_init_global,
       )?);
 
+    // webviews created before the preferences are read use the defaults until they change
+    let system_preferences = app_manager
+      .system_preferences
+      .lock()
+      .unwrap()
+      .clone()
+      .unwrap_or_default();
+    if let Some(script) =
+      crate::app::system_preferences::initialization_script(&system_preferences)?
+    {
+      webview_attributes = webview_attributes.initialization_script(&script);
+    }
+
     for plugin_init_script in plugin_init_scripts {
       webview_attributes = webview_attributes.initialization_script(&plugin_init_script);
     }
@@ -229,15 +268,23 @@ impl<R: Runtime> WebviewManager<R> {
 
     let mut registered_scheme_protocols = Vec::new();
 
+    let webview_closed = Arc::new(AtomicBool::new(false));
+    self
+      .webview_closed
+      .lock()
+      .unwrap()
+      .insert(pending.label.clone(), webview_closed.clone());
+
     for (uri_scheme, protocol) in &*self.uri_scheme_protocols.lock().unwrap() {
       registered_scheme_protocols.push(uri_scheme.clone());
       let protocol = protocol.clone();
       let app_handle = Mutex::new(manager.app_handle().clone());
+      let webview_closed = webview_closed.clone();
       pending.register_uri_scheme_protocol(uri_scheme.clone(), move |p, responder| {
         (protocol.protocol)(
           &app_handle.lock().unwrap(),
           p,
-          UriSchemeResponder(responder),
+          UriSchemeResponder::new(responder, webview_closed.clone()),
         )
       });
     }
@@ -271,16 +318,24 @@ impl<R: Runtime> WebviewManager<R> {
         &window_origin,
         web_resource_request_handler,
       );
+      let webview_closed = webview_closed.clone();
       pending.register_uri_scheme_protocol("tauri", move |request, responder| {
-        protocol(request, UriSchemeResponder(responder))
+        protocol(
+          request,
+          UriSchemeResponder::new(responder, webview_closed.clone()),
+        )
       });
       registered_scheme_protocols.push("tauri".into());
     }
 
     if !registered_scheme_protocols.contains(&"ipc".into()) {
       let protocol = crate::ipc::protocol::get(manager.manager_owned(), pending.label.clone());
+      let webview_closed = webview_closed.clone();
       pending.register_uri_scheme_protocol("ipc", move |request, responder| {
-        protocol(request, UriSchemeResponder(responder))
+        protocol(
+          request,
+          UriSchemeResponder::new(responder, webview_closed.clone()),
+        )
       });
       registered_scheme_protocols.push("ipc".into());
     }
@@ -318,8 +373,12 @@ impl<R: Runtime> WebviewManager<R> {
         .asset_protocol
         .clone();
       let protocol = crate::protocol::asset::get(asset_scope.clone(), window_origin.clone());
+      let webview_closed = webview_closed.clone();
       pending.register_uri_scheme_protocol("asset", move |request, responder| {
-        protocol(request, UriSchemeResponder(responder))
+        protocol(
+          request,
+          UriSchemeResponder::new(responder, webview_closed.clone()),
+        )
       });
     }
 
@@ -338,8 +397,12 @@ impl<R: Runtime> WebviewManager<R> {
         *crypto_keys.aes_gcm().raw(),
         window_origin,
       );
+      let webview_closed = webview_closed.clone();
       pending.register_uri_scheme_protocol(schema, move |request, responder| {
-        protocol(request, UriSchemeResponder(responder))
+        protocol(
+          request,
+          UriSchemeResponder::new(responder, webview_closed.clone()),
+        )
       });
     }
 
@@ -559,6 +622,43 @@ impl<R: Runtime> WebviewManager<R> {
       )
     }
 
+    #[cfg(desktop)]
+    {
+      let mut service_workers_clears = self.service_workers_clears.lock().unwrap();
+      service_workers_clears.remove(&pending.label);
+      if pending
+        .webview_attributes
+        .clear_service_workers_on_version_change
+      {
+        // the service workers are stored with the webview data, so the version is recorded per data directory
+        let data_directory = match &pending.webview_attributes.data_directory {
+          Some(dir) => Ok(dir.clone()),
+          None => manager.path().app_local_data_dir(),
+        };
+        match data_directory {
+          Ok(dir) => {
+            let version_file = dir.join(SERVICE_WORKERS_VERSION_FILE_NAME);
+            let version = app_manager.package_info.version.to_string();
+            let stored_version = std::fs::read_to_string(&version_file).ok();
+            if version_changed(stored_version.as_deref(), &version) {
+              // load a blank page until the service workers are unregistered
+              // so the stale service workers do not serve the first navigation
+              service_workers_clears.insert(
+                pending.label.clone(),
+                ServiceWorkersClear {
+                  url: url.clone(),
+                  version_file,
+                  version,
+                },
+              );
+              pending.url = "about:blank".into();
+            }
+          }
+          Err(e) => log::error!("failed to resolve the app local data directory: {e}"),
+        }
+      }
+    }
+
     #[cfg(feature = "isolation")]
     let pattern = app_manager.pattern.clone();
     let navigation_handler = pending.navigation_handler.take();
@@ -598,16 +698,15 @@ impl<R: Runtime> WebviewManager<R> {
     &self,
     window: Window<R>,
     webview: DetachedWebview<EventLoopMessage, R>,
+    cookie_persistence: CookiePersistence,
   ) -> Webview<R> {
-    let webview = Webview::new(window, webview);
+    let webview = Webview::new(window, webview, cookie_persistence);
 
     let webview_event_listeners = self.event_listeners.clone();
     let webview_ = webview.clone();
     webview.on_webview_event(move |event| {
       let _ = on_webview_event(&webview_, event);
-      for handler in webview_event_listeners.iter() {
-        handler(&webview_, event);
-      }
+      run_webview_event_handlers(&webview_, event, &webview_event_listeners);
     });
 
     // insert the webview into our manager
@@ -617,6 +716,17 @@ impl<R: Runtime> WebviewManager<R> {
         .insert(webview.label().to_string(), webview.clone());
     }
 
+    #[cfg(desktop)]
+    let service_workers_clear = self
+      .service_workers_clears
+      .lock()
+      .unwrap()
+      .remove(webview.label());
+    #[cfg(desktop)]
+    if let Some(clear) = service_workers_clear {
+      clear.run(&webview);
+    }
+
     // let plugins know that a new webview has been added to the manager
     let manager = webview.manager_owned();
     let webview_ = webview.clone();
@@ -684,6 +794,28 @@ impl<R: Runtime> Webview<R> {
   }
 }
 
+/// Runs the plugin handlers, then the app handlers, until one of them stops the propagation.
+fn run_webview_event_handlers<R: Runtime>(
+  webview: &Webview<R>,
+  event: &WebviewEvent,
+  listeners: &[GlobalWebviewEventListener<R>],
+) {
+  let action = webview
+    .manager()
+    .plugins
+    .lock()
+    .expect("poisoned plugin store")
+    .on_webview_event(webview, event);
+  if action == EventAction::StopPropagation {
+    return;
+  }
+  for handler in listeners {
+    if handler(webview, event) == EventAction::StopPropagation {
+      break;
+    }
+  }
+}
+
 fn on_webview_event<R: Runtime>(webview: &Webview<R>, event: &WebviewEvent) -> crate::Result<()> {
   match event {
     WebviewEvent::DragDrop(event) => match event {
@@ -719,7 +851,280 @@ fn on_webview_event<R: Runtime>(webview: &Webview<R>, event: &WebviewEvent) -> c
       DragDropEvent::Leave => webview.emit_to_webview(DRAG_LEAVE_EVENT, ())?,
       _ => unimplemented!(),
     },
+    WebviewEvent::FindResult(result) => webview.emit_to_webview(FIND_RESULT_EVENT, result)?,
   }
 
   Ok(())
 }
+
+/// Whether the app version changed since the version was last recorded.
+#[cfg(desktop)]
+fn version_changed(stored: Option<&str>, current: &str) -> bool {
+  stored.map(str::trim) != Some(current)
+}
+
+/// A pending unregistration of the service workers of a webview, created with a blank page instead of its URL.
+#[cfg(desktop)]
+pub(crate) struct ServiceWorkersClear {
+  /// The URL to load once the service workers are unregistered.
+  url: Url,
+  /// The file recording the app version the service workers were registered with.
+  version_file: std::path::PathBuf,
+  /// The current app version.
+  version: String,
+}
+
+#[cfg(desktop)]
+impl ServiceWorkersClear {
+  /// Unregisters the service workers of the webview and, once the platform completes it,
+  /// records the app version if it succeeded and loads the webview URL.
+  ///
+  /// The version is not recorded on failure so the service workers are unregistered again on the next run.
+  fn run<R: Runtime>(self, webview: &Webview<R>) {
+    let url = self.url.clone();
+    let webview_ = webview.clone();
+    let dispatched = webview
+      .webview
+      .dispatcher
+      .unregister_service_workers(move |result| self.complete(&webview_, result));
+    if let Err(e) = dispatched {
+      log::error!(
+        "failed to unregister the service workers of webview {}: {e}",
+        webview.label()
+      );
+      navigate(webview, url);
+    }
+  }
+
+  fn complete<R: Runtime>(self, webview: &Webview<R>, result: tauri_runtime::Result<()>) {
+    match result {
+      Ok(()) => {
+        if let Err(e) = record_version(&self.version_file, &self.version) {
+          log::error!("failed to record the service workers app version: {e}");
+        }
+      }
+      Err(e) => log::error!(
+        "failed to unregister the service workers of webview {}: {e}",
+        webview.label()
+      ),
+    }
+    navigate(webview, self.url);
+  }
+}
+
+#[cfg(desktop)]
+fn navigate<R: Runtime>(webview: &Webview<R>, url: Url) {
+  if let Err(e) = webview.clone().navigate(url) {
+    log::error!("failed to load the URL of webview {}: {e}", webview.label());
+  }
+}
+
+#[cfg(desktop)]
+fn record_version(path: &std::path::Path, version: &str) -> std::io::Result<()> {
+  if let Some(parent) = path.parent() {
+    create_dir_all(parent)?;
+  }
+  std::fs::write(path, version)
+}
+
+#[cfg(all(test, desktop))]
+mod tests {
+  use std::{
+    io::{self, Read, Write},
+    sync::{
+      atomic::{AtomicBool, Ordering},
+      mpsc, Arc, Mutex,
+    },
+  };
+
+  use super::{
+    run_webview_event_handlers, version_changed, CookiePersistence, ServiceWorkersClear,
+    SERVICE_WORKERS_VERSION_FILE_NAME,
+  };
+  use crate::{
+    plugin::Builder as PluginBuilder,
+    sealed::ManagerBase,
+    test::{mock_builder, mock_context, noop_assets},
+    utils::config::WindowConfig,
+    EventAction, Manager, WebviewEvent, WebviewWindowBuilder,
+  };
+  use tauri_runtime::{webview::UriSchemeResponseBody, window::DragDropEvent};
+
+  #[test]
+  fn detects_version_change() {
+    assert!(version_changed(None, "1.0.0"));
+    assert!(version_changed(Some("1.0.0"), "1.1.0"));
+    assert!(!version_changed(Some("1.0.0"), "1.0.0"));
+    assert!(!version_changed(Some("1.0.0\n"), "1.0.0"));
+  }
+
+  #[test]
+  fn clears_service_workers_before_first_navigation() {
+    let app = mock_builder().build(mock_context(noop_assets())).unwrap();
+    let build = |label: &str, clear: bool, data_directory: &std::path::Path| {
+      let config = WindowConfig {
+        label: label.into(),
+        clear_service_workers_on_version_change: clear,
+        ..Default::default()
+      };
+      WebviewWindowBuilder::from_config(&app, &config)
+        .unwrap()
+        .data_directory(data_directory.to_path_buf())
+        .build()
+        .unwrap()
+    };
+    let unregistered_at = |webview: &crate::WebviewWindow<_>| {
+      webview
+        .webview
+        .webview
+        .dispatcher
+        .service_workers_unregistered_at()
+    };
+    let data_directory = tempfile::tempdir().unwrap();
+    let version_file = data_directory
+      .path()
+      .join(SERVICE_WORKERS_VERSION_FILE_NAME);
+
+    let not_flagged = build("not-flagged", false, data_directory.path());
+    assert!(unregistered_at(&not_flagged).is_empty());
+    assert!(!version_file.exists());
+
+    let first = build("first", true, data_directory.path());
+    assert_eq!(unregistered_at(&first), vec!["about:blank".to_string()]);
+    assert_eq!(first.url().unwrap(), not_flagged.url().unwrap());
+    assert_eq!(
+      std::fs::read_to_string(&version_file).unwrap(),
+      app.package_info().version.to_string()
+    );
+
+    // the service workers of this data directory are already unregistered for this version
+    let second = build("second", true, data_directory.path());
+    assert!(unregistered_at(&second).is_empty());
+
+    let other_data_directory = tempfile::tempdir().unwrap();
+    let other = build("other", true, other_data_directory.path());
+    assert_eq!(unregistered_at(&other), vec!["about:blank".to_string()]);
+    assert_eq!(other.url().unwrap(), not_flagged.url().unwrap());
+
+    // the version is not recorded when the platform fails to unregister them
+    let failed_data_directory = tempfile::tempdir().unwrap();
+    let failed_version_file = failed_data_directory
+      .path()
+      .join(SERVICE_WORKERS_VERSION_FILE_NAME);
+    let url: url::Url = "http://tauri.app/failed".parse().unwrap();
+    ServiceWorkersClear {
+      url: url.clone(),
+      version_file: failed_version_file.clone(),
+      version: app.package_info().version.to_string(),
+    }
+    .complete(
+      &other.webview,
+      Err(tauri_runtime::Error::ClearWebviewData("failed".into())),
+    );
+    assert!(!failed_version_file.exists());
+    assert_eq!(other.url().unwrap(), url);
+  }
+
+  #[test]
+  fn cookie_persistence() {
+    let app = mock_builder().build(mock_context(noop_assets())).unwrap();
+    let persistent = WebviewWindowBuilder::new(&app, "persistent", Default::default())
+      .build()
+      .unwrap();
+    let session = WebviewWindowBuilder::new(&app, "session", Default::default())
+      .cookie_persistence(CookiePersistence::Session)
+      .build()
+      .unwrap();
+    let incognito = WebviewWindowBuilder::new(&app, "incognito", Default::default())
+      .incognito(true)
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      persistent.cookie_persistence(),
+      CookiePersistence::Persistent
+    );
+    assert_eq!(session.cookie_persistence(), CookiePersistence::Session);
+    assert_eq!(incognito.cookie_persistence(), CookiePersistence::Session);
+  }
+
+  #[test]
+  fn plugin_stops_webview_event_propagation() {
+    let app_called = Arc::new(AtomicBool::new(false));
+    let app_called_ = app_called.clone();
+    let app = mock_builder()
+      .plugin(
+        PluginBuilder::new("drop")
+          .on_webview_event(|_, event| match event {
+            WebviewEvent::DragDrop(DragDropEvent::Drop { .. }) => EventAction::StopPropagation,
+            _ => EventAction::Continue,
+          })
+          .build(),
+      )
+      .on_webview_event(move |_, _| app_called_.store(true, Ordering::SeqCst))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview_window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let listeners = &app.manager().webview.event_listeners;
+
+    let drop = WebviewEvent::DragDrop(DragDropEvent::Drop {
+      paths: Vec::new(),
+      position: Default::default(),
+    });
+    run_webview_event_handlers(webview_window.as_ref(), &drop, listeners);
+    assert!(!app_called.load(Ordering::SeqCst));
+
+    run_webview_event_handlers(
+      webview_window.as_ref(),
+      &WebviewEvent::DragDrop(DragDropEvent::Leave),
+      listeners,
+    );
+    assert!(app_called.load(Ordering::SeqCst));
+  }
+
+  #[test]
+  fn closing_webview_fails_protocol_body_writers() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let app = mock_builder()
+      .register_asynchronous_uri_scheme_protocol("export", move |_app, _request, responder| {
+        let writer = responder.body_writer(http::Response::new(()));
+        tx.lock().unwrap().send(writer).unwrap();
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview_window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let response = webview_window
+      .webview
+      .webview
+      .dispatcher
+      .uri_scheme_request(
+        http::Request::get("export://localhost/data.csv")
+          .body(Vec::new())
+          .unwrap(),
+      )
+      .recv()
+      .unwrap();
+    let mut writer = rx.recv().unwrap();
+    writer.write_all(b"chunk").unwrap();
+    assert!(!writer.is_closed());
+
+    webview_window.as_ref().close().unwrap();
+    assert!(writer.is_closed());
+    assert_eq!(
+      writer.write_all(b"chunk").unwrap_err().kind(),
+      io::ErrorKind::BrokenPipe
+    );
+    drop(writer);
+
+    let UriSchemeResponseBody::Stream(mut body) = response.into_body() else {
+      panic!("expected a streamed body");
+    };
+    assert!(body.read_to_end(&mut Vec::new()).is_err());
+  }
+}
@@ -18,7 +18,7 @@ use tauri_runtime::{
 
 use crate::{
   app::GlobalWindowEventListener, image::Image, sealed::ManagerBase, AppHandle, Emitter,
-  EventLoopMessage, EventTarget, Manager, Runtime, Scopes, Window, WindowEvent,
+  EventAction, EventLoopMessage, EventTarget, Manager, Runtime, Scopes, Window, WindowEvent,
 };
 
 const WINDOW_RESIZED_EVENT: &str = "tauri://resize";
@@ -90,9 +90,7 @@ impl<R: Runtime> WindowManager<R> {
     let window_event_listeners = self.event_listeners.clone();
     window.on_window_event(move |event| {
       let _ = on_window_event(&window_, event);
-      for handler in window_event_listeners.iter() {
-        handler(&window_, event);
-      }
+      run_window_event_handlers(&window_, event, &window_event_listeners);
     });
 
     // insert the window into our manager
@@ -143,6 +141,28 @@ impl<R: Runtime> Window<R> {
   }
 }
 
+/// Runs the plugin handlers, then the app handlers, until one of them stops the propagation.
+fn run_window_event_handlers<R: Runtime>(
+  window: &Window<R>,
+  event: &WindowEvent,
+  listeners: &[GlobalWindowEventListener<R>],
+) {
+  let action = window
+    .manager
+    .plugins
+    .lock()
+    .expect("poisoned plugin store")
+    .on_window_event(window, event);
+  if action == EventAction::StopPropagation {
+    return;
+  }
+  for handler in listeners {
+    if handler(window, event) == EventAction::StopPropagation {
+      break;
+    }
+  }
+}
+
 #[derive(Serialize, Clone)]
 pub(crate) struct DragDropPayload<'a> {
   #[serde(skip_serializing_if = "Option::is_none")]
@@ -255,6 +275,11 @@ fn on_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) -> crate
       _ => unimplemented!(),
     },
     WindowEvent::ThemeChanged(theme) => {
+      #[cfg(all(desktop, feature = "tray-icon"))]
+      window
+        .manager()
+        .tray
+        .on_theme_changed(window.app_handle(), *theme);
       window.emit_to_window(WINDOW_THEME_CHANGED, theme.to_string())?
     }
   }
@@ -267,3 +292,117 @@ struct ScaleFactorChanged {
   scale_factor: f64,
   size: PhysicalSize<u32>,
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::run_window_event_handlers;
+  use crate::{
+    plugin::Builder as PluginBuilder,
+    sealed::ManagerBase,
+    test::{mock_builder, mock_context, noop_assets, MockRuntime},
+    EventAction, WebviewWindowBuilder, Window, WindowEvent,
+  };
+
+  /// Runs a focus event through two plugin and two app handlers, returning the handlers that ran.
+  fn run_handlers(stop_at: Option<&'static str>) -> Vec<&'static str> {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let handler = |name: &'static str| {
+      let calls = calls.clone();
+      move |_: &Window<MockRuntime>, _: &WindowEvent| {
+        calls.lock().unwrap().push(name);
+        if stop_at == Some(name) {
+          EventAction::StopPropagation
+        } else {
+          EventAction::Continue
+        }
+      }
+    };
+
+    let mut builder = mock_builder();
+    for name in ["plugin-a", "plugin-b"] {
+      builder = builder.plugin(
+        PluginBuilder::new(name)
+          .on_window_event(handler(name))
+          .build(),
+      );
+    }
+    let app = builder
+      .on_window_event(handler("app-a"))
+      .on_window_event(handler("app-b"))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview_window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let window = webview_window.as_ref().window();
+    run_window_event_handlers(
+      &window,
+      &WindowEvent::Focused(true),
+      &app.manager().window.event_listeners,
+    );
+
+    let calls = calls.lock().unwrap().clone();
+    calls
+  }
+
+  #[test]
+  fn runs_all_handlers_in_order() {
+    assert_eq!(
+      run_handlers(None),
+      ["plugin-a", "plugin-b", "app-a", "app-b"]
+    );
+  }
+
+  #[test]
+  fn stops_propagation() {
+    assert_eq!(run_handlers(Some("plugin-a")), ["plugin-a"]);
+    assert_eq!(
+      run_handlers(Some("app-a")),
+      ["plugin-a", "plugin-b", "app-a"]
+    );
+  }
+
+  #[test]
+  fn unit_handlers_continue() {
+    let calls = Arc::new(Mutex::new(0));
+    let calls_ = calls.clone();
+    let calls__ = calls.clone();
+    let app = mock_builder()
+      .on_window_event(move |_, _| *calls_.lock().unwrap() += 1)
+      .on_window_event(move |_, _| *calls__.lock().unwrap() += 1)
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview_window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    run_window_event_handlers(
+      &webview_window.as_ref().window(),
+      &WindowEvent::Focused(false),
+      &app.manager().window.event_listeners,
+    );
+    assert_eq!(*calls.lock().unwrap(), 2);
+  }
+
+  #[cfg(all(desktop, feature = "tray-icon"))]
+  #[test]
+  fn theme_change_is_forwarded_to_tray() {
+    let app = crate::test::mock_app();
+    let webview_window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    super::on_window_event(
+      &webview_window.as_ref().window(),
+      &WindowEvent::ThemeChanged(crate::Theme::Dark),
+    )
+    .unwrap();
+    assert_eq!(
+      *app.manager().tray.theme.lock().unwrap(),
+      Some(crate::Theme::Dark)
+    );
+  }
+}
@@ -2,7 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{image::Image, menu::*, Manager, Runtime};
+use crate::{
+  image::Image,
+  menu::{locale::role_text, *},
+  utils::config::MenuRole,
+  Manager, Runtime,
+};
 
 /// A builder type for [`Menu`]
 ///
@@ -37,6 +42,7 @@ pub struct MenuBuilder<'m, R: Runtime, M: Manager<R>> {
   id: Option<MenuId>,
   manager: &'m M,
   items: Vec<crate::Result<MenuItemKind<R>>>,
+  locale: Option<String>,
 }
 
 impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
@@ -46,6 +52,7 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
       id: None,
       items: Vec::new(),
       manager,
+      locale: None,
     }
   }
 
@@ -55,6 +62,7 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
       id: Some(id.into()),
       items: Vec::new(),
       manager,
+      locale: None,
     }
   }
 
@@ -64,6 +72,20 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
     self
   }
 
+  /// Set the locale used to translate the predefined items added to the menu after this call,
+  /// e.g. `de` or `pt-BR`. Defaults to the `app > menu > locale` config.
+  ///
+  /// Falls back to the language without its region, then to the platform default (English) text.
+  /// The `app > menu > roleText` config overrides take precedence over the translations.
+  pub fn with_locale<S: Into<String>>(mut self, locale: S) -> Self {
+    self.locale.replace(locale.into());
+    self
+  }
+
+  fn role_text(&self, role: MenuRole) -> Option<String> {
+    role_text(self.manager.app_handle(), role, self.locale.as_deref())
+  }
+
   /// Add this item to the menu.
   pub fn item(mut self, item: &dyn IsMenuItem<R>) -> Self {
     self.items.push(Ok(item.kind()));
@@ -131,33 +153,37 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
 
   /// Add Copy menu item to the menu.
   pub fn copy(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::copy(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::copy(self.manager, self.role_text(MenuRole::Copy).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
   /// Add Cut menu item to the menu.
   pub fn cut(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::cut(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::cut(self.manager, self.role_text(MenuRole::Cut).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
   /// Add Paste menu item to the menu.
   pub fn paste(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::paste(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::paste(self.manager, self.role_text(MenuRole::Paste).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
   /// Add SelectAll menu item to the menu.
   pub fn select_all(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::select_all(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::select_all(self.manager, self.role_text(MenuRole::SelectAll).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -167,9 +193,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Windows / Linux:** Unsupported.
   pub fn undo(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::undo(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::undo(self.manager, self.role_text(MenuRole::Undo).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
   /// Add Redo menu item to the menu.
@@ -178,9 +205,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Windows / Linux:** Unsupported.
   pub fn redo(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::redo(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::redo(self.manager, self.role_text(MenuRole::Redo).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -190,9 +218,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Linux:** Unsupported.
   pub fn minimize(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::minimize(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::minimize(self.manager, self.role_text(MenuRole::Minimize).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -202,9 +231,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Linux:** Unsupported.
   pub fn maximize(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::maximize(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::maximize(self.manager, self.role_text(MenuRole::Maximize).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -214,9 +244,13 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Windows / Linux:** Unsupported.
   pub fn fullscreen(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::fullscreen(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::fullscreen(
+        self.manager,
+        self.role_text(MenuRole::Fullscreen).as_deref(),
+      )
+      .map(|i| i.kind()),
+    );
     self
   }
 
@@ -226,9 +260,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Linux:** Unsupported.
   pub fn hide(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::hide(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::hide(self.manager, self.role_text(MenuRole::Hide).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -238,9 +273,13 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Linux:** Unsupported.
   pub fn hide_others(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::hide_others(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::hide_others(
+        self.manager,
+        self.role_text(MenuRole::HideOthers).as_deref(),
+      )
+      .map(|i| i.kind()),
+    );
     self
   }
 
@@ -250,9 +289,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Windows / Linux:** Unsupported.
   pub fn show_all(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::show_all(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::show_all(self.manager, self.role_text(MenuRole::ShowAll).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -262,9 +302,13 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Linux:** Unsupported.
   pub fn close_window(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::close_window(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::close_window(
+        self.manager,
+        self.role_text(MenuRole::CloseWindow).as_deref(),
+      )
+      .map(|i| i.kind()),
+    );
     self
   }
 
@@ -274,17 +318,23 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Linux:** Unsupported.
   pub fn quit(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::quit(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::quit(self.manager, self.role_text(MenuRole::Quit).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
   /// Add About app menu item to the menu.
   pub fn about(mut self, metadata: Option<AboutMetadata<'_>>) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::about(self.manager, None, metadata).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::about(
+        self.manager,
+        self.role_text(MenuRole::About).as_deref(),
+        metadata,
+      )
+      .map(|i| i.kind()),
+    );
     self
   }
 
@@ -294,9 +344,10 @@ impl<'m, R: Runtime, M: Manager<R>> MenuBuilder<'m, R, M> {
   ///
   /// - **Windows / Linux:** Unsupported.
   pub fn services(mut self) -> Self {
-    self
-      .items
-      .push(PredefinedMenuItem::services(self.manager, None).map(|i| i.kind()));
+    self.items.push(
+      PredefinedMenuItem::services(self.manager, self.role_text(MenuRole::Services).as_deref())
+        .map(|i| i.kind()),
+    );
     self
   }
 
@@ -0,0 +1,204 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Built-in translations of the predefined menu items and the default menu submenus.
+
+use std::collections::HashMap;
+
+use crate::{utils::config::MenuRole, AppHandle, Runtime};
+
+/// Locales aliased to another translation table.
+const ALIASES: &[(&str, &str)] = &[("zh-hant", "zh-tw"), ("zh-hk", "zh-tw"), ("zh-mo", "zh-tw")];
+
+/// Translations indexed by [`MenuRole`] discriminant.
+///
+/// `{name}` is replaced with the app name.
+#[rustfmt::skip]
+const TRANSLATIONS: &[(&str, [&str; 21])] = &[
+  ("de", [
+    "Kopieren", "Ausschneiden", "Einfügen", "Alles auswählen", "Widerrufen", "Wiederholen",
+    "Minimieren", "Maximieren", "Vollbildmodus", "Ausblenden", "Andere ausblenden",
+    "Alle einblenden", "Fenster schließen", "Beenden", "Über {name}", "Dienste",
+    "Datei", "Bearbeiten", "Darstellung", "Fenster", "Hilfe",
+  ]),
+  ("es", [
+    "Copiar", "Cortar", "Pegar", "Seleccionar todo", "Deshacer", "Rehacer",
+    "Minimizar", "Maximizar", "Pantalla completa", "Ocultar", "Ocultar otros",
+    "Mostrar todo", "Cerrar ventana", "Salir", "Acerca de {name}", "Servicios",
+    "Archivo", "Edición", "Visualización", "Ventana", "Ayuda",
+  ]),
+  ("fr", [
+    "Copier", "Couper", "Coller", "Tout sélectionner", "Annuler", "Rétablir",
+    "Réduire", "Agrandir", "Plein écran", "Masquer", "Masquer les autres",
+    "Tout afficher", "Fermer la fenêtre", "Quitter", "À propos de {name}", "Services",
+    "Fichier", "Édition", "Présentation", "Fenêtre", "Aide",
+  ]),
+  ("it", [
+    "Copia", "Taglia", "Incolla", "Seleziona tutto", "Annulla", "Ripristina",
+    "Riduci a icona", "Ingrandisci", "Schermo intero", "Nascondi", "Nascondi altre",
+    "Mostra tutte", "Chiudi finestra", "Esci", "Informazioni su {name}", "Servizi",
+    "File", "Modifica", "Vista", "Finestra", "Aiuto",
+  ]),
+  ("ja", [
+    "コピー", "カット", "ペースト", "すべてを選択", "取り消す", "やり直す",
+    "最小化", "最大化", "フルスクリーン", "隠す", "ほかを隠す",
+    "すべてを表示", "ウインドウを閉じる", "終了", "{name}について", "サービス",
+    "ファイル", "編集", "表示", "ウインドウ", "ヘルプ",
+  ]),
+  ("ko", [
+    "복사", "잘라내기", "붙여넣기", "모두 선택", "실행 취소", "실행 복귀",
+    "최소화", "최대화", "전체 화면", "가리기", "기타 가리기",
+    "모두 보기", "윈도우 닫기", "종료", "{name}에 관하여", "서비스",
+    "파일", "편집", "보기", "윈도우", "도움말",
+  ]),
+  ("nl", [
+    "Kopieer", "Knip", "Plak", "Selecteer alles", "Herstel", "Opnieuw",
+    "Minimaliseer", "Maximaliseer", "Schermvullend", "Verberg", "Verberg andere",
+    "Toon alles", "Sluit venster", "Stop", "Over {name}", "Voorzieningen",
+    "Archief", "Wijzig", "Weergave", "Venster", "Help",
+  ]),
+  ("pt", [
+    "Copiar", "Recortar", "Colar", "Selecionar tudo", "Desfazer", "Refazer",
+    "Minimizar", "Maximizar", "Tela cheia", "Ocultar", "Ocultar outros",
+    "Mostrar tudo", "Fechar janela", "Sair", "Sobre {name}", "Serviços",
+    "Arquivo", "Editar", "Visualizar", "Janela", "Ajuda",
+  ]),
+  ("ru", [
+    "Копировать", "Вырезать", "Вставить", "Выбрать все", "Отменить", "Повторить",
+    "Свернуть", "Развернуть", "Полноэкранный режим", "Скрыть", "Скрыть остальные",
+    "Показать все", "Закрыть окно", "Завершить", "О программе {name}", "Службы",
+    "Файл", "Правка", "Вид", "Окно", "Справка",
+  ]),
+  ("zh", [
+    "复制", "剪切", "粘贴", "全选", "撤销", "重做",
+    "最小化", "最大化", "全屏", "隐藏", "隐藏其他",
+    "全部显示", "关闭窗口", "退出", "关于{name}", "服务",
+    "文件", "编辑", "视图", "窗口", "帮助",
+  ]),
+  ("zh-tw", [
+    "複製", "剪下", "貼上", "全選", "還原", "重做",
+    "最小化", "最大化", "全螢幕", "隱藏", "隱藏其他",
+    "顯示全部", "關閉視窗", "結束", "關於{name}", "服務",
+    "檔案", "編輯", "顯示方式", "視窗", "說明",
+  ]),
+];
+
+/// Roles whose text is localized by the system on macOS.
+fn is_system_localized(role: MenuRole) -> bool {
+  cfg!(target_os = "macos") && matches!(role, MenuRole::Services | MenuRole::Fullscreen)
+}
+
+/// Normalizes a locale such as `pt_BR.UTF-8` to a lowercase BCP 47 tag like `pt-br`.
+fn normalize(locale: &str) -> String {
+  locale
+    .split(|c: char| c == '.' || c == '@')
+    .next()
+    .unwrap_or_default()
+    .replace('_', "-")
+    .to_lowercase()
+}
+
+/// Finds the built-in translation of `role` for `locale`,
+/// removing its subtags one by one until a translation is found, e.g. `zh-hant-tw`, `zh-hant`, then `zh`.
+fn translate(role: MenuRole, locale: &str) -> Option<&'static str> {
+  let mut tag = normalize(locale);
+  loop {
+    let table = ALIASES
+      .iter()
+      .find(|(alias, _)| *alias == tag)
+      .map(|(_, target)| *target)
+      .unwrap_or(tag.as_str());
+    if let Some((_, texts)) = TRANSLATIONS.iter().find(|(locale, _)| *locale == table) {
+      return Some(texts[role as usize]);
+    }
+    tag.truncate(tag.rfind('-')?);
+  }
+}
+
+/// Resolves the text of `role`: the configured override first, then the translation for `locale`.
+///
+/// Returns `None` when the platform default (English) text should be used.
+fn resolve(
+  role: MenuRole,
+  locale: Option<&str>,
+  overrides: &HashMap<MenuRole, String>,
+  app_name: &str,
+) -> Option<String> {
+  let text = match overrides.get(&role) {
+    Some(text) => text.as_str(),
+    None if is_system_localized(role) => return None,
+    None => translate(role, locale?)?,
+  };
+  Some(text.replace("{name}", app_name))
+}
+
+/// Resolves the text of `role` for the given locale, defaulting to the `app > menu > locale` config,
+/// with the `app > menu > roleText` config overrides taking precedence.
+pub(crate) fn role_text<R: Runtime>(
+  app_handle: &AppHandle<R>,
+  role: MenuRole,
+  locale: Option<&str>,
+) -> Option<String> {
+  let config = &app_handle.config().app.menu;
+  resolve(
+    role,
+    locale.or(config.locale.as_deref()),
+    &config.role_text,
+    &app_handle.package_info().name,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn translations_are_complete() {
+    assert_eq!(MenuRole::HelpMenu as usize, 20);
+    for (_, texts) in TRANSLATIONS {
+      assert!(texts.iter().all(|text| !text.is_empty()));
+    }
+  }
+
+  #[test]
+  fn locale_fallback_chain() {
+    assert_eq!(translate(MenuRole::Copy, "de"), Some("Kopieren"));
+    assert_eq!(translate(MenuRole::Copy, "de-AT"), Some("Kopieren"));
+    assert_eq!(translate(MenuRole::Copy, "de_CH.UTF-8"), Some("Kopieren"));
+    assert_eq!(translate(MenuRole::Paste, "pt-BR"), Some("Colar"));
+    assert_eq!(translate(MenuRole::Quit, "zh-CN"), Some("退出"));
+    assert_eq!(translate(MenuRole::Quit, "zh-TW"), Some("結束"));
+    assert_eq!(translate(MenuRole::Quit, "zh-Hant-HK"), Some("結束"));
+    assert_eq!(translate(MenuRole::Quit, "zh-HK"), Some("結束"));
+    assert_eq!(translate(MenuRole::Copy, "en-US"), None);
+    assert_eq!(translate(MenuRole::Copy, "xx"), None);
+    assert_eq!(translate(MenuRole::Copy, ""), None);
+  }
+
+  #[test]
+  fn override_precedence() {
+    let mut overrides = HashMap::new();
+    overrides.insert(MenuRole::Quit, "Exit {name}".to_string());
+
+    // the override applies regardless of the locale
+    assert_eq!(
+      resolve(MenuRole::Quit, Some("de"), &overrides, "App"),
+      Some("Exit App".into())
+    );
+    assert_eq!(
+      resolve(MenuRole::Quit, None, &overrides, "App"),
+      Some("Exit App".into())
+    );
+    // other roles use the locale translation, then the platform default
+    assert_eq!(
+      resolve(MenuRole::About, Some("de"), &overrides, "App"),
+      Some("Über App".into())
+    );
+    assert_eq!(
+      resolve(MenuRole::About, Some("en"), &overrides, "App"),
+      None
+    );
+    assert_eq!(resolve(MenuRole::About, None, &overrides, "App"), None);
+  }
+}
@@ -4,12 +4,14 @@
 
 use std::sync::Arc;
 
+use super::locale::role_text;
 use super::run_item_main_thread;
 use super::sealed::ContextMenuBase;
 use super::{
   AboutMetadata, IsMenuItem, Menu, MenuInner, MenuItemKind, PredefinedMenuItem, Submenu,
 };
 use crate::run_main_thread;
+use crate::utils::config::MenuRole;
 use crate::Window;
 use crate::{AppHandle, Manager, Position, Runtime};
 use muda::ContextMenu;
@@ -136,7 +138,30 @@ impl<R: Runtime> Menu<R> {
   }
 
   /// Creates a menu filled with default menu items and submenus.
+  ///
+  /// The text of the items is translated to the `app > menu > locale` config if set,
+  /// see [`Self::default_with_locale`].
   pub fn default(app_handle: &AppHandle<R>) -> crate::Result<Self> {
+    Self::default_impl(app_handle, None)
+  }
+
+  /// Creates a menu filled with default menu items and submenus, with their text translated to the given locale,
+  /// e.g. `de` or `pt-BR`.
+  ///
+  /// Falls back to the language without its region, then to the platform default (English) text.
+  /// The `app > menu > roleText` config overrides take precedence over the translations.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **macOS:** The system provided text is kept for the Services and Toggle Full Screen items.
+  pub fn default_with_locale(app_handle: &AppHandle<R>, locale: &str) -> crate::Result<Self> {
+    Self::default_impl(app_handle, Some(locale))
+  }
+
+  fn default_impl(app_handle: &AppHandle<R>, locale: Option<&str>) -> crate::Result<Self> {
+    let text = |role| role_text(app_handle, role, locale);
+    let submenu_text = |role, default: &str| text(role).unwrap_or_else(|| default.to_string());
+
     let pkg_info = app_handle.package_info();
     let config = app_handle.config();
     let about_metadata = AboutMetadata {
@@ -150,25 +175,29 @@ impl<R: Runtime> Menu<R> {
     let window_menu = Submenu::with_id_and_items(
       app_handle,
       WINDOW_SUBMENU_ID,
-      "Window",
+      submenu_text(MenuRole::WindowMenu, "Window"),
       true,
       &[
-        &PredefinedMenuItem::minimize(app_handle, None)?,
-        &PredefinedMenuItem::maximize(app_handle, None)?,
+        &PredefinedMenuItem::minimize(app_handle, text(MenuRole::Minimize).as_deref())?,
+        &PredefinedMenuItem::maximize(app_handle, text(MenuRole::Maximize).as_deref())?,
         #[cfg(target_os = "macos")]
         &PredefinedMenuItem::separator(app_handle)?,
-        &PredefinedMenuItem::close_window(app_handle, None)?,
+        &PredefinedMenuItem::close_window(app_handle, text(MenuRole::CloseWindow).as_deref())?,
       ],
     )?;
 
     let help_menu = Submenu::with_id_and_items(
       app_handle,
       HELP_SUBMENU_ID,
-      "Help",
+      submenu_text(MenuRole::HelpMenu, "Help"),
       true,
       &[
         #[cfg(not(target_os = "macos"))]
-        &PredefinedMenuItem::about(app_handle, None, Some(about_metadata))?,
+        &PredefinedMenuItem::about(
+          app_handle,
+          text(MenuRole::About).as_deref(),
+          Some(about_metadata),
+        )?,
       ],
     )?;
 
@@ -181,14 +210,18 @@ impl<R: Runtime> Menu<R> {
           pkg_info.name.clone(),
           true,
           &[
-            &PredefinedMenuItem::about(app_handle, None, Some(about_metadata))?,
+            &PredefinedMenuItem::about(
+              app_handle,
+              text(MenuRole::About).as_deref(),
+              Some(about_metadata),
+            )?,
             &PredefinedMenuItem::separator(app_handle)?,
-            &PredefinedMenuItem::services(app_handle, None)?,
+            &PredefinedMenuItem::services(app_handle, text(MenuRole::Services).as_deref())?,
             &PredefinedMenuItem::separator(app_handle)?,
-            &PredefinedMenuItem::hide(app_handle, None)?,
-            &PredefinedMenuItem::hide_others(app_handle, None)?,
+            &PredefinedMenuItem::hide(app_handle, text(MenuRole::Hide).as_deref())?,
+            &PredefinedMenuItem::hide_others(app_handle, text(MenuRole::HideOthers).as_deref())?,
             &PredefinedMenuItem::separator(app_handle)?,
-            &PredefinedMenuItem::quit(app_handle, None)?,
+            &PredefinedMenuItem::quit(app_handle, text(MenuRole::Quit).as_deref())?,
           ],
         )?,
         #[cfg(not(any(
@@ -200,34 +233,37 @@ impl<R: Runtime> Menu<R> {
         )))]
         &Submenu::with_items(
           app_handle,
-          "File",
+          submenu_text(MenuRole::FileMenu, "File"),
           true,
           &[
-            &PredefinedMenuItem::close_window(app_handle, None)?,
+            &PredefinedMenuItem::close_window(app_handle, text(MenuRole::CloseWindow).as_deref())?,
             #[cfg(not(target_os = "macos"))]
-            &PredefinedMenuItem::quit(app_handle, None)?,
+            &PredefinedMenuItem::quit(app_handle, text(MenuRole::Quit).as_deref())?,
           ],
         )?,
         &Submenu::with_items(
           app_handle,
-          "Edit",
+          submenu_text(MenuRole::EditMenu, "Edit"),
           true,
           &[
-            &PredefinedMenuItem::undo(app_handle, None)?,
-            &PredefinedMenuItem::redo(app_handle, None)?,
+            &PredefinedMenuItem::undo(app_handle, text(MenuRole::Undo).as_deref())?,
+            &PredefinedMenuItem::redo(app_handle, text(MenuRole::Redo).as_deref())?,
             &PredefinedMenuItem::separator(app_handle)?,
-            &PredefinedMenuItem::cut(app_handle, None)?,
-            &PredefinedMenuItem::copy(app_handle, None)?,
-            &PredefinedMenuItem::paste(app_handle, None)?,
-            &PredefinedMenuItem::select_all(app_handle, None)?,
+            &PredefinedMenuItem::cut(app_handle, text(MenuRole::Cut).as_deref())?,
+            &PredefinedMenuItem::copy(app_handle, text(MenuRole::Copy).as_deref())?,
+            &PredefinedMenuItem::paste(app_handle, text(MenuRole::Paste).as_deref())?,
+            &PredefinedMenuItem::select_all(app_handle, text(MenuRole::SelectAll).as_deref())?,
           ],
         )?,
         #[cfg(target_os = "macos")]
         &Submenu::with_items(
           app_handle,
-          "View",
+          submenu_text(MenuRole::ViewMenu, "View"),
           true,
-          &[&PredefinedMenuItem::fullscreen(app_handle, None)?],
+          &[&PredefinedMenuItem::fullscreen(
+            app_handle,
+            text(MenuRole::Fullscreen).as_deref(),
+          )?],
         )?,
         &window_menu,
         &help_menu,
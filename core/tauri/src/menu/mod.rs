@@ -7,6 +7,7 @@
 mod builders;
 mod check;
 mod icon;
+mod locale;
 #[allow(clippy::module_inception)]
 mod menu;
 mod normal;
@@ -152,8 +153,6 @@ gen_wrappers!(
   MenuItem(MenuItemInner, MenuItem),
   /// A type that is a submenu inside a [`Menu`] or [`Submenu`]
   Submenu(SubmenuInner, Submenu),
-  /// A predefined (native) menu item which has a predefined behavior by the OS or by this crate.
-  PredefinedMenuItem(PredefinedMenuItemInner, Predefined),
   /// A menu item inside a [`Menu`] or [`Submenu`]
   /// and usually contains a text and a check mark or a similar toggle
   /// that corresponds to a checked and unchecked states.
@@ -163,6 +162,166 @@ gen_wrappers!(
   IconMenuItem(IconMenuItemInner, Icon)
 );
 
+/// The menu item backing a [`PredefinedMenuItem`].
+#[derive(Clone)]
+pub(crate) enum PredefinedMenuItemKind {
+  /// A native item whose behavior is implemented by the OS.
+  Native(muda::PredefinedMenuItem),
+  /// A normal item emitting menu events, whose behavior is implemented by [`PredefinedMenuAction`].
+  Routed(muda::MenuItem),
+}
+
+impl PredefinedMenuItemKind {
+  fn inner_muda(&self) -> &dyn muda::IsMenuItem {
+    match self {
+      Self::Native(i) => i,
+      Self::Routed(i) => i,
+    }
+  }
+
+  pub(crate) fn text(&self) -> String {
+    match self {
+      Self::Native(i) => i.text(),
+      Self::Routed(i) => i.text(),
+    }
+  }
+
+  pub(crate) fn set_text(&self, text: &str) {
+    match self {
+      Self::Native(i) => i.set_text(text),
+      Self::Routed(i) => i.set_text(text),
+    }
+  }
+}
+
+#[tauri_macros::default_runtime(crate::Wry, wry)]
+pub(crate) struct PredefinedMenuItemInner<R: Runtime> {
+  id: MenuId,
+  inner: Option<PredefinedMenuItemKind>,
+  app_handle: AppHandle<R>,
+}
+
+/// # Safety
+///
+/// We make sure it always runs on the main thread.
+unsafe impl<R: Runtime> Sync for PredefinedMenuItemInner<R> {}
+unsafe impl<R: Runtime> Send for PredefinedMenuItemInner<R> {}
+
+impl<R: Runtime> crate::Resource for PredefinedMenuItem<R> {}
+
+impl<R: Runtime> Clone for PredefinedMenuItemInner<R> {
+  fn clone(&self) -> Self {
+    Self {
+      id: self.id.clone(),
+      inner: self.inner.clone(),
+      app_handle: self.app_handle.clone(),
+    }
+  }
+}
+
+impl<R: Runtime> Drop for PredefinedMenuItemInner<R> {
+  fn drop(&mut self) {
+    struct SafeSend<T>(T);
+    unsafe impl<T> Send for SafeSend<T> {}
+
+    let inner = self.inner.take();
+    let inner = SafeSend(inner);
+    let _ = self.app_handle.run_on_main_thread(move || {
+      drop(inner);
+    });
+  }
+}
+
+impl<R: Runtime> AsRef<PredefinedMenuItemKind> for PredefinedMenuItemInner<R> {
+  fn as_ref(&self) -> &PredefinedMenuItemKind {
+    self.inner.as_ref().unwrap()
+  }
+}
+
+/// A predefined menu item which has a predefined behavior by the OS or by this crate.
+///
+/// When no text is given, the text is taken from the `app > menu > roleText` config
+/// or translated to the `app > menu > locale` config, defaulting to the platform text.
+///
+/// The [`quit`](Self::quit) and [`close_window`](Self::close_window) items emit menu events
+/// and run a [`PredefinedMenuAction`]: they go through the [`RunEvent::ExitRequested`](crate::RunEvent::ExitRequested)
+/// and [`WindowEvent::CloseRequested`](crate::WindowEvent::CloseRequested) events like [`AppHandle::exit`] and [`Window::close`](crate::Window::close),
+/// and the action can be overridden with [`AppHandle::on_predefined_menu_event`].
+/// All the other items are native: they do not emit menu events and their behavior is implemented by the OS,
+/// for instance the macOS services menu, hide, show all, minimize and the clipboard items.
+pub struct PredefinedMenuItem<R: Runtime>(Arc<PredefinedMenuItemInner<R>>);
+
+impl<R: Runtime> Clone for PredefinedMenuItem<R> {
+  fn clone(&self) -> Self {
+    Self(self.0.clone())
+  }
+}
+
+impl<R: Runtime> sealed::IsMenuItemBase for PredefinedMenuItem<R> {
+  fn inner_muda(&self) -> &dyn muda::IsMenuItem {
+    (*self.0).as_ref().inner_muda()
+  }
+}
+
+impl<R: Runtime> IsMenuItem<R> for PredefinedMenuItem<R> {
+  fn kind(&self) -> MenuItemKind<R> {
+    MenuItemKind::Predefined(self.clone())
+  }
+
+  fn id(&self) -> &MenuId {
+    &self.0.id
+  }
+}
+
+/// The behavior of a [`PredefinedMenuItem`] implemented by this crate instead of the OS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PredefinedMenuAction {
+  /// Exits the app with [`AppHandle::exit`], emitting [`RunEvent::ExitRequested`](crate::RunEvent::ExitRequested).
+  Quit,
+  /// Closes the focused window with [`Window::close`](crate::Window::close),
+  /// emitting [`WindowEvent::CloseRequested`](crate::WindowEvent::CloseRequested).
+  CloseWindow,
+}
+
+/// Describes the activation of a [`PredefinedMenuItem`] running a [`PredefinedMenuAction`].
+#[derive(Debug)]
+pub struct PredefinedMenuEvent {
+  id: MenuId,
+  action: PredefinedMenuAction,
+  prevented: std::cell::Cell<bool>,
+}
+
+impl PredefinedMenuEvent {
+  pub(crate) fn new(id: MenuId, action: PredefinedMenuAction) -> Self {
+    Self {
+      id,
+      action,
+      prevented: Default::default(),
+    }
+  }
+
+  /// Returns the id of the menu item which triggered this event.
+  pub fn id(&self) -> &MenuId {
+    &self.id
+  }
+
+  /// Returns the action run once all the handlers are called, unless prevented.
+  pub fn action(&self) -> PredefinedMenuAction {
+    self.action
+  }
+
+  /// Prevents the default action from running, for instance to ask for confirmation before quitting.
+  pub fn prevent_default(&self) {
+    self.prevented.set(true);
+  }
+
+  /// Whether [`Self::prevent_default`] was called.
+  pub fn is_default_prevented(&self) -> bool {
+    self.prevented.get()
+  }
+}
+
 /// Application metadata for the [`PredefinedMenuItem::about`].
 #[derive(Debug, Clone, Default)]
 pub struct AboutMetadata<'a> {
@@ -574,6 +733,15 @@ impl<R: Runtime> MenuItemKind<R> {
 
   pub(crate) fn from_muda(app_handle: AppHandle<R>, i: muda::MenuItemKind) -> Self {
     match i {
+      muda::MenuItemKind::MenuItem(i)
+        if app_handle.manager.menu.predefined_action(i.id()).is_some() =>
+      {
+        Self::Predefined(PredefinedMenuItem(Arc::new(PredefinedMenuItemInner {
+          id: i.id().clone(),
+          inner: Some(PredefinedMenuItemKind::Routed(i)),
+          app_handle,
+        })))
+      }
       muda::MenuItemKind::MenuItem(i) => Self::MenuItem(MenuItem(Arc::new(MenuItemInner {
         id: i.id().clone(),
         inner: i.into(),
@@ -587,7 +755,7 @@ impl<R: Runtime> MenuItemKind<R> {
       muda::MenuItemKind::Predefined(i) => {
         Self::Predefined(PredefinedMenuItem(Arc::new(PredefinedMenuItemInner {
           id: i.id().clone(),
-          inner: i.into(),
+          inner: Some(PredefinedMenuItemKind::Native(i)),
           app_handle,
         })))
       }
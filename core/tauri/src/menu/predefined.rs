@@ -4,11 +4,12 @@
 
 use std::sync::Arc;
 
+use super::locale::role_text;
 use super::run_item_main_thread;
 use super::{AboutMetadata, PredefinedMenuItem};
-use crate::menu::PredefinedMenuItemInner;
+use crate::menu::{PredefinedMenuAction, PredefinedMenuItemInner, PredefinedMenuItemKind};
 use crate::run_main_thread;
-use crate::{menu::MenuId, AppHandle, Manager, Runtime};
+use crate::{menu::MenuId, utils::config::MenuRole, AppHandle, Manager, Runtime};
 
 impl<R: Runtime> PredefinedMenuItem<R> {
   /// Separator menu item
@@ -20,7 +21,7 @@ impl<R: Runtime> PredefinedMenuItem<R> {
       let item = muda::PredefinedMenuItem::separator();
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -33,13 +34,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Copy, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::copy(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -52,13 +55,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Cut, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::cut(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -71,13 +76,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Paste, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::paste(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -90,13 +97,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::SelectAll, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::select_all(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -113,13 +122,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Undo, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::undo(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -135,13 +146,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Redo, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::redo(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -158,13 +171,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Minimize, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::minimize(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -181,13 +196,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Maximize, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::maximize(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -204,13 +221,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Fullscreen, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::fullscreen(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -227,13 +246,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Hide, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::hide(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -250,13 +271,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::HideOthers, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::hide_others(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -273,13 +296,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::ShowAll, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::show_all(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -289,46 +314,59 @@ impl<R: Runtime> PredefinedMenuItem<R> {
 
   /// Close window menu item
   ///
-  /// ## Platform-specific:
-  ///
-  /// - **Linux:** Unsupported.
+  /// Closes the focused window with [`Window::close`](crate::Window::close) when activated,
+  /// see [`PredefinedMenuAction::CloseWindow`].
   pub fn close_window<M: Manager<R>>(manager: &M, text: Option<&str>) -> crate::Result<Self> {
     let handle = manager.app_handle();
-    let app_handle = handle.clone();
-
-    let text = text.map(|t| t.to_owned());
-
-    let item = run_main_thread!(handle, || {
-      let item = muda::PredefinedMenuItem::close_window(text.as_deref());
-      PredefinedMenuItemInner {
-        id: item.id().clone(),
-        inner: Some(item),
-        app_handle,
-      }
-    })?;
-
-    Ok(Self(Arc::new(item)))
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::CloseWindow, None))
+      .unwrap_or_else(|| "Close Window".into());
+    Self::routed(handle, PredefinedMenuAction::CloseWindow, text, "Cmd+W")
   }
 
   /// Quit app menu item
   ///
-  /// ## Platform-specific:
-  ///
-  /// - **Linux:** Unsupported.
+  /// Exits the app with [`AppHandle::exit`] when activated, see [`PredefinedMenuAction::Quit`].
   pub fn quit<M: Manager<R>>(manager: &M, text: Option<&str>) -> crate::Result<Self> {
     let handle = manager.app_handle();
-    let app_handle = handle.clone();
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Quit, None))
+      .unwrap_or_else(|| {
+        if cfg!(target_os = "macos") {
+          format!("Quit {}", handle.package_info().name)
+        } else {
+          "Quit".into()
+        }
+      });
+    Self::routed(handle, PredefinedMenuAction::Quit, text, "Cmd+Q")
+  }
 
-    let text = text.map(|t| t.to_owned());
+  /// Creates an item emitting menu events and running `action`,
+  /// with the `macos_accelerator` of the matching native item on macOS.
+  fn routed(
+    handle: &AppHandle<R>,
+    action: PredefinedMenuAction,
+    text: String,
+    macos_accelerator: &str,
+  ) -> crate::Result<Self> {
+    let app_handle = handle.clone();
+    let accelerator = if cfg!(target_os = "macos") {
+      macos_accelerator.parse().ok()
+    } else {
+      None
+    };
 
     let item = run_main_thread!(handle, || {
-      let item = muda::PredefinedMenuItem::quit(text.as_deref());
+      let item = muda::MenuItem::new(text, true, accelerator);
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Routed(item)),
         app_handle,
       }
     })?;
+    handle.manager.menu.set_predefined_action(&item.id, action);
 
     Ok(Self(Arc::new(item)))
   }
@@ -342,7 +380,9 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::About, None));
 
     let metadata = match metadata {
       Some(m) => Some(m.try_into()?),
@@ -353,7 +393,7 @@ impl<R: Runtime> PredefinedMenuItem<R> {
       let item = muda::PredefinedMenuItem::about(text.as_deref(), metadata);
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -370,13 +410,15 @@ impl<R: Runtime> PredefinedMenuItem<R> {
     let handle = manager.app_handle();
     let app_handle = handle.clone();
 
-    let text = text.map(|t| t.to_owned());
+    let text = text
+      .map(|t| t.to_owned())
+      .or_else(|| role_text(handle, MenuRole::Services, None));
 
     let item = run_main_thread!(handle, || {
       let item = muda::PredefinedMenuItem::services(text.as_deref());
       PredefinedMenuItemInner {
         id: item.id().clone(),
-        inner: Some(item),
+        inner: Some(PredefinedMenuItemKind::Native(item)),
         app_handle,
       }
     })?;
@@ -399,7 +441,13 @@ impl<R: Runtime> PredefinedMenuItem<R> {
   /// for this menu item. To display a `&` without assigning a mnemenonic, use `&&`.
   pub fn set_text<S: AsRef<str>>(&self, text: S) -> crate::Result<()> {
     let text = text.as_ref().to_string();
-    run_item_main_thread!(self, |self_: Self| (*self_.0).as_ref().set_text(text))
+    run_item_main_thread!(self, |self_: Self| (*self_.0).as_ref().set_text(&text))
+  }
+
+  /// The action run when this item is activated,
+  /// or `None` if its behavior is implemented by the OS.
+  pub fn action(&self) -> Option<PredefinedMenuAction> {
+    self.0.app_handle.manager.menu.predefined_action(&self.0.id)
   }
 
   /// The application handle associated with this type.
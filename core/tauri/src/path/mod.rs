@@ -318,6 +318,15 @@ fn resolve_path<R: Runtime>(
           Component::Normal(p) => resource_path.push(p),
         }
       }
+
+      #[cfg(all(dev, not(target_os = "android")))]
+      if let Some(dev_resources) = &resolver.0.manager.dev_resources {
+        let strict = matches!(std::env::var(DEV_RESOURCES_STRICT_ENV).as_deref(), Ok("1"));
+        if let Some(src) = resolve_dev_resource(dev_resources, &resource_path, strict)? {
+          return Ok(src);
+        }
+      }
+
       base_dir_path.push(resource_path);
     } else {
       base_dir_path.push(path);
@@ -327,6 +336,38 @@ fn resolve_path<R: Runtime>(
   Ok(base_dir_path)
 }
 
+/// Makes resolving a resource that is not declared in the bundle resources an error in development.
+#[cfg(all(dev, not(target_os = "android")))]
+const DEV_RESOURCES_STRICT_ENV: &str = "TAURI_DEV_RESOURCES_STRICT";
+
+/// Looks up a resource in the map of resource targets to their source paths recorded at build time.
+///
+/// Returns `None` when the resource should be resolved from the resource directory instead,
+/// which is the case for directories of resources and, unless `strict` is set, undeclared resources.
+#[cfg(all(dev, not(target_os = "android")))]
+fn resolve_dev_resource(
+  dev_resources: &std::collections::HashMap<PathBuf, PathBuf>,
+  resource_path: &Path,
+  strict: bool,
+) -> Result<Option<PathBuf>> {
+  if resource_path.as_os_str().is_empty() {
+    return Ok(None);
+  }
+
+  if let Some(src) = dev_resources.get(resource_path) {
+    return Ok(Some(src.clone()));
+  }
+
+  let is_declared_dir = dev_resources
+    .keys()
+    .any(|target| target.starts_with(resource_path));
+  if strict && !is_declared_dir {
+    return Err(Error::UndeclaredResource(resource_path.to_path_buf()));
+  }
+
+  Ok(None)
+}
+
 #[cfg(test)]
 mod test {
   use super::SafePathBuf;
@@ -334,6 +375,62 @@ mod test {
 
   use std::path::PathBuf;
 
+  #[cfg(all(dev, not(target_os = "android")))]
+  fn fixture_dev_resources() -> std::collections::HashMap<PathBuf, PathBuf> {
+    [
+      ("config.toml", "/app/src-tauri/config.toml"),
+      ("assets/logo.png", "/app/src-tauri/images/logo.png"),
+      ("_up_/shared/data.json", "/app/shared/data.json"),
+    ]
+    .into_iter()
+    .map(|(target, src)| (PathBuf::from(target), PathBuf::from(src)))
+    .collect()
+  }
+
+  #[cfg(all(dev, not(target_os = "android")))]
+  #[test]
+  fn resolves_declared_dev_resources_to_their_source() {
+    let resources = fixture_dev_resources();
+    for strict in [false, true] {
+      assert_eq!(
+        super::resolve_dev_resource(&resources, "config.toml".as_ref(), strict).unwrap(),
+        Some(PathBuf::from("/app/src-tauri/config.toml"))
+      );
+      assert_eq!(
+        super::resolve_dev_resource(&resources, "assets/logo.png".as_ref(), strict).unwrap(),
+        Some(PathBuf::from("/app/src-tauri/images/logo.png"))
+      );
+      assert_eq!(
+        super::resolve_dev_resource(&resources, "_up_/shared/data.json".as_ref(), strict).unwrap(),
+        Some(PathBuf::from("/app/shared/data.json"))
+      );
+      // directories fall back to the resource directory
+      assert_eq!(
+        super::resolve_dev_resource(&resources, "assets".as_ref(), strict).unwrap(),
+        None
+      );
+    }
+  }
+
+  #[cfg(all(dev, not(target_os = "android")))]
+  #[test]
+  fn undeclared_dev_resources() {
+    let resources = fixture_dev_resources();
+    assert_eq!(
+      super::resolve_dev_resource(&resources, "missing.txt".as_ref(), false).unwrap(),
+      None
+    );
+    assert!(matches!(
+      super::resolve_dev_resource(&resources, "missing.txt".as_ref(), true),
+      Err(crate::Error::UndeclaredResource(path)) if path == PathBuf::from("missing.txt")
+    ));
+    // the resource directory itself is always resolvable
+    assert_eq!(
+      super::resolve_dev_resource(&resources, "".as_ref(), true).unwrap(),
+      None
+    );
+  }
+
   impl Arbitrary for SafePathBuf {
     fn arbitrary(g: &mut Gen) -> Self {
       Self(PathBuf::arbitrary(g))
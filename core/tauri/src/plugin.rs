@@ -6,11 +6,11 @@
 
 use crate::{
   app::UriSchemeResponder,
-  ipc::{Invoke, InvokeHandler, ScopeObject, ScopeValue},
+  ipc::{CommandState, Invoke, InvokeHandler, ScopeObject, ScopeValue},
   manager::webview::UriSchemeProtocol,
   utils::config::PluginConfig,
   webview::PageLoadPayload,
-  AppHandle, Error, RunEvent, Runtime, Webview, Window,
+  AppHandle, Error, EventAction, RunEvent, Runtime, Webview, WebviewEvent, Window, WindowEvent,
 };
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
@@ -74,6 +74,22 @@ pub trait Plugin<R: Runtime>: Send {
   #[allow(unused_variables)]
   fn on_page_load(&mut self, webview: &Webview<R>, payload: &PageLoadPayload<'_>) {}
 
+  /// Callback invoked when a window receives an event.
+  ///
+  /// Returning [`EventAction::StopPropagation`] skips the handlers of the next plugins and the app.
+  #[allow(unused_variables)]
+  fn on_window_event(&mut self, window: &Window<R>, event: &WindowEvent) -> EventAction {
+    EventAction::Continue
+  }
+
+  /// Callback invoked when a webview receives an event.
+  ///
+  /// Returning [`EventAction::StopPropagation`] skips the handlers of the next plugins and the app.
+  #[allow(unused_variables)]
+  fn on_webview_event(&mut self, webview: &Webview<R>, event: &WebviewEvent) -> EventAction {
+    EventAction::Continue
+  }
+
   /// Callback invoked when the event loop receives a new event.
   #[allow(unused_variables)]
   fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {}
@@ -89,6 +105,8 @@ type SetupHook<R, C> =
   dyn FnOnce(&AppHandle<R>, PluginApi<R, C>) -> Result<(), Box<dyn std::error::Error>> + Send;
 type OnWindowReady<R> = dyn FnMut(Window<R>) + Send;
 type OnWebviewReady<R> = dyn FnMut(Webview<R>) + Send;
+type OnWindowEvent<R> = dyn FnMut(&Window<R>, &WindowEvent) -> EventAction + Send;
+type OnWebviewEvent<R> = dyn FnMut(&Webview<R>, &WebviewEvent) -> EventAction + Send;
 type OnEvent<R> = dyn FnMut(&AppHandle<R>, &RunEvent) + Send;
 type OnNavigation<R> = dyn Fn(&Webview<R>, &Url) -> bool + Send;
 type OnPageLoad<R> = dyn FnMut(&Webview<R>, &PageLoadPayload<'_>) + Send;
@@ -238,12 +256,16 @@ const RESERVED_PLUGIN_NAMES: &[&str] = &["core", "tauri"];
 pub struct Builder<R: Runtime, C: DeserializeOwned = ()> {
   name: &'static str,
   invoke_handler: Box<InvokeHandler<R>>,
+  #[cfg(debug_assertions)]
+  command_states: Vec<CommandState>,
   setup: Option<Box<SetupHook<R, C>>>,
   js_init_script: Option<String>,
   on_navigation: Box<OnNavigation<R>>,
   on_page_load: Box<OnPageLoad<R>>,
   on_window_ready: Box<OnWindowReady<R>>,
   on_webview_ready: Box<OnWebviewReady<R>>,
+  on_window_event: Box<OnWindowEvent<R>>,
+  on_webview_event: Box<OnWebviewEvent<R>>,
   on_event: Box<OnEvent<R>>,
   on_drop: Option<Box<OnDrop<R>>>,
   uri_scheme_protocols: HashMap<String, Arc<UriSchemeProtocol<R>>>,
@@ -257,10 +279,14 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
       setup: None,
       js_init_script: None,
       invoke_handler: Box::new(|_| false),
+      #[cfg(debug_assertions)]
+      command_states: Vec::new(),
       on_navigation: Box::new(|_, _| true),
       on_page_load: Box::new(|_, _| ()),
       on_window_ready: Box::new(|_| ()),
       on_webview_ready: Box::new(|_| ()),
+      on_window_event: Box::new(|_, _| EventAction::Continue),
+      on_webview_event: Box::new(|_, _| EventAction::Continue),
       on_event: Box::new(|_, _| ()),
       on_drop: None,
       uri_scheme_protocols: Default::default(),
@@ -299,6 +325,37 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self
   }
 
+  /// Declares the managed state types requested by the commands of the invoke handler,
+  /// logging a warning at startup for each state that is not managed after the app setup in debug builds.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// struct Counter;
+  ///
+  /// #[tauri::command]
+  /// fn count(counter: tauri::State<'_, Counter>) {}
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .invoke_handler(tauri::generate_handler![count])
+  ///     .command_states(tauri::generate_command_states![count])
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn command_states(mut self, command_states: Vec<CommandState>) -> Self {
+    #[cfg(debug_assertions)]
+    {
+      self.command_states = command_states;
+    }
+    #[cfg(not(debug_assertions))]
+    drop(command_states);
+    self
+  }
+
   /// Sets the provided JavaScript to be run after the global object has been created,
   /// but before the HTML document has been parsed and before any other script included by the HTML document is run.
   ///
@@ -466,6 +523,69 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self
   }
 
+  /// Callback invoked when a window receives an event.
+  ///
+  /// Plugin handlers run in the plugin registration order, before the [`crate::Builder::on_window_event`] handlers.
+  /// Return [`EventAction::StopPropagation`] to skip the handlers registered after this one.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime, WindowEvent};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .on_window_event(|window, event| {
+  ///       if let WindowEvent::Focused(true) = event {
+  ///         println!("{} focused", window.label());
+  ///       }
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn on_window_event<F, A>(mut self, mut on_window_event: F) -> Self
+  where
+    F: FnMut(&Window<R>, &WindowEvent) -> A + Send + 'static,
+    A: Into<EventAction>,
+  {
+    self.on_window_event = Box::new(move |window, event| on_window_event(window, event).into());
+    self
+  }
+
+  /// Callback invoked when a webview receives an event.
+  ///
+  /// Plugin handlers run in the plugin registration order, before the [`crate::Builder::on_webview_event`] handlers.
+  /// Return [`EventAction::StopPropagation`] to skip the handlers registered after this one.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, DragDropEvent, EventAction, Runtime, WebviewEvent};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .on_webview_event(|_webview, event| match event {
+  ///       // handles the drop and hides it from the app handlers
+  ///       WebviewEvent::DragDrop(DragDropEvent::Drop { paths, .. }) => {
+  ///         println!("{paths:?}");
+  ///         EventAction::StopPropagation
+  ///       }
+  ///       _ => EventAction::Continue,
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn on_webview_event<F, A>(mut self, mut on_webview_event: F) -> Self
+  where
+    F: FnMut(&Webview<R>, &WebviewEvent) -> A + Send + 'static,
+    A: Into<EventAction>,
+  {
+    self.on_webview_event = Box::new(move |webview, event| on_webview_event(webview, event).into());
+    self
+  }
+
   /// Callback invoked when the event loop receives a new event.
   ///
   /// # Examples
@@ -638,12 +758,16 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
       name: self.name,
       app: None,
       invoke_handler: self.invoke_handler,
+      #[cfg(debug_assertions)]
+      command_states: self.command_states,
       setup: self.setup,
       js_init_script: self.js_init_script,
       on_navigation: self.on_navigation,
       on_page_load: self.on_page_load,
       on_window_ready: self.on_window_ready,
       on_webview_ready: self.on_webview_ready,
+      on_window_event: self.on_window_event,
+      on_webview_event: self.on_webview_event,
       on_event: self.on_event,
       on_drop: self.on_drop,
       uri_scheme_protocols: self.uri_scheme_protocols,
@@ -665,12 +789,16 @@ pub struct TauriPlugin<R: Runtime, C: DeserializeOwned = ()> {
   name: &'static str,
   app: Option<AppHandle<R>>,
   invoke_handler: Box<InvokeHandler<R>>,
+  #[cfg(debug_assertions)]
+  command_states: Vec<CommandState>,
   setup: Option<Box<SetupHook<R, C>>>,
   js_init_script: Option<String>,
   on_navigation: Box<OnNavigation<R>>,
   on_page_load: Box<OnPageLoad<R>>,
   on_window_ready: Box<OnWindowReady<R>>,
   on_webview_ready: Box<OnWebviewReady<R>>,
+  on_window_event: Box<OnWindowEvent<R>>,
+  on_webview_event: Box<OnWebviewEvent<R>>,
   on_event: Box<OnEvent<R>>,
   on_drop: Option<Box<OnDrop<R>>>,
   uri_scheme_protocols: HashMap<String, Arc<UriSchemeProtocol<R>>>,
@@ -718,6 +846,15 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
         .webview
         .register_uri_scheme_protocol(uri_scheme, protocol.clone())
     }
+
+    #[cfg(debug_assertions)]
+    app
+      .manager
+      .command_states
+      .lock()
+      .unwrap()
+      .extend(std::mem::take(&mut self.command_states));
+
     Ok(())
   }
 
@@ -741,6 +878,14 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
     (self.on_page_load)(webview, payload)
   }
 
+  fn on_window_event(&mut self, window: &Window<R>, event: &WindowEvent) -> EventAction {
+    (self.on_window_event)(window, event)
+  }
+
+  fn on_webview_event(&mut self, webview: &Webview<R>, event: &WebviewEvent) -> EventAction {
+    (self.on_webview_event)(webview, event)
+  }
+
   fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
     (self.on_event)(app, event)
   }
@@ -861,6 +1006,36 @@ impl<R: Runtime> PluginStore<R> {
     })
   }
 
+  /// Runs the on_window_event hook for the plugins in the store until one stops the propagation.
+  pub(crate) fn on_window_event(&mut self, window: &Window<R>, event: &WindowEvent) -> EventAction {
+    for plugin in self.store.iter_mut() {
+      #[cfg(feature = "tracing")]
+      let _span =
+        tracing::trace_span!("plugin::hooks::on_window_event", name = plugin.name()).entered();
+      if plugin.on_window_event(window, event) == EventAction::StopPropagation {
+        return EventAction::StopPropagation;
+      }
+    }
+    EventAction::Continue
+  }
+
+  /// Runs the on_webview_event hook for the plugins in the store until one stops the propagation.
+  pub(crate) fn on_webview_event(
+    &mut self,
+    webview: &Webview<R>,
+    event: &WebviewEvent,
+  ) -> EventAction {
+    for plugin in self.store.iter_mut() {
+      #[cfg(feature = "tracing")]
+      let _span =
+        tracing::trace_span!("plugin::hooks::on_webview_event", name = plugin.name()).entered();
+      if plugin.on_webview_event(webview, event) == EventAction::StopPropagation {
+        return EventAction::StopPropagation;
+      }
+    }
+    EventAction::Continue
+  }
+
   /// Runs the on_event hook for all plugins in the store.
   pub(crate) fn on_event(&mut self, app: &AppHandle<R>, event: &RunEvent) {
     self
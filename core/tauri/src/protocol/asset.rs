@@ -4,33 +4,64 @@
 
 use crate::{path::SafePathBuf, scope, webview::UriSchemeProtocolHandler};
 use http::{header::*, status::StatusCode, Request, Response};
-use http_range::HttpRange;
-use std::{borrow::Cow, io::SeekFrom};
+use http_range::{HttpRange, HttpRangeParseError};
+use std::io::SeekFrom;
 use tauri_utils::mime_type::MimeType;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
 pub fn get(scope: scope::fs::Scope, window_origin: String) -> UriSchemeProtocolHandler {
-  Box::new(
-    move |request, responder| match get_response(request, &scope, &window_origin) {
-      Ok(response) => responder.respond(response),
-      Err(e) => responder.respond(
-        http::Response::builder()
-          .status(http::StatusCode::BAD_REQUEST)
-          .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
-          .header("Access-Control-Allow-Origin", &window_origin)
-          .body(e.to_string().as_bytes().to_vec())
-          .unwrap(),
-      ),
-    },
-  )
+  Box::new(move |request, responder| {
+    #[cfg(feature = "tracing")]
+    let span = tracing::trace_span!(
+      "protocol::asset",
+      method = request.method().as_str(),
+      range = request.headers().contains_key(RANGE),
+      status = tracing::field::Empty,
+      size = tracing::field::Empty
+    )
+    .entered();
+
+    let response = match get_response(request, &scope, &window_origin, MAX_FULL_LEN) {
+      Ok(response) => response,
+      Err(e) => http::Response::builder()
+        .status(http::StatusCode::BAD_REQUEST)
+        .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
+        .header("Access-Control-Allow-Origin", &window_origin)
+        .body(Body::Bytes(e.to_string().into_bytes()))
+        .unwrap(),
+    };
+
+    #[cfg(feature = "tracing")]
+    {
+      span.record("status", response.status().as_u16());
+      if let Body::Bytes(bytes) = response.body() {
+        span.record("size", bytes.len());
+      }
+    }
+
+    let (parts, body) = response.into_parts();
+    match body {
+      Body::Bytes(bytes) => responder.respond(Response::from_parts(parts, bytes)),
+      Body::File(file) => responder.respond_streaming(Response::from_parts(parts, file)),
+    }
+  })
+}
+
+/// The body of an asset protocol response.
+enum Body {
+  /// The bytes read from the file.
+  Bytes(Vec<u8>),
+  /// The whole file, streamed since it is too large to be read in memory at once.
+  File(std::fs::File),
 }
 
 fn get_response(
   request: Request<Vec<u8>>,
   scope: &scope::fs::Scope,
   window_origin: &str,
-) -> Result<Response<Cow<'static, [u8]>>, Box<dyn std::error::Error>> {
+  max_full_len: u64,
+) -> Result<Response<Body>, Box<dyn std::error::Error>> {
   // skip leading `/`
   let path = percent_encoding::percent_decode(request.uri().path()[1..].as_bytes())
     .decode_utf8_lossy()
@@ -40,12 +71,18 @@ fn get_response(
 
   if let Err(e) = SafePathBuf::new(path.clone().into()) {
     log::error!("asset protocol path \"{}\" is not valid: {}", path, e);
-    return resp.status(403).body(Vec::new().into()).map_err(Into::into);
+    return resp
+      .status(403)
+      .body(Body::Bytes(Vec::new()))
+      .map_err(Into::into);
   }
 
   if !scope.is_allowed(&path) {
     log::error!("asset protocol not configured to allow the path: {}", path);
-    return resp.status(403).body(Vec::new().into()).map_err(Into::into);
+    return resp
+      .status(403)
+      .body(Body::Bytes(Vec::new()))
+      .map_err(Into::into);
   }
 
   let (mut file, len, mime_type, read_bytes) = crate::async_runtime::safe_block_on(async move {
@@ -77,38 +114,28 @@ fn get_response(
     Ok::<(File, u64, String, Option<Vec<u8>>), anyhow::Error>((file, len, mime_type, read_bytes))
   })?;
 
-  resp = resp.header(CONTENT_TYPE, &mime_type);
+  resp = resp.header(ACCEPT_RANGES, "bytes");
 
-  // handle 206 (partial range) http requests
-  let response = if let Some(range_header) = request
-    .headers()
-    .get("range")
-    .and_then(|r| r.to_str().map(|r| r.to_string()).ok())
-  {
-    resp = resp.header(ACCEPT_RANGES, "bytes");
-
-    let not_satisfiable = || {
-      Response::builder()
-        .status(StatusCode::RANGE_NOT_SATISFIABLE)
-        .header(CONTENT_RANGE, format!("bytes */{len}"))
-        .body(vec![].into())
-        .map_err(Into::into)
-    };
-
-    // parse range header
-    let ranges = if let Ok(ranges) = HttpRange::parse(&range_header, len) {
-      ranges
-        .iter()
-        // map the output to spec range <start-end>, example: 0-499
-        .map(|r| (r.start, r.start + r.length - 1))
-        .collect::<Vec<_>>()
-    } else {
-      return not_satisfiable();
-    };
+  let not_satisfiable = || {
+    Response::builder()
+      .status(StatusCode::RANGE_NOT_SATISFIABLE)
+      .header("Access-Control-Allow-Origin", window_origin)
+      .header(CONTENT_RANGE, format!("bytes */{len}"))
+      .body(Body::Bytes(Vec::new()))
+      .map_err(Into::into)
+  };
 
-    /// The Maximum bytes we send in one range
-    const MAX_LEN: u64 = 1000 * 1024;
+  let range_header = request.headers().get(RANGE).and_then(|r| r.to_str().ok());
+  let ranges = match range_header.map(|header| parse_ranges(header, len)) {
+    Some(Ok(ranges)) => Some(ranges),
+    // none of the ranges overlap the file
+    Some(Err(HttpRangeParseError::NoOverlap)) => return not_satisfiable(),
+    // an invalid range header must be ignored, see <https://www.rfc-editor.org/rfc/rfc9110#field.range>
+    Some(Err(HttpRangeParseError::InvalidRange)) | None => None,
+  };
 
+  // handle 206 (partial range) http requests
+  let response = if let Some(ranges) = ranges {
     // single-part range header
     if ranges.len() == 1 {
       let &(start, mut end) = ranges.first().unwrap();
@@ -134,10 +161,11 @@ fn get_response(
         Ok::<Vec<u8>, anyhow::Error>(buf)
       })?;
 
+      resp = resp.header(CONTENT_TYPE, &mime_type);
       resp = resp.header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"));
       resp = resp.header(CONTENT_LENGTH, end + 1 - start);
       resp = resp.status(StatusCode::PARTIAL_CONTENT);
-      resp.body(buf.into())
+      resp.body(Body::Bytes(buf))
     } else {
       let ranges = ranges
         .iter()
@@ -155,10 +183,13 @@ fn get_response(
           }
         })
         .collect::<Vec<_>>();
+      if ranges.is_empty() {
+        return not_satisfiable();
+      }
 
       let boundary = random_boundary();
       let boundary_sep = format!("\r\n--{boundary}\r\n");
-      let boundary_closer = format!("\r\n--{boundary}\r\n");
+      let boundary_closer = format!("\r\n--{boundary}--\r\n");
 
       resp = resp.header(
         CONTENT_TYPE,
@@ -169,7 +200,7 @@ fn get_response(
         // multi-part range header
         let mut buf = Vec::new();
 
-        for (end, start) in ranges {
+        for (start, end) in ranges {
           // a new range is being written, write the range boundary
           buf.write_all(boundary_sep.as_bytes()).await?;
 
@@ -197,27 +228,65 @@ fn get_response(
 
         Ok::<Vec<u8>, anyhow::Error>(buf)
       })?;
-      resp.body(buf.into())
+      resp = resp.status(StatusCode::PARTIAL_CONTENT);
+      resp.body(Body::Bytes(buf))
     }
   } else {
     // avoid reading the file if we already read it
     // as part of mime type detection
-    let buf = if let Some(b) = read_bytes {
-      b
+    let body = if let Some(b) = read_bytes {
+      Body::Bytes(b)
+    } else if len > max_full_len {
+      // files too large to be read at once are streamed
+      Body::File(crate::async_runtime::safe_block_on(file.into_std()))
     } else {
-      crate::async_runtime::safe_block_on(async move {
+      Body::Bytes(crate::async_runtime::safe_block_on(async move {
         let mut local_buf = Vec::with_capacity(len as usize);
         file.read_to_end(&mut local_buf).await?;
         Ok::<Vec<u8>, anyhow::Error>(local_buf)
-      })?
+      })?)
     };
+    resp = resp.header(CONTENT_TYPE, &mime_type);
     resp = resp.header(CONTENT_LENGTH, len);
-    resp.body(buf.into())
+    resp.body(body)
   };
 
   response.map_err(Into::into)
 }
 
+/// The maximum number of bytes sent for each range, the client requesting the rest of the range afterwards.
+const MAX_LEN: u64 = 1000 * 1024;
+
+/// The maximum size of a file read in memory at once for a request without a `Range` header.
+///
+/// Larger files are streamed instead.
+const MAX_FULL_LEN: u64 = 50 * 1024 * 1024;
+
+/// Parses the `Range` header of a request for a file of `len` bytes,
+/// returning the inclusive `(start, end)` byte positions of each range.
+///
+/// Open-ended (`bytes=1000-`) and suffix (`bytes=-500`) ranges are supported,
+/// and ranges ending after the end of the file are truncated.
+fn parse_ranges(header: &str, len: u64) -> Result<Vec<(u64, u64)>, HttpRangeParseError> {
+  let ranges = HttpRange::parse(header, len)?;
+  // `bytes=` without any range
+  if ranges.is_empty() {
+    return Err(HttpRangeParseError::InvalidRange);
+  }
+  let ranges = ranges
+    .iter()
+    // a suffix range of 0 bytes selects nothing
+    .filter(|r| r.length > 0)
+    // map the output to spec range <start-end>, example: 0-499
+    .map(|r| (r.start, r.start + r.length - 1))
+    .collect::<Vec<_>>();
+  if ranges.is_empty() {
+    Err(HttpRangeParseError::NoOverlap)
+  } else {
+    Ok(ranges)
+  }
+}
+
 fn random_boundary() -> String {
   let mut x = [0_u8; 30];
   getrandom::getrandom(&mut x).expect("failed to get random bytes");
@@ -229,3 +298,196 @@ fn random_boundary() -> String {
       a
     })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::{
+    borrow::Cow,
+    io::Read,
+    path::{Path, PathBuf},
+  };
+  use tauri_utils::config::FsScope;
+  use tempfile::TempDir;
+
+  const ORIGIN: &str = "http://asset.localhost";
+
+  /// Writes a file of `len` bytes in a temporary directory, removed when the returned [`TempDir`] is dropped.
+  fn file(name: &str, len: usize) -> (TempDir, PathBuf, Vec<u8>) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(name);
+    let contents = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    std::fs::write(&path, &contents).unwrap();
+    (dir, path, contents)
+  }
+
+  fn get(path: &Path, range: Option<&str>) -> Response<Cow<'static, [u8]>> {
+    get_with_limit(path, range, MAX_FULL_LEN)
+  }
+
+  fn get_with_limit(
+    path: &Path,
+    range: Option<&str>,
+    max_full_len: u64,
+  ) -> Response<Cow<'static, [u8]>> {
+    let app = crate::test::mock_app();
+    let scope = scope::fs::Scope::new(
+      &app,
+      &FsScope::AllowedPaths(vec![path.parent().unwrap().join("**")]),
+    )
+    .unwrap();
+    let path = percent_encoding::utf8_percent_encode(
+      path.to_str().unwrap(),
+      percent_encoding::NON_ALPHANUMERIC,
+    );
+    let mut request = Request::builder().uri(format!("asset://localhost/{path}"));
+    if let Some(range) = range {
+      request = request.header(RANGE, range);
+    }
+    get_response(
+      request.body(Vec::new()).unwrap(),
+      &scope,
+      ORIGIN,
+      max_full_len,
+    )
+    .unwrap()
+    .map(|body| match body {
+      Body::Bytes(bytes) => bytes.into(),
+      Body::File(mut file) => {
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).unwrap();
+        bytes.into()
+      }
+    })
+  }
+
+  fn header<'a>(response: &'a Response<Cow<'static, [u8]>>, name: HeaderName) -> &'a str {
+    response.headers()[name].to_str().unwrap()
+  }
+
+  #[test]
+  fn single_range() {
+    let (_dir, path, contents) = file("single_range", 2000);
+
+    for (range, start, end) in [
+      ("bytes=0-499", 0, 499),
+      // suffix range
+      ("bytes=-500", 1500, 1999),
+      // open-ended range
+      ("bytes=1000-", 1000, 1999),
+      // the end is truncated to the end of the file
+      ("bytes=1500-2500", 1500, 1999),
+      ("bytes=1999-1999", 1999, 1999),
+    ] {
+      let response = get(&path, Some(range));
+      assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT, "{range}");
+      assert_eq!(
+        header(&response, CONTENT_RANGE),
+        format!("bytes {start}-{end}/2000"),
+        "{range}"
+      );
+      assert_eq!(
+        header(&response, CONTENT_LENGTH),
+        (end + 1 - start).to_string(),
+        "{range}"
+      );
+      assert_eq!(response.body().as_ref(), &contents[start..=end], "{range}");
+    }
+  }
+
+  #[test]
+  fn unsatisfiable_range() {
+    let (_dir, path, _) = file("unsatisfiable_range", 2000);
+
+    for range in ["bytes=2000-", "bytes=5000-6000", "bytes=-0"] {
+      let response = get(&path, Some(range));
+      assert_eq!(
+        response.status(),
+        StatusCode::RANGE_NOT_SATISFIABLE,
+        "{range}"
+      );
+      assert_eq!(header(&response, CONTENT_RANGE), "bytes */2000", "{range}");
+      assert_eq!(
+        header(&response, ACCESS_CONTROL_ALLOW_ORIGIN),
+        ORIGIN,
+        "{range}"
+      );
+    }
+
+    let (_dir, path, _) = file("unsatisfiable_range_empty", 0);
+    let response = get(&path, Some("bytes=0-"));
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(header(&response, CONTENT_RANGE), "bytes */0");
+  }
+
+  #[test]
+  fn invalid_range_is_ignored() {
+    let (_dir, path, contents) = file("invalid_range_is_ignored", 2000);
+
+    for range in [None, Some("bytes=abc"), Some("items=0-10"), Some("bytes=")] {
+      let response = get(&path, range);
+      assert_eq!(response.status(), StatusCode::OK, "{range:?}");
+      assert_eq!(header(&response, ACCEPT_RANGES), "bytes", "{range:?}");
+      assert_eq!(header(&response, CONTENT_LENGTH), "2000", "{range:?}");
+      assert_eq!(response.body().as_ref(), contents, "{range:?}");
+    }
+  }
+
+  #[test]
+  fn large_file_without_range() {
+    let (_dir, path, contents) = file("large_file_without_range", 3000 * 1024);
+
+    // files over the limit are sent in full, and an invalid range header is ignored
+    for (range, max_full_len) in [
+      (None, 3000 * 1024),
+      (None, 2000 * 1024),
+      (Some("bytes=abc"), 2000 * 1024),
+    ] {
+      let response = get_with_limit(&path, range, max_full_len);
+      assert_eq!(response.status(), StatusCode::OK, "{range:?}");
+      assert!(response.headers().get(CONTENT_RANGE).is_none(), "{range:?}");
+      assert_eq!(
+        header(&response, CONTENT_LENGTH),
+        (3000 * 1024).to_string(),
+        "{range:?}"
+      );
+      assert_eq!(response.body().as_ref(), contents, "{range:?}");
+    }
+
+    // range requests are still limited to `MAX_LEN` bytes
+    let response = get_with_limit(&path, Some("bytes=0-"), 2000 * 1024);
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+      header(&response, CONTENT_RANGE),
+      format!("bytes 0-{}/{}", MAX_LEN - 1, 3000 * 1024)
+    );
+    assert_eq!(response.body().as_ref(), &contents[..MAX_LEN as usize]);
+  }
+
+  #[test]
+  fn multiple_ranges() {
+    let (_dir, path, contents) = file("multiple_ranges", 2000);
+
+    // the second range is truncated to the end of the file and the third one is ignored
+    let response = get(&path, Some("bytes=0-9, 1990-2100, 3000-"));
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers().get_all(CONTENT_TYPE).iter().count(), 1);
+    let boundary = header(&response, CONTENT_TYPE)
+      .strip_prefix("multipart/byteranges; boundary=")
+      .unwrap();
+
+    let mime_type = MimeType::parse(&contents, path.to_str().unwrap());
+    let mut expected = Vec::new();
+    for (start, end) in [(0, 9), (1990, 1999)] {
+      expected.extend_from_slice(
+        format!(
+          "\r\n--{boundary}\r\ncontent-type: {mime_type}\r\ncontent-range: bytes {start}-{end}/2000\r\n\r\n"
+        )
+        .as_bytes(),
+      );
+      expected.extend_from_slice(&contents[start..=end]);
+    }
+    expected.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    assert_eq!(response.body().as_ref(), expected);
+  }
+}
@@ -4,6 +4,7 @@
 
 use std::{
   collections::{HashMap, HashSet},
+  ffi::OsString,
   fmt,
   path::{Path, PathBuf, MAIN_SEPARATOR},
   sync::{
@@ -29,11 +30,90 @@ pub enum Event {
 
 type EventListener = Box<dyn Fn(&Event) + Send>;
 
+/// A set of patterns indexed by their literal leading components,
+/// so a path is only matched against the patterns starting with its ancestors.
+///
+/// A pattern can only match a path whose leading components are the literal components of the pattern,
+/// since the separators and the literal characters must match exactly.
+#[derive(Debug, Default)]
+struct PatternSet {
+  patterns: HashSet<Pattern>,
+  index: PatternNode,
+}
+
+#[derive(Debug, Default)]
+struct PatternNode {
+  /// The patterns whose literal components end at this node.
+  patterns: Vec<Pattern>,
+  children: HashMap<OsString, PatternNode>,
+}
+
+impl PatternSet {
+  fn insert(&mut self, pattern: Pattern) {
+    if self.patterns.contains(&pattern) {
+      return;
+    }
+    let mut node = &mut self.index;
+    for component in Path::new(pattern.as_str()).components() {
+      let Some(literal) = literal_component(&component.as_os_str().to_string_lossy()) else {
+        break;
+      };
+      node = node.children.entry(literal.into()).or_default();
+    }
+    node.patterns.push(pattern.clone());
+    self.patterns.insert(pattern);
+  }
+
+  /// Whether any pattern matches `path`, which must be normalized like the patterns.
+  fn matches(&self, path: &Path, options: glob::MatchOptions) -> bool {
+    let matches = |node: &PatternNode| {
+      node
+        .patterns
+        .iter()
+        .any(|p| p.matches_path_with(path, options))
+    };
+
+    let mut node = &self.index;
+    if matches(node) {
+      return true;
+    }
+    for component in path.components() {
+      match node.children.get(component.as_os_str()) {
+        Some(child) => node = child,
+        None => return false,
+      }
+      if matches(node) {
+        return true;
+      }
+    }
+    false
+  }
+}
+
+/// The text matched by a pattern component if it has no wildcard,
+/// unescaping the metacharacters escaped by [`Pattern::escape`].
+fn literal_component(component: &str) -> Option<String> {
+  let mut literal = String::new();
+  let mut chars = component.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '*' | '?' => return None,
+      '[' => match (chars.next(), chars.next()) {
+        (Some(escaped @ ('*' | '?' | '[' | ']')), Some(']')) => literal.push(escaped),
+        // a character class
+        _ => return None,
+      },
+      c => literal.push(c),
+    }
+  }
+  Some(literal)
+}
+
 /// Scope for filesystem access.
 #[derive(Clone)]
 pub struct Scope {
-  allowed_patterns: Arc<Mutex<HashSet<Pattern>>>,
-  forbidden_patterns: Arc<Mutex<HashSet<Pattern>>>,
+  allowed_patterns: Arc<Mutex<PatternSet>>,
+  forbidden_patterns: Arc<Mutex<PatternSet>>,
   event_listeners: Arc<Mutex<HashMap<ScopeEventId, EventListener>>>,
   match_options: glob::MatchOptions,
   next_event_id: Arc<AtomicU32>,
@@ -54,6 +134,7 @@ impl fmt::Debug for Scope {
           .allowed_patterns
           .lock()
           .unwrap()
+          .patterns
           .iter()
           .map(|p| p.as_str())
           .collect::<Vec<&str>>(),
@@ -64,6 +145,7 @@ impl fmt::Debug for Scope {
           .forbidden_patterns
           .lock()
           .unwrap()
+          .patterns
           .iter()
           .map(|p| p.as_str())
           .collect::<Vec<&str>>(),
@@ -73,7 +155,7 @@ impl fmt::Debug for Scope {
 }
 
 fn push_pattern<P: AsRef<Path>, F: Fn(&str) -> Result<Pattern, glob::PatternError>>(
-  list: &mut HashSet<Pattern>,
+  list: &mut PatternSet,
   pattern: P,
   f: F,
 ) -> crate::Result<()> {
@@ -136,14 +218,14 @@ impl Scope {
     manager: &M,
     scope: &FsScope,
   ) -> crate::Result<Self> {
-    let mut allowed_patterns = HashSet::new();
+    let mut allowed_patterns = PatternSet::default();
     for path in scope.allowed_paths() {
       if let Ok(path) = manager.path().parse(path) {
         push_pattern(&mut allowed_patterns, path, Pattern::new)?;
       }
     }
 
-    let mut forbidden_patterns = HashSet::new();
+    let mut forbidden_patterns = PatternSet::default();
     if let Some(forbidden_paths) = scope.forbidden_paths() {
       for path in forbidden_paths {
         if let Ok(path) = manager.path().parse(path) {
@@ -181,12 +263,12 @@ impl Scope {
 
   /// The list of allowed patterns.
   pub fn allowed_patterns(&self) -> HashSet<Pattern> {
-    self.allowed_patterns.lock().unwrap().clone()
+    self.allowed_patterns.lock().unwrap().patterns.clone()
   }
 
   /// The list of forbidden patterns.
   pub fn forbidden_patterns(&self) -> HashSet<Pattern> {
-    self.forbidden_patterns.lock().unwrap().clone()
+    self.forbidden_patterns.lock().unwrap().patterns.clone()
   }
 
   /// Listen to an event on this scope.
@@ -297,47 +379,67 @@ impl Scope {
 
   /// Determines if the given path is allowed on this scope.
   pub fn is_allowed<P: AsRef<Path>>(&self, path: P) -> bool {
-    let path = path.as_ref();
-    let path = if path.is_symlink() {
-      match std::fs::read_link(path) {
-        Ok(p) => p,
-        Err(_) => return false,
-      }
-    } else {
-      path.to_path_buf()
-    };
-    let path = if !path.exists() {
-      crate::Result::Ok(path)
-    } else {
-      std::fs::canonicalize(path).map_err(Into::into)
+    let Some(path) = resolve(path.as_ref()) else {
+      return false;
     };
+    let forbidden = self.forbidden_patterns.lock().unwrap();
+    let allowed = self.allowed_patterns.lock().unwrap();
+    is_allowed(&path, &allowed, &forbidden, self.match_options)
+  }
 
-    if let Ok(path) = path {
-      let path: PathBuf = path.components().collect();
-      let forbidden = self
-        .forbidden_patterns
-        .lock()
-        .unwrap()
-        .iter()
-        .any(|p| p.matches_path_with(&path, self.match_options));
-
-      if forbidden {
-        false
-      } else {
-        let allowed = self
-          .allowed_patterns
-          .lock()
-          .unwrap()
-          .iter()
-          .any(|p| p.matches_path_with(&path, self.match_options));
-        allowed
-      }
-    } else {
-      false
-    }
+  /// Determines if each of the given paths is allowed on this scope, like [`Self::is_allowed`].
+  ///
+  /// The patterns are only locked once, which is faster than calling [`Self::is_allowed`] in a loop
+  /// when checking many paths such as the entries of a directory.
+  pub fn is_allowed_batch<I>(&self, paths: I) -> Vec<bool>
+  where
+    I: IntoIterator,
+    I::Item: AsRef<Path>,
+  {
+    // resolve the symlinks before locking the patterns
+    let paths = paths
+      .into_iter()
+      .map(|path| resolve(path.as_ref()))
+      .collect::<Vec<_>>();
+    let forbidden = self.forbidden_patterns.lock().unwrap();
+    let allowed = self.allowed_patterns.lock().unwrap();
+    paths
+      .iter()
+      .map(|path| {
+        path
+          .as_ref()
+          .map(|path| is_allowed(path, &allowed, &forbidden, self.match_options))
+          .unwrap_or(false)
+      })
+      .collect()
   }
 }
 
+/// Resolves the symlink and canonicalizes `path` if it exists,
+/// returning the path matched against the patterns or `None` if it must be denied.
+fn resolve(path: &Path) -> Option<PathBuf> {
+  let path = if path.is_symlink() {
+    std::fs::read_link(path).ok()?
+  } else {
+    path.to_path_buf()
+  };
+  let path = if !path.exists() {
+    path
+  } else {
+    std::fs::canonicalize(path).ok()?
+  };
+  Some(path.components().collect())
+}
+
+fn is_allowed(
+  path: &Path,
+  allowed: &PatternSet,
+  forbidden: &PatternSet,
+  options: glob::MatchOptions,
+) -> bool {
+  !forbidden.matches(path, options) && allowed.matches(path, options)
+}
+
 fn escaped_pattern(p: &str) -> Result<Pattern, glob::PatternError> {
   Pattern::new(&glob::Pattern::escape(p))
 }
@@ -352,7 +454,11 @@ fn escaped_pattern_with(p: &str, append: &str) -> Result<Pattern, glob::PatternE
 
 #[cfg(test)]
 mod tests {
-  use super::Scope;
+  use std::path::Path;
+
+  use proptest::prelude::*;
+
+  use super::{literal_component, Pattern, PatternSet, Scope};
 
   fn new_scope() -> Scope {
     Scope {
@@ -470,4 +576,161 @@ mod tests {
       assert!(scope.is_allowed("C:\\home\\tauri\\anyfile"));
     }
   }
+
+  #[test]
+  fn literal_components() {
+    assert_eq!(literal_component("tauri").as_deref(), Some("tauri"));
+    assert_eq!(
+      literal_component(&Pattern::escape("a*b?[c]")).as_deref(),
+      Some("a*b?[c]")
+    );
+    assert_eq!(literal_component("*.txt"), None);
+    assert_eq!(literal_component("file?"), None);
+    assert_eq!(literal_component("[ab]"), None);
+    assert_eq!(literal_component("[!a]"), None);
+    assert_eq!(literal_component("**"), None);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn pattern_semantics() {
+    let scope = new_scope();
+    scope.allow_directory("/home/tauri", false).unwrap();
+    scope
+      .allow_directory("/home/tauri/[projects]", true)
+      .unwrap();
+    scope.allow_file("/home/tauri/.config").unwrap();
+    {
+      let mut allowed = scope.allowed_patterns.lock().unwrap();
+      allowed.insert(Pattern::new("/data/*/assets/**").unwrap());
+      allowed.insert(Pattern::new("/data/logs/?.log").unwrap());
+    }
+    scope.forbid_file("/data/app/assets/secret").unwrap();
+
+    // `*` does not match separators
+    assert!(scope.is_allowed("/home/tauri/file"));
+    assert!(!scope.is_allowed("/home/tauri/dir/file"));
+    // escaped metacharacters only match themselves
+    assert!(scope.is_allowed("/home/tauri/[projects]/a/b"));
+    assert!(!scope.is_allowed("/home/tauri/p/a/b"));
+    // dotfiles must be allowed explicitly
+    assert!(!scope.is_allowed("/home/tauri/.bashrc"));
+    assert!(scope.is_allowed("/home/tauri/.config"));
+    // case sensitive
+    assert!(!scope.is_allowed("/home/Tauri/file"));
+    assert!(!scope.is_allowed("/HOME/tauri/file"));
+    // wildcards before literal components
+    assert!(scope.is_allowed("/data/app/assets/icon.png"));
+    assert!(scope.is_allowed("/data/app/assets/icons/icon.png"));
+    assert!(!scope.is_allowed("/data/app/other/icon.png"));
+    assert!(scope.is_allowed("/data/logs/1.log"));
+    assert!(!scope.is_allowed("/data/logs/10.log"));
+    // forbidden patterns take precedence
+    assert!(!scope.is_allowed("/data/app/assets/secret"));
+    // normalized like the patterns
+    assert!(scope.is_allowed("/home//tauri/./file"));
+    assert!(!scope.is_allowed("/home/tauri/dir/../file"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symlinks_are_resolved() {
+    let dir = std::env::temp_dir().join(format!("tauri-fs-scope-{}", std::process::id()));
+    let allowed = dir.join("allowed");
+    let forbidden = dir.join("forbidden");
+    std::fs::create_dir_all(&allowed).unwrap();
+    std::fs::create_dir_all(&forbidden).unwrap();
+    std::fs::write(forbidden.join("secret"), "").unwrap();
+    let link = allowed.join("link");
+    let _ = std::fs::remove_file(&link);
+    std::os::unix::fs::symlink(forbidden.join("secret"), &link).unwrap();
+
+    let scope = new_scope();
+    scope.allow_directory(&allowed, true).unwrap();
+    scope.forbid_directory(&forbidden, true).unwrap();
+    assert!(scope.is_allowed(&allowed));
+    assert!(!scope.is_allowed(&link));
+    assert_eq!(
+      scope.is_allowed_batch([&allowed, &link, &forbidden.join("secret")]),
+      [true, false, false]
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn batch_matches_single_checks() {
+    let scope = new_scope();
+    #[cfg(unix)]
+    let (root, paths) = (
+      "/home/tauri",
+      [
+        "/home/tauri",
+        "/home/tauri/file",
+        "/home/tauri/dir/file",
+        "/home/other/file",
+        "/",
+        "",
+      ],
+    );
+    #[cfg(windows)]
+    let (root, paths) = (
+      "C:\\home\\tauri",
+      [
+        "C:\\home\\tauri",
+        "C:\\home\\tauri\\file",
+        "C:\\home\\tauri\\dir\\file",
+        "C:\\home\\other\\file",
+        "C:\\",
+        "",
+      ],
+    );
+    scope.allow_directory(root, false).unwrap();
+    assert_eq!(
+      scope.is_allowed_batch(paths),
+      paths.map(|path| scope.is_allowed(path))
+    );
+    assert_eq!(
+      scope.is_allowed_batch(paths),
+      [true, true, false, false, false, false]
+    );
+  }
+
+  #[cfg(unix)]
+  fn component() -> impl Strategy<Value = &'static str> {
+    prop::sample::select(vec![
+      "a", "b", "ab", ".a", "*", "**", "?", "a*", "[ab]", "[!a]", "[*]",
+    ])
+  }
+
+  #[cfg(unix)]
+  proptest! {
+    // the index must give the same result as matching every pattern
+    #[test]
+    fn index_matches_every_pattern(
+      patterns in prop::collection::vec(prop::collection::vec(component(), 0..4), 1..8),
+      path in prop::collection::vec(component(), 0..5),
+    ) {
+      let options = glob::MatchOptions {
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+        ..Default::default()
+      };
+      let patterns = patterns
+        .iter()
+        .map(|p| Pattern::new(&format!("/{}", p.join("/"))).unwrap())
+        .collect::<Vec<_>>();
+      let mut set = PatternSet::default();
+      for pattern in &patterns {
+        set.insert(pattern.clone());
+      }
+      let path = format!("/{}", path.join("/"));
+      let path = Path::new(&path).components().collect::<std::path::PathBuf>();
+
+      prop_assert_eq!(
+        set.matches(&path, options),
+        patterns.iter().any(|p| p.matches_path_with(&path, options))
+      );
+    }
+  }
 }
@@ -45,14 +45,21 @@ impl<'r, T: Send + Sync + std::fmt::Debug> std::fmt::Debug for State<'r, T> {
 }
 
 impl<'r, 'de: 'r, T: Send + Sync + 'static, R: Runtime> CommandArg<'de, R> for State<'r, T> {
-  /// Grabs the [`State`] from the [`CommandItem`]. This will never fail.
+  /// Grabs the [`State`] from the [`CommandItem`].
+  ///
+  /// Fails with [`crate::Error::StateNotManaged`] when the state type is not managed.
   fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
-    Ok(command.message.state_ref().try_get().unwrap_or_else(|| {
-      panic!(
-        "state not managed for field `{}` on command `{}`. You must call `.manage()` before using this command",
-        command.key, command.name
-      )
-    }))
+    command.message.state_ref().try_get().ok_or_else(|| {
+      crate::Error::StateNotManaged {
+        command: match command.plugin {
+          Some(plugin) => format!("plugin:{plugin}|{}", command.name),
+          None => command.name.to_string(),
+        },
+        key: command.key.to_string(),
+        type_name: std::any::type_name::<T>(),
+      }
+      .into()
+    })
   }
 }
 
@@ -84,3 +91,103 @@ impl StateManager {
     self.0.try_get().map(State)
   }
 }
+
+/// Warns about the state types requested by the commands that are not managed,
+/// which would fail the commands with [`crate::Error::StateNotManaged`].
+#[cfg(debug_assertions)]
+pub(crate) fn warn_unmanaged_states(states: &[crate::ipc::CommandState], manager: &StateManager) {
+  let mut warned = std::collections::HashSet::new();
+  for state in states {
+    if !(state.is_managed)(manager) && warned.insert((state.plugin, state.command, state.key)) {
+      let command = match state.plugin {
+        Some(plugin) => format!("plugin:{plugin}|{}", state.command),
+        None => state.command.to_string(),
+      };
+      log::warn!(
+        "state not managed for field `{}` on command `{}`: `{}`, did you forget to call `.manage()`?",
+        state.key,
+        command,
+        state.type_name
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    command,
+    ipc::{CallbackFn, InvokeBody},
+    test::{get_ipc_response, mock_builder, mock_context, noop_assets, INVOKE_KEY},
+    webview::InvokeRequest,
+    Runtime, State, WebviewWindowBuilder,
+  };
+
+  struct Counter(usize);
+  struct Missing;
+
+  #[command(root = "crate")]
+  fn count(counter: State<'_, Counter>) -> usize {
+    counter.0
+  }
+
+  #[command(root = "crate")]
+  fn missing(_missing_state: State<'_, Missing>) {}
+
+  #[command(root = "crate")]
+  fn generic<R: Runtime>(_app: crate::AppHandle<R>, _counter: State<'_, Counter>) {}
+
+  #[test]
+  fn unmanaged_state_returns_invoke_error() {
+    let app = mock_builder()
+      .manage(Counter(1))
+      .invoke_handler(crate::generate_handler![count, missing])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let invoke = |cmd: &str| {
+      get_ipc_response(
+        &webview,
+        InvokeRequest {
+          cmd: cmd.into(),
+          callback: CallbackFn(0),
+          error: CallbackFn(1),
+          url: "http://tauri.localhost".parse().unwrap(),
+          body: InvokeBody::default(),
+          headers: Default::default(),
+          invoke_key: INVOKE_KEY.to_string(),
+        },
+      )
+    };
+
+    assert_eq!(invoke("count").unwrap().deserialize::<usize>().unwrap(), 1);
+    let error = invoke("missing").unwrap_err();
+    let error = error.as_str().unwrap();
+    assert!(error.contains("`missingState` on command `missing`"));
+    assert!(error.contains(std::any::type_name::<Missing>()));
+  }
+
+  #[test]
+  fn generate_command_states() {
+    let states = crate::generate_command_states![count, missing, generic];
+    assert_eq!(states.len(), 3);
+
+    let manager = super::StateManager::new();
+    manager.set(Counter(0));
+
+    assert_eq!(states[0].command, "count");
+    assert_eq!(states[0].key, "counter");
+    assert_eq!(states[0].type_name, std::any::type_name::<Counter>());
+    assert!((states[0].is_managed)(&manager));
+
+    assert_eq!(states[1].command, "missing");
+    assert_eq!(states[1].key, "missingState");
+    assert!(!(states[1].is_managed)(&manager));
+
+    assert_eq!(states[2].command, "generic");
+    assert!(states.iter().all(|state| state.plugin.is_none()));
+  }
+}
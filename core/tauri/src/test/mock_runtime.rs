@@ -8,12 +8,12 @@
 use tauri_runtime::{
   dpi::{PhysicalPosition, PhysicalSize, Position, Size},
   monitor::Monitor,
-  webview::{DetachedWebview, PendingWebview},
+  webview::{DetachedWebview, PendingWebview, UriSchemeProtocol, UriSchemeResponseBody},
   window::{CursorIcon, DetachedWindow, PendingWindow, RawWindow, WindowEvent, WindowId},
   window::{WindowBuilder, WindowBuilderBase},
   DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, ProgressBarState,
-  Result, RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType, UserEvent,
-  WebviewDispatch, WindowDispatch, WindowEventId,
+  Result, RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, SystemSound, UserAttentionType,
+  UserEvent, WebviewDispatch, WindowDispatch, WindowEventId,
 };
 
 #[cfg(target_os = "macos")]
@@ -41,6 +41,7 @@ enum Message {
   Task(Box<dyn FnOnce() + Send>),
   CloseWindow(WindowId),
   DestroyWindow(WindowId),
+  RequestExit(i32),
 }
 
 struct Webview;
@@ -83,6 +84,7 @@ impl RuntimeContext {
         Message::CloseWindow(id) | Message::DestroyWindow(id) => {
           self.windows.borrow_mut().remove(&id);
         }
+        Message::RequestExit(_) => (),
       }
       Ok(())
     }
@@ -133,7 +135,11 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   }
 
   fn request_exit(&self, code: i32) -> Result<()> {
-    unimplemented!()
+    self.context.send_message(Message::RequestExit(code))
+  }
+
+  fn play_system_sound(&self, sound: SystemSound) -> Result<()> {
+    Ok(())
   }
 
   /// Create a new webview window.
@@ -158,15 +164,19 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
       },
     );
 
-    let webview = webview_id.map(|id| DetachedWebview {
-      label: pending.label.clone(),
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-        last_evaluated_script: Default::default(),
-      },
-    });
+    let webview = webview_id
+      .zip(pending.webview)
+      .map(|(id, webview)| DetachedWebview {
+        label: pending.label.clone(),
+        dispatcher: MockWebviewDispatcher {
+          id,
+          context: self.context.clone(),
+          url: Arc::new(Mutex::new(webview.url)),
+          last_evaluated_script: Default::default(),
+          service_workers_unregistered_at: Default::default(),
+          uri_scheme_protocols: UriSchemeProtocols(Arc::new(webview.uri_scheme_protocols)),
+        },
+      });
 
     Ok(DetachedWindow {
       id,
@@ -196,7 +206,9 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
         id,
         context: self.context.clone(),
         last_evaluated_script: Default::default(),
+        service_workers_unregistered_at: Default::default(),
         url: Arc::new(Mutex::new(pending.url)),
+        uri_scheme_protocols: UriSchemeProtocols(Arc::new(pending.uri_scheme_protocols)),
       },
     })
   }
@@ -284,12 +296,53 @@ pub struct MockWebviewDispatcher {
   context: RuntimeContext,
   url: Arc<Mutex<String>>,
   last_evaluated_script: Arc<Mutex<Option<String>>>,
+  service_workers_unregistered_at: Arc<Mutex<Vec<String>>>,
+  uri_scheme_protocols: UriSchemeProtocols,
+}
+
+/// The custom protocols registered on a mock webview.
+#[derive(Clone)]
+struct UriSchemeProtocols(Arc<HashMap<String, Box<UriSchemeProtocol>>>);
+
+impl fmt::Debug for UriSchemeProtocols {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.0.keys()).finish()
+  }
 }
 
 impl MockWebviewDispatcher {
   pub fn last_evaluated_script(&self) -> Option<String> {
     self.last_evaluated_script.lock().unwrap().clone()
   }
+
+  /// Sends the request to the custom protocol registered for its URI scheme,
+  /// returning the channel that receives the response once the protocol responds.
+  ///
+  /// Panics if no custom protocol is registered for the scheme.
+  pub fn uri_scheme_request(
+    &self,
+    request: http::Request<Vec<u8>>,
+  ) -> Receiver<http::Response<UriSchemeResponseBody>> {
+    let scheme = request.uri().scheme_str().unwrap_or_default().to_string();
+    let protocol = self
+      .uri_scheme_protocols
+      .0
+      .get(&scheme)
+      .unwrap_or_else(|| panic!("no custom protocol registered for the `{scheme}` scheme"));
+    let (tx, rx) = channel();
+    protocol(
+      request,
+      Box::new(move |response| {
+        let _ = tx.send(response);
+      }),
+    );
+    rx
+  }
+
+  /// The URLs the webview was on when its service workers were unregistered.
+  pub fn service_workers_unregistered_at(&self) -> Vec<String> {
+    self.service_workers_unregistered_at.lock().unwrap().clone()
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -508,6 +561,43 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
     Ok(())
   }
 
+  fn clear_cache(&self) -> Result<()> {
+    Ok(())
+  }
+
+  fn unregister_service_workers<F: FnOnce(Result<()>) + Send + 'static>(
+    &self,
+    on_complete: F,
+  ) -> Result<()> {
+    self
+      .service_workers_unregistered_at
+      .lock()
+      .unwrap()
+      .push(self.url.lock().unwrap().clone());
+    on_complete(Ok(()));
+    Ok(())
+  }
+
+  fn clear_all_site_data_for_origin(&self, origin: Url) -> Result<()> {
+    Ok(())
+  }
+
+  fn find(&self, text: String, options: tauri_runtime::webview::FindOptions) -> Result<()> {
+    Ok(())
+  }
+
+  fn find_next(&self) -> Result<()> {
+    Ok(())
+  }
+
+  fn find_previous(&self) -> Result<()> {
+    Ok(())
+  }
+
+  fn stop_find(&self, clear_selection: bool) -> Result<()> {
+    Ok(())
+  }
+
   fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
     self
       .last_evaluated_script
@@ -536,6 +626,20 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
     })
   }
 
+  fn process_id(&self) -> Result<Option<u32>> {
+    Ok(None)
+  }
+
+  fn webview_settings(&self) -> Result<tauri_utils::config::WebviewSettingsConfig> {
+    Ok(Default::default())
+  }
+
+  fn unsupported_webview_settings(
+    &self,
+  ) -> Result<Vec<tauri_runtime::webview::UnsupportedWebviewSetting>> {
+    Ok(Vec::new())
+  }
+
   fn navigate(&self, url: Url) -> Result<()> {
     *self.url.lock().unwrap() = url.to_string();
     Ok(())
@@ -736,6 +840,10 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
     Ok(())
   }
 
+  fn beep(&self) -> Result<()> {
+    Ok(())
+  }
+
   fn create_window<F: Fn(RawWindow<'_>) + Send + 'static>(
     &mut self,
     pending: PendingWindow<T, Self::Runtime>,
@@ -757,15 +865,19 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
       },
     );
 
-    let webview = webview_id.map(|id| DetachedWebview {
-      label: pending.label.clone(),
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-        last_evaluated_script: Default::default(),
-      },
-    });
+    let webview = webview_id
+      .zip(pending.webview)
+      .map(|(id, webview)| DetachedWebview {
+        label: pending.label.clone(),
+        dispatcher: MockWebviewDispatcher {
+          id,
+          context: self.context.clone(),
+          url: Arc::new(Mutex::new(webview.url)),
+          last_evaluated_script: Default::default(),
+          service_workers_unregistered_at: Default::default(),
+          uri_scheme_protocols: UriSchemeProtocols(Arc::new(webview.uri_scheme_protocols)),
+        },
+      });
 
     Ok(DetachedWindow {
       id,
@@ -794,7 +906,9 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
         id,
         context: self.context.clone(),
         last_evaluated_script: Default::default(),
+        service_workers_unregistered_at: Default::default(),
         url: Arc::new(Mutex::new(pending.url)),
+        uri_scheme_protocols: UriSchemeProtocols(Arc::new(pending.uri_scheme_protocols)),
       },
     })
   }
@@ -1037,15 +1151,19 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
       },
     );
 
-    let webview = webview_id.map(|id| DetachedWebview {
-      label: pending.label.clone(),
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-        last_evaluated_script: Default::default(),
-      },
-    });
+    let webview = webview_id
+      .zip(pending.webview)
+      .map(|(id, webview)| DetachedWebview {
+        label: pending.label.clone(),
+        dispatcher: MockWebviewDispatcher {
+          id,
+          context: self.context.clone(),
+          url: Arc::new(Mutex::new(webview.url)),
+          last_evaluated_script: Default::default(),
+          service_workers_unregistered_at: Default::default(),
+          uri_scheme_protocols: UriSchemeProtocols(Arc::new(webview.uri_scheme_protocols)),
+        },
+      });
 
     Ok(DetachedWindow {
       id,
@@ -1075,7 +1193,9 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
         id,
         context: self.context.clone(),
         last_evaluated_script: Default::default(),
+        service_workers_unregistered_at: Default::default(),
         url: Arc::new(Mutex::new(pending.url)),
+        uri_scheme_protocols: UriSchemeProtocols(Arc::new(pending.uri_scheme_protocols)),
       },
     })
   }
@@ -1158,6 +1278,20 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
               }
             }
           }
+          Message::RequestExit(code) => {
+            let (tx, rx) = channel();
+            callback(RunEvent::ExitRequested {
+              code: Some(code),
+              tx,
+            });
+
+            let recv = rx.try_recv();
+            let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
+
+            if !should_prevent {
+              break;
+            }
+          }
           Message::DestroyWindow(id) => {
             let removed = self.context.windows.borrow_mut().remove(&id).is_some();
             if removed {
@@ -52,8 +52,13 @@
 
 mod mock_runtime;
 pub use mock_runtime::*;
+#[cfg(all(desktop, feature = "test-webview"))]
+mod webview_harness;
 use serde::Serialize;
 use serialize_to_javascript::DefaultTemplate;
+#[cfg(all(desktop, feature = "test-webview"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-webview")))]
+pub use webview_harness::{WebviewHarness, WebviewHarnessBuilder};
 
 use std::{borrow::Cow, collections::HashMap, fmt::Debug};
 
@@ -112,8 +117,11 @@ pub fn mock_context<R: Runtime, A: Assets<R>>(assets: A) -> crate::Context<R> {
         windows: Vec::new(),
         security: Default::default(),
         tray_icon: None,
+        tray_icons: Vec::new(),
+        menu: Default::default(),
         macos_private_api: false,
         enable_gtk_app_id: false,
+        webview_metrics_interval: None,
       },
       bundle: Default::default(),
       build: Default::default(),
@@ -124,6 +132,8 @@ pub fn mock_context<R: Runtime, A: Assets<R>>(assets: A) -> crate::Context<R> {
     app_icon: None,
     #[cfg(all(desktop, feature = "tray-icon"))]
     tray_icon: None,
+    #[cfg(all(desktop, feature = "tray-icon"))]
+    tray_icons: Default::default(),
     package_info: crate::PackageInfo {
       name: "test".into(),
       version: "0.1.0".parse().unwrap(),
@@ -138,6 +148,8 @@ pub fn mock_context<R: Runtime, A: Assets<R>>(assets: A) -> crate::Context<R> {
 
     #[cfg(dev)]
     config_parent: None,
+    #[cfg(dev)]
+    dev_resources: None,
   }
 }
 
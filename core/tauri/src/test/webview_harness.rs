@@ -0,0 +1,477 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A harness running a real webview for integration tests.
+//!
+//! The [`MockRuntime`](super::MockRuntime) cannot execute JavaScript, so anything involving
+//! [`eval`](crate::Webview::eval), initialization scripts or the event bridge must be tested against
+//! an actual webview. The harness spawns a hidden window with the [`Wry`] runtime on a dedicated
+//! thread and exposes helpers to run scripts and commands on it.
+
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    mpsc::{channel, sync_channel, Receiver, Sender},
+    Arc, Mutex, MutexGuard, OnceLock,
+  },
+  thread,
+  time::Duration,
+};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tauri_utils::{
+  acl::{
+    resolved::{Resolved, ResolvedCommand},
+    ExecutionContext,
+  },
+  assets::{AssetKey, CspHash},
+};
+
+use super::mock_context;
+use crate::{
+  command,
+  ipc::RuntimeAuthority,
+  webview::{PageLoadEvent, WebviewWindowBuilder},
+  AppHandle, Assets, Builder, EventId, Listener, Runtime, State, WebviewUrl, WebviewWindow, Wry,
+};
+
+/// The label of the window created by the harness.
+const HARNESS_WINDOW_LABEL: &str = "harness";
+/// The command used by the harness scripts to report their result.
+const RESOLVE_COMMAND: &str = "plugin:__TAURI_TEST_HARNESS__|resolve";
+/// Commands the harness requires access to.
+const HARNESS_COMMANDS: &[&str] = &[
+  RESOLVE_COMMAND,
+  "plugin:event|listen",
+  "plugin:event|unlisten",
+  "plugin:event|emit",
+  "plugin:event|emit_to",
+];
+/// The default time to wait for the webview before failing.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+const INDEX_HTML: &str =
+  "<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head><body></body></html>";
+
+type Job = Box<dyn FnOnce() + Send>;
+type ScriptResult = Result<JsonValue, JsonValue>;
+
+/// The thread running every harness event loop.
+///
+/// GTK must always be used from the thread that initialized it,
+/// so the harnesses of a test binary all run on the same thread, one at a time.
+fn webview_thread() -> &'static Mutex<Sender<Job>> {
+  static THREAD: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+  THREAD.get_or_init(|| {
+    let (tx, rx) = channel::<Job>();
+    thread::Builder::new()
+      .name("tauri-webview-harness".into())
+      .spawn(move || {
+        while let Ok(job) = rx.recv() {
+          job();
+        }
+      })
+      .expect("failed to spawn the webview harness thread");
+    Mutex::new(tx)
+  })
+}
+
+/// Ensures only one harness is alive at a time.
+fn harness_lock() -> MutexGuard<'static, ()> {
+  static LOCK: Mutex<()> = Mutex::new(());
+  // a failed test holding the lock must not fail the next ones
+  LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Whether a real webview can be created in the current environment.
+///
+/// Only Linux is supported, with either an X11 (e.g. `xvfb-run`) or a Wayland display available.
+fn is_supported() -> bool {
+  cfg!(target_os = "linux")
+    && (std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some())
+}
+
+/// Serves a blank page.
+struct HarnessAssets;
+
+impl<R: Runtime> Assets<R> for HarnessAssets {
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    (key.as_ref() == "/index.html").then(|| Cow::Borrowed(INDEX_HTML.as_bytes()))
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = (&str, &[u8])> + '_> {
+    Box::new(std::iter::once(("/index.html", INDEX_HTML.as_bytes())))
+  }
+
+  fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    Box::new(std::iter::empty())
+  }
+}
+
+/// Scripts waiting for their result, keyed by script id.
+#[derive(Default, Clone)]
+struct PendingScripts(Arc<Mutex<HashMap<u32, Sender<ScriptResult>>>>);
+
+#[command(root = "crate")]
+fn resolve(pending: State<'_, PendingScripts>, id: u32, ok: bool, value: Option<JsonValue>) {
+  if let Some(tx) = pending.0.lock().unwrap().remove(&id) {
+    let value = value.unwrap_or(JsonValue::Null);
+    let _ = tx.send(if ok { Ok(value) } else { Err(value) });
+  }
+}
+
+fn plugin<R: Runtime>() -> crate::plugin::TauriPlugin<R> {
+  crate::plugin::Builder::new("__TAURI_TEST_HARNESS__")
+    .invoke_handler(crate::generate_handler![resolve])
+    .build()
+}
+
+/// Grants the given commands to every window and webview of the app.
+fn runtime_authority<'a>(commands: impl IntoIterator<Item = &'a str>) -> RuntimeAuthority {
+  let any = vec![glob::Pattern::new("*").unwrap()];
+  let allowed_commands = commands
+    .into_iter()
+    .map(|command| {
+      (
+        command.to_string(),
+        vec![ResolvedCommand {
+          context: ExecutionContext::Local,
+          windows: any.clone(),
+          webviews: any.clone(),
+          ..Default::default()
+        }],
+      )
+    })
+    .collect();
+
+  RuntimeAuthority::new(
+    Default::default(),
+    Resolved {
+      allowed_commands,
+      ..Default::default()
+    },
+  )
+}
+
+type SetupHook = Box<dyn FnOnce(Builder<Wry>) -> Builder<Wry> + Send>;
+
+/// Builds a [`WebviewHarness`].
+///
+/// # Stability
+///
+/// This API is unstable.
+pub struct WebviewHarnessBuilder {
+  setup: Option<SetupHook>,
+  allowed_commands: Vec<String>,
+  timeout: Duration,
+}
+
+impl Default for WebviewHarnessBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl WebviewHarnessBuilder {
+  /// Creates a new harness builder.
+  pub fn new() -> Self {
+    Self {
+      setup: None,
+      allowed_commands: Vec::new(),
+      timeout: DEFAULT_TIMEOUT,
+    }
+  }
+
+  /// Configures the app [`Builder`] before the harness app is built,
+  /// e.g. to register plugins, commands or state.
+  ///
+  /// The closure runs on the harness thread.
+  #[must_use]
+  pub fn setup<F: FnOnce(Builder<Wry>) -> Builder<Wry> + Send + 'static>(mut self, f: F) -> Self {
+    self.setup.replace(Box::new(f));
+    self
+  }
+
+  /// Allows the harness webview to call the given command, e.g. `plugin:my-plugin|ping`.
+  ///
+  /// Plugin commands are always checked against the app capabilities
+  /// so they must be allowed explicitly; app commands are allowed by default.
+  #[must_use]
+  pub fn allow_command(mut self, command: impl Into<String>) -> Self {
+    self.allowed_commands.push(command.into());
+    self
+  }
+
+  /// Sets how long the harness waits for the webview before panicking. Defaults to 10 seconds.
+  #[must_use]
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Spawns the harness app and waits for its webview to be loaded.
+  ///
+  /// Returns `None` if a real webview is not available in this environment,
+  /// in which case the test should be skipped.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the app could not be built or the webview did not load in time.
+  pub fn build(self) -> Option<WebviewHarness> {
+    if !is_supported() {
+      return None;
+    }
+
+    let guard = harness_lock();
+
+    let Self {
+      setup,
+      allowed_commands,
+      timeout,
+    } = self;
+
+    let pending = PendingScripts::default();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_tx, ready_rx) = channel();
+    let (loaded_tx, loaded_rx) = sync_channel(1);
+    let (done_tx, done_rx) = channel();
+
+    let pending_ = pending.clone();
+    let stop_ = stop.clone();
+    let job: Job = Box::new(move || {
+      let mut builder = Builder::default()
+        .enable_macos_default_menu(false)
+        .plugin(plugin())
+        .manage(pending_);
+      #[cfg(any(windows, target_os = "linux"))]
+      {
+        builder = builder.any_thread();
+      }
+      if let Some(setup) = setup {
+        builder = setup(builder);
+      }
+
+      let mut context = mock_context(HarnessAssets);
+      context.runtime_authority = runtime_authority(
+        HARNESS_COMMANDS
+          .iter()
+          .copied()
+          .chain(allowed_commands.iter().map(String::as_str)),
+      );
+
+      let mut app = match builder.build(context) {
+        Ok(app) => app,
+        Err(e) => {
+          let _ = ready_tx.send(Err(e));
+          let _ = done_tx.send(());
+          return;
+        }
+      };
+
+      let window = WebviewWindowBuilder::new(
+        &app,
+        HARNESS_WINDOW_LABEL,
+        WebviewUrl::App("index.html".into()),
+      )
+      .visible(false)
+      .on_page_load(move |_window, payload| {
+        if payload.event() == PageLoadEvent::Finished {
+          let _ = loaded_tx.try_send(());
+        }
+      })
+      .build();
+      let _ = ready_tx.send(window.map(|window| (app.handle().clone(), window)));
+
+      while !stop_.load(Ordering::Acquire) {
+        app.run_iteration(|_app, _event| {});
+        thread::sleep(Duration::from_millis(1));
+      }
+      app.cleanup_before_exit();
+      drop(app);
+      let _ = done_tx.send(());
+    });
+
+    webview_thread()
+      .lock()
+      .unwrap()
+      .send(job)
+      .expect("the webview harness thread is not running");
+
+    let (app_handle, window) = ready_rx
+      .recv()
+      .expect("the webview harness thread panicked")
+      .expect("failed to create the webview harness app");
+
+    let harness = WebviewHarness {
+      app_handle,
+      window,
+      pending,
+      next_script_id: AtomicU32::new(0),
+      events: Default::default(),
+      timeout,
+      stop,
+      done: done_rx,
+      _guard: guard,
+    };
+
+    loaded_rx
+      .recv_timeout(timeout)
+      .expect("timed out waiting for the webview harness page to load");
+
+    Some(harness)
+  }
+}
+
+/// Drives a real webview in integration tests.
+///
+/// The harness runs a hidden [`WebviewWindow`] with the [`Wry`] runtime, serving a blank page
+/// with the Tauri IPC initialized. Only one harness can be alive at a time in a process:
+/// [`WebviewHarnessBuilder::build`] blocks until the previous one is dropped.
+///
+/// It is currently only supported on Linux, where CI can provide a display with `xvfb-run`.
+/// Elsewhere [`WebviewHarness::new`] returns `None` so tests can be skipped.
+///
+/// # Stability
+///
+/// This API is unstable.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// #[tauri::command]
+/// fn ping() -> &'static str {
+///   "pong"
+/// }
+///
+/// let Some(harness) = tauri::test::WebviewHarness::builder()
+///   .setup(|builder| builder.invoke_handler(tauri::generate_handler![ping]))
+///   .build()
+/// else {
+///   // no webview available, skip the test
+///   return;
+/// };
+///
+/// assert_eq!(harness.invoke_command("ping", ()), Ok("pong".into()));
+/// assert_eq!(harness.invoke_js("return 1 + 1"), Ok(2.into()));
+/// ```
+pub struct WebviewHarness {
+  app_handle: AppHandle<Wry>,
+  window: WebviewWindow<Wry>,
+  pending: PendingScripts,
+  next_script_id: AtomicU32,
+  events: Mutex<HashMap<String, (EventId, Receiver<JsonValue>)>>,
+  timeout: Duration,
+  stop: Arc<AtomicBool>,
+  done: Receiver<()>,
+  _guard: MutexGuard<'static, ()>,
+}
+
+impl WebviewHarness {
+  /// Creates a harness with the default configuration. See [`WebviewHarnessBuilder::build`].
+  pub fn new() -> Option<Self> {
+    Self::builder().build()
+  }
+
+  /// Creates a new harness builder.
+  pub fn builder() -> WebviewHarnessBuilder {
+    WebviewHarnessBuilder::new()
+  }
+
+  /// The handle of the harness app.
+  pub fn app_handle(&self) -> &AppHandle<Wry> {
+    &self.app_handle
+  }
+
+  /// The harness window.
+  pub fn webview(&self) -> &WebviewWindow<Wry> {
+    &self.window
+  }
+
+  /// Runs the given script as the body of an async function and waits for it to complete.
+  ///
+  /// Use `return` to resolve a value, which must be serializable to JSON.
+  /// A thrown error or rejected promise is returned as the error.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the script does not complete in time.
+  pub fn invoke_js(&self, script: &str) -> Result<JsonValue, JsonValue> {
+    let id = self.next_script_id.fetch_add(1, Ordering::Relaxed);
+    let (tx, rx) = channel();
+    self.pending.0.lock().unwrap().insert(id, tx);
+
+    self
+      .window
+      .eval(&format!(
+        r#"(async function () {{
+{script}
+}})()
+  .then((value) => [true, value], (error) => [false, error instanceof Error ? error.toString() : error])
+  .then(([ok, value]) => window.__TAURI_INTERNALS__.invoke('{RESOLVE_COMMAND}', {{ id: {id}, ok, value }}))"#
+      ))
+      .expect("failed to evaluate script");
+
+    rx.recv_timeout(self.timeout).unwrap_or_else(|_| {
+      self.pending.0.lock().unwrap().remove(&id);
+      panic!("timed out waiting for script to complete:\n{script}")
+    })
+  }
+
+  /// Invokes the given command from the webview and returns its response,
+  /// like `window.__TAURI_INTERNALS__.invoke(cmd, args)` would.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the arguments cannot be serialized or the command does not respond in time.
+  pub fn invoke_command<A: Serialize>(&self, cmd: &str, args: A) -> Result<JsonValue, JsonValue> {
+    let cmd = serde_json::to_string(cmd).unwrap();
+    let args = serde_json::to_string(&args).expect("failed to serialize command arguments");
+    self.invoke_js(&format!(
+      "return window.__TAURI_INTERNALS__.invoke({cmd}, {args})"
+    ))
+  }
+
+  /// Starts recording emissions of the given event, to any target.
+  ///
+  /// [`Self::wait_for_event`] only records events once it is first called for a given name,
+  /// so call this beforehand when the event might be emitted earlier.
+  pub fn track_event(&self, event: &str) {
+    let mut events = self.events.lock().unwrap();
+    if !events.contains_key(event) {
+      let (tx, rx) = channel();
+      let id = self.app_handle.listen_any(event, move |event| {
+        let _ = tx.send(serde_json::from_str(event.payload()).unwrap_or(JsonValue::Null));
+      });
+      events.insert(event.into(), (id, rx));
+    }
+  }
+
+  /// Waits for the next emission of the given event and returns its payload.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the event is not emitted in time.
+  pub fn wait_for_event(&self, event: &str) -> JsonValue {
+    self.track_event(event);
+    let events = self.events.lock().unwrap();
+    events[event]
+      .1
+      .recv_timeout(self.timeout)
+      .unwrap_or_else(|_| panic!("timed out waiting for the `{event}` event"))
+  }
+}
+
+impl Drop for WebviewHarness {
+  fn drop(&mut self) {
+    for (_, (id, _)) in self.events.lock().unwrap().drain() {
+      self.app_handle.unlisten(id);
+    }
+    self.stop.store(true, Ordering::Release);
+    // wait for the app to be dropped before the next harness can start
+    let _ = self.done.recv_timeout(self.timeout);
+  }
+}
@@ -12,9 +12,10 @@ use crate::menu::MenuEvent;
 use crate::resources::Resource;
 use crate::{
   image::Image, menu::run_item_main_thread, AppHandle, Manager, PhysicalPosition, Rect, Runtime,
+  Theme,
 };
 use serde::Serialize;
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 pub use tray_icon::TrayIconId;
 
 /// Describes the mouse button state.
@@ -72,7 +73,7 @@ impl From<tray_icon::MouseButton> for MouseButton {
 ///
 /// ## Platform-specific:
 ///
-/// - **Linux**: Unsupported. The event is not emmited even though the icon is shown
+/// - **Linux**: The mouse events are not emmited even though the icon is shown
 ///   and will still show a context menu on right click.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -118,6 +119,13 @@ pub enum TrayIconEvent {
     /// Position and size of the tray icon.
     rect: Rect,
   },
+  /// The system theme changed and the icon was swapped, see [`TrayIconBuilder::icon_for_theme`].
+  ThemeChanged {
+    /// Id of the tray icon which triggered this event.
+    id: TrayIconId,
+    /// The new system theme.
+    theme: Theme,
+  },
 }
 
 impl TrayIconEvent {
@@ -128,6 +136,7 @@ impl TrayIconEvent {
       TrayIconEvent::Enter { id, .. } => id,
       TrayIconEvent::Move { id, .. } => id,
       TrayIconEvent::Leave { id, .. } => id,
+      TrayIconEvent::ThemeChanged { id, .. } => id,
     }
   }
 }
@@ -180,11 +189,31 @@ impl From<tray_icon::TrayIconEvent> for TrayIconEvent {
   }
 }
 
+/// The icons of a tray icon that follows the system theme.
+#[derive(Debug, Default)]
+pub(crate) struct ThemedIcons {
+  /// The icon used for the themes without an icon.
+  pub(crate) fallback: Option<Image<'static>>,
+  pub(crate) icons: HashMap<Theme, Image<'static>>,
+}
+
+impl ThemedIcons {
+  /// The icon to show for the given theme, the light one being used when the theme is unknown.
+  pub(crate) fn icon_for(&self, theme: Option<Theme>) -> Option<&Image<'static>> {
+    self
+      .icons
+      .get(&theme.unwrap_or(Theme::Light))
+      .or(self.fallback.as_ref())
+  }
+}
+
 /// [`TrayIcon`] builder struct and associated methods.
 #[derive(Default)]
 pub struct TrayIconBuilder<R: Runtime> {
   on_menu_event: Option<GlobalMenuEventListener<AppHandle<R>>>,
   on_tray_icon_event: Option<GlobalTrayIconEventListener<TrayIcon<R>>>,
+  icon: Option<Image<'static>>,
+  themed_icons: HashMap<Theme, Image<'static>>,
   inner: tray_icon::TrayIconBuilder,
 }
 
@@ -200,6 +229,8 @@ impl<R: Runtime> TrayIconBuilder<R> {
       inner: tray_icon::TrayIconBuilder::new(),
       on_menu_event: None,
       on_tray_icon_event: None,
+      icon: None,
+      themed_icons: HashMap::new(),
     }
   }
 
@@ -232,10 +263,18 @@ impl<R: Runtime> TrayIconBuilder<R> {
   /// - **Linux:** Sometimes the icon won't be visible unless a menu is set.
   ///   Setting an empty [`Menu`](crate::menu::Menu) is enough.
   pub fn icon(mut self, icon: Image<'_>) -> Self {
-    let icon = icon.try_into().ok();
-    if let Some(icon) = icon {
-      self.inner = self.inner.with_icon(icon);
-    }
+    self.icon.replace(icon.to_owned());
+    self
+  }
+
+  /// Set the icon to show when the system theme is `theme`, falling back to [`Self::icon`] for the other themes.
+  ///
+  /// The icon is swapped when the windows report a theme change, emitting [`TrayIconEvent::ThemeChanged`]
+  /// so apps drawing their icons can render them again. This replaces icons set with [`TrayIcon::set_icon`].
+  ///
+  /// Prefer [`Self::icon_as_template`] for monochrome icons on macOS, which also applies to the swapped icons.
+  pub fn icon_for_theme(mut self, theme: Theme, icon: Image<'_>) -> Self {
+    self.themed_icons.insert(theme, icon.to_owned());
     self
   }
 
@@ -315,11 +354,29 @@ impl<R: Runtime> TrayIconBuilder<R> {
   /// Builds and adds a new [`TrayIcon`] to the system tray.
   pub fn build<M: Manager<R>>(self, manager: &M) -> crate::Result<TrayIcon<R>> {
     let id = self.id().clone();
-    let inner = self.inner.build()?;
+    let app_handle = manager.app_handle();
+
+    let themed_icons = ThemedIcons {
+      fallback: self.icon,
+      icons: self.themed_icons,
+    };
+    let theme = if themed_icons.icons.is_empty() {
+      None
+    } else {
+      app_handle.manager.tray.current_theme(app_handle)
+    };
+    let mut inner = self.inner;
+    if let Some(icon) = themed_icons
+      .icon_for(theme)
+      .and_then(|icon| icon.clone().try_into().ok())
+    {
+      inner = inner.with_icon(icon);
+    }
+
     let icon = TrayIcon {
       id,
-      inner,
-      app_handle: manager.app_handle().clone(),
+      inner: inner.build()?,
+      app_handle: app_handle.clone(),
     };
 
     icon.register(
@@ -328,6 +385,16 @@ impl<R: Runtime> TrayIconBuilder<R> {
       self.on_tray_icon_event,
     );
 
+    if !themed_icons.icons.is_empty() {
+      app_handle
+        .manager
+        .tray
+        .themed_icons
+        .lock()
+        .unwrap()
+        .insert(icon.id.clone(), themed_icons);
+    }
+
     Ok(icon)
   }
 }
@@ -445,6 +512,33 @@ impl<R: Runtime> TrayIcon<R> {
     run_item_main_thread!(self, |self_: Self| self_.inner.set_icon(icon))?.map_err(Into::into)
   }
 
+  /// Sets the icon to show when the system theme is `theme`, see [`TrayIconBuilder::icon_for_theme`].
+  /// If `None` is provided, the icon set with [`TrayIconBuilder::icon`] is used for this theme.
+  ///
+  /// The icon is set right away if `theme` is the current system theme.
+  pub fn set_icon_for_theme(&self, theme: Theme, icon: Option<Image<'_>>) -> crate::Result<()> {
+    let tray = &self.app_handle.manager.tray;
+    let current_theme = tray.current_theme(&self.app_handle);
+    let icon = {
+      let mut themed_icons = tray.themed_icons.lock().unwrap();
+      let icons = themed_icons.entry(self.id.clone()).or_default();
+      match icon {
+        Some(icon) => {
+          icons.icons.insert(theme, icon.to_owned());
+        }
+        None => {
+          icons.icons.remove(&theme);
+        }
+      }
+      icons.icon_for(current_theme).cloned()
+    };
+
+    match icon {
+      Some(icon) if current_theme == Some(theme) => self.set_icon(Some(icon)),
+      _ => Ok(()),
+    }
+  }
+
   /// Sets a new tray menu.
   ///
   /// ## Platform-specific:
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use serde::Deserialize;
 
@@ -14,7 +14,7 @@ use crate::{
   plugin::{Builder, TauriPlugin},
   resources::ResourceId,
   tray::TrayIconBuilder,
-  AppHandle, Manager, Runtime, Webview,
+  AppHandle, Manager, Runtime, Theme, Webview,
 };
 
 use super::{TrayIcon, TrayIconEvent};
@@ -25,6 +25,7 @@ struct TrayIconOptions {
   id: Option<String>,
   menu: Option<(ResourceId, ItemKind)>,
   icon: Option<JsImage>,
+  icon_for_theme: Option<HashMap<Theme, JsImage>>,
   tooltip: Option<String>,
   title: Option<String>,
   temp_dir_path: Option<PathBuf>,
@@ -66,6 +67,9 @@ fn new<R: Runtime>(
   if let Some(icon) = options.icon {
     builder = builder.icon(icon.into_img(&resources_table)?.as_ref().clone());
   }
+  for (theme, icon) in options.icon_for_theme.unwrap_or_default() {
+    builder = builder.icon_for_theme(theme, icon.into_img(&resources_table)?.as_ref().clone());
+  }
   if let Some(tooltip) = options.tooltip {
     builder = builder.tooltip(tooltip);
   }
@@ -127,6 +131,22 @@ fn set_icon<R: Runtime>(
   tray.set_icon(icon)
 }
 
+#[command(root = "crate")]
+fn set_icon_for_theme<R: Runtime>(
+  webview: Webview<R>,
+  rid: ResourceId,
+  theme: Theme,
+  icon: Option<JsImage>,
+) -> crate::Result<()> {
+  let resources_table = webview.resources_table();
+  let tray = resources_table.get::<TrayIcon<R>>(rid)?;
+  let icon = match icon {
+    Some(i) => Some(i.into_img(&resources_table)?.as_ref().clone()),
+    None => None,
+  };
+  tray.set_icon_for_theme(theme, icon)
+}
+
 #[command(root = "crate")]
 fn set_menu<R: Runtime>(
   webview: Webview<R>,
@@ -226,6 +246,7 @@ pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
       get_by_id,
       remove_by_id,
       set_icon,
+      set_icon_for_theme,
       set_menu,
       set_tooltip,
       set_title,
@@ -0,0 +1,520 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Memory and CPU usage of the processes rendering the webviews.
+
+use std::{
+  collections::HashMap,
+  io,
+  sync::{mpsc, Mutex},
+  time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AppHandle, Emitter, Runtime};
+
+/// The event emitted with the metrics of all webviews when `app > webviewMetricsInterval` is set.
+pub(crate) const METRICS_EVENT: &str = "tauri://metrics";
+
+/// The minimum time between two CPU samples of a process.
+///
+/// Webviews sharing a process sampled in a row reuse the previous sample
+/// instead of measuring the CPU usage over a meaningless interval.
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Memory and CPU usage of the process rendering a webview.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewMetrics {
+  /// Identifier of the sampled process.
+  pub pid: u32,
+  /// Resident memory of the process in bytes.
+  pub memory_bytes: u64,
+  /// CPU usage since the previous sample of the process,
+  /// as a percentage of a single core so it can exceed 100 on multi-core systems.
+  ///
+  /// The first sample of a process reports `0`.
+  pub cpu_percent: f64,
+  /// Whether the metrics are the totals of the app (the app process and its children on Linux)
+  /// because the platform does not expose the process rendering the webview.
+  pub app_total: bool,
+}
+
+/// Cumulative resource usage of a process.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ProcessUsage {
+  cpu_time: Duration,
+  memory_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+  at: Instant,
+  cpu_time: Duration,
+  cpu_percent: f64,
+}
+
+/// Computes the CPU usage in percent of a single core.
+fn cpu_percent(cpu_time: Duration, wall_time: Duration) -> f64 {
+  if wall_time.is_zero() {
+    0.0
+  } else {
+    cpu_time.as_secs_f64() / wall_time.as_secs_f64() * 100.0
+  }
+}
+
+/// Records the cumulative CPU time of `pid` at `at` and returns its CPU usage since the previous sample.
+fn record_cpu_sample(
+  samples: &mut HashMap<u32, CpuSample>,
+  pid: u32,
+  at: Instant,
+  cpu_time: Duration,
+) -> f64 {
+  let previous = samples.get(&pid).copied();
+  match previous {
+    Some(previous) if at.saturating_duration_since(previous.at) < MIN_SAMPLE_INTERVAL => {
+      previous.cpu_percent
+    }
+    previous => {
+      let cpu_percent = previous
+        .map(|previous| {
+          cpu_percent(
+            // the CPU time of the app totals decreases when a child process exits
+            cpu_time.saturating_sub(previous.cpu_time),
+            at.saturating_duration_since(previous.at),
+          )
+        })
+        .unwrap_or_default();
+      samples.insert(
+        pid,
+        CpuSample {
+          at,
+          cpu_time,
+          cpu_percent,
+        },
+      );
+      cpu_percent
+    }
+  }
+}
+
+/// Samples the processes and keeps track of their CPU time between samples.
+#[derive(Debug, Default)]
+pub(crate) struct MetricsSampler {
+  samples: Mutex<HashMap<u32, CpuSample>>,
+}
+
+impl MetricsSampler {
+  /// Samples the given webview process, or the app totals if the process is unknown.
+  pub(crate) fn sample(&self, pid: Option<u32>) -> crate::Result<WebviewMetrics> {
+    let (pid, app_total, usage) = match pid {
+      Some(pid) => (pid, false, imp::process_usage(pid)),
+      None => (std::process::id(), true, app_usage()),
+    };
+    let usage = usage.map_err(crate::Error::ProcessMetrics)?;
+
+    let cpu_percent = record_cpu_sample(
+      &mut self.samples.lock().unwrap(),
+      pid,
+      Instant::now(),
+      usage.cpu_time,
+    );
+
+    Ok(WebviewMetrics {
+      pid,
+      memory_bytes: usage.memory_bytes,
+      cpu_percent,
+      app_total,
+    })
+  }
+}
+
+/// Sums the usage of the app process and its children.
+fn app_usage() -> io::Result<ProcessUsage> {
+  let mut total = imp::process_usage(std::process::id())?;
+  for pid in imp::child_process_ids() {
+    // the child might have exited in the meantime
+    if let Ok(usage) = imp::process_usage(pid) {
+      total.cpu_time += usage.cpu_time;
+      total.memory_bytes += usage.memory_bytes;
+    }
+  }
+  Ok(total)
+}
+
+/// The interval at which the metrics are emitted, raised to [`MIN_SAMPLE_INTERVAL`]
+/// since sampling faster reuses the previous samples.
+fn emit_interval(interval: Duration) -> Duration {
+  if interval < MIN_SAMPLE_INTERVAL {
+    log::warn!(
+      "the webview metrics interval of {}ms is raised to {}ms",
+      interval.as_millis(),
+      MIN_SAMPLE_INTERVAL.as_millis()
+    );
+    MIN_SAMPLE_INTERVAL
+  } else {
+    interval
+  }
+}
+
+/// The thread emitting the metrics of all webviews, see [`spawn_emitter`].
+pub(crate) struct MetricsEmitter {
+  stop: mpsc::Sender<()>,
+  #[cfg(test)]
+  thread: std::thread::JoinHandle<()>,
+}
+
+impl MetricsEmitter {
+  /// Stops the thread when it wakes up.
+  ///
+  /// The thread is not joined since it might be waiting for the event loop to sample the webviews.
+  pub(crate) fn stop(&self) {
+    let _ = self.stop.send(());
+  }
+}
+
+/// Emits the metrics of all webviews with the [`METRICS_EVENT`] at the given interval until the emitter is stopped.
+pub(crate) fn spawn_emitter<R: Runtime>(
+  app: AppHandle<R>,
+  interval: Duration,
+) -> io::Result<MetricsEmitter> {
+  let interval = emit_interval(interval);
+  let (stop, stopped) = mpsc::channel();
+  let _thread = std::thread::Builder::new()
+    .name("tauri-webview-metrics".into())
+    .spawn(move || {
+      // a disconnected channel also stops the thread, e.g. if the app manager is dropped
+      while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+        if let Err(e) = app.emit(METRICS_EVENT, app.all_webview_metrics()) {
+          log::error!("failed to emit webview metrics: {e}");
+        }
+      }
+    })?;
+  Ok(MetricsEmitter {
+    stop,
+    #[cfg(test)]
+    thread: _thread,
+  })
+}
+
+/// Parses the cumulative CPU time from `/proc/<pid>/stat` and the resident memory from `/proc/<pid>/statm`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_usage(
+  stat: &str,
+  statm: &str,
+  ticks_per_second: u64,
+  page_size: u64,
+) -> Option<ProcessUsage> {
+  // the process name can contain spaces and parentheses, the fields start after the last `)`
+  let fields = stat[stat.rfind(')')? + 1..]
+    .split_whitespace()
+    .collect::<Vec<_>>();
+  // `utime` and `stime` are the 14th and 15th fields, in clock ticks
+  let ticks = fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?;
+  let resident_pages = statm.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+  Some(ProcessUsage {
+    cpu_time: Duration::from_nanos(ticks * 1_000_000_000 / ticks_per_second.max(1)),
+    memory_bytes: resident_pages * page_size,
+  })
+}
+
+/// Parses the parent process identifier from `/proc/<pid>/stat`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_parent(stat: &str) -> Option<u32> {
+  stat[stat.rfind(')')? + 1..]
+    .split_whitespace()
+    .nth(1)?
+    .parse()
+    .ok()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+  use super::*;
+  use std::fs;
+
+  pub fn process_usage(pid: u32) -> io::Result<ProcessUsage> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat"))?;
+    let statm = fs::read_to_string(format!("/proc/{pid}/statm"))?;
+    let (ticks_per_second, page_size) = unsafe {
+      (
+        libc::sysconf(libc::_SC_CLK_TCK),
+        libc::sysconf(libc::_SC_PAGESIZE),
+      )
+    };
+    parse_proc_usage(&stat, &statm, ticks_per_second as u64, page_size as u64)
+      .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unexpected /proc format"))
+  }
+
+  /// The WebKitGTK web and network processes are children of the app process.
+  pub fn child_process_ids() -> Vec<u32> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+      return Vec::new();
+    };
+    let processes = entries
+      .flatten()
+      .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+      .filter_map(|pid| {
+        let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        Some((pid, parse_proc_parent(&stat)?))
+      })
+      .collect::<Vec<_>>();
+
+    let mut children = Vec::new();
+    let mut parents = vec![std::process::id()];
+    while let Some(parent) = parents.pop() {
+      for (pid, _) in processes.iter().filter(|(_, ppid)| *ppid == parent) {
+        children.push(*pid);
+        parents.push(*pid);
+      }
+    }
+    children
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use super::*;
+
+  #[allow(deprecated)]
+  pub fn process_usage(pid: u32) -> io::Result<ProcessUsage> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_taskinfo>::uninit();
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let info = unsafe {
+      if libc::proc_pidinfo(
+        pid as libc::c_int,
+        libc::PROC_PIDTASKINFO,
+        0,
+        info.as_mut_ptr().cast(),
+        size,
+      ) != size
+      {
+        return Err(io::Error::last_os_error());
+      }
+      info.assume_init()
+    };
+
+    // the CPU times are in Mach absolute time units
+    let mut timebase = libc::mach_timebase_info { numer: 1, denom: 1 };
+    unsafe { libc::mach_timebase_info(&mut timebase) };
+    let ticks = u128::from(info.pti_total_user + info.pti_total_system);
+    let nanos = ticks * u128::from(timebase.numer) / u128::from(timebase.denom.max(1));
+
+    Ok(ProcessUsage {
+      cpu_time: Duration::from_nanos(nanos as u64),
+      memory_bytes: info.pti_resident_size,
+    })
+  }
+
+  pub fn child_process_ids() -> Vec<u32> {
+    Vec::new()
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use super::*;
+  use windows::Win32::{
+    Foundation::{CloseHandle, FILETIME},
+    System::{
+      ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+      Threading::{GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+    },
+  };
+
+  fn filetime_duration(time: FILETIME) -> Duration {
+    // FILETIME is expressed in 100-nanosecond intervals
+    let intervals = (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+    Duration::from_nanos(intervals * 100)
+  }
+
+  pub fn process_usage(pid: u32) -> io::Result<ProcessUsage> {
+    unsafe {
+      let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)?;
+
+      let mut creation = FILETIME::default();
+      let mut exit = FILETIME::default();
+      let mut kernel = FILETIME::default();
+      let mut user = FILETIME::default();
+      let mut counters = PROCESS_MEMORY_COUNTERS::default();
+      let result = GetProcessTimes(process, &mut creation, &mut exit, &mut kernel, &mut user)
+        .and_then(|_| {
+          GetProcessMemoryInfo(
+            process,
+            &mut counters,
+            std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+          )
+        });
+      let _ = CloseHandle(process);
+      result?;
+
+      Ok(ProcessUsage {
+        cpu_time: filetime_duration(kernel) + filetime_duration(user),
+        memory_bytes: counters.WorkingSetSize as u64,
+      })
+    }
+  }
+
+  pub fn child_process_ids() -> Vec<u32> {
+    Vec::new()
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod imp {
+  use super::*;
+
+  pub fn process_usage(_pid: u32) -> io::Result<ProcessUsage> {
+    Err(io::Error::new(
+      io::ErrorKind::Unsupported,
+      "process metrics are not supported on this platform",
+    ))
+  }
+
+  pub fn child_process_ids() -> Vec<u32> {
+    Vec::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cpu_percent_math() {
+    assert_eq!(cpu_percent(Duration::from_millis(50), Duration::ZERO), 0.0);
+    assert_eq!(
+      cpu_percent(Duration::from_millis(50), Duration::from_millis(100)),
+      50.0
+    );
+    // two busy cores
+    assert_eq!(
+      cpu_percent(Duration::from_millis(200), Duration::from_millis(100)),
+      200.0
+    );
+  }
+
+  #[test]
+  fn cpu_samples() {
+    let mut samples = HashMap::new();
+    let start = Instant::now();
+
+    // the first sample has no reference
+    assert_eq!(
+      record_cpu_sample(&mut samples, 1, start, Duration::from_secs(10)),
+      0.0
+    );
+    let at = start + Duration::from_secs(1);
+    assert_eq!(
+      record_cpu_sample(&mut samples, 1, at, Duration::from_millis(10_250)),
+      25.0
+    );
+    // sampling again right away reuses the previous value
+    assert_eq!(
+      record_cpu_sample(
+        &mut samples,
+        1,
+        at + Duration::from_millis(1),
+        Duration::from_secs(11)
+      ),
+      25.0
+    );
+    // the CPU time going backwards does not underflow
+    assert_eq!(
+      record_cpu_sample(
+        &mut samples,
+        1,
+        at + Duration::from_secs(1),
+        Duration::from_secs(5)
+      ),
+      0.0
+    );
+    // processes are tracked separately
+    assert_eq!(
+      record_cpu_sample(&mut samples, 2, at, Duration::from_secs(1)),
+      0.0
+    );
+  }
+
+  #[test]
+  fn parse_proc_files() {
+    let stat = "1234 (Web Content (1)) S 42 1234 1234 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 1 0 100 1000 10";
+    let usage = parse_proc_usage(stat, "1000 256 10 1 0 100 0", 100, 4096).unwrap();
+    assert_eq!(usage.cpu_time, Duration::from_secs(3));
+    assert_eq!(usage.memory_bytes, 256 * 4096);
+    assert_eq!(parse_proc_parent(stat), Some(42));
+
+    assert_eq!(parse_proc_usage("1234 (app", "", 100, 4096), None);
+    assert_eq!(parse_proc_parent("garbage"), None);
+  }
+
+  #[test]
+  fn serialize_metrics() {
+    let metrics = WebviewMetrics {
+      pid: 42,
+      memory_bytes: 1024,
+      cpu_percent: 12.5,
+      app_total: true,
+    };
+    let json = serde_json::to_value(metrics).unwrap();
+    assert_eq!(
+      json,
+      serde_json::json!({
+        "pid": 42,
+        "memoryBytes": 1024,
+        "cpuPercent": 12.5,
+        "appTotal": true,
+      })
+    );
+    assert_eq!(
+      serde_json::from_value::<WebviewMetrics>(json).unwrap(),
+      metrics
+    );
+  }
+
+  #[test]
+  fn minimum_emit_interval() {
+    assert_eq!(emit_interval(Duration::ZERO), MIN_SAMPLE_INTERVAL);
+    assert_eq!(
+      emit_interval(Duration::from_millis(10)),
+      MIN_SAMPLE_INTERVAL
+    );
+    assert_eq!(
+      emit_interval(Duration::from_secs(2)),
+      Duration::from_secs(2)
+    );
+  }
+
+  #[test]
+  fn stop_emitter() {
+    let app = crate::test::mock_app();
+    let emitter = spawn_emitter(app.handle().clone(), Duration::from_secs(60)).unwrap();
+    emitter.stop();
+
+    // the thread stops without waiting for the interval to elapse
+    let start = Instant::now();
+    while !emitter.thread.is_finished() {
+      assert!(
+        start.elapsed() < Duration::from_secs(10),
+        "the metrics thread did not stop"
+      );
+      std::thread::sleep(Duration::from_millis(10));
+    }
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+  #[test]
+  fn sample_current_process() {
+    let sampler = MetricsSampler::default();
+    let metrics = sampler.sample(Some(std::process::id())).unwrap();
+    assert_eq!(metrics.pid, std::process::id());
+    assert!(metrics.memory_bytes > 0);
+    assert!(!metrics.app_total);
+
+    let metrics = sampler.sample(None).unwrap();
+    assert!(metrics.app_total);
+  }
+}
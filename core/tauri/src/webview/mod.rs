@@ -4,15 +4,19 @@
 
 //! The Tauri webview types and functions.
 
+pub(crate) mod metrics;
 pub(crate) mod plugin;
 mod webview_window;
 
+pub use metrics::WebviewMetrics;
 pub use webview_window::{WebviewWindow, WebviewWindowBuilder};
 
 use http::HeaderMap;
 use serde::Serialize;
 use tauri_macros::default_runtime;
-pub use tauri_runtime::webview::PageLoadEvent;
+pub use tauri_runtime::webview::{
+  FindOptions, FindResult, PageLoadEvent, UnsupportedWebviewSetting,
+};
 #[cfg(desktop)]
 use tauri_runtime::{
   dpi::{PhysicalPosition, PhysicalSize, Position, Size},
@@ -22,15 +26,15 @@ use tauri_runtime::{
   webview::{DetachedWebview, PendingWebview, WebviewAttributes},
   Rect, WebviewDispatch,
 };
-use tauri_utils::config::{WebviewUrl, WindowConfig};
+use tauri_utils::config::{CookiePersistence, WebviewSettingsConfig, WebviewUrl, WindowConfig};
 pub use url::Url;
 
 use crate::{
   app::{UriSchemeResponder, WebviewEvent},
   event::{EmitArgs, EventTarget},
   ipc::{
-    CallbackFn, CommandArg, CommandItem, Invoke, InvokeBody, InvokeError, InvokeMessage,
-    InvokeResolver, Origin, OwnedInvokeResponder,
+    router::Route, CallbackFn, CommandArg, CommandItem, Invoke, InvokeBody, InvokeError,
+    InvokeMessage, InvokeResolver, Origin, OwnedInvokeResponder,
   },
   manager::{webview::WebviewLabelDef, AppManager},
   sealed::{ManagerBase, RuntimeOrDispatch},
@@ -629,12 +633,17 @@ tauri::Builder::default()
       self.into_pending_webview(&window, window.label(), &window_labels, &webview_labels)?;
 
     pending.webview_attributes.bounds = Some(Rect { size, position });
+    let cookie_persistence = pending.webview_attributes.effective_cookie_persistence();
 
     let webview = match &mut window.runtime() {
       RuntimeOrDispatch::Dispatch(dispatcher) => dispatcher.create_webview(pending),
       _ => unimplemented!(),
     }
-    .map(|webview| app_manager.webview.attach_webview(window.clone(), webview))?;
+    .map(|webview| {
+      app_manager
+        .webview
+        .attach_webview(window.clone(), webview, cookie_persistence)
+    })?;
 
     Ok(webview)
   }
@@ -754,6 +763,15 @@ fn main() {
     self
   }
 
+  /// Sets whether the webview cookies and website data are kept between runs.
+  ///
+  /// See [`CookiePersistence`] for the data store used on each platform.
+  #[must_use]
+  pub fn cookie_persistence(mut self, cookie_persistence: CookiePersistence) -> Self {
+    self.webview_attributes.cookie_persistence = cookie_persistence;
+    self
+  }
+
   /// Set a proxy URL for the WebView for all network requests.
   ///
   /// Must be either a `http://` or a `socks5://` URL.
@@ -800,6 +818,31 @@ fn main() {
     self.webview_attributes.zoom_hotkeys_enabled = enabled;
     self
   }
+
+  /// Whether to unregister the service workers of the webview when the app version changes.
+  ///
+  /// The app version is recorded in the app local data directory and compared when the webview is created.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android / iOS**: Unsupported.
+  #[must_use]
+  pub fn clear_service_workers_on_version_change(mut self, enabled: bool) -> Self {
+    self
+      .webview_attributes
+      .clear_service_workers_on_version_change = enabled;
+    self
+  }
+
+  /// Sets the webview engine settings such as the media autoplay policy, the background throttling and smooth scrolling.
+  ///
+  /// Settings the platform webview does not support are ignored with a warning,
+  /// see [`Webview::unsupported_webview_settings`].
+  #[must_use]
+  pub fn webview_settings(mut self, settings: WebviewSettingsConfig) -> Self {
+    self.webview_attributes.webview_settings = settings;
+    self
+  }
 }
 
 /// Webview.
@@ -812,6 +855,7 @@ pub struct Webview<R: Runtime> {
   /// The webview created by the runtime.
   pub(crate) webview: DetachedWebview<EventLoopMessage, R>,
   pub(crate) resources_table: Arc<Mutex<ResourceTable>>,
+  cookie_persistence: CookiePersistence,
 }
 
 impl<R: Runtime> std::fmt::Debug for Webview<R> {
@@ -831,6 +875,7 @@ impl<R: Runtime> Clone for Webview<R> {
       app_handle: self.app_handle.clone(),
       webview: self.webview.clone(),
       resources_table: self.resources_table.clone(),
+      cookie_persistence: self.cookie_persistence,
     }
   }
 }
@@ -853,13 +898,18 @@ impl<R: Runtime> PartialEq for Webview<R> {
 /// Base webview functions.
 impl<R: Runtime> Webview<R> {
   /// Create a new webview that is attached to the window.
-  pub(crate) fn new(window: Window<R>, webview: DetachedWebview<EventLoopMessage, R>) -> Self {
+  pub(crate) fn new(
+    window: Window<R>,
+    webview: DetachedWebview<EventLoopMessage, R>,
+    cookie_persistence: CookiePersistence,
+  ) -> Self {
     Self {
       window_label: Arc::new(Mutex::new(window.label().into())),
       manager: window.manager.clone(),
       app_handle: window.app_handle.clone(),
       webview,
       resources_table: Default::default(),
+      cookie_persistence,
     }
   }
 
@@ -886,6 +936,14 @@ impl<R: Runtime> Webview<R> {
     &self.webview.label
   }
 
+  /// Whether the webview cookies and website data are kept between runs.
+  ///
+  /// This is the mode in effect, so it is [`CookiePersistence::Session`] for incognito webviews
+  /// and [`CookiePersistence::Persistent`] on Android.
+  pub fn cookie_persistence(&self) -> CookiePersistence {
+    self.cookie_persistence
+  }
+
   /// Registers a window event listener.
   pub fn on_webview_event<F: Fn(&WebviewEvent) + Send + 'static>(&self, f: F) {
     self
@@ -1096,6 +1154,26 @@ fn main() {
     self.webview.dispatcher.navigate(url).map_err(Into::into)
   }
 
+  /// Returns the webview engine settings in effect.
+  ///
+  /// Settings the platform webview does not support are unset.
+  pub fn webview_settings(&self) -> crate::Result<WebviewSettingsConfig> {
+    self
+      .webview
+      .dispatcher
+      .webview_settings()
+      .map_err(Into::into)
+  }
+
+  /// Returns the webview engine settings that were ignored because the platform webview does not support them.
+  pub fn unsupported_webview_settings(&self) -> crate::Result<Vec<UnsupportedWebviewSetting>> {
+    self
+      .webview
+      .dispatcher
+      .unsupported_webview_settings()
+      .map_err(Into::into)
+  }
+
   fn is_local_url(&self, current_url: &Url) -> bool {
     // if from `tauri://` custom protocol
     ({
@@ -1140,7 +1218,6 @@ fn main() {
   /// Handles this window receiving an [`InvokeRequest`].
   pub fn on_message(self, request: InvokeRequest, responder: Box<OwnedInvokeResponder<R>>) {
     let manager = self.manager_owned();
-    let is_local = self.is_local_url(&request.url);
 
     // ensure the passed key matches what our manager should have injected
     let expected = manager.invoke_key();
@@ -1160,6 +1237,18 @@ fn main() {
       return;
     }
 
+    #[cfg(feature = "tracing")]
+    let span = tracing::trace_span!(
+      "ipc::request::invoke",
+      cmd = request.cmd,
+      window = self.window().label(),
+      webview = self.label(),
+      payload_size = request.body.size(),
+      outcome = tracing::field::Empty
+    );
+    #[cfg(feature = "tracing")]
+    let invoke_span = span.clone();
+
     let custom_responder = self.manager().webview.invoke_responder.clone();
 
     let resolver = InvokeResolver::new(
@@ -1167,6 +1256,9 @@ fn main() {
       Arc::new(Mutex::new(Some(Box::new(
         #[allow(unused_variables)]
         move |webview: Webview<R>, cmd, response, callback, error| {
+          #[cfg(feature = "tracing")]
+          invoke_span.record("outcome", response.outcome());
+
           if let Some(responder) = &custom_responder {
             (responder)(&webview, &cmd, &response, callback, error);
           }
@@ -1179,13 +1271,36 @@ fn main() {
       request.error,
     );
 
+    #[cfg(feature = "tracing")]
+    let _span = span.entered();
+
+    if request.cmd == crate::ipc::batch::INVOKE_BATCH_COMMAND {
+      crate::ipc::batch::invoke_batch(self, request, resolver);
+      return;
+    }
+
+    self.handle_invoke(request, resolver);
+  }
+
+  /// Resolves the ACL of an [`InvokeRequest`] and executes its command, responding with the given resolver.
+  pub(crate) fn handle_invoke(self, request: InvokeRequest, resolver: InvokeResolver<R>) {
+    let manager = self.manager_owned();
+    let is_local = self.is_local_url(&request.url);
+
     #[cfg(mobile)]
     let app_handle = self.app_handle.clone();
 
+    // aliases and hidden commands are resolved before the ACL is checked
+    let (command, is_alias, is_hidden) = match manager.webview.command_router.route(&request.cmd) {
+      Route::Direct(command) => (command.to_string(), false, false),
+      Route::Alias(command) => (command.to_string(), true, false),
+      Route::Hidden => (request.cmd.clone(), false, true),
+    };
+
     let message = InvokeMessage::new(
       self,
       manager.state(),
-      request.cmd.to_string(),
+      command.clone(),
       request.body,
       request.headers,
     );
@@ -1198,13 +1313,29 @@ fn main() {
       }
     };
     let (resolved_acl, has_app_acl_manifest) = {
+      #[cfg(feature = "tracing")]
+      let span = tracing::trace_span!(
+        "ipc::request::acl",
+        cmd = command,
+        resolved = tracing::field::Empty
+      )
+      .entered();
+
       let runtime_authority = manager.runtime_authority.lock().unwrap();
-      let acl = runtime_authority.resolve_access(
-        &request.cmd,
-        message.webview.window().label(),
-        message.webview.label(),
-        &acl_origin,
-      );
+      let acl = if is_hidden {
+        None
+      } else {
+        runtime_authority.resolve_access(
+          &command,
+          message.webview.window().label(),
+          message.webview.label(),
+          &acl_origin,
+        )
+      };
+
+      #[cfg(feature = "tracing")]
+      span.record("resolved", acl.is_some());
+
       (acl, runtime_authority.has_app_manifest())
     };
 
@@ -1214,37 +1345,44 @@ fn main() {
       acl: resolved_acl,
     };
 
-    let plugin_command = request.cmd.strip_prefix("plugin:").map(|raw_command| {
-      let mut tokens = raw_command.split('|');
-      // safe to unwrap: split always has a least one item
-      let plugin = tokens.next().unwrap();
-      let command = tokens.next().map(|c| c.to_string()).unwrap_or_default();
-      (plugin, command)
-    });
+    fn parse_plugin_command(command: &str) -> Option<(&str, String)> {
+      command.strip_prefix("plugin:").map(|raw_command| {
+        let mut tokens = raw_command.split('|');
+        // safe to unwrap: split always has a least one item
+        let plugin = tokens.next().unwrap();
+        let command = tokens.next().map(|c| c.to_string()).unwrap_or_default();
+        (plugin, command)
+      })
+    }
+
+    let plugin_command = parse_plugin_command(&command);
 
     // we only check ACL on plugin commands or if the app defined its ACL manifest
     if (plugin_command.is_some() || has_app_acl_manifest)
-      && request.cmd != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND
+      && command != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND
       && invoke.acl.is_none()
     {
       #[cfg(debug_assertions)]
       {
-        let (key, command_name) = plugin_command
-          .clone()
-          .unwrap_or_else(|| (tauri_utils::acl::APP_ACL_KEY, request.cmd.clone()));
-        invoke.resolver.reject(
-          manager
-            .runtime_authority
-            .lock()
-            .unwrap()
-            .resolve_access_message(
-              key,
-              &command_name,
-              invoke.message.webview.window().label(),
-              invoke.message.webview.label(),
-              &acl_origin,
-            ),
-        );
+        let runtime_authority = manager.runtime_authority.lock().unwrap();
+        let message = if is_alias || is_hidden {
+          // do not reveal the aliased or hidden command internals
+          let (key, command_name) = parse_plugin_command(&request.cmd)
+            .unwrap_or_else(|| (tauri_utils::acl::APP_ACL_KEY, request.cmd.clone()));
+          runtime_authority.unknown_command_access_message(key, &command_name)
+        } else {
+          let (key, command_name) = plugin_command
+            .clone()
+            .unwrap_or_else(|| (tauri_utils::acl::APP_ACL_KEY, command.clone()));
+          runtime_authority.resolve_access_message(
+            key,
+            &command_name,
+            invoke.message.webview.window().label(),
+            invoke.message.webview.label(),
+            &acl_origin,
+          )
+        };
+        invoke.resolver.reject(message);
       }
       #[cfg(not(debug_assertions))]
       invoke
@@ -1253,10 +1391,29 @@ fn main() {
       return;
     }
 
+    if is_hidden {
+      invoke
+        .resolver
+        .reject(format!("Command {} not found", request.cmd));
+      return;
+    }
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!(
+      "ipc::request::dispatch",
+      cmd = command,
+      plugin = plugin_command.as_ref().map(|(plugin, _)| *plugin)
+    )
+    .entered();
+
     if let Some((plugin, command_name)) = plugin_command {
       invoke.message.command = command_name;
 
-      let command = invoke.message.command.clone();
+      let command = if is_alias {
+        request.cmd.clone()
+      } else {
+        invoke.message.command.clone()
+      };
 
       #[cfg(mobile)]
       let message = invoke.message.clone();
@@ -1307,10 +1464,9 @@ fn main() {
         resolver.reject(format!("Command {command} not found"));
       }
     } else {
-      let command = invoke.message.command.clone();
       let handled = manager.run_invoke_handler(invoke);
       if !handled {
-        resolver.reject(format!("Command {command} not found"));
+        resolver.reject(format!("Command {} not found", request.cmd));
       }
     }
   }
@@ -1490,6 +1646,128 @@ tauri::Builder::default()
       .set_zoom(scale_factor)
       .map_err(Into::into)
   }
+
+  /// Clears the webview HTTP disk and memory cache.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  pub fn clear_cache(&self) -> crate::Result<()> {
+    self.webview.dispatcher.clear_cache().map_err(Into::into)
+  }
+
+  /// Unregisters all service workers registered in this webview.
+  ///
+  /// Pages currently controlled by a service worker keep using it until they are reloaded.
+  /// The service workers are unregistered asynchronously, failures being logged.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  pub fn unregister_service_workers(&self) -> crate::Result<()> {
+    let label = self.label().to_string();
+    self
+      .webview
+      .dispatcher
+      .unregister_service_workers(move |result| {
+        if let Err(e) = result {
+          log::error!("failed to unregister the service workers of webview {label}: {e}");
+        }
+      })
+      .map_err(Into::into)
+  }
+
+  /// Clears all website data (cache, cookies, storage and service workers) stored for the given origin.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  /// - **macOS / iOS / Linux**: Only the data records identified by the exact origin (scheme, host and port) are cleared,
+  ///   data the platform groups by domain is kept.
+  pub fn clear_all_site_data_for_origin(&self, origin: Url) -> crate::Result<()> {
+    self
+      .webview
+      .dispatcher
+      .clear_all_site_data_for_origin(origin)
+      .map_err(Into::into)
+  }
+
+  /// Searches the page for the text and highlights the first match.
+  ///
+  /// The number of matches and the highlighted one are emitted as [`WebviewEvent::FindResult`](crate::WebviewEvent::FindResult)
+  /// and as the `tauri://find-result` event to the webview, as are the results of [`Self::find_next`], [`Self::find_previous`] and [`Self::stop_find`].
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows / macOS / iOS / Android**: The page text is searched by an injected script,
+  ///   which does not search inside iframes and highlights the match with the page selection.
+  pub fn find(&self, text: impl Into<String>, options: FindOptions) -> crate::Result<()> {
+    self
+      .webview
+      .dispatcher
+      .find(text.into(), options)
+      .map_err(Into::into)
+  }
+
+  /// Highlights the next match of the current search, in the direction of its [`FindOptions::forward`] option.
+  pub fn find_next(&self) -> crate::Result<()> {
+    self.webview.dispatcher.find_next().map_err(Into::into)
+  }
+
+  /// Highlights the previous match of the current search.
+  pub fn find_previous(&self) -> crate::Result<()> {
+    self.webview.dispatcher.find_previous().map_err(Into::into)
+  }
+
+  /// Ends the current search.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux**: The highlighted match is always cleared.
+  pub fn stop_find(&self, clear_selection: bool) -> crate::Result<()> {
+    self
+      .webview
+      .dispatcher
+      .stop_find(clear_selection)
+      .map_err(Into::into)
+  }
+
+  /// Samples the memory and CPU usage of the process rendering this webview.
+  ///
+  /// The CPU usage is measured since the previous sample of the process, so the first sample reports `0`.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: Samples the WebView2 browser process, shared by the webviews using the same data directory.
+  /// - **macOS**: Samples the WebKit web content process when the `macos-private-api` feature is enabled.
+  /// - **Linux / macOS without `macos-private-api`**: The web process is not exposed,
+  ///   so the totals of the app are returned with [`WebviewMetrics::app_total`] set.
+  /// - **Android / iOS**: Not supported.
+  pub fn process_metrics(&self) -> crate::Result<WebviewMetrics> {
+    let pid = self.webview.dispatcher.process_id()?;
+    self.manager().metrics_sampler.sample(pid)
+  }
+
+  /// The commands this webview is allowed to invoke from its current URL according to the capabilities.
+  ///
+  /// The commands aliased with [`Builder::command_alias`](crate::Builder::command_alias) are listed under their aliases,
+  /// and the ones hidden with [`Builder::hide_commands`](crate::Builder::hide_commands) are left out.
+  /// App commands are only listed when the app defines its ACL manifest, they are not checked against the capabilities otherwise.
+  pub fn allowed_commands(&self) -> crate::Result<Vec<String>> {
+    let url = self.url()?;
+    let origin = if self.is_local_url(&url) {
+      Origin::Local
+    } else {
+      Origin::Remote { url }
+    };
+    let manager = self.manager();
+    let allowed = manager
+      .runtime_authority
+      .lock()
+      .unwrap()
+      .allowed_commands_for(self.window().label(), self.label(), &origin);
+    Ok(manager.webview.command_router.visible_commands(&allowed))
+  }
 }
 
 impl<R: Runtime> Listener<R> for Webview<R> {
@@ -1527,6 +1805,23 @@ tauri::Builder::default()
     )
   }
 
+  /// Listen to an event on this webview with the given priority.
+  ///
+  /// See [`Listener::listen_with_priority`] for more information.
+  fn listen_with_priority<F>(&self, event: impl Into<String>, priority: i32, handler: F) -> EventId
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    self.manager.listen_with_priority(
+      event.into(),
+      EventTarget::Webview {
+        label: self.label().to_string(),
+      },
+      priority,
+      handler,
+    )
+  }
+
   /// Listen to an event on this webview only once.
   ///
   /// See [`Self::listen`] for more information.
@@ -1707,4 +2002,103 @@ mod tests {
     crate::test_utils::assert_send::<super::Webview>();
     crate::test_utils::assert_sync::<super::Webview>();
   }
+
+  #[cfg(feature = "tracing")]
+  #[crate::command(root = "crate")]
+  fn greet(name: String) -> String {
+    format!("Hello {name}")
+  }
+
+  #[cfg(feature = "tracing")]
+  #[crate::command(root = "crate")]
+  fn fail() -> Result<(), String> {
+    Err("failed".into())
+  }
+
+  #[cfg(feature = "tracing")]
+  #[test]
+  fn invoke_spans() {
+    use crate::{
+      ipc::{CallbackFn, InvokeBody},
+      test::{get_ipc_response, mock_builder, mock_context, noop_assets, INVOKE_KEY},
+      test_utils::CapturingSubscriber,
+      webview::InvokeRequest,
+      WebviewWindowBuilder,
+    };
+
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![greet, fail])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let payload = serde_json::json!({ "name": "secret" });
+    let invoke = |cmd: &str, body: serde_json::Value| {
+      get_ipc_response(
+        &webview,
+        InvokeRequest {
+          cmd: cmd.into(),
+          callback: CallbackFn(0),
+          error: CallbackFn(1),
+          url: "http://tauri.localhost".parse().unwrap(),
+          body: InvokeBody::Json(body),
+          headers: Default::default(),
+          invoke_key: INVOKE_KEY.to_string(),
+        },
+      )
+    };
+
+    let subscriber = CapturingSubscriber::default();
+    tracing::subscriber::with_default(subscriber.clone(), || {
+      invoke("greet", payload.clone()).unwrap();
+      invoke("fail", serde_json::json!({})).unwrap_err();
+    });
+
+    let greet = subscriber
+      .find("ipc::request::invoke", None)
+      .expect("missing ipc::request::invoke span");
+    let acl = subscriber
+      .find("ipc::request::acl", Some(greet))
+      .expect("missing ipc::request::acl span");
+    let dispatch = subscriber
+      .find("ipc::request::dispatch", Some(greet))
+      .expect("missing ipc::request::dispatch span");
+    let run = subscriber
+      .find("ipc::request::run", Some(dispatch))
+      .expect("missing ipc::request::run span");
+    let deserialize_arg = subscriber
+      .find("ipc::request::deserialize_arg", Some(run))
+      .expect("missing ipc::request::deserialize_arg span");
+
+    let spans = subscriber.spans.lock().unwrap();
+    let fields = &spans[greet].fields;
+    assert_eq!(fields["cmd"], "greet");
+    assert_eq!(fields["window"], "main");
+    assert_eq!(fields["webview"], "main");
+    assert_eq!(
+      fields["payload_size"],
+      serde_json::to_vec(&payload).unwrap().len().to_string()
+    );
+    assert_eq!(fields["outcome"], "ok");
+    assert_eq!(spans[acl].fields["cmd"], "greet");
+    assert_eq!(spans[acl].fields["resolved"], "false");
+    assert!(!spans[dispatch].fields.contains_key("plugin"));
+    assert_eq!(spans[deserialize_arg].fields["arg"], "name");
+
+    let fail = spans
+      .iter()
+      .position(|span| {
+        span.name == "ipc::request::invoke"
+          && span.fields.get("cmd").map(String::as_str) == Some("fail")
+      })
+      .expect("missing ipc::request::invoke span for the failing command");
+    assert_eq!(spans[fail].fields["outcome"], "error");
+
+    // the span fields never include the command arguments
+    assert!(spans
+      .iter()
+      .all(|span| span.fields.values().all(|v| !v.contains("secret"))));
+  }
 }
@@ -14,7 +14,7 @@ mod desktop_commands {
 
   use serde::Deserialize;
   use tauri_runtime::dpi::{Position, Size};
-  use tauri_utils::config::{WebviewUrl, WindowConfig};
+  use tauri_utils::config::{CookiePersistence, WebviewUrl, WindowConfig};
 
   use super::*;
   use crate::{
@@ -41,7 +41,11 @@ mod desktop_commands {
     #[serde(default)]
     incognito: bool,
     #[serde(default)]
+    cookie_persistence: CookiePersistence,
+    #[serde(default)]
     zoom_hotkeys_enabled: bool,
+    #[serde(default)]
+    clear_service_workers_on_version_change: bool,
   }
 
   #[command(root = "crate")]
@@ -79,7 +83,11 @@ mod desktop_commands {
     builder.webview_attributes.accept_first_mouse = options.accept_first_mouse;
     builder.webview_attributes.window_effects = options.window_effects;
     builder.webview_attributes.incognito = options.incognito;
+    builder.webview_attributes.cookie_persistence = options.cookie_persistence;
     builder.webview_attributes.zoom_hotkeys_enabled = options.zoom_hotkeys_enabled;
+    builder
+      .webview_attributes
+      .clear_service_workers_on_version_change = options.clear_service_workers_on_version_change;
 
     window.add_child(
       builder,
@@ -150,6 +158,11 @@ mod desktop_commands {
     tauri_runtime::dpi::PhysicalPosition<i32>
   );
   getter!(webview_size, size, tauri_runtime::dpi::PhysicalSize<u32>);
+  getter!(
+    webview_process_metrics,
+    process_metrics,
+    crate::webview::WebviewMetrics
+  );
   //getter!(is_focused, bool);
 
   setter!(print);
@@ -158,6 +171,29 @@ mod desktop_commands {
   setter!(set_webview_position, set_position, Position);
   setter!(set_webview_focus, set_focus);
   setter!(set_webview_zoom, set_zoom, f64);
+  setter!(clear_webview_cache, clear_cache);
+  setter!(
+    unregister_webview_service_workers,
+    unregister_service_workers
+  );
+  setter!(
+    clear_webview_site_data_for_origin,
+    clear_all_site_data_for_origin,
+    crate::webview::Url
+  );
+  setter!(webview_find_next, find_next);
+  setter!(webview_find_previous, find_previous);
+  setter!(webview_stop_find, stop_find, bool);
+
+  #[command(root = "crate")]
+  pub async fn webview_find<R: Runtime>(
+    webview: Webview<R>,
+    label: Option<String>,
+    text: String,
+    options: Option<crate::webview::FindOptions>,
+  ) -> crate::Result<()> {
+    get_webview(webview, label)?.find(text, options.unwrap_or_default())
+  }
 
   #[command(root = "crate")]
   pub async fn reparent<R: Runtime>(
@@ -186,6 +222,30 @@ mod desktop_commands {
     }
     Ok(())
   }
+
+  #[cfg(test)]
+  mod tests {
+    use super::{WebviewConfig, WindowConfig};
+
+    #[test]
+    fn clear_service_workers_on_version_change_option() {
+      let webview: WebviewConfig = serde_json::from_value(serde_json::json!({
+        "x": 0.0,
+        "y": 0.0,
+        "width": 800.0,
+        "height": 600.0,
+        "clearServiceWorkersOnVersionChange": true
+      }))
+      .unwrap();
+      assert!(webview.clear_service_workers_on_version_change);
+
+      let window: WindowConfig = serde_json::from_value(serde_json::json!({
+        "clearServiceWorkersOnVersionChange": true
+      }))
+      .unwrap();
+      assert!(window.clear_service_workers_on_version_change);
+    }
+  }
 }
 
 /// Initializes the plugin.
@@ -234,12 +294,20 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             // getters
             desktop_commands::webview_position,
             desktop_commands::webview_size,
+            desktop_commands::webview_process_metrics,
             // setters
             desktop_commands::webview_close,
             desktop_commands::set_webview_size,
             desktop_commands::set_webview_position,
             desktop_commands::set_webview_focus,
             desktop_commands::set_webview_zoom,
+            desktop_commands::clear_webview_cache,
+            desktop_commands::unregister_webview_service_workers,
+            desktop_commands::clear_webview_site_data_for_origin,
+            desktop_commands::webview_find,
+            desktop_commands::webview_find_next,
+            desktop_commands::webview_find_previous,
+            desktop_commands::webview_stop_find,
             desktop_commands::print,
             desktop_commands::reparent,
             #[cfg(any(debug_assertions, feature = "devtools"))]
@@ -28,15 +28,14 @@ use crate::{
 };
 use serde::Serialize;
 use tauri_runtime::window::WindowSizeConstraints;
-use tauri_utils::config::{WebviewUrl, WindowConfig};
+use tauri_utils::config::{CookiePersistence, WebviewSettingsConfig, WebviewUrl, WindowConfig};
 use url::Url;
 
 use crate::{
   ipc::{CommandArg, CommandItem, InvokeError, OwnedInvokeResponder},
   manager::{webview::WebviewLabelDef, AppManager},
   sealed::{ManagerBase, RuntimeOrDispatch},
-  webview::PageLoadPayload,
-  webview::WebviewBuilder,
+  webview::{FindOptions, PageLoadPayload, WebviewBuilder},
   window::WindowBuilder,
   AppHandle, Event, EventId, Manager, Runtime, Webview, WindowEvent,
 };
@@ -46,7 +45,7 @@ use tauri_macros::default_runtime;
 #[cfg(windows)]
 use windows::Win32::Foundation::HWND;
 
-use super::DownloadEvent;
+use super::{DownloadEvent, UnsupportedWebviewSetting};
 
 /// A builder for [`WebviewWindow`], a window that hosts a single webview.
 pub struct WebviewWindowBuilder<'a, R: Runtime, M: Manager<R>> {
@@ -854,6 +853,15 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     self
   }
 
+  /// Sets whether the webview cookies and website data are kept between runs.
+  ///
+  /// See [`CookiePersistence`] for the data store used on each platform.
+  #[must_use]
+  pub fn cookie_persistence(mut self, cookie_persistence: CookiePersistence) -> Self {
+    self.webview_builder = self.webview_builder.cookie_persistence(cookie_persistence);
+    self
+  }
+
   /// Sets the webview to automatically grow and shrink its size and position when the parent window resizes.
   #[must_use]
   pub fn auto_resize(mut self) -> Self {
@@ -884,6 +892,31 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     self.webview_builder = self.webview_builder.zoom_hotkeys_enabled(enabled);
     self
   }
+
+  /// Whether to unregister the service workers of the webview when the app version changes.
+  ///
+  /// The app version is recorded in the app local data directory and compared when the webview is created.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android / iOS**: Unsupported.
+  #[must_use]
+  pub fn clear_service_workers_on_version_change(mut self, enabled: bool) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .clear_service_workers_on_version_change(enabled);
+    self
+  }
+
+  /// Sets the webview engine settings such as the media autoplay policy, the background throttling and smooth scrolling.
+  ///
+  /// Settings the platform webview does not support are ignored with a warning,
+  /// see [`WebviewWindow::unsupported_webview_settings`].
+  #[must_use]
+  pub fn webview_settings(mut self, settings: WebviewSettingsConfig) -> Self {
+    self.webview_builder = self.webview_builder.webview_settings(settings);
+    self
+  }
 }
 
 /// A type that wraps a [`Window`] together with a [`Webview`].
@@ -968,6 +1001,11 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.label()
   }
 
+  /// Whether the webview cookies and website data are kept between runs, see [`Webview::cookie_persistence`].
+  pub fn cookie_persistence(&self) -> CookiePersistence {
+    self.webview.cookie_persistence()
+  }
+
   /// Registers a window event listener.
   pub fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) {
     self.webview.window().on_window_event(f);
@@ -1292,6 +1330,8 @@ impl<R: Runtime> WebviewWindow<R> {
   ///
   /// - **macOS:** `None` has no effect.
   /// - **Linux:** Urgency levels have the same effect.
+  ///   On Wayland, the activation of the window is requested instead of setting the urgency hint and `None` has no effect.
+  /// - **Android / iOS:** Unsupported.
   pub fn request_user_attention(
     &self,
     request_type: Option<UserAttentionType>,
@@ -1299,6 +1339,17 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.window().request_user_attention(request_type)
   }
 
+  /// Plays the alert sound of the system for the window, e.g. when an action of the user is refused.
+  ///
+  /// See [`AppHandle::play_system_sound`](crate::AppHandle::play_system_sound) to play the other sounds of the system.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Rings the bell of the window, which may be shown as a visual bell depending on the desktop settings.
+  pub fn beep(&self) -> crate::Result<()> {
+    self.webview.window().beep()
+  }
+
   /// Determines if this window should be resizable.
   /// When resizable is set to false, native window's maximize button is automatically disabled.
   pub fn set_resizable(&self, resizable: bool) -> crate::Result<()> {
@@ -1655,6 +1706,18 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.url()
   }
 
+  /// Returns the webview engine settings in effect.
+  ///
+  /// Settings the platform webview does not support are unset.
+  pub fn webview_settings(&self) -> crate::Result<WebviewSettingsConfig> {
+    self.webview.webview_settings()
+  }
+
+  /// Returns the webview engine settings that were ignored because the platform webview does not support them.
+  pub fn unsupported_webview_settings(&self) -> crate::Result<Vec<UnsupportedWebviewSetting>> {
+    self.webview.unsupported_webview_settings()
+  }
+
   /// Navigates the webview to the defined url.
   pub fn navigate(&mut self, url: Url) -> crate::Result<()> {
     self.webview.navigate(url)
@@ -1773,6 +1836,67 @@ impl<R: Runtime> WebviewWindow<R> {
   pub fn set_zoom(&self, scale_factor: f64) -> crate::Result<()> {
     self.webview.set_zoom(scale_factor)
   }
+
+  /// Clears the webview HTTP disk and memory cache.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  pub fn clear_cache(&self) -> crate::Result<()> {
+    self.webview.clear_cache()
+  }
+
+  /// Unregisters all service workers registered in this webview.
+  ///
+  /// Pages currently controlled by a service worker keep using it until they are reloaded.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  pub fn unregister_service_workers(&self) -> crate::Result<()> {
+    self.webview.unregister_service_workers()
+  }
+
+  /// Clears all website data (cache, cookies, storage and service workers) stored for the given origin.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Android**: Not supported.
+  /// - **macOS / iOS / Linux**: Data is grouped by domain, so the data of every origin sharing the domain is cleared.
+  pub fn clear_all_site_data_for_origin(&self, origin: Url) -> crate::Result<()> {
+    self.webview.clear_all_site_data_for_origin(origin)
+  }
+
+  /// Searches the page for the text and highlights the first match.
+  ///
+  /// See [`Webview::find`](crate::webview::Webview::find) for how the result is reported.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows / macOS / iOS / Android**: The page text is searched by an injected script,
+  ///   which does not search inside iframes and highlights the match with the page selection.
+  pub fn find(&self, text: impl Into<String>, options: FindOptions) -> crate::Result<()> {
+    self.webview.find(text, options)
+  }
+
+  /// Highlights the next match of the current search, in the direction of its [`FindOptions::forward`] option.
+  pub fn find_next(&self) -> crate::Result<()> {
+    self.webview.find_next()
+  }
+
+  /// Highlights the previous match of the current search.
+  pub fn find_previous(&self) -> crate::Result<()> {
+    self.webview.find_previous()
+  }
+
+  /// Ends the current search.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Linux**: The highlighted match is always cleared.
+  pub fn stop_find(&self, clear_selection: bool) -> crate::Result<()> {
+    self.webview.stop_find(clear_selection)
+  }
 }
 
 impl<R: Runtime> Listener<R> for WebviewWindow<R> {
@@ -1806,6 +1930,23 @@ impl<R: Runtime> Listener<R> for WebviewWindow<R> {
     )
   }
 
+  /// Listen to an event on this webview window with the given priority.
+  ///
+  /// See [`Listener::listen_with_priority`] for more information.
+  fn listen_with_priority<F>(&self, event: impl Into<String>, priority: i32, handler: F) -> EventId
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    self.manager().listen_with_priority(
+      event.into(),
+      EventTarget::WebviewWindow {
+        label: self.label().to_string(),
+      },
+      priority,
+      handler,
+    )
+  }
+
   /// Listen to an event on this window webview only once.
   ///
   /// See [`Self::listen`] for more information.
@@ -372,6 +372,10 @@ tauri::Builder::default()
     webview: Option<PendingWebview<EventLoopMessage, R>>,
   ) -> crate::Result<Window<R>> {
     let mut pending = PendingWindow::new(self.window_builder.clone(), self.label.clone())?;
+    let cookie_persistence = webview
+      .as_ref()
+      .map(|webview| webview.webview_attributes.effective_cookie_persistence())
+      .unwrap_or_default();
     if let Some(webview) = webview {
       pending.set_webview(webview);
     }
@@ -411,7 +415,9 @@ tauri::Builder::default()
       );
 
       if let Some(webview) = detached_window.webview {
-        app_manager.webview.attach_webview(window.clone(), webview);
+        app_manager
+          .webview
+          .attach_webview(window.clone(), webview, cookie_persistence);
       }
 
       window
@@ -1612,6 +1618,8 @@ impl<R: Runtime> Window<R> {
   ///
   /// - **macOS:** `None` has no effect.
   /// - **Linux:** Urgency levels have the same effect.
+  ///   On Wayland, the activation of the window is requested instead of setting the urgency hint and `None` has no effect.
+  /// - **Android / iOS:** Unsupported.
   pub fn request_user_attention(
     &self,
     request_type: Option<UserAttentionType>,
@@ -1623,6 +1631,17 @@ impl<R: Runtime> Window<R> {
       .map_err(Into::into)
   }
 
+  /// Plays the alert sound of the system for the window, e.g. when an action of the user is refused.
+  ///
+  /// See [`AppHandle::play_system_sound`](crate::AppHandle::play_system_sound) to play the other sounds of the system.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Rings the bell of the window, which may be shown as a visual bell depending on the desktop settings.
+  pub fn beep(&self) -> crate::Result<()> {
+    self.window.dispatcher.beep().map_err(Into::into)
+  }
+
   /// Determines if this window should be resizable.
   /// When resizable is set to false, native window's maximize button is automatically disabled.
   pub fn set_resizable(&self, resizable: bool) -> crate::Result<()> {
@@ -2072,6 +2091,23 @@ tauri::Builder::default()
     )
   }
 
+  /// Listen to an event on this window with the given priority.
+  ///
+  /// See [`Listener::listen_with_priority`] for more information.
+  fn listen_with_priority<F>(&self, event: impl Into<String>, priority: i32, handler: F) -> EventId
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    self.manager.listen_with_priority(
+      event.into(),
+      EventTarget::Window {
+        label: self.label().to_string(),
+      },
+      priority,
+      handler,
+    )
+  }
+
   /// Listen to an event on this window only once.
   ///
   /// See [`Self::listen`] for more information.
@@ -2273,4 +2309,40 @@ mod tests {
     crate::test_utils::assert_send::<super::Window>();
     crate::test_utils::assert_sync::<super::Window>();
   }
+
+  #[cfg(desktop)]
+  #[test]
+  fn attention_and_sounds_reach_the_runtime() {
+    use crate::{SystemSound, UserAttentionType, WebviewWindowBuilder};
+
+    let app = crate::test::mock_app();
+    let window = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    for request_type in [
+      Some(UserAttentionType::Critical),
+      Some(UserAttentionType::Informational),
+      None,
+    ] {
+      window.request_user_attention(request_type).unwrap();
+    }
+    window.beep().unwrap();
+
+    for (name, sound) in [
+      ("alert", SystemSound::Alert),
+      ("information", SystemSound::Information),
+      ("warning", SystemSound::Warning),
+      ("error", SystemSound::Error),
+      ("question", SystemSound::Question),
+    ] {
+      // the JS API sends the sound names
+      assert_eq!(
+        serde_json::from_value::<SystemSound>(serde_json::json!(name)).unwrap(),
+        sound
+      );
+      app.handle().play_system_sound(sound).unwrap();
+    }
+    assert_eq!(SystemSound::default(), SystemSound::Alert);
+  }
 }
@@ -96,6 +96,7 @@ mod desktop_commands {
 
   setter!(center);
   setter!(request_user_attention, Option<UserAttentionType>);
+  setter!(beep);
   setter!(set_resizable, bool);
   setter!(set_maximizable, bool);
   setter!(set_minimizable, bool);
@@ -243,6 +244,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             // setters
             desktop_commands::center,
             desktop_commands::request_user_attention,
+            desktop_commands::beep,
             desktop_commands::set_resizable,
             desktop_commands::set_maximizable,
             desktop_commands::set_minimizable,
@@ -0,0 +1,111 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Integration tests running on a real webview.
+//!
+//! These tests are skipped when no webview is available, run them on Linux with:
+//! `xvfb-run cargo test --features test-webview --test webview_harness`
+
+use serde_json::json;
+use tauri::{ipc::Channel, test::WebviewHarness, Emitter};
+
+#[tauri::command]
+fn stream(count: u32, on_event: Channel<u32>) -> tauri::Result<()> {
+  for i in 0..count {
+    on_event.send(i)?;
+  }
+  Ok(())
+}
+
+#[tauri::command]
+fn fail() -> Result<(), String> {
+  Err("failed".into())
+}
+
+fn harness() -> Option<WebviewHarness> {
+  let harness = WebviewHarness::builder()
+    .setup(|builder| builder.invoke_handler(tauri::generate_handler![stream, fail]))
+    .build();
+  if harness.is_none() {
+    eprintln!("skipping test: no webview available");
+  }
+  harness
+}
+
+#[test]
+fn invoke_js() {
+  let Some(harness) = harness() else { return };
+
+  assert_eq!(harness.invoke_js("return 1 + 1"), Ok(json!(2)));
+  assert_eq!(
+    harness.invoke_js("await Promise.resolve()"),
+    Ok(json!(null))
+  );
+  assert_eq!(
+    harness.invoke_js("throw new Error('oops')"),
+    Err(json!("Error: oops"))
+  );
+}
+
+#[test]
+fn invoke_command() {
+  let Some(harness) = harness() else { return };
+
+  assert_eq!(harness.invoke_command("fail", ()), Err(json!("failed")));
+}
+
+#[test]
+fn event_round_trip() {
+  let Some(harness) = harness() else { return };
+
+  // the webview answers every `ping` with a `pong`
+  harness
+    .invoke_js(
+      r#"
+      await window.__TAURI_INTERNALS__.invoke('plugin:event|listen', {
+        event: 'ping',
+        target: { kind: 'Any' },
+        handler: window.__TAURI_INTERNALS__.transformCallback((event) => {
+          window.__TAURI_INTERNALS__.invoke('plugin:event|emit', {
+            event: 'pong',
+            payload: event.payload + 1
+          })
+        })
+      })
+      "#,
+    )
+    .unwrap();
+
+  harness.track_event("pong");
+  harness.app_handle().emit("ping", 1).unwrap();
+  assert_eq!(harness.wait_for_event("pong"), json!(2));
+  harness.app_handle().emit("ping", 41).unwrap();
+  assert_eq!(harness.wait_for_event("pong"), json!(42));
+}
+
+#[test]
+fn channel_streaming() {
+  let Some(harness) = harness() else { return };
+
+  let messages = harness
+    .invoke_js(
+      r#"
+      const count = 5
+      const messages = []
+      let done
+      const received = new Promise((resolve) => { done = resolve })
+      const id = window.__TAURI_INTERNALS__.transformCallback((message) => {
+        messages.push(message)
+        if (messages.length === count) done()
+      })
+      await window.__TAURI_INTERNALS__.invoke('stream', { count, onEvent: `__CHANNEL__:${id}` })
+      await received
+      // messages are fetched concurrently so they might arrive out of order
+      return messages.sort((a, b) => a.id - b.id).map(({ message }) => message)
+      "#,
+    )
+    .unwrap();
+
+  assert_eq!(messages, json!([0, 1, 2, 3, 4]));
+}
@@ -79,4 +79,122 @@ mod tests {
       );
     }
   }
+
+  #[test]
+  fn authorize_commands() {
+    use tauri_utils::acl::{
+      test::{AclBuilder, Denial, Origin},
+      Value,
+    };
+
+    let resolved = AclBuilder::new()
+      .plugin(
+        "fs",
+        r#"
+[default]
+permissions = ["deny-write"]
+
+[[permission]]
+identifier = "allow-read"
+commands.allow = ["read"]
+[[permission.scope.allow]]
+path = "$APPDATA/*"
+
+[[permission]]
+identifier = "deny-write"
+commands.deny = ["write"]
+
+[[permission]]
+identifier = "allow-write"
+commands.allow = ["write"]
+
+[[permission]]
+identifier = "deny-home"
+[[permission.scope.deny]]
+path = "$HOME"
+"#,
+      )
+      .unwrap()
+      .capability(
+        r#"{
+  "identifier": "main",
+  "windows": ["main-*"],
+  "permissions": [
+    "fs:default",
+    "fs:allow-write",
+    "fs:deny-home",
+    { "identifier": "fs:allow-read", "allow": [{ "path": "$DOCUMENT/*" }] }
+  ]
+}"#,
+      )
+      .unwrap()
+      .capability(
+        r#"{
+  "identifier": "remote",
+  "webviews": ["preview"],
+  "local": false,
+  "remote": { "urls": ["https://*.tauri.app"] },
+  "permissions": ["fs:allow-read"]
+}"#,
+      )
+      .unwrap()
+      .resolve()
+      .unwrap();
+
+    let path =
+      |path: &str| Value::Map([("path".to_string(), Value::from(path.to_string()))].into());
+
+    let authorization = resolved.authorize("plugin:fs|read", "main-1", &Origin::Local);
+    let scope = authorization.scope().expect("read is allowed");
+    // the capability scope is added to the permission scope
+    assert_eq!(scope.allow, vec![path("$DOCUMENT/*"), path("$APPDATA/*")]);
+    assert!(scope.deny.is_empty());
+    let global_scope = authorization.global_scope().unwrap();
+    assert_eq!(global_scope.deny, vec![path("$HOME")]);
+
+    // deny takes priority over allow
+    assert_eq!(
+      resolved
+        .authorize("plugin:fs|write", "main-1", &Origin::Local)
+        .denial(),
+      Some(Denial::Denied)
+    );
+    assert_eq!(
+      resolved
+        .authorize("plugin:fs|remove", "main-1", &Origin::Local)
+        .denial(),
+      Some(Denial::NotAllowed)
+    );
+    assert_eq!(
+      resolved
+        .authorize("plugin:fs|read", "settings", &Origin::Local)
+        .denial(),
+      Some(Denial::Label)
+    );
+
+    let remote = Origin::Remote {
+      url: "https://api.tauri.app".parse().unwrap(),
+    };
+    assert!(resolved
+      .authorize("plugin:fs|read", "preview", &remote)
+      .is_allowed());
+    assert_eq!(
+      resolved
+        .authorize("plugin:fs|read", "main-1", &remote)
+        .denial(),
+      Some(Denial::Label)
+    );
+    assert_eq!(
+      resolved
+        .authorize(
+          "plugin:fs|read",
+          "preview",
+          &Origin::Remote {
+            url: "https://example.com".parse().unwrap()
+          }
+        )
+        .denial(),
+      Some(Denial::Origin)
+    );
+  }
 }
@@ -0,0 +1,7 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Integration tests generating a context with `tauri::generate_context!` from a crate without a build script.
+//!
+//! Each file in `tests/` is a separate test binary generating the context for the same fixture.
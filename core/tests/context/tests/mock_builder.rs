@@ -0,0 +1,26 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use tauri::{test::mock_builder, Manager};
+
+#[test]
+fn builds_app_from_test_context() {
+  let app = mock_builder()
+    .build(tauri::generate_context!(
+      config_path = "fixture/tauri.conf.json",
+      assets = test
+    ))
+    .expect("failed to build app");
+
+  assert_eq!(app.config().identifier, "studio.tauri.context-tests");
+  assert!(app.get_webview_window("main").is_some());
+
+  let icon = app
+    .default_window_icon()
+    .expect("missing default window icon");
+  assert_eq!(
+    icon.rgba().len(),
+    (icon.width() * icon.height() * 4) as usize
+  );
+}
@@ -0,0 +1,24 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use tauri::test::mock_builder;
+
+#[test]
+fn serves_stub_index_html() {
+  // the fixture `frontendDist` does not exist, it is never embedded
+  let app = mock_builder()
+    .build(tauri::generate_context!(
+      config_path = "fixture/tauri.conf.json",
+      assets = test
+    ))
+    .expect("failed to build app");
+
+  let assets = app.asset_resolver();
+  let index = assets
+    .get("/index.html".into())
+    .expect("missing stub index.html");
+  assert!(index.mime_type.starts_with("text/html"));
+  assert!(String::from_utf8_lossy(&index.bytes).contains("<body></body>"));
+  assert_eq!(assets.iter().count(), 1);
+}
@@ -8,8 +8,11 @@ mod menu_plugin;
 #[cfg(desktop)]
 mod tray;
 
+use std::io::Write;
+
 use serde::Serialize;
 use tauri::{
+  http::{header, Response},
   ipc::Channel,
   webview::{PageLoadEvent, WebviewWindowBuilder},
   App, AppHandle, Emitter, Listener, Manager, RunEvent, Runtime, WebviewUrl,
@@ -42,6 +45,25 @@ pub fn run_app<R: Runtime, F: FnOnce(&App<R>) + Send + 'static>(
   #[allow(unused_mut)]
   let mut builder = builder
     .plugin(tauri_plugin_sample::init())
+    // a large generated export written in chunks, see `Communication.svelte`
+    .register_asynchronous_uri_scheme_protocol("stream", |_app, _request, responder| {
+      std::thread::spawn(move || {
+        let mut writer = responder.body_writer(
+          Response::builder()
+            .header(header::CONTENT_TYPE, "text/csv")
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(())
+            .unwrap(),
+        );
+        for row in 0..2_000_000u64 {
+          // stop generating the export when the request is cancelled or the webview is closed
+          if writeln!(writer, "{row},{},{}", row * row, row % 7 == 0).is_err() {
+            return;
+          }
+        }
+        writer.finish();
+      });
+    })
     .setup(move |app| {
       #[cfg(all(desktop, not(test)))]
       {
@@ -0,0 +1,73 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+use tauri::{
+  menu::{Menu, MenuItem},
+  Manager,
+};
+
+fn main() {
+  tauri::Builder::default()
+    // the menus referenced by the `menu` of the tray icons in tauri.conf.json
+    .tray_menu(|handle| {
+      Menu::with_id_and_items(
+        handle,
+        "status-menu",
+        &[
+          &MenuItem::with_id(handle, "show", "Show window", true, None::<&str>)?,
+          &MenuItem::with_id(handle, "quit", "Quit", true, None::<&str>)?,
+        ],
+      )
+    })
+    .tray_menu(|handle| {
+      Menu::with_id_and_items(
+        handle,
+        "capture-menu",
+        &[
+          &MenuItem::with_id(handle, "start", "Start capture", true, None::<&str>)?,
+          &MenuItem::with_id(handle, "stop", "Stop capture", true, None::<&str>)?,
+          &MenuItem::with_id(handle, "remove", "Remove", true, None::<&str>)?,
+        ],
+      )
+    })
+    .setup(|app| {
+      // the events carry the id of the tray icon that triggered them
+      app.on_tray_icon_event(|_app, event| {
+        println!("tray icon `{}` event: {event:?}", event.id().as_ref());
+      });
+
+      app.on_menu_event(|app, event| match event.id().as_ref() {
+        "show" => {
+          if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+          }
+        }
+        "quit" => app.exit(0),
+        id @ ("start" | "stop") => {
+          if let Some(tray) = app.tray_by_id("capture") {
+            let tooltip = if id == "start" {
+              "Capture running"
+            } else {
+              "Capture stopped"
+            };
+            let _ = tray.set_tooltip(Some(tooltip));
+          }
+        }
+        // the status tray icon keeps working
+        "remove" => {
+          app.remove_tray_by_id("capture");
+        }
+        _ => {}
+      });
+
+      Ok(())
+    })
+    .run(tauri::generate_context!(
+      "../../examples/tray/tauri.conf.json"
+    ))
+    .expect("error while running tauri application");
+}
@@ -4,6 +4,8 @@
 
 import { invoke } from './core'
 import { Image } from './image'
+import type { WebviewMetrics } from './webview'
+import type { Color, Theme } from './window'
 
 /**
  * Application metadata and related APIs.
@@ -101,4 +103,115 @@ async function defaultWindowIcon(): Promise<Image | null> {
   )
 }
 
-export { getName, getVersion, getTauriVersion, show, hide, defaultWindowIcon }
+/**
+ * Samples the memory and CPU usage of the processes rendering all webviews, keyed by webview label.
+ *
+ * Webviews that cannot be sampled, e.g. because they are being closed, are left out.
+ *
+ * The metrics are also emitted periodically with the `tauri://metrics` event
+ * when the `app > webviewMetricsInterval` configuration is set.
+ *
+ * @example
+ * ```typescript
+ * import { allWebviewMetrics } from '@tauri-apps/api/app';
+ * const metrics = await allWebviewMetrics();
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function allWebviewMetrics(): Promise<Record<string, WebviewMetrics>> {
+  return invoke('plugin:app|all_webview_metrics')
+}
+
+/**
+ * Accessibility and appearance preferences of the system.
+ *
+ * @since 2.0.0
+ */
+interface SystemPreferences {
+  /** Whether the user asked to minimize non-essential motion, e.g. by disabling animations. */
+  reducedMotion: boolean
+  /** Whether a high contrast mode is enabled. */
+  highContrast: boolean
+  /** The system color scheme. */
+  colorScheme: Theme
+  /** The system accent color, if the platform has one. */
+  accentColor: Color | null
+}
+
+/**
+ * Reads the accessibility and appearance preferences of the system.
+ *
+ * The `tauri://system-preferences-changed` event is emitted with the new preferences when they change.
+ * On Linux, WebKitGTK does not reflect the high contrast mode in the `prefers-contrast` media query,
+ * so the `tauri-reduced-motion`, `tauri-high-contrast` and `tauri-dark` classes are toggled on the document element
+ * and the `--tauri-accent-color` custom property is set instead.
+ *
+ * @example
+ * ```typescript
+ * import { systemPreferences } from '@tauri-apps/api/app';
+ * const { reducedMotion } = await systemPreferences();
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function systemPreferences(): Promise<SystemPreferences> {
+  return invoke('plugin:app|system_preferences')
+}
+
+/**
+ * A sound of the system.
+ *
+ * @since 2.0.0
+ */
+enum SystemSound {
+  /** The default alert sound. */
+  Alert = 'alert',
+  /** The sound of informational messages. */
+  Information = 'information',
+  /** The sound of warnings. */
+  Warning = 'warning',
+  /** The sound of errors. */
+  Error = 'error',
+  /** The sound of questions. */
+  Question = 'question'
+}
+
+/**
+ * Plays a sound of the system, e.g. to notify the user that a background task finished.
+ *
+ * #### Platform-specific
+ *
+ * - **Windows:** Plays the sound configured for the matching system event.
+ * - **macOS:** Plays the alert sound selected in the system settings for all the sounds.
+ * - **Linux:** Rings the display bell for all the sounds, which desktops usually map to their alert sound.
+ * - **Android / iOS:** Unsupported.
+ *
+ * @example
+ * ```typescript
+ * import { playSystemSound, SystemSound } from '@tauri-apps/api/app';
+ * await playSystemSound(SystemSound.Information);
+ * ```
+ *
+ * @param sound The sound to play, defaults to {@link SystemSound.Alert}.
+ *
+ * @since 2.0.0
+ */
+async function playSystemSound(sound?: SystemSound): Promise<void> {
+  return invoke('plugin:app|play_system_sound', { sound })
+}
+
+export type { SystemPreferences }
+
+export {
+  getName,
+  getVersion,
+  getTauriVersion,
+  show,
+  hide,
+  defaultWindowIcon,
+  allWebviewMetrics,
+  systemPreferences,
+  SystemSound,
+  playSystemSound
+}
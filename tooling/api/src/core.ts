@@ -160,6 +160,81 @@ async function invoke<T>(
   return window.__TAURI_INTERNALS__.invoke(cmd, args, options)
 }
 
+/**
+ * A command executed by {@linkcode invokeBatch}.
+ *
+ * @since 2.0.0
+ */
+interface InvokeBatchEntry {
+  /** The command name. */
+  cmd: string
+  /** The arguments to pass to the command. Channels and raw binary arguments are not supported. */
+  args?: Record<string, unknown> | number[]
+}
+
+/**
+ * @since 2.0.0
+ */
+interface InvokeBatchOptions {
+  /**
+   * Whether the commands are executed concurrently instead of one after the other.
+   *
+   * Defaults to `false`.
+   */
+  concurrent?: boolean
+}
+
+/**
+ * The outcome of a command executed by {@linkcode invokeBatch}.
+ *
+ * @since 2.0.0
+ */
+type InvokeBatchResult<T = unknown> =
+  | { status: 'ok'; data: T }
+  | { status: 'error'; error: unknown }
+
+/**
+ * Executes several commands in a single round trip to the backend.
+ *
+ * Each command is checked against the capabilities like a regular {@linkcode invoke} call,
+ * and a failing command does not fail the whole batch.
+ * Commands that take a {@linkcode Channel} or respond with raw binary data cannot be batched and result in an error.
+ *
+ * @example
+ * ```typescript
+ * import { invokeBatch } from '@tauri-apps/api/core';
+ * const [user, settings] = await invokeBatch([
+ *   { cmd: 'get_user', args: { id: 1 } },
+ *   { cmd: 'get_settings' }
+ * ], { concurrent: true });
+ * if (user.status === 'ok') {
+ *   console.log(user.data);
+ * }
+ * ```
+ *
+ * @param entries The commands to execute.
+ * @param options The batch options.
+ * @return A promise resolving to the result of each command, in order.
+ *
+ * @since 2.0.0
+ */
+async function invokeBatch(
+  entries: InvokeBatchEntry[],
+  options?: InvokeBatchOptions
+): Promise<InvokeBatchResult[]> {
+  for (const { cmd, args } of entries) {
+    if (args instanceof ArrayBuffer || ArrayBuffer.isView(args)) {
+      throw new Error(
+        `Command ${cmd} cannot be batched because its arguments are raw binary data`
+      )
+    }
+  }
+  return invoke('__TAURI_INVOKE_BATCH__', {
+    entries,
+    concurrent: options?.concurrent ?? false
+  })
+}
+
 /**
  * Convert a device file path to an URL that can be loaded by the webview.
  * Note that `asset:` and `http://asset.localhost` must be added to [`tauri.security.csp`](https://tauri.app/v1/api/config/#securityconfig.csp) in `tauri.conf.json`.
@@ -242,7 +317,13 @@ function isTauri(): boolean {
   return 'isTauri' in window && !!window.isTauri
 }
 
-export type { InvokeArgs, InvokeOptions }
+export type {
+  InvokeArgs,
+  InvokeOptions,
+  InvokeBatchEntry,
+  InvokeBatchOptions,
+  InvokeBatchResult
+}
 
 export {
   transformCallback,
@@ -250,6 +331,7 @@ export {
   PluginListener,
   addPluginListener,
   invoke,
+  invokeBatch,
   convertFileSrc,
   isTauri
 }
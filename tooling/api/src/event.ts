@@ -60,7 +60,10 @@ enum TauriEvent {
   DRAG_ENTER = 'tauri://drag-enter',
   DRAG_OVER = 'tauri://drag-over',
   DRAG_DROP = 'tauri://drag-drop',
-  DRAG_LEAVE = 'tauri://drag-leave'
+  DRAG_LEAVE = 'tauri://drag-leave',
+  WEBVIEW_METRICS = 'tauri://metrics',
+  SYSTEM_PREFERENCES_CHANGED = 'tauri://system-preferences-changed',
+  FIND_RESULT = 'tauri://find-result'
 }
 
 /**
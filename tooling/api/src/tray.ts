@@ -6,6 +6,7 @@ import type { Menu, Submenu } from './menu'
 import { Channel, invoke, Resource } from './core'
 import { Image, transformImage } from './image'
 import { PhysicalPosition, PhysicalSize } from './dpi'
+import type { Theme } from './window'
 
 export type MouseButtonState = 'Up' | 'Down'
 export type MouseButton = 'Left' | 'Right' | 'Middle'
@@ -74,12 +75,22 @@ export interface TrayIconLeaveEvent {
   }
 }
 
+/**
+ * The system theme changed and the icon was swapped, see {@linkcode TrayIconOptions.iconForTheme}.
+ */
+export interface TrayIconThemeChangedEvent {
+  /** Id of the tray icon which triggered this event. */
+  id: string
+  /** The new system theme. */
+  theme: Theme
+}
+
 /**
  * Describes a tray icon event.
  *
  * #### Platform-specific:
  *
- * - **Linux**: Unsupported. The event is not emitted even though the icon is shown,
+ * - **Linux**: The mouse events are not emitted even though the icon is shown,
  * the icon will still show a context menu on right click.
  */
 export type TrayIconEvent =
@@ -87,6 +98,7 @@ export type TrayIconEvent =
   | { enter: TrayIconEnterEvent }
   | { move: TrayIconMoveEvent }
   | { leave: TrayIconLeaveEvent }
+  | { themeChanged: TrayIconThemeChangedEvent }
 
 /**
  * Tray icon types and utilities.
@@ -112,6 +124,14 @@ export interface TrayIconOptions {
    * ```
    */
   icon?: string | Uint8Array | ArrayBuffer | number[] | Image
+  /**
+   * The icons to show for each system theme, falling back to {@linkcode TrayIconOptions.icon} for the other themes.
+   *
+   * The icon is swapped when the windows report a theme change, emitting a `themeChanged` event to the {@linkcode TrayIconOptions.action} handler.
+   */
+  iconForTheme?: Partial<
+    Record<Theme, string | Uint8Array | ArrayBuffer | number[] | Image>
+  >
   /** The tray icon tooltip */
   tooltip?: string
   /**
@@ -204,6 +224,14 @@ export class TrayIcon extends Resource {
     if (options?.icon) {
       options.icon = transformImage(options.icon)
     }
+    if (options?.iconForTheme) {
+      options.iconForTheme = Object.fromEntries(
+        Object.entries(options.iconForTheme).map(([theme, icon]) => [
+          theme,
+          transformImage(icon)
+        ])
+      )
+    }
 
     const handler = new Channel<TrayIconEvent>()
     if (options?.action) {
@@ -237,6 +265,27 @@ export class TrayIcon extends Resource {
     return invoke('plugin:tray|set_icon', { rid: this.rid, icon: trayIcon })
   }
 
+  /**
+   * Sets the icon to show when the system theme is `theme`, see {@linkcode TrayIconOptions.iconForTheme}.
+   * If `null` is provided, the {@linkcode TrayIconOptions.icon} is used for this theme.
+   *
+   * The icon is set right away if `theme` is the current system theme.
+   */
+  async setIconForTheme(
+    theme: Theme,
+    icon: string | Image | Uint8Array | ArrayBuffer | number[] | null
+  ): Promise<void> {
+    let trayIcon = null
+    if (icon) {
+      trayIcon = transformImage(icon)
+    }
+    return invoke('plugin:tray|set_icon_for_theme', {
+      rid: this.rid,
+      theme,
+      icon: trayIcon
+    })
+  }
+
   /**
    * Sets a new tray menu.
    *
@@ -76,6 +76,58 @@ const localTauriEvents = ['tauri://created', 'tauri://error']
 /** @ignore */
 export type WebviewLabel = string
 
+/**
+ * Memory and CPU usage of the process rendering a webview.
+ *
+ * @since 2.0.0
+ */
+interface WebviewMetrics {
+  /** Identifier of the sampled process. */
+  pid: number
+  /** Resident memory of the process in bytes. */
+  memoryBytes: number
+  /**
+   * CPU usage since the previous sample of the process,
+   * as a percentage of a single core so it can exceed 100 on multi-core systems.
+   * The first sample of a process reports `0`.
+   */
+  cpuPercent: number
+  /**
+   * Whether the metrics are the totals of the app
+   * because the platform does not expose the process rendering the webview.
+   */
+  appTotal: boolean
+}
+
+/**
+ * Options of a find-in-page search.
+ *
+ * @since 2.0.0
+ */
+interface FindOptions {
+  /**
+   * Search towards the end of the page, which is also the direction {@link Webview.findNext} moves in.
+   * Defaults to `true`.
+   */
+  forward?: boolean
+  /** Only find the text with the same case. Defaults to `false`. */
+  matchCase?: boolean
+  /** Continue from the other end of the page after the last match. Defaults to `true`. */
+  wrap?: boolean
+}
+
+/**
+ * Result of a find-in-page search.
+ *
+ * @since 2.0.0
+ */
+interface FindResult {
+  /** Number of matches in the page. */
+  matches: number
+  /** Index of the highlighted match, `null` when nothing is highlighted. */
+  activeIndex: number | null
+}
+
 /**
  * Create new webview or get a handle to an existing one.
  *
@@ -373,6 +425,22 @@ class Webview {
     ).then(({ width, height }) => new PhysicalSize(width, height))
   }
 
+  /**
+   * Samples the memory and CPU usage of the process rendering the webview.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * const { memoryBytes, cpuPercent } = await getCurrentWebview().processMetrics();
+   * ```
+   *
+   * @returns The webview process metrics.
+   */
+  async processMetrics(): Promise<WebviewMetrics> {
+    return invoke('plugin:webview|webview_process_metrics', {
+      label: this.label
+    })
+  }
+
   // Setters
 
   /**
@@ -489,6 +557,133 @@ class Webview {
     })
   }
 
+  /**
+   * Clears the webview HTTP disk and memory cache.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().clearCache();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async clearCache(): Promise<void> {
+    return invoke('plugin:webview|clear_webview_cache', {
+      label: this.label
+    })
+  }
+
+  /**
+   * Unregisters all service workers registered in the webview.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().unregisterServiceWorkers();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async unregisterServiceWorkers(): Promise<void> {
+    return invoke('plugin:webview|unregister_webview_service_workers', {
+      label: this.label
+    })
+  }
+
+  /**
+   * Clears all website data (cache, cookies, storage and service workers) stored for the given origin.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().clearAllSiteDataForOrigin('https://tauri.app');
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async clearAllSiteDataForOrigin(origin: string): Promise<void> {
+    return invoke('plugin:webview|clear_webview_site_data_for_origin', {
+      label: this.label,
+      value: origin
+    })
+  }
+
+  /**
+   * Searches the page for the text and highlights the first match.
+   * The result is reported to the {@link Webview.onFindResult} listeners.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().find('tauri', { matchCase: true });
+   * ```
+   *
+   * #### Platform-specific
+   *
+   * - **Windows / macOS / iOS / Android:** The page text is searched by an injected script,
+   *   which does not search inside iframes and highlights the match with the page selection.
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async find(text: string, options?: FindOptions): Promise<void> {
+    return invoke('plugin:webview|webview_find', {
+      label: this.label,
+      text,
+      options
+    })
+  }
+
+  /**
+   * Highlights the next match of the current search.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().findNext();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async findNext(): Promise<void> {
+    return invoke('plugin:webview|webview_find_next', {
+      label: this.label
+    })
+  }
+
+  /**
+   * Highlights the previous match of the current search.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().findPrevious();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async findPrevious(): Promise<void> {
+    return invoke('plugin:webview|webview_find_previous', {
+      label: this.label
+    })
+  }
+
+  /**
+   * Ends the current search.
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from '@tauri-apps/api/webview';
+   * await getCurrentWebview().stopFind();
+   * ```
+   *
+   * #### Platform-specific
+   *
+   * - **Linux:** The highlighted match is always cleared.
+   *
+   * @param clearSelection Whether to clear the highlighted match.
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async stopFind(clearSelection = true): Promise<void> {
+    return invoke('plugin:webview|webview_stop_find', {
+      label: this.label,
+      value: clearSelection
+    })
+  }
+
   /**
    * Moves this webview to the given label.
    * @example
@@ -593,6 +788,27 @@ class Webview {
       unlistenDragLeave()
     }
   }
+
+  /**
+   * Listen to the results of the find-in-page searches.
+   *
+   * @example
+   * ```typescript
+   * import { getCurrentWebview } from "@tauri-apps/api/webview";
+   * const unlisten = await getCurrentWebview().onFindResult(({ payload }) => {
+   *  console.log(`${payload.activeIndex ?? 0} of ${payload.matches}`);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   */
+  async onFindResult(handler: EventCallback<FindResult>): Promise<UnlistenFn> {
+    return this.listen<FindResult>(TauriEvent.FIND_RESULT, handler)
+  }
 }
 
 function mapPhysicalPosition(m: PhysicalPosition): PhysicalPosition {
@@ -649,6 +865,16 @@ interface WebviewOptions {
    * - **Android:** Unsupported.
    */
   incognito?: boolean
+  /**
+   * Whether the webview cookies and website data are kept between runs.
+   * `session` keeps them in memory and discards them when the webview is closed.
+   * Incognito webviews always use `session`.
+   *
+   * #### Platform-specific
+   *
+   * - **Android:** Unsupported, the data is always persisted.
+   */
+  cookiePersistence?: 'persistent' | 'session'
   /**
    * The proxy URL for the WebView for all network requests.
    *
@@ -671,8 +897,24 @@ interface WebviewOptions {
    * - **Android / iOS**: Unsupported.
    */
   zoomHotkeysEnabled?: boolean
+  /**
+   * Whether to unregister the service workers of the webview when the app version changes.
+   *
+   * The webview loads a blank page until its service workers are unregistered.
+   *
+   * ## Platform-specific:
+   *
+   * - **Android / iOS**: Unsupported.
+   */
+  clearServiceWorkersOnVersionChange?: boolean
 }
 
 export { Webview, getCurrentWebview, getAllWebviews }
 
-export type { DragDropEvent, WebviewOptions }
+export type {
+  DragDropEvent,
+  WebviewOptions,
+  WebviewMetrics,
+  FindOptions,
+  FindResult
+}
@@ -88,12 +88,16 @@ enum UserAttentionType {
    * #### Platform-specific
    * - **macOS:** Bounces the dock icon until the application is in focus.
    * - **Windows:** Flashes both the window and the taskbar button until the application is in focus.
+   * - **Linux (X11):** Sets the urgency hint, usually highlighting the taskbar entry until the window is focused.
+   * - **Linux (Wayland):** Requests the activation of the window, which the compositor shows as a notification or a highlighted taskbar entry when the window is not focused.
    */
   Critical = 1,
   /**
    * #### Platform-specific
    * - **macOS:** Bounces the dock icon once.
    * - **Windows:** Flashes the taskbar button until the application is in focus.
+   * - **Linux (X11):** Sets the urgency hint, usually highlighting the taskbar entry until the window is focused.
+   * - **Linux (Wayland):** Requests the activation of the window, which the compositor shows as a notification or a highlighted taskbar entry when the window is not focused.
    */
   Informational
 }
@@ -825,6 +829,7 @@ class Window {
    *
    * - **macOS:** `null` has no effect.
    * - **Linux:** Urgency levels have the same effect.
+   *   On Wayland, the activation of the window is requested instead of setting the urgency hint and `null` has no effect.
    * @example
    * ```typescript
    * import { getCurrentWindow } from '@tauri-apps/api/window';
@@ -851,6 +856,30 @@ class Window {
     })
   }
 
+  /**
+   * Plays the alert sound of the system for the window, e.g. when an action of the user is refused.
+   *
+   * See `playSystemSound` in the `app` module to play the other sounds of the system.
+   *
+   * #### Platform-specific
+   *
+   * - **Linux:** Rings the bell of the window, which may be shown as a visual bell depending on the desktop settings.
+   * @example
+   * ```typescript
+   * import { getCurrentWindow } from '@tauri-apps/api/window';
+   * await getCurrentWindow().beep();
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   *
+   * @since 2.0.0
+   */
+  async beep(): Promise<void> {
+    return invoke('plugin:window|beep', {
+      label: this.label
+    })
+  }
+
   /**
    * Updates the window resizable flag.
    * @example
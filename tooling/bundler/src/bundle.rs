@@ -3,15 +3,21 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+mod archive;
 mod category;
+mod checksum;
 mod common;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
+mod manifest;
+mod output;
 mod path_utils;
 mod platform;
 mod settings;
+mod staging;
+mod strip;
 mod updater_bundle;
 mod windows;
 
@@ -19,30 +25,119 @@ use tauri_utils::display_path;
 
 pub use self::{
   category::AppCategory,
+  common::{
+    copy_dir_with, copy_dir_with_progress, copy_file_with, CloneMode, CommandExt, CopyOptions,
+    CopyProgress, OutputLine,
+  },
+  manifest::{
+    bundle_manifest, bundle_manifest_path, write_bundle_manifest, BundleArtifact, BundleManifest,
+    BUNDLE_MANIFEST_FILE_NAME,
+  },
   settings::{
-    AppImageSettings, BundleBinary, BundleSettings, DebianSettings, DmgSettings, MacOsSettings,
-    PackageSettings, PackageType, Position, RpmSettings, Settings, SettingsBuilder, Size,
-    UpdaterSettings,
+    AppImageSettings, ArchiveFormat, ArchiveSettings, BundleBinary, BundleSettings, ChecksumKind,
+    DebCompression, DebianSettings, DigestAlgorithm, DmgSettings, FlatpakSettings, MacOsSettings,
+    MacSigningBackend, MacUpdaterArtifact, PackageSettings, PackageType, PacmanSettings,
+    PkgSettings, Position, RpmSettings, Settings, SettingsBuilder, Size, SnapSettings,
+    UniversalBinary, UpdaterSettings,
   },
 };
 #[cfg(target_os = "macos")]
 use anyhow::Context;
-pub use settings::{NsisSettings, WindowsSettings, WixLanguage, WixLanguageConfig, WixSettings};
+pub use settings::{
+  CustomSignCommandSettings, NsisSettings, WindowsSettings, WixLanguage, WixLanguageConfig,
+  WixSettings,
+};
 
-use std::{fmt::Write, path::PathBuf};
+use std::{
+  fmt::Write,
+  panic::{self, AssertUnwindSafe},
+  path::PathBuf,
+  sync::mpsc,
+  time::{Duration, Instant},
+};
 
 /// Generated bundle metadata.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Bundle {
   /// The package type.
   pub package_type: PackageType,
   /// All paths for this package.
   pub bundle_paths: Vec<PathBuf>,
+  /// Total size of the bundle paths in bytes, including the contents of directories.
+  pub size_bytes: u64,
+  /// Total size in bytes of the package files before they were archived or compressed into the bundle,
+  /// or `None` if the package type does not compress its files, e.g. the app bundle.
+  pub staged_size_bytes: Option<u64>,
+  /// Architecture of the bundled binaries, see [`Settings::binary_arch`].
+  pub architecture: String,
+}
+
+impl Bundle {
+  /// Creates a bundle whose size and architecture are filled once the bundler is finished.
+  pub(crate) fn new(package_type: PackageType, bundle_paths: Vec<PathBuf>) -> Self {
+    Self {
+      package_type,
+      bundle_paths,
+      size_bytes: 0,
+      staged_size_bytes: None,
+      architecture: String::new(),
+    }
+  }
+}
+
+/// The progress reported by [`bundle_project_with_progress`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum BundleProgress {
+  /// A binary is about to be signed before being bundled.
+  Signing {
+    /// The path of the binary.
+    path: PathBuf,
+  },
+  /// A package type started to be bundled.
+  Started {
+    /// The package type.
+    package_type: PackageType,
+  },
+  /// A package type was bundled.
+  ///
+  /// The bundle paths are where the bundles were written:
+  /// they are moved afterwards if `bundle > outputDir` or `bundle > artifactName` is set,
+  /// see the paths returned by [`bundle_project_with_progress`] for their final location.
+  Finished {
+    /// The package type.
+    package_type: PackageType,
+    /// The bundles created by the package type,
+    /// which also include the app bundle when the DMG had to create it.
+    bundles: Vec<Bundle>,
+    /// The time spent bundling the package type.
+    duration: Duration,
+  },
+}
+
+/// Flattens the bundles returned by [`bundle_project`] into the list of their paths.
+pub fn bundle_paths(bundles: &[Bundle]) -> Vec<PathBuf> {
+  bundles
+    .iter()
+    .flat_map(|bundle| bundle.bundle_paths.iter().cloned())
+    .collect()
 }
 
 /// Bundles the project.
-/// Returns the list of paths where the bundles can be found.
+/// Returns the bundles that were created, see [`bundle_paths`] to get the list of paths where they can be found.
+///
+/// The artifacts are also described in the JSON manifest at [`bundle_manifest_path`], see [`bundle_manifest`].
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
+  bundle_project_with_progress(settings, &|_| {})
+}
+
+/// Bundles the project like [`bundle_project`], calling `on_progress` as the package types are bundled.
+///
+/// `on_progress` is called from the threads bundling the package types when [`Settings::jobs`] is greater than 1.
+pub fn bundle_project_with_progress(
+  settings: &Settings,
+  on_progress: &(dyn Fn(BundleProgress) + Sync),
+) -> crate::Result<Vec<Bundle>> {
   let mut package_types = settings.package_types()?;
   if package_types.is_empty() {
     return Ok(Vec::new());
@@ -61,11 +156,18 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
     log::warn!("Cross-platform compilation is experimental and does not support all features. Please use a matching host system for full compatibility.");
   }
 
-  // Sign windows binaries before the bundling step in case neither wix and nsis bundles are enabled
-  if target_os == "windows" {
+  // strip the binaries before they are signed and copied to the bundles
+  common::with_dry_run(settings.dry_run(), || strip::strip_binaries(settings))?;
+
+  // Sign windows binaries before the bundling step in case neither wix and nsis bundles are enabled,
+  // dry runs do not run any tool
+  if target_os == "windows" && !settings.dry_run() {
     if settings.can_sign() {
       for bin in settings.binaries() {
         let bin_path = settings.binary_path(bin);
+        on_progress(BundleProgress::Signing {
+          path: bin_path.clone(),
+        });
         windows::sign::try_sign(&bin_path, settings)?;
       }
 
@@ -78,8 +180,9 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
           continue;
         }
 
+        // the custom sign command does not require signtool to be installed
         #[cfg(windows)]
-        if windows::sign::verify(&path)? {
+        if settings.windows().sign_command.is_none() && windows::sign::verify(&path)? {
           log::info!(
             "sidecar at \"{}\" already signed. Skipping...",
             path.display()
@@ -87,6 +190,7 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
           continue;
         }
 
+        on_progress(BundleProgress::Signing { path: path.clone() });
         windows::sign::try_sign(&path, settings)?;
       }
     } else {
@@ -95,52 +199,25 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
     }
   }
 
-  let mut bundles = Vec::<Bundle>::new();
-  for package_type in &package_types {
-    // bundle was already built! e.g. DMG already built .app
-    if bundles.iter().any(|b| b.package_type == *package_type) {
-      continue;
-    }
+  // catch the files overwriting each other before any package is written
+  staging::validate(settings, &package_types)?;
 
-    let bundle_paths = match package_type {
-      #[cfg(target_os = "macos")]
-      PackageType::MacOsBundle => macos::app::bundle_project(settings)?,
-      #[cfg(target_os = "macos")]
-      PackageType::IosBundle => macos::ios::bundle_project(settings)?,
-      // dmg is dependent of MacOsBundle, we send our bundles to prevent rebuilding
-      #[cfg(target_os = "macos")]
-      PackageType::Dmg => {
-        let bundled = macos::dmg::bundle_project(settings, &bundles)?;
-        if !bundled.app.is_empty() {
-          bundles.push(Bundle {
-            package_type: PackageType::MacOsBundle,
-            bundle_paths: bundled.app,
-          });
+  let mut bundles = run_scheduled(&package_types, settings.jobs(), |package_type, bundles| {
+    with_progress(on_progress, package_type, || {
+      common::with_dry_run(settings.dry_run(), || {
+        let (created, staged_size_bytes) =
+          common::with_staged_size(|| bundle_package(settings, package_type, bundles));
+        let mut created = created?;
+        if let Some(bundle) = created
+          .iter_mut()
+          .find(|bundle| bundle.package_type == package_type)
+        {
+          bundle.staged_size_bytes = staged_size_bytes;
         }
-        bundled.dmg
-      }
-
-      #[cfg(target_os = "windows")]
-      PackageType::WindowsMsi => windows::msi::bundle_project(settings, false)?,
-      PackageType::Nsis => windows::nsis::bundle_project(settings, false)?,
-
-      #[cfg(target_os = "linux")]
-      PackageType::Deb => linux::debian::bundle_project(settings)?,
-      #[cfg(target_os = "linux")]
-      PackageType::Rpm => linux::rpm::bundle_project(settings)?,
-      #[cfg(target_os = "linux")]
-      PackageType::AppImage => linux::appimage::bundle_project(settings)?,
-      _ => {
-        log::warn!("ignoring {}", package_type.short_name());
-        continue;
-      }
-    };
-
-    bundles.push(Bundle {
-      package_type: package_type.to_owned(),
-      bundle_paths,
-    });
-  }
+        Ok(created)
+      })
+    })
+  })?;
 
   if let Some(updater) = settings.updater() {
     if package_types.iter().any(|package_type| {
@@ -156,11 +233,13 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
         matches!(package_type, PackageType::MacOsBundle)
       }
     }) {
-      let updater_paths = updater_bundle::bundle_project(settings, &bundles)?;
-      bundles.push(Bundle {
-        package_type: PackageType::Updater,
-        bundle_paths: updater_paths,
-      });
+      let updater = with_progress(on_progress, PackageType::Updater, || {
+        let updater_paths = common::with_dry_run(settings.dry_run(), || {
+          updater_bundle::bundle_project(settings, &bundles)
+        })?;
+        Ok(vec![Bundle::new(PackageType::Updater, updater_paths)])
+      })?;
+      bundles.extend(updater);
     } else if updater.v1_compatible
       || !package_types.iter().any(|package_type| {
         // Self contained updater, no need to zip
@@ -177,9 +256,15 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
     }
   }
 
+  // nothing was written, so there is nothing to clean up, relocate or checksum
+  if settings.dry_run() {
+    log::info!(action = "Dry run"; "finished without writing the bundles");
+    return Ok(bundles);
+  }
+
   #[cfg(target_os = "macos")]
   {
-    // Clean up .app if only building dmg or updater
+    // Clean up .app if only building dmg, pkg or updater
     if !package_types.contains(&PackageType::MacOsBundle) {
       if let Some(app_bundle_paths) = bundles
         .iter()
@@ -208,6 +293,26 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
     return Err(anyhow::anyhow!("No bundles were built").into());
   }
 
+  // Move and rename the artifacts according to `bundle > outputDir` and `bundle > artifactName`
+  output::relocate_bundles(settings, &mut bundles)?;
+
+  for bundle in &mut bundles {
+    bundle.size_bytes = bundle_size(&bundle.bundle_paths)?;
+    bundle.architecture = settings.binary_arch().to_string();
+    if let Some(staged_size_bytes) = bundle.staged_size_bytes {
+      log::info!(
+        action = "Size";
+        "{}: {} staged, {} bundled",
+        bundle.package_type.short_name(),
+        common::format_size(staged_size_bytes),
+        common::format_size(bundle.size_bytes)
+      );
+    }
+  }
+
+  let checksum_paths = checksum::write_checksums(settings, &bundles)?;
+  write_bundle_manifest(settings, &bundle_manifest(settings, &bundles)?)?;
+
   let bundles_wo_updater = bundles
     .iter()
     .filter(|b| b.package_type != PackageType::Updater)
@@ -231,12 +336,207 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<Bundle>> {
       writeln!(printable_paths, "        {path_display}{note}").unwrap();
     }
   }
+  for path in &checksum_paths {
+    let path_display = display_path(path);
+    writeln!(printable_paths, "        {path_display} (checksum)").unwrap();
+  }
 
   log::info!(action = "Finished"; "{finished_bundles} {pluralised} at:\n{printable_paths}");
 
   Ok(bundles)
 }
 
+/// Reports the start and the end of `bundle` for `package_type` to `on_progress`.
+fn with_progress<F>(
+  on_progress: &(dyn Fn(BundleProgress) + Sync),
+  package_type: PackageType,
+  bundle: F,
+) -> crate::Result<Vec<Bundle>>
+where
+  F: FnOnce() -> crate::Result<Vec<Bundle>>,
+{
+  on_progress(BundleProgress::Started { package_type });
+  let start = Instant::now();
+  let bundles = bundle()?;
+  on_progress(BundleProgress::Finished {
+    package_type,
+    bundles: bundles.clone(),
+    duration: start.elapsed(),
+  });
+  Ok(bundles)
+}
+
+/// Total size of the given files and directories in bytes.
+fn bundle_size(paths: &[PathBuf]) -> crate::Result<u64> {
+  let mut size = 0;
+  for path in paths {
+    size += common::dir_size(path)?;
+  }
+  Ok(size)
+}
+
+/// Bundles a single package type, returning its bundles and the bundles it built along the way.
+///
+/// `bundles` are the bundles created so far.
+fn bundle_package(
+  settings: &Settings,
+  package_type: PackageType,
+  #[allow(unused_variables)] bundles: &[Bundle],
+) -> crate::Result<Vec<Bundle>> {
+  #[allow(unused_mut)]
+  let mut created = Vec::new();
+
+  let bundle_paths = match package_type {
+    #[cfg(target_os = "macos")]
+    PackageType::MacOsBundle => macos::app::bundle_project(settings)?,
+    #[cfg(target_os = "macos")]
+    PackageType::IosBundle => macos::ios::bundle_project(settings)?,
+    // dmg is dependent of MacOsBundle, we send our bundles to prevent rebuilding
+    #[cfg(target_os = "macos")]
+    PackageType::Dmg => {
+      let bundled = macos::dmg::bundle_project(settings, bundles)?;
+      if !bundled.app.is_empty() {
+        created.push(Bundle::new(PackageType::MacOsBundle, bundled.app));
+      }
+      bundled.dmg
+    }
+    // the installer package wraps the app bundle as well
+    #[cfg(target_os = "macos")]
+    PackageType::MacOsPkg => {
+      let bundled = macos::pkg::bundle_project(settings, bundles)?;
+      if !bundled.app.is_empty() {
+        created.push(Bundle::new(PackageType::MacOsBundle, bundled.app));
+      }
+      bundled.pkg
+    }
+
+    #[cfg(target_os = "windows")]
+    PackageType::WindowsMsi => windows::msi::bundle_project(settings, false)?,
+    PackageType::Nsis => windows::nsis::bundle_project(settings, false)?,
+
+    #[cfg(target_os = "linux")]
+    PackageType::Deb => linux::debian::bundle_project(settings)?,
+    #[cfg(target_os = "linux")]
+    PackageType::Rpm => linux::rpm::bundle_project(settings)?,
+    #[cfg(target_os = "linux")]
+    PackageType::AppImage => linux::appimage::bundle_project(settings)?,
+    #[cfg(target_os = "linux")]
+    PackageType::Flatpak => linux::flatpak::bundle_project(settings)?,
+    #[cfg(target_os = "linux")]
+    PackageType::Snap => linux::snap::bundle_project(settings)?,
+    #[cfg(target_os = "linux")]
+    PackageType::Pacman => linux::pacman::bundle_project(settings)?,
+    PackageType::Archive => archive::bundle_project(settings)?,
+    _ => {
+      log::warn!("ignoring {}", package_type.short_name());
+      return Ok(created);
+    }
+  };
+
+  created.push(Bundle::new(package_type, bundle_paths));
+  Ok(created)
+}
+
+/// Package types that must be bundled before the given one when both are requested.
+fn bundle_dependencies(package_type: PackageType) -> &'static [PackageType] {
+  match package_type {
+    // the DMG reuses the app bundle instead of building it again
+    PackageType::Dmg => &[PackageType::MacOsBundle],
+    // the installer package reuses the app bundle created by the DMG when the app is not requested
+    PackageType::MacOsPkg => &[PackageType::MacOsBundle, PackageType::Dmg],
+    // both installers sign the same binaries
+    PackageType::Nsis => &[PackageType::WindowsMsi],
+    _ => &[],
+  }
+}
+
+/// Calls `bundle` for each package type, running up to `jobs` of them concurrently.
+/// A package type only starts once the package types it depends on are bundled,
+/// and each call receives the bundles created so far.
+///
+/// The bundles are returned in the order of `package_types` regardless of when they finished.
+/// With a single job, the package types are bundled one after the other on the current thread.
+fn run_scheduled<F>(
+  package_types: &[PackageType],
+  jobs: usize,
+  bundle: F,
+) -> crate::Result<Vec<Bundle>>
+where
+  F: Fn(PackageType, &[Bundle]) -> crate::Result<Vec<Bundle>> + Sync,
+{
+  let mut pending = package_types.to_vec();
+  let mut finished = Vec::<PackageType>::new();
+  // bundles along with the index of the package type that created them
+  let mut bundles = Vec::<(usize, Bundle)>::new();
+  let mut error = None;
+
+  std::thread::scope(|scope| {
+    let (tx, rx) = mpsc::channel();
+    let mut running = 0;
+
+    loop {
+      while error.is_none() && running < jobs.max(1) {
+        let Some(next) = pending.iter().position(|package_type| {
+          bundle_dependencies(*package_type)
+            .iter()
+            .all(|dependency| !package_types.contains(dependency) || finished.contains(dependency))
+        }) else {
+          break;
+        };
+        let package_type = pending.remove(next);
+
+        // bundle was already built! e.g. DMG already built .app
+        if bundles.iter().any(|(_, b)| b.package_type == package_type) {
+          finished.push(package_type);
+          continue;
+        }
+
+        let built = bundles.iter().map(|(_, b)| b.clone()).collect::<Vec<_>>();
+        if jobs <= 1 {
+          let _ = tx.send((package_type, Ok(bundle(package_type, &built))));
+        } else {
+          let tx = tx.clone();
+          let bundle = &bundle;
+          scope.spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+              common::with_log_prefix(package_type.short_name(), || bundle(package_type, &built))
+            }));
+            let _ = tx.send((package_type, result));
+          });
+        }
+        running += 1;
+      }
+
+      if running == 0 {
+        break;
+      }
+
+      // the channel cannot be closed while we hold a sender
+      let (package_type, result) = rx.recv().unwrap();
+      running -= 1;
+      match result {
+        Ok(Ok(created)) => {
+          let index = package_types.iter().position(|p| *p == package_type);
+          bundles.extend(created.into_iter().map(|b| (index.unwrap_or_default(), b)));
+          finished.push(package_type);
+        }
+        // keep the first error, but let the running package types finish
+        Ok(Err(e)) => {
+          error.get_or_insert(e);
+        }
+        Err(payload) => panic::resume_unwind(payload),
+      }
+    }
+  });
+
+  if let Some(e) = error {
+    return Err(e);
+  }
+
+  bundles.sort_by_key(|(index, _)| *index);
+  Ok(bundles.into_iter().map(|(_, b)| b).collect())
+}
+
 /// Check to see if there are icons in the settings struct
 pub fn check_icons(settings: &Settings) -> crate::Result<bool> {
   // make a peekable iterator of the icon_files
@@ -249,3 +549,185 @@ pub fn check_icons(settings: &Settings) -> crate::Result<bool> {
     Ok(true)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    bundle_paths, bundle_size, run_scheduled, with_progress, Bundle, BundleProgress, PackageType,
+  };
+  use std::{path::PathBuf, sync::Mutex, thread, time::Duration};
+
+  #[test]
+  fn bundle_size_includes_directories() {
+    let temp = tempfile::tempdir().unwrap();
+    let artifact = temp.path().join("app.deb");
+    std::fs::write(&artifact, [0; 10]).unwrap();
+    let app_dir = temp.path().join("app.app");
+    std::fs::create_dir_all(app_dir.join("Contents/MacOS")).unwrap();
+    std::fs::write(app_dir.join("Contents/Info.plist"), [0; 5]).unwrap();
+    std::fs::write(app_dir.join("Contents/MacOS/app"), [0; 20]).unwrap();
+
+    assert_eq!(bundle_size(std::slice::from_ref(&artifact)).unwrap(), 10);
+    assert_eq!(bundle_size(std::slice::from_ref(&app_dir)).unwrap(), 25);
+    assert_eq!(bundle_size(&[artifact, app_dir]).unwrap(), 35);
+  }
+
+  #[test]
+  fn flattens_bundle_paths() {
+    let bundles = vec![
+      Bundle::new(PackageType::Deb, vec!["app.deb".into()]),
+      Bundle::new(
+        PackageType::Updater,
+        vec!["app.tar.gz".into(), "app.tar.gz.sig".into()],
+      ),
+    ];
+    assert_eq!(
+      bundle_paths(&bundles),
+      vec![
+        PathBuf::from("app.deb"),
+        PathBuf::from("app.tar.gz"),
+        PathBuf::from("app.tar.gz.sig")
+      ]
+    );
+  }
+
+  fn bundle(package_type: PackageType) -> Vec<Bundle> {
+    vec![Bundle::new(
+      package_type,
+      vec![PathBuf::from(package_type.short_name())],
+    )]
+  }
+
+  #[test]
+  fn scheduled_bundles_keep_package_types_order() {
+    let package_types = [PackageType::Deb, PackageType::Rpm, PackageType::AppImage];
+    let started = Mutex::new(Vec::new());
+    let bundles = run_scheduled(&package_types, 3, |package_type, _| {
+      started.lock().unwrap().push(package_type);
+      // finish in the reverse order
+      if package_type == PackageType::Deb {
+        thread::sleep(Duration::from_millis(200));
+      } else if package_type == PackageType::Rpm {
+        thread::sleep(Duration::from_millis(100));
+      }
+      Ok(bundle(package_type))
+    })
+    .unwrap();
+
+    assert_eq!(started.lock().unwrap().len(), 3);
+    assert_eq!(
+      bundles.iter().map(|b| b.package_type).collect::<Vec<_>>(),
+      package_types
+    );
+  }
+
+  #[test]
+  fn scheduled_bundles_wait_for_dependencies() {
+    let package_types = [
+      PackageType::MacOsBundle,
+      PackageType::WindowsMsi,
+      PackageType::Nsis,
+      PackageType::Dmg,
+    ];
+    let finished = Mutex::new(Vec::new());
+    let bundles = run_scheduled(&package_types, 4, |package_type, built| {
+      match package_type {
+        PackageType::Dmg => {
+          assert!(finished.lock().unwrap().contains(&PackageType::MacOsBundle));
+          assert!(built
+            .iter()
+            .any(|b| b.package_type == PackageType::MacOsBundle));
+        }
+        PackageType::Nsis => {
+          assert!(finished.lock().unwrap().contains(&PackageType::WindowsMsi));
+        }
+        _ => thread::sleep(Duration::from_millis(100)),
+      }
+      finished.lock().unwrap().push(package_type);
+      Ok(bundle(package_type))
+    })
+    .unwrap();
+
+    assert_eq!(
+      bundles.iter().map(|b| b.package_type).collect::<Vec<_>>(),
+      package_types
+    );
+  }
+
+  #[test]
+  fn reports_bundle_progress() {
+    let progress = Mutex::new(Vec::new());
+    let on_progress = |p: BundleProgress| {
+      progress.lock().unwrap().push(match p {
+        BundleProgress::Started { package_type } => {
+          format!("started {}", package_type.short_name())
+        }
+        BundleProgress::Finished {
+          package_type,
+          bundles,
+          ..
+        } => format!(
+          "finished {} {:?}",
+          package_type.short_name(),
+          bundle_paths(&bundles)
+        ),
+        p => panic!("unexpected progress {p:?}"),
+      })
+    };
+    run_scheduled(
+      &[PackageType::Deb, PackageType::Rpm],
+      1,
+      |package_type, _| {
+        with_progress(&on_progress, package_type, || {
+          if package_type == PackageType::Rpm {
+            return Err(anyhow::anyhow!("rpm failed").into());
+          }
+          Ok(bundle(package_type))
+        })
+      },
+    )
+    .unwrap_err();
+
+    // a failed package type is not reported as finished
+    assert_eq!(
+      *progress.lock().unwrap(),
+      ["started deb", "finished deb [\"deb\"]", "started rpm"]
+    );
+  }
+
+  #[test]
+  fn scheduled_bundles_single_job_is_sequential() {
+    let package_types = [PackageType::Deb, PackageType::Rpm, PackageType::AppImage];
+    let current = thread::current().id();
+    let order = Mutex::new(Vec::new());
+    run_scheduled(&package_types, 1, |package_type, built| {
+      assert_eq!(thread::current().id(), current);
+      // every previous package type is bundled already
+      assert_eq!(built.len(), order.lock().unwrap().len());
+      order.lock().unwrap().push(package_type);
+      Ok(bundle(package_type))
+    })
+    .unwrap();
+    assert_eq!(*order.lock().unwrap(), package_types);
+  }
+
+  #[test]
+  fn scheduled_bundles_stop_on_error() {
+    let package_types = [PackageType::Deb, PackageType::Rpm, PackageType::AppImage];
+    let started = Mutex::new(Vec::new());
+    let result = run_scheduled(&package_types, 1, |package_type, _| {
+      started.lock().unwrap().push(package_type);
+      if package_type == PackageType::Rpm {
+        Err(anyhow::anyhow!("failed to bundle rpm").into())
+      } else {
+        Ok(bundle(package_type))
+      }
+    });
+
+    assert!(result.is_err());
+    assert_eq!(
+      *started.lock().unwrap(),
+      [PackageType::Deb, PackageType::Rpm]
+    );
+  }
+}
@@ -0,0 +1,444 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// A portable archive is a compressed directory that runs the app once extracted,
+// without installing it. The directory is named `<product_name>_<version>_<arch>`
+// and laid out like the installed app so the resources are found at runtime:
+//
+// Linux, the layout of the Debian package data:
+//
+//     usr/bin/foobar                  # Main binary and sidecars
+//     usr/lib/foobar/...              # Resource files
+//     usr/share/icons/hicolor/...     # Icon files
+//
+// macOS, the layout of the app bundle `Contents` directory:
+//
+//     MacOS/foobar                    # Main binary and sidecars
+//     Resources/...                   # Resource files
+//     icons/...                       # Icon files
+//
+// Windows, the resources next to the executable:
+//
+//     foobar.exe                      # Main binary and sidecars
+//     ...                             # Resource files
+//     icons/...                       # Icon files
+
+use super::{common, output::artifact_arch, staging};
+use crate::{ArchiveFormat, PackageType, Settings};
+use anyhow::Context;
+use walkdir::WalkDir;
+
+use std::{
+  fs,
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+/// The compression level used when `bundle > archive > compressionLevel` is not set.
+const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the archives were created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let level = compression_level(settings)?;
+  let package_base_name = format!(
+    "{}_{}_{}",
+    settings.product_name(),
+    settings.version_string(),
+    artifact_arch(settings, PackageType::Archive)
+  );
+
+  let base_dir = settings.project_out_directory().join("bundle/archive");
+  let package_dir = base_dir.join(&package_base_name);
+  let archives = formats(settings)
+    .into_iter()
+    .map(|format| {
+      let extension = match format {
+        ArchiveFormat::TarGz => "tar.gz",
+        ArchiveFormat::Zip => "zip",
+      };
+      (
+        format,
+        base_dir.join(format!("{package_base_name}.{extension}")),
+      )
+    })
+    .collect::<Vec<_>>();
+  let paths = archives
+    .iter()
+    .map(|(_, path)| path.clone())
+    .collect::<Vec<_>>();
+
+  if staging::dry_run_package(settings, PackageType::Archive, &paths)? {
+    return Ok(paths);
+  }
+
+  if package_dir.exists() {
+    fs::remove_dir_all(&package_dir)
+      .with_context(|| format!("Failed to remove old {package_base_name}"))?;
+  }
+
+  stage(settings, &package_dir).with_context(|| "Failed to stage the archive files")?;
+  common::clamp_mtimes(settings, &package_dir)
+    .with_context(|| "Failed to clamp modification times")?;
+  common::record_staged_size([&package_dir])?;
+
+  for (format, archive_path) in archives {
+    let archive_name = archive_path.file_name().unwrap().to_string_lossy();
+    log::info!(action = "Bundling"; "{} ({})", archive_name, archive_path.display());
+
+    match format {
+      ArchiveFormat::TarGz => create_tar_gz(settings, &package_dir, &archive_path, level),
+      ArchiveFormat::Zip => create_zip(&package_dir, &archive_path, level),
+    }
+    .with_context(|| format!("Failed to create {}", archive_path.display()))?;
+  }
+
+  Ok(paths)
+}
+
+/// The configured archive formats, defaulting to the format native to the target platform.
+fn formats(settings: &Settings) -> Vec<ArchiveFormat> {
+  let formats = &settings.archive().formats;
+  if !formats.is_empty() {
+    let mut unique = Vec::new();
+    for format in formats {
+      if !unique.contains(format) {
+        unique.push(*format);
+      }
+    }
+    unique
+  } else if is_windows(settings) {
+    vec![ArchiveFormat::Zip]
+  } else {
+    vec![ArchiveFormat::TarGz]
+  }
+}
+
+fn is_windows(settings: &Settings) -> bool {
+  settings.target().contains("windows")
+}
+
+fn is_macos(settings: &Settings) -> bool {
+  settings.target().contains("apple-darwin")
+}
+
+fn compression_level(settings: &Settings) -> crate::Result<u32> {
+  match settings.archive().compression_level {
+    Some(level) if level > 9 => Err(crate::Error::GenericError(format!(
+      "invalid archive compression level {level}, expected a value from 0 to 9"
+    ))),
+    Some(level) => Ok(level),
+    None => Ok(DEFAULT_COMPRESSION_LEVEL),
+  }
+}
+
+/// The directories of the binaries and the resources in the archive,
+/// which must match the resource directory resolution of `tauri_utils::platform::resource_dir`.
+fn layout(settings: &Settings, package_dir: &Path) -> (PathBuf, PathBuf) {
+  if is_windows(settings) {
+    (package_dir.to_path_buf(), package_dir.to_path_buf())
+  } else if is_macos(settings) {
+    (package_dir.join("MacOS"), package_dir.join("Resources"))
+  } else {
+    (
+      package_dir.join("usr/bin"),
+      package_dir
+        .join("usr/lib")
+        .join(settings.main_binary_name()),
+    )
+  }
+}
+
+/// Copies the binaries, sidecars, resources and icons to the archive directory.
+fn stage(settings: &Settings, package_dir: &Path) -> crate::Result<()> {
+  let (bin_dir, resource_dir) = layout(settings, package_dir);
+
+  for bin in settings.binaries() {
+    let bin_path = settings.binary_path(bin);
+    common::copy_file(&bin_path, bin_dir.join(bin.name()))
+      .with_context(|| format!("Failed to copy binary from {bin_path:?}"))?;
+  }
+
+  settings
+    .copy_binaries(&bin_dir)
+    .with_context(|| "Failed to copy external binaries")?;
+
+  copy_resources(settings, &resource_dir).with_context(|| "Failed to copy resource files")?;
+
+  copy_icons(settings, package_dir).with_context(|| "Failed to copy icon files")?;
+
+  Ok(())
+}
+
+/// Copies the resources, keeping the symbolic links so the tarballs store them as links.
+fn copy_resources(settings: &Settings, resource_dir: &Path) -> crate::Result<()> {
+  for resource in settings.resources()? {
+    let dest = resource_dir.join(resource.target());
+    #[cfg(unix)]
+    if fs::symlink_metadata(resource.path())?.is_symlink() {
+      fs::create_dir_all(dest.parent().expect("resource target has no parent"))?;
+      std::os::unix::fs::symlink(fs::read_link(resource.path())?, &dest)?;
+      continue;
+    }
+    common::copy_file(resource.path(), &dest)?;
+  }
+  Ok(())
+}
+
+/// Copies the icons to the freedesktop `hicolor` theme on Linux and to the `icons` directory on the other platforms.
+fn copy_icons(settings: &Settings, package_dir: &Path) -> crate::Result<()> {
+  #[cfg(target_os = "linux")]
+  if !is_windows(settings) && !is_macos(settings) {
+    super::linux::freedesktop::copy_icon_files(settings, package_dir)?;
+    return Ok(());
+  }
+
+  let icons_dir = package_dir.join("icons");
+  for icon in settings.icon_files() {
+    let icon = icon?;
+    let dest = icons_dir.join(icon.file_name().expect("icon path has no file name"));
+    if dest.exists() {
+      return Err(crate::Error::GenericError(format!(
+        "{} is bundled both as a resource and as an icon",
+        dest.display()
+      )));
+    }
+    common::copy_file(&icon, &dest)?;
+  }
+  Ok(())
+}
+
+/// Archives the directory in a gzip compressed tarball, storing the symbolic links as links.
+fn create_tar_gz(
+  settings: &Settings,
+  src_dir: &Path,
+  dest_path: &Path,
+  level: u32,
+) -> crate::Result<()> {
+  use flate2::{write::GzEncoder, Compression};
+
+  let dest_file = common::create_file(dest_path)?;
+  let mut builder = tar::Builder::new(GzEncoder::new(dest_file, Compression::new(level)));
+  builder.follow_symlinks(false);
+  if settings.source_date_epoch().is_some() {
+    builder.mode(tar::HeaderMode::Deterministic);
+  }
+
+  for (path, name) in entries(src_dir)? {
+    builder.append_path_with_name(&path, name)?;
+  }
+
+  let mut dest_file = builder.into_inner()?.finish()?;
+  dest_file.flush()?;
+  Ok(())
+}
+
+/// Archives the directory in a deflate compressed zip, storing the content of the symbolic links.
+fn create_zip(src_dir: &Path, dest_path: &Path, level: u32) -> crate::Result<()> {
+  use zip::{write::FileOptions, CompressionMethod};
+
+  let mut zip = zip::ZipWriter::new(common::create_file(dest_path)?);
+  let options = FileOptions::default()
+    .compression_method(CompressionMethod::Deflated)
+    .compression_level(Some(level as i32));
+
+  for (path, name) in entries(src_dir)? {
+    let metadata = fs::metadata(&path)?;
+    #[cfg(unix)]
+    let options = {
+      use std::os::unix::fs::PermissionsExt;
+      options.unix_permissions(metadata.permissions().mode() & 0o777)
+    };
+    if metadata.is_dir() {
+      zip.add_directory(name, options)?;
+    } else {
+      zip.start_file(name, options)?;
+      io::copy(&mut fs::File::open(&path)?, &mut zip)?;
+    }
+  }
+
+  zip.finish()?.flush()?;
+  Ok(())
+}
+
+/// The entries of the directory sorted by path, with their `/` separated names in the archive,
+/// which include the directory itself.
+fn entries(src_dir: &Path) -> crate::Result<Vec<(PathBuf, String)>> {
+  // safe to unwrap: the package directory is in the bundle directory
+  let base_dir = src_dir.parent().unwrap();
+  let mut entries = Vec::new();
+  for entry in WalkDir::new(src_dir).sort_by_file_name() {
+    let entry = entry?;
+    let name = entry
+      .path()
+      .strip_prefix(base_dir)?
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+    entries.push((entry.into_path(), name));
+  }
+  Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{ArchiveSettings, BundleBinary, BundleSettings, PackageSettings, SettingsBuilder};
+  use std::{collections::HashMap, io::Read};
+
+  fn settings(out_dir: &Path, target: &str, archive: ArchiveSettings) -> Settings {
+    let bin = if target.contains("windows") {
+      "my-app.exe"
+    } else {
+      "my-app"
+    };
+    fs::write(out_dir.join(bin), b"my-app binary").unwrap();
+    let assets = out_dir.join("assets");
+    fs::create_dir_all(&assets).unwrap();
+    fs::write(assets.join("data.json"), "{}").unwrap();
+    SettingsBuilder::new()
+      .project_out_directory(out_dir)
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        resources_map: Some(HashMap::from([(
+          assets.display().to_string(),
+          "assets".into(),
+        )])),
+        archive,
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new(bin.into(), true)])
+      .target(target.into())
+      .build()
+      .unwrap()
+  }
+
+  fn tar_entries(path: &Path) -> Vec<(String, tar::EntryType)> {
+    let file = fs::File::open(path).unwrap();
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive
+      .entries()
+      .unwrap()
+      .map(|entry| {
+        let entry = entry.unwrap();
+        (
+          entry.path().unwrap().display().to_string(),
+          entry.header().entry_type(),
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn linux_layout_matches_the_debian_package() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = settings(
+      tmp.path(),
+      "x86_64-unknown-linux-gnu",
+      ArchiveSettings::default(),
+    );
+
+    let paths = bundle_project(&settings).unwrap();
+    assert_eq!(
+      paths,
+      vec![tmp.path().join("bundle/archive/My App_1.0.0_x64.tar.gz")]
+    );
+
+    let names = tar_entries(&paths[0])
+      .into_iter()
+      .map(|(name, _)| name)
+      .collect::<Vec<_>>();
+    assert!(names.contains(&"My App_1.0.0_x64/usr/bin/my-app".to_string()));
+    assert!(names.contains(&"My App_1.0.0_x64/usr/lib/my-app/assets/data.json".to_string()));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn tarball_keeps_symlinks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let assets = tmp.path().join("assets");
+    fs::create_dir_all(&assets).unwrap();
+    std::os::unix::fs::symlink("data.json", assets.join("link.json")).unwrap();
+    let settings = settings(
+      tmp.path(),
+      "x86_64-unknown-linux-gnu",
+      ArchiveSettings::default(),
+    );
+
+    let paths = bundle_project(&settings).unwrap();
+    let entries = tar_entries(&paths[0]);
+    assert!(entries.contains(&(
+      "My App_1.0.0_x64/usr/lib/my-app/assets/link.json".to_string(),
+      tar::EntryType::Symlink
+    )));
+  }
+
+  #[test]
+  fn windows_zip_keeps_resources_next_to_the_executable() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = settings(
+      tmp.path(),
+      "x86_64-pc-windows-msvc",
+      ArchiveSettings {
+        compression_level: Some(9),
+        ..Default::default()
+      },
+    );
+
+    let paths = bundle_project(&settings).unwrap();
+    assert_eq!(
+      paths,
+      vec![tmp.path().join("bundle/archive/My App_1.0.0_x64.zip")]
+    );
+
+    let mut zip = zip::ZipArchive::new(fs::File::open(&paths[0]).unwrap()).unwrap();
+    let mut data = String::new();
+    zip
+      .by_name("My App_1.0.0_x64/assets/data.json")
+      .unwrap()
+      .read_to_string(&mut data)
+      .unwrap();
+    assert_eq!(data, "{}");
+    assert!(zip.by_name("My App_1.0.0_x64/my-app.exe").is_ok());
+  }
+
+  #[test]
+  fn formats_and_compression_level() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = settings(
+      tmp.path(),
+      "aarch64-apple-darwin",
+      ArchiveSettings {
+        formats: vec![ArchiveFormat::Zip, ArchiveFormat::TarGz, ArchiveFormat::Zip],
+        compression_level: Some(10),
+      },
+    );
+    assert_eq!(
+      formats(&settings),
+      vec![ArchiveFormat::Zip, ArchiveFormat::TarGz]
+    );
+    assert!(bundle_project(&settings).is_err());
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/archive/My App_1.0.0_x64/usr/bin/my-app"],
+      &["bundle/archive/My App_1.0.0_x64.tar.gz"],
+      || bundle_project(&settings),
+    );
+  }
+}
@@ -0,0 +1,192 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{Bundle, Settings};
+
+use sha2::{Digest, Sha256, Sha512};
+use tauri_utils::{config::ChecksumKind, display_path};
+
+use std::{
+  ffi::OsString,
+  fs::File,
+  io::{ErrorKind, Read},
+  path::{Path, PathBuf},
+};
+
+/// Size of the chunks read from the artifacts while hashing them.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+enum Hasher {
+  Sha256(Sha256),
+  Sha512(Sha512),
+}
+
+impl Hasher {
+  fn new(kind: ChecksumKind) -> Self {
+    match kind {
+      ChecksumKind::Sha256 => Self::Sha256(Sha256::new()),
+      ChecksumKind::Sha512 => Self::Sha512(Sha512::new()),
+    }
+  }
+
+  fn update(&mut self, data: &[u8]) {
+    match self {
+      Self::Sha256(hasher) => hasher.update(data),
+      Self::Sha512(hasher) => hasher.update(data),
+    }
+  }
+
+  fn finalize(self) -> String {
+    match self {
+      Self::Sha256(hasher) => hex::encode(hasher.finalize()),
+      Self::Sha512(hasher) => hex::encode(hasher.finalize()),
+    }
+  }
+}
+
+/// The extension appended to the artifact path for the checksum file of the given kind.
+fn extension(kind: ChecksumKind) -> &'static str {
+  match kind {
+    ChecksumKind::Sha256 => "sha256",
+    ChecksumKind::Sha512 => "sha512",
+  }
+}
+
+/// Computes the hex encoded digests of a file, reading it once in chunks
+/// so large artifacts are not loaded in memory.
+pub(crate) fn digest_file(path: &Path, kinds: &[ChecksumKind]) -> crate::Result<Vec<String>> {
+  let mut hashers = kinds
+    .iter()
+    .map(|kind| Hasher::new(*kind))
+    .collect::<Vec<_>>();
+  let mut file = File::open(path)?;
+  let mut buf = vec![0; CHUNK_SIZE];
+  loop {
+    let read = match file.read(&mut buf) {
+      Ok(0) => break,
+      Ok(read) => read,
+      Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+      Err(e) => return Err(e.into()),
+    };
+    for hasher in &mut hashers {
+      hasher.update(&buf[..read]);
+    }
+  }
+  Ok(hashers.into_iter().map(Hasher::finalize).collect())
+}
+
+/// Writes a `sha256sum` compatible checksum file next to each bundle artifact
+/// for each of the [`Settings::checksums`] kinds, returning the paths of the written files.
+///
+/// Directory artifacts are skipped.
+pub fn write_checksums(settings: &Settings, bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
+  let kinds = settings.checksums();
+  let mut checksum_paths = Vec::new();
+  if kinds.is_empty() {
+    return Ok(checksum_paths);
+  }
+
+  for path in bundles.iter().flat_map(|bundle| &bundle.bundle_paths) {
+    if !path.is_file() {
+      log::debug!("Skipping the checksum of {}", display_path(path));
+      continue;
+    }
+
+    let file_name = path
+      .file_name()
+      .expect("failed to extract the artifact file name")
+      .to_string_lossy();
+    for (kind, digest) in kinds.iter().zip(digest_file(path, kinds)?) {
+      let mut checksum_path = OsString::from(path);
+      checksum_path.push(".");
+      checksum_path.push(extension(*kind));
+      let checksum_path = PathBuf::from(checksum_path);
+
+      std::fs::write(&checksum_path, format!("{digest}  {file_name}\n"))?;
+      checksum_paths.push(checksum_path);
+    }
+  }
+
+  Ok(checksum_paths)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{digest_file, write_checksums, CHUNK_SIZE};
+  use crate::{Bundle, BundleSettings, PackageSettings, PackageType, SettingsBuilder};
+  use sha2::{Digest, Sha256};
+  use tauri_utils::config::ChecksumKind;
+
+  #[test]
+  fn digests_files_in_chunks() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+
+    let path = tmp.path().join("hello.txt");
+    std::fs::write(&path, "hello world").unwrap();
+    assert_eq!(
+      digest_file(&path, &[ChecksumKind::Sha256, ChecksumKind::Sha512]).unwrap(),
+      [
+        "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+        "309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f"
+      ]
+    );
+
+    let contents = (0..CHUNK_SIZE * 3 + 7)
+      .map(|i| (i % 251) as u8)
+      .collect::<Vec<_>>();
+    let path = tmp.path().join("large.bin");
+    std::fs::write(&path, &contents).unwrap();
+    assert_eq!(
+      digest_file(&path, &[ChecksumKind::Sha256]).unwrap(),
+      [hex::encode(Sha256::digest(&contents))]
+    );
+  }
+
+  #[test]
+  fn writes_checksum_files() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let artifact = tmp.path().join("MyApp_1.2.3_amd64.deb");
+    std::fs::write(&artifact, "hello world").unwrap();
+    let app_dir = tmp.path().join("MyApp.app");
+    std::fs::create_dir(&app_dir).unwrap();
+
+    let settings = SettingsBuilder::new()
+      .project_out_directory(tmp.path())
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        checksums: vec![ChecksumKind::Sha256, ChecksumKind::Sha512],
+        ..Default::default()
+      })
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .expect("failed to build settings");
+
+    let bundles = [
+      Bundle::new(PackageType::Deb, vec![artifact]),
+      Bundle::new(PackageType::MacOsBundle, vec![app_dir]),
+    ];
+    let paths = write_checksums(&settings, &bundles).unwrap();
+    assert_eq!(
+      paths,
+      [
+        tmp.path().join("MyApp_1.2.3_amd64.deb.sha256"),
+        tmp.path().join("MyApp_1.2.3_amd64.deb.sha512")
+      ]
+    );
+    assert_eq!(
+      std::fs::read_to_string(&paths[0]).unwrap(),
+      "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9  MyApp_1.2.3_amd64.deb\n"
+    );
+    assert!(std::fs::read_to_string(&paths[1])
+      .unwrap()
+      .ends_with("  MyApp_1.2.3_amd64.deb\n"));
+  }
+}
@@ -3,27 +3,48 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use crate::Settings;
+use filetime::FileTime;
+
 use std::{
+  cell::Cell,
   ffi::OsStr,
   fs::{self, File},
-  io::{self, BufRead, BufReader, BufWriter},
-  path::Path,
+  io::{self, BufRead, BufReader, BufWriter, Read},
+  path::{Component, Path, PathBuf},
   process::{Command, ExitStatus, Output, Stdio},
-  sync::{Arc, Mutex},
+  sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc, Arc, Mutex,
+  },
+  thread::{self, JoinHandle},
+  time::{Duration, Instant},
 };
 
-/// Returns true if the path has a filename indicating that it is a high-density
-/// "retina" icon.  Specifically, returns true the file stem ends with
-/// "@2x" (a convention specified by the [Apple developer docs](
-/// <https://developer.apple.com/library/mac/documentation/GraphicsAnimation/Conceptual/HighResolutionOSX/Optimizing/Optimizing.html>)).
+/// Returns the scale factor of an icon from its filename.  Specifically, returns
+/// 2 or 3 if the file stem ends with "@2x" or "@3x" (a convention specified by the [Apple developer docs](
+/// <https://developer.apple.com/library/mac/documentation/GraphicsAnimation/Conceptual/HighResolutionOSX/Optimizing/Optimizing.html>)),
+/// and 1 otherwise.
 #[allow(dead_code)]
-pub fn is_retina<P: AsRef<Path>>(path: P) -> bool {
+pub fn icon_scale<P: AsRef<Path>>(path: P) -> u32 {
   path
     .as_ref()
     .file_stem()
     .and_then(OsStr::to_str)
-    .map(|stem| stem.ends_with("@2x"))
-    .unwrap_or(false)
+    .and_then(|stem| stem.rsplit_once('@'))
+    .and_then(|(_, suffix)| match suffix {
+      "2x" => Some(2),
+      "3x" => Some(3),
+      _ => None,
+    })
+    .unwrap_or(1)
+}
+
+/// Returns true if the path has a filename indicating that it is a high-density
+/// "retina" icon, see [`icon_scale`].
+#[allow(dead_code)]
+pub fn is_retina<P: AsRef<Path>>(path: P) -> bool {
+  icon_scale(path) >= 2
 }
 
 /// Creates a new file at the given path, creating any parent directories as
@@ -62,10 +83,408 @@ fn symlink_file(src: &Path, dst: &Path) -> io::Result<()> {
   std::os::windows::fs::symlink_file(src, dst)
 }
 
+/// Options for [`copy_file_with`] and [`copy_dir_with`].
+#[derive(Debug, Clone)]
+pub struct CopyOptions {
+  /// Whether to merge into an existing destination directory instead of failing.
+  ///
+  /// Files and symlinks with the same relative path are replaced,
+  /// entries that only exist in the destination are left untouched.
+  /// Without it [`copy_file_with`] only replaces an existing file with [`CloneMode::Copy`].
+  pub overwrite: bool,
+  /// Whether to set the modification time of the copied entries to the source modification time.
+  ///
+  /// Enabled by default.
+  pub preserve_timestamps: bool,
+  /// Whether to set the permissions of the copied entries to the source permissions.
+  ///
+  /// The owner can always write the copied entries so the later bundling steps can strip or sign them.
+  /// Enabled by default.
+  pub preserve_mode: bool,
+  /// Unix permissions set on the copied files and directories matching a pattern instead of the source permissions,
+  /// the first matching pattern wins.
+  ///
+  /// The patterns are matched like [`CopyOptions::exclude`],
+  /// and against the file name of the source by [`copy_file_with`].
+  /// The modes are used as is, without the owner write permission added by [`CopyOptions::preserve_mode`].
+  /// Hard linked files keep the source permissions, see [`CloneMode::Hardlink`]. Ignored on Windows.
+  pub modes: Vec<(glob::Pattern, u32)>,
+  /// Number of threads [`copy_dir_with`] uses to copy files. `0` and `1` copy serially.
+  ///
+  /// Directories and symlinks are always created serially before the files are copied.
+  pub threads: usize,
+  /// Entries [`copy_dir_with`] skips, along with their contents when they are directories.
+  ///
+  /// The patterns are matched against the path of each entry relative to the source directory,
+  /// not its absolute path, so use e.g. `**/node_modules` to exclude a directory at any depth.
+  pub exclude: Vec<glob::Pattern>,
+  /// How the files are created in the destination.
+  pub clone_mode: CloneMode,
+}
+
+impl Default for CopyOptions {
+  fn default() -> Self {
+    Self {
+      overwrite: false,
+      preserve_timestamps: true,
+      preserve_mode: true,
+      modes: Vec::new(),
+      threads: 0,
+      exclude: Vec::new(),
+      clone_mode: CloneMode::default(),
+    }
+  }
+}
+
+/// How [`copy_file_with`] and [`copy_dir_with`] create the copied files.
+///
+/// The faster methods only work when the source and destination are on the same filesystem
+/// and the filesystem supports them, otherwise the file is transparently copied.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CloneMode {
+  /// Tries a reflink, then a hard link, then falls back to a regular copy.
+  ///
+  /// See [`CloneMode::Hardlink`] for when hard links must not be used.
+  Auto,
+  /// Tries a copy-on-write clone of the file (`FICLONE` on Linux, `clonefile` on macOS),
+  /// falling back to a regular copy.
+  Reflink,
+  /// Tries a hard link, falling back to a regular copy.
+  ///
+  /// The destination shares its contents and metadata with the source,
+  /// so this must only be used for files that are never modified after being copied.
+  Hardlink,
+  /// Always copies the contents of the file.
+  #[default]
+  Copy,
+}
+
+/// Progress of a [`copy_dir_with_progress`] operation, reported after each copied file or symlink.
+#[derive(Debug, Clone, Copy)]
+pub struct CopyProgress<'a> {
+  /// Number of files and symlinks copied so far.
+  pub files_copied: u64,
+  /// Total number of files and symlinks to copy.
+  pub total_files: u64,
+  /// Number of bytes copied so far.
+  pub bytes_copied: u64,
+  /// Path of the entry that was just copied, relative to the source directory.
+  pub path: &'a Path,
+}
+
+/// Applies the source metadata requested by the [`CopyOptions`] to a copied entry,
+/// `rel_path` being the path matched against [`CopyOptions::modes`].
+fn apply_metadata(
+  from: &Path,
+  to: &Path,
+  rel_path: &Path,
+  options: &CopyOptions,
+) -> io::Result<()> {
+  let mode = forced_mode(rel_path, options);
+  if !options.preserve_mode && !options.preserve_timestamps && mode.is_none() {
+    return Ok(());
+  }
+  let metadata = fs::metadata(from)?;
+  // a hard link shares the source metadata, which must be left untouched
+  if is_same_file(&metadata, to)? {
+    return Ok(());
+  }
+  if let Some(mode) = mode {
+    set_mode(to, mode)?;
+  } else if options.preserve_mode {
+    fs::set_permissions(to, writable_permissions(&metadata))?;
+  }
+  if options.preserve_timestamps {
+    filetime::set_file_mtime(to, FileTime::from_last_modification_time(&metadata))?;
+  }
+  Ok(())
+}
+
+/// The mode of the first [`CopyOptions::modes`] pattern matching the path.
+fn forced_mode(rel_path: &Path, options: &CopyOptions) -> Option<u32> {
+  options
+    .modes
+    .iter()
+    .find(|(pattern, _)| pattern.matches_path(rel_path))
+    .map(|(_, mode)| *mode)
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+  fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+  Ok(())
+}
+
+/// The source permissions with the owner access needed to strip, sign or fill the copied entry.
+fn writable_permissions(metadata: &fs::Metadata) -> fs::Permissions {
+  let mut permissions = metadata.permissions();
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    let owner = if metadata.is_dir() { 0o700 } else { 0o600 };
+    permissions.set_mode(permissions.mode() | owner);
+  }
+  // Windows only has the read-only attribute
+  #[cfg(not(unix))]
+  #[allow(clippy::permissions_set_readonly_false)]
+  {
+    permissions.set_readonly(false);
+  }
+  permissions
+}
+
+#[cfg(unix)]
+fn is_same_file(metadata: &fs::Metadata, path: &Path) -> io::Result<bool> {
+  use std::os::unix::fs::MetadataExt;
+  let other = fs::metadata(path)?;
+  Ok(metadata.dev() == other.dev() && metadata.ino() == other.ino())
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_metadata: &fs::Metadata, _path: &Path) -> io::Result<bool> {
+  Ok(false)
+}
+
+/// Removes a file, symlink or directory at the given path if it exists.
+fn remove_existing(path: &Path) -> io::Result<()> {
+  match fs::symlink_metadata(path) {
+    Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+    Ok(_) => fs::remove_file(path),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+    Err(e) => Err(e),
+  }
+}
+
+/// Creates `to` from `from` with the given [`CloneMode`], returning the size of the file.
+///
+/// Falls back to [`fs::copy`] when the requested method fails.
+///
+/// Fails if `to` already exists and the mode is not [`CloneMode::Copy`]: reflinks and hard links never replace a file,
+/// and copying over a previous hard link would truncate the source,
+/// so the destination must be removed beforehand when [`CopyOptions::overwrite`] is set.
+fn clone_file(from: &Path, to: &Path, mode: CloneMode) -> io::Result<u64> {
+  if mode != CloneMode::Copy && fs::symlink_metadata(to).is_ok() {
+    return Err(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("{} already exists", to.display()),
+    ));
+  }
+  if matches!(mode, CloneMode::Auto | CloneMode::Reflink) && reflink_copy::reflink(from, to).is_ok()
+  {
+    return fs::metadata(to).map(|m| m.len());
+  }
+  if matches!(mode, CloneMode::Auto | CloneMode::Hardlink) && fs::hard_link(from, to).is_ok() {
+    return fs::metadata(to).map(|m| m.len());
+  }
+  fs::copy(from, to)
+}
+
+/// Copies a regular file to an existing directory, returning the number of copied bytes.
+fn copy_regular_file(
+  from: &Path,
+  to: &Path,
+  rel_path: &Path,
+  options: &CopyOptions,
+) -> io::Result<u64> {
+  if options.overwrite {
+    // never write through an existing symlink or into a directory
+    remove_existing(to)?;
+  }
+  let bytes = clone_file(from, to, options.clone_mode)?;
+  apply_metadata(from, to, rel_path, options)?;
+  Ok(bytes)
+}
+
+/// Copies the given `(source, destination)` files of the `root` directory on `threads` worker threads,
+/// calling `on_copied` with the index and size of each copied file.
+///
+/// Once a copy fails the workers stop picking up new files,
+/// and the error of the first failed file in `files` order is returned.
+fn copy_files_parallel(
+  root: &Path,
+  files: &[(PathBuf, PathBuf)],
+  options: &CopyOptions,
+  threads: usize,
+  on_copied: &mut dyn FnMut(usize, u64),
+) -> crate::Result<()> {
+  let next = AtomicUsize::new(0);
+  let failed = AtomicBool::new(false);
+  let (tx, rx) = mpsc::channel();
+
+  thread::scope(|scope| {
+    for _ in 0..threads.min(files.len()) {
+      let tx = tx.clone();
+      let (next, failed) = (&next, &failed);
+      scope.spawn(move || {
+        while !failed.load(Ordering::Relaxed) {
+          let index = next.fetch_add(1, Ordering::Relaxed);
+          let Some((from, to)) = files.get(index) else {
+            break;
+          };
+          let rel_path = from.strip_prefix(root).unwrap_or(from);
+          let result = copy_regular_file(from, to, rel_path, options);
+          if result.is_err() {
+            failed.store(true, Ordering::Relaxed);
+          }
+          if tx.send((index, result)).is_err() {
+            break;
+          }
+        }
+      });
+    }
+    drop(tx);
+
+    let mut first_error: Option<(usize, io::Error)> = None;
+    for (index, result) in rx {
+      match result {
+        Ok(bytes) => on_copied(index, bytes),
+        Err(e) => {
+          if first_error.as_ref().map_or(true, |(i, _)| index < *i) {
+            first_error.replace((index, e));
+          }
+        }
+      }
+    }
+    match first_error {
+      Some((_, e)) => Err(e.into()),
+      None => Ok(()),
+    }
+  })
+}
+
+thread_local! {
+  /// Whether the copies and archives of the current thread are only logged, see [`Settings::dry_run`].
+  static DRY_RUN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with the copies and archives of the current thread only logged when `dry_run` is set.
+pub fn with_dry_run<T>(dry_run: bool, f: impl FnOnce() -> T) -> T {
+  let previous = DRY_RUN.with(|d| d.replace(dry_run));
+  let result = f();
+  DRY_RUN.with(|d| d.set(previous));
+  result
+}
+
+/// Whether the copies and archives of the current thread are only logged.
+pub fn is_dry_run() -> bool {
+  DRY_RUN.with(Cell::get)
+}
+
+/// Total size in bytes of the files in the directory at `path`, or of `path` itself if it is a file.
+///
+/// Symbolic links are not followed.
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+  let mut size = 0;
+  for entry in walkdir::WalkDir::new(path) {
+    let entry = entry?;
+    if entry.file_type().is_file() {
+      size += entry.metadata()?.len();
+    }
+  }
+  Ok(size)
+}
+
+thread_local! {
+  /// The size of the files staged by the package type being bundled on the current thread, see [`record_staged_size`].
+  static STAGED_SIZE: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Records the size of the files of the package before they are archived or compressed,
+/// reported as [`crate::Bundle::staged_size_bytes`] of the package type being bundled on the current thread.
+///
+/// Nothing is recorded in dry-run mode since the files are not staged.
+pub fn record_staged_size<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> crate::Result<()> {
+  if is_dry_run() {
+    return Ok(());
+  }
+  let mut size = 0;
+  for path in paths {
+    size += dir_size(path.as_ref())?;
+  }
+  record_staged_bytes(size);
+  Ok(())
+}
+
+/// Records `size` bytes of package files, for the packages built without staging the files first.
+///
+/// See [`record_staged_size`].
+pub fn record_staged_bytes(size: u64) {
+  STAGED_SIZE.with(|s| s.set(Some(s.get().unwrap_or(0) + size)));
+}
+
+/// Runs `f`, returning the size recorded by [`record_staged_size`] on the current thread while it ran.
+pub fn with_staged_size<T>(f: impl FnOnce() -> T) -> (T, Option<u64>) {
+  let previous = STAGED_SIZE.with(|s| s.replace(None));
+  let result = f();
+  let size = STAGED_SIZE.with(|s| s.replace(previous));
+  (result, size)
+}
+
+/// Formats `bytes` with a binary unit, e.g. `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+  if bytes < 1024 {
+    return format!("{bytes} B");
+  }
+  let mut size = bytes as f64 / 1024.0;
+  let mut unit = UNITS[0];
+  for next in &UNITS[1..] {
+    if size < 1024.0 {
+      break;
+    }
+    size /= 1024.0;
+    unit = next;
+  }
+  format!("{size:.1} {unit}")
+}
+
+/// Logs the entries of the archive that would be written to `dest` from the given files and directories.
+///
+/// Returns `false` when not running in dry-run mode, in which case the archive must actually be written.
+pub fn dry_run_archive(srcs: &[&Path], dest: &Path) -> crate::Result<bool> {
+  if !is_dry_run() {
+    return Ok(false);
+  }
+  log::info!(action = "Dry run"; "{}would write archive {}", log_prefix(), dest.display());
+  for src in srcs {
+    for entry in walkdir::WalkDir::new(src).sort_by_file_name() {
+      let entry = entry?;
+      if entry.file_type().is_file() {
+        log::info!(
+          action = "Dry run";
+          "{}would archive {} ({} bytes)",
+          log_prefix(),
+          entry.path().display(),
+          entry.metadata()?.len()
+        );
+      }
+    }
+  }
+  Ok(true)
+}
+
 /// Copies a regular file from one path to another, creating any parent
 /// directories of the destination path as necessary.  Fails if the source path
 /// is a directory or doesn't exist.
+///
+/// The permissions and modification time of the source are preserved, see [`CopyOptions::default`].
 pub fn copy_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> crate::Result<()> {
+  copy_file_with(from, to, &CopyOptions::default())
+}
+
+/// Copies a regular file from one path to another using the given [`CopyOptions`],
+/// creating any parent directories of the destination path as necessary.  Fails if the source path
+/// is a directory or doesn't exist.
+pub fn copy_file_with(
+  from: impl AsRef<Path>,
+  to: impl AsRef<Path>,
+  options: &CopyOptions,
+) -> crate::Result<()> {
   let from = from.as_ref();
   let to = to.as_ref();
   if !from.exists() {
@@ -78,9 +497,26 @@ pub fn copy_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> crate::Result<
       "{from:?} is not a file"
     )));
   }
+  if is_dry_run() {
+    log::info!(
+      action = "Dry run";
+      "{}would copy {} -> {} ({} bytes)",
+      log_prefix(),
+      from.display(),
+      to.display(),
+      fs::metadata(from)?.len()
+    );
+    return Ok(());
+  }
   let dest_dir = to.parent().expect("No data in parent");
   fs::create_dir_all(dest_dir)?;
-  fs::copy(from, to)?;
+  if options.overwrite {
+    // never write through an existing symlink or into a directory
+    remove_existing(to)?;
+  }
+  clone_file(from, to, options.clone_mode)?;
+  let file_name = Path::new(from.file_name().unwrap_or_default());
+  apply_metadata(from, to, file_name, options)?;
   Ok(())
 }
 
@@ -88,8 +524,39 @@ pub fn copy_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> crate::Result<
 /// parent directories of the destination path as necessary.  Fails if the
 /// source path is not a directory or doesn't exist, or if the destination path
 /// already exists.
+///
+/// The permissions and modification times of the source entries are preserved, see [`CopyOptions::default`].
 #[allow(dead_code)]
 pub fn copy_dir(from: &Path, to: &Path) -> crate::Result<()> {
+  copy_dir_with(from, to, &CopyOptions::default())
+}
+
+/// Recursively copies a directory file from one path to another using the given [`CopyOptions`],
+/// creating any parent directories of the destination path as necessary.  Fails if the
+/// source path is not a directory or doesn't exist, or if the destination path
+/// already exists and [`CopyOptions::overwrite`] is not set.
+pub fn copy_dir_with(from: &Path, to: &Path, options: &CopyOptions) -> crate::Result<()> {
+  copy_dir_impl(from, to, options, None)
+}
+
+/// Same as [`copy_dir_with`], but calls `on_progress` after each copied file or symlink.
+///
+/// The source directory is walked once beforehand to compute [`CopyProgress::total_files`].
+pub fn copy_dir_with_progress(
+  from: &Path,
+  to: &Path,
+  options: &CopyOptions,
+  on_progress: &mut dyn FnMut(CopyProgress<'_>),
+) -> crate::Result<()> {
+  copy_dir_impl(from, to, options, Some(on_progress))
+}
+
+fn copy_dir_impl(
+  from: &Path,
+  to: &Path,
+  options: &CopyOptions,
+  mut on_progress: Option<&mut dyn FnMut(CopyProgress<'_>)>,
+) -> crate::Result<()> {
   if !from.exists() {
     return Err(crate::Error::GenericError(format!(
       "{from:?} does not exist"
@@ -100,27 +567,137 @@ pub fn copy_dir(from: &Path, to: &Path) -> crate::Result<()> {
       "{from:?} is not a Directory"
     )));
   }
-  if to.exists() {
+  if to.exists() && !options.overwrite {
     return Err(crate::Error::GenericError(format!("{to:?} already exists")));
   }
+  if is_dry_run() {
+    return log_dir_copy(from, to, options);
+  }
   let parent = to.parent().expect("No data in parent");
   fs::create_dir_all(parent)?;
-  for entry in walkdir::WalkDir::new(from) {
+  let total_files = if on_progress.is_some() {
+    let mut total = 0;
+    for entry in walk_dir(from, options) {
+      if !entry?.file_type().is_dir() {
+        total += 1;
+      }
+    }
+    total
+  } else {
+    0
+  };
+  let mut files_copied = 0;
+  let mut bytes_copied = 0;
+  // directory metadata is applied once all of their entries are copied
+  let mut dirs = Vec::new();
+  // regular files copied once every directory is created when using multiple threads
+  let mut files = Vec::new();
+  for entry in walk_dir(from, options) {
     let entry = entry?;
     debug_assert!(entry.path().starts_with(from));
     let rel_path = entry.path().strip_prefix(from)?;
     let dest_path = to.join(rel_path);
     if entry.file_type().is_symlink() {
       let target = fs::read_link(entry.path())?;
+      if options.overwrite {
+        remove_existing(&dest_path)?;
+      }
       if entry.path().is_dir() {
         symlink_dir(&target, &dest_path)?;
       } else {
         symlink_file(&target, &dest_path)?;
       }
+      files_copied += 1;
+    } else if entry.file_type().is_dir() {
+      if options.overwrite && dest_path.is_dir() && !dest_path.is_symlink() {
+        dirs.push((entry.path().to_path_buf(), dest_path));
+        continue;
+      }
+      if options.overwrite {
+        remove_existing(&dest_path)?;
+      }
+      fs::create_dir(&dest_path)?;
+      dirs.push((entry.path().to_path_buf(), dest_path));
+    } else if options.threads > 1 {
+      files.push((entry.path().to_path_buf(), dest_path));
+      continue;
+    } else {
+      bytes_copied += copy_regular_file(entry.path(), &dest_path, rel_path, options)?;
+      files_copied += 1;
+    }
+    if let (Some(on_progress), false) = (on_progress.as_mut(), entry.file_type().is_dir()) {
+      on_progress(CopyProgress {
+        files_copied,
+        total_files,
+        bytes_copied,
+        path: rel_path,
+      });
+    }
+  }
+  copy_files_parallel(
+    from,
+    &files,
+    options,
+    options.threads,
+    &mut |index, bytes| {
+      files_copied += 1;
+      bytes_copied += bytes;
+      if let Some(on_progress) = on_progress.as_mut() {
+        on_progress(CopyProgress {
+          files_copied,
+          total_files,
+          bytes_copied,
+          path: files[index].0.strip_prefix(from).unwrap_or(&files[index].0),
+        });
+      }
+    },
+  )?;
+  for (src, dest) in dirs.iter().rev() {
+    apply_metadata(src, dest, src.strip_prefix(from)?, options)?;
+  }
+  Ok(())
+}
+
+/// Walks the entries of `from`, skipping the ones matching [`CopyOptions::exclude`] and their contents.
+fn walk_dir<'a>(
+  from: &'a Path,
+  options: &'a CopyOptions,
+) -> impl Iterator<Item = walkdir::Result<walkdir::DirEntry>> + 'a {
+  walkdir::WalkDir::new(from)
+    .into_iter()
+    .filter_entry(move |entry| {
+      let rel_path = entry.path().strip_prefix(from).unwrap_or(entry.path());
+      !options
+        .exclude
+        .iter()
+        .any(|pattern| pattern.matches_path(rel_path))
+    })
+}
+
+/// Logs the entries [`copy_dir_with`] would create when copying `from` to `to`.
+fn log_dir_copy(from: &Path, to: &Path, options: &CopyOptions) -> crate::Result<()> {
+  for entry in walk_dir(from, options) {
+    let entry = entry?;
+    let dest_path = to.join(entry.path().strip_prefix(from)?);
+    if entry.file_type().is_symlink() {
+      log::info!(
+        action = "Dry run";
+        "{}would symlink {} -> {}",
+        log_prefix(),
+        dest_path.display(),
+        fs::read_link(entry.path())?.display()
+      );
     } else if entry.file_type().is_dir() {
-      fs::create_dir(dest_path)?;
+      log::info!(action = "Dry run"; "{}would create directory {}", log_prefix(), dest_path.display());
     } else {
-      fs::copy(entry.path(), dest_path)?;
+      log::info!(
+        action = "Dry run";
+        "{}would copy {} -> {} ({} bytes)",
+        log_prefix(),
+        entry.path().display(),
+        dest_path.display(),
+        entry.metadata()?.len()
+      );
     }
   }
   Ok(())
@@ -129,134 +706,589 @@ pub fn copy_dir(from: &Path, to: &Path) -> crate::Result<()> {
 /// Copies user-defined files specified in the configuration file to the package.
 ///
 /// The configuration object maps the path in the package to the path of the file on the filesystem,
-/// relative to the tauri.conf.json file.
+/// relative to the tauri.conf.json file. The source paths can be glob patterns, see [`expand_custom_files`].
 ///
 /// Expects a HashMap of PathBuf entries, representing destination and source paths,
 /// and also a path of a directory. The files will be stored with respect to this directory.
-#[cfg(any(
-  target_os = "linux",
-  target_os = "dragonfly",
-  target_os = "freebsd",
-  target_os = "netbsd",
-  target_os = "openbsd"
-))]
+#[allow(dead_code)]
 pub fn copy_custom_files(
   files_map: &std::collections::HashMap<std::path::PathBuf, std::path::PathBuf>,
   data_dir: &Path,
+  allow_empty_globs: bool,
 ) -> crate::Result<()> {
-  for (pkg_path, path) in files_map.iter() {
-    let pkg_path = if pkg_path.is_absolute() {
-      pkg_path.strip_prefix("/").unwrap()
-    } else {
-      pkg_path
-    };
+  let options = CopyOptions {
+    preserve_mode: true,
+    ..Default::default()
+  };
+  for (pkg_path, path) in expand_custom_files(files_map, allow_empty_globs)? {
+    let pkg_path = package_relative_path(&pkg_path);
     if path.is_file() {
-      copy_file(path, data_dir.join(pkg_path))?;
+      copy_file_with(&path, data_dir.join(pkg_path), &options)?;
     } else {
-      copy_dir(path, &data_dir.join(pkg_path))?;
+      copy_dir_with(&path, &data_dir.join(pkg_path), &options)?;
     }
   }
   Ok(())
 }
 
+/// Lists the files of a custom files map, see [`copy_custom_files`],
+/// returning the destination of each file relative to the package root and its source path.
+///
+/// Used by the installers that declare each file instead of copying them to a staging directory.
+/// Directories are walked so each of their files is returned.
+#[allow(dead_code)]
+pub fn custom_files(
+  files_map: &std::collections::HashMap<std::path::PathBuf, std::path::PathBuf>,
+  allow_empty_globs: bool,
+) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
+  let mut files = Vec::new();
+  for (pkg_path, path) in expand_custom_files(files_map, allow_empty_globs)? {
+    let pkg_path = package_relative_path(&pkg_path);
+    if path.is_file() {
+      files.push((pkg_path, path));
+      continue;
+    }
+    if !path.exists() {
+      return Err(crate::Error::GenericError(format!(
+        "{path:?} does not exist"
+      )));
+    }
+    for entry in walkdir::WalkDir::new(&path).sort_by_file_name() {
+      let entry = entry?;
+      if entry.file_type().is_file() {
+        let relative_path = entry.path().strip_prefix(&path).unwrap();
+        files.push((pkg_path.join(relative_path), entry.path().to_path_buf()));
+      }
+    }
+  }
+  Ok(files)
+}
+
+/// Makes a package path relative to the package root,
+/// dropping the root (`/`) and prefix (e.g. `C:\`) of absolute paths.
+fn package_relative_path(pkg_path: &Path) -> PathBuf {
+  pkg_path
+    .components()
+    .filter(|c| !matches!(c, Component::Prefix(_) | Component::RootDir))
+    .collect()
+}
+
+/// Expands the glob patterns in the source paths of a custom files map,
+/// returning the destination and source path of each entry to copy.
+///
+/// A pattern matching a single path is copied to its destination like a regular source.
+/// When a pattern matches multiple paths or is recursive (contains `**`), the destination is a directory
+/// receiving the matches at their path relative to the part of the pattern before the first wildcard,
+/// e.g. `assets/**/*.dat` copies `assets/a/b.dat` to `<destination>/a/b.dat`.
+/// Matched directories are copied with their whole contents, so the matches inside them are not repeated.
+///
+/// A pattern that does not match anything fails with [`crate::Error::GlobPathNotFound`]
+/// unless `allow_empty_globs` is set.
+pub fn expand_custom_files(
+  files_map: &std::collections::HashMap<std::path::PathBuf, std::path::PathBuf>,
+  allow_empty_globs: bool,
+) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
+  let mut files = Vec::new();
+  for (pkg_path, source) in files_map {
+    let pattern = source.to_string_lossy();
+    if !is_glob_pattern(&pattern) {
+      files.push((pkg_path.clone(), source.clone()));
+      continue;
+    }
+
+    let matches = glob::glob(&pattern)?.collect::<Result<Vec<_>, _>>()?;
+    match matches.as_slice() {
+      [] if allow_empty_globs => {
+        log::warn!("`{pattern}` did not match any file, skipping it");
+      }
+      [] => return Err(crate::Error::GlobPathNotFound(pattern.into_owned())),
+      [path] if !pattern.contains("**") => files.push((pkg_path.clone(), path.clone())),
+      matches => {
+        let base = glob_base(source);
+        for path in matches {
+          // a matched directory is copied with its contents, so its matched entries are skipped
+          if matches
+            .iter()
+            .any(|dir| dir != path && path.starts_with(dir))
+          {
+            continue;
+          }
+          let relative_path = strip_glob_base(path, &base).ok_or_else(|| {
+            crate::Error::GenericError(format!(
+              "glob match {} is not under the pattern base directory {}",
+              path.display(),
+              base.display()
+            ))
+          })?;
+          files.push((pkg_path.join(relative_path), path.clone()));
+        }
+      }
+    }
+  }
+  Ok(files)
+}
+
+/// The part of a glob pattern before the first component with a wildcard.
+fn glob_base(pattern: &Path) -> PathBuf {
+  pattern
+    .components()
+    .take_while(|c| !is_glob_pattern(&c.as_os_str().to_string_lossy()))
+    .collect()
+}
+
+/// Returns the path of a glob match relative to the pattern base,
+/// ignoring the `.` components the glob crate may add or drop.
+fn strip_glob_base(path: &Path, base: &Path) -> Option<PathBuf> {
+  let mut components = path.components().filter(|c| *c != Component::CurDir);
+  for base_component in base.components().filter(|c| *c != Component::CurDir) {
+    if components.next() != Some(base_component) {
+      return None;
+    }
+  }
+  Some(components.collect())
+}
+
+/// Whether the path contains glob wildcards.
+fn is_glob_pattern(path: &str) -> bool {
+  path.contains(['*', '?', '['])
+}
+
+/// Maximum number of bytes of the command output streams included in
+/// [`crate::Error::CommandFailed`] and [`crate::Error::CommandTimeout`].
+const OUTPUT_TAIL_LIMIT: usize = 8 * 1024;
+
+/// How long to wait for the output streams to close after killing a process that timed out.
+const KILLED_OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(200);
+
+/// A line of a command output stream, without its line ending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputLine {
+  /// A line written to stdout.
+  Stdout(String),
+  /// A line written to stderr.
+  Stderr(String),
+}
+
+impl OutputLine {
+  /// The text of the line.
+  pub fn text(&self) -> &str {
+    match self {
+      Self::Stdout(text) | Self::Stderr(text) => text,
+    }
+  }
+}
+
+thread_local! {
+  /// The package type being bundled on the current thread,
+  /// used to prefix the command logs when several targets are bundled concurrently.
+  static LOG_PREFIX: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with the command logs of the current thread prefixed with `[prefix]`.
+pub fn with_log_prefix<T>(prefix: &'static str, f: impl FnOnce() -> T) -> T {
+  let previous = LOG_PREFIX.with(|p| p.replace(Some(prefix)));
+  let result = f();
+  LOG_PREFIX.with(|p| p.set(previous));
+  result
+}
+
+/// The `[prefix] ` of the logs of the current thread, see [`with_log_prefix`].
+pub fn log_prefix() -> String {
+  LOG_PREFIX
+    .with(Cell::get)
+    .map(|prefix| format!("[{prefix}] "))
+    .unwrap_or_default()
+}
+
 pub trait CommandExt {
   // The `pipe` function sets the stdout and stderr to properly
   // show the command output in the Node.js wrapper.
+  // Both are written to stderr so stdout is left to the caller, e.g. the CLI JSON progress events.
   fn piped(&mut self) -> std::io::Result<ExitStatus>;
   fn output_ok(&mut self) -> crate::Result<Output>;
+  /// Same as [`CommandExt::output_ok`], but kills the process and returns
+  /// [`crate::Error::CommandTimeout`] with the output captured so far
+  /// if it does not finish within the given duration.
+  fn output_ok_with_timeout(&mut self, timeout: Duration) -> crate::Result<Output>;
+  /// Same as [`CommandExt::output_ok`], but also calls `on_line` with each line of the command output
+  /// as soon as it is read, e.g. to forward it to a custom UI.
+  ///
+  /// The callback runs on the calling thread, in the order the lines were read from both streams.
+  fn run_with_line_callback(&mut self, on_line: impl FnMut(OutputLine)) -> crate::Result<Output>;
+  /// Same as [`CommandExt::output_ok`], but runs the command again up to `retries` times
+  /// while it fails with an error matching `should_retry`, e.g. a transient network error.
+  ///
+  /// The delay before the first retry is `backoff` and doubles before each following one.
+  /// When the command was retried and still failed, the error of the last attempt is wrapped in
+  /// [`crate::Error::CommandRetriesFailed`] with the number of attempts.
+  fn output_ok_retry(
+    &mut self,
+    retries: u32,
+    backoff: Duration,
+    should_retry: impl Fn(&crate::Error) -> bool,
+  ) -> crate::Result<Output>;
 }
 
 impl CommandExt for Command {
   fn piped(&mut self) -> std::io::Result<ExitStatus> {
-    self.stdout(os_pipe::dup_stdout()?);
+    self.stdout(os_pipe::dup_stderr()?);
     self.stderr(os_pipe::dup_stderr()?);
     let program = self.get_program().to_string_lossy().into_owned();
-    log::debug!(action = "Running"; "Command `{} {}`", program, self.get_args().map(|arg| arg.to_string_lossy()).fold(String::new(), |acc, arg| format!("{acc} {arg}")));
+    log::debug!(action = "Running"; "{}Command `{} {}`", log_prefix(), program, self.get_args().map(|arg| arg.to_string_lossy()).fold(String::new(), |acc, arg| format!("{acc} {arg}")));
 
     self.status().map_err(Into::into)
   }
 
   fn output_ok(&mut self) -> crate::Result<Output> {
-    let program = self.get_program().to_string_lossy().into_owned();
-    log::debug!(action = "Running"; "Command `{} {}`", program, self.get_args().map(|arg| arg.to_string_lossy()).fold(String::new(), |acc, arg| format!("{acc} {arg}")));
+    run_command(self, None, None)
+  }
 
-    self.stdout(Stdio::piped());
-    self.stderr(Stdio::piped());
+  fn output_ok_with_timeout(&mut self, timeout: Duration) -> crate::Result<Output> {
+    run_command(self, Some(timeout), None)
+  }
 
-    let mut child = self.spawn()?;
+  fn run_with_line_callback(
+    &mut self,
+    mut on_line: impl FnMut(OutputLine),
+  ) -> crate::Result<Output> {
+    run_command(self, None, Some(&mut on_line))
+  }
 
-    let mut stdout = child.stdout.take().map(BufReader::new).unwrap();
-    let stdout_lines = Arc::new(Mutex::new(Vec::new()));
-    let stdout_lines_ = stdout_lines.clone();
-    std::thread::spawn(move || {
-      let mut line = String::new();
-      let mut lines = stdout_lines_.lock().unwrap();
-      loop {
-        line.clear();
-        match stdout.read_line(&mut line) {
-          Ok(0) => break,
-          Ok(_) => {
-            log::debug!(action = "stdout"; "{}", line.trim_end());
-            lines.extend(line.as_bytes().to_vec());
+  fn output_ok_retry(
+    &mut self,
+    retries: u32,
+    backoff: Duration,
+    should_retry: impl Fn(&crate::Error) -> bool,
+  ) -> crate::Result<Output> {
+    let mut delay = backoff;
+    let mut attempts = 1;
+    loop {
+      let error = match run_command(self, None, None) {
+        Ok(output) => return Ok(output),
+        Err(error) => error,
+      };
+      if attempts > retries || !should_retry(&error) {
+        return Err(if attempts == 1 {
+          error
+        } else {
+          crate::Error::CommandRetriesFailed {
+            attempts,
+            source: Box::new(error),
           }
-          Err(_) => (),
+        });
+      }
+      log::warn!(
+        "{}`{}` failed, retrying in {}s ({attempts}/{retries})",
+        log_prefix(),
+        self.get_program().to_string_lossy(),
+        delay.as_secs_f32()
+      );
+      thread::sleep(delay);
+      delay = delay.saturating_mul(2);
+      attempts += 1;
+    }
+  }
+}
+
+/// Flags whose value is a secret, hidden from the logged command line and the command errors.
+const SECRET_FLAGS: &[&str] = &["--password"];
+
+/// Replaces the value of the [`SECRET_FLAGS`] with `***`.
+fn redact_args(args: impl Iterator<Item = String>) -> Vec<String> {
+  let mut redact_next = false;
+  args
+    .map(|arg| {
+      if std::mem::take(&mut redact_next) {
+        return "***".into();
+      }
+      match arg.split_once('=') {
+        Some((flag, _)) if SECRET_FLAGS.contains(&flag) => format!("{flag}=***"),
+        _ => {
+          redact_next = SECRET_FLAGS.contains(&arg.as_str());
+          arg
         }
       }
-    });
+    })
+    .collect()
+}
+
+fn run_command(
+  cmd: &mut Command,
+  timeout: Option<Duration>,
+  mut on_line: Option<&mut dyn FnMut(OutputLine)>,
+) -> crate::Result<Output> {
+  let program = cmd.get_program().to_string_lossy().into_owned();
+  let args = redact_args(cmd.get_args().map(|arg| arg.to_string_lossy().into_owned()));
+  log::debug!(action = "Running"; "{}Command `{} {}`", log_prefix(), program, args.join(" "));
+
+  cmd.stdout(Stdio::piped());
+  cmd.stderr(Stdio::piped());
+
+  let mut child = cmd.spawn()?;
+
+  // the lines are only sent to the calling thread when there's a callback to forward them to
+  let (lines_tx, lines_rx) = mpsc::channel();
+  let lines_tx = on_line.is_some().then_some(lines_tx);
+  let stdout = OutputReader::spawn(
+    child.stdout.take().unwrap(),
+    "stdout",
+    OutputLine::Stdout,
+    lines_tx.clone(),
+  );
+  let stderr = OutputReader::spawn(
+    child.stderr.take().unwrap(),
+    "stderr",
+    OutputLine::Stderr,
+    lines_tx,
+  );
+
+  let status = match timeout {
+    Some(timeout) => {
+      let start = Instant::now();
+      loop {
+        if let Some(on_line) = on_line.as_mut() {
+          lines_rx.try_iter().for_each(&mut *on_line);
+        }
+        if let Some(status) = child.try_wait()? {
+          break status;
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+          let _ = child.kill();
+          let _ = child.wait();
+          // the streams stay open if the process spawned children inheriting them,
+          // so the reader threads are left to finish on their own in that case
+          let deadline = Instant::now() + KILLED_OUTPUT_GRACE_PERIOD;
+          return Err(crate::Error::CommandTimeout {
+            program,
+            args,
+            timeout,
+            stdout: output_tail(&stdout.output_until(deadline)),
+            stderr: output_tail(&stderr.output_until(deadline)),
+          });
+        }
+        std::thread::sleep((timeout - elapsed).min(Duration::from_millis(50)));
+      }
+    }
+    None => {
+      if let Some(on_line) = on_line.as_mut() {
+        // ends once both streams are closed
+        lines_rx.iter().for_each(&mut *on_line);
+      }
+      child.wait()?
+    }
+  };
+
+  if let Some(on_line) = on_line {
+    lines_rx.iter().for_each(on_line);
+  }
+
+  let output = Output {
+    status,
+    stdout: stdout.join(),
+    stderr: stderr.join(),
+  };
+
+  if output.status.success() {
+    Ok(output)
+  } else {
+    #[cfg(unix)]
+    let signal = std::os::unix::process::ExitStatusExt::signal(&output.status);
+    #[cfg(not(unix))]
+    let signal = None;
+
+    Err(crate::Error::CommandFailed {
+      program,
+      args,
+      exit_code: output.status.code(),
+      signal,
+      stdout: output_tail(&output.stdout),
+      stderr: output_tail(&output.stderr),
+    })
+  }
+}
+
+/// A command output stream read line by line on a separate thread, logging each line
+/// and sending it to `lines` if set.
+struct OutputReader {
+  handle: JoinHandle<()>,
+  output: Arc<Mutex<Vec<u8>>>,
+}
 
-    let mut stderr = child.stderr.take().map(BufReader::new).unwrap();
-    let stderr_lines = Arc::new(Mutex::new(Vec::new()));
-    let stderr_lines_ = stderr_lines.clone();
-    std::thread::spawn(move || {
-      let mut line = String::new();
-      let mut lines = stderr_lines_.lock().unwrap();
+impl OutputReader {
+  fn spawn<R: Read + Send + 'static>(
+    reader: R,
+    name: &'static str,
+    output_line: fn(String) -> OutputLine,
+    lines: Option<mpsc::Sender<OutputLine>>,
+  ) -> Self {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let thread_output = output.clone();
+    // the reader thread does not inherit the prefix of the thread running the command
+    let prefix = log_prefix();
+    let handle = std::thread::spawn(move || {
+      let mut reader = BufReader::new(reader);
+      let mut line = Vec::new();
       loop {
         line.clear();
-        match stderr.read_line(&mut line) {
-          Ok(0) => break,
+        match reader.read_until(b'\n', &mut line) {
+          Ok(0) | Err(_) => break,
           Ok(_) => {
-            log::debug!(action = "stderr"; "{}", line.trim_end());
-            lines.extend(line.as_bytes().to_vec());
+            let text = String::from_utf8_lossy(&line);
+            let text = text.trim_end();
+            log::debug!(action = name; "{prefix}{text}");
+            if let Some(lines) = &lines {
+              let _ = lines.send(output_line(text.to_string()));
+            }
+            thread_output.lock().unwrap().extend_from_slice(&line);
           }
-          Err(_) => (),
         }
       }
     });
+    Self { handle, output }
+  }
 
-    let status = child.wait()?;
-    let output = Output {
-      status,
-      stdout: std::mem::take(&mut *stdout_lines.lock().unwrap()),
-      stderr: std::mem::take(&mut *stderr_lines.lock().unwrap()),
-    };
+  /// Waits for the stream to be closed and returns the whole output.
+  fn join(self) -> Vec<u8> {
+    let _ = self.handle.join();
+    take_output(&self.output)
+  }
 
-    if output.status.success() {
-      Ok(output)
-    } else {
-      Err(crate::Error::GenericError(format!(
-        "failed to run {program}"
-      )))
+  /// Waits for the stream to be closed until `deadline`, then returns the output read so far.
+  fn output_until(self, deadline: Instant) -> Vec<u8> {
+    while !self.handle.is_finished() && Instant::now() < deadline {
+      std::thread::sleep(Duration::from_millis(10));
     }
+    take_output(&self.output)
   }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::{create_file, is_retina};
-  use std::{io::Write, path::PathBuf};
-  use tauri_utils::resources::resource_relpath;
+fn take_output(output: &Mutex<Vec<u8>>) -> Vec<u8> {
+  output
+    .lock()
+    .map(|mut output| std::mem::take(&mut *output))
+    .unwrap_or_default()
+}
 
-  #[test]
-  fn create_file_with_parent_dirs() {
-    let tmp = tempfile::tempdir().expect("Unable to create temp dir");
-    assert!(!tmp.path().join("parent").exists());
-    {
-      let mut file =
-        create_file(&tmp.path().join("parent/file.txt")).expect("Failed to create file");
-      writeln!(file, "Hello, world!").expect("unable to write file");
-    }
-    assert!(tmp.path().join("parent").is_dir());
-    assert!(tmp.path().join("parent/file.txt").is_file());
+/// Returns the last [`OUTPUT_TAIL_LIMIT`] bytes of a command output stream as a lossy UTF-8 string.
+fn output_tail(output: &[u8]) -> String {
+  let start = output.len().saturating_sub(OUTPUT_TAIL_LIMIT);
+  let tail = String::from_utf8_lossy(&output[start..]);
+  let tail = tail.trim_end();
+  if start > 0 {
+    format!("...{tail}")
+  } else {
+    tail.to_string()
+  }
+}
+
+/// Returns the `SOURCE_DATE_EPOCH` timestamp, used to make the bundles reproducible.
+///
+/// See <https://reproducible-builds.org/specs/source-date-epoch/>.
+pub fn source_date_epoch() -> Option<u64> {
+  std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|epoch| epoch.trim().parse().ok())
+}
+
+/// Clamps the given timestamp to [`Settings::source_date_epoch`], if set.
+///
+/// The timestamp is pinned to it instead for [reproducible](Settings::reproducible) bundles.
+#[allow(dead_code)]
+pub fn clamp_timestamp(settings: &Settings, timestamp: u64) -> u64 {
+  match settings.source_date_epoch() {
+    Some(epoch) if settings.reproducible() => epoch,
+    Some(epoch) => timestamp.min(epoch),
+    None => timestamp,
+  }
+}
+
+/// Clamps the modification time of `path` and all of its contents to [`Settings::source_date_epoch`].
+///
+/// The modification times are pinned to it instead for [reproducible](Settings::reproducible) bundles.
+/// Does nothing if there is no such timestamp.
+#[allow(dead_code)]
+pub fn clamp_mtimes(settings: &Settings, path: &Path) -> crate::Result<()> {
+  if let Some(epoch) = settings.source_date_epoch() {
+    clamp_mtimes_to(path, epoch, settings.reproducible())?;
+  }
+  Ok(())
+}
+
+/// Clamps the modification times to `epoch`, or sets them all to it when `pin` is true.
+fn clamp_mtimes_to(path: &Path, epoch: u64, pin: bool) -> crate::Result<()> {
+  let epoch = FileTime::from_unix_time(epoch as i64, 0);
+  // the contents are updated first since it could otherwise change the mtime of their directory
+  for entry in walkdir::WalkDir::new(path).contents_first(true) {
+    let entry = entry?;
+    let metadata = entry.path().symlink_metadata()?;
+    let mtime = FileTime::from_last_modification_time(&metadata);
+    if mtime > epoch || (pin && mtime != epoch) {
+      filetime::set_symlink_file_times(entry.path(), epoch, epoch)?;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{create_file, icon_scale, is_retina};
+  use std::{
+    io::Write,
+    path::{Path, PathBuf},
+  };
+  use tauri_utils::resources::resource_relpath;
+
+  #[test]
+  fn create_file_with_parent_dirs() {
+    let tmp = tempfile::tempdir().expect("Unable to create temp dir");
+    assert!(!tmp.path().join("parent").exists());
+    {
+      let mut file =
+        create_file(&tmp.path().join("parent/file.txt")).expect("Failed to create file");
+      writeln!(file, "Hello, world!").expect("unable to write file");
+    }
+    assert!(tmp.path().join("parent").is_dir());
+    assert!(tmp.path().join("parent/file.txt").is_file());
+  }
+
+  #[test]
+  fn records_staged_size() {
+    use super::{
+      dir_size, record_staged_bytes, record_staged_size, with_dry_run, with_staged_size,
+    };
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let dir = tmp.path().join("package");
+    std::fs::create_dir_all(dir.join("usr/bin")).unwrap();
+    std::fs::write(dir.join("usr/bin/app"), [0; 20]).unwrap();
+    std::fs::write(dir.join("control"), [0; 5]).unwrap();
+    let file = tmp.path().join("app.exe");
+    std::fs::write(&file, [0; 7]).unwrap();
+
+    assert_eq!(dir_size(&dir).unwrap(), 25);
+    assert_eq!(dir_size(&file).unwrap(), 7);
+
+    let (_, size) = with_staged_size(|| {
+      record_staged_size([&dir, &file]).unwrap();
+      record_staged_bytes(100);
+    });
+    assert_eq!(size, Some(132));
+    assert_eq!(with_staged_size(|| ()).1, None);
+    let (_, size) = with_staged_size(|| {
+      with_dry_run(true, || record_staged_size([&dir]).unwrap());
+    });
+    assert_eq!(size, None);
+  }
+
+  #[test]
+  fn formats_size() {
+    use super::format_size;
+
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
   }
 
   #[cfg(not(windows))]
@@ -310,10 +1342,524 @@ mod tests {
     );
   }
 
+  #[test]
+  fn copy_custom_files_with_globs() {
+    use super::copy_custom_files;
+    use std::collections::HashMap;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let src = tmp.path().join("src");
+    for path in [
+      "plugins/a.so",
+      "plugins/b.so",
+      "plugins/readme.txt",
+      "assets/x/1.dat",
+      "assets/y/z/2.dat",
+      "assets/y/ignored.txt",
+      "single/only.bin",
+    ] {
+      let mut file = create_file(&src.join(path)).expect("Unable to create file");
+      write!(file, "{path}").expect("Unable to write to file");
+    }
+
+    let files_map = HashMap::from([
+      (PathBuf::from("usr/lib/plugins"), src.join("plugins/*.so")),
+      (
+        PathBuf::from("/usr/share/data"),
+        src.join("assets/**/*.dat"),
+      ),
+      (PathBuf::from("usr/lib/only.bin"), src.join("single/*.bin")),
+    ]);
+    let data_dir = tmp.path().join("data");
+    copy_custom_files(&files_map, &data_dir, false).expect("Failed to copy custom files");
+
+    for (path, contents) in [
+      ("usr/lib/plugins/a.so", "plugins/a.so"),
+      ("usr/lib/plugins/b.so", "plugins/b.so"),
+      ("usr/share/data/x/1.dat", "assets/x/1.dat"),
+      ("usr/share/data/y/z/2.dat", "assets/y/z/2.dat"),
+      ("usr/lib/only.bin", "single/only.bin"),
+    ] {
+      assert_eq!(
+        std::fs::read_to_string(data_dir.join(path)).expect("Failed to read file"),
+        contents
+      );
+    }
+    assert!(!data_dir.join("usr/lib/plugins/readme.txt").exists());
+    assert!(!data_dir.join("usr/share/data/y/ignored.txt").exists());
+
+    let empty_map = HashMap::from([(PathBuf::from("usr/lib"), src.join("missing/*.so"))]);
+    assert!(matches!(
+      copy_custom_files(&empty_map, &data_dir, false),
+      Err(crate::Error::GlobPathNotFound(_))
+    ));
+    copy_custom_files(&empty_map, &data_dir, true).expect("Failed to allow empty glob");
+  }
+
+  #[test]
+  fn glob_matching_directories() {
+    use super::{custom_files, strip_glob_base};
+    use std::collections::HashMap;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let src = tmp.path().join("src");
+    for path in [
+      "assets/a.txt",
+      "assets/nested/b.txt",
+      "assets/nested/deep/c.txt",
+    ] {
+      let mut file = create_file(&src.join(path)).expect("Unable to create file");
+      write!(file, "{path}").expect("Unable to write to file");
+    }
+
+    let files_map = HashMap::from([(PathBuf::from("share"), src.join("assets/**/*"))]);
+    let mut files = custom_files(&files_map, false).expect("Failed to list custom files");
+    files.sort();
+    assert_eq!(
+      files,
+      vec![
+        (
+          PathBuf::from("share").join("a.txt"),
+          src.join("assets/a.txt")
+        ),
+        (
+          PathBuf::from("share").join("nested").join("b.txt"),
+          src.join("assets").join("nested").join("b.txt")
+        ),
+        (
+          PathBuf::from("share")
+            .join("nested")
+            .join("deep")
+            .join("c.txt"),
+          src.join("assets").join("nested").join("deep").join("c.txt")
+        ),
+      ]
+    );
+
+    assert_eq!(
+      strip_glob_base(Path::new("assets/x/1.dat"), Path::new("./assets")),
+      Some(PathBuf::from("x/1.dat"))
+    );
+    assert_eq!(
+      strip_glob_base(Path::new("./assets/x/1.dat"), Path::new("assets")),
+      Some(PathBuf::from("x/1.dat"))
+    );
+    assert_eq!(
+      strip_glob_base(Path::new("other/1.dat"), Path::new("assets")),
+      None
+    );
+  }
+
+  #[test]
+  fn custom_files_lists_directory_files() {
+    use super::{custom_files, package_relative_path};
+    use std::collections::HashMap;
+
+    assert_eq!(
+      package_relative_path(Path::new("/usr/lib/a.so")),
+      PathBuf::from("usr/lib/a.so")
+    );
+    assert_eq!(
+      package_relative_path(Path::new("plugins/a.dll")),
+      PathBuf::from("plugins/a.dll")
+    );
+    #[cfg(windows)]
+    assert_eq!(
+      package_relative_path(Path::new("C:\\plugins\\a.dll")),
+      PathBuf::from("plugins\\a.dll")
+    );
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let src = tmp.path().join("src");
+    for path in ["data/a.txt", "data/nested/b.txt", "readme.txt"] {
+      let mut file = create_file(&src.join(path)).expect("Unable to create file");
+      write!(file, "{path}").expect("Unable to write to file");
+    }
+
+    let files_map = HashMap::from([
+      (PathBuf::from("/share"), src.join("data")),
+      (PathBuf::from("docs/readme.txt"), src.join("readme.txt")),
+    ]);
+    let mut files = custom_files(&files_map, false).expect("Failed to list custom files");
+    files.sort();
+    assert_eq!(
+      files,
+      vec![
+        (PathBuf::from("docs/readme.txt"), src.join("readme.txt")),
+        (PathBuf::from("share").join("a.txt"), src.join("data/a.txt")),
+        (
+          PathBuf::from("share").join("nested").join("b.txt"),
+          src.join("data").join("nested").join("b.txt")
+        ),
+      ]
+    );
+
+    let missing_map = HashMap::from([(PathBuf::from("share"), src.join("missing"))]);
+    assert!(custom_files(&missing_map, false).is_err());
+  }
+
+  #[cfg(not(windows))]
+  #[test]
+  fn copy_dir_overwrite() {
+    use super::{copy_dir, copy_dir_with, CopyOptions};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    let copy = tmp.path().join("copy");
+    {
+      let mut file = create_file(&orig.join("sub/file.txt")).expect("Unable to create file");
+      writeln!(file, "new").expect("Unable to write to file");
+    }
+    super::symlink_file(&PathBuf::from("sub/file.txt"), &orig.join("link"))
+      .expect("Failed to create symlink");
+
+    // existing destination with a stale file, a stale symlink and an unrelated file
+    {
+      let mut file = create_file(&copy.join("sub/file.txt")).expect("Unable to create file");
+      writeln!(file, "old").expect("Unable to write to file");
+      let mut file = create_file(&copy.join("untouched.txt")).expect("Unable to create file");
+      writeln!(file, "untouched").expect("Unable to write to file");
+    }
+    super::symlink_file(&PathBuf::from("untouched.txt"), &copy.join("link"))
+      .expect("Failed to create symlink");
+
+    assert!(copy_dir(&orig, &copy).is_err());
+
+    copy_dir_with(
+      &orig,
+      &copy,
+      &CopyOptions {
+        overwrite: true,
+        ..Default::default()
+      },
+    )
+    .expect("Failed to copy dir");
+    assert_eq!(
+      std::fs::read(copy.join("sub/file.txt"))
+        .expect("Failed to read file")
+        .as_slice(),
+      b"new\n"
+    );
+    assert_eq!(
+      std::fs::read_link(copy.join("link")).expect("Failed to read from symlink"),
+      PathBuf::from("sub/file.txt")
+    );
+    assert_eq!(
+      std::fs::read(copy.join("untouched.txt"))
+        .expect("Failed to read file")
+        .as_slice(),
+      b"untouched\n"
+    );
+  }
+
+  #[test]
+  fn copy_dir_with_exclude() {
+    use super::{copy_dir_with, CopyOptions};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    for path in [
+      "index.js",
+      "index.js.map",
+      ".DS_Store",
+      "lib/util.js",
+      "lib/node_modules/dep/index.js",
+      "node_modules/dep/index.js",
+    ] {
+      let mut file = create_file(&orig.join(path)).expect("Unable to create file");
+      write!(file, "{path}").expect("Unable to write to file");
+    }
+
+    let copy = tmp.path().join("copy");
+    copy_dir_with(
+      &orig,
+      &copy,
+      &CopyOptions {
+        exclude: ["**/node_modules", "**/*.map", ".DS_Store"]
+          .iter()
+          .map(|pattern| glob::Pattern::new(pattern).expect("invalid pattern"))
+          .collect(),
+        ..Default::default()
+      },
+    )
+    .expect("Failed to copy dir");
+
+    assert!(copy.join("index.js").is_file());
+    assert!(copy.join("lib/util.js").is_file());
+    assert!(!copy.join("index.js.map").exists());
+    assert!(!copy.join(".DS_Store").exists());
+    assert!(!copy.join("node_modules").exists());
+    assert!(!copy.join("lib/node_modules").exists());
+  }
+
+  #[test]
+  fn copy_file_clone_modes() {
+    use super::{copy_file_with, CloneMode, CopyOptions};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig.txt");
+    std::fs::write(&orig, "contents").expect("Unable to write file");
+
+    for (i, clone_mode) in [
+      CloneMode::Auto,
+      CloneMode::Reflink,
+      CloneMode::Hardlink,
+      CloneMode::Copy,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      let options = CopyOptions {
+        clone_mode,
+        ..Default::default()
+      };
+      let overwrite_options = CopyOptions {
+        overwrite: true,
+        ..options.clone()
+      };
+      let copy = tmp.path().join(format!("copy/{i}.txt"));
+      copy_file_with(&orig, &copy, &options).expect("Failed to copy file");
+      assert_eq!(std::fs::read_to_string(&copy).unwrap(), "contents");
+
+      // copying again over the previous copy keeps the source intact
+      copy_file_with(&orig, &copy, &overwrite_options).expect("Failed to copy file");
+      assert_eq!(std::fs::read_to_string(&orig).unwrap(), "contents");
+      assert_eq!(std::fs::read_to_string(&copy).unwrap(), "contents");
+
+      // an existing destination is only replaced when overwriting, or by a plain copy
+      std::fs::remove_file(&copy).expect("Unable to remove file");
+      std::fs::write(&copy, "existing").expect("Unable to write file");
+      let result = copy_file_with(&orig, &copy, &options);
+      if clone_mode == CloneMode::Copy {
+        result.expect("Failed to copy file");
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "contents");
+        std::fs::write(&copy, "existing").expect("Unable to write file");
+      } else {
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "existing");
+      }
+      copy_file_with(&orig, &copy, &overwrite_options).expect("Failed to copy file");
+      assert_eq!(std::fs::read_to_string(&copy).unwrap(), "contents");
+    }
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::MetadataExt;
+
+      let copy = tmp.path().join("hardlink.txt");
+      copy_file_with(
+        &orig,
+        &copy,
+        &CopyOptions {
+          clone_mode: CloneMode::Hardlink,
+          ..Default::default()
+        },
+      )
+      .expect("Failed to copy file");
+      assert_eq!(
+        std::fs::metadata(&copy).unwrap().ino(),
+        std::fs::metadata(&orig).unwrap().ino()
+      );
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copy_preserves_mode_and_mtime() {
+    use super::{copy_dir_with, copy_file_with, CopyOptions};
+    use filetime::FileTime;
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let script = tmp.path().join("orig/bin/helper.sh");
+    {
+      let mut file = create_file(&script).expect("Unable to create file");
+      writeln!(file, "#!/bin/sh").expect("Unable to write to file");
+    }
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+      .expect("Failed to set permissions");
+    let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(&script, mtime).expect("Failed to set mtime");
+
+    let options = CopyOptions {
+      preserve_mode: true,
+      preserve_timestamps: true,
+      ..Default::default()
+    };
+
+    let copied_file = tmp.path().join("file/helper.sh");
+    copy_file_with(&script, &copied_file, &options).expect("Failed to copy file");
+    copy_dir_with(&tmp.path().join("orig"), &tmp.path().join("dir"), &options)
+      .expect("Failed to copy dir");
+
+    for copied in [copied_file, tmp.path().join("dir/bin/helper.sh")] {
+      let metadata = std::fs::metadata(&copied).expect("Failed to read metadata");
+      assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+      assert_eq!(FileTime::from_last_modification_time(&metadata), mtime);
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copy_preserves_executable_bit_by_default() {
+    use super::{copy_dir, copy_file, copy_file_with, CloneMode, CopyOptions};
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let script = tmp.path().join("resources/bin/helper.sh");
+    {
+      let mut file = create_file(&script).expect("Unable to create file");
+      writeln!(file, "#!/bin/sh").expect("Unable to write to file");
+    }
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+      .expect("Failed to set permissions");
+
+    copy_file(&script, tmp.path().join("file/helper.sh")).expect("Failed to copy file");
+    // resources are reflinked, which creates the file with the default mode
+    copy_file_with(
+      &script,
+      tmp.path().join("reflink/helper.sh"),
+      &CopyOptions {
+        clone_mode: CloneMode::Reflink,
+        ..Default::default()
+      },
+    )
+    .expect("Failed to copy file");
+    copy_dir(&tmp.path().join("resources"), &tmp.path().join("dir")).expect("Failed to copy dir");
+
+    for copied in ["file/helper.sh", "reflink/helper.sh", "dir/bin/helper.sh"] {
+      let metadata = std::fs::metadata(tmp.path().join(copied)).expect("Failed to read metadata");
+      assert_eq!(metadata.permissions().mode() & 0o777, 0o755, "{copied}");
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copy_read_only_entries_stay_writable() {
+    use super::copy_dir;
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let src = tmp.path().join("src");
+    let file = src.join("read-only/lib.so");
+    create_file(&file).expect("Unable to create file");
+    std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o444))
+      .expect("Failed to set permissions");
+    std::fs::set_permissions(
+      file.parent().unwrap(),
+      std::fs::Permissions::from_mode(0o555),
+    )
+    .expect("Failed to set permissions");
+
+    let dest = tmp.path().join("dest");
+    let result = copy_dir(&src, &dest);
+    // restore the permissions so the temp dir can be removed
+    std::fs::set_permissions(
+      file.parent().unwrap(),
+      std::fs::Permissions::from_mode(0o755),
+    )
+    .expect("Failed to set permissions");
+    result.expect("Failed to copy dir");
+
+    let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(&dest.join("read-only/lib.so")), 0o644);
+    assert_eq!(mode(&dest.join("read-only")), 0o755);
+    // later bundling steps can modify the copy
+    std::fs::write(dest.join("read-only/lib.so"), "stripped").expect("Failed to write copy");
+    create_file(&dest.join("read-only/signature")).expect("Failed to create file");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copy_forces_modes() {
+    use super::{copy_dir_with, copy_file_with, CopyOptions};
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let src = tmp.path().join("src");
+    for file in ["bin/helper.sh", "bin/README.md", "share/data.txt"] {
+      create_file(&src.join(file)).expect("Unable to create file");
+      std::fs::set_permissions(src.join(file), std::fs::Permissions::from_mode(0o644))
+        .expect("Failed to set permissions");
+    }
+
+    let options = CopyOptions {
+      modes: vec![
+        (glob::Pattern::new("**/*.sh").unwrap(), 0o755),
+        (glob::Pattern::new("share").unwrap(), 0o750),
+        (glob::Pattern::new("**/*.md").unwrap(), 0o600),
+      ],
+      ..Default::default()
+    };
+    copy_dir_with(&src, &tmp.path().join("dir"), &options).expect("Failed to copy dir");
+    copy_file_with(
+      src.join("bin/helper.sh"),
+      tmp.path().join("file/helper.sh"),
+      &options,
+    )
+    .expect("Failed to copy file");
+
+    let mode = |path: &str| {
+      std::fs::metadata(tmp.path().join(path))
+        .unwrap()
+        .permissions()
+        .mode()
+        & 0o777
+    };
+    assert_eq!(mode("dir/bin/helper.sh"), 0o755);
+    assert_eq!(mode("dir/bin/README.md"), 0o600);
+    assert_eq!(mode("dir/share"), 0o750);
+    assert_eq!(mode("file/helper.sh"), 0o755);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn clamp_mtimes_to_epoch() {
+    use super::clamp_mtimes_to;
+    use filetime::FileTime;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let new_file = tmp.path().join("dir/new.txt");
+    let old_file = tmp.path().join("dir/old.txt");
+    create_file(&new_file).expect("Unable to create file");
+    create_file(&old_file).expect("Unable to create file");
+    std::os::unix::fs::symlink("new.txt", tmp.path().join("dir/link")).expect("Failed to symlink");
+    let old = FileTime::from_unix_time(500_000_000, 0);
+    filetime::set_file_mtime(&old_file, old).expect("Failed to set mtime");
+
+    clamp_mtimes_to(tmp.path(), 1_000_000_000, false).expect("Failed to clamp mtimes");
+
+    let epoch = FileTime::from_unix_time(1_000_000_000, 0);
+    let mtime = |path: &std::path::Path| {
+      FileTime::from_last_modification_time(&path.symlink_metadata().unwrap())
+    };
+    assert_eq!(mtime(&new_file), epoch);
+    assert_eq!(mtime(&tmp.path().join("dir/link")), epoch);
+    assert_eq!(mtime(&tmp.path().join("dir")), epoch);
+    assert_eq!(mtime(tmp.path()), epoch);
+    // older entries are left untouched
+    assert_eq!(mtime(&old_file), old);
+
+    clamp_mtimes_to(tmp.path(), 1_000_000_000, true).expect("Failed to pin mtimes");
+    assert_eq!(mtime(&old_file), epoch);
+  }
+
   #[test]
   fn retina_icon_paths() {
     assert!(!is_retina("data/icons/512x512.png"));
     assert!(is_retina("data/icons/512x512@2x.png"));
+    assert!(is_retina("data/icons/icon@3x.png"));
+    assert!(!is_retina("data/icons/foo@2xbar.png"));
+  }
+
+  #[test]
+  fn icon_path_scales() {
+    assert_eq!(icon_scale("data/icons/512x512.png"), 1);
+    assert_eq!(icon_scale("data/icons/512x512@2x.png"), 2);
+    assert_eq!(icon_scale("data/icons/icon@3x.png"), 3);
+    assert_eq!(icon_scale("data/icons/foo@2xbar.png"), 1);
+    assert_eq!(icon_scale("data/icons/icon@4x.png"), 1);
+    assert_eq!(icon_scale("data/icons@2x/icon.png"), 1);
   }
 
   #[test]
@@ -331,4 +1877,470 @@ mod tests {
       PathBuf::from("_root_/home/ferris/crab.png")
     );
   }
+
+  #[test]
+  fn resource_collisions() {
+    use tauri_utils::{
+      config::ResourceCollision,
+      resources::{resolve_collisions, Resource},
+    };
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let up = tmp.path().join("assets/a.png");
+    let literal = tmp.path().join("app/_up_/assets/a.png");
+    let copy = tmp.path().join("app/copy/a.png");
+    for (path, content) in [(&up, "up"), (&literal, "literal"), (&copy, "up")] {
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, content).unwrap();
+    }
+    // `../assets/a.png` and `_up_/assets/a.png` from the `app` directory
+    let target = resource_relpath(Path::new("../assets/a.png"));
+    assert_eq!(target, resource_relpath(Path::new("_up_/assets/a.png")));
+    let resources = || {
+      vec![
+        Resource::new(&up, &target),
+        Resource::new(&literal, &target),
+        Resource::new(&copy, "copy/a.png"),
+      ]
+    };
+
+    let err = resolve_collisions(resources(), ResourceCollision::Error).unwrap_err();
+    match err {
+      tauri_utils::Error::ResourceCollision { target: t, sources } => {
+        assert_eq!(t, target);
+        assert_eq!(sources, vec![up.clone(), literal.clone()]);
+      }
+      e => panic!("unexpected error {e}"),
+    }
+
+    // the last resource was the one overwriting the others
+    assert_eq!(
+      resolve_collisions(resources(), ResourceCollision::Warn).unwrap(),
+      vec![
+        Resource::new(&literal, &target),
+        Resource::new(&copy, "copy/a.png")
+      ]
+    );
+
+    let hashed = resolve_collisions(resources(), ResourceCollision::HashSubdir).unwrap();
+    assert_eq!(
+      hashed,
+      vec![
+        Resource::new(&up, "_up_/assets/08c43a07/a.png"),
+        Resource::new(&literal, "_up_/assets/8429a5b7/a.png"),
+        Resource::new(&copy, "copy/a.png"),
+      ]
+    );
+    // the hashes only depend on the content
+    assert_eq!(
+      resolve_collisions(resources(), ResourceCollision::HashSubdir).unwrap(),
+      hashed
+    );
+  }
+
+  #[test]
+  fn resources_with_same_content_do_not_collide() {
+    use tauri_utils::{
+      config::ResourceCollision,
+      resources::{resolve_collisions, Resource},
+    };
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let first = tmp.path().join("first/a.png");
+    let second = tmp.path().join("second/a.png");
+    for path in [&first, &second] {
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, "same").unwrap();
+    }
+
+    // a file matched by two patterns and two files with the same content are bundled once
+    let resources = vec![
+      Resource::new(&first, "assets/a.png"),
+      Resource::new(&first, "assets/a.png"),
+      Resource::new(&second, "assets/a.png"),
+    ];
+    assert_eq!(
+      resolve_collisions(resources, ResourceCollision::Error).unwrap(),
+      vec![Resource::new(&first, "assets/a.png")]
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn output_ok_failure_includes_output_and_status() {
+    use super::CommandExt;
+    use std::process::Command;
+
+    let err = Command::new("sh")
+      .args([
+        "-c",
+        "echo 'signing failed'; printf 'bad \\377 output\\n' >&2; exit 3",
+      ])
+      .output_ok()
+      .unwrap_err();
+    match &err {
+      crate::Error::CommandFailed {
+        program,
+        exit_code,
+        signal,
+        stdout,
+        stderr,
+        ..
+      } => {
+        assert_eq!(program, "sh");
+        assert_eq!(*exit_code, Some(3));
+        assert_eq!(*signal, None);
+        assert_eq!(stdout, "signing failed");
+        assert_eq!(stderr, "bad \u{FFFD} output");
+      }
+      e => panic!("unexpected error {e:?}"),
+    }
+    let message = err.to_string();
+    assert!(message.contains("exited with code 3"));
+    assert!(message.contains("stdout:\nsigning failed"));
+    assert!(message.contains("bad \u{FFFD} output"));
+
+    let err = Command::new("sh")
+      .args(["-c", "kill -9 $$"])
+      .output_ok()
+      .unwrap_err();
+    assert!(matches!(
+      err,
+      crate::Error::CommandFailed {
+        exit_code: None,
+        signal: Some(9),
+        ..
+      }
+    ));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn output_ok_failure_redacts_secrets() {
+    use super::CommandExt;
+    use std::process::Command;
+
+    let err = Command::new("sh")
+      .args([
+        "-c",
+        "exit 1",
+        "sh",
+        "--password",
+        "hunter2",
+        "--password=hunter2",
+        "--team-id",
+        "T",
+      ])
+      .output_ok()
+      .unwrap_err();
+    match &err {
+      crate::Error::CommandFailed { args, .. } => assert_eq!(
+        args,
+        &[
+          "-c",
+          "exit 1",
+          "sh",
+          "--password",
+          "***",
+          "--password=***",
+          "--team-id",
+          "T"
+        ]
+      ),
+      e => panic!("unexpected error {e:?}"),
+    }
+    assert!(!err.to_string().contains("hunter2"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn output_ok_with_timeout() {
+    use super::CommandExt;
+    use std::{process::Command, time::Duration};
+
+    let output = Command::new("sh")
+      .args(["-c", "echo done"])
+      .output_ok_with_timeout(Duration::from_secs(10))
+      .unwrap();
+    assert_eq!(output.stdout, b"done\n");
+
+    let err = Command::new("sh")
+      .args(["-c", "echo started; echo waiting >&2; exec sleep 10"])
+      .output_ok_with_timeout(Duration::from_millis(500))
+      .unwrap_err();
+    match &err {
+      crate::Error::CommandTimeout { stdout, stderr, .. } => {
+        assert_eq!(stdout, "started");
+        assert_eq!(stderr, "waiting");
+      }
+      e => panic!("unexpected error {e:?}"),
+    }
+    let message = err.to_string();
+    assert!(message.contains("did not finish within 0.5s"));
+    assert!(message.contains("stdout:\nstarted"));
+    assert!(message.contains("stderr:\nwaiting"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn output_ok_retry() {
+    use super::CommandExt;
+    use std::{process::Command, time::Duration};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let attempts = tmp.path().join("attempts");
+    // fails with a transient error until the third attempt
+    let script = format!(
+      "echo x >> '{0}'; [ $(wc -l < '{0}') -ge 3 ] && echo signed && exit 0; echo 'network unreachable' >&2; exit 1",
+      attempts.display()
+    );
+    let is_transient = |e: &crate::Error| matches!(e, crate::Error::CommandFailed { stderr, .. } if stderr.contains("network"));
+
+    let output = Command::new("sh")
+      .args(["-c", &script])
+      .output_ok_retry(3, Duration::from_millis(1), is_transient)
+      .unwrap();
+    assert_eq!(output.stdout, b"signed\n");
+    assert_eq!(
+      std::fs::read_to_string(&attempts).unwrap().lines().count(),
+      3
+    );
+
+    std::fs::remove_file(&attempts).unwrap();
+    let err = Command::new("sh")
+      .args(["-c", &script])
+      .output_ok_retry(1, Duration::from_millis(1), is_transient)
+      .unwrap_err();
+    match &err {
+      crate::Error::CommandRetriesFailed { attempts, source } => {
+        assert_eq!(*attempts, 2);
+        assert!(matches!(
+          **source,
+          crate::Error::CommandFailed { ref stderr, .. } if stderr == "network unreachable"
+        ));
+      }
+      e => panic!("unexpected error {e:?}"),
+    }
+    assert!(err.to_string().contains("gave up after 2 attempts"));
+    assert!(err.to_string().contains("stderr:\nnetwork unreachable"));
+
+    // errors not matching the predicate are returned right away
+    std::fs::remove_file(&attempts).unwrap();
+    let err = Command::new("sh")
+      .args(["-c", &script])
+      .output_ok_retry(3, Duration::from_millis(1), |_| false)
+      .unwrap_err();
+    assert!(matches!(err, crate::Error::CommandFailed { .. }));
+    assert_eq!(
+      std::fs::read_to_string(&attempts).unwrap().lines().count(),
+      1
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn run_with_line_callback() {
+    use super::{CommandExt, OutputLine};
+    use std::process::Command;
+
+    let mut lines = Vec::new();
+    let output = Command::new("sh")
+      .args([
+        "-c",
+        "echo first; sleep 0.1; echo warning >&2; sleep 0.1; echo last",
+      ])
+      .run_with_line_callback(|line| lines.push(line))
+      .unwrap();
+    assert_eq!(
+      lines,
+      [
+        OutputLine::Stdout("first".into()),
+        OutputLine::Stderr("warning".into()),
+        OutputLine::Stdout("last".into()),
+      ]
+    );
+    assert_eq!(output.stdout, b"first\nlast\n");
+
+    let mut lines = Vec::new();
+    let err = Command::new("sh")
+      .args(["-c", "echo failed >&2; exit 1"])
+      .run_with_line_callback(|line| lines.push(line.text().to_string()))
+      .unwrap_err();
+    assert!(matches!(err, crate::Error::CommandFailed { .. }));
+    assert_eq!(lines, ["failed"]);
+  }
+
+  #[test]
+  fn output_tail_is_truncated() {
+    let output = vec![b'a'; super::OUTPUT_TAIL_LIMIT + 10];
+    let tail = super::output_tail(&output);
+    assert!(tail.starts_with("..."));
+    assert_eq!(tail.len(), super::OUTPUT_TAIL_LIMIT + 3);
+  }
+
+  #[test]
+  fn copy_dir_reports_progress() {
+    use super::{copy_dir_with_progress, CopyOptions};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    {
+      let mut file = create_file(&orig.join("a.txt")).expect("Unable to create file");
+      write!(file, "1234").expect("Unable to write to file");
+      let mut file = create_file(&orig.join("sub/b.txt")).expect("Unable to create file");
+      write!(file, "123456").expect("Unable to write to file");
+    }
+
+    let mut reports = Vec::new();
+    copy_dir_with_progress(
+      &orig,
+      &tmp.path().join("copy"),
+      &CopyOptions::default(),
+      &mut |progress| {
+        reports.push((
+          progress.files_copied,
+          progress.total_files,
+          progress.bytes_copied,
+          progress.path.to_path_buf(),
+        ))
+      },
+    )
+    .expect("Failed to copy dir");
+
+    assert_eq!(reports.len(), 2);
+    let (files_copied, total_files, bytes_copied, _) = reports.last().unwrap();
+    assert_eq!(*files_copied, 2);
+    assert_eq!(*total_files, 2);
+    assert_eq!(*bytes_copied, 10);
+    assert!(reports
+      .iter()
+      .any(|(_, _, _, path)| path == &PathBuf::from("sub").join("b.txt")));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    use super::{copy_dir, copy_file, dry_run_archive, is_dry_run, with_dry_run};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    {
+      let mut file = create_file(&orig.join("a.txt")).expect("Unable to create file");
+      write!(file, "1234").expect("Unable to write to file");
+    }
+    let file_copy = tmp.path().join("out/a.txt");
+    let dir_copy = tmp.path().join("out/dir");
+    let archive = tmp.path().join("out/archive.tar.gz");
+
+    with_dry_run(true, || {
+      copy_file(orig.join("a.txt"), &file_copy).expect("Failed to copy file");
+      copy_dir(&orig, &dir_copy).expect("Failed to copy dir");
+      assert!(dry_run_archive(&[orig.as_path()], &archive).expect("Failed to archive"));
+      // the sources are still validated
+      assert!(copy_file(orig.join("missing.txt"), &file_copy).is_err());
+    });
+
+    assert!(!tmp.path().join("out").exists());
+    assert!(!is_dry_run());
+    assert!(!dry_run_archive(&[orig.as_path()], &archive).expect("Failed to archive"));
+  }
+
+  #[cfg(not(windows))]
+  #[test]
+  fn copy_dir_parallel() {
+    use super::{copy_dir_with_progress, CopyOptions};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    let copy = tmp.path().join("copy");
+    for i in 0..50 {
+      let mut file = create_file(&orig.join(format!("dir{}/file{i}.txt", i % 5)))
+        .expect("Unable to create file");
+      write!(file, "{i}").expect("Unable to write to file");
+    }
+    super::symlink_file(&PathBuf::from("dir0/file0.txt"), &orig.join("link"))
+      .expect("Failed to create symlink");
+
+    let mut files_copied = 0;
+    copy_dir_with_progress(
+      &orig,
+      &copy,
+      &CopyOptions {
+        threads: 4,
+        ..Default::default()
+      },
+      &mut |progress| files_copied = progress.files_copied,
+    )
+    .expect("Failed to copy dir");
+
+    assert_eq!(files_copied, 51);
+    for i in 0..50 {
+      assert_eq!(
+        std::fs::read_to_string(copy.join(format!("dir{}/file{i}.txt", i % 5)))
+          .expect("Failed to read file"),
+        i.to_string()
+      );
+    }
+    assert_eq!(
+      std::fs::read_link(copy.join("link")).expect("Failed to read from symlink"),
+      PathBuf::from("dir0/file0.txt")
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copy_dir_parallel_returns_error() {
+    use super::{copy_dir_with, CopyOptions};
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    for i in 0..20 {
+      create_file(&orig.join(format!("file{i}.txt"))).expect("Unable to create file");
+    }
+    // sockets are neither regular files nor symlinks so they cannot be copied
+    let _listener =
+      std::os::unix::net::UnixListener::bind(orig.join("socket")).expect("Failed to create socket");
+
+    assert!(copy_dir_with(
+      &orig,
+      &tmp.path().join("copy"),
+      &CopyOptions {
+        threads: 4,
+        ..Default::default()
+      },
+    )
+    .is_err());
+  }
+
+  /// Compares the serial and parallel copies of 5,000 small files.
+  ///
+  /// Run with `cargo test copy_dir_parallel_benchmark -- --ignored --nocapture`.
+  #[test]
+  #[ignore]
+  fn copy_dir_parallel_benchmark() {
+    use super::{copy_dir_with, CopyOptions};
+    use std::time::Instant;
+
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let orig = tmp.path().join("orig");
+    for i in 0..5000 {
+      let mut file = create_file(&orig.join(format!("dir{}/file{i}.txt", i % 50)))
+        .expect("Unable to create file");
+      write!(file, "{}", "x".repeat(1024)).expect("Unable to write to file");
+    }
+
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+    for (name, threads) in [("serial", 0), ("parallel", threads)] {
+      let start = Instant::now();
+      copy_dir_with(
+        &orig,
+        &tmp.path().join(name),
+        &CopyOptions {
+          threads,
+          ..Default::default()
+        },
+      )
+      .expect("Failed to copy dir");
+      println!("{name} copy ({threads} threads): {:?}", start.elapsed());
+    }
+  }
 }
@@ -6,20 +6,27 @@
 use super::{
   super::{
     common::{self, CommandExt},
-    path_utils,
+    path_utils, staging,
+    windows::download,
   },
   debian,
 };
-use crate::Settings;
+use crate::{PackageType, Settings};
 use anyhow::Context;
 use handlebars::Handlebars;
+use sha2::{Digest, Sha256};
 use std::{
   collections::BTreeMap,
+  ffi::OsString,
   fs::{remove_dir_all, write},
-  path::PathBuf,
+  os::unix::fs::PermissionsExt,
+  path::{Path, PathBuf},
   process::{Command, Stdio},
 };
 
+/// The version of the cached AppImage tools, bumped when their pinned releases change.
+const TOOLS_CACHE_VERSION: &str = "2";
+
 /// Bundles the project.
 /// Returns a vector of PathBuf that shows where the AppImage was created.
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
@@ -30,43 +37,95 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     other => other,
   };
   let package_dir = settings.project_out_directory().join("bundle/appimage_deb");
+  let output_path = settings.project_out_directory().join("bundle/appimage");
+  let appimage_filename = format!(
+    "{}_{}_{}.AppImage",
+    settings.product_name(),
+    settings.version_string(),
+    arch
+  );
+  let appimage_path = output_path.join(&appimage_filename);
+
+  if staging::dry_run_package(settings, PackageType::AppImage, &[appimage_path.clone()])? {
+    return Ok(vec![appimage_path]);
+  }
 
   // generate deb_folder structure
   let (data_dir, icons) = debian::generate_data(settings, &package_dir)
     .with_context(|| "Failed to build data folders and files")?;
-  common::copy_custom_files(&settings.deb().files, &data_dir)
-    .with_context(|| "Failed to copy custom files")?;
+  common::copy_custom_files(
+    &settings.deb().files,
+    &data_dir,
+    settings.allow_empty_file_globs(),
+  )
+  .with_context(|| "Failed to copy custom files")?;
+  common::clamp_mtimes(settings, &data_dir)
+    .with_context(|| "Failed to clamp modification times")?;
+  common::record_staged_size([&data_dir])?;
 
-  let output_path = settings.project_out_directory().join("bundle/appimage");
   if output_path.exists() {
     remove_dir_all(&output_path)?;
   }
   std::fs::create_dir_all(output_path.clone())?;
   let app_dir_path = output_path.join(format!("{}.AppDir", settings.product_name()));
-  let appimage_filename = format!(
-    "{}_{}_{}.AppImage",
-    settings.product_name(),
-    settings.version_string(),
-    arch
-  );
-  let appimage_path = output_path.join(&appimage_filename);
   path_utils::create(app_dir_path, true)?;
 
+  let target_arch = settings.target().split('-').next().unwrap();
+  let linuxdeploy_arch = if target_arch == "i686" {
+    "i386"
+  } else {
+    target_arch
+  };
+  let tools_cache_dir = dirs::cache_dir()
+    .map_or_else(
+      || output_path.to_path_buf(),
+      |mut p| {
+        p.push("tauri");
+        p
+      },
+    )
+    .join("appimage-tools")
+    .join(TOOLS_CACHE_VERSION);
+  let resolve = |tool: &Tool| {
+    resolve_tool(tool, &tools_cache_dir, settings.offline(), |var| {
+      std::env::var_os(var)
+    })
+  };
+
+  let apprun_path = resolve(&apprun(target_arch))?;
+  let linuxdeploy_path = resolve(&linuxdeploy(linuxdeploy_arch))?;
+  // the plugins are looked up by linuxdeploy on the PATH
+  let linuxdeploy_plugins_path = output_path.join("linuxdeploy-plugins");
+  std::fs::create_dir_all(&linuxdeploy_plugins_path)?;
+  let mut plugins = vec![linuxdeploy_plugin_gtk()];
+  if std::env::var_os("APPIMAGE_BUNDLE_GSTREAMER").is_some_and(|v| v != "0") {
+    plugins.push(linuxdeploy_plugin_gstreamer());
+  }
+  for plugin in &plugins {
+    std::os::unix::fs::symlink(
+      resolve(plugin)?,
+      linuxdeploy_plugins_path.join(&plugin.file_names[0]),
+    )?;
+  }
+
   // setup data to insert into shell script
+  let apprun_path = apprun_path.to_string_lossy();
+  let linuxdeploy_path = linuxdeploy_path.to_string_lossy();
+  let linuxdeploy_plugins_path = linuxdeploy_plugins_path.to_string_lossy();
   let mut sh_map = BTreeMap::new();
-  sh_map.insert("arch", settings.target().split('-').next().unwrap());
+  sh_map.insert("arch", target_arch);
   sh_map.insert("crate_name", settings.main_binary_name());
   sh_map.insert("appimage_filename", &appimage_filename);
-  let tauri_tools_path = dirs::cache_dir().map_or_else(
-    || output_path.to_path_buf(),
-    |mut p| {
-      p.push("tauri");
-      p
-    },
-  );
-  std::fs::create_dir_all(&tauri_tools_path)?;
-  let tauri_tools_path_str = tauri_tools_path.to_string_lossy();
-  sh_map.insert("tauri_tools_path", &tauri_tools_path_str);
+  sh_map.insert("apprun_path", &apprun_path);
+  sh_map.insert("linuxdeploy_path", &linuxdeploy_path);
+  sh_map.insert("linuxdeploy_plugins_path", &linuxdeploy_plugins_path);
+  let update_information = settings
+    .appimage()
+    .update_information
+    .as_deref()
+    .map(shell_quote)
+    .unwrap_or_default();
+  sh_map.insert("update_information", &update_information);
   let larger_icon = icons
     .iter()
     .filter(|i| i.width == i.height)
@@ -79,6 +138,15 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     .to_string_lossy()
     .to_string();
   sh_map.insert("icon_path", &larger_icon_path);
+  let webkit_lib_dirs = webkit_lib_dirs(settings);
+  sh_map.insert("webkit_lib_dirs", &webkit_lib_dirs);
+  if settings.is_cross_arch() {
+    log::warn!(
+      "Bundling an AppImage for {arch} on a {} host: linuxdeploy resolves the libraries of the binaries with the host tools, which requires the {arch} libraries to be installed and an emulator such as qemu-user",
+      std::env::consts::ARCH,
+      arch = settings.binary_arch()
+    );
+  }
 
   // initialize shell script template.
   let mut handlebars = Handlebars::new();
@@ -107,10 +175,523 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
 
   // execute the shell script to build the appimage.
   Command::new(&sh_file)
-    .current_dir(output_path)
+    .current_dir(&output_path)
     .output_ok()
     .context("error running build_appimage.sh")?;
 
   remove_dir_all(&package_dir)?;
-  Ok(vec![appimage_path])
+
+  let mut paths = vec![appimage_path];
+  if let Some(zsync_path) = zsync_file(
+    &paths[0],
+    settings.appimage().update_information.is_some(),
+    |var| std::env::var_os(var),
+  )? {
+    paths.push(zsync_path);
+  }
+  Ok(paths)
+}
+
+/// The library directories searched for the WebKit helper processes.
+///
+/// When cross compiling, only the Debian multiarch directory of the target is searched
+/// since the other directories hold the libraries of the host architecture.
+fn webkit_lib_dirs(settings: &Settings) -> String {
+  if settings.is_cross_arch() {
+    let triplet = match settings.binary_arch() {
+      "x86" => "i386-linux-gnu",
+      "arm" => "arm-linux-gnueabihf",
+      arch => return format!("/usr/lib/{arch}-linux-gnu"),
+    };
+    format!("/usr/lib/{triplet}")
+  } else {
+    "/usr/lib*".into()
+  }
+}
+
+/// Quotes a value for the AppImage build script.
+fn shell_quote(value: &str) -> String {
+  format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Returns the `.zsync` file used by AppImageUpdate for delta updates of the given AppImage,
+/// generating it with `zsyncmake` when it was not created by appimagetool.
+///
+/// Returns `None` without update information or when `zsyncmake` is not installed.
+fn zsync_file(
+  appimage_path: &Path,
+  has_update_information: bool,
+  var_os: impl Fn(&str) -> Option<OsString>,
+) -> crate::Result<Option<PathBuf>> {
+  if !has_update_information {
+    return Ok(None);
+  }
+
+  let mut zsync_path = appimage_path.as_os_str().to_owned();
+  zsync_path.push(".zsync");
+  let zsync_path = PathBuf::from(zsync_path);
+  if zsync_path.is_file() {
+    return Ok(Some(zsync_path));
+  }
+
+  match find_on_path(&["zsyncmake".into()], &var_os) {
+    Some(zsyncmake) => {
+      zsyncmake_command(&zsyncmake, appimage_path, &zsync_path)
+        .output_ok()
+        .context("error running zsyncmake")?;
+      Ok(Some(zsync_path))
+    }
+    None => {
+      log::warn!(
+        "zsyncmake was not found on the PATH, skipping the .zsync file for AppImageUpdate delta updates"
+      );
+      Ok(None)
+    }
+  }
+}
+
+fn zsyncmake_command(zsyncmake: &Path, appimage_path: &Path, zsync_path: &Path) -> Command {
+  let mut command = Command::new(zsyncmake);
+  // the AppImage URL is relative to the .zsync file, which is published next to it
+  command
+    .arg("-u")
+    .arg(
+      appimage_path
+        .file_name()
+        .expect("AppImage without file name"),
+    )
+    .arg("-o")
+    .arg(zsync_path)
+    .arg(appimage_path);
+  if let Some(dir) = appimage_path.parent() {
+    command.current_dir(dir);
+  }
+  command
+}
+
+/// Finds the first of the given file names in the directories of the `PATH`.
+fn find_on_path(
+  file_names: &[String],
+  var_os: impl Fn(&str) -> Option<OsString>,
+) -> Option<PathBuf> {
+  let paths = var_os("PATH")?;
+  std::env::split_paths(&paths).find_map(|dir| {
+    file_names
+      .iter()
+      .map(|file_name| dir.join(file_name))
+      .find(|path| path.is_file())
+  })
+}
+
+/// A tool release pinned to a versioned URL and the SHA-256 of its content.
+struct PinnedTool {
+  /// The file name the tool is downloaded as.
+  file_name: &'static str,
+  /// The versioned download URL.
+  url: &'static str,
+  /// The SHA-256 of the downloaded file, verified on every download and cache hit.
+  sha256: &'static str,
+}
+
+/// The pinned releases of the tools downloaded to build the AppImage.
+///
+/// Tools without a pinned release for the target architecture are never downloaded,
+/// they must be provided with their environment variable or on the `PATH`.
+const PINNED_TOOLS: &[PinnedTool] = &[];
+
+/// The magic bytes of type 2 AppImages, at offset 8.
+const APPIMAGE_MAGIC: &[u8] = b"AI\x02";
+
+/// A tool used to build the AppImage.
+struct Tool {
+  /// The tool name, used in error messages.
+  name: &'static str,
+  /// The environment variable pointing to a preinstalled tool.
+  env_var: &'static str,
+  /// The file names looked up on the `PATH`, the first one is also used for the download.
+  file_names: Vec<String>,
+  /// The pinned release downloaded when the tool is not found.
+  pinned: Option<&'static PinnedTool>,
+  /// Whether the AppImage magic bytes are erased from the download,
+  /// so it is not intercepted by AppImageLauncher or binfmt handlers when executed.
+  erase_appimage_magic: bool,
+}
+
+fn pinned(file_name: &str) -> Option<&'static PinnedTool> {
+  PINNED_TOOLS.iter().find(|tool| tool.file_name == file_name)
+}
+
+fn apprun(arch: &str) -> Tool {
+  let file_name = format!("AppRun-{arch}");
+  Tool {
+    name: "AppRun",
+    env_var: "TAURI_APPRUN_PATH",
+    pinned: pinned(&file_name),
+    file_names: vec![file_name],
+    erase_appimage_magic: false,
+  }
+}
+
+fn linuxdeploy(arch: &str) -> Tool {
+  let file_name = format!("linuxdeploy-{arch}.AppImage");
+  Tool {
+    name: "linuxdeploy",
+    env_var: "TAURI_LINUXDEPLOY_PATH",
+    pinned: pinned(&file_name),
+    file_names: vec![file_name, "linuxdeploy".into()],
+    erase_appimage_magic: true,
+  }
+}
+
+fn linuxdeploy_plugin_gtk() -> Tool {
+  Tool {
+    name: "linuxdeploy-plugin-gtk",
+    env_var: "TAURI_LINUXDEPLOY_PLUGIN_GTK_PATH",
+    file_names: vec!["linuxdeploy-plugin-gtk.sh".into()],
+    pinned: pinned("linuxdeploy-plugin-gtk.sh"),
+    erase_appimage_magic: false,
+  }
+}
+
+fn linuxdeploy_plugin_gstreamer() -> Tool {
+  Tool {
+    name: "linuxdeploy-plugin-gstreamer",
+    env_var: "TAURI_LINUXDEPLOY_PLUGIN_GSTREAMER_PATH",
+    file_names: vec!["linuxdeploy-plugin-gstreamer.sh".into()],
+    pinned: pinned("linuxdeploy-plugin-gstreamer.sh"),
+    erase_appimage_magic: false,
+  }
+}
+
+/// Finds a tool from its environment variable, on the `PATH` or in the cache, in this order.
+///
+/// The tool is downloaded to the cache when it is not found, unless `offline` is set.
+fn resolve_tool(
+  tool: &Tool,
+  cache_dir: &Path,
+  offline: bool,
+  var_os: impl Fn(&str) -> Option<OsString>,
+) -> crate::Result<PathBuf> {
+  if let Some(path) = var_os(tool.env_var) {
+    let path = PathBuf::from(path);
+    return if path.is_file() {
+      Ok(path)
+    } else {
+      Err(crate::Error::ToolPathNotFound {
+        env_var: tool.env_var.into(),
+        path,
+      })
+    };
+  }
+
+  if let Some(path) = find_on_path(&tool.file_names, &var_os) {
+    return Ok(path);
+  }
+
+  let Some(pinned) = tool.pinned else {
+    return Err(crate::Error::ToolNotPinned {
+      tool: tool.name.into(),
+      env_var: tool.env_var.into(),
+    });
+  };
+  let path = cache_dir.join(pinned.file_name);
+  if path.is_file() {
+    let mut data = std::fs::read(&path)?;
+    // the magic bytes are erased from the cached file
+    if tool.erase_appimage_magic && data.len() >= 11 {
+      data[8..11].copy_from_slice(APPIMAGE_MAGIC);
+    }
+    verify_sha256(tool, pinned, &path.display().to_string(), &data)?;
+    return Ok(path);
+  }
+  if offline {
+    return Err(crate::Error::ToolNotFound {
+      tool: tool.name.into(),
+      env_var: tool.env_var.into(),
+    });
+  }
+  download_tool(tool, pinned, &path)?;
+  Ok(path)
+}
+
+/// Fails if the content of the tool read from `location` does not match its pinned SHA-256.
+fn verify_sha256(
+  tool: &Tool,
+  pinned: &PinnedTool,
+  location: &str,
+  data: &[u8],
+) -> crate::Result<()> {
+  let actual = hex::encode(Sha256::digest(data));
+  if actual.eq_ignore_ascii_case(pinned.sha256) {
+    Ok(())
+  } else {
+    Err(crate::Error::ToolChecksumMismatch {
+      tool: tool.name.into(),
+      location: location.into(),
+      expected: pinned.sha256.into(),
+      actual,
+    })
+  }
+}
+
+fn download_tool(tool: &Tool, pinned: &PinnedTool, path: &Path) -> crate::Result<()> {
+  let mut data = download(pinned.url)?;
+  verify_sha256(tool, pinned, pinned.url, &data)?;
+
+  if tool.erase_appimage_magic && data.len() >= 11 {
+    data[8..11].fill(0);
+  }
+
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  // the tool is renamed once fully written so an interrupted download is not cached
+  let mut partial_path = path.as_os_str().to_owned();
+  partial_path.push(".part");
+  std::fs::write(&partial_path, &data)?;
+  std::fs::set_permissions(&partial_path, std::fs::Permissions::from_mode(0o755))?;
+  std::fs::rename(&partial_path, path)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashMap;
+
+  fn resolve(tool: &Tool, cache_dir: &Path, vars: &[(&str, &Path)]) -> crate::Result<PathBuf> {
+    let vars: HashMap<_, _> = vars.iter().copied().collect();
+    // offline so the tests never download
+    resolve_tool(tool, cache_dir, true, |var| {
+      vars.get(var).map(|path| path.as_os_str().to_owned())
+    })
+  }
+
+  #[test]
+  fn update_information_is_quoted() {
+    assert_eq!(
+      shell_quote("gh-releases-zsync|owner|repo|latest|*x86_64.AppImage.zsync"),
+      "'gh-releases-zsync|owner|repo|latest|*x86_64.AppImage.zsync'"
+    );
+    assert_eq!(shell_quote("zsync|it's"), r"'zsync|it'\''s'");
+  }
+
+  #[test]
+  fn zsyncmake_args() {
+    let command = zsyncmake_command(
+      Path::new("/usr/bin/zsyncmake"),
+      Path::new("/out/app_1.0.0_amd64.AppImage"),
+      Path::new("/out/app_1.0.0_amd64.AppImage.zsync"),
+    );
+    assert_eq!(command.get_program(), "/usr/bin/zsyncmake");
+    assert_eq!(
+      command.get_args().collect::<Vec<_>>(),
+      [
+        "-u",
+        "app_1.0.0_amd64.AppImage",
+        "-o",
+        "/out/app_1.0.0_amd64.AppImage.zsync",
+        "/out/app_1.0.0_amd64.AppImage"
+      ]
+    );
+    assert_eq!(command.get_current_dir(), Some(Path::new("/out")));
+  }
+
+  #[test]
+  fn zsync_artifact() {
+    let tmp = tempfile::tempdir().unwrap();
+    let appimage_path = tmp.path().join("app_1.0.0_amd64.AppImage");
+    std::fs::write(&appimage_path, "appimage").unwrap();
+    let zsync_path = tmp.path().join("app_1.0.0_amd64.AppImage.zsync");
+    let empty_path = |var: &str| (var == "PATH").then(|| tmp.path().join("bin").into_os_string());
+
+    // not listed without update information
+    std::fs::write(&zsync_path, "zsync").unwrap();
+    assert_eq!(zsync_file(&appimage_path, false, empty_path).unwrap(), None);
+
+    // listed when created by appimagetool
+    assert_eq!(
+      zsync_file(&appimage_path, true, empty_path).unwrap(),
+      Some(zsync_path.clone())
+    );
+
+    // skipped when zsyncmake is not installed
+    std::fs::remove_file(&zsync_path).unwrap();
+    assert_eq!(zsync_file(&appimage_path, true, empty_path).unwrap(), None);
+  }
+
+  #[test]
+  fn tool_from_env_var() {
+    let tmp = tempfile::tempdir().unwrap();
+    let linuxdeploy_path = tmp.path().join("my-linuxdeploy");
+    std::fs::write(&linuxdeploy_path, "").unwrap();
+    // takes precedence over the PATH
+    std::fs::write(tmp.path().join("linuxdeploy"), "").unwrap();
+
+    let path = resolve(
+      &linuxdeploy("x86_64"),
+      &tmp.path().join("cache"),
+      &[
+        ("TAURI_LINUXDEPLOY_PATH", linuxdeploy_path.as_path()),
+        ("PATH", tmp.path()),
+      ],
+    )
+    .unwrap();
+    assert_eq!(path, linuxdeploy_path);
+  }
+
+  #[test]
+  fn tool_from_env_var_must_exist() {
+    let tmp = tempfile::tempdir().unwrap();
+    let linuxdeploy_path = tmp.path().join("my-linuxdeploy");
+
+    let error = resolve(
+      &linuxdeploy("x86_64"),
+      &tmp.path().join("cache"),
+      &[("TAURI_LINUXDEPLOY_PATH", linuxdeploy_path.as_path())],
+    )
+    .unwrap_err();
+    assert!(matches!(
+      error,
+      crate::Error::ToolPathNotFound { env_var, path }
+        if env_var == "TAURI_LINUXDEPLOY_PATH" && path == linuxdeploy_path
+    ));
+  }
+
+  #[test]
+  fn tool_from_path() {
+    let tmp = tempfile::tempdir().unwrap();
+    let bin_dir = tmp.path().join("bin");
+    std::fs::create_dir(&bin_dir).unwrap();
+    std::fs::write(bin_dir.join("linuxdeploy-plugin-gtk.sh"), "").unwrap();
+    let path_var = std::env::join_paths([tmp.path().join("empty"), bin_dir.clone()]).unwrap();
+
+    let path = resolve(
+      &linuxdeploy_plugin_gtk(),
+      &tmp.path().join("cache"),
+      &[("PATH", Path::new(&path_var))],
+    )
+    .unwrap();
+    assert_eq!(path, bin_dir.join("linuxdeploy-plugin-gtk.sh"));
+  }
+
+  static PINNED_APPRUN: PinnedTool = PinnedTool {
+    file_name: "AppRun-x86_64",
+    url: "https://example.com/AppRun-x86_64",
+    // SHA-256 of `apprun`
+    sha256: "d7ac12ae1e0896517be8b9897f6f78beb969cb3a8968ebfe7b5c9d1df648ed1d",
+  };
+
+  static PINNED_LINUXDEPLOY: PinnedTool = PinnedTool {
+    file_name: "linuxdeploy-x86_64.AppImage",
+    url: "https://example.com/linuxdeploy-x86_64.AppImage",
+    // SHA-256 of `LINUXDEPLOY`
+    sha256: "dca84bcea5402467ea93a0a42e5b4782fcea2999277d7d742ba73e1db5cef4e5",
+  };
+
+  const LINUXDEPLOY: &[u8] = b"\x7fELF\x02\x01\x01\x00AI\x02\x00\x00\x00\x00\x00";
+
+  #[test]
+  fn tool_from_cache() {
+    let tmp = tempfile::tempdir().unwrap();
+    let tool = Tool {
+      pinned: Some(&PINNED_APPRUN),
+      ..apprun("x86_64")
+    };
+    let cached_path = tmp.path().join("AppRun-x86_64");
+    std::fs::write(&cached_path, "apprun").unwrap();
+
+    assert_eq!(resolve(&tool, tmp.path(), &[]).unwrap(), cached_path);
+
+    // modified tools are rejected
+    std::fs::write(&cached_path, "modified").unwrap();
+    assert!(matches!(
+      resolve(&tool, tmp.path(), &[]).unwrap_err(),
+      crate::Error::ToolChecksumMismatch { tool, expected, .. }
+        if tool == "AppRun" && expected == PINNED_APPRUN.sha256
+    ));
+  }
+
+  #[test]
+  fn tool_from_cache_with_erased_magic() {
+    let tmp = tempfile::tempdir().unwrap();
+    let tool = Tool {
+      pinned: Some(&PINNED_LINUXDEPLOY),
+      ..linuxdeploy("x86_64")
+    };
+    let cached_path = tmp.path().join("linuxdeploy-x86_64.AppImage");
+    let mut cached = LINUXDEPLOY.to_vec();
+    cached[8..11].fill(0);
+    std::fs::write(&cached_path, cached).unwrap();
+
+    assert_eq!(resolve(&tool, tmp.path(), &[]).unwrap(), cached_path);
+  }
+
+  #[test]
+  fn download_must_match_pinned_checksum() {
+    let tool = Tool {
+      pinned: Some(&PINNED_LINUXDEPLOY),
+      ..linuxdeploy("x86_64")
+    };
+    assert!(verify_sha256(
+      &tool,
+      &PINNED_LINUXDEPLOY,
+      PINNED_LINUXDEPLOY.url,
+      LINUXDEPLOY
+    )
+    .is_ok());
+    assert!(matches!(
+      verify_sha256(&tool, &PINNED_LINUXDEPLOY, PINNED_LINUXDEPLOY.url, b"tampered").unwrap_err(),
+      crate::Error::ToolChecksumMismatch { location, .. } if location == PINNED_LINUXDEPLOY.url
+    ));
+  }
+
+  #[test]
+  fn offline_without_tool() {
+    let tmp = tempfile::tempdir().unwrap();
+    let tool = Tool {
+      pinned: Some(&PINNED_LINUXDEPLOY),
+      ..linuxdeploy("x86_64")
+    };
+    // an interrupted download is not renamed to the tool file name
+    std::fs::write(tmp.path().join("linuxdeploy-x86_64.AppImage.part"), "").unwrap();
+
+    let error = resolve(&tool, tmp.path(), &[]).unwrap_err();
+    assert!(matches!(
+      error,
+      crate::Error::ToolNotFound { tool, env_var }
+        if tool == "linuxdeploy" && env_var == "TAURI_LINUXDEPLOY_PATH"
+    ));
+  }
+
+  #[test]
+  fn tool_without_pinned_release() {
+    let tmp = tempfile::tempdir().unwrap();
+    let tool = Tool {
+      pinned: None,
+      ..linuxdeploy("x86_64")
+    };
+
+    let error = resolve(&tool, tmp.path(), &[]).unwrap_err();
+    assert!(matches!(
+      error,
+      crate::Error::ToolNotPinned { tool, env_var }
+        if tool == "linuxdeploy" && env_var == "TAURI_LINUXDEPLOY_PATH"
+    ));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &[
+        "bundle/appimage/My App_1.0.0_amd64.AppImage",
+        "bundle/appimage_deb/data/usr/bin/my-app",
+      ],
+      &["bundle/appimage/My App_1.0.0_amd64.AppImage"],
+      || bundle_project(&settings),
+    );
+  }
 }
@@ -7,9 +7,11 @@
 //
 // foobar_1.2.3_i386.deb   # Actually an ar archive
 //     debian-binary           # Specifies deb format version (2.0 in our case)
-//     control.tar.gz          # Contains files controlling the installation:
+//     control.tar.gz          # Contains files controlling the installation
+//                             # (or .tar.xz/.tar.zst, see `DebianSettings::compression`):
 //         control                  # Basic package metadata
 //         md5sums                  # Checksums for files in data.tar.gz below
+//         conffiles                # Configuration files under /etc (optional)
 //         postinst                 # Post-installation script (optional)
 //         prerm                    # Pre-uninstallation script (optional)
 //     data.tar.gz             # Contains files to be installed:
@@ -17,23 +19,33 @@
 //         usr/share/applications/foobar.desktop     # Desktop file (for apps)
 //         usr/share/icons/hicolor/...               # Icon files (for apps)
 //         usr/lib/foobar/...                        # Other resource files
+//         usr/share/doc/foobar/changelog.gz         # Changelog (optional)
+//         usr/share/doc/foobar/copyright            # Copyright file (optional)
+//     _gpgorigin              # Detached GPG signature of the members above (optional)
 //
 // For cargo-bundle, we put bundle resource files under /usr/lib/package_name/,
 // and then generate the desktop file and control file from the bundle
 // metadata, as well as generating the md5sums file.  Currently we do not
 // generate postinst or prerm files.
 
-use super::{super::common, freedesktop};
-use crate::Settings;
+use super::{
+  super::{common, staging},
+  freedesktop, gpg,
+};
+use crate::{DebCompression, DebianSettings, PackageType, Settings};
 use anyhow::Context;
 use flate2::{write::GzEncoder, Compression};
 use tar::HeaderMode;
 use walkdir::WalkDir;
 
 use std::{
+  collections::BTreeMap,
   fs::{self, File, OpenOptions},
   io::{self, Write},
-  os::unix::fs::{MetadataExt, OpenOptionsExt},
+  os::unix::{
+    ffi::OsStrExt,
+    fs::{MetadataExt, OpenOptionsExt, PermissionsExt},
+  },
   path::{Path, PathBuf},
 };
 
@@ -58,24 +70,40 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
 
   let base_dir = settings.project_out_directory().join("bundle/deb");
   let package_dir = base_dir.join(&package_base_name);
+  let package_path = base_dir.join(&package_name);
+
+  if staging::dry_run_package(settings, PackageType::Deb, &[package_path.clone()])? {
+    if let Some(key) = &settings.deb().sign_key {
+      log::info!(action = "Dry run"; "would sign {} with GPG key {}", package_name, key);
+    }
+    return Ok(vec![package_path]);
+  }
+
   if package_dir.exists() {
     fs::remove_dir_all(&package_dir)
       .with_context(|| format!("Failed to remove old {package_base_name}"))?;
   }
-  let package_path = base_dir.join(&package_name);
 
   log::info!(action = "Bundling"; "{} ({})", package_name, package_path.display());
 
   let (data_dir, _) = generate_data(settings, &package_dir)
     .with_context(|| "Failed to build data folders and files")?;
-  common::copy_custom_files(&settings.deb().files, &data_dir)
-    .with_context(|| "Failed to copy custom files")?;
+  common::copy_custom_files(
+    &settings.deb().files,
+    &data_dir,
+    settings.allow_empty_file_globs(),
+  )
+  .with_context(|| "Failed to copy custom files")?;
 
   // Generate control files.
   let control_dir = package_dir.join("control");
   generate_control_file(settings, arch, &control_dir, &data_dir)
     .with_context(|| "Failed to create control file")?;
   generate_scripts(settings, &control_dir).with_context(|| "Failed to create control scripts")?;
+  if !settings.deb().skip_conffiles {
+    generate_conffiles(&control_dir, &data_dir)
+      .with_context(|| "Failed to create conffiles file")?;
+  }
   generate_md5sums(&control_dir, &data_dir).with_context(|| "Failed to create md5sums file")?;
 
   // Generate `debian-binary` file; see
@@ -84,16 +112,25 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   create_file_with_data(&debian_binary_path, "2.0\n")
     .with_context(|| "Failed to create debian-binary file")?;
 
-  // Apply tar/gzip/ar to create the final package file.
-  let control_tar_gz_path =
-    tar_and_gzip_dir(control_dir).with_context(|| "Failed to tar/gzip control directory")?;
-  let data_tar_gz_path =
-    tar_and_gzip_dir(data_dir).with_context(|| "Failed to tar/gzip data directory")?;
-  create_archive(
-    vec![debian_binary_path, control_tar_gz_path, data_tar_gz_path],
-    &package_path,
-  )
-  .with_context(|| "Failed to create package archive")?;
+  common::clamp_mtimes(settings, &package_dir)
+    .with_context(|| "Failed to clamp modification times")?;
+  common::record_staged_size([&package_dir])?;
+
+  // Apply tar/compression/ar to create the final package file.
+  let compression = settings.deb().compression;
+  let control_tar_path = tar_and_compress_dir(control_dir, compression)
+    .with_context(|| "Failed to tar/compress control directory")?;
+  let data_tar_path = tar_and_compress_dir(data_dir, compression)
+    .with_context(|| "Failed to tar/compress data directory")?;
+  let mut members = vec![debian_binary_path, control_tar_path, data_tar_path];
+  if let Some(key) = &settings.deb().sign_key {
+    log::info!(action = "Signing"; "{} with GPG key {}", package_name, key);
+    let signature_path =
+      sign_members(key, &members, &package_dir).with_context(|| "Failed to sign package")?;
+    members.push(signature_path);
+  }
+  create_archive(settings, members, &package_path)
+    .with_context(|| "Failed to create package archive")?;
   Ok(vec![package_path])
 }
 
@@ -124,6 +161,8 @@ pub fn generate_data(
     .with_context(|| "Failed to create desktop file")?;
   generate_changelog_file(settings, &data_dir)
     .with_context(|| "Failed to create changelog.gz file")?;
+  generate_copyright_file(settings, &data_dir)
+    .with_context(|| "Failed to create copyright file")?;
 
   Ok((data_dir, icons))
 }
@@ -132,13 +171,19 @@ pub fn generate_data(
 /// <https://www.debian.org/doc/debian-policy/ch-docs.html#changelog-files-and-release-notes>
 fn generate_changelog_file(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
   if let Some(changelog_src_path) = &settings.deb().changelog {
-    let mut src_file = File::open(changelog_src_path)?;
-    let bin_name = settings.main_binary_name();
-    let dest_path = data_dir.join(format!("usr/share/doc/{}/changelog.gz", bin_name));
+    let changelog = fs::read_to_string(changelog_src_path)?;
+    validate_changelog(&changelog, settings.version_string()).map_err(|reason| {
+      crate::Error::InvalidDebChangelog {
+        path: changelog_src_path.clone(),
+        reason,
+      }
+    })?;
+    let dest_path = doc_dir(settings, data_dir).join("changelog.gz");
 
     let changelog_file = common::create_file(&dest_path)?;
+    // the gzip header has no file name and a zero modification time, keeping the package deterministic
     let mut gzip_encoder = GzEncoder::new(changelog_file, Compression::new(9));
-    io::copy(&mut src_file, &mut gzip_encoder)?;
+    gzip_encoder.write_all(changelog.as_bytes())?;
 
     let mut changelog_file = gzip_encoder.finish()?;
     changelog_file.flush()?;
@@ -146,6 +191,79 @@ fn generate_changelog_file(settings: &Settings, data_dir: &Path) -> crate::Resul
   Ok(())
 }
 
+/// Checks that the latest entry of a changelog in the Debian format, e.g.
+/// `my-app (1.0.0) unstable; urgency=medium`, is for the given version.
+fn validate_changelog(changelog: &str, version: &str) -> Result<(), String> {
+  let Some(entry) = changelog.lines().find(|line| !line.trim().is_empty()) else {
+    return Err("the changelog is empty".into());
+  };
+  let Some(entry_version) = entry
+    .split_once(" (")
+    .and_then(|(_, rest)| rest.split_once(')'))
+    .map(|(entry_version, _)| entry_version)
+  else {
+    return Err(format!(
+      "expected the first line to be a Debian changelog entry like `package ({version}) unstable; urgency=medium`, found `{entry}`"
+    ));
+  };
+  if entry_version != version {
+    return Err(format!(
+      "the latest entry is for version {entry_version}, expected the package version {version}"
+    ));
+  }
+  Ok(())
+}
+
+/// Generates the machine-readable copyright file from the license metadata, see
+/// <https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/>
+fn generate_copyright_file(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+  let license = settings.license();
+  let license_file = settings.license_file();
+  if license.is_none() && license_file.is_none() {
+    return Ok(());
+  }
+
+  let mut copyright = String::new();
+  copyright
+    .push_str("Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\n");
+  copyright.push_str(&format!("Upstream-Name: {}\n", settings.product_name()));
+  if let Some(homepage) = settings.homepage_url() {
+    copyright.push_str(&format!("Source: {homepage}\n"));
+  }
+  copyright.push_str("\nFiles: *\n");
+  let holders = settings
+    .copyright_string()
+    .map(ToString::to_string)
+    .or_else(|| settings.authors_comma_separated())
+    .unwrap_or_else(|| settings.product_name().to_string());
+  copyright.push_str(&format!("Copyright: {holders}\n"));
+  copyright.push_str(&format!(
+    "License: {}\n",
+    license.as_deref().unwrap_or("custom")
+  ));
+  if let Some(license_file) = license_file {
+    for line in fs::read_to_string(license_file)?.lines() {
+      if line.trim().is_empty() {
+        copyright.push_str(" .\n");
+      } else {
+        copyright.push_str(&format!(" {line}\n"));
+      }
+    }
+  }
+
+  create_file_with_data(doc_dir(settings, data_dir).join("copyright"), &copyright)
+}
+
+/// The package name, the product name in kebab case.
+fn package_name(settings: &Settings) -> String {
+  heck::AsKebabCase(settings.product_name()).to_string()
+}
+
+/// The `/usr/share/doc/<package>` directory.
+fn doc_dir(settings: &Settings, data_dir: &Path) -> PathBuf {
+  data_dir.join("usr/share/doc").join(package_name(settings))
+}
+
 /// Generates the debian control file and stores it under the `control_dir`.
 fn generate_control_file(
   settings: &Settings,
@@ -157,8 +275,7 @@ fn generate_control_file(
   // https://www.debian.org/doc/debian-policy/ch-controlfields.html
   let dest_path = control_dir.join("control");
   let mut file = common::create_file(&dest_path)?;
-  let package = heck::AsKebabCase(settings.product_name());
-  writeln!(file, "Package: {}", package)?;
+  writeln!(file, "Package: {}", package_name(settings))?;
   writeln!(file, "Version: {}", settings.version_string())?;
   writeln!(file, "Architecture: {arch}")?;
   // Installed-Size must be divided by 1024, see https://www.debian.org/doc/debian-policy/ch-controlfields.html#installed-size
@@ -232,29 +349,47 @@ fn generate_control_file(
 }
 
 fn generate_scripts(settings: &Settings, control_dir: &Path) -> crate::Result<()> {
-  if let Some(script_path) = &settings.deb().pre_install_script {
-    let dest_path = control_dir.join("preinst");
+  for (name, script_path) in maintainer_scripts(settings.deb())? {
+    let dest_path = control_dir.join(name);
     create_script_file_from_path(script_path, &dest_path)?
   }
+  Ok(())
+}
 
-  if let Some(script_path) = &settings.deb().post_install_script {
-    let dest_path = control_dir.join("postinst");
-    create_script_file_from_path(script_path, &dest_path)?
+/// The maintainer scripts of the package, sorted by name, from their dedicated options and [`DebianSettings::maintainer_scripts`].
+fn maintainer_scripts(deb: &DebianSettings) -> crate::Result<BTreeMap<&str, &Path>> {
+  let mut scripts = BTreeMap::new();
+  for (name, config_key, script_path) in [
+    ("preinst", "preInstallScript", &deb.pre_install_script),
+    ("postinst", "postInstallScript", &deb.post_install_script),
+    ("prerm", "preRemoveScript", &deb.pre_remove_script),
+    ("postrm", "postRemoveScript", &deb.post_remove_script),
+  ] {
+    if let Some(script_path) = script_path {
+      scripts.insert(name, script_path.as_path());
+    }
+    if let Some(script_path) = deb.maintainer_scripts.get(name) {
+      if scripts.insert(name, script_path.as_path()).is_some() {
+        return Err(crate::Error::DuplicateDebMaintainerScript {
+          name: name.into(),
+          config_key,
+        });
+      }
+    }
   }
 
-  if let Some(script_path) = &settings.deb().pre_remove_script {
-    let dest_path = control_dir.join("prerm");
-    create_script_file_from_path(script_path, &dest_path)?
+  if let Some(name) = deb
+    .maintainer_scripts
+    .keys()
+    .find(|name| !scripts.contains_key(name.as_str()))
+  {
+    return Err(crate::Error::UnknownDebMaintainerScript(name.clone()));
   }
 
-  if let Some(script_path) = &settings.deb().post_remove_script {
-    let dest_path = control_dir.join("postrm");
-    create_script_file_from_path(script_path, &dest_path)?
-  }
-  Ok(())
+  Ok(scripts)
 }
 
-fn create_script_file_from_path(from: &PathBuf, to: &PathBuf) -> crate::Result<()> {
+fn create_script_file_from_path(from: &Path, to: &Path) -> crate::Result<()> {
   let mut from = File::open(from)?;
   let mut file = OpenOptions::new()
     .create(true)
@@ -263,6 +398,31 @@ fn create_script_file_from_path(from: &PathBuf, to: &PathBuf) -> crate::Result<(
     .mode(0o755)
     .open(to)?;
   std::io::copy(&mut from, &mut file)?;
+  // the mode is only applied to new files and masked by the umask
+  fs::set_permissions(to, fs::Permissions::from_mode(0o755))?;
+  Ok(())
+}
+
+/// Create a `conffiles` file in the `control_dir` listing the files installed under `/etc`,
+/// so dpkg keeps the changes made by the user on upgrades.
+fn generate_conffiles(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
+  let etc_dir = data_dir.join("etc");
+  if !etc_dir.is_dir() {
+    return Ok(());
+  }
+  let mut conffiles = String::new();
+  for entry in WalkDir::new(&etc_dir).sort_by_file_name() {
+    let entry = entry?;
+    if entry.file_type().is_dir() {
+      continue;
+    }
+    let path = Path::new("/").join(entry.path().strip_prefix(data_dir)?);
+    conffiles.push_str(&path.to_string_lossy());
+    conffiles.push('\n');
+  }
+  if !conffiles.is_empty() {
+    create_file_with_data(control_dir.join("conffiles"), &conffiles)?;
+  }
   Ok(())
 }
 
@@ -271,7 +431,7 @@ fn create_script_file_from_path(from: &PathBuf, to: &PathBuf) -> crate::Result<(
 fn generate_md5sums(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
   let md5sums_path = control_dir.join("md5sums");
   let mut md5sums_file = common::create_file(&md5sums_path)?;
-  for entry in WalkDir::new(data_dir) {
+  for entry in WalkDir::new(data_dir).sort_by_file_name() {
     let entry = entry?;
     let path = entry.path();
     if path.is_dir() {
@@ -323,7 +483,7 @@ fn total_dir_size(dir: &Path) -> crate::Result<u64> {
 fn create_tar_from_dir<P: AsRef<Path>, W: Write>(src_dir: P, dest_file: W) -> crate::Result<W> {
   let src_dir = src_dir.as_ref();
   let mut tar_builder = tar::Builder::new(dest_file);
-  for entry in WalkDir::new(src_dir) {
+  for entry in WalkDir::new(src_dir).sort_by_file_name() {
     let entry = entry?;
     let src_path = entry.path();
     if src_path == src_dir {
@@ -346,27 +506,410 @@ fn create_tar_from_dir<P: AsRef<Path>, W: Write>(src_dir: P, dest_file: W) -> cr
   Ok(dest_file)
 }
 
-/// Creates a `.tar.gz` file from the given directory (placing the new file
-/// within the given directory's parent directory), then deletes the original
-/// directory and returns the path to the new file.
-fn tar_and_gzip_dir<P: AsRef<Path>>(src_dir: P) -> crate::Result<PathBuf> {
+/// The extension of the tar files compressed with the given algorithm.
+fn tar_extension(compression: DebCompression) -> &'static str {
+  match compression {
+    DebCompression::Gzip => "tar.gz",
+    DebCompression::Xz => "tar.xz",
+    DebCompression::Zstd => "tar.zst",
+  }
+}
+
+/// Creates a compressed tar file from the given directory (placing the new file
+/// within the given directory's parent directory) and returns the path to the new file.
+///
+/// The file extension matches the compression algorithm, as `dpkg` uses the member name to pick the decompressor.
+fn tar_and_compress_dir<P: AsRef<Path>>(
+  src_dir: P,
+  compression: DebCompression,
+) -> crate::Result<PathBuf> {
   let src_dir = src_dir.as_ref();
-  let dest_path = src_dir.with_extension("tar.gz");
+  let dest_path = src_dir.with_extension(tar_extension(compression));
   let dest_file = common::create_file(&dest_path)?;
-  let gzip_encoder = GzEncoder::new(dest_file, Compression::default());
-  let gzip_encoder = create_tar_from_dir(src_dir, gzip_encoder)?;
-  let mut dest_file = gzip_encoder.finish()?;
+  let mut dest_file = match compression {
+    DebCompression::Gzip => {
+      let encoder = GzEncoder::new(dest_file, Compression::default());
+      create_tar_from_dir(src_dir, encoder)?.finish()?
+    }
+    DebCompression::Xz => {
+      let encoder = xz2::write::XzEncoder::new(dest_file, 6);
+      create_tar_from_dir(src_dir, encoder)?.finish()?
+    }
+    DebCompression::Zstd => {
+      let encoder = zstd::Encoder::new(dest_file, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+      create_tar_from_dir(src_dir, encoder)?.finish()?
+    }
+  };
   dest_file.flush()?;
   Ok(dest_path)
 }
 
+/// Signs the concatenated contents of the package members with `gpg`, like `debsigs` does for the origin signature.
+///
+/// Returns the path to the `_gpgorigin` detached signature, appended to the package as the last member.
+fn sign_members(key: &str, members: &[PathBuf], package_dir: &Path) -> crate::Result<PathBuf> {
+  let signature_path = package_dir.join("_gpgorigin");
+  let data_path = package_dir.join("_gpgorigin.data");
+  let mut data = common::create_file(&data_path)?;
+  for member in members {
+    io::copy(&mut File::open(member)?, &mut data)?;
+  }
+  data.flush()?;
+  drop(data);
+
+  gpg::detach_sign(
+    key,
+    "TAURI_SIGNING_DEB_KEY_PASSPHRASE",
+    &data_path,
+    &signature_path,
+  )?;
+  fs::remove_file(data_path)?;
+  Ok(signature_path)
+}
+
 /// Creates an `ar` archive from the given source files and writes it to the
 /// given destination path.
-fn create_archive(srcs: Vec<PathBuf>, dest: &Path) -> crate::Result<()> {
+///
+/// The entries are owned by root with `0644` permissions, like the ones created by `dpkg-deb`.
+fn create_archive(settings: &Settings, srcs: Vec<PathBuf>, dest: &Path) -> crate::Result<()> {
   let mut builder = ar::Builder::new(common::create_file(dest)?);
   for path in &srcs {
-    builder.append_path(path)?;
+    let stat = fs::metadata(path)?;
+    let file_name = path.file_name().expect("archive entry has no file name");
+    let mut header = ar::Header::new(file_name.as_bytes().to_vec(), stat.len());
+    header.set_mode(0o100644);
+    header.set_mtime(common::clamp_timestamp(settings, stat.mtime() as u64));
+    builder.append(&header, File::open(path)?)?;
   }
   builder.into_inner()?.flush()?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, DebianSettings, PackageSettings, SettingsBuilder};
+  use sha2::{Digest, Sha256};
+  use std::{collections::HashMap, io::Read};
+
+  const CHANGELOG: &str = "my-app (1.0.0) unstable; urgency=medium
+
+  * Initial release.
+
+ -- Jane Doe <jane@example.com>  Mon, 01 Jan 2024 00:00:00 +0000
+";
+
+  fn settings(out_dir: &Path, reproducible: bool) -> Settings {
+    settings_with(
+      out_dir,
+      BundleSettings {
+        reproducible,
+        ..Default::default()
+      },
+    )
+  }
+
+  fn settings_with(out_dir: &Path, bundle_settings: BundleSettings) -> Settings {
+    settings_for_target(out_dir, "x86_64-unknown-linux-gnu", bundle_settings)
+  }
+
+  fn settings_for_target(
+    out_dir: &Path,
+    target: &str,
+    bundle_settings: BundleSettings,
+  ) -> Settings {
+    fs::write(out_dir.join("my-app"), b"\x7fELF my-app binary").unwrap();
+    fs::write(out_dir.join("changelog"), CHANGELOG).unwrap();
+    SettingsBuilder::new()
+      .project_out_directory(out_dir)
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: Some(vec!["Jane Doe".into()]),
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        deb: DebianSettings {
+          changelog: Some(out_dir.join("changelog")),
+          ..bundle_settings.deb
+        },
+        ..bundle_settings
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target(target.into())
+      .build()
+      .unwrap()
+  }
+
+  fn sha256(path: &Path) -> String {
+    hex::encode(Sha256::digest(fs::read(path).unwrap()))
+  }
+
+  #[test]
+  fn reproducible_with_source_date_epoch() {
+    std::env::set_var("SOURCE_DATE_EPOCH", "1700000000");
+
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = settings(tmp.path(), false);
+
+    let mut checksums = Vec::new();
+    for _ in 0..2 {
+      let paths = bundle_project(&settings).unwrap();
+      checksums.push(sha256(&paths[0]));
+      // makes sure the staged files get different modification times
+      std::thread::sleep(std::time::Duration::from_millis(1100));
+    }
+    assert_eq!(checksums[0], checksums[1]);
+  }
+
+  #[test]
+  fn reproducible_pins_mtimes() {
+    // the tests share the environment, so use the same timestamp as the other test
+    std::env::set_var("SOURCE_DATE_EPOCH", "1700000000");
+
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = settings(tmp.path(), true);
+
+    let mut checksums = Vec::new();
+    for mtime in [500_000_000, 600_000_000] {
+      // rewrites the source files with modification times older than `SOURCE_DATE_EPOCH`,
+      // which are only clamped without the reproducible flag
+      for file in ["my-app", "changelog"] {
+        let path = tmp.path().join(file);
+        fs::write(&path, fs::read(&path).unwrap()).unwrap();
+        filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(mtime, 0)).unwrap();
+      }
+      let paths = bundle_project(&settings).unwrap();
+      checksums.push(sha256(&paths[0]));
+    }
+    assert_eq!(checksums[0], checksums[1]);
+  }
+
+  #[test]
+  fn cross_arch_control_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = settings_for_target(tmp.path(), "aarch64-unknown-linux-gnu", Default::default());
+
+    let paths = bundle_project(&settings).unwrap();
+    assert_eq!(
+      paths,
+      vec![tmp.path().join("bundle/deb/My App_1.0.0_arm64.deb")]
+    );
+    let control = fs::read_to_string(
+      tmp
+        .path()
+        .join("bundle/deb/My App_1.0.0_arm64/control/control"),
+    )
+    .unwrap();
+    assert!(control.contains("Architecture: arm64\n"));
+  }
+
+  #[test]
+  fn changelog_version() {
+    assert_eq!(validate_changelog(CHANGELOG, "1.0.0"), Ok(()));
+    assert_eq!(
+      validate_changelog(CHANGELOG, "1.1.0"),
+      Err("the latest entry is for version 1.0.0, expected the package version 1.1.0".into())
+    );
+    assert!(validate_changelog("# Changelog", "1.0.0").is_err());
+    assert!(validate_changelog("\n", "1.0.0").is_err());
+  }
+
+  #[test]
+  fn doc_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(
+      tmp.path().join("LICENSE"),
+      "Permission is granted.\n\nNo warranty.",
+    )
+    .unwrap();
+    let settings = settings_with(
+      tmp.path(),
+      BundleSettings {
+        license: Some("MIT".into()),
+        license_file: Some(tmp.path().join("LICENSE")),
+        ..Default::default()
+      },
+    );
+
+    let data_dir = tmp.path().join("data");
+    generate_changelog_file(&settings, &data_dir).unwrap();
+    generate_copyright_file(&settings, &data_dir).unwrap();
+
+    let doc_dir = data_dir.join("usr/share/doc/my-app");
+    let mut changelog = String::new();
+    flate2::read::GzDecoder::new(File::open(doc_dir.join("changelog.gz")).unwrap())
+      .read_to_string(&mut changelog)
+      .unwrap();
+    assert_eq!(changelog, CHANGELOG);
+    assert_eq!(
+      fs::read_to_string(doc_dir.join("copyright")).unwrap(),
+      "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+Upstream-Name: My App
+
+Files: *
+Copyright: Jane Doe
+License: MIT
+ Permission is granted.
+ .
+ No warranty.
+"
+    );
+  }
+
+  #[test]
+  fn etc_files_are_conffiles() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    let control_dir = tmp.path().join("control");
+    for file in [
+      "etc/my-app/b.conf",
+      "etc/my-app/a.conf",
+      "usr/share/my-app/c.conf",
+    ] {
+      create_file_with_data(data_dir.join(file), "").unwrap();
+    }
+
+    generate_conffiles(&control_dir, &data_dir).unwrap();
+    assert_eq!(
+      fs::read_to_string(control_dir.join("conffiles")).unwrap(),
+      "/etc/my-app/a.conf\n/etc/my-app/b.conf\n"
+    );
+  }
+
+  #[test]
+  fn compression_member_names() {
+    for compression in [
+      DebCompression::Gzip,
+      DebCompression::Xz,
+      DebCompression::Zstd,
+    ] {
+      let tmp = tempfile::tempdir().unwrap();
+      let settings = settings_with(
+        tmp.path(),
+        BundleSettings {
+          deb: DebianSettings {
+            compression,
+            ..Default::default()
+          },
+          ..Default::default()
+        },
+      );
+
+      let paths = bundle_project(&settings).unwrap();
+      let mut archive = ar::Archive::new(File::open(&paths[0]).unwrap());
+      let mut members = Vec::new();
+      while let Some(entry) = archive.next_entry() {
+        let mut entry = entry.unwrap();
+        let name = String::from_utf8(entry.header().identifier().to_vec()).unwrap();
+        if name.starts_with("data.tar") {
+          let decoder: Box<dyn Read + '_> = match compression {
+            DebCompression::Gzip => Box::new(flate2::read::GzDecoder::new(&mut entry)),
+            DebCompression::Xz => Box::new(xz2::read::XzDecoder::new(&mut entry)),
+            DebCompression::Zstd => Box::new(zstd::Decoder::new(&mut entry).unwrap()),
+          };
+          let mut data = tar::Archive::new(decoder);
+          assert!(data
+            .entries()
+            .unwrap()
+            .any(|e| e.unwrap().path().unwrap() == Path::new("usr/bin/my-app")));
+        }
+        members.push(name);
+      }
+
+      let extension = tar_extension(compression);
+      assert_eq!(
+        members,
+        vec![
+          "debian-binary".to_string(),
+          format!("control.{extension}"),
+          format!("data.{extension}"),
+        ]
+      );
+    }
+  }
+
+  #[test]
+  fn maintainer_scripts_in_control() {
+    let tmp = tempfile::tempdir().unwrap();
+    for script in ["postinst.sh", "prerm.sh"] {
+      fs::write(tmp.path().join(script), "#!/bin/sh\nexit 0\n").unwrap();
+    }
+    let settings = settings_with(
+      tmp.path(),
+      BundleSettings {
+        deb: DebianSettings {
+          maintainer_scripts: HashMap::from([
+            ("prerm".into(), tmp.path().join("prerm.sh")),
+            ("postinst".into(), tmp.path().join("postinst.sh")),
+          ]),
+          ..Default::default()
+        },
+        ..Default::default()
+      },
+    );
+
+    let control_dir = tmp.path().join("control");
+    fs::create_dir_all(&control_dir).unwrap();
+    generate_scripts(&settings, &control_dir).unwrap();
+    for script in ["postinst", "prerm"] {
+      let metadata = fs::metadata(control_dir.join(script)).unwrap();
+      assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+    }
+    assert!(!control_dir.join("preinst").exists());
+  }
+
+  #[test]
+  fn maintainer_scripts_validation() {
+    let unknown = DebianSettings {
+      maintainer_scripts: HashMap::from([("postinstall".into(), PathBuf::from("postinst.sh"))]),
+      ..Default::default()
+    };
+    assert!(matches!(
+      super::maintainer_scripts(&unknown),
+      Err(crate::Error::UnknownDebMaintainerScript(name)) if name == "postinstall"
+    ));
+
+    let duplicate = DebianSettings {
+      post_install_script: Some("postinst.sh".into()),
+      maintainer_scripts: HashMap::from([("postinst".into(), PathBuf::from("other.sh"))]),
+      ..Default::default()
+    };
+    assert!(matches!(
+      super::maintainer_scripts(&duplicate),
+      Err(crate::Error::DuplicateDebMaintainerScript { name, config_key: "postInstallScript" })
+        if name == "postinst"
+    ));
+
+    let scripts = DebianSettings {
+      pre_install_script: Some("preinst.sh".into()),
+      maintainer_scripts: HashMap::from([("postrm".into(), PathBuf::from("postrm.sh"))]),
+      ..Default::default()
+    };
+    assert_eq!(
+      super::maintainer_scripts(&scripts)
+        .unwrap()
+        .into_iter()
+        .collect::<Vec<_>>(),
+      [
+        ("postrm", Path::new("postrm.sh")),
+        ("preinst", Path::new("preinst.sh"))
+      ]
+    );
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/deb/My App_1.0.0_amd64/data/usr/bin/my-app"],
+      &["bundle/deb/My App_1.0.0_amd64.deb"],
+      || bundle_project(&settings),
+    );
+  }
+}
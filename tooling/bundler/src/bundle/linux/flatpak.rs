@@ -0,0 +1,295 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Flatpak single-file bundles.
+//!
+//! The application files are staged under a `files` directory mirroring the `/app` prefix
+//! of the sandbox, then a [flatpak-builder manifest] is generated from the bundle settings
+//! to install them, and the result is exported to a `.flatpak` bundle with `flatpak build-bundle`.
+//!
+//! [flatpak-builder manifest]: https://docs.flatpak.org/en/latest/flatpak-builder-command-reference.html#flatpak-manifest
+
+use super::{
+  super::{
+    common::{self, CommandExt},
+    staging,
+  },
+  freedesktop,
+};
+use crate::{PackageType, Settings};
+use anyhow::Context;
+use serde_json::{json, Value};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the Flatpak bundle was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let app_id = app_id(settings)?;
+  let arch = flatpak_arch(settings.binary_arch());
+
+  let base_dir = settings.project_out_directory().join("bundle/flatpak");
+  let package_dir = base_dir.join(app_id);
+  let bundle_name = format!(
+    "{}_{}_{}.flatpak",
+    settings.product_name(),
+    settings.version_string(),
+    arch
+  );
+  let bundle_path = base_dir.join(&bundle_name);
+
+  if staging::dry_run_package(settings, PackageType::Flatpak, &[bundle_path.clone()])? {
+    return Ok(vec![bundle_path]);
+  }
+
+  if Command::new("flatpak-builder")
+    .arg("--version")
+    .output_ok()
+    .is_err()
+  {
+    return Err(crate::Error::GenericError(
+      "`flatpak-builder` was not found, it is required to bundle Flatpak packages. See <https://docs.flatpak.org/en/latest/first-build.html>".into(),
+    ));
+  }
+
+  if package_dir.exists() {
+    fs::remove_dir_all(&package_dir)
+      .with_context(|| format!("Failed to remove old {app_id} Flatpak build"))?;
+  }
+  fs::create_dir_all(&package_dir)?;
+
+  log::info!(action = "Bundling"; "{} ({})", bundle_name, bundle_path.display());
+
+  let files_dir = package_dir.join("files");
+  stage_files(settings, app_id, &files_dir).with_context(|| "Failed to stage Flatpak files")?;
+  common::record_staged_size([&files_dir])?;
+
+  let manifest_path = package_dir.join(format!("{app_id}.json"));
+  fs::write(
+    &manifest_path,
+    serde_json::to_string_pretty(&manifest(settings, app_id))?,
+  )?;
+
+  let build_dir = package_dir.join(".build");
+  let repo_dir = package_dir.join(".repo");
+  let state_dir = package_dir.join(".flatpak-builder");
+
+  Command::new("flatpak-builder")
+    .arg("--force-clean")
+    .arg(format!("--arch={arch}"))
+    .arg("--state-dir")
+    .arg(&state_dir)
+    .arg("--repo")
+    .arg(&repo_dir)
+    .arg(&build_dir)
+    .arg(&manifest_path)
+    .current_dir(&package_dir)
+    .output_ok()
+    .context("error running flatpak-builder")?;
+
+  Command::new("flatpak")
+    .arg("build-bundle")
+    .arg(format!("--arch={arch}"))
+    .arg(&repo_dir)
+    .arg(&bundle_path)
+    .arg(app_id)
+    .output_ok()
+    .context("error running flatpak build-bundle")?;
+
+  // keep the staged files and the manifest so the build can be reproduced with flatpak-builder
+  for dir in [build_dir, repo_dir, state_dir] {
+    fs::remove_dir_all(dir)?;
+  }
+
+  Ok(vec![bundle_path])
+}
+
+/// Returns the Flatpak application id, defaulting to the bundle identifier.
+fn app_id(settings: &Settings) -> crate::Result<&str> {
+  let app_id = settings
+    .flatpak()
+    .app_id
+    .as_deref()
+    .unwrap_or_else(|| settings.bundle_identifier());
+  if is_valid_app_id(app_id) {
+    Ok(app_id)
+  } else {
+    Err(crate::Error::GenericError(format!(
+      "`{app_id}` is not a valid Flatpak application id, it must contain at least three elements separated by `.` made of ASCII letters, digits, `_` and `-`, none starting with a digit. Set `bundle > linux > flatpak > appId` to override it. See <https://docs.flatpak.org/en/latest/conventions.html#application-ids>"
+    )))
+  }
+}
+
+/// Checks the application id against the D-Bus well-known name rules enforced by Flatpak.
+fn is_valid_app_id(app_id: &str) -> bool {
+  let elements: Vec<&str> = app_id.split('.').collect();
+  app_id.len() <= 255
+    && elements.len() >= 3
+    && elements.iter().all(|element| {
+      !element.is_empty()
+        && !element.starts_with(|c: char| c.is_ascii_digit())
+        && element
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    })
+}
+
+/// Maps the Rust target architecture to the Flatpak architecture name.
+fn flatpak_arch(arch: &str) -> &str {
+  match arch {
+    "x86" => "i386",
+    other => other,
+  }
+}
+
+/// Stages the application files under `files_dir`, which is installed as the `/app` prefix.
+fn stage_files(settings: &Settings, app_id: &str, files_dir: &Path) -> crate::Result<()> {
+  let bin_dir = files_dir.join("bin");
+  for bin in settings.binaries() {
+    let bin_path = settings.binary_path(bin);
+    common::copy_file(&bin_path, bin_dir.join(bin.name()))
+      .with_context(|| format!("Failed to copy binary from {bin_path:?}"))?;
+  }
+  settings
+    .copy_binaries(&bin_dir)
+    .with_context(|| "Failed to copy external binaries")?;
+
+  // the resource directory of the app is `/app/lib/<crate name>` inside the sandbox
+  settings
+    .copy_resources(&files_dir.join("lib").join(settings.main_binary_name()))
+    .with_context(|| "Failed to copy resource files")?;
+
+  // Flatpak only exports desktop files, metainfo and icons prefixed with the app id
+  let share_dir = files_dir.join("share");
+  freedesktop::copy_named_icon_files(settings, &share_dir.join("icons/hicolor"), app_id)
+    .with_context(|| "Failed to create icon files")?;
+  freedesktop::write_desktop_file(
+    settings,
+    &settings.flatpak().desktop_template,
+    &share_dir.join(format!("applications/{app_id}.desktop")),
+    app_id,
+  )
+  .with_context(|| "Failed to create desktop file")?;
+  freedesktop::write_metainfo_file(
+    settings,
+    app_id,
+    &share_dir.join(format!("metainfo/{app_id}.metainfo.xml")),
+  )
+  .with_context(|| "Failed to create metainfo file")?;
+
+  common::copy_custom_files(
+    &settings.flatpak().files,
+    files_dir,
+    settings.allow_empty_file_globs(),
+  )
+  .with_context(|| "Failed to copy custom files")?;
+
+  Ok(())
+}
+
+/// Generates the flatpak-builder manifest installing the staged `files` directory.
+fn manifest(settings: &Settings, app_id: &str) -> Value {
+  let flatpak = settings.flatpak();
+
+  let mut modules = flatpak.modules.clone();
+  modules.push(json!({
+    "name": settings.main_binary_name(),
+    "buildsystem": "simple",
+    "build-commands": ["cp -a . /app/"],
+    "sources": [{
+      "type": "dir",
+      "path": "files",
+    }],
+  }));
+
+  json!({
+    "id": app_id,
+    "runtime": flatpak.runtime,
+    "runtime-version": flatpak.runtime_version,
+    "sdk": flatpak.sdk,
+    "command": settings.main_binary_name(),
+    "finish-args": flatpak.finish_args,
+    "modules": modules,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, FlatpakSettings, PackageSettings, SettingsBuilder};
+
+  #[test]
+  fn generates_manifest() {
+    let settings = SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        flatpak: FlatpakSettings {
+          runtime: "org.gnome.Platform".into(),
+          runtime_version: "46".into(),
+          sdk: "org.gnome.Sdk".into(),
+          finish_args: vec!["--share=ipc".into()],
+          modules: vec![json!("shared-modules/libappindicator.json")],
+          ..Default::default()
+        },
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .expect("failed to build settings");
+
+    let manifest = manifest(&settings, app_id(&settings).unwrap());
+    assert_eq!(manifest["id"], "com.example.myapp");
+    assert_eq!(manifest["runtime-version"], "46");
+    assert_eq!(manifest["command"], "my-app");
+    assert_eq!(manifest["finish-args"], json!(["--share=ipc"]));
+
+    let modules = manifest["modules"].as_array().unwrap();
+    assert_eq!(modules.len(), 2);
+    assert_eq!(modules[0], "shared-modules/libappindicator.json");
+    assert_eq!(modules[1]["name"], "my-app");
+    assert_eq!(modules[1]["sources"][0]["path"], "files");
+  }
+
+  #[test]
+  fn validates_app_id() {
+    assert!(is_valid_app_id("com.example.myapp"));
+    assert!(is_valid_app_id("org.example.My_App-2"));
+    assert!(!is_valid_app_id("com.myapp"));
+    assert!(!is_valid_app_id("com..myapp"));
+    assert!(!is_valid_app_id("com.example.2app"));
+    assert!(!is_valid_app_id("com.example.my app"));
+  }
+
+  #[test]
+  fn maps_flatpak_arch() {
+    assert_eq!(flatpak_arch("x86"), "i386");
+    assert_eq!(flatpak_arch("x86_64"), "x86_64");
+    assert_eq!(flatpak_arch("aarch64"), "aarch64");
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/flatpak/com.example.myapp/files/bin/my-app"],
+      &["bundle/flatpak/My App_1.0.0_x86_64.flatpak"],
+      || bundle_project(&settings),
+    );
+  }
+}
@@ -7,12 +7,14 @@
 //! applications for Linux:
 //!
 //! - Generation of [desktop entries] (`.desktop` files)
+//! - Generation of [AppStream metainfo] files
 //! - Copy of icons in the [icons file hierarchy]
 //!
 //! The specifications are developed and hosted at [freedesktop.org].
 //!
 //! [freedesktop.org]: https://www.freedesktop.org
 //! [desktop entries]: https://www.freedesktop.org/wiki/Specifications/desktop-entry-spec/
+//! [AppStream metainfo]: https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html
 //! [icons file hierarchy]: https://specifications.freedesktop.org/icon-theme-spec/icon-theme-spec-latest.html#icon_lookup
 
 use std::collections::BTreeMap;
@@ -32,7 +34,7 @@ use crate::Settings;
 pub struct Icon {
   pub width: u32,
   pub height: u32,
-  pub is_high_density: bool,
+  pub scale: u32,
   pub path: PathBuf,
 }
 
@@ -42,14 +44,31 @@ pub fn list_icon_files(
   settings: &Settings,
   data_dir: &Path,
 ) -> crate::Result<BTreeMap<Icon, PathBuf>> {
-  let base_dir = data_dir.join("usr/share/icons/hicolor");
-  let get_dest_path = |width: u32, height: u32, is_high_density: bool| {
+  list_named_icon_files(
+    settings,
+    &data_dir.join("usr/share/icons/hicolor"),
+    settings.main_binary_name(),
+  )
+}
+
+/// Same as [`list_icon_files`], but places the icons under the given `hicolor` theme directory
+/// using `icon_name` as the file name.
+pub fn list_named_icon_files(
+  settings: &Settings,
+  base_dir: &Path,
+  icon_name: &str,
+) -> crate::Result<BTreeMap<Icon, PathBuf>> {
+  let get_dest_path = |width: u32, height: u32, scale: u32| {
     base_dir.join(format!(
       "{}x{}{}/apps/{}.png",
       width,
       height,
-      if is_high_density { "@2" } else { "" },
-      settings.main_binary_name()
+      if scale > 1 {
+        format!("@{scale}")
+      } else {
+        String::new()
+      },
+      icon_name
     ))
   };
   let mut icons = BTreeMap::new();
@@ -63,12 +82,12 @@ pub fn list_icon_files(
       let decoder = PngDecoder::new(File::open(&icon_path)?)?;
       let width = decoder.dimensions().0;
       let height = decoder.dimensions().1;
-      let is_high_density = common::is_retina(&icon_path);
-      let dest_path = get_dest_path(width, height, is_high_density);
+      let scale = common::icon_scale(&icon_path);
+      let dest_path = get_dest_path(width, height, scale);
       Icon {
         width,
         height,
-        is_high_density,
+        scale,
         path: dest_path,
       }
     };
@@ -80,7 +99,19 @@ pub fn list_icon_files(
 
 /// Generate the icon files and store them under the `data_dir`.
 pub fn copy_icon_files(settings: &Settings, data_dir: &Path) -> crate::Result<Vec<Icon>> {
-  let icons = list_icon_files(settings, data_dir)?;
+  copy_icons(list_icon_files(settings, data_dir)?)
+}
+
+/// Generate the icon files under the given `hicolor` theme directory using `icon_name` as the file name.
+pub fn copy_named_icon_files(
+  settings: &Settings,
+  base_dir: &Path,
+  icon_name: &str,
+) -> crate::Result<Vec<Icon>> {
+  copy_icons(list_named_icon_files(settings, base_dir, icon_name)?)
+}
+
+fn copy_icons(icons: BTreeMap<Icon, PathBuf>) -> crate::Result<Vec<Icon>> {
   for (icon, src) in &icons {
     common::copy_file(src, &icon.path)?;
   }
@@ -101,7 +132,20 @@ pub fn generate_desktop_file(
   let path = PathBuf::from("usr/share/applications").join(desktop_file_name);
   let dest_path = PathBuf::from("/").join(&path);
   let file_path = data_dir.join(&path);
-  let file = &mut common::create_file(&file_path)?;
+  write_desktop_file(settings, custom_template_path, &file_path, bin_name)?;
+
+  Ok((file_path, dest_path))
+}
+
+/// Writes the application desktop file to `file_path`, referencing the given icon name.
+pub fn write_desktop_file(
+  settings: &Settings,
+  custom_template_path: &Option<PathBuf>,
+  file_path: &Path,
+  icon: &str,
+) -> crate::Result<()> {
+  let bin_name = settings.main_binary_name();
+  let file = &mut common::create_file(file_path)?;
 
   let mut handlebars = Handlebars::new();
   handlebars.register_escape_fn(handlebars::no_escape);
@@ -122,10 +166,17 @@ pub fn generate_desktop_file(
     exec: &'a str,
     icon: &'a str,
     name: &'a str,
+    localized_names: Vec<LocalizedName<'a>>,
     mime_type: Option<String>,
     long_description: String,
   }
 
+  #[derive(Serialize)]
+  struct LocalizedName<'a> {
+    locale: String,
+    name: &'a str,
+  }
+
   let mut mime_type: Vec<String> = Vec::new();
 
   if let Some(associations) = settings.file_associations() {
@@ -147,6 +198,17 @@ pub fn generate_desktop_file(
 
   let mime_type = (!mime_type.is_empty()).then_some(mime_type.join(";"));
 
+  // desktop entries use POSIX locale names, e.g. `zh_CN` for `zh-CN`
+  let mut localized_names = settings
+    .display_names()
+    .iter()
+    .map(|(language, name)| LocalizedName {
+      locale: language.replace('-', "_"),
+      name,
+    })
+    .collect::<Vec<_>>();
+  localized_names.sort_by(|a, b| a.locale.cmp(&b.locale));
+
   handlebars.render_to_write(
     "main.desktop",
     &DesktopTemplateParams {
@@ -160,13 +222,105 @@ pub fn generate_desktop_file(
         None
       },
       exec: bin_name,
-      icon: bin_name,
+      icon,
       name: settings.product_name(),
+      localized_names,
       mime_type,
       long_description: settings.long_description().unwrap_or_default().to_string(),
     },
     file,
   )?;
 
-  Ok((file_path, dest_path))
+  Ok(())
+}
+
+/// Writes the AppStream metainfo file of the application identified by `app_id` to `file_path`.
+pub fn write_metainfo_file(
+  settings: &Settings,
+  app_id: &str,
+  file_path: &Path,
+) -> crate::Result<()> {
+  let file = &mut common::create_file(file_path)?;
+
+  let mut handlebars = Handlebars::new();
+  handlebars
+    .register_template_string(
+      "main.metainfo.xml",
+      include_str!("./templates/main.metainfo.xml"),
+    )
+    .with_context(|| "Failed to setup metainfo handlebar template")?;
+
+  #[derive(Serialize)]
+  struct MetainfoTemplateParams<'a> {
+    id: &'a str,
+    name: &'a str,
+    summary: &'a str,
+    description: Option<&'a str>,
+    license: Option<String>,
+    homepage: Option<&'a str>,
+    developer: Option<&'a str>,
+    version: &'a str,
+  }
+
+  // `handlebars` escapes HTML entities by default, which produces valid XML
+  handlebars.render_to_write(
+    "main.metainfo.xml",
+    &MetainfoTemplateParams {
+      id: app_id,
+      name: settings.product_name(),
+      summary: settings.short_description().trim(),
+      description: settings.long_description(),
+      license: settings.license(),
+      homepage: settings.homepage_url(),
+      developer: settings.publisher(),
+      version: settings.version_string(),
+    },
+    file,
+  )?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, SettingsBuilder};
+
+  #[test]
+  fn desktop_file_localized_names() {
+    let settings = SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        display_names: [
+          ("zh-CN".to_string(), "我的应用".to_string()),
+          ("ja".to_string(), "私のアプリ".to_string()),
+        ]
+        .into(),
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .expect("failed to build settings");
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("my-app.desktop");
+    write_desktop_file(&settings, &None, &path, "my-app").unwrap();
+
+    let desktop = read_to_string(&path).unwrap();
+    let lines = desktop.lines().collect::<Vec<_>>();
+    assert!(lines.contains(&"Name=My App"));
+    assert!(lines.contains(&"Name[ja]=私のアプリ"));
+    assert!(lines.contains(&"Name[zh_CN]=我的应用"));
+    assert!(lines.contains(&"Exec=my-app"));
+  }
 }
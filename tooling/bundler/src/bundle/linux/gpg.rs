@@ -0,0 +1,133 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! GPG signing of the Debian and RPM packages.
+
+use super::super::common::CommandExt;
+
+use std::{env, io::Write, path::Path, process::Command};
+
+/// Makes sure the signing tool is installed, checking its `--version`.
+pub fn ensure_tool(tool: &'static str, package: &'static str) -> crate::Result<()> {
+  if Command::new(tool).arg("--version").output_ok().is_err() {
+    return Err(crate::Error::SigningToolNotFound { tool, package });
+  }
+  Ok(())
+}
+
+/// Writes the key passphrase read from the given environment variable to a private temporary file,
+/// so it is not exposed on the command line.
+fn passphrase_file(env_var: &str) -> crate::Result<Option<tempfile::NamedTempFile>> {
+  match env::var(env_var) {
+    Ok(passphrase) => {
+      let mut file = tempfile::NamedTempFile::new()?;
+      file.write_all(passphrase.as_bytes())?;
+      file.flush()?;
+      Ok(Some(file))
+    }
+    Err(_) => Ok(None),
+  }
+}
+
+/// The `gpg` arguments reading the passphrase from the given file without prompting.
+fn passphrase_args(passphrase_file: &Path) -> Vec<String> {
+  vec![
+    "--batch".into(),
+    "--pinentry-mode".into(),
+    "loopback".into(),
+    "--passphrase-file".into(),
+    passphrase_file.to_string_lossy().into_owned(),
+  ]
+}
+
+/// Writes a detached binary signature of `data` to `signature` with the given key.
+pub fn detach_sign(
+  key: &str,
+  passphrase_env_var: &str,
+  data: &Path,
+  signature: &Path,
+) -> crate::Result<()> {
+  ensure_tool("gpg", "deb")?;
+  let passphrase = passphrase_file(passphrase_env_var)?;
+
+  let mut cmd = Command::new("gpg");
+  if let Some(passphrase) = &passphrase {
+    cmd.args(passphrase_args(passphrase.path()));
+  }
+  cmd
+    .arg("--yes")
+    .arg("--local-user")
+    .arg(key)
+    .arg("--output")
+    .arg(signature)
+    .arg("--detach-sign")
+    .arg(data)
+    .output_ok()?;
+  Ok(())
+}
+
+/// The `rpm --addsign` command signing the package with the given key.
+fn rpm_addsign_command(key: &str, package: &Path, passphrase_file: Option<&Path>) -> Command {
+  let mut cmd = Command::new("rpm");
+  cmd.arg("--define").arg(format!("_gpg_name {key}"));
+  if let Some(passphrase_file) = passphrase_file {
+    cmd.arg("--define").arg(format!(
+      "_gpg_sign_cmd_extra_args {}",
+      passphrase_args(passphrase_file).join(" ")
+    ));
+  }
+  cmd.arg("--addsign").arg(package);
+  cmd
+}
+
+/// Signs the RPM package in place with the given key.
+pub fn rpm_addsign(key: &str, passphrase_env_var: &str, package: &Path) -> crate::Result<()> {
+  ensure_tool("rpm", "rpm")?;
+  ensure_tool("gpg", "rpm")?;
+  let passphrase = passphrase_file(passphrase_env_var)?;
+  rpm_addsign_command(key, package, passphrase.as_ref().map(|f| f.path())).output_ok()?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn missing_tool() {
+    assert!(matches!(
+      ensure_tool("tauri-bundler-missing-signing-tool", "deb"),
+      Err(crate::Error::SigningToolNotFound {
+        tool: "tauri-bundler-missing-signing-tool",
+        package: "deb"
+      })
+    ));
+  }
+
+  #[test]
+  fn rpm_addsign_args() {
+    let args = |passphrase_file: Option<&Path>| {
+      rpm_addsign_command("ABCDEF", Path::new("my-app.rpm"), passphrase_file)
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+      args(None),
+      ["--define", "_gpg_name ABCDEF", "--addsign", "my-app.rpm"]
+    );
+    assert_eq!(
+      args(Some(Path::new("/tmp/passphrase"))),
+      [
+        "--define",
+        "_gpg_name ABCDEF",
+        "--define",
+        "_gpg_sign_cmd_extra_args --batch --pinentry-mode loopback --passphrase-file /tmp/passphrase",
+        "--addsign",
+        "my-app.rpm"
+      ]
+    );
+  }
+}
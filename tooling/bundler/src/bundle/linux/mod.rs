@@ -5,5 +5,9 @@
 
 pub mod appimage;
 pub mod debian;
+pub mod flatpak;
 pub mod freedesktop;
+pub mod gpg;
+pub mod pacman;
 pub mod rpm;
+pub mod snap;
@@ -0,0 +1,504 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// The structure of a pacman package looks something like this:
+//
+// foobar-1.2.3-1-x86_64.pkg.tar.zst   # A zstd compressed tar archive
+//     .INSTALL                            # Install scriptlet (optional)
+//     .MTREE                              # Gzipped mtree listing of the files below
+//     .PKGINFO                            # Package metadata
+//     usr/bin/foobar                      # Binary executable file
+//     usr/share/applications/foobar.desktop   # Desktop file (for apps)
+//     usr/share/icons/hicolor/...         # Icon files (for apps)
+//     usr/lib/foobar/...                  # Other resource files
+//
+// The archive is built directly instead of going through `makepkg`,
+// so it can be created on any Linux host.
+
+use super::{
+  super::{common, staging},
+  freedesktop,
+};
+use crate::{PackageType, Settings};
+use anyhow::Context;
+use flate2::{write::GzEncoder, Compression};
+use sha2::{Digest, Sha256};
+use tar::HeaderMode;
+use walkdir::WalkDir;
+
+use std::{
+  fs::{self, File},
+  io::{self, Read, Write},
+  os::unix::fs::MetadataExt,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the pacman package was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let arch = match settings.binary_arch() {
+    "x86" => "i686",
+    "arm" => "armv7h",
+    other => other,
+  };
+  let package_base_name = format!(
+    "{}-{}-{}-{}",
+    settings.product_name(),
+    settings.version_string(),
+    settings.pacman().release,
+    arch
+  );
+  let package_name = format!("{package_base_name}.pkg.tar.zst");
+
+  let base_dir = settings.project_out_directory().join("bundle/pacman");
+  let package_dir = base_dir.join(&package_base_name);
+  let package_path = base_dir.join(&package_name);
+
+  if staging::dry_run_package(settings, PackageType::Pacman, &[package_path.clone()])? {
+    return Ok(vec![package_path]);
+  }
+
+  if package_dir.exists() {
+    fs::remove_dir_all(&package_dir)
+      .with_context(|| format!("Failed to remove old {package_base_name}"))?;
+  }
+
+  log::info!(action = "Bundling"; "{} ({})", package_name, package_path.display());
+
+  let data_dir = package_dir.join("data");
+  generate_data(settings, &data_dir).with_context(|| "Failed to build data folders and files")?;
+
+  let installed_size = total_files_size(&data_dir)?;
+  let build_date = settings.source_date_epoch().unwrap_or_else(|| {
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or_default()
+  });
+  let mut pkginfo = common::create_file(&data_dir.join(".PKGINFO"))?;
+  write_pkginfo(settings, arch, installed_size, build_date, &mut pkginfo)
+    .with_context(|| "Failed to create .PKGINFO file")?;
+  if let Some(install) = install_scriptlet(settings)? {
+    fs::write(data_dir.join(".INSTALL"), install)
+      .with_context(|| "Failed to create .INSTALL file")?;
+  }
+  common::clamp_mtimes(settings, &data_dir)
+    .with_context(|| "Failed to clamp modification times")?;
+  generate_mtree(&data_dir).with_context(|| "Failed to create .MTREE file")?;
+  common::clamp_mtimes(settings, &data_dir)
+    .with_context(|| "Failed to clamp modification times")?;
+  common::record_staged_size([&data_dir])?;
+
+  create_package(&data_dir, &package_path).with_context(|| "Failed to create package archive")?;
+
+  Ok(vec![package_path])
+}
+
+/// Stages the package files under `data_dir`, with the same layout as the Debian package.
+fn generate_data(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+  let bin_dir = data_dir.join("usr/bin");
+
+  for bin in settings.binaries() {
+    let bin_path = settings.binary_path(bin);
+    common::copy_file(&bin_path, bin_dir.join(bin.name()))
+      .with_context(|| format!("Failed to copy binary from {bin_path:?}"))?;
+  }
+
+  settings
+    .copy_resources(&data_dir.join("usr/lib").join(settings.main_binary_name()))
+    .with_context(|| "Failed to copy resource files")?;
+
+  settings
+    .copy_binaries(&bin_dir)
+    .with_context(|| "Failed to copy external binaries")?;
+
+  freedesktop::copy_icon_files(settings, data_dir)
+    .with_context(|| "Failed to create icon files")?;
+  freedesktop::generate_desktop_file(settings, &settings.pacman().desktop_template, data_dir)
+    .with_context(|| "Failed to create desktop file")?;
+
+  common::copy_custom_files(
+    &settings.pacman().files,
+    data_dir,
+    settings.allow_empty_file_globs(),
+  )
+  .with_context(|| "Failed to copy custom files")?;
+
+  Ok(())
+}
+
+/// Writes the `.PKGINFO` package metadata, see the `write_pkginfo` function of `makepkg` for the format.
+fn write_pkginfo<W: Write>(
+  settings: &Settings,
+  arch: &str,
+  installed_size: u64,
+  build_date: u64,
+  file: &mut W,
+) -> crate::Result<()> {
+  let pacman = settings.pacman();
+  let name = heck::AsKebabCase(settings.product_name());
+  // pacman uses `-` to separate the version from the release
+  let version = settings.version_string().replace('-', "_");
+  let description = match settings.short_description().trim() {
+    "" => settings.product_name(),
+    description => description,
+  };
+
+  writeln!(
+    file,
+    "# Generated by tauri-bundler {}",
+    env!("CARGO_PKG_VERSION")
+  )?;
+  writeln!(file, "pkgname = {name}")?;
+  writeln!(file, "pkgbase = {name}")?;
+  writeln!(file, "pkgver = {version}-{}", pacman.release)?;
+  writeln!(file, "pkgdesc = {}", description.replace('\n', " "))?;
+  if let Some(homepage) = settings.homepage_url() {
+    writeln!(file, "url = {homepage}")?;
+  }
+  writeln!(file, "builddate = {build_date}")?;
+  let packager = settings
+    .authors_comma_separated()
+    .unwrap_or_else(|| "Unknown Packager".into());
+  writeln!(file, "packager = {packager}")?;
+  writeln!(file, "size = {installed_size}")?;
+  writeln!(file, "arch = {arch}")?;
+  if let Some(license) = settings.license() {
+    writeln!(file, "license = {license}")?;
+  }
+
+  let relations = [
+    ("replaces", &pacman.replaces),
+    ("conflict", &pacman.conflicts),
+    ("provides", &pacman.provides),
+  ];
+  for (key, packages) in relations {
+    for package in packages.iter().flatten() {
+      writeln!(file, "{key} = {package}")?;
+    }
+  }
+  for dependency in pacman.depends.iter().flatten() {
+    writeln!(file, "depend = {}", pacman_dependency(dependency))?;
+  }
+
+  file.flush()?;
+  Ok(())
+}
+
+/// Converts a dependency from the Debian syntax, e.g. `libfoo (>= 1.0)`, to the pacman syntax, e.g. `libfoo>=1.0`.
+fn pacman_dependency(dependency: &str) -> String {
+  // pacman does not support alternatives, so the first one is required
+  let dependency = dependency.split('|').next().unwrap_or_default().trim();
+  let (name, constraint) = match dependency.split_once('(') {
+    Some((name, constraint)) => (name.trim(), constraint.trim_end_matches(')')),
+    None => (dependency, ""),
+  };
+  // drop the Debian architecture qualifier, e.g. `:any`
+  let name = name.split(':').next().unwrap_or(name);
+  let constraint = constraint
+    .split_whitespace()
+    .collect::<String>()
+    .replace("<<", "<")
+    .replace(">>", ">");
+  format!("{name}{constraint}")
+}
+
+/// Generates the `.INSTALL` scriptlet from the configured install and remove scripts.
+fn install_scriptlet(settings: &Settings) -> crate::Result<Option<String>> {
+  let pacman = settings.pacman();
+  let hooks = [
+    ("pre_install", &pacman.pre_install_script),
+    ("post_install", &pacman.post_install_script),
+    ("pre_remove", &pacman.pre_remove_script),
+    ("post_remove", &pacman.post_remove_script),
+  ];
+
+  let mut scriptlet = String::new();
+  for (function, script_path) in hooks {
+    if let Some(script_path) = script_path {
+      let script = fs::read_to_string(script_path)
+        .with_context(|| format!("Failed to read {function} script {script_path:?}"))?;
+      scriptlet.push_str(&install_function(function, &script));
+    }
+  }
+
+  Ok((!scriptlet.is_empty()).then_some(scriptlet))
+}
+
+/// Wraps a script in a scriptlet function, since pacman sources the `.INSTALL` file and calls the hooks.
+fn install_function(function: &str, script: &str) -> String {
+  let mut body = script
+    .lines()
+    .filter(|line| !line.starts_with("#!"))
+    .map(|line| format!("  {line}\n"))
+    .collect::<String>();
+  // a function body can't be empty
+  if body.trim().is_empty() {
+    body = "  :\n".into();
+  }
+  format!("{function}() {{\n{body}}}\n\n")
+}
+
+/// Creates the gzipped `.MTREE` file listing the attributes and checksums of each package entry.
+fn generate_mtree(data_dir: &Path) -> crate::Result<()> {
+  // the entries are listed before creating the file so it doesn't list itself
+  let entries = package_entries(data_dir)?;
+  let mut mtree = GzEncoder::new(
+    common::create_file(&data_dir.join(".MTREE"))?,
+    Compression::default(),
+  );
+  writeln!(mtree, "#mtree")?;
+  writeln!(mtree, "/set type=file uid=0 gid=0 mode=644")?;
+  for entry in entries {
+    let path = data_dir.join(&entry);
+    let metadata = fs::metadata(&path)?;
+    let entry = mtree_escape(&entry.to_string_lossy());
+    let time = metadata.mtime();
+    if metadata.is_dir() {
+      writeln!(mtree, "./{entry} time={time}.0 mode=755 type=dir")?;
+    } else {
+      write!(mtree, "./{entry} time={time}.0")?;
+      let mode = entry_mode(&metadata);
+      if mode != 0o644 {
+        write!(mtree, " mode={mode:o}")?;
+      }
+      let (md5, sha256) = file_digests(&path)?;
+      writeln!(
+        mtree,
+        " size={} md5digest={md5} sha256digest={sha256}",
+        metadata.len()
+      )?;
+    }
+  }
+  mtree.finish()?.flush()?;
+  Ok(())
+}
+
+/// Escapes a path for an mtree file, which uses octal escapes for whitespace and special characters.
+fn mtree_escape(path: &str) -> String {
+  path
+    .bytes()
+    .map(|byte| {
+      if byte.is_ascii_graphic() && !matches!(byte, b'\\' | b'#' | b'=') {
+        (byte as char).to_string()
+      } else {
+        format!("\\{byte:03o}")
+      }
+    })
+    .collect()
+}
+
+/// Computes the MD5 and SHA-256 checksums of a file.
+fn file_digests(path: &Path) -> io::Result<(String, String)> {
+  let mut file = File::open(path)?;
+  let mut md5 = md5::Context::new();
+  let mut sha256 = Sha256::new();
+  let mut buffer = [0; 8192];
+  loop {
+    let read = file.read(&mut buffer)?;
+    if read == 0 {
+      break;
+    }
+    md5.consume(&buffer[..read]);
+    sha256.update(&buffer[..read]);
+  }
+  Ok((
+    format!("{:x}", md5.compute()),
+    hex::encode(sha256.finalize()),
+  ))
+}
+
+/// The permissions of a package entry, matching the deterministic mode of the tar headers.
+fn entry_mode(metadata: &fs::Metadata) -> u32 {
+  if metadata.is_dir() || metadata.mode() & 0o100 != 0 {
+    0o755
+  } else {
+    0o644
+  }
+}
+
+/// Lists the package entries relative to `data_dir` in a stable order,
+/// which puts the metadata files at the beginning of the archive.
+fn package_entries(data_dir: &Path) -> crate::Result<Vec<PathBuf>> {
+  let mut entries = Vec::new();
+  for entry in WalkDir::new(data_dir).min_depth(1).sort_by_file_name() {
+    let entry = entry?;
+    entries.push(entry.path().strip_prefix(data_dir)?.to_path_buf());
+  }
+  Ok(entries)
+}
+
+/// Computes the total size, in bytes, of the files in the given directory.
+fn total_files_size(dir: &Path) -> crate::Result<u64> {
+  let mut total: u64 = 0;
+  for entry in WalkDir::new(dir) {
+    let metadata = entry?.metadata()?;
+    if !metadata.is_dir() {
+      total += metadata.len();
+    }
+  }
+  Ok(total)
+}
+
+/// Creates the zstd compressed tar archive of the given directory.
+fn create_package(data_dir: &Path, dest: &Path) -> crate::Result<()> {
+  let encoder = zstd::Encoder::new(common::create_file(dest)?, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+  let mut tar_builder = tar::Builder::new(encoder);
+  for entry in package_entries(data_dir)? {
+    let src_path = data_dir.join(&entry);
+    let stat = fs::metadata(&src_path)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_metadata_in_mode(&stat, HeaderMode::Deterministic);
+    header.set_mtime(stat.mtime() as u64);
+
+    if stat.is_dir() {
+      tar_builder.append_data(&mut header, &entry, &mut io::empty())?;
+    } else {
+      let mut src_file = File::open(&src_path)?;
+      tar_builder.append_data(&mut header, &entry, &mut src_file)?;
+    }
+  }
+  tar_builder.into_inner()?.finish()?.flush()?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, PacmanSettings, SettingsBuilder};
+
+  fn settings(pacman: PacmanSettings) -> Settings {
+    SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.2.3-beta.1".into(),
+        description: "An example application".into(),
+        homepage: Some("https://example.com".into()),
+        authors: Some(vec!["Jane Doe".into()]),
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        pacman,
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .expect("failed to build settings")
+  }
+
+  #[test]
+  fn generates_pkginfo() {
+    let settings = settings(PacmanSettings {
+      release: "2".into(),
+      depends: Some(vec!["gtk3".into(), "libfoo (>= 1.0)".into()]),
+      conflicts: Some(vec!["my-app-git".into()]),
+      ..Default::default()
+    });
+
+    let mut pkginfo = Vec::new();
+    write_pkginfo(&settings, "x86_64", 4096, 1700000000, &mut pkginfo).unwrap();
+    let pkginfo = String::from_utf8(pkginfo).unwrap();
+    let lines = pkginfo.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(
+      lines,
+      [
+        "pkgname = my-app",
+        "pkgbase = my-app",
+        "pkgver = 1.2.3_beta.1-2",
+        "pkgdesc = An example application",
+        "url = https://example.com",
+        "builddate = 1700000000",
+        "packager = Jane Doe",
+        "size = 4096",
+        "arch = x86_64",
+        "conflict = my-app-git",
+        "depend = gtk3",
+        "depend = libfoo>=1.0",
+      ]
+    );
+  }
+
+  #[test]
+  fn converts_debian_dependencies() {
+    assert_eq!(pacman_dependency("gtk3"), "gtk3");
+    assert_eq!(pacman_dependency("libfoo (>= 1.0)"), "libfoo>=1.0");
+    assert_eq!(pacman_dependency("libfoo (<< 2)"), "libfoo<2");
+    assert_eq!(pacman_dependency("libfoo (= 1.0-1)"), "libfoo=1.0-1");
+    assert_eq!(pacman_dependency("python3:any (>> 3.8)"), "python3>3.8");
+    assert_eq!(pacman_dependency("libfoo | libbar"), "libfoo");
+  }
+
+  #[test]
+  fn wraps_install_functions() {
+    assert_eq!(
+      install_function("post_install", "#!/bin/sh\necho installed\n"),
+      "post_install() {\n  echo installed\n}\n\n"
+    );
+    assert_eq!(
+      install_function("pre_remove", "#!/bin/sh\n"),
+      "pre_remove() {\n  :\n}\n\n"
+    );
+    assert_eq!(
+      mtree_escape("usr/share/My App#1"),
+      "usr/share/My\\040App\\0431"
+    );
+  }
+
+  #[test]
+  fn creates_package_archive() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(data_dir.join("usr/bin")).unwrap();
+    fs::write(data_dir.join("usr/bin/my-app"), "binary").unwrap();
+    fs::write(data_dir.join(".PKGINFO"), "pkgname = my-app\n").unwrap();
+    generate_mtree(&data_dir).unwrap();
+
+    let package_path = tmp.path().join("my-app.pkg.tar.zst");
+    create_package(&data_dir, &package_path).unwrap();
+
+    let decoder = zstd::Decoder::new(File::open(&package_path).unwrap()).unwrap();
+    let mut archive = tar::Archive::new(decoder);
+    let entries = archive
+      .entries()
+      .unwrap()
+      .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      entries,
+      [".MTREE", ".PKGINFO", "usr", "usr/bin", "usr/bin/my-app"]
+    );
+
+    let mut mtree = String::new();
+    flate2::read::GzDecoder::new(File::open(data_dir.join(".MTREE")).unwrap())
+      .read_to_string(&mut mtree)
+      .unwrap();
+    assert!(mtree.starts_with("#mtree\n"));
+    assert!(mtree.contains("./.PKGINFO time="));
+    assert!(mtree
+      .lines()
+      .any(|line| line.starts_with("./usr/bin time=") && line.ends_with(" mode=755 type=dir")));
+    assert!(mtree.contains(&format!(
+      " size=6 md5digest={:x} sha256digest={}",
+      md5::compute("binary"),
+      hex::encode(Sha256::digest("binary"))
+    )));
+    assert!(!mtree.contains("./.MTREE"));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/pacman/My App-1.0.0--x86_64/data/usr/bin/my-app"],
+      &["bundle/pacman/My App-1.0.0--x86_64.pkg.tar.zst"],
+      || bundle_project(&settings),
+    );
+  }
+}
@@ -3,17 +3,20 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::Settings;
+use crate::{PackageType, RpmSettings, Settings};
 
 use anyhow::Context;
-use rpm::{self, signature::pgp, Dependency, FileMode, FileOptions};
+use rpm::{self, signature::pgp, Dependency, FileMode, FileOptions, FileOptionsBuilder};
 use std::{
   env,
   fs::{self, File},
   path::{Path, PathBuf},
 };
 
-use super::freedesktop;
+use super::{
+  super::{common, staging},
+  freedesktop, gpg,
+};
 
 /// Bundles the project.
 /// Returns a vector of PathBuf that shows where the RPM was created.
@@ -35,12 +38,17 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
 
   let base_dir = settings.project_out_directory().join("bundle/rpm");
   let package_dir = base_dir.join(&package_base_name);
+  let package_path = base_dir.join(&package_name);
+
+  if staging::dry_run_package(settings, PackageType::Rpm, &[package_path.clone()])? {
+    return Ok(vec![package_path]);
+  }
+
   if package_dir.exists() {
     fs::remove_dir_all(&package_dir)
       .with_context(|| format!("Failed to remove old {package_base_name}"))?;
   }
   fs::create_dir_all(&package_dir)?;
-  let package_path = base_dir.join(&package_name);
 
   log::info!(action = "Bundling"; "{} ({})", package_name, package_path.display());
 
@@ -52,6 +60,14 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     // This matches .deb compression. On a 240MB source binary the bundle will be 100KB larger than rpm's default while reducing build times by ~25%.
     .compression(rpm::CompressionWithLevel::Gzip(6));
 
+  // clamps the file modification times and sets the build time, making the package reproducible
+  if let Some(epoch) = settings
+    .source_date_epoch()
+    .and_then(|epoch| u32::try_from(epoch).ok())
+  {
+    builder = builder.source_date(epoch);
+  }
+
   if let Some(description) = settings.long_description() {
     builder = builder.description(description);
   }
@@ -61,41 +77,23 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   }
 
   // Add requirements
-  for dep in settings.rpm().depends.as_ref().cloned().unwrap_or_default() {
-    builder = builder.requires(Dependency::any(dep));
+  for dep in settings.rpm().depends.iter().flatten() {
+    builder = builder.requires(dependency("depends", dep)?);
   }
 
   // Add provides
-  for dep in settings
-    .rpm()
-    .provides
-    .as_ref()
-    .cloned()
-    .unwrap_or_default()
-  {
-    builder = builder.provides(Dependency::any(dep));
+  for dep in settings.rpm().provides.iter().flatten() {
+    builder = builder.provides(dependency("provides", dep)?);
   }
 
   // Add conflicts
-  for dep in settings
-    .rpm()
-    .conflicts
-    .as_ref()
-    .cloned()
-    .unwrap_or_default()
-  {
-    builder = builder.conflicts(Dependency::any(dep));
+  for dep in settings.rpm().conflicts.iter().flatten() {
+    builder = builder.conflicts(dependency("conflicts", dep)?);
   }
 
   // Add obsoletes
-  for dep in settings
-    .rpm()
-    .obsoletes
-    .as_ref()
-    .cloned()
-    .unwrap_or_default()
-  {
-    builder = builder.obsoletes(Dependency::any(dep));
+  for dep in settings.rpm().obsoletes.iter().flatten() {
+    builder = builder.obsoletes(dependency("obsoletes", dep)?);
   }
 
   // Add binaries
@@ -119,28 +117,32 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   }
 
   // Add scripts
-  if let Some(script_path) = &settings.rpm().pre_install_script {
-    let script = fs::read_to_string(script_path)?;
+  let scriptlets = scriptlets(settings.rpm())?;
+  if let Some(script) = scriptlets.pre_install {
     builder = builder.pre_install_script(script);
   }
 
-  if let Some(script_path) = &settings.rpm().post_install_script {
-    let script = fs::read_to_string(script_path)?;
+  if let Some(script) = scriptlets.post_install {
     builder = builder.post_install_script(script);
   }
 
-  if let Some(script_path) = &settings.rpm().pre_remove_script {
-    let script = fs::read_to_string(script_path)?;
+  if let Some(script) = scriptlets.pre_remove {
     builder = builder.pre_uninstall_script(script);
   }
 
-  if let Some(script_path) = &settings.rpm().post_remove_script {
-    let script = fs::read_to_string(script_path)?;
+  if let Some(script) = scriptlets.post_remove {
     builder = builder.post_uninstall_script(script);
   }
 
+  // Add systemd units
+  for unit in &settings.rpm().systemd_units {
+    let dest = Path::new(SYSTEMD_UNITS_DIR).join(unit_name(unit)?);
+    builder = builder.with_file(unit, FileOptions::new(dest.to_string_lossy()))?;
+  }
+
   // Add resources
-  if settings.resource_files().count() > 0 {
+  let resources = settings.resources()?;
+  if !resources.is_empty() {
     let resource_dir = Path::new("/usr/lib").join(settings.main_binary_name());
     // Create an empty file, needed to add a directory to the RPM package
     // (cf https://github.com/rpm-rs/rpm/issues/177)
@@ -152,10 +154,9 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
       FileOptions::new(resource_dir.to_string_lossy()).mode(FileMode::Dir { permissions: 0o755 }),
     )?;
     // Then add the resources files in that directory
-    for src in settings.resource_files() {
-      let src = src?;
-      let dest = resource_dir.join(tauri_utils::resources::resource_relpath(&src));
-      builder = builder.with_file(&src, FileOptions::new(dest.to_string_lossy()))?;
+    for resource in resources {
+      let dest = resource_dir.join(resource.target());
+      builder = builder.with_file(resource.path(), FileOptions::new(dest.to_string_lossy()))?;
     }
   }
 
@@ -173,33 +174,503 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   }
 
   // Add custom files
-  for (rpm_path, src_path) in settings.rpm().files.iter() {
+  let config_files = &settings.rpm().config_files;
+  for (rpm_path, src_path) in
+    common::expand_custom_files(&settings.rpm().files, settings.allow_empty_file_globs())?
+  {
     if src_path.is_file() {
-      builder = builder.with_file(src_path, FileOptions::new(rpm_path.to_string_lossy()))?;
+      builder = builder.with_file(&src_path, custom_file_options(&rpm_path, config_files))?;
     } else {
-      for entry in walkdir::WalkDir::new(src_path) {
+      for entry in walkdir::WalkDir::new(&src_path) {
         let entry_path = entry?.into_path();
         if entry_path.is_file() {
-          let dest_path = rpm_path.join(entry_path.strip_prefix(src_path).unwrap());
+          let dest_path = rpm_path.join(entry_path.strip_prefix(&src_path).unwrap());
           builder =
-            builder.with_file(&entry_path, FileOptions::new(dest_path.to_string_lossy()))?;
+            builder.with_file(&entry_path, custom_file_options(&dest_path, config_files))?;
         }
       }
     }
   }
 
-  let pkg = if let Ok(raw_secret_key) = env::var("TAURI_SIGNING_RPM_KEY") {
-    let mut signer = pgp::Signer::load_from_asc(&raw_secret_key)?;
-    if let Ok(passphrase) = env::var("TAURI_SIGNING_RPM_KEY_PASSPHRASE") {
-      signer = signer.with_key_passphrase(passphrase);
+  let sign_key = settings.rpm().sign_key.as_deref();
+  let pkg = match env::var("TAURI_SIGNING_RPM_KEY") {
+    // the configured GPG key replaces the signature anyway
+    Ok(raw_secret_key) if sign_key.is_none() => {
+      let mut signer = pgp::Signer::load_from_asc(&raw_secret_key)?;
+      if let Ok(passphrase) = env::var("TAURI_SIGNING_RPM_KEY_PASSPHRASE") {
+        signer = signer.with_key_passphrase(passphrase);
+      }
+      builder.build_and_sign(signer)?
     }
-    builder.build_and_sign(signer)?
-  } else {
-    builder.build()?
+    _ => builder.build()?,
   };
 
+  // the files are added from their location instead of being staged
+  let files_size = pkg
+    .metadata
+    .get_file_entries()?
+    .iter()
+    .map(|entry| entry.size as u64)
+    .sum();
+  common::record_staged_bytes(files_size);
+
   let mut f = fs::File::create(&package_path)?;
   pkg.write(&mut f)?;
+  drop(f);
+
+  if let Some(key) = sign_key {
+    log::info!(action = "Signing"; "{} with GPG key {}", package_path.display(), key);
+    gpg::rpm_addsign(key, "TAURI_SIGNING_RPM_KEY_PASSPHRASE", &package_path)?;
+  }
 
   Ok(vec![package_path])
 }
+
+/// Parses a dependency with an optional version constraint such as `libfoo >= 2.0`.
+///
+/// Rich dependencies, e.g. `(libfoo or libbar)`, are passed through as is.
+fn dependency(config_key: &'static str, dep: &str) -> crate::Result<Dependency> {
+  let invalid = || crate::Error::InvalidRpmDependency {
+    config_key,
+    dependency: dep.into(),
+  };
+
+  let dep_trimmed = dep.trim();
+  if dep_trimmed.starts_with('(') {
+    return Ok(Dependency::any(dep_trimmed));
+  }
+
+  let (name, constraint) = match dep_trimmed.find(['<', '>', '=']) {
+    Some(index) => dep_trimmed.split_at(index),
+    None => (dep_trimmed, ""),
+  };
+  let name = name.trim();
+  if name.is_empty() || name.contains(char::is_whitespace) {
+    return Err(invalid());
+  }
+  if constraint.is_empty() {
+    return Ok(Dependency::any(name));
+  }
+
+  let operator_len = constraint
+    .find(|c| !matches!(c, '<' | '>' | '='))
+    .unwrap_or(constraint.len());
+  let (operator, version) = constraint.split_at(operator_len);
+  let version = version.trim();
+  if version.is_empty() || version.contains(char::is_whitespace) {
+    return Err(invalid());
+  }
+
+  match operator {
+    "<" => Ok(Dependency::less(name, version)),
+    "<=" => Ok(Dependency::less_eq(name, version)),
+    "=" | "==" => Ok(Dependency::eq(name, version)),
+    ">=" => Ok(Dependency::greater_eq(name, version)),
+    ">" => Ok(Dependency::greater(name, version)),
+    _ => Err(invalid()),
+  }
+}
+
+/// The directory where the systemd units of the package are installed.
+const SYSTEMD_UNITS_DIR: &str = "/usr/lib/systemd/system";
+
+/// The scriptlets of the package.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Scriptlets {
+  pre_install: Option<String>,
+  post_install: Option<String>,
+  pre_remove: Option<String>,
+  post_remove: Option<String>,
+}
+
+/// Reads the configured scripts and appends the scriptlets managing the systemd units,
+/// expanded from the `%systemd_post` and `%systemd_preun` macros since the package is not built with rpmbuild.
+fn scriptlets(rpm: &RpmSettings) -> crate::Result<Scriptlets> {
+  let mut scriptlets = Scriptlets {
+    pre_install: read_script("preInstallScript", &rpm.pre_install_script)?,
+    post_install: read_script("postInstallScript", &rpm.post_install_script)?,
+    pre_remove: read_script("preRemoveScript", &rpm.pre_remove_script)?,
+    post_remove: read_script("postRemoveScript", &rpm.post_remove_script)?,
+  };
+
+  if !rpm.systemd_units.is_empty() {
+    let units = rpm
+      .systemd_units
+      .iter()
+      .map(|unit| unit_name(unit))
+      .collect::<crate::Result<Vec<_>>>()?
+      .join(" ");
+    // enables the units according to the presets on initial installation
+    scriptlets.post_install =
+      Some(format!(
+      "{}if [ $1 -eq 1 ]; then\n  systemctl --no-reload preset {units} >/dev/null 2>&1 || :\nfi\n",
+      scriptlets.post_install.map(with_newline).unwrap_or_default()
+    ));
+    // stops the units before the package is removed, but not when it is upgraded
+    scriptlets.pre_remove = Some(format!(
+      "if [ $1 -eq 0 ]; then\n  systemctl --no-reload disable --now {units} >/dev/null 2>&1 || :\nfi\n{}",
+      scriptlets.pre_remove.unwrap_or_default()
+    ));
+  }
+
+  Ok(scriptlets)
+}
+
+fn read_script(config_key: &'static str, path: &Option<PathBuf>) -> crate::Result<Option<String>> {
+  let Some(path) = path else {
+    return Ok(None);
+  };
+  if !path.is_file() {
+    return Err(crate::Error::RpmFileNotFound {
+      config_key,
+      path: path.clone(),
+    });
+  }
+  Ok(Some(fs::read_to_string(path)?))
+}
+
+fn with_newline(mut script: String) -> String {
+  if !script.ends_with('\n') {
+    script.push('\n');
+  }
+  script
+}
+
+/// The file name of a systemd unit, which must exist.
+fn unit_name(unit: &Path) -> crate::Result<String> {
+  match unit.file_name() {
+    Some(name) if unit.is_file() => Ok(name.to_string_lossy().into_owned()),
+    _ => Err(crate::Error::RpmFileNotFound {
+      config_key: "systemdUnits",
+      path: unit.to_path_buf(),
+    }),
+  }
+}
+
+/// The options of a custom file, marked as `%config(noreplace)` when it is in one of the `config_files`.
+fn custom_file_options(rpm_path: &Path, config_files: &[PathBuf]) -> FileOptionsBuilder {
+  let options = FileOptions::new(rpm_path.to_string_lossy());
+  if config_files
+    .iter()
+    .any(|config_file| rpm_path.starts_with(config_file))
+  {
+    options.is_config_noreplace()
+  } else {
+    options
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, SettingsBuilder};
+  use sha2::{Digest, Sha256};
+
+  #[test]
+  fn reproducible_with_source_date_epoch() {
+    std::env::set_var("SOURCE_DATE_EPOCH", "1700000000");
+
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("my-app"), b"\x7fELF my-app binary").unwrap();
+    let settings = SettingsBuilder::new()
+      .project_out_directory(tmp.path())
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .unwrap();
+
+    let mut checksums = Vec::new();
+    for _ in 0..2 {
+      let paths = bundle_project(&settings).unwrap();
+      checksums.push(hex::encode(Sha256::digest(fs::read(&paths[0]).unwrap())));
+      // makes sure the staged files get different modification times
+      std::thread::sleep(std::time::Duration::from_millis(1100));
+    }
+    assert_eq!(checksums[0], checksums[1]);
+  }
+
+  #[test]
+  fn cross_arch_package() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("my-app"), b"\x7fELF my-app binary").unwrap();
+    let settings = SettingsBuilder::new()
+      .project_out_directory(tmp.path())
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        rpm: RpmSettings {
+          release: "1".into(),
+          ..Default::default()
+        },
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("aarch64-unknown-linux-gnu".into())
+      .build()
+      .unwrap();
+
+    let paths = bundle_project(&settings).unwrap();
+    assert_eq!(
+      paths,
+      vec![tmp.path().join("bundle/rpm/My App-1.0.0-1.aarch64.rpm")]
+    );
+    let package = rpm::Package::open(&paths[0]).unwrap();
+    assert_eq!(package.metadata.get_arch().unwrap(), "aarch64");
+  }
+
+  #[test]
+  fn dependency_constraints() {
+    for (dep, expected) in [
+      ("libfoo", Dependency::any("libfoo")),
+      ("  libfoo ", Dependency::any("libfoo")),
+      ("libfoo >= 2.0", Dependency::greater_eq("libfoo", "2.0")),
+      ("libfoo>=2.0", Dependency::greater_eq("libfoo", "2.0")),
+      ("libfoo > 2.0-1", Dependency::greater("libfoo", "2.0-1")),
+      ("libfoo <= 1:2.0", Dependency::less_eq("libfoo", "1:2.0")),
+      ("libfoo < 3", Dependency::less("libfoo", "3")),
+      ("libfoo = 2.0", Dependency::eq("libfoo", "2.0")),
+      ("libfoo == 2.0", Dependency::eq("libfoo", "2.0")),
+      (
+        "(libfoo >= 2.0 or libbar)",
+        Dependency::any("(libfoo >= 2.0 or libbar)"),
+      ),
+    ] {
+      assert_eq!(dependency("depends", dep).unwrap(), expected, "{dep}");
+    }
+
+    for dep in [
+      "",
+      ">= 2.0",
+      "libfoo >=",
+      "libfoo => 2.0",
+      "libfoo bar",
+      "libfoo >= 2 .0",
+    ] {
+      assert!(
+        matches!(
+          dependency("provides", dep).unwrap_err(),
+          crate::Error::InvalidRpmDependency { config_key: "provides", dependency } if dependency == dep
+        ),
+        "{dep}"
+      );
+    }
+  }
+
+  #[test]
+  fn package_dependencies() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("my-app"), b"\x7fELF my-app binary").unwrap();
+    let settings = SettingsBuilder::new()
+      .project_out_directory(tmp.path())
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        rpm: RpmSettings {
+          release: "1".into(),
+          depends: Some(vec!["libfoo >= 2.0".into(), "libbar".into()]),
+          provides: Some(vec!["my-app-cli = 1.0.0".into()]),
+          conflicts: Some(vec!["my-app-legacy < 1.0".into()]),
+          ..Default::default()
+        },
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .unwrap();
+
+    let paths = bundle_project(&settings).unwrap();
+    let package = rpm::Package::open(&paths[0]).unwrap();
+
+    let requires = package.metadata.get_requires().unwrap();
+    assert!(requires.contains(&Dependency::greater_eq("libfoo", "2.0")));
+    assert!(requires.contains(&Dependency::any("libbar")));
+    assert!(package
+      .metadata
+      .get_provides()
+      .unwrap()
+      .contains(&Dependency::eq("my-app-cli", "1.0.0")));
+    assert!(package
+      .metadata
+      .get_conflicts()
+      .unwrap()
+      .contains(&Dependency::less("my-app-legacy", "1.0")));
+  }
+
+  #[test]
+  fn systemd_unit_scriptlets() {
+    let tmp = tempfile::tempdir().unwrap();
+    let post_install = tmp.path().join("postinst.sh");
+    fs::write(&post_install, "groupadd -r my-app || :").unwrap();
+    let unit = tmp.path().join("my-app.service");
+    fs::write(&unit, "[Service]").unwrap();
+
+    let scriptlets = scriptlets(&RpmSettings {
+      post_install_script: Some(post_install),
+      systemd_units: vec![unit],
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(
+      scriptlets,
+      Scriptlets {
+        pre_install: None,
+        post_install: Some(
+          "groupadd -r my-app || :\nif [ $1 -eq 1 ]; then\n  systemctl --no-reload preset my-app.service >/dev/null 2>&1 || :\nfi\n".into()
+        ),
+        pre_remove: Some(
+          "if [ $1 -eq 0 ]; then\n  systemctl --no-reload disable --now my-app.service >/dev/null 2>&1 || :\nfi\n".into()
+        ),
+        post_remove: None,
+      }
+    );
+  }
+
+  #[test]
+  fn missing_scripts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let missing = tmp.path().join("missing.sh");
+
+    for (rpm, config_key) in [
+      (
+        RpmSettings {
+          pre_remove_script: Some(missing.clone()),
+          ..Default::default()
+        },
+        "preRemoveScript",
+      ),
+      (
+        RpmSettings {
+          systemd_units: vec![missing.clone()],
+          ..Default::default()
+        },
+        "systemdUnits",
+      ),
+    ] {
+      assert!(matches!(
+        scriptlets(&rpm).unwrap_err(),
+        crate::Error::RpmFileNotFound { config_key: key, path } if key == config_key && path == missing
+      ));
+    }
+  }
+
+  #[test]
+  fn package_scripts_and_config_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("my-app"), b"\x7fELF my-app binary").unwrap();
+    fs::write(tmp.path().join("preinst.sh"), "echo pre-install").unwrap();
+    fs::write(tmp.path().join("my-app.service"), "[Service]").unwrap();
+    fs::write(tmp.path().join("my-app.conf"), "key=value").unwrap();
+    fs::write(tmp.path().join("README"), "readme").unwrap();
+
+    let settings = SettingsBuilder::new()
+      .project_out_directory(tmp.path())
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        rpm: RpmSettings {
+          release: "1".into(),
+          pre_install_script: Some(tmp.path().join("preinst.sh")),
+          systemd_units: vec![tmp.path().join("my-app.service")],
+          files: [
+            (
+              PathBuf::from("/etc/my-app/my-app.conf"),
+              tmp.path().join("my-app.conf"),
+            ),
+            (
+              PathBuf::from("/usr/share/doc/my-app/README"),
+              tmp.path().join("README"),
+            ),
+          ]
+          .into(),
+          config_files: vec!["/etc/my-app".into()],
+          ..Default::default()
+        },
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .unwrap();
+
+    let paths = bundle_project(&settings).unwrap();
+
+    // inspect the package headers with the rpm CLI when it is installed
+    let Ok(scripts) = std::process::Command::new("rpm")
+      .args(["-qp", "--scripts"])
+      .arg(&paths[0])
+      .output()
+    else {
+      return;
+    };
+    let scripts = String::from_utf8_lossy(&scripts.stdout);
+    assert!(scripts.contains("preinstall scriptlet"));
+    assert!(scripts.contains("echo pre-install"));
+    assert!(scripts.contains("systemctl --no-reload preset my-app.service"));
+    assert!(scripts.contains("systemctl --no-reload disable --now my-app.service"));
+
+    let config_files = std::process::Command::new("rpm")
+      .args(["-qp", "--configfiles"])
+      .arg(&paths[0])
+      .output()
+      .unwrap();
+    assert_eq!(
+      String::from_utf8_lossy(&config_files.stdout).trim(),
+      "/etc/my-app/my-app.conf"
+    );
+
+    let files = std::process::Command::new("rpm")
+      .args(["-qpl"])
+      .arg(&paths[0])
+      .output()
+      .unwrap();
+    assert!(String::from_utf8_lossy(&files.stdout)
+      .lines()
+      .any(|file| file == "/usr/lib/systemd/system/my-app.service"));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/rpm/My App-1.0.0-.x86_64/previous"],
+      &["bundle/rpm/My App-1.0.0-.x86_64.rpm"],
+      || bundle_project(&settings),
+    );
+  }
+}
@@ -0,0 +1,362 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Snap packages.
+//!
+//! The application files are staged under a `files` directory mirroring the root of the snap,
+//! then a [snapcraft.yaml] is generated from the bundle settings to dump them into the package,
+//! which is built with `snapcraft pack`.
+//!
+//! [snapcraft.yaml]: https://snapcraft.io/docs/snapcraft-yaml-reference
+
+use super::{
+  super::{
+    common::{self, CommandExt},
+    staging,
+  },
+  freedesktop,
+};
+use crate::{PackageType, Settings};
+use anyhow::Context;
+use serde_json::{json, Value};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+use tauri_utils::config::{SnapConfinement, SnapGrade};
+
+/// The interfaces plugged by every strictly confined application.
+const DEFAULT_PLUGS: &[&str] = &[
+  "network",
+  "desktop",
+  "desktop-legacy",
+  "wayland",
+  "x11",
+  "opengl",
+  "home",
+];
+
+/// The maximum length of the snap `summary`.
+const MAX_SUMMARY_LEN: usize = 78;
+
+/// The maximum length of the snap `name`.
+const MAX_NAME_LEN: usize = 40;
+
+/// The base snap used when `bundle > linux > snap > base` is not set.
+const DEFAULT_BASE: &str = "core22";
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the Snap package was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let name = snap_name(settings.main_binary_name());
+  let arch = match settings.binary_arch() {
+    "x86" => "i386",
+    "x86_64" => "amd64",
+    "arm" => "armhf",
+    "aarch64" => "arm64",
+    other => other,
+  };
+
+  let base_dir = settings.project_out_directory().join("bundle/snap");
+  let package_dir = base_dir.join(&name);
+  let package_name = format!(
+    "{}_{}_{}.snap",
+    settings.product_name(),
+    settings.version_string(),
+    arch
+  );
+  let package_path = base_dir.join(&package_name);
+
+  if staging::dry_run_package(settings, PackageType::Snap, &[package_path.clone()])? {
+    return Ok(vec![package_path]);
+  }
+
+  if Command::new("snapcraft")
+    .arg("--version")
+    .output_ok()
+    .is_err()
+  {
+    return Err(crate::Error::GenericError(
+      "`snapcraft` was not found, it is required to bundle Snap packages. Install it with `sudo snap install snapcraft --classic`, see <https://snapcraft.io/docs/snapcraft-setup>".into(),
+    ));
+  }
+
+  if package_dir.exists() {
+    fs::remove_dir_all(&package_dir)
+      .with_context(|| format!("Failed to remove old {name} Snap build"))?;
+  }
+  fs::create_dir_all(&package_dir)?;
+
+  log::info!(action = "Bundling"; "{} ({})", package_name, package_path.display());
+
+  let files_dir = package_dir.join("files");
+  let icon = stage_files(settings, &files_dir).with_context(|| "Failed to stage Snap files")?;
+  common::record_staged_size([&files_dir])?;
+
+  // YAML is a superset of JSON, so the snapcraft.yaml can be written with serde_json
+  fs::write(
+    package_dir.join("snapcraft.yaml"),
+    serde_json::to_string_pretty(&snapcraft_yaml(settings, &name, icon.as_deref()))?,
+  )?;
+
+  Command::new("snapcraft")
+    .arg("pack")
+    .arg("--output")
+    .arg(&package_path)
+    .current_dir(&package_dir)
+    .output_ok()
+    .context("error running snapcraft")?;
+
+  Ok(vec![package_path])
+}
+
+/// Converts `name` to a valid snap name: lowercase ASCII letters, digits and single hyphens,
+/// up to 40 characters.
+fn snap_name(name: &str) -> String {
+  let mut snap_name = String::new();
+  for c in name.chars() {
+    if c.is_ascii_alphanumeric() {
+      snap_name.push(c.to_ascii_lowercase());
+    } else if !snap_name.is_empty() && !snap_name.ends_with('-') {
+      snap_name.push('-');
+    }
+  }
+  snap_name.truncate(MAX_NAME_LEN);
+  snap_name.trim_end_matches('-').to_string()
+}
+
+/// The snapcraft extension providing the GNOME platform and WebKitGTK for the given base.
+fn gnome_extension(base: &str) -> &'static str {
+  match base {
+    "core18" => "gnome-3-28",
+    "core20" => "gnome-3-38",
+    _ => "gnome",
+  }
+}
+
+/// Stages the application files under `files_dir`, which is the root of the snap.
+///
+/// Returns the path of the snap icon relative to `files_dir`, if any.
+fn stage_files(settings: &Settings, files_dir: &Path) -> crate::Result<Option<String>> {
+  let bin_dir = files_dir.join("usr/bin");
+  for bin in settings.binaries() {
+    let bin_path = settings.binary_path(bin);
+    common::copy_file(&bin_path, bin_dir.join(bin.name()))
+      .with_context(|| format!("Failed to copy binary from {bin_path:?}"))?;
+  }
+  settings
+    .copy_binaries(&bin_dir)
+    .with_context(|| "Failed to copy external binaries")?;
+
+  // the resource directory of the app is `$SNAP/usr/lib/<crate name>`
+  settings
+    .copy_resources(&files_dir.join("usr/lib").join(settings.main_binary_name()))
+    .with_context(|| "Failed to copy resource files")?;
+
+  let icons = freedesktop::copy_icon_files(settings, files_dir)
+    .with_context(|| "Failed to create icon files")?;
+  let icon = icons
+    .iter()
+    .filter(|i| i.width == i.height)
+    .max_by_key(|i| i.width)
+    .map(|i| {
+      i.path
+        .strip_prefix(files_dir)
+        .unwrap()
+        .to_string_lossy()
+        .to_string()
+    });
+
+  // the icon theme of the host is not available to the snap, so the desktop file points to the icon path
+  let bin_name = settings.main_binary_name();
+  freedesktop::write_desktop_file(
+    settings,
+    &settings.snap().desktop_template,
+    &files_dir.join(format!("usr/share/applications/{bin_name}.desktop")),
+    &icon
+      .as_ref()
+      .map(|icon| format!("${{SNAP}}/{icon}"))
+      .unwrap_or_else(|| bin_name.to_string()),
+  )
+  .with_context(|| "Failed to create desktop file")?;
+
+  common::copy_custom_files(
+    &settings.snap().files,
+    files_dir,
+    settings.allow_empty_file_globs(),
+  )
+  .with_context(|| "Failed to copy custom files")?;
+
+  Ok(icon)
+}
+
+/// Generates the snapcraft.yaml dumping the staged `files` directory in the snap.
+fn snapcraft_yaml(settings: &Settings, name: &str, icon: Option<&str>) -> Value {
+  let snap = settings.snap();
+  let bin_name = settings.main_binary_name();
+  let base = snap.base.as_deref().unwrap_or(DEFAULT_BASE);
+
+  let summary = if settings.short_description().is_empty() {
+    settings.product_name()
+  } else {
+    settings.short_description()
+  };
+  let description = settings.long_description().unwrap_or(summary);
+  let summary = match summary.char_indices().nth(MAX_SUMMARY_LEN) {
+    Some((end, _)) => &summary[..end],
+    None => summary,
+  };
+
+  let mut app = json!({
+    "command": format!("usr/bin/{bin_name}"),
+    "desktop": format!("usr/share/applications/{bin_name}.desktop"),
+  });
+  // the GNOME extension provides the WebKitGTK runtime, but is not available to classic snaps
+  if snap.confinement == SnapConfinement::Strict {
+    let mut plugs: Vec<&str> = DEFAULT_PLUGS.to_vec();
+    for plug in &snap.plugs {
+      if !plugs.contains(&plug.as_str()) {
+        plugs.push(plug);
+      }
+    }
+    app["extensions"] = json!([gnome_extension(base)]);
+    app["plugs"] = json!(plugs);
+  }
+
+  let mut yaml = json!({
+    "name": name,
+    "title": settings.product_name(),
+    "version": settings.version_string(),
+    "summary": summary,
+    "description": description,
+    "base": base,
+    "grade": match snap.grade {
+      SnapGrade::Stable => "stable",
+      SnapGrade::Devel => "devel",
+    },
+    "confinement": match snap.confinement {
+      SnapConfinement::Strict => "strict",
+      SnapConfinement::Classic => "classic",
+    },
+    "apps": {
+      name: app,
+    },
+    "parts": {
+      name: {
+        "plugin": "dump",
+        "source": "files",
+      },
+    },
+  });
+  if let Some(icon) = icon {
+    yaml["icon"] = json!(format!("files/{icon}"));
+  }
+
+  yaml
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, SettingsBuilder, SnapSettings};
+
+  fn settings(snap: SnapSettings) -> Settings {
+    SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        snap,
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-unknown-linux-gnu".into())
+      .build()
+      .expect("failed to build settings")
+  }
+
+  #[test]
+  fn generates_snapcraft_yaml() {
+    let settings = settings(SnapSettings {
+      grade: SnapGrade::Devel,
+      plugs: vec!["audio-playback".into(), "network".into()],
+      ..Default::default()
+    });
+
+    let yaml = snapcraft_yaml(&settings, "my-app", Some("usr/share/icons/icon.png"));
+    assert_eq!(yaml["name"], "my-app");
+    assert_eq!(yaml["version"], "1.2.3");
+    assert_eq!(yaml["summary"], "An example application");
+    assert_eq!(yaml["grade"], "devel");
+    assert_eq!(yaml["confinement"], "strict");
+    assert_eq!(yaml["base"], "core22");
+    assert_eq!(yaml["icon"], "files/usr/share/icons/icon.png");
+
+    let app = &yaml["apps"]["my-app"];
+    assert_eq!(app["command"], "usr/bin/my-app");
+    assert_eq!(app["extensions"], json!(["gnome"]));
+    let plugs = app["plugs"].as_array().unwrap();
+    assert_eq!(plugs.len(), DEFAULT_PLUGS.len() + 1);
+    assert_eq!(plugs.last().unwrap(), "audio-playback");
+
+    assert_eq!(yaml["parts"]["my-app"]["source"], "files");
+  }
+
+  #[test]
+  fn classic_confinement_has_no_plugs() {
+    let settings = settings(SnapSettings {
+      confinement: SnapConfinement::Classic,
+      plugs: vec!["audio-playback".into()],
+      ..Default::default()
+    });
+
+    let yaml = snapcraft_yaml(&settings, "my-app", None);
+    assert_eq!(yaml["confinement"], "classic");
+    assert!(yaml.get("icon").is_none());
+    assert!(yaml["apps"]["my-app"].get("plugs").is_none());
+    assert!(yaml["apps"]["my-app"].get("extensions").is_none());
+  }
+
+  #[test]
+  fn picks_gnome_extension_for_base() {
+    let settings = settings(SnapSettings {
+      base: Some("core20".into()),
+      ..Default::default()
+    });
+
+    let yaml = snapcraft_yaml(&settings, "my-app", None);
+    assert_eq!(yaml["base"], "core20");
+    assert_eq!(yaml["apps"]["my-app"]["extensions"], json!(["gnome-3-38"]));
+    assert_eq!(gnome_extension("core24"), "gnome");
+  }
+
+  #[test]
+  fn normalizes_snap_name() {
+    assert_eq!(snap_name("my-app"), "my-app");
+    assert_eq!(snap_name("My_App"), "my-app");
+    assert_eq!(snap_name("__my  app__"), "my-app");
+    assert_eq!(snap_name(&"a".repeat(50)), "a".repeat(40));
+    assert_eq!(snap_name(&format!("{}_b", "a".repeat(39))), "a".repeat(39));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-unknown-linux-gnu");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/snap/my-app/files/usr/bin/my-app"],
+      &["bundle/snap/My App_1.0.0_amd64.snap"],
+      || bundle_project(&settings),
+    );
+  }
+}
@@ -23,17 +23,22 @@
 // files into the `Contents` directory of the bundle.
 
 use super::{
-  super::common::{self, CommandExt},
+  super::{
+    common::{self, CommandExt},
+    staging,
+  },
   icon::create_icns_file,
   sign::{notarize, notarize_auth, sign, NotarizeAuthError, SignTarget},
+  universal,
 };
-use crate::Settings;
+use crate::{PackageType, Settings};
 
 use anyhow::Context;
 
 use std::{
   ffi::OsStr,
   fs,
+  io::Write,
   path::{Path, PathBuf},
   process::Command,
 };
@@ -59,6 +64,14 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     .join("bundle/macos")
     .join(&app_product_name);
 
+  if staging::dry_run_package(
+    settings,
+    PackageType::MacOsBundle,
+    &[app_bundle_path.clone()],
+  )? {
+    return Ok(vec![app_bundle_path]);
+  }
+
   log::info!(action = "Bundling"; "{} ({})", app_product_name, app_bundle_path.display());
 
   if app_bundle_path.exists() {
@@ -83,6 +96,9 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   create_info_plist(&bundle_directory, bundle_icon_file, settings)
     .with_context(|| "Failed to create Info.plist")?;
 
+  create_localized_info_plist_strings(&resources_dir, settings)
+    .with_context(|| "Failed to create localized InfoPlist.strings")?;
+
   let framework_paths = copy_frameworks_to_bundle(&bundle_directory, settings)
     .with_context(|| "Failed to bundle frameworks")?;
   sign_paths.extend(framework_paths);
@@ -118,12 +134,12 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     remove_extra_attr(&app_bundle_path)?;
 
     // sign application
-    let keychain = sign(sign_paths, identity, settings)?;
+    let signer = sign(sign_paths, identity, settings)?;
 
     // notarization is required for distribution
     match notarize_auth() {
       Ok(auth) => {
-        notarize(&keychain, app_bundle_path.clone(), &auth)?;
+        notarize(&signer, app_bundle_path.clone(), &auth)?;
       }
       Err(e) => {
         if matches!(e, NotarizeAuthError::MissingTeamId) {
@@ -155,10 +171,14 @@ fn copy_binaries_to_bundle(
   let mut paths = Vec::new();
   let dest_dir = bundle_directory.join("MacOS");
   for bin in settings.binaries() {
-    let bin_path = settings.binary_path(bin);
     let dest_path = dest_dir.join(bin.name());
-    common::copy_file(&bin_path, &dest_path)
-      .with_context(|| format!("Failed to copy binary from {:?}", bin_path))?;
+    if let Some(inputs) = universal::binary_inputs(settings, bin) {
+      universal::create(&inputs, &dest_path)?;
+    } else {
+      let bin_path = settings.binary_path(bin);
+      common::copy_file(&bin_path, &dest_path)
+        .with_context(|| format!("Failed to copy binary from {:?}", bin_path))?;
+    }
     paths.push(dest_path);
   }
   Ok(paths)
@@ -166,23 +186,35 @@ fn copy_binaries_to_bundle(
 
 /// Copies user-defined files to the app under Contents.
 fn copy_custom_files_to_bundle(bundle_directory: &Path, settings: &Settings) -> crate::Result<()> {
-  for (contents_path, path) in settings.macos().files.iter() {
-    let contents_path = if contents_path.is_absolute() {
-      contents_path.strip_prefix("/").unwrap()
-    } else {
-      contents_path
-    };
-    if path.is_file() {
-      common::copy_file(path, bundle_directory.join(contents_path))
-        .with_context(|| format!("Failed to copy file {:?} to {:?}", path, contents_path))?;
-    } else {
-      common::copy_dir(path, &bundle_directory.join(contents_path))
-        .with_context(|| format!("Failed to copy directory {:?} to {:?}", path, contents_path))?;
-    }
+  common::copy_custom_files(
+    &settings.macos().files,
+    bundle_directory,
+    settings.allow_empty_file_globs(),
+  )
+  .with_context(|| "Failed to copy custom files")?;
+  Ok(())
+}
+
+// Creates a `<language>.lproj/InfoPlist.strings` file for each localized display name.
+fn create_localized_info_plist_strings(
+  resources_dir: &Path,
+  settings: &Settings,
+) -> crate::Result<()> {
+  for (language, name) in settings.display_names() {
+    let path = resources_dir
+      .join(format!("{language}.lproj"))
+      .join("InfoPlist.strings");
+    let mut file = common::create_file(&path)?;
+    file.write_all(info_plist_strings(name).as_bytes())?;
   }
   Ok(())
 }
 
+fn info_plist_strings(display_name: &str) -> String {
+  let display_name = display_name.replace('\\', "\\\\").replace('"', "\\\"");
+  format!("CFBundleDisplayName = \"{display_name}\";\n")
+}
+
 // Creates the Info.plist file.
 fn create_info_plist(
   bundle_dir: &Path,
@@ -191,9 +223,22 @@ fn create_info_plist(
 ) -> crate::Result<()> {
   let format = time::format_description::parse("[year][month][day].[hour][minute][second]")
     .map_err(time::error::Error::from)?;
-  let build_number = time::OffsetDateTime::now_utc()
-    .format(&format)
-    .map_err(time::error::Error::from)?;
+  let build_date = match settings.source_date_epoch() {
+    Some(epoch) => {
+      time::OffsetDateTime::from_unix_timestamp(epoch as i64).map_err(time::error::Error::from)?
+    }
+    None => time::OffsetDateTime::now_utc(),
+  };
+  let (short_version, bundle_version) =
+    tauri_utils::config::version::apple_bundle_versions(settings.version_string(), "macOS")
+      .map_err(anyhow::Error::from)?;
+  // the build number of the version, e.g. from `version > buildNumberEnv`, or the build date
+  let build_number = match bundle_version {
+    Some(bundle_version) => bundle_version,
+    None => build_date
+      .format(&format)
+      .map_err(time::error::Error::from)?,
+  };
 
   let mut plist = plist::Dictionary::new();
   plist.insert("CFBundleDevelopmentRegion".into(), "English".into());
@@ -220,10 +265,7 @@ fn create_info_plist(
   plist.insert("CFBundleInfoDictionaryVersion".into(), "6.0".into());
   plist.insert("CFBundleName".into(), settings.product_name().into());
   plist.insert("CFBundlePackageType".into(), "APPL".into());
-  plist.insert(
-    "CFBundleShortVersionString".into(),
-    settings.version_string().into(),
-  );
+  plist.insert("CFBundleShortVersionString".into(), short_version.into());
   plist.insert("CFBundleVersion".into(), build_number.into());
   plist.insert("CSResourcesFileMapped".into(), true.into());
   if let Some(category) = settings.app_category() {
@@ -232,6 +274,9 @@ fn create_info_plist(
       category.macos_application_category_type().into(),
     );
   }
+  if !settings.display_names().is_empty() {
+    plist.insert("LSHasLocalizedDisplayName".into(), true.into());
+  }
   if let Some(version) = settings.macos().minimum_system_version.clone() {
     plist.insert("LSMinimumSystemVersion".into(), version.into());
   }
@@ -515,3 +560,60 @@ fn add_nested_code_sign_path(src_path: &Path, dest_path: &Path, sign_paths: &mut
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, SettingsBuilder};
+
+  #[test]
+  fn creates_localized_info_plist_strings() {
+    let settings = SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        display_names: [
+          ("zh-CN".to_string(), "我的应用".to_string()),
+          ("ja".to_string(), "私の\"アプリ\"".to_string()),
+        ]
+        .into(),
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("aarch64-apple-darwin".into())
+      .build()
+      .expect("failed to build settings");
+
+    let tmp = tempfile::tempdir().unwrap();
+    create_localized_info_plist_strings(tmp.path(), &settings).unwrap();
+
+    assert_eq!(
+      fs::read_to_string(tmp.path().join("zh-CN.lproj/InfoPlist.strings")).unwrap(),
+      "CFBundleDisplayName = \"我的应用\";\n"
+    );
+    assert_eq!(
+      fs::read_to_string(tmp.path().join("ja.lproj/InfoPlist.strings")).unwrap(),
+      "CFBundleDisplayName = \"私の\\\"アプリ\\\"\";\n"
+    );
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "aarch64-apple-darwin");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["bundle/macos/My App.app/Contents/MacOS/my-app"],
+      &["bundle/macos/My App.app"],
+      || bundle_project(&settings),
+    );
+  }
+}
@@ -5,7 +5,10 @@
 
 use super::{app, icon::create_icns_file};
 use crate::{
-  bundle::{common::CommandExt, Bundle},
+  bundle::{
+    common::{self, CommandExt},
+    staging, Bundle,
+  },
   PackageType, Settings,
 };
 
@@ -14,10 +17,103 @@ use anyhow::Context;
 use std::{
   env,
   fs::{self, write},
-  path::PathBuf,
+  path::{Path, PathBuf},
   process::{Command, Stdio},
 };
 
+/// License agreement formats supported by `hdiutil udifrez`.
+const LICENSE_EXTENSIONS: &[&str] = &["txt", "rtf"];
+/// Background image formats the Finder displays.
+const BACKGROUND_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif"];
+
+/// Returns whether the file has one of the given extensions, ignoring the case.
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+  path
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(|extension| extensions.iter().any(|e| extension.eq_ignore_ascii_case(e)))
+    .unwrap_or(false)
+}
+
+/// Returns the license agreement to attach to the DMG,
+/// failing if `dmg > licensePath` does not exist or has an unsupported format.
+fn license_path(settings: &Settings) -> crate::Result<Option<PathBuf>> {
+  let Some(license_path) = &settings.dmg().license_path else {
+    // keep accepting any `licenseFile` since it is shared with the other bundles
+    return Ok(
+      settings
+        .license_file()
+        .map(|license_file| env::current_dir().map(|dir| dir.join(license_file)))
+        .transpose()?,
+    );
+  };
+
+  if !has_extension(license_path, LICENSE_EXTENSIONS) {
+    return Err(crate::Error::GenericError(format!(
+      "unsupported DMG license agreement {}, expected a {} file",
+      license_path.display(),
+      LICENSE_EXTENSIONS.join("/")
+    )));
+  }
+  let license_path = env::current_dir()?.join(license_path);
+  if !license_path.is_file() {
+    return Err(crate::Error::GenericError(format!(
+      "DMG license agreement {} not found",
+      license_path.display()
+    )));
+  }
+  Ok(Some(license_path))
+}
+
+/// Returns the custom icon of the mounted volume,
+/// failing if `dmg > volumeIcon` does not exist or is not an ICNS file.
+fn volume_icon_path(settings: &Settings) -> crate::Result<Option<PathBuf>> {
+  let Some(volume_icon) = &settings.dmg().volume_icon else {
+    return Ok(None);
+  };
+
+  if !has_extension(volume_icon, &["icns"]) {
+    return Err(crate::Error::GenericError(format!(
+      "unsupported DMG volume icon {}, expected an icns file",
+      volume_icon.display()
+    )));
+  }
+  let volume_icon = env::current_dir()?.join(volume_icon);
+  if !volume_icon.is_file() {
+    return Err(crate::Error::GenericError(format!(
+      "DMG volume icon {} not found",
+      volume_icon.display()
+    )));
+  }
+  Ok(Some(volume_icon))
+}
+
+/// Returns the background image of the DMG window,
+/// failing if `dmg > background` does not exist or has an unsupported format.
+///
+/// Without a background the Finder shows its default window.
+fn background_path(settings: &Settings) -> crate::Result<Option<PathBuf>> {
+  let Some(background) = &settings.dmg().background else {
+    return Ok(None);
+  };
+
+  if !has_extension(background, BACKGROUND_EXTENSIONS) {
+    return Err(crate::Error::GenericError(format!(
+      "unsupported DMG background {}, expected a {} file",
+      background.display(),
+      BACKGROUND_EXTENSIONS.join("/")
+    )));
+  }
+  let background = env::current_dir()?.join(background);
+  if !background.is_file() {
+    return Err(crate::Error::GenericError(format!(
+      "DMG background {} not found",
+      background.display()
+    )));
+  }
+  Ok(Some(background))
+}
+
 pub struct Bundled {
   pub dmg: Vec<PathBuf>,
   pub app: Vec<PathBuf>,
@@ -26,6 +122,11 @@ pub struct Bundled {
 /// Bundles the project.
 /// Returns a vector of PathBuf that shows where the DMG was created.
 pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<Bundled> {
+  // validate the DMG resources before bundling anything
+  let license_path = license_path(settings)?;
+  let volume_icon_path = volume_icon_path(settings)?;
+  let background_path = background_path(settings)?;
+
   // generate the .app bundle if needed
   let app_bundle_paths = if !bundles
     .iter()
@@ -50,6 +151,13 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
   let dmg_name = format!("{}.dmg", &package_base_name);
   let dmg_path = output_path.join(&dmg_name);
 
+  if staging::dry_run_package(settings, PackageType::Dmg, &[dmg_path.clone()])? {
+    return Ok(Bundled {
+      dmg: vec![dmg_path],
+      app: app_bundle_paths,
+    });
+  }
+
   let product_name = settings.product_name();
   let bundle_file_name = format!("{}.app", product_name);
   let bundle_dir = settings.project_out_directory().join("bundle/macos");
@@ -138,29 +246,20 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
     bundle_dmg_cmd.arg(&window_position.1);
   }
 
-  let background_path = if let Some(background_path) = &dmg_settings.background {
-    Some(env::current_dir()?.join(background_path))
-  } else {
-    None
-  };
-
   if let Some(background_path) = &background_path {
     bundle_dmg_cmd.arg("--background");
     bundle_dmg_cmd.arg(background_path);
   }
 
-  let icns_icon_path = create_icns_file(&output_path, settings)?;
+  let icns_icon_path = match volume_icon_path {
+    Some(volume_icon_path) => Some(volume_icon_path),
+    None => create_icns_file(&output_path, settings)?,
+  };
   if let Some(icon) = &icns_icon_path {
     bundle_dmg_cmd.arg("--volicon");
     bundle_dmg_cmd.arg(icon);
   }
 
-  let license_path = if let Some(license_path) = settings.license_file() {
-    Some(env::current_dir()?.join(license_path))
-  } else {
-    None
-  };
-
   if let Some(license_path) = &license_path {
     bundle_dmg_cmd.arg("--eula");
     bundle_dmg_cmd.arg(license_path);
@@ -174,6 +273,8 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
     }
   }
 
+  common::record_staged_size([bundle_dir.join(&bundle_file_name)])?;
+
   log::info!(action = "Running"; "bundle_dmg.sh");
 
   // execute the bundle script
@@ -202,3 +303,138 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
     app: app_bundle_paths,
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    background_path, has_extension, license_path, volume_icon_path, BACKGROUND_EXTENSIONS,
+    LICENSE_EXTENSIONS,
+  };
+  use super::{bundle_project, staging};
+  use crate::{BundleSettings, DmgSettings, PackageSettings, Settings, SettingsBuilder};
+  use std::path::{Path, PathBuf};
+
+  fn settings(dmg: DmgSettings, license_file: Option<PathBuf>) -> Settings {
+    SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        dmg,
+        license_file,
+        ..Default::default()
+      })
+      .target("aarch64-apple-darwin".into())
+      .build()
+      .expect("failed to build settings")
+  }
+
+  #[test]
+  fn license_extensions() {
+    assert!(has_extension(Path::new("EULA.txt"), LICENSE_EXTENSIONS));
+    assert!(has_extension(
+      Path::new("legal/EULA.RTF"),
+      LICENSE_EXTENSIONS
+    ));
+    assert!(!has_extension(Path::new("EULA.pdf"), LICENSE_EXTENSIONS));
+    assert!(!has_extension(Path::new("EULA"), LICENSE_EXTENSIONS));
+    assert!(!has_extension(Path::new("rtf"), LICENSE_EXTENSIONS));
+    assert!(has_extension(
+      Path::new("assets/background.JPEG"),
+      BACKGROUND_EXTENSIONS
+    ));
+    assert!(!has_extension(
+      Path::new("background.tiff"),
+      BACKGROUND_EXTENSIONS
+    ));
+  }
+
+  #[test]
+  fn dmg_resources_are_optional() {
+    let without_resources = settings(Default::default(), None);
+    assert!(license_path(&without_resources).unwrap().is_none());
+    assert!(volume_icon_path(&without_resources).unwrap().is_none());
+    assert!(background_path(&without_resources).unwrap().is_none());
+
+    // the shared license file is used as is
+    let with_license_file = settings(Default::default(), Some("LICENSE".into()));
+    assert!(license_path(&with_license_file)
+      .unwrap()
+      .unwrap()
+      .ends_with("LICENSE"));
+  }
+
+  #[test]
+  fn unsupported_dmg_resources_fail() {
+    let err = license_path(&settings(
+      DmgSettings {
+        license_path: Some("EULA.pdf".into()),
+        ..Default::default()
+      },
+      Some("LICENSE".into()),
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("expected a txt/rtf file"));
+
+    let err = license_path(&settings(
+      DmgSettings {
+        license_path: Some("missing/EULA.rtf".into()),
+        ..Default::default()
+      },
+      None,
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("not found"));
+
+    let err = volume_icon_path(&settings(
+      DmgSettings {
+        volume_icon: Some("icon.png".into()),
+        ..Default::default()
+      },
+      None,
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("expected an icns file"));
+
+    let err = background_path(&settings(
+      DmgSettings {
+        background: Some("background.svg".into()),
+        ..Default::default()
+      },
+      None,
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("expected a png/jpg/jpeg/gif file"));
+
+    let err = background_path(&settings(
+      DmgSettings {
+        background: Some("missing/background.png".into()),
+        ..Default::default()
+      },
+      None,
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("not found"));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "aarch64-apple-darwin");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &[
+        "bundle/dmg/support/previous",
+        "bundle/macos/My App.app/Contents/MacOS/my-app",
+      ],
+      &["bundle/dmg/My App_1.0.0_aarch64.dmg"],
+      || bundle_project(&settings, &[]).map(|bundled| bundled.dmg),
+    );
+  }
+}
@@ -14,6 +14,21 @@ use std::{
 
 use image::GenericImageView;
 
+/// The icons of a complete ICNS file, as their size in points and their density.
+const ICNS_ICONS: [(u32, u32); 11] = [
+  (16, 1),
+  (16, 2),
+  (32, 1),
+  (32, 2),
+  (64, 1),
+  (128, 1),
+  (128, 2),
+  (256, 1),
+  (256, 2),
+  (512, 1),
+  (512, 2),
+];
+
 // Given a list of icon files, try to produce an ICNS file in the out_dir
 // and return the path to it.  Returns `Ok(None)` if no usable icons
 // were provided.
@@ -42,30 +57,34 @@ pub fn create_icns_file(out_dir: &Path, settings: &Settings) -> crate::Result<Op
     family: &mut icns::IconFamily,
   ) -> io::Result<()> {
     // Try to add this image to the icon family.  Ignore images whose sizes
-    // don't map to any ICNS icon type; print warnings and skip images that
-    // fail to encode.
-    match icns::IconType::from_pixel_size_and_density(icon.width(), icon.height(), density) {
-      Some(icon_type) => {
-        if !family.has_icon_with_type(icon_type) {
-          let icon = make_icns_image(icon)?;
-          family.add_icon_with_type(&icon, icon_type)?;
-        }
-        Ok(())
+    // don't map to any ICNS icon type, they are only used to generate the
+    // missing sizes.
+    if let Some(icon_type) =
+      icns::IconType::from_pixel_size_and_density(icon.width(), icon.height(), density)
+    {
+      if !family.has_icon_with_type(icon_type) {
+        let icon = make_icns_image(icon)?;
+        family.add_icon_with_type(&icon, icon_type)?;
       }
-      None => Err(io::Error::new(
-        io::ErrorKind::InvalidData,
-        "No matching IconType",
-      )),
     }
+    Ok(())
   }
 
   let mut images_to_resize: Vec<(image::DynamicImage, u32, u32)> = vec![];
+  let mut largest_icon: Option<(image::DynamicImage, PathBuf)> = None;
   for icon_path in settings.icon_files() {
     let icon_path = icon_path?;
     let icon = image::open(&icon_path)?;
-    let density = if common::is_retina(&icon_path) { 2 } else { 1 };
+    // ICNS only has 1x and 2x icon types
+    let density = common::icon_scale(&icon_path).min(2);
     let (w, h) = icon.dimensions();
     let orig_size = min(w, h);
+    if largest_icon
+      .as_ref()
+      .map_or(true, |(largest, _)| icon_size(largest) < orig_size)
+    {
+      largest_icon.replace((icon.clone(), icon_path));
+    }
     let next_size_down = 2f32.powf((orig_size as f32).log2().floor()) as u32;
     if orig_size > next_size_down {
       images_to_resize.push((icon, next_size_down, density));
@@ -83,6 +102,38 @@ pub fn create_icns_file(out_dir: &Path, settings: &Settings) -> crate::Result<Op
     add_icon_to_family(icon, density, &mut family)?;
   }
 
+  // Generate the missing sizes from the largest image.
+  if let Some((largest, largest_path)) = largest_icon {
+    let largest_size = icon_size(&largest);
+    let mut upscaled = Vec::new();
+    for (size, density) in ICNS_ICONS {
+      let pixels = size * density;
+      let Some(icon_type) = icns::IconType::from_pixel_size_and_density(pixels, pixels, density)
+      else {
+        continue;
+      };
+      if family.has_icon_with_type(icon_type) {
+        continue;
+      }
+      if pixels > largest_size {
+        upscaled.push(if density == 1 {
+          format!("{size}x{size}")
+        } else {
+          format!("{size}x{size}@{density}x")
+        });
+      }
+      let icon = largest.resize_exact(pixels, pixels, image::imageops::FilterType::Lanczos3);
+      add_icon_to_family(icon, density, &mut family)?;
+    }
+    if !upscaled.is_empty() {
+      log::warn!(
+        "The {} icons of the .icns file are upscaled from the {largest_size}x{largest_size} {} and may look blurry, add an icon of at least 1024x1024 to `bundle > icon`",
+        upscaled.join(", "),
+        largest_path.display()
+      );
+    }
+  }
+
   if !family.is_empty() {
     fs::create_dir_all(out_dir)?;
     let mut dest_path = out_dir.to_path_buf();
@@ -98,6 +149,11 @@ pub fn create_icns_file(out_dir: &Path, settings: &Settings) -> crate::Result<Op
   }
 }
 
+// The size of the largest square icon the image can be resized to without upscaling.
+fn icon_size(icon: &image::DynamicImage) -> u32 {
+  min(icon.width(), icon.height())
+}
+
 // Converts an image::DynamicImage into an icns::Image.
 fn make_icns_image(img: image::DynamicImage) -> io::Result<icns::Image> {
   let pixel_format = match img.color() {
@@ -112,3 +168,99 @@ fn make_icns_image(img: image::DynamicImage) -> io::Result<icns::Image> {
   };
   icns::Image::from_data(pixel_format, img.width(), img.height(), img.into_bytes())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleSettings, PackageSettings, SettingsBuilder};
+
+  fn settings(out_dir: &Path, icons: &[PathBuf]) -> Settings {
+    SettingsBuilder::new()
+      .project_out_directory(out_dir)
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        icon: Some(
+          icons
+            .iter()
+            .map(|icon| icon.to_string_lossy().into_owned())
+            .collect(),
+        ),
+        ..Default::default()
+      })
+      .target("aarch64-apple-darwin".into())
+      .build()
+      .expect("failed to build settings")
+  }
+
+  fn write_png(path: &Path, size: u32, color: [u8; 4]) {
+    image::RgbaImage::from_pixel(size, size, image::Rgba(color))
+      .save(path)
+      .unwrap();
+  }
+
+  fn icon_types(icns_path: &Path) -> Vec<icns::IconType> {
+    let family = icns::IconFamily::read(File::open(icns_path).unwrap()).unwrap();
+    family.available_icons()
+  }
+
+  fn expected_icon_types() -> Vec<icns::IconType> {
+    ICNS_ICONS
+      .iter()
+      .filter_map(|&(size, density)| {
+        icns::IconType::from_pixel_size_and_density(size * density, size * density, density)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn generates_missing_sizes_from_single_icon() {
+    let dir = tempfile::tempdir().unwrap();
+    let icon = dir.path().join("icon.png");
+    write_png(&icon, 1024, [255, 0, 0, 255]);
+
+    let icns_path = create_icns_file(&dir.path().join("out"), &settings(dir.path(), &[icon]))
+      .unwrap()
+      .unwrap();
+
+    let icon_types = icon_types(&icns_path);
+    for icon_type in expected_icon_types() {
+      assert!(icon_types.contains(&icon_type), "missing {icon_type:?}");
+    }
+  }
+
+  #[test]
+  fn keeps_provided_sizes() {
+    let dir = tempfile::tempdir().unwrap();
+    let large = dir.path().join("icon.png");
+    write_png(&large, 512, [255, 0, 0, 255]);
+    let small = dir.path().join("32x32.png");
+    write_png(&small, 32, [0, 0, 255, 255]);
+
+    let icns_path = create_icns_file(
+      &dir.path().join("out"),
+      &settings(dir.path(), &[large, small]),
+    )
+    .unwrap()
+    .unwrap();
+
+    let family = icns::IconFamily::read(File::open(&icns_path).unwrap()).unwrap();
+    let icon_type = icns::IconType::from_pixel_size_and_density(32, 32, 1).unwrap();
+    let icon = family.get_icon_with_type(icon_type).unwrap();
+    // the provided 32x32 icon is used instead of being generated from the larger one
+    assert_eq!(&icon.data()[..3], &[0, 0, 255]);
+    // the larger sizes are still generated, upscaling the 512x512 icon
+    for icon_type in expected_icon_types() {
+      assert!(
+        family.has_icon_with_type(icon_type),
+        "missing {icon_type:?}"
+      );
+    }
+  }
+}
@@ -13,7 +13,10 @@
 // See https://developer.apple.com/go/?id=bundle-structure for a full
 // explanation.
 
-use crate::{bundle::common, Settings};
+use crate::{
+  bundle::{common, staging},
+  PackageType, Settings,
+};
 
 use anyhow::Context;
 use image::{codecs::png::PngDecoder, GenericImageView, ImageDecoder};
@@ -38,6 +41,10 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     .join("bundle/ios")
     .join(&app_product_name);
 
+  if staging::dry_run_package(settings, PackageType::IosBundle, &[app_bundle_path.clone()])? {
+    return Ok(vec![app_bundle_path]);
+  }
+
   log::info!(action = "Bundling"; "{} ({})", app_product_name, app_bundle_path.display());
 
   if app_bundle_path.exists() {
@@ -47,11 +54,10 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   fs::create_dir_all(&app_bundle_path)
     .with_context(|| format!("Failed to create bundle directory at {:?}", app_bundle_path))?;
 
-  for src in settings.resource_files() {
-    let src = src?;
-    let dest = app_bundle_path.join(tauri_utils::resources::resource_relpath(&src));
-    common::copy_file(&src, &dest)
-      .with_context(|| format!("Failed to copy resource file {:?}", src))?;
+  for resource in settings.resources()? {
+    let dest = app_bundle_path.join(resource.target());
+    common::copy_file(resource.path(), &dest)
+      .with_context(|| format!("Failed to copy resource file {:?}", resource.path()))?;
   }
 
   let icon_filenames = generate_icon_files(&app_bundle_path, settings)
@@ -72,13 +78,12 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
 fn generate_icon_files(bundle_dir: &Path, settings: &Settings) -> crate::Result<Vec<String>> {
   let mut filenames = Vec::new();
   {
-    let mut get_dest_path = |width: u32, height: u32, is_retina: bool| {
-      let filename = format!(
-        "icon_{}x{}{}.png",
-        width,
-        height,
-        if is_retina { "@2x" } else { "" }
-      );
+    let mut get_dest_path = |width: u32, height: u32, scale: u32| {
+      let filename = if scale > 1 {
+        format!("icon_{width}x{height}@{scale}x.png")
+      } else {
+        format!("icon_{width}x{height}.png")
+      };
       let path = bundle_dir.join(&filename);
       filenames.push(filename);
       path
@@ -93,10 +98,10 @@ fn generate_icon_files(bundle_dir: &Path, settings: &Settings) -> crate::Result<
       let decoder = PngDecoder::new(File::open(&icon_path)?)?;
       let width = decoder.dimensions().0;
       let height = decoder.dimensions().1;
-      let is_retina = common::is_retina(&icon_path);
-      if !sizes.contains(&(width, height, is_retina)) {
-        sizes.insert((width, height, is_retina));
-        let dest_path = get_dest_path(width, height, is_retina);
+      let scale = common::icon_scale(&icon_path);
+      if !sizes.contains(&(width, height, scale)) {
+        sizes.insert((width, height, scale));
+        let dest_path = get_dest_path(width, height, scale);
         common::copy_file(&icon_path, &dest_path)?;
       }
     }
@@ -110,10 +115,10 @@ fn generate_icon_files(bundle_dir: &Path, settings: &Settings) -> crate::Result<
         for icon_type in icon_family.available_icons() {
           let width = icon_type.screen_width();
           let height = icon_type.screen_height();
-          let is_retina = icon_type.pixel_density() > 1;
-          if !sizes.contains(&(width, height, is_retina)) {
-            sizes.insert((width, height, is_retina));
-            let dest_path = get_dest_path(width, height, is_retina);
+          let scale = icon_type.pixel_density();
+          if !sizes.contains(&(width, height, scale)) {
+            sizes.insert((width, height, scale));
+            let dest_path = get_dest_path(width, height, scale);
             let icon = icon_family.get_icon_with_type(icon_type)?;
             icon.write_png(File::create(dest_path)?)?;
           }
@@ -121,10 +126,10 @@ fn generate_icon_files(bundle_dir: &Path, settings: &Settings) -> crate::Result<
       } else {
         let icon = image::open(&icon_path)?;
         let (width, height) = icon.dimensions();
-        let is_retina = common::is_retina(&icon_path);
-        if !sizes.contains(&(width, height, is_retina)) {
-          sizes.insert((width, height, is_retina));
-          let dest_path = get_dest_path(width, height, is_retina);
+        let scale = common::icon_scale(&icon_path);
+        if !sizes.contains(&(width, height, scale)) {
+          sizes.insert((width, height, scale));
+          let dest_path = get_dest_path(width, height, scale);
           icon.write_to(
             &mut common::create_file(&dest_path)?,
             image::ImageOutputFormat::Png,
@@ -172,15 +177,17 @@ fn generate_info_plist(
     "  <key>CFBundleExecutable</key>\n  <string>{}</string>",
     settings.main_binary_name()
   )?;
+  let (short_version, bundle_version) =
+    tauri_utils::config::version::apple_bundle_versions(settings.version_string(), "iOS")
+      .map_err(anyhow::Error::from)?;
   writeln!(
     file,
     "  <key>CFBundleVersion</key>\n  <string>{}</string>",
-    settings.version_string()
+    bundle_version.as_deref().unwrap_or(&short_version)
   )?;
   writeln!(
     file,
-    "  <key>CFBundleShortVersionString</key>\n  <string>{}</string>",
-    settings.version_string()
+    "  <key>CFBundleShortVersionString</key>\n  <string>{short_version}</string>",
   )?;
   writeln!(
     file,
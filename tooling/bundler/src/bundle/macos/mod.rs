@@ -7,4 +7,8 @@ pub mod app;
 pub mod dmg;
 pub mod icon;
 pub mod ios;
+pub mod notarytool;
+pub mod pkg;
+pub mod rcodesign;
 pub mod sign;
+pub mod universal;
@@ -0,0 +1,246 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Notarization with `xcrun notarytool`, polling the submission status so the progress shows up in the logs.
+
+use std::{
+  ffi::OsStr,
+  path::Path,
+  process::Command,
+  thread::sleep,
+  time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use serde::{de::DeserializeOwned, Deserialize};
+use tauri_macos_sign::{AppleNotarizationCredentials, Keychain, NotarytoolCmdExt};
+
+use crate::bundle::common::CommandExt;
+
+/// Environment variable setting the maximum number of seconds to wait for the notarization to finish.
+pub const NOTARIZATION_TIMEOUT_ENV_VAR: &str = "APPLE_NOTARIZATION_TIMEOUT";
+
+/// Delay between two notarization status checks.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The JSON output of `notarytool submit` and `notarytool info`.
+#[derive(Debug, Deserialize)]
+struct Submission {
+  id: String,
+  /// Only set by `notarytool info`.
+  status: Option<String>,
+}
+
+/// The status of a notarization submission.
+#[derive(Debug, PartialEq, Eq)]
+enum Status {
+  InProgress,
+  Accepted,
+  /// `Invalid` or `Rejected`.
+  Failed,
+}
+
+impl From<&str> for Status {
+  fn from(status: &str) -> Self {
+    match status {
+      "In Progress" => Self::InProgress,
+      "Accepted" => Self::Accepted,
+      _ => Self::Failed,
+    }
+  }
+}
+
+/// Reads the notarization timeout from the [`NOTARIZATION_TIMEOUT_ENV_VAR`] value.
+pub fn notarization_timeout(value: Option<&OsStr>) -> crate::Result<Option<Duration>> {
+  let Some(value) = value else {
+    return Ok(None);
+  };
+  let value = value.to_string_lossy();
+  value
+    .trim()
+    .parse()
+    .map(|secs| Some(Duration::from_secs(secs)))
+    .map_err(|_| {
+      anyhow::anyhow!(
+        "`{NOTARIZATION_TIMEOUT_ENV_VAR}` must be a number of seconds, found `{value}`"
+      )
+      .into()
+    })
+}
+
+/// Submits the app bundle to Apple's notarization service, waits for the result and staples the ticket to the bundle.
+///
+/// When the submission is not accepted, the notarization log is fetched and included in the error.
+pub fn notarize(
+  keychain: &Keychain,
+  app_bundle_path: &Path,
+  credentials: &AppleNotarizationCredentials,
+  timeout: Option<Duration>,
+) -> crate::Result<()> {
+  let bundle_stem = app_bundle_path
+    .file_stem()
+    .expect("failed to get bundle filename");
+
+  let tmp_dir = tempfile::tempdir()?;
+  let zip_path = tmp_dir
+    .path()
+    .join(format!("{}.zip", bundle_stem.to_string_lossy()));
+
+  // use ditto to create a PKZip almost identical to Finder
+  // this removes almost 99% of false alarms in notarization
+  Command::new("ditto")
+    .args(["-c", "-k", "--keepParent", "--sequesterRsrc"])
+    .arg(app_bundle_path)
+    .arg(&zip_path)
+    .output_ok()
+    .context("failed to zip app with ditto")?;
+
+  keychain.sign(&zip_path, None, false)?;
+
+  log::info!(action = "Notarizing"; "{}", app_bundle_path.display());
+  let submission: Submission = notarytool(
+    &[OsStr::new("submit"), zip_path.as_os_str()],
+    credentials,
+    tmp_dir.path(),
+  )
+  .context("failed to upload app to Apple's notarization servers")?;
+  log::info!("Notarization submission id: {}", submission.id);
+
+  let start = Instant::now();
+  let mut last_status = None;
+  let status = loop {
+    let info: Submission = notarytool(
+      &[OsStr::new("info"), OsStr::new(&submission.id)],
+      credentials,
+      tmp_dir.path(),
+    )
+    .with_context(|| format!("failed to get the status of notarization {}", submission.id))?;
+    let status = info.status.unwrap_or_default();
+
+    if last_status.as_ref() != Some(&status) {
+      log::info!("Notarization {} status: {status}", submission.id);
+    }
+    if Status::from(status.as_str()) != Status::InProgress {
+      break status;
+    }
+
+    let delay = match timeout {
+      Some(timeout) => match timeout.checked_sub(start.elapsed()) {
+        Some(remaining) if !remaining.is_zero() => remaining.min(POLL_INTERVAL),
+        _ => {
+          return Err(crate::Error::NotarizationTimeout {
+            id: submission.id,
+            timeout,
+          })
+        }
+      },
+      None => POLL_INTERVAL,
+    };
+    last_status.replace(status);
+    sleep(delay);
+  };
+
+  if Status::from(status.as_str()) == Status::Accepted {
+    staple(app_bundle_path)
+  } else {
+    let log = Command::new("xcrun")
+      .args(["notarytool", "log"])
+      .arg(&submission.id)
+      .notarytool_args(credentials, tmp_dir.path())?
+      .output_ok()
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+      .unwrap_or_else(|e| {
+        log::warn!("failed to fetch the notarization log: {e}");
+        String::new()
+      });
+    Err(crate::Error::NotarizationFailed {
+      id: submission.id,
+      status,
+      log,
+    })
+  }
+}
+
+/// Runs `xcrun notarytool` and parses its JSON output.
+fn notarytool<T: DeserializeOwned>(
+  args: &[&OsStr],
+  credentials: &AppleNotarizationCredentials,
+  tmp_dir: &Path,
+) -> crate::Result<T> {
+  let output = Command::new("xcrun")
+    .arg("notarytool")
+    .args(args)
+    .args(["--output-format", "json"])
+    .notarytool_args(credentials, tmp_dir)?
+    .output_ok()?;
+  parse_output(&output.stdout)
+}
+
+fn parse_output<T: DeserializeOwned>(stdout: &[u8]) -> crate::Result<T> {
+  serde_json::from_slice(stdout)
+    .with_context(|| {
+      format!(
+        "failed to parse notarytool output as JSON: `{}`",
+        String::from_utf8_lossy(stdout)
+      )
+    })
+    .map_err(Into::into)
+}
+
+fn staple(app_bundle_path: &Path) -> crate::Result<()> {
+  let (Some(dir), Some(filename)) = (app_bundle_path.parent(), app_bundle_path.file_name()) else {
+    return Err(anyhow::anyhow!("invalid app bundle path {}", app_bundle_path.display()).into());
+  };
+
+  Command::new("xcrun")
+    .args(["stapler", "staple", "-v"])
+    .arg(filename)
+    .current_dir(dir)
+    .output_ok()
+    .context("failed to staple app")?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{notarization_timeout, parse_output, Status, Submission};
+  use std::{ffi::OsStr, time::Duration};
+
+  #[test]
+  fn parses_notarytool_output() {
+    let submitted: Submission = parse_output(
+      br#"{"id":"2efe2717-52ef-43a5-96dc-0797e4ca1041","message":"Successfully uploaded file","path":"/tmp/app.zip"}"#,
+    )
+    .unwrap();
+    assert_eq!(submitted.id, "2efe2717-52ef-43a5-96dc-0797e4ca1041");
+    assert_eq!(submitted.status, None);
+
+    let info: Submission = parse_output(
+      br#"{"createdDate":"2024-05-13T10:00:00.000Z","id":"2efe2717-52ef-43a5-96dc-0797e4ca1041","message":"Successfully received submission info","name":"app.zip","status":"In Progress"}"#,
+    )
+    .unwrap();
+    assert_eq!(info.status.as_deref(), Some("In Progress"));
+
+    assert!(parse_output::<Submission>(b"Error: HTTP status code: 401").is_err());
+  }
+
+  #[test]
+  fn submission_status() {
+    assert_eq!(Status::from("In Progress"), Status::InProgress);
+    assert_eq!(Status::from("Accepted"), Status::Accepted);
+    assert_eq!(Status::from("Invalid"), Status::Failed);
+    assert_eq!(Status::from("Rejected"), Status::Failed);
+  }
+
+  #[test]
+  fn reads_notarization_timeout() {
+    assert_eq!(notarization_timeout(None).unwrap(), None);
+    assert_eq!(
+      notarization_timeout(Some(OsStr::new("900"))).unwrap(),
+      Some(Duration::from_secs(900))
+    );
+    assert!(notarization_timeout(Some(OsStr::new("15m"))).is_err());
+  }
+}
@@ -0,0 +1,299 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! macOS installer packages (.pkg), wrapping the app bundle with `pkgbuild` and `productbuild`.
+
+use super::app;
+use crate::{
+  bundle::{
+    common::{self, CommandExt},
+    output::artifact_arch,
+    staging, Bundle,
+  },
+  PackageType, Settings,
+};
+
+use anyhow::Context;
+
+use std::{
+  env,
+  fs::{self, Permissions},
+  os::unix::fs::PermissionsExt,
+  path::PathBuf,
+  process::Command,
+};
+
+pub struct Bundled {
+  pub pkg: Vec<PathBuf>,
+  pub app: Vec<PathBuf>,
+}
+
+/// Returns the installer scripts along with the name `pkgbuild` expects,
+/// failing if `pkg > preinstallScript` or `pkg > postinstallScript` does not exist.
+fn scripts(settings: &Settings) -> crate::Result<Vec<(&'static str, PathBuf)>> {
+  let pkg = settings.pkg();
+  let mut scripts = Vec::new();
+  for (name, config_key, path) in [
+    ("preinstall", "preinstallScript", &pkg.preinstall_script),
+    ("postinstall", "postinstallScript", &pkg.postinstall_script),
+  ] {
+    let Some(path) = path else {
+      continue;
+    };
+    let path = env::current_dir()?.join(path);
+    if !path.is_file() {
+      return Err(crate::Error::PkgScriptNotFound { config_key, path });
+    }
+    scripts.push((name, path));
+  }
+  Ok(scripts)
+}
+
+/// Whether the output of `security find-identity` lists `identity`,
+/// either by the SHA-1 hash or by a part of the name of the certificate like `productsign` does.
+fn has_signing_identity(identities: &str, identity: &str) -> bool {
+  identities.lines().any(|line| {
+    // `  1) 0123456789ABCDEF0123456789ABCDEF01234567 "Developer ID Installer: Name (TEAMID)"`
+    let mut parts = line.trim().splitn(3, ' ');
+    let (Some(_), Some(hash), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+      return false;
+    };
+    hash.eq_ignore_ascii_case(identity)
+      || name
+        .trim()
+        .strip_prefix('"')
+        .and_then(|name| name.split('"').next())
+        .map_or(false, |name| name.contains(identity))
+  })
+}
+
+/// Checks that the installer signing identity is in the keychain, so it fails before anything is bundled.
+fn ensure_signing_identity(identity: &str) -> crate::Result<()> {
+  let output = Command::new("security")
+    .args(["find-identity", "-v"])
+    .output_ok()
+    .context("failed to list the signing identities")?;
+  if has_signing_identity(&String::from_utf8_lossy(&output.stdout), identity) {
+    Ok(())
+  } else {
+    Err(crate::Error::PkgSigningIdentityNotFound(identity.into()))
+  }
+}
+
+/// Bundles the project.
+/// Returns the path of the installer package and of the app bundle if it had to be created.
+pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<Bundled> {
+  let pkg_settings = settings.pkg();
+
+  // validate the configuration before bundling anything
+  let scripts = scripts(settings)?;
+  if let Some(identity) = &pkg_settings.signing_identity {
+    // dry runs do not run any tool
+    if !common::is_dry_run() {
+      ensure_signing_identity(identity)?;
+    }
+  }
+
+  // reuse the .app bundle if it was already created
+  let existing_app = bundles
+    .iter()
+    .filter(|bundle| bundle.package_type == PackageType::MacOsBundle)
+    .flat_map(|bundle| bundle.bundle_paths.iter())
+    .find(|path| path.extension().map_or(false, |ext| ext == "app"))
+    .cloned();
+  let (app_bundle_path, app_bundle_paths) = match existing_app {
+    Some(path) => (path, Vec::new()),
+    None => {
+      let paths = app::bundle_project(settings)?;
+      let path = paths
+        .iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "app"))
+        .cloned()
+        .unwrap_or_else(|| {
+          settings
+            .project_out_directory()
+            .join("bundle/macos")
+            .join(format!("{}.app", settings.product_name()))
+        });
+      (path, paths)
+    }
+  };
+
+  let output_path = settings.project_out_directory().join("bundle/pkg");
+  let pkg_name = format!(
+    "{}_{}_{}.pkg",
+    settings.product_name(),
+    settings.version_string(),
+    artifact_arch(settings, PackageType::MacOsPkg)
+  );
+  let pkg_path = output_path.join(&pkg_name);
+
+  if staging::dry_run_package(settings, PackageType::MacOsPkg, &[pkg_path.clone()])? {
+    return Ok(Bundled {
+      pkg: vec![pkg_path],
+      app: app_bundle_paths,
+    });
+  }
+
+  if !app_bundle_path.is_dir() {
+    return Err(crate::Error::PkgAppBundleNotFound(app_bundle_path));
+  }
+  common::record_staged_size([&app_bundle_path])?;
+
+  if output_path.exists() {
+    fs::remove_dir_all(&output_path).with_context(|| format!("Failed to remove old {pkg_name}"))?;
+  }
+  let work_dir = output_path.join("work");
+  fs::create_dir_all(&work_dir)
+    .with_context(|| format!("Failed to create output directory at {work_dir:?}"))?;
+
+  log::info!(action = "Bundling"; "{} ({})", pkg_name, pkg_path.display());
+
+  let mut pkgbuild = Command::new("pkgbuild");
+  pkgbuild
+    .arg("--component")
+    .arg(&app_bundle_path)
+    .arg("--install-location")
+    .arg(&pkg_settings.install_location)
+    .arg("--identifier")
+    .arg(
+      pkg_settings
+        .identifier
+        .as_deref()
+        .unwrap_or_else(|| settings.bundle_identifier()),
+    )
+    .arg("--version")
+    .arg(settings.version_string());
+
+  if !scripts.is_empty() {
+    let scripts_dir = work_dir.join("scripts");
+    fs::create_dir_all(&scripts_dir)?;
+    for (name, path) in &scripts {
+      let script = scripts_dir.join(name);
+      fs::copy(path, &script)
+        .with_context(|| format!("Failed to copy the {name} script {}", path.display()))?;
+      fs::set_permissions(&script, Permissions::from_mode(0o755))?;
+    }
+    pkgbuild.arg("--scripts").arg(&scripts_dir);
+  }
+
+  let component_path = work_dir.join("component.pkg");
+  log::info!(action = "Running"; "pkgbuild");
+  pkgbuild
+    .arg(&component_path)
+    .output_ok()
+    .context("error running pkgbuild")?;
+
+  // wrap the component in a product archive, the format expected by the installers and MDM solutions
+  let unsigned_path = if pkg_settings.signing_identity.is_some() {
+    work_dir.join(&pkg_name)
+  } else {
+    pkg_path.clone()
+  };
+  log::info!(action = "Running"; "productbuild");
+  Command::new("productbuild")
+    .arg("--package")
+    .arg(&component_path)
+    .arg(&unsigned_path)
+    .output_ok()
+    .context("error running productbuild")?;
+
+  if let Some(identity) = &pkg_settings.signing_identity {
+    log::info!(action = "Signing"; "{} with identity \"{}\"", pkg_name, identity);
+    Command::new("productsign")
+      .arg("--sign")
+      .arg(identity)
+      .arg(&unsigned_path)
+      .arg(&pkg_path)
+      .output_ok()
+      .context("error running productsign")?;
+  }
+
+  if let Err(e) = fs::remove_dir_all(&work_dir) {
+    log::warn!("failed to remove {}: {e}", work_dir.display());
+  }
+
+  Ok(Bundled {
+    pkg: vec![pkg_path],
+    app: app_bundle_paths,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{bundle_project, staging};
+  use super::{has_signing_identity, scripts};
+  use crate::{BundleSettings, PackageSettings, PkgSettings, SettingsBuilder};
+
+  const IDENTITIES: &str = r#"  1) 0123456789ABCDEF0123456789ABCDEF01234567 "Developer ID Application: Tauri (TEAMID)"
+  2) 89ABCDEF0123456789ABCDEF0123456789ABCDEF "Developer ID Installer: Tauri (TEAMID)"
+     2 valid identities found
+"#;
+
+  #[test]
+  fn finds_signing_identity() {
+    assert!(has_signing_identity(
+      IDENTITIES,
+      "Developer ID Installer: Tauri (TEAMID)"
+    ));
+    assert!(has_signing_identity(IDENTITIES, "Developer ID Installer"));
+    assert!(has_signing_identity(
+      IDENTITIES,
+      "89abcdef0123456789abcdef0123456789abcdef"
+    ));
+    assert!(!has_signing_identity(
+      IDENTITIES,
+      "3rd Party Mac Developer Installer"
+    ));
+    assert!(!has_signing_identity("", "Developer ID Installer"));
+  }
+
+  #[test]
+  fn missing_script_fails() {
+    let settings = SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        pkg: PkgSettings {
+          postinstall_script: Some("missing/postinstall.sh".into()),
+          ..Default::default()
+        },
+        ..Default::default()
+      })
+      .target("aarch64-apple-darwin".into())
+      .build()
+      .expect("failed to build settings");
+
+    let err = scripts(&settings).unwrap_err();
+    assert!(matches!(
+      err,
+      crate::Error::PkgScriptNotFound {
+        config_key: "postinstallScript",
+        ..
+      }
+    ));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "aarch64-apple-darwin");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &[
+        "bundle/pkg/work/previous",
+        "bundle/macos/My App.app/Contents/MacOS/my-app",
+      ],
+      &["bundle/pkg/My App_1.0.0_aarch64.pkg"],
+      || bundle_project(&settings, &[]).map(|bundled| bundled.pkg),
+    );
+  }
+}
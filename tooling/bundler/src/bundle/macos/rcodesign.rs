@@ -0,0 +1,287 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Signing and notarization with [rcodesign](https://github.com/indygreg/apple-platform-rs/tree/main/apple-codesign),
+//! which does not require Xcode.
+
+use std::{
+  ffi::{OsStr, OsString},
+  path::{Path, PathBuf},
+  process::Command,
+  time::Duration,
+};
+
+use anyhow::Context;
+use base64::Engine;
+use tauri_macos_sign::{ApiKey, AppleNotarizationCredentials};
+
+use super::sign::SignTarget;
+use crate::bundle::common::CommandExt;
+
+/// A PKCS#12 certificate written to a temporary directory for the `rcodesign` commands.
+pub struct Certificate {
+  // keeps the certificate files until the bundles are signed
+  _dir: tempfile::TempDir,
+  p12_path: PathBuf,
+  password_path: PathBuf,
+}
+
+impl Certificate {
+  /// Decodes the base64 encoded certificate of the `APPLE_CERTIFICATE` environment variable.
+  pub fn from_base64(certificate_encoded: &OsStr, password: &OsStr) -> crate::Result<Self> {
+    // the encoded certificate is often wrapped
+    let certificate_encoded = certificate_encoded
+      .to_string_lossy()
+      .split_whitespace()
+      .collect::<String>();
+    let certificate = base64::engine::general_purpose::STANDARD
+      .decode(certificate_encoded)
+      .context("failed to decode the APPLE_CERTIFICATE certificate")?;
+
+    let dir = tempfile::tempdir()?;
+    let p12_path = dir.path().join("certificate.p12");
+    let password_path = dir.path().join("certificate-password");
+    std::fs::write(&p12_path, certificate)?;
+    std::fs::write(&password_path, password.to_string_lossy().as_bytes())?;
+
+    Ok(Self {
+      _dir: dir,
+      p12_path,
+      password_path,
+    })
+  }
+}
+
+/// Signs the targets with rcodesign.
+///
+/// rcodesign signs the nested code of a bundle along with the bundle,
+/// so the targets inside another target are signed through the settings of the outer target
+/// scoped to their path, see <https://gregoryszorc.com/docs/apple-codesign/main/apple_codesign_concepts.html#settings-scope>.
+pub fn sign(
+  certificate: &Certificate,
+  targets: &[SignTarget],
+  entitlements: Option<&Path>,
+  hardened_runtime: bool,
+) -> crate::Result<()> {
+  let rcodesign = rcodesign_path()?;
+  for target in targets {
+    if targets
+      .iter()
+      .any(|outer| outer.path != target.path && target.path.starts_with(&outer.path))
+    {
+      continue;
+    }
+
+    log::info!(action = "Signing"; "{} with rcodesign", target.path.display());
+    Command::new(&rcodesign)
+      .args(sign_args(
+        certificate,
+        target,
+        targets,
+        entitlements,
+        hardened_runtime,
+      ))
+      .output_ok()
+      .with_context(|| format!("failed to sign {} with rcodesign", target.path.display()))?;
+  }
+  Ok(())
+}
+
+/// The `rcodesign sign` arguments of a target, including the settings of the targets nested in it.
+fn sign_args(
+  certificate: &Certificate,
+  target: &SignTarget,
+  targets: &[SignTarget],
+  entitlements: Option<&Path>,
+  hardened_runtime: bool,
+) -> Vec<OsString> {
+  let mut args: Vec<OsString> = vec![
+    "sign".into(),
+    "--p12-file".into(),
+    certificate.p12_path.clone().into(),
+    "--p12-password-file".into(),
+    certificate.password_path.clone().into(),
+  ];
+
+  let mut scoped_settings = |scope: Option<&Path>, is_an_executable: bool| {
+    let scoped = |value: &OsStr| match scope {
+      Some(scope) => {
+        let mut scoped = scope.as_os_str().to_os_string();
+        scoped.push(":");
+        scoped.push(value);
+        scoped
+      }
+      None => value.to_os_string(),
+    };
+    if let Some(entitlements) = entitlements {
+      args.push("--entitlements-xml-file".into());
+      args.push(scoped(entitlements.as_os_str()));
+    }
+    if is_an_executable && hardened_runtime {
+      args.push("--code-signature-flags".into());
+      args.push(scoped(OsStr::new("runtime")));
+    }
+  };
+
+  scoped_settings(None, target.is_an_executable);
+  for nested in targets {
+    if nested.path != target.path {
+      if let Ok(scope) = nested.path.strip_prefix(&target.path) {
+        scoped_settings(Some(scope), nested.is_an_executable);
+      }
+    }
+  }
+
+  args.push(target.path.clone().into());
+  args
+}
+
+/// Notarizes the bundle with rcodesign and staples the notarization ticket to it.
+///
+/// rcodesign only authenticates with an App Store Connect API key.
+pub fn notarize(
+  app_bundle_path: &Path,
+  credentials: &AppleNotarizationCredentials,
+  timeout: Option<Duration>,
+) -> crate::Result<()> {
+  let AppleNotarizationCredentials::ApiKey {
+    issuer,
+    key_id,
+    key,
+  } = credentials
+  else {
+    return Err(anyhow::anyhow!(
+      "rcodesign can only notarize with an App Store Connect API key, set the APPLE_API_KEY, APPLE_API_ISSUER and APPLE_API_KEY_PATH environment variables instead of APPLE_ID and APPLE_PASSWORD"
+    )
+    .into());
+  };
+  let rcodesign = rcodesign_path()?;
+
+  let dir = tempfile::tempdir()?;
+  let key_path = match key {
+    ApiKey::Path(path) => path.clone(),
+    ApiKey::Raw(key) => {
+      let path = dir.path().join("AuthKey.p8");
+      std::fs::write(&path, key)?;
+      path
+    }
+  };
+  let api_key_path = dir.path().join("api-key.json");
+  Command::new(&rcodesign)
+    .arg("encode-app-store-connect-api-key")
+    .arg("-o")
+    .arg(&api_key_path)
+    .arg(issuer)
+    .arg(key_id)
+    .arg(&key_path)
+    .output_ok()
+    .context("failed to encode the App Store Connect API key with rcodesign")?;
+
+  log::info!(action = "Notarizing"; "{} with rcodesign", app_bundle_path.display());
+  let mut cmd = Command::new(&rcodesign);
+  cmd
+    .arg("notary-submit")
+    .arg("--api-key-path")
+    .arg(&api_key_path)
+    .args(["--wait", "--staple"])
+    .arg(app_bundle_path);
+  let output = match timeout {
+    Some(timeout) => cmd.output_ok_with_timeout(timeout),
+    None => cmd.output_ok(),
+  };
+  output.with_context(|| {
+    format!(
+      "failed to notarize {} with rcodesign",
+      app_bundle_path.display()
+    )
+  })?;
+
+  Ok(())
+}
+
+/// Returns whether the program is in `PATH`.
+pub fn is_in_path(program: &str) -> bool {
+  find_in_path(program).is_some()
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+  std::env::var_os("PATH").and_then(|paths| {
+    std::env::split_paths(&paths)
+      .map(|dir| dir.join(program))
+      .find(|path| path.is_file())
+  })
+}
+
+fn rcodesign_path() -> crate::Result<PathBuf> {
+  find_in_path("rcodesign").ok_or_else(|| {
+    anyhow::anyhow!(
+      "the rcodesign signing backend is selected but `rcodesign` was not found in PATH, install it with `cargo install apple-codesign`"
+    )
+    .into()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{sign_args, Certificate, SignTarget};
+  use std::{ffi::OsString, path::Path};
+
+  fn target(path: &str, is_an_executable: bool) -> SignTarget {
+    SignTarget {
+      path: path.into(),
+      is_an_executable,
+    }
+  }
+
+  #[test]
+  fn nested_targets_are_scoped() {
+    let certificate = Certificate::from_base64("Y2VydA==\n".as_ref(), "password".as_ref()).unwrap();
+    assert_eq!(std::fs::read(&certificate.p12_path).unwrap(), b"cert");
+
+    let targets = [
+      target("/out/App.app/Contents/Frameworks/Lib.framework", false),
+      target("/out/App.app/Contents/MacOS/sidecar", true),
+      target("/out/App.app", true),
+    ];
+    let args = sign_args(
+      &certificate,
+      &targets[2],
+      &targets,
+      Some(Path::new("entitlements.plist")),
+      true,
+    );
+
+    let mut expected: Vec<OsString> = vec![
+      "sign".into(),
+      "--p12-file".into(),
+      certificate.p12_path.clone().into(),
+      "--p12-password-file".into(),
+      certificate.password_path.clone().into(),
+    ];
+    expected.extend(
+      [
+        "--entitlements-xml-file",
+        "entitlements.plist",
+        "--code-signature-flags",
+        "runtime",
+        "--entitlements-xml-file",
+        "Contents/Frameworks/Lib.framework:entitlements.plist",
+        "--entitlements-xml-file",
+        "Contents/MacOS/sidecar:entitlements.plist",
+        "--code-signature-flags",
+        "Contents/MacOS/sidecar:runtime",
+        "/out/App.app",
+      ]
+      .map(OsString::from),
+    );
+    assert_eq!(args, expected);
+
+    let args = sign_args(&certificate, &targets[1], &targets[1..2], None, false);
+    assert_eq!(
+      args.last().map(OsString::as_os_str),
+      Some("/out/App.app/Contents/MacOS/sidecar".as_ref())
+    );
+    assert!(!args.iter().any(|arg| arg == "--code-signature-flags"));
+  }
+}
@@ -9,19 +9,99 @@ use std::{
   path::{Path, PathBuf},
 };
 
-use crate::Settings;
+use super::{notarytool, rcodesign};
+use crate::{MacSigningBackend, Settings};
 
 pub struct SignTarget {
   pub path: PathBuf,
   pub is_an_executable: bool,
 }
 
+/// The signing backend that signed the bundle, reused to notarize it.
+pub enum Signer {
+  Codesign(tauri_macos_sign::Keychain),
+  Rcodesign(rcodesign::Certificate),
+}
+
 pub fn sign(
   targets: Vec<SignTarget>,
   identity: &str,
   settings: &Settings,
+) -> crate::Result<Signer> {
+  let entitlements = settings.macos().entitlements.as_ref().map(Path::new);
+  if let Some(entitlements) = entitlements {
+    validate_entitlements(entitlements)?;
+  }
+
+  let backend = signing_backend(
+    settings.macos().signing_backend,
+    rcodesign::is_in_path("codesign"),
+    var_os("APPLE_CERTIFICATE").is_some(),
+  );
+  match backend {
+    MacSigningBackend::Codesign => {
+      sign_with_codesign(targets, identity, entitlements, settings).map(Signer::Codesign)
+    }
+    MacSigningBackend::Rcodesign => {
+      log::info!(action = "Signing"; "with the APPLE_CERTIFICATE certificate using rcodesign");
+      let (Some(certificate_encoded), Some(certificate_password)) = (
+        var_os("APPLE_CERTIFICATE"),
+        var_os("APPLE_CERTIFICATE_PASSWORD"),
+      ) else {
+        return Err(anyhow::anyhow!(
+          "the rcodesign signing backend requires the APPLE_CERTIFICATE and APPLE_CERTIFICATE_PASSWORD environment variables"
+        )
+        .into());
+      };
+      let certificate =
+        rcodesign::Certificate::from_base64(&certificate_encoded, &certificate_password)?;
+      rcodesign::sign(
+        &certificate,
+        &targets,
+        entitlements,
+        settings.macos().hardened_runtime,
+      )?;
+      Ok(Signer::Rcodesign(certificate))
+    }
+  }
+}
+
+/// Selects the configured signing backend,
+/// or rcodesign when `codesign` is not installed and a certificate is provided.
+fn signing_backend(
+  configured: Option<MacSigningBackend>,
+  codesign_available: bool,
+  has_certificate: bool,
+) -> MacSigningBackend {
+  match configured {
+    Some(backend) => backend,
+    None if !codesign_available && has_certificate => MacSigningBackend::Rcodesign,
+    None => MacSigningBackend::Codesign,
+  }
+}
+
+/// Checks that the entitlements file is a property list, so a typo fails before any binary is signed.
+fn validate_entitlements(path: &Path) -> crate::Result<()> {
+  let invalid = |reason: String| crate::Error::InvalidEntitlements {
+    path: path.to_path_buf(),
+    reason,
+  };
+  let entitlements = plist::Value::from_file(path).map_err(|e| invalid(e.to_string()))?;
+  if entitlements.as_dictionary().is_none() {
+    return Err(invalid(
+      "expected a dictionary of entitlements at the root".into(),
+    ));
+  }
+  Ok(())
+}
+
+fn sign_with_codesign(
+  targets: Vec<SignTarget>,
+  identity: &str,
+  entitlements: Option<&Path>,
+  settings: &Settings,
 ) -> crate::Result<tauri_macos_sign::Keychain> {
-  log::info!(action = "Signing"; "with identity \"{}\"", identity);
+  log::info!(action = "Signing"; "with identity \"{}\" using codesign", identity);
 
   let keychain = if let (Some(certificate_encoded), Some(certificate_password)) = (
     var_os("APPLE_CERTIFICATE"),
@@ -39,7 +119,7 @@ pub fn sign(
   for target in targets {
     keychain.sign(
       &target.path,
-      settings.macos().entitlements.as_ref().map(Path::new),
+      entitlements,
       target.is_an_executable && settings.macos().hardened_runtime,
     )?;
   }
@@ -48,11 +128,18 @@ pub fn sign(
 }
 
 pub fn notarize(
-  keychain: &tauri_macos_sign::Keychain,
+  signer: &Signer,
   app_bundle_path: PathBuf,
   credentials: &tauri_macos_sign::AppleNotarizationCredentials,
 ) -> crate::Result<()> {
-  tauri_macos_sign::notarize(keychain, &app_bundle_path, credentials).map_err(Into::into)
+  let timeout =
+    notarytool::notarization_timeout(var_os(notarytool::NOTARIZATION_TIMEOUT_ENV_VAR).as_deref())?;
+  match signer {
+    Signer::Codesign(keychain) => {
+      notarytool::notarize(keychain, &app_bundle_path, credentials, timeout)
+    }
+    Signer::Rcodesign(_) => rcodesign::notarize(&app_bundle_path, credentials, timeout),
+  }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -125,3 +212,77 @@ fn find_api_key(folder: PathBuf, file_name: &OsString) -> Option<PathBuf> {
     None
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{signing_backend, validate_entitlements};
+  use crate::MacSigningBackend;
+
+  #[test]
+  fn validates_entitlements() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let entitlements = dir.path().join("entitlements.plist");
+    std::fs::write(
+      &entitlements,
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>com.apple.security.cs.allow-jit</key>
+  <true/>
+</dict>
+</plist>"#,
+    )
+    .unwrap();
+    validate_entitlements(&entitlements).unwrap();
+
+    let not_a_dictionary = dir.path().join("array.plist");
+    std::fs::write(
+      &not_a_dictionary,
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><array/></plist>"#,
+    )
+    .unwrap();
+    let missing = dir.path().join("missing.plist");
+    let not_a_plist = dir.path().join("entitlements.json");
+    std::fs::write(&not_a_plist, "{}").unwrap();
+
+    for path in [not_a_dictionary, missing, not_a_plist] {
+      match validate_entitlements(&path) {
+        Err(crate::Error::InvalidEntitlements { path: invalid, .. }) => assert_eq!(invalid, path),
+        other => panic!(
+          "expected invalid entitlements for {}, got {other:?}",
+          path.display()
+        ),
+      }
+    }
+  }
+
+  #[test]
+  fn selects_signing_backend() {
+    for (configured, codesign_available, has_certificate, expected) in [
+      (None, true, true, MacSigningBackend::Codesign),
+      (None, true, false, MacSigningBackend::Codesign),
+      (None, false, true, MacSigningBackend::Rcodesign),
+      (None, false, false, MacSigningBackend::Codesign),
+      (
+        Some(MacSigningBackend::Rcodesign),
+        true,
+        false,
+        MacSigningBackend::Rcodesign,
+      ),
+      (
+        Some(MacSigningBackend::Codesign),
+        false,
+        true,
+        MacSigningBackend::Codesign,
+      ),
+    ] {
+      assert_eq!(
+        signing_backend(configured, codesign_available, has_certificate),
+        expected
+      );
+    }
+  }
+}
@@ -0,0 +1,190 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Universal macOS binaries created with `lipo`,
+//! see <https://developer.apple.com/documentation/apple-silicon/building-a-universal-macos-binary>.
+
+use std::{
+  ffi::OsStr,
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use tauri_utils::display_path;
+
+use crate::{
+  bundle::{common::CommandExt, settings::UniversalBinary},
+  BundleBinary, Settings,
+};
+
+/// The binaries to merge into the universal version of `bin`, if any.
+pub fn binary_inputs(settings: &Settings, bin: &BundleBinary) -> Option<[PathBuf; 2]> {
+  match settings.macos().universal_binary.as_ref()? {
+    UniversalBinary::Binaries(first, second) => bin.main().then(|| [first.clone(), second.clone()]),
+    UniversalBinary::Targets(first, second) => {
+      // the project output directory is `<target dir>[/<target triple>]/<profile>`
+      let out_dir = settings.project_out_directory();
+      let profile = out_dir.file_name()?;
+      let mut target_dir = out_dir.parent()?;
+      if target_dir.file_name() == Some(OsStr::new(settings.target())) {
+        target_dir = target_dir.parent()?;
+      }
+      Some([first, second].map(|triple| target_dir.join(triple).join(profile).join(bin.name())))
+    }
+  }
+}
+
+/// Merges the inputs into a universal binary at `dest`,
+/// failing if an input is missing or if they share an architecture.
+pub fn create(inputs: &[PathBuf; 2], dest: &Path) -> crate::Result<()> {
+  for input in inputs {
+    if !input.is_file() {
+      return Err(crate::Error::UniversalBinaryInputNotFound(input.clone()));
+    }
+  }
+
+  let [first, second] = inputs;
+  let first_architectures = architectures(first)?;
+  let second_architectures = architectures(second)?;
+  let overlap = first_architectures
+    .iter()
+    .filter(|arch| second_architectures.contains(arch))
+    .cloned()
+    .collect::<Vec<_>>();
+  if !overlap.is_empty() {
+    return Err(crate::Error::UniversalBinaryArchitecturesOverlap {
+      first: first.clone(),
+      second: second.clone(),
+      architectures: overlap,
+    });
+  }
+
+  log::info!(action = "Creating"; "universal binary {}", display_path(dest));
+  if let Some(parent) = dest.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  Command::new("lipo")
+    .arg("-create")
+    .arg("-output")
+    .arg(dest)
+    .args(inputs)
+    .output_ok()?;
+
+  let expected = [first_architectures, second_architectures].concat();
+  let found = architectures(dest)?;
+  if expected.iter().any(|arch| !found.contains(arch)) {
+    return Err(crate::Error::UniversalBinaryInvalid {
+      path: dest.to_path_buf(),
+      expected,
+      found,
+    });
+  }
+
+  Ok(())
+}
+
+/// The architectures of a binary, read with `lipo -info`.
+fn architectures(path: &Path) -> crate::Result<Vec<String>> {
+  let output = Command::new("lipo").arg("-info").arg(path).output_ok()?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  parse_lipo_info(&stdout).ok_or_else(|| {
+    crate::Error::GenericError(format!(
+      "failed to read the architectures of {} from `lipo -info`: {}",
+      display_path(path),
+      stdout.trim()
+    ))
+  })
+}
+
+/// Parses the `Non-fat file: <path> is architecture: <arch>`
+/// or `Architectures in the fat file: <path> are: <archs>` output of `lipo -info`.
+fn parse_lipo_info(stdout: &str) -> Option<Vec<String>> {
+  let line = stdout.lines().find(|line| !line.trim().is_empty())?;
+  let (_, architectures) = line
+    .rsplit_once(" is architecture: ")
+    .or_else(|| line.rsplit_once(" are: "))?;
+  let architectures = architectures
+    .split_whitespace()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>();
+  (!architectures.is_empty()).then_some(architectures)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{binary_inputs, parse_lipo_info};
+  use crate::{
+    BundleBinary, BundleSettings, MacOsSettings, PackageSettings, SettingsBuilder, UniversalBinary,
+  };
+  use std::path::PathBuf;
+
+  #[test]
+  fn parses_lipo_info() {
+    assert_eq!(
+      parse_lipo_info("Non-fat file: target/release/app is architecture: arm64\n"),
+      Some(vec!["arm64".into()])
+    );
+    assert_eq!(
+      parse_lipo_info("Architectures in the fat file: My App: beta/app are: x86_64 arm64 \n"),
+      Some(vec!["x86_64".into(), "arm64".into()])
+    );
+    assert_eq!(
+      parse_lipo_info("fatal error: lipo: can't open input file"),
+      None
+    );
+  }
+
+  #[test]
+  fn resolves_binary_inputs() {
+    let settings = |universal_binary| {
+      SettingsBuilder::new()
+        .project_out_directory("target/universal-apple-darwin/release")
+        .package_settings(PackageSettings {
+          product_name: "app".into(),
+          version: "1.0.0".into(),
+          description: String::new(),
+          homepage: None,
+          authors: None,
+          default_run: None,
+        })
+        .bundle_settings(BundleSettings {
+          macos: MacOsSettings {
+            universal_binary: Some(universal_binary),
+            ..Default::default()
+          },
+          ..Default::default()
+        })
+        .binaries(vec![
+          BundleBinary::new("app".into(), true),
+          BundleBinary::new("helper".into(), false),
+        ])
+        .target("universal-apple-darwin".into())
+        .build()
+        .expect("failed to build settings")
+    };
+
+    let targets = settings(UniversalBinary::Targets(
+      "x86_64-apple-darwin".into(),
+      "aarch64-apple-darwin".into(),
+    ));
+    assert_eq!(
+      binary_inputs(&targets, &targets.binaries()[1]),
+      Some([
+        PathBuf::from("target/x86_64-apple-darwin/release/helper"),
+        PathBuf::from("target/aarch64-apple-darwin/release/helper"),
+      ])
+    );
+
+    let binaries = settings(UniversalBinary::Binaries(
+      PathBuf::from("x86_64/app"),
+      PathBuf::from("arm64/app"),
+    ));
+    assert_eq!(
+      binary_inputs(&binaries, &binaries.binaries()[0]),
+      Some([PathBuf::from("x86_64/app"), PathBuf::from("arm64/app")])
+    );
+    assert_eq!(binary_inputs(&binaries, &binaries.binaries()[1]), None);
+  }
+}
@@ -0,0 +1,214 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{bundle_size, checksum::digest_file, Bundle, PackageType, Settings};
+
+use serde::{Serialize, Serializer};
+use tauri_utils::config::ChecksumKind;
+
+use std::{
+  ffi::OsString,
+  fs,
+  path::{Path, PathBuf},
+};
+
+/// File name of the manifest written by [`write_bundle_manifest`].
+pub const BUNDLE_MANIFEST_FILE_NAME: &str = "bundle-manifest.json";
+
+/// The artifacts produced by the bundler, see [`bundle_manifest`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+  /// The artifacts, in the order of the bundles they belong to.
+  pub artifacts: Vec<BundleArtifact>,
+}
+
+/// A file or directory produced by the bundler.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleArtifact {
+  /// The package type of the bundle that produced the artifact.
+  #[serde(serialize_with = "serialize_package_type")]
+  pub package_type: PackageType,
+  /// The target triple the artifact was bundled for.
+  pub target: String,
+  /// The absolute path of the artifact.
+  pub path: PathBuf,
+  /// The size of the artifact in bytes, including the contents of directories.
+  pub size: u64,
+  /// The size in bytes of the files of the bundle before they were archived or compressed,
+  /// see [`Bundle::staged_size_bytes`].
+  pub staged_size: Option<u64>,
+  /// The hex encoded SHA-256 digest of the artifact, `None` for directories such as the macOS app bundle.
+  pub sha256: Option<String>,
+  /// The path of the updater signature of the artifact, if it was signed.
+  pub signature_path: Option<PathBuf>,
+}
+
+fn serialize_package_type<S: Serializer>(
+  package_type: &PackageType,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(package_type.short_name())
+}
+
+/// Describes the artifacts of the given bundles, as returned by [`crate::bundle_project`].
+///
+/// The artifacts are hashed by streaming their contents, so large files are not loaded in memory.
+pub fn bundle_manifest(settings: &Settings, bundles: &[Bundle]) -> crate::Result<BundleManifest> {
+  let mut artifacts = Vec::new();
+  for bundle in bundles {
+    for path in &bundle.bundle_paths {
+      let sha256 = if path.is_file() {
+        digest_file(path, &[ChecksumKind::Sha256])?.pop()
+      } else {
+        None
+      };
+      artifacts.push(BundleArtifact {
+        package_type: bundle.package_type,
+        target: settings.target().to_string(),
+        path: dunce::canonicalize(path)?,
+        size: bundle_size(std::slice::from_ref(path))?,
+        staged_size: bundle.staged_size_bytes,
+        sha256,
+        signature_path: signature_path(path)?,
+      });
+    }
+  }
+  Ok(BundleManifest { artifacts })
+}
+
+/// The path of the manifest written by [`write_bundle_manifest`]:
+/// `bundle > outputDir` if set, or the `bundle` folder of the project output directory.
+pub fn bundle_manifest_path(settings: &Settings) -> PathBuf {
+  settings
+    .output_dir()
+    .map(Path::to_path_buf)
+    .unwrap_or_else(|| settings.project_out_directory().join("bundle"))
+    .join(BUNDLE_MANIFEST_FILE_NAME)
+}
+
+/// Writes the manifest to [`bundle_manifest_path`], returning its path.
+pub fn write_bundle_manifest(
+  settings: &Settings,
+  manifest: &BundleManifest,
+) -> crate::Result<PathBuf> {
+  let path = bundle_manifest_path(settings);
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  fs::write(&path, serde_json::to_string_pretty(manifest)?)?;
+  Ok(path)
+}
+
+/// The updater signature written next to the artifact, ignoring signatures older than the artifact
+/// since they were left by a previous build.
+fn signature_path(path: &Path) -> crate::Result<Option<PathBuf>> {
+  let mut signature_path = OsString::from(path);
+  signature_path.push(".sig");
+  let signature_path = PathBuf::from(signature_path);
+
+  let Ok(signature_metadata) = fs::metadata(&signature_path) else {
+    return Ok(None);
+  };
+  if signature_metadata.modified()? < fs::metadata(path)?.modified()? {
+    return Ok(None);
+  }
+  Ok(Some(dunce::canonicalize(signature_path)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{bundle_manifest, write_bundle_manifest};
+  use crate::{Bundle, BundleSettings, PackageSettings, PackageType, SettingsBuilder};
+
+  use filetime::FileTime;
+  use std::time::{Duration, SystemTime};
+
+  #[test]
+  fn writes_manifest() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let tmp_path = dunce::canonicalize(tmp.path()).unwrap();
+    let bundle_dir = tmp_path.join("bundle");
+    std::fs::create_dir_all(bundle_dir.join("MyApp.app/Contents")).unwrap();
+    std::fs::write(bundle_dir.join("MyApp.app/Contents/Info.plist"), "plist").unwrap();
+    let archive = bundle_dir.join("MyApp.app.tar.gz");
+    std::fs::write(&archive, "hello world").unwrap();
+    std::fs::write(bundle_dir.join("MyApp.app.tar.gz.sig"), "signature").unwrap();
+    // a signature left by a previous build
+    let deb = bundle_dir.join("MyApp_1.2.3_amd64.deb");
+    std::fs::write(&deb, "deb").unwrap();
+    let stale_signature = bundle_dir.join("MyApp_1.2.3_amd64.deb.sig");
+    std::fs::write(&stale_signature, "signature").unwrap();
+    filetime::set_file_mtime(
+      &stale_signature,
+      FileTime::from_system_time(SystemTime::now() - Duration::from_secs(60)),
+    )
+    .unwrap();
+
+    let settings = SettingsBuilder::new()
+      .project_out_directory(&tmp_path)
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings::default())
+      .target("x86_64-apple-darwin".into())
+      .build()
+      .expect("failed to build settings");
+
+    let bundles = [
+      Bundle::new(PackageType::MacOsBundle, vec![bundle_dir.join("MyApp.app")]),
+      Bundle::new(PackageType::Updater, vec![archive.clone()]),
+      Bundle {
+        staged_size_bytes: Some(42),
+        ..Bundle::new(PackageType::Deb, vec![deb.clone()])
+      },
+    ];
+    let manifest = bundle_manifest(&settings, &bundles).unwrap();
+    let path = write_bundle_manifest(&settings, &manifest).unwrap();
+    assert_eq!(path, bundle_dir.join("bundle-manifest.json"));
+
+    let json: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+      json,
+      serde_json::json!({
+        "artifacts": [
+          {
+            "packageType": "app",
+            "target": "x86_64-apple-darwin",
+            "path": bundle_dir.join("MyApp.app"),
+            "size": 5,
+            "stagedSize": null,
+            "sha256": null,
+            "signaturePath": null
+          },
+          {
+            "packageType": "updater",
+            "target": "x86_64-apple-darwin",
+            "path": archive,
+            "size": 11,
+            "stagedSize": null,
+            "sha256": "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+            "signaturePath": bundle_dir.join("MyApp.app.tar.gz.sig")
+          },
+          {
+            "packageType": "deb",
+            "target": "x86_64-apple-darwin",
+            "path": deb,
+            "size": 3,
+            "stagedSize": 42,
+            "sha256": "9cfa1468c93fc18652e34a000f0c6614b0fa18f6f4887477ad9b0d36ca6a7eaa",
+            "signaturePath": null
+          }
+        ]
+      })
+    );
+  }
+}
@@ -0,0 +1,436 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{common, Bundle, PackageType, Settings};
+
+use handlebars::Handlebars;
+use serde::Serialize;
+use tauri_utils::display_path;
+
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+};
+
+/// Inner extensions of the updater archives, e.g. `app` in `MyApp.app.tar.gz`.
+const UPDATER_INNER_EXTENSIONS: &[&str] = &["app", "AppImage", "msi", "nsis"];
+
+/// The variables available in the `bundle > artifactName` template.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactNameData<'a> {
+  product_name: &'a str,
+  version: &'a str,
+  arch: &'a str,
+  target_triple: &'a str,
+  package_type: &'a str,
+  ext: &'a str,
+}
+
+/// Returns the architecture name used by the default file name of the given package type.
+pub fn artifact_arch(settings: &Settings, package_type: PackageType) -> &str {
+  let arch = settings.binary_arch();
+  match package_type {
+    PackageType::Deb | PackageType::Snap => match arch {
+      "x86" => "i386",
+      "x86_64" => "amd64",
+      "arm" => "armhf",
+      "aarch64" => "arm64",
+      other => other,
+    },
+    PackageType::AppImage => match arch {
+      "x86" => "i386",
+      "x86_64" => "amd64",
+      other => other,
+    },
+    PackageType::Flatpak => match arch {
+      "x86" => "i386",
+      other => other,
+    },
+    PackageType::Rpm => match arch {
+      "x86" => "i386",
+      "arm" => "armhfp",
+      other => other,
+    },
+    PackageType::Pacman => match arch {
+      "x86" => "i686",
+      "arm" => "armv7h",
+      other => other,
+    },
+    PackageType::WindowsMsi | PackageType::Nsis => match arch {
+      "x86_64" => "x64",
+      "aarch64" => "arm64",
+      other => other,
+    },
+    _ => match arch {
+      "x86_64" => "x64",
+      other => other,
+    },
+  }
+}
+
+/// Returns the extension of an artifact, keeping the `pkg.tar.zst` extension of pacman packages
+/// and the inner extension of updater archives (e.g. `app.tar.gz`).
+fn artifact_extension(path: &Path) -> String {
+  let file_name = path
+    .file_name()
+    .map(|name| name.to_string_lossy())
+    .unwrap_or_default();
+
+  if file_name.ends_with(".pkg.tar.zst") {
+    return "pkg.tar.zst".into();
+  }
+
+  for archive in ["tar.gz", "zip"] {
+    if let Some(stem) = file_name.strip_suffix(&format!(".{archive}")) {
+      return match Path::new(stem).extension().and_then(|ext| ext.to_str()) {
+        Some(inner) if UPDATER_INNER_EXTENSIONS.contains(&inner) => format!("{inner}.{archive}"),
+        _ => archive.to_string(),
+      };
+    }
+  }
+
+  path
+    .extension()
+    .map(|ext| ext.to_string_lossy().into_owned())
+    .unwrap_or_default()
+}
+
+/// Returns the package type an updater archive wraps based on its inner extension, e.g. [`PackageType::AppImage`]
+/// for `MyApp.AppImage.tar.gz`, so it is named like the bundle it contains. Other package types are returned as is.
+fn wrapped_package_type(package_type: PackageType, ext: &str) -> PackageType {
+  if package_type != PackageType::Updater {
+    return package_type;
+  }
+  match ext.split('.').next() {
+    Some("app") => PackageType::MacOsBundle,
+    Some("AppImage") => PackageType::AppImage,
+    Some("msi") => PackageType::WindowsMsi,
+    Some("nsis") => PackageType::Nsis,
+    _ => package_type,
+  }
+}
+
+/// Renders the `bundle > artifactName` template for the given artifact.
+pub fn render_artifact_name(
+  settings: &Settings,
+  template: &str,
+  package_type: PackageType,
+  path: &Path,
+) -> crate::Result<String> {
+  let mut handlebars = Handlebars::new();
+  handlebars.register_escape_fn(handlebars::no_escape);
+  handlebars.set_strict_mode(true);
+
+  let ext = artifact_extension(path);
+  let name = handlebars.render_template(
+    template,
+    &ArtifactNameData {
+      product_name: settings.product_name(),
+      version: settings.version_string(),
+      arch: artifact_arch(settings, wrapped_package_type(package_type, &ext)),
+      target_triple: settings.target(),
+      package_type: package_type.short_name(),
+      ext: &ext,
+    },
+  )?;
+
+  if name.is_empty() || name.contains(['/', '\\']) {
+    return Err(crate::Error::GenericError(format!(
+      "artifact name template `{template}` rendered an invalid file name `{name}`"
+    )));
+  }
+
+  Ok(name)
+}
+
+/// Computes the final path of an artifact based on `bundle > outputDir` and `bundle > artifactName`.
+fn artifact_path(
+  settings: &Settings,
+  package_type: PackageType,
+  path: &Path,
+) -> crate::Result<PathBuf> {
+  let dir = match settings.output_dir() {
+    Some(dir) => dir,
+    None => path.parent().expect("artifact path has no parent"),
+  };
+  let file_name = match settings.artifact_name() {
+    Some(template) => render_artifact_name(settings, template, package_type, path)?.into(),
+    None => path
+      .file_name()
+      .expect("artifact path has no file name")
+      .to_os_string(),
+  };
+  Ok(dir.join(file_name))
+}
+
+/// Moves a file or directory, falling back to copying when renaming is not possible (e.g. across file systems).
+fn move_artifact(from: &Path, to: &Path) -> crate::Result<()> {
+  if to.is_dir() {
+    fs::remove_dir_all(to)?;
+  } else if to.exists() {
+    fs::remove_file(to)?;
+  }
+
+  log::info!(action = "Moving"; "{} to {}", display_path(from), display_path(to));
+
+  if fs::rename(from, to).is_err() {
+    if from.is_dir() {
+      common::copy_dir(from, to)?;
+      fs::remove_dir_all(from)?;
+    } else {
+      common::copy_file(from, to)?;
+      fs::remove_file(from)?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Moves the produced artifacts to `bundle > outputDir` and renames them using `bundle > artifactName`,
+/// updating the bundle paths in place.
+///
+/// All destinations are resolved before anything is moved so collisions are reported without touching the artifacts.
+pub fn relocate_bundles(settings: &Settings, bundles: &mut [Bundle]) -> crate::Result<()> {
+  if settings.output_dir().is_none() && settings.artifact_name().is_none() {
+    return Ok(());
+  }
+
+  let mut destinations = HashMap::<PathBuf, PathBuf>::new();
+  let mut moves = Vec::new();
+  for (bundle_index, bundle) in bundles.iter().enumerate() {
+    for (path_index, path) in bundle.bundle_paths.iter().enumerate() {
+      let destination = artifact_path(settings, bundle.package_type, path)?;
+      if let Some(first) = destinations.insert(destination.clone(), path.clone()) {
+        return Err(crate::Error::ArtifactPathCollision {
+          first,
+          second: path.clone(),
+          destination,
+        });
+      }
+      moves.push((bundle_index, path_index, destination));
+    }
+  }
+
+  if let Some(output_dir) = settings.output_dir() {
+    fs::create_dir_all(output_dir)?;
+  }
+
+  for (bundle_index, path_index, destination) in moves {
+    let path = &mut bundles[bundle_index].bundle_paths[path_index];
+    if *path != destination {
+      move_artifact(path, &destination)?;
+      *path = destination;
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleSettings, PackageSettings, SettingsBuilder};
+
+  fn settings(target: &str, output_dir: Option<PathBuf>, artifact_name: Option<&str>) -> Settings {
+    SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        output_dir,
+        artifact_name: artifact_name.map(Into::into),
+        ..Default::default()
+      })
+      .target(target.into())
+      .build()
+      .expect("failed to build settings")
+  }
+
+  #[test]
+  fn renders_artifact_name_template() {
+    let settings = settings("x86_64-pc-windows-msvc", None, None);
+    let template = "{{productName}}-Setup-{{version}}-{{arch}}.{{ext}}";
+    assert_eq!(
+      render_artifact_name(
+        &settings,
+        template,
+        PackageType::Nsis,
+        Path::new("bundle/nsis/MyApp_1.2.3_x64-setup.exe")
+      )
+      .unwrap(),
+      "MyApp-Setup-1.2.3-x64.exe"
+    );
+    assert_eq!(
+      render_artifact_name(
+        &settings,
+        "{{packageType}}/{{targetTriple}}",
+        PackageType::WindowsMsi,
+        Path::new("MyApp.msi")
+      )
+      .map_err(|e| e.to_string())
+      .unwrap_err(),
+      "`artifact name template `{{packageType}}/{{targetTriple}}` rendered an invalid file name `msi/x86_64-pc-windows-msvc``"
+    );
+    assert!(render_artifact_name(
+      &settings,
+      "{{unknown}}",
+      PackageType::Nsis,
+      Path::new("MyApp.exe")
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn keeps_updater_archive_extensions() {
+    assert_eq!(
+      artifact_extension(Path::new("MyApp.app.tar.gz")),
+      "app.tar.gz"
+    );
+    assert_eq!(
+      artifact_extension(Path::new("MyApp_1.2.3_amd64.AppImage.tar.gz")),
+      "AppImage.tar.gz"
+    );
+    assert_eq!(
+      artifact_extension(Path::new("MyApp_1.2.3_x64-setup.nsis.zip")),
+      "nsis.zip"
+    );
+    assert_eq!(artifact_extension(Path::new("MyApp_1.2.3_x64.zip")), "zip");
+    assert_eq!(
+      artifact_extension(Path::new("MyApp_1.2.3_amd64.deb")),
+      "deb"
+    );
+    assert_eq!(
+      artifact_extension(Path::new("MyApp-1.2.3-1-x86_64.pkg.tar.zst")),
+      "pkg.tar.zst"
+    );
+  }
+
+  #[test]
+  fn updater_archives_use_wrapped_bundle_arch() {
+    let settings = settings("x86_64-pc-windows-msvc", None, None);
+    let template = "{{productName}}-{{arch}}.{{ext}}";
+    assert_eq!(
+      render_artifact_name(
+        &settings,
+        template,
+        PackageType::Updater,
+        Path::new("MyApp_1.2.3_x64-setup.nsis.zip")
+      )
+      .unwrap(),
+      "MyApp-x64.nsis.zip"
+    );
+
+    let settings = settings("aarch64-pc-windows-msvc", None, None);
+    assert_eq!(
+      render_artifact_name(
+        &settings,
+        template,
+        PackageType::Updater,
+        Path::new("MyApp_1.2.3_arm64_en-US.msi.zip")
+      )
+      .unwrap(),
+      "MyApp-arm64.msi.zip"
+    );
+  }
+
+  #[test]
+  fn rejects_colliding_artifact_names() {
+    let tmp = tempfile::tempdir().unwrap();
+    let deb = tmp.path().join("deb/MyApp_1.2.3_amd64.deb");
+    let rpm = tmp.path().join("rpm/MyApp-1.2.3-1.x86_64.rpm");
+    common::create_file(&deb).unwrap();
+    common::create_file(&rpm).unwrap();
+
+    let settings = settings(
+      "x86_64-unknown-linux-gnu",
+      Some(tmp.path().join("out")),
+      Some("{{productName}}-{{version}}"),
+    );
+    let mut bundles = vec![
+      Bundle::new(PackageType::Deb, vec![deb.clone()]),
+      Bundle::new(PackageType::Rpm, vec![rpm.clone()]),
+    ];
+
+    let err = relocate_bundles(&settings, &mut bundles).unwrap_err();
+    assert!(matches!(
+      err,
+      crate::Error::ArtifactPathCollision { destination, .. } if destination == tmp.path().join("out/MyApp-1.2.3")
+    ));
+    // nothing was moved
+    assert!(deb.is_file());
+    assert!(rpm.is_file());
+    assert!(!tmp.path().join("out").exists());
+  }
+
+  #[test]
+  fn relocates_bundle_paths() {
+    let tmp = tempfile::tempdir().unwrap();
+    let deb = tmp.path().join("deb/MyApp_1.2.3_amd64.deb");
+    let appimage = tmp.path().join("appimage/MyApp_1.2.3_amd64.AppImage");
+    let updater = tmp
+      .path()
+      .join("appimage/MyApp_1.2.3_amd64.AppImage.tar.gz");
+    for path in [&deb, &appimage, &updater] {
+      common::create_file(path).unwrap();
+    }
+
+    let out = tmp.path().join("out");
+    let settings = settings(
+      "x86_64-unknown-linux-gnu",
+      Some(out.clone()),
+      Some("{{productName}}-{{version}}-{{arch}}.{{ext}}"),
+    );
+    let mut bundles = vec![
+      Bundle::new(PackageType::Deb, vec![deb.clone()]),
+      Bundle::new(PackageType::AppImage, vec![appimage.clone()]),
+      Bundle::new(PackageType::Updater, vec![updater.clone()]),
+    ];
+
+    relocate_bundles(&settings, &mut bundles).unwrap();
+
+    let paths = bundles
+      .iter()
+      .flat_map(|b| b.bundle_paths.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      paths,
+      vec![
+        out.join("MyApp-1.2.3-amd64.deb"),
+        out.join("MyApp-1.2.3-amd64.AppImage"),
+        out.join("MyApp-1.2.3-amd64.AppImage.tar.gz"),
+      ]
+    );
+    for path in paths {
+      assert!(path.is_file());
+    }
+    assert!(!deb.exists());
+    assert!(!appimage.exists());
+    assert!(!updater.exists());
+  }
+
+  #[test]
+  fn output_dir_keeps_default_names() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dmg = tmp.path().join("dmg/MyApp_1.2.3_x64.dmg");
+    common::create_file(&dmg).unwrap();
+
+    let out = tmp.path().join("out");
+    let settings = settings("x86_64-apple-darwin", Some(out.clone()), None);
+    let mut bundles = vec![Bundle::new(PackageType::Dmg, vec![dmg])];
+
+    relocate_bundles(&settings, &mut bundles).unwrap();
+    assert_eq!(
+      bundles[0].bundle_paths,
+      vec![out.join("MyApp_1.2.3_x64.dmg")]
+    );
+  }
+}
@@ -4,11 +4,17 @@
 // SPDX-License-Identifier: MIT
 
 use super::category::AppCategory;
-use crate::bundle::{common, platform::target_triple};
-pub use tauri_utils::config::WebviewInstallMode;
+use crate::bundle::{common, platform::target_triple, staging};
+pub use tauri_utils::config::{
+  ArchiveFormat, ChecksumKind, DebCompression, DigestAlgorithm, MacSigningBackend,
+  MacUpdaterArtifact, WebviewInstallMode,
+};
 use tauri_utils::{
-  config::{BundleType, DeepLinkProtocol, FileAssociation, NSISInstallerMode, NsisCompression},
-  resources::{external_binaries, ResourcePaths},
+  config::{
+    BundleType, DeepLinkProtocol, FileAssociation, NSISInstallerMode, NsisCompression,
+    ResourceCollision, SnapConfinement, SnapGrade, WixInstallMode,
+  },
+  resources::{external_binaries, resolve_collisions, Resource, ResourcePaths},
 };
 
 use std::{
@@ -34,8 +40,18 @@ pub enum PackageType {
   Rpm,
   /// The Linux AppImage bundle (.AppImage).
   AppImage,
+  /// The Linux Flatpak single-file bundle (.flatpak).
+  Flatpak,
+  /// The Linux Snap package (.snap).
+  Snap,
+  /// The Linux pacman package (.pkg.tar.zst).
+  Pacman,
+  /// The portable archive (.tar.gz or .zip).
+  Archive,
   /// The macOS DMG bundle (.dmg).
   Dmg,
+  /// The macOS installer package (.pkg).
+  MacOsPkg,
   /// The Updater bundle.
   Updater,
 }
@@ -50,13 +66,18 @@ impl From<BundleType> for PackageType {
       BundleType::Nsis => Self::Nsis,
       BundleType::App => Self::MacOsBundle,
       BundleType::Dmg => Self::Dmg,
+      BundleType::Pkg => Self::MacOsPkg,
+      BundleType::Flatpak => Self::Flatpak,
+      BundleType::Snap => Self::Snap,
+      BundleType::Pacman => Self::Pacman,
+      BundleType::Archive => Self::Archive,
     }
   }
 }
 
 impl PackageType {
   /// Maps a short name to a PackageType.
-  /// Possible values are "deb", "ios", "msi", "app", "rpm", "appimage", "dmg", "updater".
+  /// Possible values are "deb", "ios", "msi", "app", "rpm", "appimage", "flatpak", "snap", "pacman", "archive", "dmg", "pkg", "updater".
   pub fn from_short_name(name: &str) -> Option<PackageType> {
     // Other types we may eventually want to support: apk.
     match name {
@@ -67,7 +88,12 @@ impl PackageType {
       "app" => Some(PackageType::MacOsBundle),
       "rpm" => Some(PackageType::Rpm),
       "appimage" => Some(PackageType::AppImage),
+      "flatpak" => Some(PackageType::Flatpak),
+      "snap" => Some(PackageType::Snap),
+      "pacman" => Some(PackageType::Pacman),
+      "archive" => Some(PackageType::Archive),
       "dmg" => Some(PackageType::Dmg),
+      "pkg" => Some(PackageType::MacOsPkg),
       "updater" => Some(PackageType::Updater),
       _ => None,
     }
@@ -84,7 +110,12 @@ impl PackageType {
       PackageType::MacOsBundle => "app",
       PackageType::Rpm => "rpm",
       PackageType::AppImage => "appimage",
+      PackageType::Flatpak => "flatpak",
+      PackageType::Snap => "snap",
+      PackageType::Pacman => "pacman",
+      PackageType::Archive => "archive",
       PackageType::Dmg => "dmg",
+      PackageType::MacOsPkg => "pkg",
       PackageType::Updater => "updater",
     }
   }
@@ -109,7 +140,12 @@ impl PackageType {
       PackageType::Deb => 0,
       PackageType::Rpm => 0,
       PackageType::AppImage => 0,
+      PackageType::Flatpak => 0,
+      PackageType::Snap => 0,
+      PackageType::Pacman => 0,
+      PackageType::Archive => 0,
       PackageType::Dmg => 1,
+      PackageType::MacOsPkg => 1,
       PackageType::Updater => 2,
     }
   }
@@ -130,11 +166,24 @@ const ALL_PACKAGE_TYPES: &[PackageType] = &[
   PackageType::Rpm,
   #[cfg(target_os = "macos")]
   PackageType::Dmg,
+  #[cfg(target_os = "macos")]
+  PackageType::MacOsPkg,
   #[cfg(target_os = "linux")]
   PackageType::AppImage,
+  #[cfg(target_os = "linux")]
+  PackageType::Flatpak,
+  #[cfg(target_os = "linux")]
+  PackageType::Snap,
+  #[cfg(target_os = "linux")]
+  PackageType::Pacman,
+  PackageType::Archive,
   PackageType::Updater,
 ];
 
+/// The timestamp of reproducible bundles when `SOURCE_DATE_EPOCH` is not set: `1980-01-01T00:00:00Z`,
+/// the earliest modification time that can be stored in zip archives.
+const REPRODUCIBLE_EPOCH: u64 = 315_532_800;
+
 /// The package settings.
 #[derive(Debug, Clone)]
 pub struct PackageSettings {
@@ -194,7 +243,11 @@ pub struct DebianSettings {
   pub priority: Option<String>,
   /// Path of the uncompressed Changelog file, to be stored at /usr/share/doc/package-name/changelog.gz. See
   /// <https://www.debian.org/doc/debian-policy/ch-docs.html#changelog-files-and-release-notes>
+  ///
+  /// It must use the Debian changelog format and its latest entry must match the package version.
   pub changelog: Option<PathBuf>,
+  /// Do not register the custom files installed under `/etc` as conffiles.
+  pub skip_conffiles: bool,
   /// Path to script that will be executed before the package is unpacked. See
   /// <https://www.debian.org/doc/debian-policy/ch-maintainerscripts.html>
   pub pre_install_script: Option<PathBuf>,
@@ -207,6 +260,15 @@ pub struct DebianSettings {
   /// Path to script that will be executed after the package is removed. See
   /// <https://www.debian.org/doc/debian-policy/ch-maintainerscripts.html>
   pub post_remove_script: Option<PathBuf>,
+  /// Maintainer scripts to include in the package, keyed by their name: `preinst`, `postinst`, `prerm` or `postrm`. See
+  /// <https://www.debian.org/doc/debian-policy/ch-maintainerscripts.html>
+  pub maintainer_scripts: HashMap<String, PathBuf>,
+  /// The compression algorithm of the `control.tar` and `data.tar` members of the package.
+  pub compression: DebCompression,
+  /// The GPG key ID used to sign the package, embedding a `_gpgorigin` signature member.
+  ///
+  /// The key passphrase is read from the `TAURI_SIGNING_DEB_KEY_PASSPHRASE` environment variable if set.
+  pub sign_key: Option<String>,
 }
 
 /// The Linux AppImage bundle settings.
@@ -214,12 +276,74 @@ pub struct DebianSettings {
 pub struct AppImageSettings {
   /// The files to include in the Appimage Binary.
   pub files: HashMap<PathBuf, PathBuf>,
+  /// The update information embedded in the AppImage for delta updates with AppImageUpdate.
+  ///
+  /// When set, a `.zsync` file is also generated next to the AppImage if `zsyncmake` is installed.
+  pub update_information: Option<String>,
+}
+
+/// The Flatpak bundle settings.
+#[derive(Clone, Debug, Default)]
+pub struct FlatpakSettings {
+  /// The Flatpak application id. Defaults to the bundle identifier.
+  pub app_id: Option<String>,
+  /// The Flatpak runtime the application runs on, e.g. `org.gnome.Platform`.
+  pub runtime: String,
+  /// The version of the runtime.
+  pub runtime_version: String,
+  /// The SDK used to build the application, e.g. `org.gnome.Sdk`.
+  pub sdk: String,
+  /// The sandbox permissions of the application, passed as `finish-args` to `flatpak-builder`.
+  pub finish_args: Vec<String>,
+  /// Additional `flatpak-builder` modules built before the application,
+  /// either inline module objects or paths to module manifest files.
+  pub modules: Vec<serde_json::Value>,
+  /// List of custom files to add to the Flatpak.
+  /// Maps the path in the `/app` prefix to the path of the file to include (relative to the current working directory).
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Path to a custom desktop file Handlebars template.
+  ///
+  /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
+  ///
+  /// Default file contents:
+  /// ```text
+  #[doc = include_str!("./linux/templates/main.desktop")]
+  /// ```
+  pub desktop_template: Option<PathBuf>,
+}
+
+/// The Snap package settings.
+#[derive(Clone, Debug, Default)]
+pub struct SnapSettings {
+  /// The confinement level of the snap.
+  pub confinement: SnapConfinement,
+  /// The quality grade of the snap.
+  pub grade: SnapGrade,
+  /// The base snap providing the run-time environment. Defaults to `core22`.
+  pub base: Option<String>,
+  /// Additional interfaces the application plugs into, on top of
+  /// `network`, `desktop`, `desktop-legacy`, `wayland`, `x11`, `opengl` and `home`.
+  pub plugs: Vec<String>,
+  /// List of custom files to add to the snap.
+  /// Maps the path in the snap to the path of the file to include (relative to the current working directory).
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Path to a custom desktop file Handlebars template.
+  ///
+  /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
+  ///
+  /// Default file contents:
+  /// ```text
+  #[doc = include_str!("./linux/templates/main.desktop")]
+  /// ```
+  pub desktop_template: Option<PathBuf>,
 }
 
 /// The RPM bundle settings.
 #[derive(Clone, Debug, Default)]
 pub struct RpmSettings {
   /// The list of RPM dependencies your application relies on.
+  ///
+  /// Each dependency is a package name optionally followed by a version constraint, e.g. `libfoo >= 2.0`.
   pub depends: Option<Vec<String>>,
   /// The list of RPM dependencies your application provides.
   pub provides: Option<Vec<String>>,
@@ -257,6 +381,68 @@ pub struct RpmSettings {
   /// Path to script that will be executed after the package is removed. See
   /// <http://ftp.rpm.org/max-rpm/s1-rpm-inside-scripts.html>
   pub post_remove_script: Option<PathBuf>,
+  /// Paths on the package of the [`Self::files`] to mark as `%config(noreplace)`.
+  ///
+  /// A directory marks all of its files.
+  pub config_files: Vec<PathBuf>,
+  /// Paths to systemd unit files installed to `/usr/lib/systemd/system`,
+  /// enabled on install and disabled on removal.
+  pub systemd_units: Vec<PathBuf>,
+  /// The GPG key ID used to sign the package with `rpm --addsign`.
+  ///
+  /// The key passphrase is read from the `TAURI_SIGNING_RPM_KEY_PASSPHRASE` environment variable if set.
+  pub sign_key: Option<String>,
+}
+
+/// The portable archive bundle settings.
+#[derive(Clone, Debug, Default)]
+pub struct ArchiveSettings {
+  /// The archive formats to produce.
+  ///
+  /// Defaults to `zip` on Windows and `tar.gz` on Linux and macOS when empty.
+  pub formats: Vec<ArchiveFormat>,
+  /// The compression level, from `0` to `9`. Defaults to `6`.
+  pub compression_level: Option<u32>,
+}
+
+/// The pacman bundle settings.
+#[derive(Clone, Debug, Default)]
+pub struct PacmanSettings {
+  /// The list of pacman dependencies your application relies on.
+  /// Debian version constraints such as `libfoo (>= 1.0)` are converted to the pacman syntax.
+  pub depends: Option<Vec<String>>,
+  /// The list of virtual packages your application provides.
+  pub provides: Option<Vec<String>>,
+  /// The list of packages your application conflicts with.
+  pub conflicts: Option<Vec<String>>,
+  /// The list of packages your application replaces.
+  pub replaces: Option<Vec<String>>,
+  /// The package release number, appended to the version.
+  pub release: String,
+  /// List of custom files to add to the pacman package.
+  /// Maps the path on the pacman package to the path of the file to include (relative to the current working directory).
+  pub files: HashMap<PathBuf, PathBuf>,
+  /// Path to a custom desktop file Handlebars template.
+  ///
+  /// Available variables: `categories`, `comment` (optional), `exec`, `icon` and `name`.
+  ///
+  /// Default file contents:
+  /// ```text
+  #[doc = include_str!("./linux/templates/main.desktop")]
+  /// ```
+  pub desktop_template: Option<PathBuf>,
+  /// Path to script used as the body of the `pre_install` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  pub pre_install_script: Option<PathBuf>,
+  /// Path to script used as the body of the `post_install` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  pub post_install_script: Option<PathBuf>,
+  /// Path to script used as the body of the `pre_remove` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  pub pre_remove_script: Option<PathBuf>,
+  /// Path to script used as the body of the `post_remove` function of the `.INSTALL` file. See
+  /// <https://wiki.archlinux.org/title/PKGBUILD#install>
+  pub post_remove_script: Option<PathBuf>,
 }
 
 /// Position coordinates struct.
@@ -290,6 +476,54 @@ pub struct DmgSettings {
   pub app_position: Position,
   /// Position of application folder on window.
   pub application_folder_position: Position,
+  /// License agreement shown before the dmg file is mounted. Accepted formats: `txt`/`rtf`.
+  ///
+  /// Defaults to [`Settings::license_file`].
+  pub license_path: Option<PathBuf>,
+  /// Icon of the mounted volume. Accepted format: `icns`.
+  ///
+  /// Defaults to the app icon.
+  pub volume_icon: Option<PathBuf>,
+}
+
+/// The macOS installer package settings.
+#[derive(Clone, Debug)]
+pub struct PkgSettings {
+  /// The directory the app is installed to.
+  pub install_location: String,
+  /// The package identifier recorded in the installer receipts.
+  ///
+  /// Defaults to [`Settings::bundle_identifier`].
+  pub identifier: Option<String>,
+  /// Path to a script run before the app is installed.
+  pub preinstall_script: Option<PathBuf>,
+  /// Path to a script run after the app is installed.
+  pub postinstall_script: Option<PathBuf>,
+  /// The installer signing identity used by `productsign`, e.g. `Developer ID Installer: Name (TEAMID)`.
+  pub signing_identity: Option<String>,
+}
+
+impl Default for PkgSettings {
+  fn default() -> Self {
+    Self {
+      install_location: "/Applications".into(),
+      identifier: None,
+      preinstall_script: None,
+      postinstall_script: None,
+      signing_identity: None,
+    }
+  }
+}
+
+/// The inputs of a universal macOS binary, merged with `lipo`.
+#[derive(Clone, Debug)]
+pub enum UniversalBinary {
+  /// The paths of the main binary built for each architecture.
+  Binaries(PathBuf, PathBuf),
+  /// The target triples the binaries were built for,
+  /// read from their Cargo output directory next to the project output directory,
+  /// e.g. `target/x86_64-apple-darwin/release`.
+  Targets(String, String),
 }
 
 /// The macOS bundle settings.
@@ -322,12 +556,22 @@ pub struct MacOsSettings {
   ///
   /// Settings this to `false` is useful when using an ad-hoc signature, making it less strict.
   pub hardened_runtime: bool,
+  /// The tool used to sign and notarize the bundles.
+  ///
+  /// Defaults to `codesign`, or to `rcodesign` when `codesign` is not installed and the `APPLE_CERTIFICATE` environment variable is set.
+  pub signing_backend: Option<MacSigningBackend>,
   /// Provider short name for notarization.
   pub provider_short_name: Option<String>,
   /// Path to the entitlements.plist file.
   pub entitlements: Option<String>,
   /// Path to the Info.plist file for the bundle.
   pub info_plist_path: Option<PathBuf>,
+  /// Merge binaries built for two architectures into the universal binaries copied to `Contents/MacOS`.
+  ///
+  /// With [`UniversalBinary::Binaries`] only the main binary is merged, the other binaries are copied as is.
+  pub universal_binary: Option<UniversalBinary>,
+  /// The archive format of the updater artifact.
+  pub updater_artifact: MacUpdaterArtifact,
 }
 
 /// Configuration for a target language for the WiX build.
@@ -381,6 +625,8 @@ pub struct WixSettings {
   pub dialog_image_path: Option<PathBuf>,
   /// Enables FIPS compliant algorithms.
   pub fips_compliant: bool,
+  /// Whether the installation will be for all users or just the current user.
+  pub install_mode: WixInstallMode,
 }
 
 /// Settings specific to the NSIS implementation.
@@ -403,6 +649,7 @@ pub struct NsisSettings {
   /// A list of installer languages.
   /// By default the OS language is used. If the OS language is not in the list of languages, the first language will be used.
   /// To allow the user to select the language, set `display_language_selector` to `true`.
+  /// When the list is empty, only English is used.
   ///
   /// See <https://github.com/kichik/nsis/tree/9465c08046f00ccb6eda985abbdbf52c275c6c4d/Contrib/Language%20files> for the complete list of languages.
   pub languages: Option<Vec<String>>,
@@ -430,6 +677,9 @@ pub struct NsisSettings {
   /// A path to a `.nsh` file that contains special NSIS macros to be hooked into the
   /// main installer.nsi script.
   ///
+  /// The file is copied into the NSIS working directory and must exist.
+  /// Hooks that are not defined by the file are skipped.
+  ///
   /// Supported hooks are:
   /// - `NSIS_HOOK_PREINSTALL`: This hook runs before copying files, setting registry key values and creating shortcuts.
   /// - `NSIS_HOOK_POSTINSTALL`: This hook runs after the installer has finished copying all files, setting the registry keys and created shortcuts.
@@ -462,8 +712,11 @@ pub struct NsisSettings {
 /// The Windows bundle settings.
 #[derive(Clone, Debug)]
 pub struct WindowsSettings {
-  /// The file digest algorithm to use for creating file signatures. Required for code signing. SHA-256 is recommended.
-  pub digest_algorithm: Option<String>,
+  /// The file digest algorithms to use for creating file signatures, SHA-256 if empty.
+  ///
+  /// The files are signed with the first algorithm and a signature is appended for each following one,
+  /// e.g. `[Sha1, Sha256]` to dual sign the files for older Windows versions.
+  pub digest_algorithm: Vec<DigestAlgorithm>,
   /// The SHA1 hash of the signing certificate.
   pub certificate_thumbprint: Option<String>,
   /// Server to use during timestamping.
@@ -475,6 +728,8 @@ pub struct WindowsSettings {
   pub wix: Option<WixSettings>,
   /// Nsis configuration.
   pub nsis: Option<NsisSettings>,
+  /// The files to include in the installation directory.
+  pub files: HashMap<PathBuf, PathBuf>,
   /// The path to the application icon. Defaults to `./icons/icon.ico`.
   pub icon_path: PathBuf,
   /// The installation mode for the Webview2 runtime.
@@ -492,30 +747,40 @@ pub struct WindowsSettings {
   /// /// The default value of this flag is `true`.
   pub allow_downgrades: bool,
 
-  /// Specify a custom command to sign the binaries.
-  /// This command needs to have a `%1` in it which is just a placeholder for the binary path,
-  /// which we will detect and replace before calling the command.
+  /// Specify a custom command to sign the binaries, used instead of `signtool.exe`.
   ///
-  /// Example:
-  /// ```text
-  /// sign-cli --arg1 --arg2 %1
-  /// ```
+  /// Every `%1` in [`CustomSignCommandSettings::args`] is replaced with the path of the file to sign.
+  /// The command runs once for each of the [`Self::digest_algorithm`].
   ///
   /// By Default we use `signtool.exe` which can be found only on Windows so
   /// if you are on another platform and want to cross-compile and sign you will
   /// need to use another tool like `osslsigncode`.
-  pub sign_command: Option<String>,
+  pub sign_command: Option<CustomSignCommandSettings>,
+}
+
+/// A custom command to sign the Windows binaries and installers.
+#[derive(Clone, Debug)]
+pub struct CustomSignCommandSettings {
+  /// The program to run.
+  pub cmd: String,
+  /// The arguments of the program.
+  ///
+  /// Every `%1` is replaced with the path of the file to sign,
+  /// so it can be a standalone argument or a part of one like `--file=%1`.
+  /// `%2` is replaced with the digest algorithm and `%3` with the timestamp server URL.
+  pub args: Vec<String>,
 }
 
 impl Default for WindowsSettings {
   fn default() -> Self {
     Self {
-      digest_algorithm: None,
+      digest_algorithm: Vec::new(),
       certificate_thumbprint: None,
       timestamp_url: None,
       tsp: false,
       wix: None,
       nsis: None,
+      files: Default::default(),
       icon_path: PathBuf::from("icons/icon.ico"),
       webview_install_mode: Default::default(),
       webview_fixed_runtime_path: None,
@@ -552,6 +817,8 @@ pub struct BundleSettings {
   ///
   /// Supports glob patterns.
   pub resources_map: Option<HashMap<String, String>>,
+  /// How the resources whose distinct sources are bundled at the same path are handled.
+  pub resource_collision: ResourceCollision,
   /// the app's copyright.
   pub copyright: Option<String>,
   /// The package's license identifier to be included in the appropriate bundles.
@@ -567,6 +834,8 @@ pub struct BundleSettings {
   pub short_description: Option<String>,
   /// the app's long description.
   pub long_description: Option<String>,
+  /// Localized product names, keyed by language tag.
+  pub display_names: HashMap<String, String>,
   // Bundles for other binaries:
   /// Configuration map for the apps to bundle.
   pub bin: Option<HashMap<String, BundleSettings>>,
@@ -585,6 +854,35 @@ pub struct BundleSettings {
   /// e.g. `sqlite3-universal-apple-darwin`. See
   /// <https://developer.apple.com/documentation/apple-silicon/building-a-universal-macos-binary>
   pub external_bin: Option<Vec<String>>,
+  /// The directory of the frontend assets embedded in the main binary.
+  ///
+  /// Used to warn about resources that would be bundled twice.
+  pub frontend_dist: Option<PathBuf>,
+  /// The directory where the final bundle artifacts are moved to.
+  ///
+  /// Defaults to the per package type `bundle/<type>` directory.
+  pub output_dir: Option<PathBuf>,
+  /// The file name template for the final bundle artifacts.
+  ///
+  /// Available variables: `productName`, `version`, `arch`, `targetTriple`, `packageType` and `ext`.
+  pub artifact_name: Option<String>,
+  /// The digest algorithms of the checksum files written next to each bundle artifact.
+  ///
+  /// Empty to skip writing checksum files.
+  pub checksums: Vec<ChecksumKind>,
+  /// Whether the bundles are byte-for-byte reproducible.
+  ///
+  /// Pins the modification times of the archive entries and the build dates of the packages
+  /// to `SOURCE_DATE_EPOCH`, or to `1980-01-01T00:00:00Z` when it is not set.
+  pub reproducible: bool,
+  /// Whether the debug symbols of the app binaries are stripped before they are bundled, see [`Settings::strip`].
+  pub strip: bool,
+  /// Whether to only log the files that would be copied and archived instead of writing them.
+  ///
+  /// Useful to check the resources and custom files mappings without running a full bundle.
+  pub dry_run: bool,
+  /// Portable archive settings.
+  pub archive: ArchiveSettings,
   /// Deep-link protocols.
   pub deep_link_protocols: Option<Vec<DeepLinkProtocol>>,
   /// Debian-specific settings.
@@ -593,8 +891,19 @@ pub struct BundleSettings {
   pub appimage: AppImageSettings,
   /// Rpm-specific settings.
   pub rpm: RpmSettings,
+  /// Flatpak-specific settings.
+  pub flatpak: FlatpakSettings,
+  /// Snap-specific settings.
+  pub snap: SnapSettings,
+  /// Pacman-specific settings.
+  pub pacman: PacmanSettings,
+  /// Whether glob patterns in the Linux `files` maps that do not match any file are ignored
+  /// instead of failing the bundle.
+  pub allow_empty_file_globs: bool,
   /// DMG-specific settings.
   pub dmg: DmgSettings,
+  /// Installer package specific settings.
+  pub pkg: PkgSettings,
   /// MacOS-specific settings.
   pub macos: MacOsSettings,
   /// Updater configuration.
@@ -682,6 +991,10 @@ pub struct Settings {
   binaries: Vec<BundleBinary>,
   /// The target triple.
   target: String,
+  /// The maximum number of package types bundled concurrently.
+  jobs: usize,
+  /// Whether the tools required to bundle the app must not be downloaded.
+  offline: bool,
 }
 
 /// A builder for [`Settings`].
@@ -694,6 +1007,8 @@ pub struct SettingsBuilder {
   bundle_settings: BundleSettings,
   binaries: Vec<BundleBinary>,
   target: Option<String>,
+  jobs: Option<usize>,
+  offline: bool,
 }
 
 impl SettingsBuilder {
@@ -753,6 +1068,21 @@ impl SettingsBuilder {
     self
   }
 
+  /// Sets the maximum number of package types bundled concurrently.
+  /// Defaults to the available parallelism, use `1` to bundle them sequentially.
+  #[must_use]
+  pub fn jobs(mut self, jobs: usize) -> Self {
+    self.jobs.replace(jobs);
+    self
+  }
+
+  /// Fails instead of downloading the tools required to bundle the app when they are not installed.
+  #[must_use]
+  pub fn offline(mut self, offline: bool) -> Self {
+    self.offline = offline;
+    self
+  }
+
   /// Builds a Settings from the CLI args.
   ///
   /// Package settings will be read from Cargo.toml.
@@ -782,6 +1112,15 @@ impl SettingsBuilder {
         ..self.bundle_settings
       },
       target,
+      jobs: self
+        .jobs
+        .unwrap_or_else(|| {
+          std::thread::available_parallelism()
+            .map(|jobs| jobs.get())
+            .unwrap_or(1)
+        })
+        .max(1),
+      offline: self.offline,
     })
   }
 }
@@ -797,6 +1136,36 @@ impl Settings {
     self.log_level
   }
 
+  /// Sets the maximum number of package types bundled concurrently.
+  pub fn set_jobs(&mut self, jobs: usize) {
+    self.jobs = jobs.max(1);
+  }
+
+  /// Returns the maximum number of package types bundled concurrently.
+  pub fn jobs(&self) -> usize {
+    self.jobs
+  }
+
+  /// Sets whether the tools required to bundle the app must not be downloaded.
+  pub fn set_offline(&mut self, offline: bool) {
+    self.offline = offline;
+  }
+
+  /// Returns whether the tools required to bundle the app must not be downloaded.
+  pub fn offline(&self) -> bool {
+    self.offline
+  }
+
+  /// Sets whether the files are only logged instead of being copied and archived.
+  pub fn set_dry_run(&mut self, dry_run: bool) {
+    self.bundle_settings.dry_run = dry_run;
+  }
+
+  /// Returns whether the files are only logged instead of being copied and archived.
+  pub fn dry_run(&self) -> bool {
+    self.bundle_settings.dry_run
+  }
+
   /// Returns the directory where the bundle should be placed.
   pub fn project_out_directory(&self) -> &Path {
     &self.project_out_directory
@@ -824,6 +1193,13 @@ impl Settings {
     }
   }
 
+  /// Returns whether the binaries are built for another architecture than the host's,
+  /// in which case the host libraries and tools cannot be used to inspect them.
+  pub fn is_cross_arch(&self) -> bool {
+    let arch = self.binary_arch();
+    arch != "universal" && arch != std::env::consts::ARCH
+  }
+
   /// Returns the file name of the binary being bundled.
   pub fn main_binary_name(&self) -> &str {
     self
@@ -836,8 +1212,21 @@ impl Settings {
   }
 
   /// Returns the path to the specified binary.
+  ///
+  /// This is the stripped copy of the binary when [`Self::stripped_binaries_dir`] is set.
   pub fn binary_path(&self, binary: &BundleBinary) -> PathBuf {
-    self.project_out_directory.join(binary.name())
+    self
+      .stripped_binaries_dir()
+      .unwrap_or_else(|| self.project_out_directory.clone())
+      .join(binary.name())
+  }
+
+  /// Returns the directory of the stripped copies of the app binaries, bundled instead of the cargo outputs.
+  ///
+  /// `None` unless [`Self::strip`] is set, and for the MSVC targets whose debug symbols are in separate `.pdb` files.
+  pub fn stripped_binaries_dir(&self) -> Option<PathBuf> {
+    (self.strip() && !self.target.ends_with("windows-msvc"))
+      .then(|| self.project_out_directory.join("bundle/stripped"))
   }
 
   /// Returns the list of binaries to bundle.
@@ -863,10 +1252,27 @@ impl Settings {
       .replace("darwin", "macos");
 
     let platform_types = match target_os.as_str() {
-      "macos" => vec![PackageType::MacOsBundle, PackageType::Dmg],
+      "macos" => vec![
+        PackageType::MacOsBundle,
+        PackageType::Dmg,
+        PackageType::MacOsPkg,
+        PackageType::Archive,
+      ],
       "ios" => vec![PackageType::IosBundle],
-      "linux" => vec![PackageType::Deb, PackageType::Rpm, PackageType::AppImage],
-      "windows" => vec![PackageType::WindowsMsi, PackageType::Nsis],
+      "linux" => vec![
+        PackageType::Deb,
+        PackageType::Rpm,
+        PackageType::AppImage,
+        PackageType::Flatpak,
+        PackageType::Snap,
+        PackageType::Pacman,
+        PackageType::Archive,
+      ],
+      "windows" => vec![
+        PackageType::WindowsMsi,
+        PackageType::Nsis,
+        PackageType::Archive,
+      ],
       os => {
         return Err(crate::Error::GenericError(format!(
           "Native {os} bundles not yet supported."
@@ -888,7 +1294,22 @@ impl Settings {
       }
       Ok(types)
     } else {
-      Ok(platform_types)
+      // Flatpak and Snap require `flatpak-builder` and `snapcraft` so they are only bundled when explicitly requested,
+      // the portable archive and the installer package are opt-in as well
+      Ok(
+        platform_types
+          .into_iter()
+          .filter(|t| {
+            !matches!(
+              t,
+              PackageType::Flatpak
+                | PackageType::Snap
+                | PackageType::Archive
+                | PackageType::MacOsPkg
+            )
+          })
+          .collect(),
+      )
     }
   }
 
@@ -940,31 +1361,45 @@ impl Settings {
 
   /// Copies external binaries to a path.
   ///
+  /// Sidecars with the same file name and content are copied once.
+  ///
   /// Returns the list of destination paths.
   pub fn copy_binaries(&self, path: &Path) -> crate::Result<Vec<PathBuf>> {
     let mut paths = Vec::new();
 
-    for src in self.external_binaries() {
-      let src = src?;
-      let dest = path.join(
-        src
-          .file_name()
-          .expect("failed to extract external binary filename")
-          .to_string_lossy()
-          .replace(&format!("-{}", self.target), ""),
-      );
-      common::copy_file(&src, &dest)?;
+    let (sidecars, _) = staging::dedupe(staging::sidecars(self)?)?;
+    for sidecar in sidecars {
+      let dest = path.join(sidecar.destination);
+      common::copy_file(&sidecar.source, &dest)?;
       paths.push(dest);
     }
     Ok(paths)
   }
 
+  /// Returns the resource files to be included in this bundle,
+  /// the resources bundled at the same target being resolved with [`BundleSettings::resource_collision`].
+  pub fn resources(&self) -> crate::Result<Vec<Resource>> {
+    let resources = self
+      .resource_files()
+      .iter()
+      .collect::<tauri_utils::Result<Vec<_>>>()?;
+    resolve_collisions(resources, self.bundle_settings.resource_collision).map_err(Into::into)
+  }
+
   /// Copies resources to a path.
+  ///
+  /// The resources are cloned when the filesystem supports it, see [`common::CloneMode::Reflink`].
   pub fn copy_resources(&self, path: &Path) -> crate::Result<()> {
-    for resource in self.resource_files().iter() {
-      let resource = resource?;
+    let options = common::CopyOptions {
+      // resources may be signed or stripped in place after being copied, so never hard link them
+      clone_mode: common::CloneMode::Reflink,
+      // replace the resources copied by a previous run
+      overwrite: true,
+      ..Default::default()
+    };
+    for resource in self.resources()? {
       let dest = path.join(resource.target());
-      common::copy_file(resource.path(), dest)?;
+      common::copy_file_with(resource.path(), dest, &options)?;
     }
     Ok(())
   }
@@ -1046,6 +1481,31 @@ impl Settings {
     self.bundle_settings.long_description.as_deref()
   }
 
+  /// Returns the localized product names, keyed by language tag.
+  pub fn display_names(&self) -> &HashMap<String, String> {
+    &self.bundle_settings.display_names
+  }
+
+  /// Returns the product name localized for the given language tag, if any.
+  ///
+  /// Tags are compared case-insensitively and `_` is treated as `-`.
+  /// When there's no exact match, the primary language of the tag is looked up (`ja` for `ja-JP`).
+  pub fn localized_product_name(&self, language: &str) -> Option<&str> {
+    let normalize = |tag: &str| tag.replace('_', "-").to_lowercase();
+    let language = normalize(language);
+    let primary_language = language.split('-').next().unwrap_or_default();
+    let names = &self.bundle_settings.display_names;
+    names
+      .iter()
+      .find(|(tag, _)| normalize(tag) == language)
+      .or_else(|| {
+        names
+          .iter()
+          .find(|(tag, _)| normalize(tag) == primary_language)
+      })
+      .map(|(_, name)| name.as_str())
+  }
+
   /// Returns the debian settings.
   pub fn deb(&self) -> &DebianSettings {
     &self.bundle_settings.deb
@@ -1061,11 +1521,46 @@ impl Settings {
     &self.bundle_settings.rpm
   }
 
+  /// Returns the directory of the frontend assets embedded in the main binary.
+  pub fn frontend_dist(&self) -> Option<&Path> {
+    self.bundle_settings.frontend_dist.as_deref()
+  }
+
+  /// Returns the Flatpak settings.
+  pub fn flatpak(&self) -> &FlatpakSettings {
+    &self.bundle_settings.flatpak
+  }
+
+  /// Returns the Snap settings.
+  pub fn snap(&self) -> &SnapSettings {
+    &self.bundle_settings.snap
+  }
+
+  /// Returns the portable archive settings.
+  pub fn archive(&self) -> &ArchiveSettings {
+    &self.bundle_settings.archive
+  }
+
+  /// Returns the pacman settings.
+  pub fn pacman(&self) -> &PacmanSettings {
+    &self.bundle_settings.pacman
+  }
+
+  /// Returns whether glob patterns in the Linux `files` maps may match no file.
+  pub fn allow_empty_file_globs(&self) -> bool {
+    self.bundle_settings.allow_empty_file_globs
+  }
+
   /// Returns the DMG settings.
   pub fn dmg(&self) -> &DmgSettings {
     &self.bundle_settings.dmg
   }
 
+  /// Returns the installer package settings.
+  pub fn pkg(&self) -> &PkgSettings {
+    &self.bundle_settings.pkg
+  }
+
   /// Returns the MacOS settings.
   pub fn macos(&self) -> &MacOsSettings {
     &self.bundle_settings.macos
@@ -1080,4 +1575,39 @@ impl Settings {
   pub fn updater(&self) -> Option<&UpdaterSettings> {
     self.bundle_settings.updater.as_ref()
   }
+
+  /// Returns the directory where the final bundle artifacts should be moved to.
+  pub fn output_dir(&self) -> Option<&Path> {
+    self.bundle_settings.output_dir.as_deref()
+  }
+
+  /// Returns the file name template for the final bundle artifacts.
+  pub fn artifact_name(&self) -> Option<&str> {
+    self.bundle_settings.artifact_name.as_deref()
+  }
+
+  /// Returns the digest algorithms of the checksum files written next to each bundle artifact.
+  pub fn checksums(&self) -> &[ChecksumKind] {
+    &self.bundle_settings.checksums
+  }
+
+  /// Returns whether the bundles must be byte-for-byte reproducible.
+  pub fn reproducible(&self) -> bool {
+    self.bundle_settings.reproducible
+  }
+
+  /// Returns whether the debug symbols of the app binaries are stripped before they are bundled.
+  ///
+  /// The binaries are stripped with `strip --strip-debug`, or `strip -x` on macOS to keep the symbols needed for code signing.
+  /// The MSVC binaries do not contain their debug symbols, so their `.pdb` files are copied to the `bundle/symbols` directory instead.
+  pub fn strip(&self) -> bool {
+    self.bundle_settings.strip
+  }
+
+  /// Returns the timestamp used for the modification times and build dates of the bundles.
+  ///
+  /// This is the `SOURCE_DATE_EPOCH` environment variable, falling back to `1980-01-01T00:00:00Z` for reproducible bundles.
+  pub fn source_date_epoch(&self) -> Option<u64> {
+    common::source_date_epoch().or(self.reproducible().then_some(REPRODUCIBLE_EPOCH))
+  }
 }
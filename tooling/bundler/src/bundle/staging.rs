@@ -0,0 +1,505 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Validation of the files staged in the packages.
+//!
+//! The binaries, sidecars, resources and custom files of each package type are listed with their
+//! path in the package before bundling, so a file silently overwriting another one is caught
+//! regardless of the bundler. Files with the same content are bundled once,
+//! files with different contents fail the build with [`crate::Error::StagingConflict`].
+
+use std::{
+  collections::HashMap,
+  fmt, fs,
+  io::{self, Read},
+  path::{Path, PathBuf},
+};
+
+use super::{common, PackageType, Settings};
+
+/// The kind of a staged file, reported in the diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StagedKind {
+  /// An application binary.
+  Binary,
+  /// An external binary, see [`crate::BundleSettings::external_bin`].
+  Sidecar,
+  /// A resource, see [`crate::BundleSettings::resources`].
+  Resource,
+  /// A custom file of the package type, e.g. [`crate::DebianSettings::files`].
+  CustomFile,
+}
+
+impl StagedKind {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Binary => "binary",
+      Self::Sidecar => "sidecar",
+      Self::Resource => "resource",
+      Self::CustomFile => "custom file",
+    }
+  }
+}
+
+impl fmt::Display for StagedKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// A file copied to a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFile {
+  /// The kind of file.
+  pub kind: StagedKind,
+  /// The path of the file on disk.
+  pub source: PathBuf,
+  /// The path of the file relative to the package root.
+  pub destination: PathBuf,
+}
+
+impl StagedFile {
+  fn new(kind: StagedKind, source: impl Into<PathBuf>, destination: impl Into<PathBuf>) -> Self {
+    Self {
+      kind,
+      source: source.into(),
+      destination: destination.into(),
+    }
+  }
+}
+
+/// A staged file dropped by [`dedupe`] since a file with the same content is already staged at its destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+  /// The file bundled at the destination.
+  pub kept: StagedFile,
+  /// The file that is not bundled.
+  pub dropped: StagedFile,
+}
+
+/// Where the files are staged in a package, relative to its root.
+struct Layout<'a> {
+  binaries: PathBuf,
+  resources: PathBuf,
+  custom_files: Option<&'a HashMap<PathBuf, PathBuf>>,
+}
+
+fn layout(settings: &Settings, package_type: PackageType) -> Option<Layout<'_>> {
+  let linux = |custom_files| Layout {
+    binaries: "usr/bin".into(),
+    resources: Path::new("usr/lib").join(settings.main_binary_name()),
+    custom_files,
+  };
+  let layout = match package_type {
+    PackageType::Deb | PackageType::AppImage => linux(Some(&settings.deb().files)),
+    PackageType::Rpm => linux(Some(&settings.rpm().files)),
+    PackageType::Pacman => linux(Some(&settings.pacman().files)),
+    PackageType::Snap => linux(Some(&settings.snap().files)),
+    // the package root is the `/app` prefix
+    PackageType::Flatpak => Layout {
+      binaries: "bin".into(),
+      resources: Path::new("lib").join(settings.main_binary_name()),
+      custom_files: Some(&settings.flatpak().files),
+    },
+    // the package root is the `Contents` directory
+    PackageType::MacOsBundle => Layout {
+      binaries: "MacOS".into(),
+      resources: "Resources".into(),
+      custom_files: Some(&settings.macos().files),
+    },
+    // the package root is the installation directory
+    PackageType::WindowsMsi | PackageType::Nsis => Layout {
+      binaries: PathBuf::new(),
+      resources: PathBuf::new(),
+      custom_files: Some(&settings.windows().files),
+    },
+    PackageType::Archive if settings.target().contains("windows") => Layout {
+      binaries: PathBuf::new(),
+      resources: PathBuf::new(),
+      custom_files: None,
+    },
+    PackageType::Archive if settings.target().contains("apple-darwin") => Layout {
+      binaries: "MacOS".into(),
+      resources: "Resources".into(),
+      custom_files: None,
+    },
+    PackageType::Archive => linux(None),
+    // these packages are created from the other bundles
+    _ => return None,
+  };
+  Some(layout)
+}
+
+/// Lists the external binaries with their file name, the target triple suffix being removed.
+pub fn sidecars(settings: &Settings) -> crate::Result<Vec<StagedFile>> {
+  let suffix = format!("-{}", settings.target());
+  let mut files = Vec::new();
+  for src in settings.external_binaries() {
+    let src = src?;
+    let name = src
+      .file_name()
+      .expect("failed to extract external binary filename")
+      .to_string_lossy()
+      .replace(&suffix, "");
+    files.push(StagedFile::new(StagedKind::Sidecar, src, name));
+  }
+  Ok(files)
+}
+
+/// Lists the files staged in the given package type, in the order they are copied.
+///
+/// Returns an empty list for the package types created from other bundles, e.g. DMG.
+pub fn plan(settings: &Settings, package_type: PackageType) -> crate::Result<Vec<StagedFile>> {
+  let Some(layout) = layout(settings, package_type) else {
+    return Ok(Vec::new());
+  };
+
+  let mut files = Vec::new();
+  for bin in settings.binaries() {
+    files.push(StagedFile::new(
+      StagedKind::Binary,
+      settings.binary_path(bin),
+      layout.binaries.join(bin.name()),
+    ));
+  }
+  for sidecar in sidecars(settings)? {
+    let destination = layout.binaries.join(&sidecar.destination);
+    files.push(StagedFile {
+      destination,
+      ..sidecar
+    });
+  }
+  for resource in settings.resources()? {
+    files.push(StagedFile::new(
+      StagedKind::Resource,
+      resource.path(),
+      layout.resources.join(resource.target()),
+    ));
+  }
+  if let Some(custom_files) = layout.custom_files {
+    for (destination, source) in
+      common::custom_files(custom_files, settings.allow_empty_file_globs())?
+    {
+      files.push(StagedFile::new(StagedKind::CustomFile, source, destination));
+    }
+  }
+  Ok(files)
+}
+
+/// Removes the files staged at the destination of a previous file with the same content.
+///
+/// Fails with [`crate::Error::StagingConflict`] when the contents differ.
+pub fn dedupe(files: Vec<StagedFile>) -> crate::Result<(Vec<StagedFile>, Vec<Duplicate>)> {
+  let mut kept: Vec<StagedFile> = Vec::with_capacity(files.len());
+  let mut destinations: HashMap<PathBuf, usize> = HashMap::new();
+  let mut duplicates = Vec::new();
+
+  for file in files {
+    let Some(&index) = destinations.get(&file.destination) else {
+      destinations.insert(file.destination.clone(), kept.len());
+      kept.push(file);
+      continue;
+    };
+    let existing = &kept[index];
+    if existing.source != file.source && !same_content(&existing.source, &file.source)? {
+      return Err(crate::Error::StagingConflict {
+        destination: file.destination,
+        first_kind: existing.kind.as_str(),
+        first: existing.source.clone(),
+        second_kind: file.kind.as_str(),
+        second: file.source,
+      });
+    }
+    duplicates.push(Duplicate {
+      kept: existing.clone(),
+      dropped: file,
+    });
+  }
+
+  Ok((kept, duplicates))
+}
+
+/// Logs the packages of the given type that would be written to `package_paths`
+/// and the files that would be staged in them, see [`plan`].
+///
+/// Returns `false` when not running in dry-run mode, in which case the packages must actually be written.
+/// The bundlers check it before changing anything on disk or running any tool.
+pub fn dry_run_package(
+  settings: &Settings,
+  package_type: PackageType,
+  package_paths: &[PathBuf],
+) -> crate::Result<bool> {
+  if !common::is_dry_run() {
+    return Ok(false);
+  }
+  for path in package_paths {
+    log::info!(action = "Dry run"; "{}would write {}", common::log_prefix(), path.display());
+  }
+  let (files, _) = dedupe(plan(settings, package_type)?)?;
+  for file in files {
+    // the stripped binaries are not written in dry-run mode either
+    let size = fs::metadata(&file.source)
+      .map(|metadata| format!(" ({} bytes)", metadata.len()))
+      .unwrap_or_default();
+    log::info!(
+      action = "Dry run";
+      "{}would bundle {} {} -> {}{}",
+      common::log_prefix(),
+      file.kind,
+      file.source.display(),
+      file.destination.display(),
+      size
+    );
+  }
+  Ok(true)
+}
+
+/// Validates the files staged in each package type, see [`plan`] and [`dedupe`].
+///
+/// The files bundled once and the resources already embedded in the app with the frontend
+/// are logged, so they show up in dry runs too.
+pub fn validate(settings: &Settings, package_types: &[PackageType]) -> crate::Result<()> {
+  if let Some(frontend_dist) = &settings.frontend_dist() {
+    warn_frontend_resources(settings, frontend_dist)?;
+  }
+
+  let mut reported = Vec::new();
+  for package_type in package_types {
+    let (_, duplicates) = dedupe(plan(settings, *package_type)?)?;
+    for duplicate in duplicates {
+      if reported.contains(&duplicate) {
+        continue;
+      }
+      if duplicate.kept.source == duplicate.dropped.source {
+        log::debug!(
+          "{} `{}` is staged twice at `{}`",
+          duplicate.dropped.kind,
+          duplicate.dropped.source.display(),
+          duplicate.dropped.destination.display()
+        );
+      } else {
+        log::info!(
+          "{} `{}` and {} `{}` are both staged at `{}` with the same content, bundling it once",
+          duplicate.kept.kind,
+          duplicate.kept.source.display(),
+          duplicate.dropped.kind,
+          duplicate.dropped.source.display(),
+          duplicate.dropped.destination.display()
+        );
+      }
+      reported.push(duplicate);
+    }
+  }
+  Ok(())
+}
+
+/// Warns about the resources inside the frontend assets directory, which is already embedded in the main binary.
+fn warn_frontend_resources(settings: &Settings, frontend_dist: &Path) -> crate::Result<()> {
+  let Ok(frontend_dist) = dunce::canonicalize(frontend_dist) else {
+    return Ok(());
+  };
+
+  let mut count = 0;
+  let mut size = 0;
+  for resource in settings.resources()? {
+    let Ok(path) = dunce::canonicalize(resource.path()) else {
+      continue;
+    };
+    if path.starts_with(&frontend_dist) {
+      count += 1;
+      size += fs::metadata(&path)?.len();
+    }
+  }
+
+  if count > 0 {
+    log::warn!(
+      "{count} resources ({size} bytes) are in the frontend assets directory `{}`, which is already embedded in the app binary. Remove it from `bundle > resources` unless the app reads these files from its resource directory",
+      frontend_dist.display()
+    );
+  }
+  Ok(())
+}
+
+/// Whether two files have the same content.
+fn same_content(a: &Path, b: &Path) -> crate::Result<bool> {
+  if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+    return Ok(false);
+  }
+
+  let mut a = io::BufReader::new(fs::File::open(a)?);
+  let mut b = io::BufReader::new(fs::File::open(b)?);
+  let mut buf_a = [0; 8192];
+  let mut buf_b = [0; 8192];
+  loop {
+    let read = a.read(&mut buf_a)?;
+    if read == 0 {
+      return Ok(true);
+    }
+    b.read_exact(&mut buf_b[..read])?;
+    if buf_a[..read] != buf_b[..read] {
+      return Ok(false);
+    }
+  }
+}
+
+/// Helpers checking that the bundlers do not write anything in dry-run mode, see [`dry_run_package`].
+#[cfg(test)]
+pub mod dry_run {
+  use super::common;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, Settings, SettingsBuilder};
+  use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+  };
+
+  /// Settings bundling a binary and a resource written to `out_dir` for the given target.
+  pub fn settings(out_dir: &Path, target: &str) -> Settings {
+    let bin = if target.contains("windows") {
+      "my-app.exe"
+    } else {
+      "my-app"
+    };
+    fs::write(out_dir.join(bin), b"my-app binary").unwrap();
+    let resource = out_dir.join("data.json");
+    fs::write(&resource, "{}").unwrap();
+    SettingsBuilder::new()
+      .project_out_directory(out_dir)
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        resources_map: Some(HashMap::from([(
+          resource.display().to_string(),
+          "data.json".into(),
+        )])),
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new(bin.into(), true)])
+      .target(target.into())
+      .build()
+      .unwrap()
+  }
+
+  /// The entries of `dir` with their size and modification time.
+  fn snapshot(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    walkdir::WalkDir::new(dir)
+      .sort_by_file_name()
+      .into_iter()
+      .map(|entry| {
+        let entry = entry.unwrap();
+        let metadata = entry.metadata().unwrap();
+        (
+          entry.path().to_path_buf(),
+          metadata.len(),
+          metadata.modified().unwrap(),
+        )
+      })
+      .collect()
+  }
+
+  /// Runs `bundle` in dry-run mode after writing the `previous` files of an earlier build to `out_dir`,
+  /// checking that it returns the `expected` package paths and leaves `out_dir` untouched.
+  pub fn assert_untouched(
+    out_dir: &Path,
+    previous: &[&str],
+    expected: &[&str],
+    bundle: impl FnOnce() -> crate::Result<Vec<PathBuf>>,
+  ) {
+    for path in previous {
+      let path = out_dir.join(path);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, "previous build").unwrap();
+    }
+    let before = snapshot(out_dir);
+    let paths = common::with_dry_run(true, bundle).unwrap();
+    assert_eq!(
+      paths,
+      expected
+        .iter()
+        .map(|path| out_dir.join(path))
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(snapshot(out_dir), before);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{dedupe, Duplicate, StagedFile, StagedKind};
+
+  #[test]
+  fn dedupes_identical_contents() {
+    let tmp = tempfile::tempdir().unwrap();
+    let sidecar = tmp.path().join("binaries/helper");
+    let resource = tmp.path().join("resources/helper");
+    for path in [&sidecar, &resource] {
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, "helper").unwrap();
+    }
+
+    let files = vec![
+      StagedFile::new(StagedKind::Sidecar, &sidecar, "helper"),
+      StagedFile::new(StagedKind::Resource, &resource, "helper"),
+      StagedFile::new(StagedKind::Sidecar, &sidecar, "helper"),
+      StagedFile::new(StagedKind::Resource, &resource, "assets/helper"),
+    ];
+    let (kept, duplicates) = dedupe(files.clone()).unwrap();
+    assert_eq!(kept, vec![files[0].clone(), files[3].clone()]);
+    assert_eq!(
+      duplicates,
+      vec![
+        Duplicate {
+          kept: files[0].clone(),
+          dropped: files[1].clone(),
+        },
+        Duplicate {
+          kept: files[0].clone(),
+          dropped: files[2].clone(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn fails_on_conflicting_contents() {
+    let tmp = tempfile::tempdir().unwrap();
+    let first = tmp.path().join("x86_64/helper");
+    let second = tmp.path().join("aarch64/helper");
+    for (path, content) in [(&first, "x86_64"), (&second, "arm64")] {
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, content).unwrap();
+    }
+
+    let err = dedupe(vec![
+      StagedFile::new(StagedKind::Sidecar, &first, "usr/bin/helper"),
+      StagedFile::new(StagedKind::CustomFile, &second, "usr/bin/helper"),
+    ])
+    .unwrap_err();
+    match &err {
+      crate::Error::StagingConflict {
+        destination,
+        first_kind,
+        first: f,
+        second_kind,
+        second: s,
+      } => {
+        assert_eq!(destination, std::path::Path::new("usr/bin/helper"));
+        assert_eq!((*first_kind, f), ("sidecar", &first));
+        assert_eq!((*second_kind, s), ("custom file", &second));
+      }
+      e => panic!("unexpected error {e}"),
+    }
+    let message = err.to_string();
+    assert!(message.contains(&first.display().to_string()));
+    assert!(message.contains(&second.display().to_string()));
+  }
+}
@@ -0,0 +1,172 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Strips the debug symbols of the app binaries before they are bundled, see [`Settings::strip`].
+
+use super::common::{self, CommandExt};
+use crate::Settings;
+
+use anyhow::Context;
+
+use std::{
+  env, fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// The environment variable overriding the `strip` tool, e.g. `aarch64-linux-gnu-strip` when cross compiling.
+const STRIP_ENV_VAR: &str = "STRIP";
+
+/// Writes the stripped copies of the app binaries to [`Settings::stripped_binaries_dir`],
+/// or copies their `.pdb` files to the `bundle/symbols` directory for the MSVC targets,
+/// whose binaries do not contain the debug symbols.
+///
+/// The binaries are copied as is with a warning when the `strip` tool is not installed.
+pub fn strip_binaries(settings: &Settings) -> crate::Result<()> {
+  let Some(stripped_dir) = settings.stripped_binaries_dir() else {
+    if settings.strip() {
+      copy_pdbs(settings)?;
+    }
+    return Ok(());
+  };
+
+  if stripped_dir.exists() && !common::is_dry_run() {
+    fs::remove_dir_all(&stripped_dir)
+      .with_context(|| format!("Failed to remove {}", stripped_dir.display()))?;
+  }
+
+  let tool = env::var_os(STRIP_ENV_VAR).unwrap_or_else(|| "strip".into());
+  let args = strip_args(settings.target());
+  let mut tool_found = true;
+  for bin in settings.binaries() {
+    let src = settings.project_out_directory().join(bin.name());
+    let dest = stripped_dir.join(bin.name());
+    common::copy_file(&src, &dest)?;
+
+    if common::is_dry_run() {
+      log::info!(action = "Dry run"; "would strip {}", dest.display());
+      continue;
+    }
+    if !tool_found {
+      continue;
+    }
+
+    log::info!(action = "Stripping"; "{}", src.display());
+    let output = Command::new(&tool).args(args).arg(&dest).output_ok();
+    if matches!(&output, Err(crate::Error::IoError(e)) if e.kind() == ErrorKind::NotFound) {
+      log::warn!(
+        "`{}` not found, bundling the binaries with their debug symbols; install it or set `{STRIP_ENV_VAR}` to its path",
+        tool.to_string_lossy()
+      );
+      tool_found = false;
+      continue;
+    }
+    output.with_context(|| format!("failed to strip {}", src.display()))?;
+  }
+
+  Ok(())
+}
+
+/// The `strip` arguments removing the debug symbols of the binaries for the given target.
+///
+/// On macOS only the local symbols are removed (`-x`), keeping the global symbols needed for code signing.
+fn strip_args(target: &str) -> &'static [&'static str] {
+  if target.contains("apple") {
+    &["-x"]
+  } else {
+    &["--strip-debug"]
+  }
+}
+
+/// Copies the `.pdb` files of the app binaries to the `bundle/symbols` directory,
+/// so they can be archived separately from the installers.
+fn copy_pdbs(settings: &Settings) -> crate::Result<()> {
+  let symbols_dir = settings.project_out_directory().join("bundle/symbols");
+  for bin in settings.binaries() {
+    let pdb = settings.project_out_directory().join(pdb_name(bin.name()));
+    if pdb.is_file() {
+      let dest = symbols_dir.join(pdb.file_name().unwrap());
+      log::info!(action = "Copying"; "{} to {}", pdb.display(), dest.display());
+      common::copy_file(&pdb, &dest)?;
+    } else {
+      log::warn!(
+        "{} not found, the debug symbols of {} are not copied",
+        pdb.display(),
+        bin.name()
+      );
+    }
+  }
+  Ok(())
+}
+
+/// The name of the `.pdb` file cargo writes next to the given binary, with the hyphens replaced by underscores.
+fn pdb_name(binary_name: &str) -> PathBuf {
+  let stem = Path::new(binary_name)
+    .file_stem()
+    .unwrap_or_default()
+    .to_string_lossy()
+    .replace('-', "_");
+  PathBuf::from(format!("{stem}.pdb"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{pdb_name, strip_args};
+  use crate::{BundleBinary, BundleSettings, PackageSettings, SettingsBuilder};
+  use std::path::PathBuf;
+
+  fn settings(target: &str, strip: bool) -> crate::Settings {
+    SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "MyApp".into(),
+        version: "1.2.3".into(),
+        description: String::new(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        strip,
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target(target.into())
+      .build()
+      .expect("failed to build settings")
+  }
+
+  #[test]
+  fn strips_for_target() {
+    assert_eq!(strip_args("aarch64-apple-darwin"), ["-x"]);
+    assert_eq!(strip_args("x86_64-unknown-linux-gnu"), ["--strip-debug"]);
+    assert_eq!(strip_args("x86_64-pc-windows-gnu"), ["--strip-debug"]);
+    assert_eq!(pdb_name("my-app.exe"), PathBuf::from("my_app.pdb"));
+  }
+
+  #[test]
+  fn bundles_stripped_binaries() {
+    let linux = settings("x86_64-unknown-linux-gnu", true);
+    let bin = &linux.binaries()[0];
+    assert_eq!(
+      linux.binary_path(bin),
+      PathBuf::from("target/release/bundle/stripped/my-app")
+    );
+
+    let unstripped = settings("x86_64-unknown-linux-gnu", false);
+    assert_eq!(
+      unstripped.binary_path(bin),
+      PathBuf::from("target/release/my-app")
+    );
+
+    // the MSVC binaries are signed in place and their debug symbols are in the .pdb files
+    let windows = settings("x86_64-pc-windows-msvc", true);
+    assert_eq!(windows.stripped_binaries_dir(), None);
+    assert_eq!(
+      windows.binary_path(bin),
+      PathBuf::from("target/release/my-app")
+    );
+  }
+}
@@ -40,9 +40,9 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
   }
 
   #[cfg(target_os = "macos")]
-  return bundle_update_macos(bundles);
+  return bundle_update_macos(settings, bundles);
   #[cfg(target_os = "linux")]
-  return bundle_update_linux(bundles);
+  return bundle_update_linux(settings, bundles);
 
   #[cfg(not(any(target_os = "macos", target_os = "linux")))]
   {
@@ -51,10 +51,11 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
   }
 }
 
-// Create simple update-macos.tar.gz
+// Create simple update-macos.tar.gz or update-macos.zip
 // This is the Mac OS App packaged
 #[cfg(target_os = "macos")]
-fn bundle_update_macos(bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
+fn bundle_update_macos(settings: &Settings, bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
+  use crate::MacUpdaterArtifact;
   use std::ffi::OsStr;
 
   // find our .app or rebuild our bundle
@@ -68,15 +69,27 @@ fn bundle_update_macos(bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
         .find(|path| path.extension() == Some(OsStr::new("app")))
     })
   {
-    // add .tar.gz to our path
-    let osx_archived = format!("{}.tar.gz", source_path.display());
+    let osx_archived = match settings.macos().updater_artifact {
+      MacUpdaterArtifact::TarGz => {
+        // add .tar.gz to our path
+        let osx_archived = format!("{}.tar.gz", source_path.display());
+
+        // Create our gzip file (need to send parent)
+        // as we walk the source directory (source isnt added)
+        create_tar(settings, source_path, Path::new(&osx_archived))
+          .with_context(|| "Failed to tar.gz update directory")?;
+        osx_archived
+      }
+      MacUpdaterArtifact::Zip => {
+        // add .zip to our path
+        let osx_archived = format!("{}.zip", source_path.display());
+        create_app_zip(source_path, Path::new(&osx_archived))
+          .with_context(|| "Failed to zip update directory")?;
+        osx_archived
+      }
+    };
     let osx_archived_path = PathBuf::from(&osx_archived);
 
-    // Create our gzip file (need to send parent)
-    // as we walk the source directory (source isnt added)
-    create_tar(source_path, &osx_archived_path)
-      .with_context(|| "Failed to tar.gz update directory")?;
-
     log::info!(action = "Bundling"; "{} ({})", osx_archived, display_path(&osx_archived_path));
 
     Ok(vec![osx_archived_path])
@@ -90,7 +103,7 @@ fn bundle_update_macos(bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
 // Right now in linux we hot replace the bin and request a restart
 // No assets are replaced
 #[cfg(target_os = "linux")]
-fn bundle_update_linux(bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
+fn bundle_update_linux(settings: &Settings, bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
   use std::ffi::OsStr;
 
   // build our app actually we support only appimage on linux
@@ -109,7 +122,7 @@ fn bundle_update_linux(bundles: &[Bundle]) -> crate::Result<Vec<PathBuf>> {
     let appimage_archived_path = PathBuf::from(&appimage_archived);
 
     // Create our gzip file
-    create_tar(source_path, &appimage_archived_path)
+    create_tar(settings, source_path, &appimage_archived_path)
       .with_context(|| "Failed to tar.gz update directory")?;
 
     log::info!(action = "Bundling"; "{} ({})", appimage_archived, display_path(&appimage_archived_path));
@@ -208,6 +221,9 @@ fn bundle_update_windows(settings: &Settings, bundles: &[Bundle]) -> crate::Resu
 }
 
 pub fn create_zip(src_file: &Path, dst_file: &Path) -> crate::Result<PathBuf> {
+  if common::dry_run_archive(&[src_file], dst_file)? {
+    return Ok(dst_file.to_owned());
+  }
   let parent_dir = dst_file.parent().expect("No data in parent");
   fs::create_dir_all(parent_dir)?;
   let writer = common::create_file(dst_file)?;
@@ -231,14 +247,40 @@ pub fn create_zip(src_file: &Path, dst_file: &Path) -> crate::Result<PathBuf> {
   Ok(dst_file.to_owned())
 }
 
+/// Creates a `.app.zip` archive with `ditto`, keeping the `.app` directory as the archive root.
+///
+/// Unlike a tar archive, `ditto` preserves the symbolic links, permissions and extended attributes
+/// (stored as AppleDouble resources) exactly, so the code signature remains valid after extraction.
+#[cfg(target_os = "macos")]
+fn create_app_zip(src_dir: &Path, dest_path: &Path) -> crate::Result<PathBuf> {
+  use common::CommandExt;
+  use std::process::Command;
+
+  if common::dry_run_archive(&[src_dir], dest_path)? {
+    return Ok(dest_path.to_owned());
+  }
+  if dest_path.exists() {
+    fs::remove_file(dest_path)?;
+  }
+  Command::new("ditto")
+    .args(["-c", "-k", "--sequesterRsrc", "--keepParent"])
+    .arg(src_dir)
+    .arg(dest_path)
+    .output_ok()?;
+  Ok(dest_path.to_owned())
+}
+
 #[cfg(not(target_os = "windows"))]
-fn create_tar(src_dir: &Path, dest_path: &Path) -> crate::Result<PathBuf> {
+fn create_tar(settings: &Settings, src_dir: &Path, dest_path: &Path) -> crate::Result<PathBuf> {
   use flate2::{write::GzEncoder, Compression};
 
+  if common::dry_run_archive(&[src_dir], dest_path)? {
+    return Ok(dest_path.to_owned());
+  }
   let dest_file = common::create_file(dest_path)?;
   let gzip_encoder = GzEncoder::new(dest_file, Compression::default());
 
-  let gzip_encoder = create_tar_from_src(src_dir, gzip_encoder)?;
+  let gzip_encoder = create_tar_from_src(settings, src_dir, gzip_encoder)?;
 
   let mut dest_file = gzip_encoder.finish()?;
   dest_file.flush()?;
@@ -246,18 +288,33 @@ fn create_tar(src_dir: &Path, dest_path: &Path) -> crate::Result<PathBuf> {
 }
 
 #[cfg(target_os = "macos")]
-fn create_tar_from_src<P: AsRef<Path>, W: Write>(src_dir: P, dest_file: W) -> crate::Result<W> {
+fn create_tar_from_src<P: AsRef<Path>, W: Write>(
+  settings: &Settings,
+  src_dir: P,
+  dest_file: W,
+) -> crate::Result<W> {
   let src_dir = src_dir.as_ref();
   let mut builder = tar::Builder::new(dest_file);
   builder.follow_symlinks(false);
-  builder.append_dir_all(src_dir.file_name().expect("Path has no file_name"), src_dir)?;
+  set_deterministic_mode(settings, &mut builder);
+  // safe to unwrap: the path has a parent
+  let base_dir = src_dir.parent().unwrap();
+  for entry in walkdir::WalkDir::new(src_dir).sort_by_file_name() {
+    let entry = entry?;
+    builder.append_path_with_name(entry.path(), entry.path().strip_prefix(base_dir)?)?;
+  }
   builder.into_inner().map_err(Into::into)
 }
 
 #[cfg(target_os = "linux")]
-fn create_tar_from_src<P: AsRef<Path>, W: Write>(src_dir: P, dest_file: W) -> crate::Result<W> {
+fn create_tar_from_src<P: AsRef<Path>, W: Write>(
+  settings: &Settings,
+  src_dir: P,
+  dest_file: W,
+) -> crate::Result<W> {
   let src_dir = src_dir.as_ref();
   let mut tar_builder = tar::Builder::new(dest_file);
+  set_deterministic_mode(settings, &mut tar_builder);
 
   // validate source type
   let file_type = fs::metadata(src_dir).expect("Can't read source directory");
@@ -270,7 +327,7 @@ fn create_tar_from_src<P: AsRef<Path>, W: Write>(src_dir: P, dest_file: W) -> cr
 
     tar_builder.append_file(file_name, &mut src_file)?;
   } else {
-    for entry in walkdir::WalkDir::new(src_dir) {
+    for entry in walkdir::WalkDir::new(src_dir).sort_by_file_name() {
       let entry = entry?;
       let src_path = entry.path();
       if src_path == src_dir {
@@ -293,3 +350,95 @@ fn create_tar_from_src<P: AsRef<Path>, W: Write>(src_dir: P, dest_file: W) -> cr
   let dest_file = tar_builder.into_inner()?;
   Ok(dest_file)
 }
+
+/// Uses stable ownership, permissions and timestamps for the archive entries
+/// when `SOURCE_DATE_EPOCH` is set or the bundles are reproducible.
+#[cfg(not(target_os = "windows"))]
+fn set_deterministic_mode<W: Write>(settings: &Settings, builder: &mut tar::Builder<W>) {
+  if settings.source_date_epoch().is_some() {
+    builder.mode(tar::HeaderMode::Deterministic);
+  }
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+  use super::*;
+  use common::CommandExt;
+  use std::{
+    os::unix::fs::{symlink, PermissionsExt},
+    process::Command,
+  };
+
+  /// Creates an app with a versioned framework, like the ones produced by Xcode.
+  fn fixture_app(dir: &Path) -> PathBuf {
+    let app = dir.join("MyApp.app");
+    let framework = app.join("Contents/Frameworks/Foo.framework");
+    fs::create_dir_all(framework.join("Versions/A")).unwrap();
+    fs::write(framework.join("Versions/A/Foo"), "framework binary").unwrap();
+    fs::set_permissions(
+      framework.join("Versions/A/Foo"),
+      fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    symlink("A", framework.join("Versions/Current")).unwrap();
+    symlink("Versions/Current/Foo", framework.join("Foo")).unwrap();
+
+    fs::create_dir_all(app.join("Contents/Resources")).unwrap();
+    let resource = app.join("Contents/Resources/data.txt");
+    fs::write(&resource, "data").unwrap();
+    fs::set_permissions(&resource, fs::Permissions::from_mode(0o644)).unwrap();
+    Command::new("xattr")
+      .args(["-w", "com.tauri.test", "value"])
+      .arg(&resource)
+      .output_ok()
+      .unwrap();
+    app
+  }
+
+  #[test]
+  fn app_zip_round_trip() {
+    let tmp = tempfile::tempdir().unwrap();
+    let app = fixture_app(tmp.path());
+    let archive = tmp.path().join("MyApp.app.zip");
+    create_app_zip(&app, &archive).unwrap();
+
+    let extracted = tmp.path().join("extracted");
+    Command::new("ditto")
+      .args(["-x", "-k"])
+      .arg(&archive)
+      .arg(&extracted)
+      .output_ok()
+      .unwrap();
+
+    let app = extracted.join("MyApp.app");
+    let framework = app.join("Contents/Frameworks/Foo.framework");
+    assert_eq!(
+      fs::read_link(framework.join("Versions/Current")).unwrap(),
+      Path::new("A")
+    );
+    assert_eq!(
+      fs::read_link(framework.join("Foo")).unwrap(),
+      Path::new("Versions/Current/Foo")
+    );
+    assert_eq!(
+      fs::metadata(framework.join("Versions/A/Foo"))
+        .unwrap()
+        .permissions()
+        .mode()
+        & 0o777,
+      0o755
+    );
+
+    let resource = app.join("Contents/Resources/data.txt");
+    assert_eq!(
+      fs::metadata(&resource).unwrap().permissions().mode() & 0o777,
+      0o644
+    );
+    let xattr = Command::new("xattr")
+      .args(["-p", "com.tauri.test"])
+      .arg(&resource)
+      .output_ok()
+      .unwrap();
+    assert_eq!(String::from_utf8_lossy(&xattr.stdout).trim(), "value");
+  }
+}
@@ -9,6 +9,8 @@ pub mod nsis;
 pub mod sign;
 
 mod util;
+#[cfg(target_os = "linux")]
+pub(crate) use util::download;
 pub use util::{
   NSIS_OUTPUT_FOLDER_NAME, NSIS_UPDATER_OUTPUT_FOLDER_NAME, WIX_OUTPUT_FOLDER_NAME,
   WIX_UPDATER_OUTPUT_FOLDER_NAME,
@@ -5,7 +5,7 @@
 
 mod wix;
 
-use crate::Settings;
+use crate::{bundle::staging, PackageType, Settings};
 
 use std::{self, path::PathBuf};
 
@@ -25,6 +25,11 @@ const WIX_REQUIRED_FILES: &[&str] = &[
 /// Runs all of the commands to build the MSI installer.
 /// Returns a vector of PathBuf that shows where the MSI was created.
 pub fn bundle_project(settings: &Settings, updater: bool) -> crate::Result<Vec<PathBuf>> {
+  let installer_paths = wix::app_installer_output_paths(settings, updater)?;
+  if staging::dry_run_package(settings, PackageType::WindowsMsi, &installer_paths)? {
+    return Ok(installer_paths);
+  }
+
   let mut wix_path = dirs::cache_dir().unwrap();
   wix_path.push("tauri/WixTools314");
 
@@ -41,3 +46,20 @@ pub fn bundle_project(settings: &Settings, updater: bool) -> crate::Result<Vec<P
 
   wix::build_wix_app_installer(settings, &wix_path, updater)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-pc-windows-msvc");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["wix/x64/main.wxs"],
+      &["bundle/msi/My App_1.0.0_x64_en-US.msi"],
+      || bundle_project(&settings, false),
+    );
+  }
+}
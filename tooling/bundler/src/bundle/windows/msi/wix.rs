@@ -4,9 +4,9 @@
 // SPDX-License-Identifier: MIT
 
 use crate::bundle::{
-  common::CommandExt,
+  common::{self, CommandExt},
   path_utils::{copy_file, FileOpts},
-  settings::Settings,
+  settings::{Settings, WixLanguage},
   windows::{
     sign::try_sign,
     util::{
@@ -26,7 +26,10 @@ use std::{
   path::{Path, PathBuf},
   process::Command,
 };
-use tauri_utils::{config::WebviewInstallMode, display_path};
+use tauri_utils::{
+  config::{WebviewInstallMode, WixInstallMode},
+  display_path,
+};
 use uuid::Uuid;
 
 // URLS for the WIX toolchain.  Can be used for cross-platform compilation.
@@ -110,6 +113,15 @@ impl ResourceDirectory {
     self.files.push(file);
   }
 
+  /// The paths of the files of this directory and of its children.
+  fn file_paths(&self) -> Vec<&Path> {
+    let mut paths: Vec<&Path> = self.files.iter().map(|file| file.path.as_path()).collect();
+    for directory in &self.directories {
+      paths.extend(directory.file_paths());
+    }
+    paths
+  }
+
   /// Generates the wix XML string to bundle this directory resources recursively
   fn get_wix_data(self) -> crate::Result<(String, Vec<String>)> {
     let mut files = String::from("");
@@ -172,6 +184,40 @@ fn copy_icon(settings: &Settings, filename: &str, path: &Path) -> crate::Result<
   Ok(icon_target_path)
 }
 
+/// Resolves a file referenced by `bundle > windows > wix > {config_key}` against `current_dir`,
+/// returning [`crate::Error::WixFileNotFound`] if it does not exist.
+fn wix_file(current_dir: &Path, config_key: &'static str, path: &Path) -> crate::Result<PathBuf> {
+  let path = current_dir.join(path);
+  if path.is_file() {
+    Ok(path)
+  } else {
+    Err(crate::Error::WixFileNotFound { config_key, path })
+  }
+}
+
+/// The paths of the installers built for each configured language, see [`app_installer_output_path`].
+pub fn app_installer_output_paths(
+  settings: &Settings,
+  updater: bool,
+) -> crate::Result<Vec<PathBuf>> {
+  configured_languages(settings)
+    .0
+    .iter()
+    .map(|(language, _)| {
+      app_installer_output_path(settings, language, settings.version_string(), updater)
+    })
+    .collect()
+}
+
+fn configured_languages(settings: &Settings) -> WixLanguage {
+  settings
+    .windows()
+    .wix
+    .as_ref()
+    .map(|w| w.language.clone())
+    .unwrap_or_default()
+}
+
 /// The app installer output path.
 fn app_installer_output_path(
   settings: &Settings,
@@ -248,25 +294,21 @@ fn clear_env_for_wix(cmd: &mut Command) {
 pub fn convert_version(version_str: &str) -> anyhow::Result<String> {
   let version = semver::Version::parse(version_str).context("invalid app version")?;
   if version.major > 255 {
-    bail!("app version major number cannot be greater than 255");
+    bail!("app version major number cannot be greater than 255 for msi target");
   }
   if version.minor > 255 {
-    bail!("app version minor number cannot be greater than 255");
+    bail!("app version minor number cannot be greater than 255 for msi target");
   }
   if version.patch > 65535 {
-    bail!("app version patch number cannot be greater than 65535");
+    bail!("app version patch number cannot be greater than 65535 for msi target");
   }
 
-  if !version.build.is_empty() {
-    let build = version.build.parse::<u64>();
-    if build.map(|b| b <= 65535).unwrap_or_default() {
-      return Ok(format!(
-        "{}.{}.{}.{}",
-        version.major, version.minor, version.patch, version.build
-      ));
-    } else {
-      bail!("optional build metadata in app version must be numeric-only and cannot be greater than 65535 for msi target");
-    }
+  // the build number, e.g. from `version > buildNumberEnv`, is the fourth field
+  if let Some(build) = tauri_utils::config::version::build_number(&version, "msi", 65535)? {
+    return Ok(format!(
+      "{}.{}.{}.{}",
+      version.major, version.minor, version.patch, build
+    ));
   }
 
   if !version.pre.is_empty() {
@@ -508,12 +550,7 @@ pub fn build_wix_app_installer(
   let language_map: HashMap<String, LanguageMetadata> =
     serde_json::from_str(include_str!("./languages.json")).unwrap();
 
-  let configured_languages = settings
-    .windows()
-    .wix
-    .as_ref()
-    .map(|w| w.language.clone())
-    .unwrap_or_default();
+  let configured_languages = configured_languages(settings);
 
   data.insert("product_name", to_json(settings.product_name()));
   data.insert("version", to_json(app_version));
@@ -535,6 +572,15 @@ pub fn build_wix_app_installer(
   .to_string();
 
   data.insert("upgrade_code", to_json(upgrade_code.as_str()));
+
+  let install_mode = settings
+    .windows()
+    .wix
+    .as_ref()
+    .map(|w| w.install_mode)
+    .unwrap_or_default();
+  insert_install_mode_data(&mut data, install_mode);
+
   data.insert(
     "allow_downgrades",
     to_json(settings.windows().allow_downgrades),
@@ -550,11 +596,18 @@ pub fn build_wix_app_installer(
   data.insert("app_exe_name", to_json(app_exe_name));
 
   let binaries = generate_binaries_data(settings)?;
+  let resources = generate_resource_data(settings)?;
+
+  // the files are compressed into the installer from their location
+  common::record_staged_size(
+    std::iter::once(app_exe_source.as_path())
+      .chain(binaries.iter().map(|binary| Path::new(&binary.path)))
+      .chain(resources.values().flat_map(ResourceDirectory::file_paths)),
+  )?;
 
   let binaries_json = to_json(binaries);
   data.insert("binaries", binaries_json);
 
-  let resources = generate_resource_data(settings)?;
   let mut resources_wix_string = String::from("");
   let mut files_ids = Vec::new();
   for (_, dir) in resources {
@@ -578,6 +631,7 @@ pub fn build_wix_app_installer(
 
   data.insert("icon_path", to_json(icon_path));
 
+  let current_dir = std::env::current_dir()?;
   let mut fragment_paths = Vec::new();
   let mut handlebars = Handlebars::new();
   handlebars.register_escape_fn(handlebars::no_escape);
@@ -590,11 +644,17 @@ pub fn build_wix_app_installer(
     data.insert("feature_group_refs", to_json(&wix.feature_group_refs));
     data.insert("feature_refs", to_json(&wix.feature_refs));
     data.insert("merge_refs", to_json(&wix.merge_refs));
-    fragment_paths.clone_from(&wix.fragment_paths);
+    // fail before running WiX when a referenced file is missing
+    fragment_paths = wix
+      .fragment_paths
+      .iter()
+      .map(|path| wix_file(&current_dir, "fragmentPaths", path))
+      .collect::<crate::Result<Vec<_>>>()?;
     enable_elevated_update_task = wix.enable_elevated_update_task;
     custom_template_path.clone_from(&wix.template);
 
     if let Some(banner_path) = &wix.banner_path {
+      let banner_path = &wix_file(&current_dir, "bannerPath", banner_path)?;
       let filename = banner_path
         .file_name()
         .unwrap()
@@ -607,6 +667,7 @@ pub fn build_wix_app_installer(
     }
 
     if let Some(dialog_image_path) = &wix.dialog_image_path {
+      let dialog_image_path = &wix_file(&current_dir, "dialogImagePath", dialog_image_path)?;
       let filename = dialog_image_path
         .file_name()
         .unwrap()
@@ -698,10 +759,8 @@ pub fn build_wix_app_installer(
 
   let mut candle_inputs = vec![("main.wxs".into(), Vec::new())];
 
-  let current_dir = std::env::current_dir()?;
   let extension_regex = Regex::new("\"http://schemas.microsoft.com/wix/(\\w+)\"")?;
   for fragment_path in fragment_paths {
-    let fragment_path = current_dir.join(fragment_path);
     let fragment = fs::read_to_string(&fragment_path)?;
     let mut extensions = Vec::new();
     for cap in extension_regex.captures_iter(&fragment) {
@@ -745,10 +804,12 @@ pub fn build_wix_app_installer(
       ),
     };
 
-    let locale_strings = include_str!("./default-locale-strings.xml")
-      .replace("__language__", &language_metadata.lang_id.to_string())
-      .replace("__codepage__", &language_metadata.ascii_code.to_string())
-      .replace("__productName__", settings.product_name());
+    let locale_strings = default_locale_strings(
+      language_metadata,
+      settings
+        .localized_product_name(&language)
+        .unwrap_or_else(|| settings.product_name()),
+    );
 
     let mut unset_locale_strings = String::new();
     let prefix_len = "<String ".len();
@@ -774,7 +835,7 @@ pub fn build_wix_app_installer(
       fileout.write_all(locale_contents.as_bytes())?;
     }
 
-    let arguments = vec![
+    let mut arguments = vec![
       format!(
         "-cultures:{}",
         if language == "en-US" {
@@ -787,6 +848,11 @@ pub fn build_wix_app_installer(
       display_path(&locale_path),
       "*.wixobj".into(),
     ];
+    if install_mode == WixInstallMode::PerUser {
+      // components installed to the user profile must use a HKCU registry key path
+      // and remove every folder they create, which the app files and resources do not
+      arguments.extend(["-sice:ICE38".into(), "-sice:ICE64".into()]);
+    }
     let msi_output_path = output_path.join("output.msi");
     let msi_path =
       app_installer_output_path(settings, &language, settings.version_string(), updater)?;
@@ -813,6 +879,37 @@ pub fn build_wix_app_installer(
   Ok(output_paths)
 }
 
+/// Inserts the template data that depends on the install scope.
+fn insert_install_mode_data(
+  data: &mut BTreeMap<&str, serde_json::Value>,
+  install_mode: WixInstallMode,
+) {
+  let (install_scope, registry_root) = match install_mode {
+    WixInstallMode::PerMachine => ("perMachine", "HKLM"),
+    WixInstallMode::PerUser => ("perUser", "HKCU"),
+    // HKMU resolves to HKLM or HKCU depending on the install scope selected at install time
+    WixInstallMode::Both => ("both", "HKMU"),
+  };
+  data.insert("install_scope", to_json(install_scope));
+  data.insert(
+    "per_user_install",
+    to_json(install_mode == WixInstallMode::PerUser),
+  );
+  data.insert(
+    "dual_scope_install",
+    to_json(install_mode == WixInstallMode::Both),
+  );
+  data.insert("registry_root", to_json(registry_root));
+}
+
+/// Renders the default locale strings of a language using its localized product name.
+fn default_locale_strings(language_metadata: &LanguageMetadata, product_name: &str) -> String {
+  include_str!("./default-locale-strings.xml")
+    .replace("__language__", &language_metadata.lang_id.to_string())
+    .replace("__codepage__", &language_metadata.ascii_code.to_string())
+    .replace("__productName__", product_name)
+}
+
 /// Generates the data required for the external binaries and extra binaries bundling.
 fn generate_binaries_data(settings: &Settings) -> crate::Result<Vec<Binary>> {
   let mut binaries = Vec::new();
@@ -899,10 +996,10 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
   let cwd = std::env::current_dir()?;
 
   let mut added_resources = Vec::new();
+  // the source path and the path relative to the installation directory of each file
+  let mut files = Vec::new();
 
-  for resource in settings.resource_files().iter() {
-    let resource = resource?;
-
+  for resource in settings.resources()? {
     let src = cwd.join(resource.path());
     let resource_path = dunce::simplified(&src).to_path_buf();
     // In some glob resource paths like `assets/**/*` a file might appear twice
@@ -913,15 +1010,23 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
     }
 
     added_resources.push(resource_path.clone());
+    files.push((resource_path, resource.target().to_path_buf()));
+  }
 
+  for (target_path, src) in
+    common::custom_files(&settings.windows().files, settings.allow_empty_file_globs())?
+  {
+    files.push((dunce::simplified(&cwd.join(src)).to_path_buf(), target_path));
+  }
+
+  for (resource_path, target_path) in files {
     let resource_entry = ResourceFile {
       id: format!("I{}", Uuid::new_v4().as_simple()),
       guid: Uuid::new_v4().to_string(),
-      path: resource_path.clone(),
+      path: resource_path,
     };
 
     // split the resource path directories
-    let target_path = resource.target();
     let components_count = target_path.components().count();
     let directories = target_path
       .components()
@@ -1015,3 +1120,100 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
 
   Ok(resources)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn default_locale_strings_use_localized_name() {
+    let language_map: HashMap<String, LanguageMetadata> =
+      serde_json::from_str(include_str!("./languages.json")).unwrap();
+
+    let zh_cn = default_locale_strings(&language_map["zh-CN"], "我的应用");
+    assert!(zh_cn.contains(r#"<String Id="TauriDisplayName">我的应用</String>"#));
+    assert!(zh_cn.contains(r#"<String Id="LaunchApp">Launch 我的应用</String>"#));
+
+    let ja_jp = default_locale_strings(&language_map["ja-JP"], "私のアプリ");
+    assert!(ja_jp.contains(r#"<String Id="TauriDisplayName">私のアプリ</String>"#));
+    assert!(!ja_jp.contains("__productName__"));
+  }
+
+  fn render_main_template(install_mode: WixInstallMode) -> String {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+      .register_template_string("main.wxs", include_str!("../templates/main.wxs"))
+      .unwrap();
+
+    let mut data = BTreeMap::new();
+    data.insert("manufacturer", to_json("tauri"));
+    data.insert("product_name", to_json("MyApp"));
+    data.insert("deep_link_protocols", to_json(["my-app"]));
+    insert_install_mode_data(&mut data, install_mode);
+    handlebars.render("main.wxs", &data).unwrap()
+  }
+
+  #[test]
+  fn main_template_install_mode() {
+    let per_machine = render_main_template(WixInstallMode::PerMachine);
+    assert!(per_machine.contains(r#"InstallScope="perMachine""#));
+    assert!(
+      per_machine.contains(r#"<Directory Id="$(var.PlatformProgramFilesFolder)" Name="PFiles">"#)
+    );
+    assert!(per_machine.contains(r#"<RegistryKey Root="HKLM" Key="Software\Classes\my-app">"#));
+    assert!(per_machine.contains(r#"<RegistryKey Root="HKLM" Key="Software\tauri\MyApp">"#));
+    assert!(!per_machine.contains("InstallScopeDlg"));
+    assert!(!per_machine.contains("PERMACHINEINSTALLDIR"));
+
+    let per_user = render_main_template(WixInstallMode::PerUser);
+    assert!(per_user.contains(r#"InstallScope="perUser""#));
+    assert!(per_user.contains(r#"<Directory Id="LocalAppDataFolder">"#));
+    assert!(!per_user.contains(r#"Name="PFiles""#));
+    assert!(per_user.contains(r#"<RegistryKey Root="HKCU" Key="Software\Classes\my-app">"#));
+    assert!(!per_user.contains(r#"<RegistryKey Root="HKLM""#));
+    // an existing per-machine installation blocks the per-user installation
+    assert!(per_user.contains(r#"<Condition Message="!(loc.PerMachineInstallExists)">"#));
+
+    let both = render_main_template(WixInstallMode::Both);
+    assert!(!both.contains("InstallScope="));
+    assert!(both.contains(r#"<Property Id="ALLUSERS" Value="2" Secure="yes" />"#));
+    assert!(both.contains(r#"<RegistryKey Root="HKMU" Key="Software\Classes\my-app">"#));
+    assert!(both.contains(r#"<Dialog Id="InstallScopeDlg""#));
+    assert!(both.contains(r#"Value="[LocalAppDataFolder]MyApp\""#));
+    assert!(!both.contains(r#"<Condition Message="!(loc.PerMachineInstallExists)">"#));
+  }
+
+  #[test]
+  fn converts_version() {
+    assert_eq!(convert_version("1.2.3").unwrap(), "1.2.3");
+    assert_eq!(convert_version("1.2.3+456").unwrap(), "1.2.3.456");
+    assert_eq!(convert_version("1.2.3-7").unwrap(), "1.2.3.7");
+
+    let error = convert_version("1.2.3+70000").unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "app version `1.2.3+70000` is invalid for the msi target: the build metadata must be a number lower than or equal to 65535"
+    );
+    assert!(convert_version("1.2.3+abc").is_err());
+    assert!(convert_version("256.0.0")
+      .unwrap_err()
+      .to_string()
+      .contains("msi target"));
+  }
+
+  #[test]
+  fn missing_wix_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::write(tmp.path().join("fragment.wxs"), "<Wix />").unwrap();
+
+    assert_eq!(
+      wix_file(tmp.path(), "fragmentPaths", Path::new("fragment.wxs")).unwrap(),
+      tmp.path().join("fragment.wxs")
+    );
+    assert!(matches!(
+      wix_file(tmp.path(), "fragmentPaths", Path::new("missing.wxs")).unwrap_err(),
+      crate::Error::WixFileNotFound { config_key: "fragmentPaths", path } if path == tmp.path().join("missing.wxs")
+    ));
+  }
+}
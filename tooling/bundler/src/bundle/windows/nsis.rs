@@ -2,17 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::bundle::windows::sign::{sign_command, try_sign};
+use crate::bundle::windows::sign::{sign_commands, try_sign};
 
 use crate::{
   bundle::{
-    common::CommandExt,
+    common::{self, CommandExt},
+    staging,
     windows::util::{
       download_and_verify, download_webview2_bootstrapper, download_webview2_offline_installer,
       verify_file_hash, HashAlgorithm, NSIS_OUTPUT_FOLDER_NAME, NSIS_UPDATER_OUTPUT_FOLDER_NAME,
     },
   },
-  Settings,
+  PackageType, Settings,
 };
 use tauri_utils::display_path;
 
@@ -63,6 +64,11 @@ const NSIS_REQUIRED_FILES_HASH: &[(&str, &str, &str, HashAlgorithm)] = &[(
 /// Runs all of the commands to build the NSIS installer.
 /// Returns a vector of PathBuf that shows where the NSIS installer was created.
 pub fn bundle_project(settings: &Settings, updater: bool) -> crate::Result<Vec<PathBuf>> {
+  let installer_path = installer_path(settings, arch(settings)?, updater);
+  if staging::dry_run_package(settings, PackageType::Nsis, &[installer_path.clone()])? {
+    return Ok(vec![installer_path]);
+  }
+
   let tauri_tools_path = dirs::cache_dir().unwrap().join("tauri");
   let nsis_toolset_path = tauri_tools_path.join("NSIS");
 
@@ -124,16 +130,11 @@ fn get_and_extract_nsis(nsis_toolset_path: &Path, _tauri_tools_path: &Path) -> c
 
 fn add_build_number_if_needed(version_str: &str) -> anyhow::Result<String> {
   let version = semver::Version::parse(version_str).context("invalid app version")?;
-  if !version.build.is_empty() {
-    let build = version.build.parse::<u64>();
-    if build.is_ok() {
-      return Ok(format!(
-        "{}.{}.{}.{}",
-        version.major, version.minor, version.patch, version.build
-      ));
-    } else {
-      anyhow::bail!("optional build metadata in app version must be numeric-only");
-    }
+  if let Some(build) = tauri_utils::config::version::build_number(&version, "nsis", u64::MAX)? {
+    return Ok(format!(
+      "{}.{}.{}.{}",
+      version.major, version.minor, version.patch, build
+    ));
   }
 
   Ok(format!(
@@ -141,23 +142,45 @@ fn add_build_number_if_needed(version_str: &str) -> anyhow::Result<String> {
     version.major, version.minor, version.patch,
   ))
 }
+/// The architecture of the installer, used in its file name.
+fn arch(settings: &Settings) -> crate::Result<&'static str> {
+  match settings.binary_arch() {
+    "x86_64" => Ok("x64"),
+    "x86" => Ok("x86"),
+    "aarch64" => Ok("arm64"),
+    target => Err(crate::Error::ArchError(format!(
+      "unsupported target: {}",
+      target
+    ))),
+  }
+}
+
+/// The path of the installer built for the given architecture.
+fn installer_path(settings: &Settings, arch: &str, updater: bool) -> PathBuf {
+  let package_base_name = format!(
+    "{}_{}_{}-setup",
+    settings.product_name(),
+    settings.version_string(),
+    arch,
+  );
+  settings.project_out_directory().join(format!(
+    "bundle/{}/{}.exe",
+    if updater {
+      NSIS_UPDATER_OUTPUT_FOLDER_NAME
+    } else {
+      NSIS_OUTPUT_FOLDER_NAME
+    },
+    package_base_name
+  ))
+}
+
 fn build_nsis_app_installer(
   settings: &Settings,
   _nsis_toolset_path: &Path,
   tauri_tools_path: &Path,
   updater: bool,
 ) -> crate::Result<Vec<PathBuf>> {
-  let arch = match settings.binary_arch() {
-    "x86_64" => "x64",
-    "x86" => "x86",
-    "aarch64" => "arm64",
-    target => {
-      return Err(crate::Error::ArchError(format!(
-        "unsupported target: {}",
-        target
-      )))
-    }
-  };
+  let arch = arch(settings)?;
 
   log::info!("Target: {}", arch);
 
@@ -197,8 +220,15 @@ fn build_nsis_app_installer(
   data.insert("copyright", to_json(settings.copyright_string()));
 
   if settings.can_sign() {
-    let sign_cmd = format!("{:?}", sign_command("%1", &settings.sign_params())?);
-    data.insert("uninstaller_sign_cmd", to_json(sign_cmd));
+    let mut sign_cmds = sign_commands("%1", &settings.sign_params())?
+      .into_iter()
+      .map(|cmd| format!("{cmd:?}"));
+    data.insert("uninstaller_sign_cmd", to_json(sign_cmds.next()));
+    // the signatures with the other digest algorithms
+    data.insert(
+      "uninstaller_append_sign_cmds",
+      to_json(sign_cmds.collect::<Vec<_>>()),
+    );
   }
 
   let version = settings.version_string();
@@ -250,8 +280,7 @@ fn build_nsis_app_installer(
     }
 
     if let Some(installer_hooks) = &nsis.installer_hooks {
-      let installer_hooks = dunce::canonicalize(installer_hooks)?;
-      data.insert("installer_hooks", to_json(installer_hooks));
+      insert_installer_hooks_data(&mut data, installer_hooks, &output_path)?;
     }
 
     if let Some(start_menu_folder) = &nsis.start_menu_folder {
@@ -284,9 +313,7 @@ fn build_nsis_app_installer(
     }),
   );
 
-  let languages = nsis
-    .and_then(|nsis| nsis.languages.clone())
-    .unwrap_or_else(|| vec!["English".into()]);
+  let languages = installer_languages(nsis.and_then(|nsis| nsis.languages.as_deref()));
   data.insert("languages", to_json(languages.clone()));
 
   data.insert(
@@ -299,6 +326,13 @@ fn build_nsis_app_installer(
   );
 
   let custom_language_files = nsis.and_then(|nsis| nsis.custom_language_files.clone());
+  if let Some(custom_language_files) = &custom_language_files {
+    for lang in custom_language_files.keys() {
+      if !languages.iter().any(|l| l.eq_ignore_ascii_case(lang)) {
+        log::warn!("Custom language file for {lang} is ignored because it is not in `tauri.conf.json > bundle > windows > nsis > languages`");
+      }
+    }
+  }
 
   let mut language_files_paths = Vec::new();
   for lang in &languages {
@@ -325,6 +359,10 @@ fn build_nsis_app_installer(
     }
   }
   data.insert("language_files", to_json(language_files_paths));
+  data.insert(
+    "display_names",
+    to_json(display_names_data(settings, &languages)),
+  );
 
   let main_binary = settings
     .binaries()
@@ -392,6 +430,12 @@ fn build_nsis_app_installer(
   data.insert("binaries", to_json(&binaries));
 
   let estimated_size = generate_estimated_size(&main_binary_path, &binaries, &resources)?;
+  // the files are compressed into the installer from their location
+  common::record_staged_size(
+    std::iter::once(&main_binary_path)
+      .chain(binaries.keys())
+      .chain(resources.keys()),
+  )?;
   data.insert("estimated_size", to_json(estimated_size));
 
   if let Some(file_associations) = settings.file_associations() {
@@ -463,35 +507,7 @@ fn build_nsis_app_installer(
     _ => {}
   }
 
-  let mut handlebars = Handlebars::new();
-  handlebars.register_helper("or", Box::new(handlebars_or));
-  handlebars.register_helper("association-description", Box::new(association_description));
-  handlebars.register_escape_fn(|s| {
-    let mut output = String::new();
-    for c in s.chars() {
-      match c {
-        '\"' => output.push_str("$\\\""),
-        '$' => output.push_str("$$"),
-        '`' => output.push_str("$\\`"),
-        '\n' => output.push_str("$\\n"),
-        '\t' => output.push_str("$\\t"),
-        '\r' => output.push_str("$\\r"),
-        _ => output.push(c),
-      }
-    }
-    output
-  });
-  if let Some(path) = custom_template_path {
-    handlebars
-      .register_template_string("installer.nsi", std::fs::read_to_string(path)?)
-      .map_err(|e| e.to_string())
-      .expect("Failed to setup custom handlebar template");
-  } else {
-    handlebars
-      .register_template_string("installer.nsi", include_str!("./templates/installer.nsi"))
-      .map_err(|e| e.to_string())
-      .expect("Failed to setup handlebar template");
-  }
+  let handlebars = installer_template(custom_template_path.as_deref())?;
 
   write_utf8_with_bom(
     output_path.join("FileAssociation.nsh"),
@@ -508,23 +524,8 @@ fn build_nsis_app_installer(
     handlebars.render("installer.nsi", &data)?,
   )?;
 
-  let package_base_name = format!(
-    "{}_{}_{}-setup",
-    settings.product_name(),
-    settings.version_string(),
-    arch,
-  );
-
   let nsis_output_path = output_path.join(out_file);
-  let nsis_installer_path = settings.project_out_directory().to_path_buf().join(format!(
-    "bundle/{}/{}.exe",
-    if updater {
-      NSIS_UPDATER_OUTPUT_FOLDER_NAME
-    } else {
-      NSIS_OUTPUT_FOLDER_NAME
-    },
-    package_base_name
-  ));
+  let nsis_installer_path = installer_path(settings, arch, updater);
   fs::create_dir_all(nsis_installer_path.parent().unwrap())?;
 
   log::info!(action = "Running"; "makensis.exe to produce {}", display_path(&nsis_installer_path));
@@ -561,6 +562,73 @@ fn build_nsis_app_installer(
   Ok(vec![nsis_installer_path])
 }
 
+/// Creates the handlebars registry rendering the `installer.nsi` script,
+/// using the custom template if provided.
+fn installer_template(custom_template_path: Option<&Path>) -> crate::Result<Handlebars<'static>> {
+  let mut handlebars = Handlebars::new();
+  handlebars.register_helper("or", Box::new(handlebars_or));
+  handlebars.register_helper("association-description", Box::new(association_description));
+  handlebars.register_escape_fn(|s| {
+    let mut output = String::new();
+    for c in s.chars() {
+      match c {
+        '\"' => output.push_str("$\\\""),
+        '$' => output.push_str("$$"),
+        '`' => output.push_str("$\\`"),
+        '\n' => output.push_str("$\\n"),
+        '\t' => output.push_str("$\\t"),
+        '\r' => output.push_str("$\\r"),
+        _ => output.push(c),
+      }
+    }
+    output
+  });
+  if let Some(path) = custom_template_path {
+    handlebars
+      .register_template_string("installer.nsi", std::fs::read_to_string(path)?)
+      .map_err(|e| e.to_string())
+      .expect("Failed to setup custom handlebar template");
+  } else {
+    handlebars
+      .register_template_string("installer.nsi", include_str!("./templates/installer.nsi"))
+      .map_err(|e| e.to_string())
+      .expect("Failed to setup handlebar template");
+  }
+
+  Ok(handlebars)
+}
+
+/// Copies the installer hooks file into the NSIS working directory and inserts it in the template data.
+///
+/// The original directory of the hooks file is added to the include directories
+/// so it can still `!include` files relative to itself.
+fn insert_installer_hooks_data(
+  data: &mut BTreeMap<&str, serde_json::Value>,
+  installer_hooks: &Path,
+  output_path: &Path,
+) -> crate::Result<()> {
+  if !installer_hooks.is_file() {
+    return Err(crate::Error::NsisInstallerHooksNotFound(
+      installer_hooks.to_path_buf(),
+    ));
+  }
+  let installer_hooks = dunce::canonicalize(installer_hooks)?;
+
+  let content = fs::read(&installer_hooks)?;
+  // the file is rewritten with a BOM, like the other scripts
+  let content = content
+    .strip_prefix(&[0xEF, 0xBB, 0xBF])
+    .unwrap_or(&content);
+  let hooks_path = output_path.join("installer-hooks.nsh");
+  write_utf8_with_bom(&hooks_path, content)?;
+
+  data.insert("installer_hooks", to_json(hooks_path));
+  if let Some(hooks_dir) = installer_hooks.parent() {
+    data.insert("installer_hooks_dir", to_json(hooks_dir));
+  }
+  Ok(())
+}
+
 fn handlebars_or(
   h: &handlebars::Helper<'_>,
   _: &Handlebars<'_>,
@@ -605,9 +673,7 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourcesMap> {
 
   let mut added_resources = Vec::new();
 
-  for resource in settings.resource_files().iter() {
-    let resource = resource?;
-
+  for resource in settings.resources()? {
     let src = cwd.join(resource.path());
     let resource_path = dunce::simplified(&src).to_path_buf();
 
@@ -632,6 +698,22 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourcesMap> {
     );
   }
 
+  for (target_path, src) in
+    common::custom_files(&settings.windows().files, settings.allow_empty_file_globs())?
+  {
+    let resource_path = dunce::simplified(&cwd.join(src)).to_path_buf();
+    resources.insert(
+      resource_path,
+      (
+        target_path
+          .parent()
+          .expect("Couldn't get parent of target path")
+          .to_path_buf(),
+        target_path,
+      ),
+    );
+  }
+
   Ok(resources)
 }
 
@@ -686,6 +768,23 @@ fn generate_estimated_size(
   Ok(size / 1024)
 }
 
+/// Returns the installer languages without duplicates, defaulting to English when none is configured.
+fn installer_languages(languages: Option<&[String]>) -> Vec<String> {
+  let mut installer_languages: Vec<String> = Vec::new();
+  for lang in languages.unwrap_or_default() {
+    if !installer_languages
+      .iter()
+      .any(|l| l.eq_ignore_ascii_case(lang))
+    {
+      installer_languages.push(lang.clone());
+    }
+  }
+  if installer_languages.is_empty() {
+    installer_languages.push("English".into());
+  }
+  installer_languages
+}
+
 fn get_lang_data(lang: &str) -> Option<(String, &[u8])> {
   let path = format!("{lang}.nsh");
   let content: &[u8] = match lang.to_lowercase().as_str() {
@@ -711,6 +810,50 @@ fn get_lang_data(lang: &str) -> Option<(String, &[u8])> {
   Some((path, content))
 }
 
+/// Maps an NSIS language name to its language tag.
+fn nsis_language_tag(lang: &str) -> Option<&'static str> {
+  let tag = match lang.to_lowercase().as_str() {
+    "arabic" => "ar",
+    "bulgarian" => "bg-BG",
+    "dutch" => "nl-NL",
+    "english" => "en-US",
+    "german" => "de-DE",
+    "japanese" => "ja-JP",
+    "korean" => "ko-KR",
+    "portuguesebr" => "pt-BR",
+    "russian" => "ru-RU",
+    "tradchinese" => "zh-TW",
+    "simpchinese" => "zh-CN",
+    "french" => "fr-FR",
+    "spanish" => "es-ES",
+    "spanishinternational" => "es",
+    "persian" => "fa-IR",
+    "turkish" => "tr-TR",
+    "swedish" => "sv-SE",
+    _ => return None,
+  };
+  Some(tag)
+}
+
+#[derive(Debug, PartialEq, Eq, serde::Serialize)]
+struct DisplayName<'a> {
+  /// The NSIS language identifier, used as `${LANG_<language>}`.
+  language: String,
+  /// The localized product name, `None` when the product name should be used.
+  name: Option<&'a str>,
+}
+
+/// Returns the product name to show in shortcuts and Add/Remove Programs for each installer language.
+fn display_names_data<'a>(settings: &'a Settings, languages: &[String]) -> Vec<DisplayName<'a>> {
+  languages
+    .iter()
+    .map(|lang| DisplayName {
+      language: lang.to_uppercase(),
+      name: nsis_language_tag(lang).and_then(|tag| settings.localized_product_name(tag)),
+    })
+    .collect()
+}
+
 fn write_utf8_with_bom<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, content: C) -> crate::Result<()> {
   use std::fs::File;
   use std::io::{BufWriter, Write};
@@ -721,3 +864,211 @@ fn write_utf8_with_bom<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, content: C) -> c
   output.write_all(content.as_ref())?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{BundleBinary, BundleSettings, PackageSettings, SettingsBuilder};
+
+  #[test]
+  fn installer_languages_default_and_dedupe() {
+    assert_eq!(installer_languages(None), ["English"]);
+    assert_eq!(installer_languages(Some(&[])), ["English"]);
+    assert_eq!(
+      installer_languages(Some(
+        &["German", "English", "german", "SimpChinese"].map(String::from)
+      )),
+      ["German", "English", "SimpChinese"]
+    );
+  }
+
+  #[test]
+  fn version_with_build_number() {
+    assert_eq!(add_build_number_if_needed("1.2.3").unwrap(), "1.2.3.0");
+    assert_eq!(
+      add_build_number_if_needed("1.2.3+456").unwrap(),
+      "1.2.3.456"
+    );
+    assert!(add_build_number_if_needed("1.2.3+abc")
+      .unwrap_err()
+      .to_string()
+      .contains("for the nsis target"));
+  }
+
+  #[test]
+  fn display_names_per_language() {
+    let settings = SettingsBuilder::new()
+      .project_out_directory("target/release")
+      .package_settings(PackageSettings {
+        product_name: "My App".into(),
+        version: "1.0.0".into(),
+        description: "An example application".into(),
+        homepage: None,
+        authors: None,
+        default_run: None,
+      })
+      .bundle_settings(BundleSettings {
+        identifier: Some("com.example.myapp".into()),
+        display_names: [
+          ("zh-CN".to_string(), "我的应用".to_string()),
+          ("ja".to_string(), "私のアプリ".to_string()),
+        ]
+        .into(),
+        ..Default::default()
+      })
+      .binaries(vec![BundleBinary::new("my-app".into(), true)])
+      .target("x86_64-pc-windows-msvc".into())
+      .build()
+      .expect("failed to build settings");
+
+    let languages = ["English", "SimpChinese", "Japanese", "TradChinese"].map(String::from);
+    assert_eq!(
+      display_names_data(&settings, &languages),
+      [
+        DisplayName {
+          language: "ENGLISH".into(),
+          name: None,
+        },
+        DisplayName {
+          language: "SIMPCHINESE".into(),
+          name: Some("我的应用"),
+        },
+        DisplayName {
+          language: "JAPANESE".into(),
+          name: Some("私のアプリ"),
+        },
+        DisplayName {
+          language: "TRADCHINESE".into(),
+          name: None,
+        },
+      ]
+    );
+  }
+
+  const INSTALLER_HOOKS: [&str; 4] = [
+    "NSIS_HOOK_PREINSTALL",
+    "NSIS_HOOK_POSTINSTALL",
+    "NSIS_HOOK_PREUNINSTALL",
+    "NSIS_HOOK_POSTUNINSTALL",
+  ];
+
+  fn render_installer(data: &BTreeMap<&str, serde_json::Value>) -> String {
+    installer_template(None)
+      .unwrap()
+      .render("installer.nsi", data)
+      .unwrap()
+      .replace("\r\n", "\n")
+  }
+
+  #[test]
+  fn installer_without_hooks() {
+    let installer = render_installer(&BTreeMap::new());
+    assert!(!installer.contains("!addincludedir"));
+    assert!(!installer.contains("installer-hooks.nsh"));
+    // the hooks are only inserted when defined
+    for hook in INSTALLER_HOOKS {
+      assert!(installer.contains(&format!("!ifmacrodef {hook}\n    !insertmacro {hook}\n")));
+    }
+  }
+
+  #[test]
+  fn installer_install_modes() {
+    for mode in ["currentUser", "perMachine", "both"] {
+      let installer = render_installer(&BTreeMap::from([("install_mode", to_json(mode))]));
+      assert!(installer.contains(&format!("!define INSTALLMODE \"{mode}\"\n")));
+    }
+
+    let installer = render_installer(&BTreeMap::new());
+    // the previous install mode is restored after MultiUser picks its default
+    assert!(
+      installer.contains("    !insertmacro MULTIUSER_INIT\n    Call RestorePreviousInstallMode\n")
+    );
+    // an explicit mode flag is respected
+    assert!(installer
+      .contains("${GetOptions} $CMDLINE \"/ALLUSERS\" $R0\n  ${IfNot} ${Errors}\n    Return"));
+    assert!(installer
+      .contains("${GetOptions} $CMDLINE \"/CURRENTUSER\" $R0\n  ${IfNot} ${Errors}\n    Return"));
+    // per-machine installations are updated elevated
+    assert!(installer.contains("ExecShell \"runas\" \"$EXEPATH\" \"$R0 /ALLUSERS\""));
+    // the install mode page is skipped when updating
+    assert!(installer.contains(
+      "!define MUI_PAGE_CUSTOMFUNCTION_PRE SkipIfPassiveOrUpdate\n  !insertmacro MULTIUSER_PAGE_INSTALLMODE"
+    ));
+    // the uninstall registry key is removed from the hive of the install mode
+    assert!(installer
+      .contains("!if \"${INSTALLMODE}\" == \"both\"\n    DeleteRegKey SHCTX \"${UNINSTKEY}\""));
+  }
+
+  #[test]
+  fn installer_with_hooks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let output_path = tmp.path().join("nsis");
+    fs::create_dir(&output_path).unwrap();
+    let hooks = INSTALLER_HOOKS
+      .map(|hook| format!("!macro {hook}\n  DetailPrint \"{hook}\"\n!macroend\n"))
+      .concat();
+    let hooks_path = tmp.path().join("hooks.nsh");
+    fs::write(&hooks_path, &hooks).unwrap();
+
+    let mut data = BTreeMap::new();
+    insert_installer_hooks_data(&mut data, &hooks_path, &output_path).unwrap();
+
+    let copied_hooks_path = output_path.join("installer-hooks.nsh");
+    assert_eq!(
+      fs::read(&copied_hooks_path).unwrap(),
+      [&[0xEF, 0xBB, 0xBF], hooks.as_bytes()].concat()
+    );
+    let installer = render_installer(&data);
+    assert!(installer.contains(&format!(
+      "!addincludedir \"{}\"\n!include \"{}\"\n",
+      dunce::canonicalize(tmp.path()).unwrap().display(),
+      copied_hooks_path.display()
+    )));
+  }
+
+  #[test]
+  fn installer_with_partial_hooks() {
+    let tmp = tempfile::tempdir().unwrap();
+    // the BOM is not duplicated
+    let hooks = "\u{feff}!macro NSIS_HOOK_POSTINSTALL\n  DetailPrint \"installed\"\n!macroend\n";
+    let hooks_path = tmp.path().join("hooks.nsh");
+    fs::write(&hooks_path, hooks).unwrap();
+
+    let mut data = BTreeMap::new();
+    insert_installer_hooks_data(&mut data, &hooks_path, tmp.path()).unwrap();
+
+    assert_eq!(
+      fs::read_to_string(tmp.path().join("installer-hooks.nsh")).unwrap(),
+      hooks
+    );
+    // undefined hooks are skipped by makensis
+    let installer = render_installer(&data);
+    for hook in INSTALLER_HOOKS {
+      assert!(installer.contains(&format!("!ifmacrodef {hook}\n    !insertmacro {hook}\n")));
+    }
+  }
+
+  #[test]
+  fn missing_installer_hooks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let hooks_path = tmp.path().join("hooks.nsh");
+    let error =
+      insert_installer_hooks_data(&mut BTreeMap::new(), &hooks_path, tmp.path()).unwrap_err();
+    assert!(matches!(
+      error,
+      crate::Error::NsisInstallerHooksNotFound(path) if path == hooks_path
+    ));
+  }
+
+  #[test]
+  fn dry_run_does_not_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let settings = staging::dry_run::settings(tmp.path(), "x86_64-pc-windows-msvc");
+    staging::dry_run::assert_untouched(
+      tmp.path(),
+      &["nsis/x64/installer.nsi"],
+      &["bundle/nsis/My App_1.0.0_x64-setup.exe"],
+      || bundle_project(&settings, false),
+    );
+  }
+}
@@ -5,13 +5,12 @@
 
 #[cfg(windows)]
 use crate::bundle::windows::util;
-use crate::{bundle::common::CommandExt, Settings};
-use anyhow::Context;
+use crate::{bundle::common::CommandExt, CustomSignCommandSettings, DigestAlgorithm, Settings};
 #[cfg(windows)]
 use std::path::PathBuf;
 #[cfg(windows)]
 use std::sync::OnceLock;
-use std::{path::Path, process::Command};
+use std::{ffi::OsString, path::Path, process::Command};
 
 impl Settings {
   pub(crate) fn can_sign(&self) -> bool {
@@ -21,12 +20,16 @@ impl Settings {
   pub(crate) fn sign_params(&self) -> SignParams {
     SignParams {
       product_name: self.product_name().into(),
-      digest_algorithm: self
-        .windows()
-        .digest_algorithm
-        .as_ref()
-        .map(|algorithm| algorithm.to_string())
-        .unwrap_or_else(|| "sha256".to_string()),
+      digest_algorithms: if self.windows().digest_algorithm.is_empty() {
+        vec![DigestAlgorithm::Sha256.to_string()]
+      } else {
+        self
+          .windows()
+          .digest_algorithm
+          .iter()
+          .map(|algorithm| algorithm.to_string())
+          .collect()
+      },
       certificate_thumbprint: self
         .windows()
         .certificate_thumbprint
@@ -46,11 +49,12 @@ impl Settings {
 #[cfg_attr(not(windows), allow(dead_code))]
 pub struct SignParams {
   pub product_name: String,
-  pub digest_algorithm: String,
+  /// The digest algorithm of each signature, in order. Never empty.
+  pub digest_algorithms: Vec<String>,
   pub certificate_thumbprint: String,
   pub timestamp_url: Option<String>,
   pub tsp: bool,
-  pub sign_command: Option<String>,
+  pub sign_command: Option<CustomSignCommandSettings>,
 }
 
 #[cfg(windows)]
@@ -132,79 +136,212 @@ pub fn verify(path: &Path) -> crate::Result<bool> {
   Ok(cmd.status()?.success())
 }
 
-pub fn sign_command_custom<P: AsRef<Path>>(path: P, command: &str) -> crate::Result<Command> {
+/// Expands the placeholders of a custom sign command argument:
+/// `%1` with the path of the file to sign, `%2` with the digest algorithm and `%3` with the timestamp server URL.
+fn expand_sign_command_arg(
+  arg: &str,
+  path: &Path,
+  digest_algorithm: &str,
+  timestamp_url: Option<&str>,
+) -> OsString {
+  if arg == "%1" {
+    // keep paths that are not valid UTF-8 intact
+    path.as_os_str().to_os_string()
+  } else {
+    let path = path.to_string_lossy();
+    // expand in a single pass so placeholders in the values are kept as is
+    let mut expanded = String::new();
+    let mut rest = arg;
+    while let Some(i) = rest.find('%') {
+      expanded.push_str(&rest[..i]);
+      let value = match rest[i + 1..].chars().next() {
+        Some('1') => Some(&*path),
+        Some('2') => Some(digest_algorithm),
+        Some('3') => Some(timestamp_url.unwrap_or_default()),
+        _ => None,
+      };
+      match value {
+        Some(value) => {
+          expanded.push_str(value);
+          rest = &rest[i + 2..];
+        }
+        None => {
+          expanded.push('%');
+          rest = &rest[i + 1..];
+        }
+      }
+    }
+    expanded.push_str(rest);
+    expanded.into()
+  }
+}
+
+pub fn sign_command_custom<P: AsRef<Path>>(
+  path: P,
+  command: &CustomSignCommandSettings,
+  digest_algorithm: &str,
+  timestamp_url: Option<&str>,
+) -> crate::Result<Command> {
   let path = path.as_ref();
 
-  let mut args = command.trim().split(' ');
-  let bin = args
-    .next()
-    .context("custom signing command doesn't contain a bin?")?;
+  let mut cmd = Command::new(&command.cmd);
+  for arg in &command.args {
+    cmd.arg(expand_sign_command_arg(
+      arg,
+      path,
+      digest_algorithm,
+      timestamp_url,
+    ));
+  }
+  Ok(cmd)
+}
+
+/// The `signtool` arguments creating a signature with the given digest algorithm,
+/// appended to the existing signatures of the file if `append` is set.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn signtool_args<'a>(
+  params: &'a SignParams,
+  digest_algorithm: &'a str,
+  append: bool,
+) -> Vec<&'a str> {
+  let mut args = vec!["sign"];
+  if append {
+    args.push("/as");
+  }
+  args.extend(["/fd", digest_algorithm]);
+  args.extend(["/sha1", &params.certificate_thumbprint]);
+  args.extend(["/d", &params.product_name]);
 
-  let mut cmd = Command::new(bin);
-  for arg in args {
-    if arg == "%1" {
-      cmd.arg(path);
+  if let Some(timestamp_url) = &params.timestamp_url {
+    if params.tsp {
+      args.extend(["/tr", timestamp_url]);
+      args.extend(["/td", digest_algorithm]);
     } else {
-      cmd.arg(arg);
+      args.extend(["/t", timestamp_url]);
     }
   }
-  Ok(cmd)
+
+  args
 }
 
 #[cfg(windows)]
 pub fn sign_command_default<P: AsRef<Path>>(
   path: P,
   params: &SignParams,
+  digest_algorithm: &str,
+  append: bool,
 ) -> crate::Result<Command> {
   let signtool = signtool().ok_or(crate::Error::SignToolNotFound)?;
 
   let mut cmd = Command::new(signtool);
-  cmd.arg("sign");
-  cmd.args(["/fd", &params.digest_algorithm]);
-  cmd.args(["/sha1", &params.certificate_thumbprint]);
-  cmd.args(["/d", &params.product_name]);
-
-  if let Some(ref timestamp_url) = params.timestamp_url {
-    if params.tsp {
-      cmd.args(["/tr", timestamp_url]);
-      cmd.args(["/td", &params.digest_algorithm]);
-    } else {
-      cmd.args(["/t", timestamp_url]);
-    }
-  }
-
+  cmd.args(signtool_args(params, digest_algorithm, append));
   cmd.arg(path.as_ref());
 
   Ok(cmd)
 }
 
-pub fn sign_command<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Result<Command> {
-  match &params.sign_command {
-    Some(custom_command) => sign_command_custom(path, custom_command),
-    #[cfg(windows)]
-    None => sign_command_default(path, params),
-
-    // should not be reachable
-    #[cfg(not(windows))]
-    None => Ok(Command::new("")),
-  }
+/// The commands signing the file, one for each digest algorithm.
+#[cfg_attr(not(windows), allow(unused_variables))]
+pub fn sign_commands<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Result<Vec<Command>> {
+  let path = path.as_ref();
+  params
+    .digest_algorithms
+    .iter()
+    .enumerate()
+    .map(|(i, digest_algorithm)| match &params.sign_command {
+      Some(custom_command) => sign_command_custom(
+        path,
+        custom_command,
+        digest_algorithm,
+        params.timestamp_url.as_deref(),
+      ),
+      #[cfg(windows)]
+      None => sign_command_default(path, params, digest_algorithm, i > 0),
+
+      // should not be reachable
+      #[cfg(not(windows))]
+      None => Ok(Command::new("")),
+    })
+    .collect()
 }
 
-pub fn sign_custom<P: AsRef<Path>>(path: P, custom_command: &str) -> crate::Result<()> {
+/// Runs the custom sign command once for each digest algorithm.
+pub fn sign_custom<P: AsRef<Path>>(
+  path: P,
+  custom_command: &CustomSignCommandSettings,
+  params: &SignParams,
+) -> crate::Result<()> {
   let path = path.as_ref();
 
   log::info!(action = "Signing";"{} with a custom signing command", tauri_utils::display_path(path));
 
-  let mut cmd = sign_command_custom(path, custom_command)?;
+  for digest_algorithm in &params.digest_algorithms {
+    let mut cmd = sign_command_custom(
+      path,
+      custom_command,
+      digest_algorithm,
+      params.timestamp_url.as_deref(),
+    )?;
 
-  let output = cmd.output_ok()?;
+    let output = cmd.output_ok()?;
 
-  let stdout = String::from_utf8_lossy(output.stdout.as_slice()).into_owned();
-  log::info!("{:?}", stdout);
+    let stdout = String::from_utf8_lossy(output.stdout.as_slice()).into_owned();
+    log::info!("{:?}", stdout);
+  }
 
   Ok(())
 }
 
+#[cfg(windows)]
+const TIMESTAMP_RETRIES: u32 = 3;
+#[cfg(windows)]
+const TIMESTAMP_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Messages of the signtool errors caused by the signing certificate or its private key.
+const CERTIFICATE_ERRORS: &[&str] = &[
+  "No certificates were found",
+  "private key",
+  // NTE_BAD_KEYSET, the private key is not accessible
+  "0x80090016",
+];
+
+/// Whether signtool printed any of the given messages before failing.
+fn signtool_failed_with(error: &crate::Error, messages: &[&str]) -> bool {
+  let error = match error {
+    crate::Error::CommandRetriesFailed { source, .. } => source,
+    error => error,
+  };
+  matches!(
+    error,
+    crate::Error::CommandFailed { stdout, stderr, .. }
+      if messages.iter().any(|message| stderr.contains(message) || stdout.contains(message))
+  )
+}
+
+/// Whether signtool failed because the timestamp server could not be reached or returned an invalid response.
+fn is_timestamp_error(error: &crate::Error) -> bool {
+  signtool_failed_with(error, &["timestamp server"])
+}
+
+/// Turns a signtool error into a [`crate::Error::TimestampServer`] or a [`crate::Error::SigningCertificate`] error
+/// when the timestamp server or the certificate is the cause.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn sign_error(path: &Path, params: &SignParams, error: crate::Error) -> crate::Error {
+  match &params.timestamp_url {
+    Some(url) if is_timestamp_error(&error) => crate::Error::TimestampServer {
+      path: path.to_path_buf(),
+      url: url.clone(),
+      source: Box::new(error),
+    },
+    _ if signtool_failed_with(&error, CERTIFICATE_ERRORS) => crate::Error::SigningCertificate {
+      path: path.to_path_buf(),
+      thumbprint: params.certificate_thumbprint.clone(),
+      source: Box::new(error),
+    },
+    _ => error,
+  }
+}
+
 #[cfg(windows)]
 pub fn sign_default<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Result<()> {
   let signtool = signtool().ok_or(crate::Error::SignToolNotFound)?;
@@ -212,21 +349,30 @@ pub fn sign_default<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Resu
 
   log::info!(action = "Signing"; "{} with identity \"{}\"", tauri_utils::display_path(path), params.certificate_thumbprint);
 
-  let mut cmd = sign_command_default(path, params)?;
-  log::debug!("Running signtool {:?}", signtool);
-
-  // Execute SignTool command
-  let output = cmd.output_ok()?;
-
-  let stdout = String::from_utf8_lossy(output.stdout.as_slice()).into_owned();
-  log::info!("{:?}", stdout);
+  // the first signature replaces the existing ones, the next ones are appended to it
+  for (i, digest_algorithm) in params.digest_algorithms.iter().enumerate() {
+    let mut cmd = sign_command_default(path, params, digest_algorithm, i > 0)?;
+    log::debug!("Running signtool {:?}", signtool);
+
+    // Execute SignTool command, the timestamp server is often unreachable for a short while
+    let output = cmd
+      .output_ok_retry(
+        TIMESTAMP_RETRIES,
+        TIMESTAMP_RETRY_BACKOFF,
+        is_timestamp_error,
+      )
+      .map_err(|error| sign_error(path, params, error))?;
+
+    let stdout = String::from_utf8_lossy(output.stdout.as_slice()).into_owned();
+    log::info!("{:?}", stdout);
+  }
 
   Ok(())
 }
 
 pub fn sign<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Result<()> {
   match &params.sign_command {
-    Some(custom_command) => sign_custom(path, custom_command),
+    Some(custom_command) => sign_custom(path, custom_command, params),
     #[cfg(windows)]
     None => sign_default(path, params),
     // should not be reachable, as user should either use Windows
@@ -243,3 +389,233 @@ pub fn try_sign(file_path: &std::path::PathBuf, settings: &Settings) -> crate::R
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    sign_command_custom, sign_error, signtool_args, CustomSignCommandSettings, SignParams,
+  };
+  use std::path::Path;
+
+  fn command(cmd: &str, args: &[&str]) -> CustomSignCommandSettings {
+    CustomSignCommandSettings {
+      cmd: cmd.into(),
+      args: args.iter().map(|arg| arg.to_string()).collect(),
+    }
+  }
+
+  fn sign_params(digest_algorithms: &[&str], timestamp_url: Option<&str>, tsp: bool) -> SignParams {
+    SignParams {
+      product_name: "My App".into(),
+      digest_algorithms: digest_algorithms.iter().map(|a| a.to_string()).collect(),
+      certificate_thumbprint: "A1B2C3".into(),
+      timestamp_url: timestamp_url.map(Into::into),
+      tsp,
+      sign_command: None,
+    }
+  }
+
+  fn signtool_error(stdout: &str) -> crate::Error {
+    crate::Error::CommandFailed {
+      program: "signtool.exe".into(),
+      args: Vec::new(),
+      exit_code: Some(1),
+      signal: None,
+      stdout: stdout.into(),
+      stderr: String::new(),
+    }
+  }
+
+  #[test]
+  fn expands_placeholders() {
+    let cmd = sign_command_custom(
+      Path::new("dir with spaces/app.exe"),
+      &command("sign-cli", &["--file=%1", "%1", "%1%1", "--arg"]),
+      "sha256",
+      None,
+    )
+    .unwrap();
+    assert_eq!(cmd.get_program(), "sign-cli");
+    assert_eq!(
+      cmd.get_args().collect::<Vec<_>>(),
+      [
+        "--file=dir with spaces/app.exe",
+        "dir with spaces/app.exe",
+        "dir with spaces/app.exedir with spaces/app.exe",
+        "--arg",
+      ]
+    );
+
+    let cmd = sign_command_custom(
+      Path::new("app%2.exe"),
+      &command(
+        "osslsigncode",
+        &[
+          "sign",
+          "-h",
+          "%2",
+          "-ts",
+          "%3",
+          "-in",
+          "%1",
+          "-out",
+          "%1.signed",
+          "100%",
+        ],
+      ),
+      "sha1",
+      Some("http://timestamp.digicert.com"),
+    )
+    .unwrap();
+    assert_eq!(
+      cmd.get_args().collect::<Vec<_>>(),
+      [
+        "sign",
+        "-h",
+        "sha1",
+        "-ts",
+        "http://timestamp.digicert.com",
+        "-in",
+        "app%2.exe",
+        "-out",
+        "app%2.exe.signed",
+        "100%",
+      ]
+    );
+  }
+
+  #[test]
+  fn signtool_signatures() {
+    let params = sign_params(
+      &["sha1", "sha256"],
+      Some("http://timestamp.digicert.com"),
+      true,
+    );
+    assert_eq!(
+      signtool_args(&params, "sha1", false),
+      [
+        "sign",
+        "/fd",
+        "sha1",
+        "/sha1",
+        "A1B2C3",
+        "/d",
+        "My App",
+        "/tr",
+        "http://timestamp.digicert.com",
+        "/td",
+        "sha1",
+      ]
+    );
+    assert_eq!(
+      signtool_args(&params, "sha256", true),
+      [
+        "sign",
+        "/as",
+        "/fd",
+        "sha256",
+        "/sha1",
+        "A1B2C3",
+        "/d",
+        "My App",
+        "/tr",
+        "http://timestamp.digicert.com",
+        "/td",
+        "sha256",
+      ]
+    );
+
+    let params = sign_params(&["sha256"], None, false);
+    assert_eq!(
+      signtool_args(&params, "sha256", false),
+      ["sign", "/fd", "sha256", "/sha1", "A1B2C3", "/d", "My App"]
+    );
+  }
+
+  #[test]
+  fn classifies_signtool_errors() {
+    let path = Path::new("app.exe");
+    let params = sign_params(&["sha256"], Some("http://timestamp.digicert.com"), true);
+
+    let error = sign_error(
+      path,
+      &params,
+      crate::Error::CommandRetriesFailed {
+        attempts: 4,
+        source: Box::new(signtool_error(
+          "SignTool Error: The specified timestamp server either could not be reached or returned an invalid response.",
+        )),
+      },
+    );
+    assert!(
+      matches!(&error, crate::Error::TimestampServer { url, .. } if url == "http://timestamp.digicert.com")
+    );
+
+    let error = sign_error(
+      path,
+      &params,
+      signtool_error("SignTool Error: No certificates were found that met all the given criteria."),
+    );
+    assert!(
+      matches!(&error, crate::Error::SigningCertificate { thumbprint, .. } if thumbprint == "A1B2C3")
+    );
+
+    let error = sign_error(
+      path,
+      &params,
+      signtool_error("SignTool Error: File not found"),
+    );
+    assert!(matches!(error, crate::Error::CommandFailed { .. }));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn runs_sign_script() {
+    use super::sign_custom;
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let script = tmp.path().join("sign.sh");
+    std::fs::write(
+      &script,
+      "#!/bin/sh\n[ \"$1\" = \"--marker\" ] || exit 1\nprintf '%s' \"$2\" >> \"$3\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let file = tmp.path().join("app.exe");
+    std::fs::write(&file, "binary").unwrap();
+
+    let script = script.to_string_lossy();
+    let params = sign_params(&["sha256"], None, false);
+    sign_custom(
+      &file,
+      &command(&script, &["--marker", "-signed", "%1"]),
+      &params,
+    )
+    .unwrap();
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "binary-signed");
+
+    // a non-zero exit status fails the signing
+    assert!(sign_custom(
+      &file,
+      &command(&script, &["--other", "-signed", "%1"]),
+      &params
+    )
+    .is_err());
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "binary-signed");
+
+    // the command runs once for each digest algorithm
+    let params = sign_params(&["sha1", "sha256"], None, false);
+    sign_custom(
+      &file,
+      &command(&script, &["--marker", "-%2", "%1"]),
+      &params,
+    )
+    .unwrap();
+    assert_eq!(
+      std::fs::read_to_string(&file).unwrap(),
+      "binary-signed-sha1-sha256"
+    );
+  }
+}
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{io, num, path};
+use std::{io, num, path, time::Duration};
 use thiserror::Error as DeriveError;
 
 /// Errors returned by the bundler.
@@ -51,13 +51,14 @@ pub enum Error {
   #[error("`{0}`")]
   HttpError(#[from] Box<ureq::Error>),
   /// Invalid glob pattern.
-  #[cfg(windows)]
   #[error("{0}")]
   GlobPattern(#[from] glob::PatternError),
   /// Failed to use glob pattern.
-  #[cfg(windows)]
   #[error("`{0}`")]
   Glob(#[from] glob::GlobError),
+  /// Glob pattern did not find any results.
+  #[error("path matching {0} not found.")]
+  GlobPathNotFound(String),
   /// Failed to validate downloaded file hash.
   #[error("hash mismatch of downloaded file")]
   HashError,
@@ -79,6 +80,291 @@ pub enum Error {
   /// Generic error.
   #[error("`{0}`")]
   GenericError(String),
+  /// A tool required to bundle the app was not found and downloading it is disabled.
+  #[error(
+    "{tool} not found, set `{env_var}` to its path or add it to the PATH, or disable the offline mode to download it"
+  )]
+  ToolNotFound {
+    /// The tool name.
+    tool: String,
+    /// The environment variable pointing to a preinstalled tool.
+    env_var: String,
+  },
+  /// A tool has no pinned release to download for the target architecture.
+  #[error(
+    "{tool} has no pinned release to download for this architecture, set `{env_var}` to its path or add it to the PATH"
+  )]
+  ToolNotPinned {
+    /// The tool name.
+    tool: String,
+    /// The environment variable pointing to a preinstalled tool.
+    env_var: String,
+  },
+  /// A downloaded or cached tool does not match the SHA-256 of its pinned release.
+  #[error("{tool} at `{location}` does not match its pinned SHA-256, expected {expected} but got {actual}")]
+  ToolChecksumMismatch {
+    /// The tool name.
+    tool: String,
+    /// The download URL or cache path of the tool.
+    location: String,
+    /// The pinned SHA-256.
+    expected: String,
+    /// The SHA-256 of the tool content.
+    actual: String,
+  },
+  /// The environment variable pointing to a preinstalled tool references a missing file.
+  #[error("`{env_var}` is set to `{}` which does not exist", .path.display())]
+  ToolPathNotFound {
+    /// The environment variable pointing to a preinstalled tool.
+    env_var: String,
+    /// The missing path.
+    path: path::PathBuf,
+  },
+  /// The NSIS installer hooks file does not exist.
+  #[error(
+    "NSIS installer hooks file `{}` does not exist, make sure `bundle > windows > nsis > installerHooks` points to an existing `.nsh` file",
+    .0.display()
+  )]
+  NsisInstallerHooksNotFound(path::PathBuf),
+  /// The Debian changelog is invalid.
+  #[error("invalid Debian changelog `{}`: {reason}", .path.display())]
+  InvalidDebChangelog {
+    /// The changelog path.
+    path: path::PathBuf,
+    /// Why the changelog is invalid.
+    reason: String,
+  },
+  /// A file referenced by the RPM configuration does not exist.
+  #[error(
+    "RPM file `{}` does not exist, make sure `bundle > linux > rpm > {config_key}` points to an existing file",
+    .path.display()
+  )]
+  RpmFileNotFound {
+    /// The configuration key referencing the file.
+    config_key: &'static str,
+    /// The missing path.
+    path: path::PathBuf,
+  },
+  /// A file referenced by the WiX configuration does not exist.
+  #[error(
+    "WiX file `{}` does not exist, make sure `bundle > windows > wix > {config_key}` points to an existing file",
+    .path.display()
+  )]
+  WixFileNotFound {
+    /// The configuration key referencing the file.
+    config_key: &'static str,
+    /// The missing path.
+    path: path::PathBuf,
+  },
+  /// An RPM dependency that is not a package name with an optional version constraint.
+  #[error(
+    "invalid RPM dependency `{dependency}` in `bundle > linux > rpm > {config_key}`, expected a package name optionally followed by a version constraint such as `libfoo >= 2.0`"
+  )]
+  InvalidRpmDependency {
+    /// The configuration key listing the dependency.
+    config_key: &'static str,
+    /// The invalid dependency.
+    dependency: String,
+  },
+  /// An unknown Debian maintainer script name.
+  #[error(
+    "unknown Debian maintainer script `{0}` in `bundle > linux > deb > maintainerScripts`, expected one of `preinst`, `postinst`, `prerm` or `postrm`"
+  )]
+  UnknownDebMaintainerScript(String),
+  /// A Debian maintainer script is configured twice.
+  #[error(
+    "the Debian `{name}` maintainer script is set by both `bundle > linux > deb > maintainerScripts` and `bundle > linux > deb > {config_key}`"
+  )]
+  DuplicateDebMaintainerScript {
+    /// The script name.
+    name: String,
+    /// The dedicated configuration key of the script.
+    config_key: &'static str,
+  },
+  /// The tool used to sign a Linux package is not installed.
+  #[error(
+    "`{tool}` is required to sign the {package} package with `bundle > linux > {package} > signKey` but it was not found, make sure it is installed and available on the PATH"
+  )]
+  SigningToolNotFound {
+    /// The signing tool.
+    tool: &'static str,
+    /// The package format, `deb` or `rpm`.
+    package: &'static str,
+  },
+  /// Multiple bundle artifacts resolve to the same output path.
+  #[error(
+    "bundle artifacts `{}` and `{}` resolve to the same output path `{}`, make sure `bundle > artifactName` is unique per artifact",
+    .first.display(),
+    .second.display(),
+    .destination.display()
+  )]
+  ArtifactPathCollision {
+    /// The first artifact resolving to the destination.
+    first: path::PathBuf,
+    /// The second artifact resolving to the destination.
+    second: path::PathBuf,
+    /// The colliding destination path.
+    destination: path::PathBuf,
+  },
+  /// Files with different contents are staged at the same path of a package.
+  #[error(
+    "{first_kind} `{}` and {second_kind} `{}` are both bundled at `{}` with different contents, rename one of them or remove it from the configuration",
+    .first.display(),
+    .second.display(),
+    .destination.display()
+  )]
+  StagingConflict {
+    /// The path of the files in the package.
+    destination: path::PathBuf,
+    /// The kind of the first file, e.g. `sidecar`.
+    first_kind: &'static str,
+    /// The first file staged at the destination.
+    first: path::PathBuf,
+    /// The kind of the second file.
+    second_kind: &'static str,
+    /// The second file staged at the destination.
+    second: path::PathBuf,
+  },
+  /// An input of the universal macOS binary does not exist.
+  #[error(
+    "universal binary input `{}` does not exist, make sure it was built for each architecture",
+    .0.display()
+  )]
+  UniversalBinaryInputNotFound(path::PathBuf),
+  /// The inputs of the universal macOS binary were built for the same architectures.
+  #[error(
+    "cannot create a universal binary from `{}` and `{}`, both contain the {} architectures",
+    .first.display(),
+    .second.display(),
+    .architectures.join(", ")
+  )]
+  UniversalBinaryArchitecturesOverlap {
+    /// The first input.
+    first: path::PathBuf,
+    /// The second input.
+    second: path::PathBuf,
+    /// The architectures contained in both inputs.
+    architectures: Vec<String>,
+  },
+  /// The universal macOS binary created by `lipo` does not contain the architectures of its inputs.
+  #[error(
+    "universal binary `{}` contains the {} architectures instead of {}",
+    .path.display(),
+    .found.join(", "),
+    .expected.join(", ")
+  )]
+  UniversalBinaryInvalid {
+    /// The universal binary path.
+    path: path::PathBuf,
+    /// The architectures of the inputs.
+    expected: Vec<String>,
+    /// The architectures reported by `lipo -info`.
+    found: Vec<String>,
+  },
+  /// The macOS entitlements file cannot be read as a property list.
+  #[error(
+    "invalid entitlements file `{}`: {reason}, make sure `bundle > macOS > entitlements` points to a property list file",
+    .path.display()
+  )]
+  InvalidEntitlements {
+    /// The entitlements path.
+    path: path::PathBuf,
+    /// Why the file is invalid.
+    reason: String,
+  },
+  /// A script referenced by the installer package configuration does not exist.
+  #[error(
+    "installer package script `{}` does not exist, make sure `bundle > macOS > pkg > {config_key}` points to an existing file",
+    .path.display()
+  )]
+  PkgScriptNotFound {
+    /// The configuration key referencing the script.
+    config_key: &'static str,
+    /// The missing path.
+    path: path::PathBuf,
+  },
+  /// The app bundle wrapped by the installer package does not exist.
+  #[error("app bundle `{}` not found, it must be bundled before the installer package", .0.display())]
+  PkgAppBundleNotFound(path::PathBuf),
+  /// The installer signing identity is not in the keychain.
+  #[error(
+    "installer signing identity `{0}` not found in the keychain, make sure `bundle > macOS > pkg > signingIdentity` names an installed `Developer ID Installer` or `3rd Party Mac Developer Installer` certificate"
+  )]
+  PkgSigningIdentityNotFound(String),
+  /// External command exited with a failure status.
+  #[error(
+    "failed to run `{}`: {}{}{}",
+    command_line(.program, .args),
+    exit_status(*.exit_code, *.signal),
+    output_section("stdout", .stdout),
+    output_section("stderr", .stderr)
+  )]
+  CommandFailed {
+    /// The program that was executed.
+    program: String,
+    /// The arguments passed to the program.
+    args: Vec<String>,
+    /// The exit code, if the process exited normally.
+    exit_code: Option<i32>,
+    /// The signal that terminated the process, if any (Unix only).
+    signal: Option<i32>,
+    /// The last bytes of the process stdout, lossily converted to UTF-8.
+    stdout: String,
+    /// The last bytes of the process stderr, lossily converted to UTF-8.
+    stderr: String,
+  },
+  /// External command kept failing after being retried with [`crate::bundle::CommandExt::output_ok_retry`].
+  #[error("gave up after {attempts} attempts, the last one failed with: {source}")]
+  CommandRetriesFailed {
+    /// The number of times the command was run.
+    attempts: u32,
+    /// The error of the last attempt.
+    source: Box<Error>,
+  },
+  /// External command did not finish in time.
+  #[error(
+    "`{}` did not finish within {}s and was killed{}{}",
+    command_line(.program, .args),
+    .timeout.as_secs_f32(),
+    output_section("stdout", .stdout),
+    output_section("stderr", .stderr)
+  )]
+  CommandTimeout {
+    /// The program that was executed.
+    program: String,
+    /// The arguments passed to the program.
+    args: Vec<String>,
+    /// The timeout that elapsed.
+    timeout: Duration,
+    /// The last bytes the process wrote to stdout before being killed, lossily converted to UTF-8.
+    stdout: String,
+    /// The last bytes the process wrote to stderr before being killed, lossily converted to UTF-8.
+    stderr: String,
+  },
+  /// Apple did not accept the notarization submission.
+  #[error(
+    "notarization {id} finished with status {status}{}",
+    output_section("log", .log)
+  )]
+  NotarizationFailed {
+    /// The submission id.
+    id: String,
+    /// The submission status, e.g. `Invalid`.
+    status: String,
+    /// The notarization log, listing the issues found in each binary.
+    log: String,
+  },
+  /// The notarization did not finish in time.
+  #[error(
+    "notarization {id} did not finish within {}s, check its status with `xcrun notarytool info {id}`",
+    .timeout.as_secs()
+  )]
+  NotarizationTimeout {
+    /// The submission id.
+    id: String,
+    /// The timeout that elapsed.
+    timeout: Duration,
+  },
   /// No bundled project found for the updater.
   #[error("Unable to find a bundled project for the updater")]
   UnableToFindProject,
@@ -103,6 +389,32 @@ pub enum Error {
   /// Failed to sign application.
   #[error("failed to sign app: {0}")]
   Sign(String),
+  /// The timestamp server could not be reached or returned an invalid response while signing a Windows file.
+  #[error(
+    "failed to timestamp the signature of `{}` with {url}, check `bundle > windows > timestampUrl` and `bundle > windows > tsp` or try again later: {source}",
+    .path.display()
+  )]
+  TimestampServer {
+    /// The signed file.
+    path: path::PathBuf,
+    /// The timestamp server URL.
+    url: String,
+    /// The signing error.
+    source: Box<Error>,
+  },
+  /// The signing certificate or its private key could not be used to sign a Windows file.
+  #[error(
+    "failed to sign `{}` with the certificate {thumbprint}, make sure it is installed in the certificate store with its private key: {source}",
+    .path.display()
+  )]
+  SigningCertificate {
+    /// The file to sign.
+    path: path::PathBuf,
+    /// The SHA1 hash of the signing certificate.
+    thumbprint: String,
+    /// The signing error.
+    source: Box<Error>,
+  },
   /// time error.
   #[cfg(target_os = "macos")]
   #[error("`{0}`")]
@@ -119,3 +431,26 @@ pub enum Error {
 
 /// Convenient type alias of Result type.
 pub type Result<T> = std::result::Result<T, Error>;
+
+fn command_line(program: &str, args: &[String]) -> String {
+  std::iter::once(program)
+    .chain(args.iter().map(String::as_str))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn exit_status(exit_code: Option<i32>, signal: Option<i32>) -> String {
+  match (exit_code, signal) {
+    (Some(code), _) => format!("exited with code {code}"),
+    (None, Some(signal)) => format!("terminated by signal {signal}"),
+    (None, None) => "terminated without an exit code".into(),
+  }
+}
+
+fn output_section(name: &str, output: &str) -> String {
+  if output.is_empty() {
+    String::new()
+  } else {
+    format!("\n\n{name}:\n{output}")
+  }
+}
@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+  collections::HashSet,
+  path::{Component, Path, PathBuf},
+};
 
 use clap::Parser;
-use tauri_utils::acl::capability::{Capability, PermissionEntry};
+use tauri_utils::acl::{
+  capability::{Capability, PermissionEntry},
+  CAPABILITIES_SCHEMA_FILE_NAME,
+};
 
 use crate::{
   acl::FileFormat,
@@ -103,11 +109,11 @@ pub fn command(options: Options) -> Result<()> {
     platforms: None,
   };
 
+  let tauri_dir = tauri_dir();
   let path = match options.out {
     Some(o) => o.canonicalize()?,
     None => {
-      let dir = tauri_dir();
-      let capabilities_dir = dir.join("capabilities");
+      let capabilities_dir = tauri_dir.join("capabilities");
       capabilities_dir.join(format!(
         "{}.{}",
         capability.identifier,
@@ -133,9 +139,78 @@ pub fn command(options: Options) -> Result<()> {
     std::fs::create_dir_all(parent)?;
   }
 
-  std::fs::write(&path, options.format.serialize(&capability)?)?;
+  let contents = match options.format {
+    FileFormat::Json => {
+      // reference the schema generated by tauri-build for editor autocompletion
+      let mut file = serde_json::Map::new();
+      file.insert("$schema".into(), schema_reference(&tauri_dir, &path).into());
+      if let serde_json::Value::Object(capability) = serde_json::to_value(&capability)? {
+        file.extend(capability);
+      }
+      options.format.serialize(&file)?
+    }
+    FileFormat::Toml => options.format.serialize(&capability)?,
+  };
+
+  std::fs::write(&path, contents)?;
 
   log::info!(action = "Created"; "capability at {}", dunce::simplified(&path).display());
 
   Ok(())
 }
+
+/// Path of the generated capabilities schema, relative to the given capability file when possible.
+fn schema_reference(tauri_dir: &Path, capability_path: &Path) -> String {
+  let schema_path = Path::new("gen")
+    .join("schemas")
+    .join(CAPABILITIES_SCHEMA_FILE_NAME);
+  match capability_path
+    .parent()
+    .and_then(|parent| parent.strip_prefix(tauri_dir).ok())
+  {
+    Some(relative_dir) => {
+      let mut reference = PathBuf::new();
+      for component in relative_dir.components() {
+        if let Component::Normal(_) = component {
+          reference.push("..");
+        }
+      }
+      reference.join(schema_path)
+    }
+    None => tauri_dir.join(schema_path),
+  }
+  .to_string_lossy()
+  .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use super::schema_reference;
+
+  #[test]
+  fn schema_reference_is_relative_to_the_capability() {
+    let tauri_dir = Path::new("/app/src-tauri");
+    for (capability, schema) in [
+      (
+        "/app/src-tauri/capabilities/main.json",
+        "../gen/schemas/capabilities.schema.json",
+      ),
+      (
+        "/app/src-tauri/capabilities/windows/main.json",
+        "../../gen/schemas/capabilities.schema.json",
+      ),
+      (
+        "/app/src-tauri/main.json",
+        "gen/schemas/capabilities.schema.json",
+      ),
+      (
+        "/other/main.json",
+        "/app/src-tauri/gen/schemas/capabilities.schema.json",
+      ),
+    ] {
+      assert_eq!(schema_reference(tauri_dir, Path::new(capability)), schema);
+    }
+  }
+}
@@ -0,0 +1,593 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  ffi::OsStr,
+  fs::read_to_string,
+  path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use serde::Serialize;
+use tauri_utils::{
+  acl::{
+    capability::{Capability, CapabilityFile},
+    manifest::Manifest,
+    resolved::Resolved,
+    APP_ACL_KEY,
+  },
+  config::{CapabilityEntry, Config},
+  platform::Target,
+};
+
+use crate::{
+  helpers::{app_paths::tauri_dir, config::get as get_config},
+  Result,
+};
+
+/// Where the inlined capabilities of the configuration are defined.
+const CONFIG_SOURCE: &str = "app > security > capabilities";
+
+#[derive(Debug, Clone, ValueEnum)]
+enum OutputFormat {
+  Text,
+  Json,
+}
+
+#[derive(Debug, Parser)]
+#[clap(about = "Print the commands allowed and denied for each window and webview of your app")]
+pub struct Options {
+  /// Only print the permissions of the window or webview with this label.
+  #[clap(short, long)]
+  window: Option<String>,
+  /// Output format.
+  #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+  format: OutputFormat,
+}
+
+/// A capability and the file defining it.
+#[derive(Debug)]
+struct SourcedCapability {
+  capability: Capability,
+  source: String,
+}
+
+/// The resolved permissions of a window or webview label (or label pattern).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LabelReport {
+  label: String,
+  /// Identifiers of the capabilities applied to the label.
+  capabilities: Vec<String>,
+  /// Allowed commands grouped by plugin.
+  allow: BTreeMap<String, Vec<CommandEntry>>,
+  /// Denied commands grouped by plugin.
+  deny: BTreeMap<String, Vec<CommandEntry>>,
+}
+
+/// A command and the permission allowing or denying it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandEntry {
+  command: String,
+  permission: String,
+  capability: String,
+  source: String,
+}
+
+/// A capability or permission that does not have any effect.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Issue {
+  capability: String,
+  source: String,
+  message: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+  target: Target,
+  labels: Vec<LabelReport>,
+  issues: Vec<Issue>,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  let tauri_dir = tauri_dir();
+  let acl_manifests_path = tauri_dir
+    .join("gen")
+    .join("schemas")
+    .join("acl-manifests.json");
+  if !acl_manifests_path.exists() {
+    anyhow::bail!("permission file not found, please build your application once first");
+  }
+  let acl: BTreeMap<String, Manifest> =
+    serde_json::from_str(&read_to_string(&acl_manifests_path)?)?;
+
+  let target = Target::current();
+  let config = get_config(target, None)?;
+  let config_guard = config.lock().unwrap();
+  let config = config_guard.as_ref().unwrap();
+
+  let mut capability_files = Vec::new();
+  for path in list_capability_files(&tauri_dir.join("capabilities"))? {
+    let source = path
+      .strip_prefix(&tauri_dir)
+      .unwrap_or(&path)
+      .display()
+      .to_string();
+    let capabilities = match CapabilityFile::load(&path)? {
+      CapabilityFile::Capability(capability) => vec![capability],
+      CapabilityFile::List(capabilities) | CapabilityFile::NamedList { capabilities } => {
+        capabilities
+      }
+    };
+    capability_files.extend(
+      capabilities
+        .into_iter()
+        .map(|capability| SourcedCapability {
+          capability,
+          source: source.clone(),
+        }),
+    );
+  }
+
+  let report = inspect(
+    &acl,
+    capability_files,
+    config,
+    target,
+    options.window.as_deref(),
+  );
+
+  match options.format {
+    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    OutputFormat::Text => print_report(&report),
+  }
+
+  Ok(())
+}
+
+/// Lists the capability files in the given directory, like the app build script does.
+fn list_capability_files(capabilities_dir: &Path) -> Result<Vec<PathBuf>> {
+  let pattern = format!(
+    "{}/**/*",
+    glob::Pattern::escape(&capabilities_dir.to_string_lossy())
+  );
+  Ok(
+    glob::glob(&pattern)?
+      .flatten()
+      .filter(|p| {
+        p.extension()
+          .and_then(|e| e.to_str())
+          .map(|e| e == "json" || e == "toml")
+          .unwrap_or_default()
+      })
+      // the generated schemas are not capabilities
+      .filter(|p| p.parent().and_then(|p| p.file_name()) != Some(OsStr::new("schemas")))
+      .collect(),
+  )
+}
+
+/// Selects the capabilities used by the app, like the context codegen does.
+///
+/// The capability files are only used if the configuration does not list the app capabilities.
+fn select_capabilities(
+  files: Vec<SourcedCapability>,
+  config: &Config,
+  issues: &mut Vec<Issue>,
+) -> Vec<SourcedCapability> {
+  if config.app.security.capabilities.is_empty() {
+    return files;
+  }
+
+  let mut files = files
+    .into_iter()
+    .map(|c| (c.capability.identifier.clone(), c))
+    .collect::<BTreeMap<_, _>>();
+  let mut capabilities = Vec::new();
+  for entry in &config.app.security.capabilities {
+    match entry {
+      CapabilityEntry::Inlined(capability) => capabilities.push(SourcedCapability {
+        capability: capability.clone(),
+        source: CONFIG_SOURCE.into(),
+      }),
+      CapabilityEntry::Reference(id) => match files.remove(id) {
+        Some(capability) => capabilities.push(capability),
+        None => issues.push(Issue {
+          capability: id.clone(),
+          source: CONFIG_SOURCE.into(),
+          message: "referenced capability not found in the capabilities directory".into(),
+        }),
+      },
+    }
+  }
+
+  for (id, ignored) in files {
+    issues.push(Issue {
+      capability: id,
+      source: ignored.source,
+      message: format!("ignored because `{CONFIG_SOURCE}` does not reference it"),
+    });
+  }
+
+  capabilities
+}
+
+fn inspect(
+  acl: &BTreeMap<String, Manifest>,
+  files: Vec<SourcedCapability>,
+  config: &Config,
+  target: Target,
+  window: Option<&str>,
+) -> Report {
+  let mut issues = Vec::new();
+  let capabilities = select_capabilities(files, config, &mut issues);
+
+  // the commands allowed and denied by each capability
+  let mut resolved_capabilities = Vec::new();
+  for SourcedCapability { capability, source } in capabilities {
+    if !capability
+      .platforms
+      .as_ref()
+      .map(|platforms| platforms.contains(&target))
+      .unwrap_or(true)
+    {
+      issues.push(Issue {
+        capability: capability.identifier.clone(),
+        source,
+        message: format!("not enabled on {target}"),
+      });
+      continue;
+    }
+
+    let mut allow = Vec::new();
+    let mut deny = Vec::new();
+    for permission in &capability.permissions {
+      let permission_id = permission.identifier();
+      let key = permission_id.get_prefix().unwrap_or(APP_ACL_KEY);
+      let mut issue = |message: String| {
+        issues.push(Issue {
+          capability: capability.identifier.clone(),
+          source: source.clone(),
+          message: format!("permission `{}` {message}", permission_id.get()),
+        })
+      };
+
+      if !acl.contains_key(key) && permission_id.get() != "core:default" {
+        if key == APP_ACL_KEY {
+          issue("not found, the app does not define any permission".into());
+        } else {
+          issue(format!(
+            "references the `{key}` plugin which is not a dependency of the app"
+          ));
+        }
+        continue;
+      }
+
+      // resolve each permission on its own to know which one allows or denies each command
+      let single = Capability {
+        permissions: vec![permission.clone()],
+        ..capability.clone()
+      };
+      let resolved = match Resolved::resolve(
+        acl,
+        BTreeMap::from([(single.identifier.clone(), single)]),
+        target,
+      ) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+          issue(format!("cannot be resolved: {e}"));
+          continue;
+        }
+      };
+
+      if resolved.allowed_commands.is_empty()
+        && resolved.denied_commands.is_empty()
+        && resolved.global_scope.is_empty()
+      {
+        issue(format!("does not allow or deny any command on {target}"));
+      }
+
+      let entry = |command: &String| CommandEntry {
+        command: command.clone(),
+        permission: permission_id.get().to_string(),
+        capability: capability.identifier.clone(),
+        source: source.clone(),
+      };
+      allow.extend(resolved.allowed_commands.keys().map(&entry));
+      deny.extend(resolved.denied_commands.keys().map(&entry));
+    }
+
+    resolved_capabilities.push((capability, allow, deny));
+  }
+
+  let labels = match window {
+    Some(label) => BTreeSet::from([label.to_string()]),
+    None => resolved_capabilities
+      .iter()
+      .flat_map(|(capability, _, _)| capability.windows.iter().chain(&capability.webviews))
+      .cloned()
+      .collect(),
+  };
+
+  let labels = labels
+    .into_iter()
+    .map(|label| {
+      let mut report = LabelReport {
+        label,
+        capabilities: Vec::new(),
+        allow: BTreeMap::new(),
+        deny: BTreeMap::new(),
+      };
+      for (capability, allow, deny) in &resolved_capabilities {
+        if !capability
+          .windows
+          .iter()
+          .chain(&capability.webviews)
+          .any(|pattern| {
+            glob::Pattern::new(pattern)
+              .map(|pattern| pattern.matches(&report.label))
+              .unwrap_or_default()
+          })
+        {
+          continue;
+        }
+
+        report.capabilities.push(capability.identifier.clone());
+        for (entries, commands) in [(allow, &mut report.allow), (deny, &mut report.deny)] {
+          for entry in entries {
+            let (plugin, command) = split_command(&entry.command);
+            commands
+              .entry(plugin.to_string())
+              .or_default()
+              .push(CommandEntry {
+                command: command.to_string(),
+                ..entry.clone()
+              });
+          }
+        }
+      }
+      report
+    })
+    .collect();
+
+  Report {
+    target,
+    labels,
+    issues,
+  }
+}
+
+/// Splits a resolved command name into the plugin name and the command, e.g. `plugin:fs|read` to `fs` and `read`.
+fn split_command(command: &str) -> (&str, &str) {
+  command
+    .strip_prefix("plugin:")
+    .and_then(|command| command.split_once('|'))
+    .unwrap_or((APP_ACL_KEY, command))
+}
+
+fn print_report(report: &Report) {
+  for label in &report.labels {
+    println!("{}", label.label.cyan().bold());
+    if label.capabilities.is_empty() {
+      println!("  no capability applies to this label\n");
+      continue;
+    }
+    println!(
+      "  {}: {}",
+      "Capabilities".bold(),
+      label.capabilities.join(", ")
+    );
+    for (title, commands) in [("Allow", &label.allow), ("Deny", &label.deny)] {
+      if commands.is_empty() {
+        continue;
+      }
+      println!("  {}:", title.bold());
+      for (plugin, entries) in commands {
+        let plugin = if plugin == APP_ACL_KEY { "app" } else { plugin };
+        println!("    {}", plugin.magenta());
+        for entry in entries {
+          let command = if title == "Allow" {
+            entry.command.green()
+          } else {
+            entry.command.red()
+          };
+          println!(
+            "      {command} ({} in {}, {})",
+            entry.permission, entry.capability, entry.source
+          );
+        }
+      }
+    }
+    println!();
+  }
+
+  if !report.issues.is_empty() {
+    println!("{}", "Issues".yellow().bold());
+    for issue in &report.issues {
+      println!(
+        "  {} ({}): {}",
+        issue.capability, issue.source, issue.message
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use tauri_utils::{
+    acl::{
+      capability::Capability,
+      manifest::{Manifest, PermissionFile},
+    },
+    config::Config,
+    platform::Target,
+  };
+
+  use super::{inspect, SourcedCapability};
+
+  fn acl() -> BTreeMap<String, Manifest> {
+    let fs: PermissionFile = toml::from_str(
+      r#"
+[default]
+permissions = ["allow-read"]
+
+[[permission]]
+identifier = "allow-read"
+commands.allow = ["read"]
+
+[[permission]]
+identifier = "deny-write"
+commands.deny = ["write"]
+"#,
+    )
+    .unwrap();
+    BTreeMap::from([("fs".to_string(), Manifest::new(vec![fs], None))])
+  }
+
+  fn capability(json: &str, source: &str) -> SourcedCapability {
+    SourcedCapability {
+      capability: serde_json::from_str::<Capability>(json).unwrap(),
+      source: source.into(),
+    }
+  }
+
+  fn config(json: serde_json::Value) -> Config {
+    let mut config = serde_json::json!({ "identifier": "com.tauri.test" });
+    json_patch::merge(&mut config, &json);
+    serde_json::from_value(config).unwrap()
+  }
+
+  #[test]
+  fn resolves_commands_per_label() {
+    let report = inspect(
+      &acl(),
+      vec![
+        capability(
+          r#"{ "identifier": "main", "windows": ["main"], "permissions": ["fs:default", "fs:deny-write"] }"#,
+          "capabilities/main.json",
+        ),
+        capability(
+          r#"{ "identifier": "all", "windows": ["*"], "permissions": ["fs:allow-read"] }"#,
+          "capabilities/all.json",
+        ),
+      ],
+      &config(serde_json::json!({})),
+      Target::Linux,
+      None,
+    );
+
+    assert!(report.issues.is_empty());
+    let labels = report
+      .labels
+      .iter()
+      .map(|l| l.label.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(labels, ["*", "main"]);
+
+    let main = &report.labels[1];
+    assert_eq!(main.capabilities, ["main", "all"]);
+    let allowed = &main.allow["fs"];
+    assert_eq!(allowed.len(), 2);
+    assert!(allowed.iter().all(|entry| entry.command == "read"));
+    assert_eq!(allowed[0].permission, "fs:default");
+    assert_eq!(allowed[0].source, "capabilities/main.json");
+    assert_eq!(allowed[1].capability, "all");
+    assert_eq!(main.deny["fs"][0].command, "write");
+    assert_eq!(main.deny["fs"][0].permission, "fs:deny-write");
+
+    let all = &report.labels[0];
+    assert_eq!(all.capabilities, ["all"]);
+    assert!(all.deny.is_empty());
+  }
+
+  #[test]
+  fn filters_window() {
+    let report = inspect(
+      &acl(),
+      vec![capability(
+        r#"{ "identifier": "settings", "windows": ["settings-*"], "permissions": ["fs:allow-read"] }"#,
+        "capabilities/settings.json",
+      )],
+      &config(serde_json::json!({})),
+      Target::Linux,
+      Some("settings-general"),
+    );
+    assert_eq!(report.labels.len(), 1);
+    assert_eq!(report.labels[0].capabilities, ["settings"]);
+
+    let report = inspect(
+      &acl(),
+      vec![capability(
+        r#"{ "identifier": "settings", "windows": ["settings-*"], "permissions": ["fs:allow-read"] }"#,
+        "capabilities/settings.json",
+      )],
+      &config(serde_json::json!({})),
+      Target::Linux,
+      Some("main"),
+    );
+    assert!(report.labels[0].capabilities.is_empty());
+  }
+
+  #[test]
+  fn reports_ineffective_capabilities() {
+    let report = inspect(
+      &acl(),
+      vec![
+        capability(
+          r#"{ "identifier": "main", "windows": ["main"], "permissions": ["fs:allow-delete", "http:default", "fs:allow-read"] }"#,
+          "capabilities/main.json",
+        ),
+        capability(
+          r#"{ "identifier": "mobile", "windows": ["main"], "permissions": ["fs:allow-read"], "platforms": ["android", "iOS"] }"#,
+          "capabilities/mobile.json",
+        ),
+        capability(
+          r#"{ "identifier": "unused", "windows": ["main"], "permissions": ["fs:allow-read"] }"#,
+          "capabilities/unused.json",
+        ),
+      ],
+      &config(serde_json::json!({
+        "app": { "security": { "capabilities": ["main", "mobile", "missing"] } }
+      })),
+      Target::Linux,
+      None,
+    );
+
+    let issues = report
+      .issues
+      .iter()
+      .map(|issue| (issue.capability.as_str(), issue.message.as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      issues,
+      [
+        (
+          "missing",
+          "referenced capability not found in the capabilities directory"
+        ),
+        (
+          "unused",
+          "ignored because `app > security > capabilities` does not reference it"
+        ),
+        (
+          "main",
+          "permission `fs:allow-delete` cannot be resolved: unknown permission allow-delete for fs"
+        ),
+        (
+          "main",
+          "permission `http:default` references the `http` plugin which is not a dependency of the app"
+        ),
+        ("mobile", "not enabled on linux"),
+      ]
+    );
+
+    assert_eq!(report.labels[0].allow["fs"].len(), 1);
+  }
+}
@@ -7,6 +7,7 @@ use clap::{Parser, Subcommand};
 use crate::Result;
 
 pub mod add;
+mod inspect;
 mod ls;
 mod new;
 mod rm;
@@ -27,6 +28,7 @@ enum Commands {
   Rm(rm::Options),
   #[clap(alias = "list")]
   Ls(ls::Options),
+  Inspect(inspect::Options),
 }
 
 pub fn command(cli: Cli) -> Result<()> {
@@ -35,5 +37,6 @@ pub fn command(cli: Cli) -> Result<()> {
     Commands::Add(options) => add::command(options),
     Commands::Rm(options) => rm::command(options),
     Commands::Ls(options) => ls::command(options),
+    Commands::Inspect(options) => inspect::command(options),
   }
 }
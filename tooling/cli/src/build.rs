@@ -8,13 +8,16 @@ use crate::{
     self,
     app_paths::tauri_dir,
     config::{get as get_config, ConfigHandle, FrontendDist},
+    progress::{self, ProgressFormat, Step},
   },
-  interface::{AppInterface, AppSettings, Interface},
+  interface::{rust::apply_profile, AppInterface, AppSettings, Interface},
   ConfigValue, Result,
 };
 use anyhow::Context;
 use clap::{ArgAction, Parser};
-use std::env::set_current_dir;
+use serde::Serialize;
+use std::{env::set_current_dir, path::PathBuf, time::Instant};
+use tauri_bundler::Bundle;
 use tauri_utils::platform::Target;
 
 #[derive(Debug, Clone, Parser)]
@@ -29,13 +32,25 @@ pub struct Options {
   /// Builds with the debug flag
   #[clap(short, long)]
   pub debug: bool,
+  /// The cargo profile to build with, defaults to `build > profile` or `release`.
+  ///
+  /// Custom profiles must be defined in the workspace Cargo.toml.
+  #[clap(long, conflicts_with("debug"))]
+  pub profile: Option<String>,
   /// Target triple to build against.
   ///
   /// It must be one of the values outputted by `$rustc --print target-list` or `universal-apple-darwin` for an universal macOS application.
+  /// It can be repeated or comma separated to build and bundle several targets one after the other.
   ///
   /// Note that compiling an universal macOS application requires both `aarch64-apple-darwin` and `x86_64-apple-darwin` targets to be installed.
-  #[clap(short, long)]
+  #[clap(short, long = "target", action = ArgAction::Append, value_delimiter = ',')]
+  pub targets: Vec<String>,
+  /// The target of the current build, one of `targets`.
+  #[clap(skip)]
   pub target: Option<String>,
+  /// Stop at the first target that fails instead of building the remaining targets.
+  #[clap(long)]
+  pub fail_fast: bool,
   /// Space or comma separated list of features to activate
   #[clap(short, long, action = ArgAction::Append, num_args(0..))]
   pub features: Option<Vec<String>>,
@@ -47,6 +62,27 @@ pub struct Options {
   /// Skip the bundling step even if `bundle > active` is `true` in tauri config.
   #[clap(long)]
   pub no_bundle: bool,
+  /// Maximum number of bundle formats to package concurrently. Defaults to the number of available CPUs.
+  ///
+  /// Use `1` to package them one after the other.
+  #[clap(long)]
+  pub bundle_jobs: Option<usize>,
+  /// Fail instead of downloading the tools required to bundle the app when they are not installed.
+  ///
+  /// Currently applies to the AppImage tools, which can also be provided with the `TAURI_LINUXDEPLOY_PATH` and related environment variables.
+  #[clap(long, env = "TAURI_BUNDLER_OFFLINE")]
+  pub offline: bool,
+  /// Print the JSON manifest of the produced bundle artifacts to stdout.
+  ///
+  /// The manifest is also written to `bundle-manifest.json` in the bundle output directory.
+  /// Ignored with `--format json`, which reports the artifacts in its summary event.
+  #[clap(long)]
+  pub print_artifacts: bool,
+  /// The format of the progress.
+  ///
+  /// With `json`, newline-delimited JSON events are printed to stdout and the logs are written to stderr.
+  #[clap(long, value_enum, default_value_t)]
+  pub format: ProgressFormat,
   /// JSON string or path to JSON file to merge with tauri.conf.json
   #[clap(short, long)]
   pub config: Option<ConfigValue>,
@@ -58,6 +94,160 @@ pub struct Options {
 }
 
 pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
+  progress::enable(options.format);
+
+  let mut targets = std::mem::take(&mut options.targets);
+  targets.dedup();
+  if targets.len() <= 1 {
+    options.target = targets.pop();
+    let bundles = build_target(options, verbosity)?;
+    progress::summary(&bundles);
+    return Ok(());
+  }
+
+  // each target prints its artifacts in the combined summary instead
+  let print_artifacts = std::mem::take(&mut options.print_artifacts);
+  let outcomes = build_targets(&targets, options.fail_fast, |target| {
+    build_target(
+      Options {
+        target: Some(target.into()),
+        ..options.clone()
+      },
+      verbosity,
+    )
+  });
+
+  let summary = TargetsSummary::new(&outcomes);
+  summary.log();
+  if print_artifacts && !progress::is_json() {
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+  }
+
+  let failed = outcomes
+    .iter()
+    .filter(|outcome| outcome.result.is_err())
+    .map(|outcome| outcome.target.as_str())
+    .collect::<Vec<_>>();
+  if failed.is_empty() {
+    let bundles = outcomes
+      .into_iter()
+      .flat_map(|outcome| outcome.result.unwrap_or_default())
+      .collect::<Vec<_>>();
+    progress::summary(&bundles);
+    Ok(())
+  } else {
+    let skipped = targets.len() - outcomes.len();
+    Err(anyhow::anyhow!(
+      "failed to build {} of {} targets: {}{}",
+      failed.len(),
+      targets.len(),
+      failed.join(", "),
+      if skipped > 0 {
+        format!(" ({skipped} skipped with --fail-fast)")
+      } else {
+        String::new()
+      }
+    ))
+  }
+}
+
+/// The result of building and bundling one of the `--target` values.
+struct TargetOutcome {
+  target: String,
+  result: Result<Vec<Bundle>>,
+}
+
+/// Calls `build` for each target, stopping at the first failure if `fail_fast` is set.
+fn build_targets<F>(targets: &[String], fail_fast: bool, mut build: F) -> Vec<TargetOutcome>
+where
+  F: FnMut(&str) -> Result<Vec<Bundle>>,
+{
+  let mut outcomes = Vec::new();
+  for target in targets {
+    log::info!(action = "Building"; "target {target}");
+    progress::emit(progress::Event::TargetStarted { target });
+    let start = Instant::now();
+    let result = build(target);
+    if let Err(e) = &result {
+      log::error!("failed to build target {target}: {e:#}");
+    }
+    progress::emit(progress::Event::TargetFinished {
+      target,
+      duration_ms: progress::millis(start.elapsed()),
+      error: result.as_ref().err().map(|e| format!("{e:#}")),
+    });
+
+    let failed = result.is_err();
+    outcomes.push(TargetOutcome {
+      target: target.clone(),
+      result,
+    });
+    if failed && fail_fast {
+      break;
+    }
+  }
+  outcomes
+}
+
+/// The artifacts of all the targets, printed with `--print-artifacts`.
+#[derive(Debug, Serialize)]
+struct TargetsSummary<'a> {
+  targets: Vec<TargetSummary<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct TargetSummary<'a> {
+  target: &'a str,
+  /// The error that stopped the target, if any.
+  error: Option<String>,
+  bundles: Vec<TargetBundle<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct TargetBundle<'a> {
+  format: &'a str,
+  paths: &'a [PathBuf],
+}
+
+impl<'a> TargetsSummary<'a> {
+  fn new(outcomes: &'a [TargetOutcome]) -> Self {
+    Self {
+      targets: outcomes
+        .iter()
+        .map(|outcome| TargetSummary {
+          target: &outcome.target,
+          error: outcome.result.as_ref().err().map(|e| format!("{e:#}")),
+          bundles: outcome
+            .result
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|bundle| TargetBundle {
+              format: bundle.package_type.short_name(),
+              paths: &bundle.bundle_paths,
+            })
+            .collect(),
+        })
+        .collect(),
+    }
+  }
+
+  fn log(&self) {
+    for target in &self.targets {
+      if target.error.is_some() {
+        log::error!(action = "Failed"; "{}", target.target);
+        continue;
+      }
+      log::info!(action = "Finished"; "{}", target.target);
+      for path in target.bundles.iter().flat_map(|bundle| bundle.paths) {
+        log::info!("        {}", tauri_utils::display_path(path));
+      }
+    }
+  }
+}
+
+/// Builds and bundles the app for `options.target`, returning the bundles.
+fn build_target(mut options: Options, verbosity: u8) -> Result<Vec<Bundle>> {
   let ci = options.ci;
 
   let target = options
@@ -68,6 +258,23 @@ pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
 
   let config = get_config(target, options.config.as_ref().map(|c| &c.0))?;
 
+  let config_profile = config
+    .lock()
+    .unwrap()
+    .as_ref()
+    .unwrap()
+    .build
+    .profile
+    .clone();
+  if let Some(debug) = apply_profile(
+    &mut options.args,
+    options.profile.take(),
+    // `--debug` and `--release` select the default profiles instead
+    config_profile.as_deref().filter(|_| !options.debug),
+  )? {
+    options.debug = debug;
+  }
+
   let mut interface = AppInterface::new(
     config.lock().unwrap().as_ref().unwrap(),
     options.target.clone(),
@@ -84,13 +291,13 @@ pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
   let bin_path = app_settings.app_binary_path(&interface_options)?;
   let out_dir = bin_path.parent().unwrap();
 
-  interface.build(interface_options)?;
+  progress::step(Step::CargoBuild, || interface.build(interface_options))?;
 
   log::info!(action ="Built"; "application at: {}", tauri_utils::display_path(&bin_path));
 
   let app_settings = interface.app_settings();
 
-  if !options.no_bundle && (config_.bundle.active || options.bundles.is_some()) {
+  let bundles = if !options.no_bundle && (config_.bundle.active || options.bundles.is_some()) {
     crate::bundle::bundle(
       &options.into(),
       verbosity,
@@ -99,10 +306,12 @@ pub fn command(mut options: Options, verbosity: u8) -> Result<()> {
       &app_settings,
       config_,
       out_dir,
-    )?;
-  }
+    )?
+  } else {
+    Vec::new()
+  };
 
-  Ok(())
+  Ok(bundles)
 }
 
 pub fn setup(
@@ -143,7 +352,9 @@ pub fn setup(
   }
 
   if let Some(before_build) = config_.build.before_build_command.clone() {
-    helpers::run_hook("beforeBuildCommand", before_build, interface, options.debug)?;
+    progress::step(Step::BeforeBuildCommand, || {
+      helpers::run_hook("beforeBuildCommand", before_build, interface, options.debug)
+    })?;
   }
 
   if let Some(FrontendDist::Directory(web_asset_path)) = &config_.build.frontend_dist {
@@ -192,3 +403,117 @@ pub fn setup(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tauri_bundler::PackageType;
+
+  fn bundle(package_type: PackageType, path: &str) -> Bundle {
+    Bundle {
+      package_type,
+      bundle_paths: vec![path.into()],
+      size_bytes: 0,
+      staged_size_bytes: None,
+      architecture: String::new(),
+    }
+  }
+
+  #[test]
+  fn parses_multiple_targets() {
+    let options = Options::try_parse_from([
+      "build",
+      "--target",
+      "x86_64-unknown-linux-gnu",
+      "-t",
+      "aarch64-unknown-linux-gnu,armv7-unknown-linux-gnueabihf",
+      "--fail-fast",
+    ])
+    .unwrap();
+    assert_eq!(
+      options.targets,
+      [
+        "x86_64-unknown-linux-gnu",
+        "aarch64-unknown-linux-gnu",
+        "armv7-unknown-linux-gnueabihf"
+      ]
+    );
+    assert_eq!(options.target, None);
+    assert!(options.fail_fast);
+
+    let options = Options::try_parse_from(["build"]).unwrap();
+    assert!(options.targets.is_empty());
+    assert!(!options.fail_fast);
+  }
+
+  #[test]
+  fn merges_target_summaries() {
+    let targets = [
+      "x86_64-unknown-linux-gnu".to_string(),
+      "aarch64-unknown-linux-gnu".into(),
+    ];
+    let outcomes = build_targets(&targets, false, |target| {
+      Ok(vec![
+        bundle(PackageType::Deb, &format!("{target}/app.deb")),
+        bundle(PackageType::AppImage, &format!("{target}/app.AppImage")),
+      ])
+    });
+
+    let summary = serde_json::to_value(TargetsSummary::new(&outcomes)).unwrap();
+    assert_eq!(
+      summary,
+      serde_json::json!({
+        "targets": [
+          {
+            "target": "x86_64-unknown-linux-gnu",
+            "error": null,
+            "bundles": [
+              { "format": "deb", "paths": ["x86_64-unknown-linux-gnu/app.deb"] },
+              { "format": "appimage", "paths": ["x86_64-unknown-linux-gnu/app.AppImage"] }
+            ]
+          },
+          {
+            "target": "aarch64-unknown-linux-gnu",
+            "error": null,
+            "bundles": [
+              { "format": "deb", "paths": ["aarch64-unknown-linux-gnu/app.deb"] },
+              { "format": "appimage", "paths": ["aarch64-unknown-linux-gnu/app.AppImage"] }
+            ]
+          }
+        ]
+      })
+    );
+  }
+
+  #[test]
+  fn failed_targets_do_not_stop_the_build_unless_fail_fast() {
+    let targets = [
+      "x86_64-unknown-linux-gnu".to_string(),
+      "aarch64-unknown-linux-gnu".into(),
+      "armv7-unknown-linux-gnueabihf".into(),
+    ];
+    let build = |target: &str| {
+      if target.starts_with("aarch64") {
+        Err(anyhow::anyhow!("linker not found"))
+      } else {
+        Ok(vec![bundle(PackageType::Deb, &format!("{target}/app.deb"))])
+      }
+    };
+
+    let outcomes = build_targets(&targets, false, build);
+    assert_eq!(outcomes.len(), 3);
+    assert!(outcomes[0].result.is_ok());
+    assert!(outcomes[1].result.is_err());
+    assert!(outcomes[2].result.is_ok());
+    let summary = TargetsSummary::new(&outcomes);
+    assert_eq!(
+      summary.targets[1].error.as_deref(),
+      Some("linker not found")
+    );
+    assert!(summary.targets[1].bundles.is_empty());
+
+    let outcomes = build_targets(&targets, true, build);
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes[1].result.is_err());
+  }
+}
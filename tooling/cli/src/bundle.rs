@@ -19,6 +19,7 @@ use crate::{
     self,
     app_paths::tauri_dir,
     config::{get as get_config, ConfigMetadata},
+    progress::{self, ProgressFormat, Step},
     updater_signature,
   },
   interface::{AppInterface, AppSettings, Interface},
@@ -62,6 +63,32 @@ pub struct Options {
   /// Note that the `updater` bundle is not automatically added so you must specify it if the updater is enabled.
   #[clap(short, long, action = ArgAction::Append, num_args(0..), value_delimiter = ',')]
   pub bundles: Option<Vec<BundleFormat>>,
+  /// Maximum number of bundle formats to package concurrently. Defaults to the number of available CPUs.
+  ///
+  /// Use `1` to package them one after the other.
+  #[clap(long)]
+  pub bundle_jobs: Option<usize>,
+  /// Fail instead of downloading the tools required to bundle the app when they are not installed.
+  ///
+  /// Currently applies to the AppImage tools, which can also be provided with the `TAURI_LINUXDEPLOY_PATH` and related environment variables.
+  #[clap(long, env = "TAURI_BUNDLER_OFFLINE")]
+  pub offline: bool,
+  /// Log the files that would be copied and archived into the bundles instead of writing them.
+  ///
+  /// Useful to check the resources and custom files mappings without running a full bundle.
+  #[clap(long)]
+  pub dry_run: bool,
+  /// Print the JSON manifest of the produced bundle artifacts to stdout.
+  ///
+  /// The manifest is also written to `bundle-manifest.json` in the bundle output directory.
+  /// Ignored with `--format json`, which reports the artifacts in its summary event.
+  #[clap(long)]
+  pub print_artifacts: bool,
+  /// The format of the progress.
+  ///
+  /// With `json`, newline-delimited JSON events are printed to stdout and the logs are written to stderr.
+  #[clap(long, value_enum, default_value_t)]
+  pub format: ProgressFormat,
   /// JSON string or path to JSON file to merge with tauri.conf.json
   #[clap(short, long)]
   pub config: Option<ConfigValue>,
@@ -84,6 +111,11 @@ impl From<crate::build::Options> for Options {
   fn from(value: crate::build::Options) -> Self {
     Self {
       bundles: value.bundles,
+      bundle_jobs: value.bundle_jobs,
+      offline: value.offline,
+      dry_run: false,
+      print_artifacts: value.print_artifacts,
+      format: value.format,
       target: value.target,
       features: value.features,
       debug: value.debug,
@@ -94,6 +126,7 @@ impl From<crate::build::Options> for Options {
 }
 
 pub fn command(options: Options, verbosity: u8) -> crate::Result<()> {
+  progress::enable(options.format);
   let ci = options.ci;
 
   let target = options
@@ -122,7 +155,7 @@ pub fn command(options: Options, verbosity: u8) -> crate::Result<()> {
   let bin_path = app_settings.app_binary_path(&interface_options)?;
   let out_dir = bin_path.parent().unwrap();
 
-  bundle(
+  let bundles = bundle(
     &options,
     verbosity,
     ci,
@@ -130,7 +163,11 @@ pub fn command(options: Options, verbosity: u8) -> crate::Result<()> {
     &app_settings,
     config_,
     out_dir,
-  )
+  )?;
+
+  progress::summary(&bundles);
+
+  Ok(())
 }
 
 pub fn bundle<A: AppSettings>(
@@ -141,7 +178,7 @@ pub fn bundle<A: AppSettings>(
   app_settings: &std::sync::Arc<A>,
   config: &ConfigMetadata,
   out_dir: &Path,
-) -> crate::Result<()> {
+) -> crate::Result<Vec<tauri_bundler::Bundle>> {
   let package_types: Vec<PackageType> = if let Some(bundles) = &options.bundles {
     bundles.iter().map(|bundle| bundle.0).collect::<Vec<_>>()
   } else {
@@ -155,18 +192,20 @@ pub fn bundle<A: AppSettings>(
   };
 
   if package_types.is_empty() {
-    return Ok(());
+    return Ok(Vec::new());
   }
 
   // if we have a package to bundle, let's run the `before_bundle_command`.
   if !package_types.is_empty() {
     if let Some(before_bundle) = config.build.before_bundle_command.clone() {
-      helpers::run_hook(
-        "beforeBundleCommand",
-        before_bundle,
-        interface,
-        options.debug,
-      )?;
+      progress::step(Step::BeforeBundleCommand, || {
+        helpers::run_hook(
+          "beforeBundleCommand",
+          before_bundle,
+          interface,
+          options.debug,
+        )
+      })?;
     }
   }
 
@@ -180,6 +219,12 @@ pub fn bundle<A: AppSettings>(
     _ => log::Level::Trace,
   });
 
+  if let Some(jobs) = options.bundle_jobs {
+    settings.set_jobs(jobs);
+  }
+  settings.set_offline(options.offline);
+  settings.set_dry_run(options.dry_run);
+
   // set env vars used by the bundler
   #[cfg(target_os = "linux")]
   {
@@ -198,26 +243,53 @@ pub fn bundle<A: AppSettings>(
     }
   }
 
-  let bundles = tauri_bundler::bundle_project(&settings)
-    .map_err(|e| match e {
-      tauri_bundler::Error::BundlerError(e) => e,
-      e => anyhow::anyhow!("{e:#}"),
-    })
-    .with_context(|| "failed to bundle project")?;
+  let bundles = progress::step(Step::Bundle, || {
+    tauri_bundler::bundle_project_with_progress(&settings, &progress::bundle_progress)
+      .map_err(|e| match e {
+        tauri_bundler::Error::BundlerError(e) => e,
+        e => anyhow::anyhow!("{e:#}"),
+      })
+      .with_context(|| "failed to bundle project")
+  })?;
+
+  // the updater artifacts and the bundle manifest were not written
+  if options.dry_run {
+    return Ok(bundles);
+  }
 
-  sign_updaters(settings, bundles, ci)?;
+  let signed_paths = if settings.updater().is_some() {
+    progress::step(Step::SignUpdaters, || {
+      sign_updaters(&settings, &bundles, ci)
+    })?
+  } else {
+    Vec::new()
+  };
 
-  Ok(())
+  // the manifest written by the bundler does not include the updater signatures yet
+  if !signed_paths.is_empty() {
+    let manifest = tauri_bundler::bundle_manifest(&settings, &bundles)?;
+    tauri_bundler::write_bundle_manifest(&settings, &manifest)?;
+  }
+
+  let manifest_path = tauri_bundler::bundle_manifest_path(&settings);
+  log::info!(action = "Finished"; "bundle manifest at {}", tauri_utils::display_path(&manifest_path));
+
+  if options.print_artifacts && !progress::is_json() {
+    println!("{}", std::fs::read_to_string(&manifest_path)?);
+  }
+
+  Ok(bundles)
 }
 
+/// Signs the updater artifacts, returning the paths of the signatures.
 fn sign_updaters(
-  settings: tauri_bundler::Settings,
-  bundles: Vec<tauri_bundler::Bundle>,
+  settings: &tauri_bundler::Settings,
+  bundles: &[tauri_bundler::Bundle],
   ci: bool,
-) -> crate::Result<()> {
+) -> crate::Result<Vec<PathBuf>> {
   let Some(update_settings) = settings.updater() else {
     // Updater not enabled
-    return Ok(());
+    return Ok(Vec::new());
   };
 
   let update_enabled_bundles: Vec<&tauri_bundler::Bundle> = bundles
@@ -238,7 +310,7 @@ fn sign_updaters(
     .collect();
 
   if update_enabled_bundles.is_empty() {
-    return Ok(());
+    return Ok(Vec::new());
   }
 
   // get the public key
@@ -278,6 +350,7 @@ fn sign_updaters(
     // another type of updater package who require multiple file signature
     for path in &bundle.bundle_paths {
       // sign our path from environment variables
+      progress::emit(progress::Event::Signing { path });
       let (signature_path, signature) = updater_signature::sign_file(&secret_key, path)?;
       if signature.keynum() != public_key.keynum() {
         log::warn!("The updater secret key from `TAURI_SIGNING_PRIVATE_KEY` does not match the public key from `plugins > updater > pubkey`. If you are not rotating keys, this means your configuration is wrong and won't be accepted at runtime when performing update.");
@@ -288,7 +361,7 @@ fn sign_updaters(
 
   print_signed_updater_archive(&signed_paths)?;
 
-  Ok(())
+  Ok(signed_paths)
 }
 
 fn print_signed_updater_archive(output_paths: &[PathBuf]) -> crate::Result<()> {
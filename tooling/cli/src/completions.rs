@@ -12,7 +12,7 @@ use std::{fs::write, path::PathBuf};
 const PKG_MANAGERS: &[&str] = &["cargo", "pnpm", "npm", "yarn", "bun"];
 
 #[derive(Debug, Clone, Parser)]
-#[clap(about = "Generate Tauri CLI shell completions for Bash, Zsh, PowerShell or Fish")]
+#[clap(about = "Generate Tauri CLI shell completions for Bash, Zsh, PowerShell, Fish or Elvish")]
 pub struct Options {
   /// Shell to generate a completion script for.
   #[clap(short, long, verbatim_doc_comment)]
@@ -20,6 +20,11 @@ pub struct Options {
   /// Output file for the shell completions. By default the completions are printed to stdout.
   #[clap(short, long)]
   output: Option<PathBuf>,
+  /// Binary name to generate the completions for, e.g. when the CLI is aliased.
+  ///
+  /// By default the completions are generated for `tauri` invoked through cargo and the Node.js package managers.
+  #[clap(long)]
+  binary_name: Option<String>,
 }
 
 fn completions_for(shell: Shell, manager: &'static str, cmd: Command) -> Vec<u8> {
@@ -37,8 +42,18 @@ fn completions_for(shell: Shell, manager: &'static str, cmd: Command) -> Vec<u8>
   buf
 }
 
-fn get_completions(shell: Shell, cmd: Command) -> Result<String> {
-  let completions = if shell == Shell::Bash {
+fn completions_for_binary(shell: Shell, binary_name: &str, cmd: Command) -> Vec<u8> {
+  let mut command = cmd.bin_name(binary_name);
+
+  let mut buf = Vec::new();
+  generate(shell, &mut command, binary_name, &mut buf);
+  buf
+}
+
+fn get_completions(shell: Shell, binary_name: Option<&str>, cmd: Command) -> Result<String> {
+  let completions = if let Some(binary_name) = binary_name {
+    String::from_utf8_lossy(&completions_for_binary(shell, binary_name, cmd)).into_owned()
+  } else if shell == Shell::Bash {
     let mut completions =
       String::from_utf8_lossy(&completions_for(shell, "cargo", cmd)).into_owned();
     for manager in PKG_MANAGERS {
@@ -87,7 +102,7 @@ fn get_completions(shell: Shell, cmd: Command) -> Result<String> {
 pub fn command(options: Options, cmd: Command) -> Result<()> {
   log::info!("Generating completion file for {}...", options.shell);
 
-  let completions = get_completions(options.shell, cmd)?;
+  let completions = get_completions(options.shell, options.binary_name.as_deref(), cmd)?;
   if let Some(output) = options.output {
     write(output, completions).context("failed to write to output path")?;
   } else {
@@ -96,3 +111,45 @@ pub fn command(options: Options, cmd: Command) -> Result<()> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::get_completions;
+  use clap::CommandFactory;
+  use clap_complete::Shell;
+
+  #[test]
+  fn generates_completions_for_each_shell() {
+    for shell in [
+      Shell::Bash,
+      Shell::Zsh,
+      Shell::Fish,
+      Shell::PowerShell,
+      Shell::Elvish,
+    ] {
+      for binary_name in [None, Some("tauri-alias")] {
+        let completions = get_completions(shell, binary_name, crate::Cli::command()).unwrap();
+        for expected in [
+          "--release",
+          "--no-bundle",
+          "--config",
+          "--features",
+          "android",
+          #[cfg(target_os = "macos")]
+          "ios",
+        ] {
+          assert!(
+            completions.contains(expected),
+            "{shell} completions for {binary_name:?} are missing `{expected}`"
+          );
+        }
+        if let Some(binary_name) = binary_name {
+          assert!(completions.contains(binary_name));
+          assert!(!completions.contains("pnpm"));
+        } else {
+          assert!(completions.contains("pnpm"));
+        }
+      }
+    }
+  }
+}
@@ -10,7 +10,7 @@ use crate::{
       get as get_config, reload as reload_config, BeforeDevCommand, ConfigHandle, FrontendDist,
     },
   },
-  interface::{AppInterface, DevProcess, ExitReason, Interface},
+  interface::{rust::apply_profile, AppInterface, DevProcess, ExitReason, Interface},
   CommandExt, ConfigValue, Result,
 };
 
@@ -22,6 +22,7 @@ use tauri_utils::platform::Target;
 use std::{
   env::set_current_dir,
   net::{IpAddr, Ipv4Addr},
+  path::PathBuf,
   process::{exit, Command, Stdio},
   sync::{
     atomic::{AtomicBool, Ordering},
@@ -30,6 +31,7 @@ use std::{
 };
 
 mod builtin_dev_server;
+mod dev_server_wait;
 
 static BEFORE_DEV: OnceLock<Mutex<Arc<SharedChild>>> = OnceLock::new();
 static KILL_BEFORE_DEV_FLAG: OnceLock<AtomicBool> = OnceLock::new();
@@ -65,6 +67,11 @@ pub struct Options {
   /// Run the code in release mode
   #[clap(long = "release")]
   pub release_mode: bool,
+  /// The cargo profile to build with, defaults to `build > devProfile` or `dev`.
+  ///
+  /// Custom profiles must be defined in the workspace Cargo.toml.
+  #[clap(long, conflicts_with("release_mode"))]
+  pub profile: Option<String>,
   /// Command line arguments passed to the runner.
   /// Use `--` to explicitly mark the start of the arguments. Arguments after a second `--` are passed to the application
   /// e.g. `tauri dev -- [runnerArgs] -- [appArgs]`.
@@ -75,6 +82,13 @@ pub struct Options {
   /// Disable the file watcher.
   #[clap(long)]
   pub no_watch: bool,
+  /// Additional path to watch for changes, on top of `build > watch > paths`.
+  #[clap(long, action = ArgAction::Append, conflicts_with("no_watch"))]
+  pub watch: Vec<PathBuf>,
+  /// Gitignore-style pattern of paths whose changes must not rebuild the application,
+  /// on top of `build > watch > ignore`. Patterns containing a `/` are relative to the configuration file.
+  #[clap(long, action = ArgAction::Append, conflicts_with("no_watch"))]
+  pub ignore: Vec<String>,
 
   /// Disable the built-in dev server for static files.
   #[clap(long)]
@@ -104,6 +118,29 @@ fn command_internal(mut options: Options) -> Result<()> {
 
   let config = get_config(target, options.config.as_ref().map(|c| &c.0))?;
 
+  let config_profile = config
+    .lock()
+    .unwrap()
+    .as_ref()
+    .unwrap()
+    .build
+    .dev_profile
+    .clone();
+  if let Some(debug) = apply_profile(
+    &mut options.args,
+    options.profile.take(),
+    // `--debug` and `--release` select the default profiles instead
+    config_profile.as_deref().filter(|_| !options.release_mode),
+  )? {
+    options.release_mode = !debug;
+  }
+
+  // the `--watch` paths are relative to the current directory, which is changed in `setup`
+  let cwd = std::env::current_dir()?;
+  for path in &mut options.watch {
+    *path = cwd.join(&*path);
+  }
+
   let mut interface = AppInterface::new(
     config.lock().unwrap().as_ref().unwrap(),
     options.target.clone(),
@@ -141,7 +178,7 @@ pub fn setup(interface: &AppInterface, options: &mut Options, config: ConfigHand
     let cwd = script_cwd.unwrap_or_else(|| app_dir().clone());
     if let Some(before_dev) = script {
       log::info!(action = "Running"; "BeforeDevCommand (`{}`)", before_dev);
-      let mut env = command_env(true);
+      let mut env = command_env(!options.release_mode);
       env.extend(interface.env());
 
       #[cfg(windows)]
@@ -286,50 +323,9 @@ pub fn setup(interface: &AppInterface, options: &mut Options, config: ConfigHand
 
   if !options.no_dev_server_wait {
     if let Some(url) = dev_url {
-      let host = url
-        .host()
-        .unwrap_or_else(|| panic!("No host name in the URL"));
-      let port = url
-        .port_or_known_default()
-        .unwrap_or_else(|| panic!("No port number in the URL"));
-      let addrs;
-      let addr;
-      let addrs = match host {
-        url::Host::Domain(domain) => {
-          use std::net::ToSocketAddrs;
-          addrs = (domain, port).to_socket_addrs()?;
-          addrs.as_slice()
-        }
-        url::Host::Ipv4(ip) => {
-          addr = (ip, port).into();
-          std::slice::from_ref(&addr)
-        }
-        url::Host::Ipv6(ip) => {
-          addr = (ip, port).into();
-          std::slice::from_ref(&addr)
-        }
-      };
-      let mut i = 0;
-      let sleep_interval = std::time::Duration::from_secs(2);
-      let timeout_duration = std::time::Duration::from_secs(1);
-      let max_attempts = 90;
-      'waiting: loop {
-        for addr in addrs.iter() {
-          if std::net::TcpStream::connect_timeout(addr, timeout_duration).is_ok() {
-            break 'waiting;
-          }
-        }
-
-        if i % 3 == 1 {
-          log::warn!("Waiting for your frontend dev server to start on {url}...",);
-        }
-        i += 1;
-        if i == max_attempts {
-          log::error!("Could not connect to `{url}` after {}s. Please make sure that is the URL to your dev server.", i * sleep_interval.as_secs());
-          exit(1);
-        }
-        std::thread::sleep(sleep_interval);
-      }
+      let wait_options =
+        dev_server_wait::WaitOptions::new(&url, &config.lock().unwrap().as_ref().unwrap().build)?;
+      dev_server_wait::wait(&url, &wait_options)?;
     }
   }
 
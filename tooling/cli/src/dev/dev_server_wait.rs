@@ -0,0 +1,302 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Waits for the frontend dev server configured in `build > devUrl` to be ready before running the app.
+
+use std::{
+  net::{SocketAddr, TcpStream, ToSocketAddrs},
+  time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+use tauri_utils::config::BuildConfig;
+use url::Url;
+
+use crate::Result;
+
+/// The time waited for the dev server to be ready when `build > devUrlTimeout` is not set.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(180);
+/// The time between two checks when `build > devUrlPollInterval` is not set.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The time after which a single check gives up.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How the dev server is checked, read from the build configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitOptions {
+  /// The URL responding with a 2xx or 3xx status once the dev server is ready,
+  /// or `None` to only wait for `devUrl` to accept TCP connections.
+  pub health_url: Option<Url>,
+  pub timeout: Duration,
+  pub poll_interval: Duration,
+}
+
+impl WaitOptions {
+  pub fn new(dev_url: &Url, config: &BuildConfig) -> Result<Self> {
+    let health_url = config
+      .dev_url_health_path
+      .as_deref()
+      .map(|path| {
+        dev_url
+          .join(path)
+          .with_context(|| format!("invalid `build > devUrlHealthPath` `{path}`"))
+      })
+      .transpose()?;
+    let poll_interval = config
+      .dev_url_poll_interval
+      .map(Duration::from_millis)
+      .unwrap_or(DEFAULT_POLL_INTERVAL);
+    if poll_interval.is_zero() {
+      bail!("`build > devUrlPollInterval` must be greater than 0");
+    }
+    Ok(Self {
+      health_url,
+      timeout: config
+        .dev_url_timeout
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT),
+      poll_interval,
+    })
+  }
+}
+
+/// The result of a dev server check.
+#[derive(Debug, PartialEq, Eq)]
+enum Check {
+  Ready,
+  /// The dev server is not ready yet, with the reason to report once the wait times out.
+  Pending(String),
+}
+
+/// Blocks until the dev server at `url` is ready, failing once [`WaitOptions::timeout`] is elapsed.
+pub fn wait(url: &Url, options: &WaitOptions) -> Result<()> {
+  let agent = options.health_url.as_ref().map(|_| agent()).transpose()?;
+
+  let start = Instant::now();
+  let mut attempt = 0;
+  loop {
+    let check = match (&agent, &options.health_url) {
+      (Some(agent), Some(health_url)) => check_health(agent, health_url),
+      _ => check_listening(url)?,
+    };
+    let reason = match check {
+      Check::Ready => return Ok(()),
+      Check::Pending(reason) => reason,
+    };
+
+    let Some(remaining) = options
+      .timeout
+      .checked_sub(start.elapsed())
+      .filter(|remaining| !remaining.is_zero())
+    else {
+      bail!(
+        "the frontend dev server at `{}` was not ready after {}s ({reason}). Make sure `build > devUrl` is the URL of your dev server and that it started, or increase `build > devUrlTimeout`",
+        options.health_url.as_ref().unwrap_or(url),
+        options.timeout.as_secs_f32()
+      );
+    };
+
+    if attempt % 3 == 1 {
+      log::warn!("Waiting for your frontend dev server to start on {url}...");
+    }
+    attempt += 1;
+    std::thread::sleep(remaining.min(options.poll_interval));
+  }
+}
+
+/// Checks that one of the addresses of `url` accepts TCP connections.
+fn check_listening(url: &Url) -> Result<Check> {
+  let host = url.host().context("no host name in the dev server URL")?;
+  let port = url
+    .port_or_known_default()
+    .context("no port number in the dev server URL")?;
+  let addrs: Vec<SocketAddr> = match host {
+    // resolved on each check since the name may only resolve once the dev server started
+    url::Host::Domain(domain) => match (domain, port).to_socket_addrs() {
+      Ok(addrs) => addrs.collect(),
+      Err(e) => return Ok(Check::Pending(format!("failed to resolve `{domain}`: {e}"))),
+    },
+    url::Host::Ipv4(ip) => vec![(ip, port).into()],
+    url::Host::Ipv6(ip) => vec![(ip, port).into()],
+  };
+
+  let mut reason = format!("`{url}` does not resolve to any address");
+  for addr in &addrs {
+    match TcpStream::connect_timeout(addr, CHECK_TIMEOUT) {
+      Ok(_) => return Ok(Check::Ready),
+      Err(e) => reason = format!("failed to connect to {addr}: {e}"),
+    }
+  }
+  Ok(Check::Pending(reason))
+}
+
+/// Checks that `health_url` responds with a 2xx or 3xx status.
+fn check_health(agent: &ureq::Agent, health_url: &Url) -> Check {
+  let status = match agent.get(health_url.as_str()).call() {
+    Ok(response) => response.status(),
+    Err(ureq::Error::Status(status, _)) => status,
+    Err(e) => return Check::Pending(e.to_string()),
+  };
+  health_check(status)
+}
+
+fn health_check(status: u16) -> Check {
+  if (200..400).contains(&status) {
+    Check::Ready
+  } else {
+    Check::Pending(format!("responded with status {status}"))
+  }
+}
+
+/// The HTTP client checking the dev server health.
+///
+/// Redirects are not followed since a 3xx status already means the dev server is up,
+/// and certificates are not verified so dev servers using self-signed certificates can be checked.
+fn agent() -> Result<ureq::Agent> {
+  #[allow(unused_mut)]
+  let mut builder = ureq::AgentBuilder::new()
+    .redirects(0)
+    .timeout_connect(CHECK_TIMEOUT)
+    .timeout(CHECK_TIMEOUT * 5);
+
+  #[cfg(feature = "rustls")]
+  {
+    let config = rustls::ClientConfig::builder()
+      .dangerous()
+      .with_custom_certificate_verifier(std::sync::Arc::new(
+        insecure::NoCertificateVerification::new(),
+      ))
+      .with_no_client_auth();
+    builder = builder.tls_config(std::sync::Arc::new(config));
+  }
+
+  #[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+  {
+    let connector = native_tls::TlsConnector::builder()
+      .danger_accept_invalid_certs(true)
+      .danger_accept_invalid_hostnames(true)
+      .build()
+      .context("failed to create the TLS connector of the dev server health check")?;
+    builder = builder.tls_connector(std::sync::Arc::new(connector));
+  }
+
+  Ok(builder.build())
+}
+
+#[cfg(feature = "rustls")]
+mod insecure {
+  use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{ring, verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    DigitallySignedStruct, SignatureScheme,
+  };
+
+  /// Accepts any server certificate, only checking the handshake signatures.
+  #[derive(Debug)]
+  pub struct NoCertificateVerification(WebPkiSupportedAlgorithms);
+
+  impl NoCertificateVerification {
+    pub(super) fn new() -> Self {
+      Self(ring::default_provider().signature_verification_algorithms)
+    }
+  }
+
+  impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+      &self,
+      _end_entity: &CertificateDer<'_>,
+      _intermediates: &[CertificateDer<'_>],
+      _server_name: &ServerName<'_>,
+      _ocsp_response: &[u8],
+      _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+      Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+      &self,
+      message: &[u8],
+      cert: &CertificateDer<'_>,
+      dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+      verify_tls12_signature(message, cert, dss, &self.0)
+    }
+
+    fn verify_tls13_signature(
+      &self,
+      message: &[u8],
+      cert: &CertificateDer<'_>,
+      dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+      verify_tls13_signature(message, cert, dss, &self.0)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+      self.0.supported_schemes()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_wait_options() {
+    let dev_url: Url = "https://localhost:5173/app/".parse().unwrap();
+
+    let options = WaitOptions::new(&dev_url, &BuildConfig::default()).unwrap();
+    assert_eq!(options.health_url, None);
+    assert_eq!(options.timeout, DEFAULT_TIMEOUT);
+    assert_eq!(options.poll_interval, DEFAULT_POLL_INTERVAL);
+
+    let config = BuildConfig {
+      dev_url_health_path: Some("/health".into()),
+      dev_url_timeout: Some(30_000),
+      dev_url_poll_interval: Some(500),
+      ..Default::default()
+    };
+    let options = WaitOptions::new(&dev_url, &config).unwrap();
+    assert_eq!(
+      options.health_url.unwrap().as_str(),
+      "https://localhost:5173/health"
+    );
+    assert_eq!(options.timeout, Duration::from_secs(30));
+    assert_eq!(options.poll_interval, Duration::from_millis(500));
+
+    let config = BuildConfig {
+      dev_url_poll_interval: Some(0),
+      ..Default::default()
+    };
+    assert!(WaitOptions::new(&dev_url, &config).is_err());
+  }
+
+  #[test]
+  fn health_status() {
+    assert_eq!(health_check(200), Check::Ready);
+    assert_eq!(health_check(302), Check::Ready);
+    assert!(matches!(health_check(502), Check::Pending(_)));
+    assert!(matches!(health_check(404), Check::Pending(_)));
+  }
+
+  #[test]
+  fn times_out() {
+    // bind then drop a listener to get a port nothing listens on
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+      .unwrap()
+      .local_addr()
+      .unwrap()
+      .port();
+    let url: Url = format!("http://127.0.0.1:{port}").parse().unwrap();
+    let options = WaitOptions {
+      health_url: None,
+      timeout: Duration::from_millis(200),
+      poll_interval: Duration::from_millis(50),
+    };
+    let err = wait(&url, &options).unwrap_err().to_string();
+    assert!(err.contains("was not ready after"), "{err}");
+    assert!(err.contains("devUrlTimeout"), "{err}");
+  }
+}
@@ -0,0 +1,260 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Newline-delimited JSON events describing the progress of `tauri build` and `tauri bundle`.
+//!
+//! With `--format json` the events are printed to stdout while the logs and the output of the commands are written to stderr.
+
+use std::{
+  io::Write,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex, OnceLock,
+  },
+  time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use tauri_bundler::{Bundle, BundleProgress};
+
+use crate::{CommandError, Result};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static START: OnceLock<Instant> = OnceLock::new();
+static STEPS: Mutex<Vec<StepDuration>> = Mutex::new(Vec::new());
+
+/// The format of the `tauri build` and `tauri bundle` progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+  /// Human-readable logs.
+  #[default]
+  Human,
+  /// Newline-delimited JSON events on stdout, with the logs on stderr.
+  Json,
+}
+
+/// A step of the build timed in the summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+  BeforeBuildCommand,
+  CargoBuild,
+  BeforeBundleCommand,
+  Bundle,
+  SignUpdaters,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepDuration {
+  step: Step,
+  duration_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleSummary<'a> {
+  format: &'a str,
+  paths: &'a [PathBuf],
+  size_bytes: u64,
+  /// The size of the files before they were archived or compressed, see [`Bundle::staged_size_bytes`].
+  staged_size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+  StepStarted {
+    step: Step,
+  },
+  StepFinished {
+    step: Step,
+    duration_ms: u64,
+  },
+  /// One of the targets of a build with multiple `--target` values started.
+  TargetStarted {
+    target: &'a str,
+  },
+  /// One of the targets of a build with multiple `--target` values finished, successfully if `error` is not set.
+  TargetFinished {
+    target: &'a str,
+    duration_ms: u64,
+    error: Option<String>,
+  },
+  /// A message printed by cargo with `--message-format json`.
+  CargoMessage {
+    message: serde_json::Value,
+  },
+  BundleStarted {
+    format: &'a str,
+  },
+  BundleFinished {
+    format: &'a str,
+    paths: Vec<PathBuf>,
+    duration_ms: u64,
+  },
+  Signing {
+    path: &'a Path,
+  },
+  /// The last event of a successful run.
+  Summary {
+    duration_ms: u64,
+    steps: Vec<StepDuration>,
+    /// The bundles at their final location.
+    bundles: Vec<BundleSummary<'a>>,
+  },
+  /// The last event of a failed run.
+  Error {
+    message: &'a str,
+    /// The stderr of the failed command, if any.
+    stderr: Option<&'a str>,
+  },
+}
+
+pub fn millis(duration: Duration) -> u64 {
+  duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+/// Enables the JSON events if `format` is [`ProgressFormat::Json`].
+pub fn enable(format: ProgressFormat) {
+  if format == ProgressFormat::Json {
+    START.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+  }
+}
+
+/// Whether the JSON events are printed, in which case nothing else must be written to stdout.
+pub fn is_json() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// Prints `event` as a single line of JSON if the JSON events are enabled.
+pub fn emit(event: Event<'_>) {
+  if !is_json() {
+    return;
+  }
+  match serde_json::to_string(&event) {
+    Ok(line) => {
+      let mut stdout = std::io::stdout().lock();
+      let _ = writeln!(stdout, "{line}");
+      let _ = stdout.flush();
+    }
+    Err(e) => log::error!("failed to serialize progress event: {e}"),
+  }
+}
+
+/// Runs `f`, reporting its start and end as `step`.
+pub fn step<T, F: FnOnce() -> Result<T>>(step: Step, f: F) -> Result<T> {
+  if !is_json() {
+    return f();
+  }
+
+  emit(Event::StepStarted { step });
+  let start = Instant::now();
+  let value = f()?;
+  let duration_ms = millis(start.elapsed());
+  STEPS
+    .lock()
+    .unwrap()
+    .push(StepDuration { step, duration_ms });
+  emit(Event::StepFinished { step, duration_ms });
+  Ok(value)
+}
+
+/// Forwards the progress of the bundler.
+pub fn bundle_progress(progress: BundleProgress) {
+  match progress {
+    BundleProgress::Signing { path } => emit(Event::Signing { path: &path }),
+    BundleProgress::Started { package_type } => emit(Event::BundleStarted {
+      format: package_type.short_name(),
+    }),
+    BundleProgress::Finished {
+      package_type,
+      bundles,
+      duration,
+    } => emit(Event::BundleFinished {
+      format: package_type.short_name(),
+      paths: tauri_bundler::bundle_paths(&bundles),
+      duration_ms: millis(duration),
+    }),
+    _ => (),
+  }
+}
+
+/// Reports the end of a successful run.
+pub fn summary(bundles: &[Bundle]) {
+  if !is_json() {
+    return;
+  }
+  emit(Event::Summary {
+    duration_ms: START
+      .get()
+      .map(|start| millis(start.elapsed()))
+      .unwrap_or(0),
+    steps: STEPS.lock().unwrap().clone(),
+    bundles: bundles
+      .iter()
+      .map(|bundle| BundleSummary {
+        format: bundle.package_type.short_name(),
+        paths: &bundle.bundle_paths,
+        size_bytes: bundle.size_bytes,
+        staged_size_bytes: bundle.staged_size_bytes,
+      })
+      .collect(),
+  });
+}
+
+/// Reports the error ending the run, `message` being the logged error.
+pub fn error(message: &str, error: &anyhow::Error) {
+  emit(error_event(message, error));
+}
+
+fn error_event<'a>(message: &'a str, error: &'a anyhow::Error) -> Event<'a> {
+  Event::Error {
+    message,
+    stderr: error
+      .chain()
+      .find_map(|e| e.downcast_ref::<CommandError>())
+      .map(|e| e.stderr.as_str()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serializes_events() {
+    assert_eq!(
+      serde_json::to_string(&Event::StepStarted {
+        step: Step::CargoBuild
+      })
+      .unwrap(),
+      r#"{"event":"step_started","step":"cargo_build"}"#
+    );
+    assert_eq!(
+      serde_json::to_string(&Event::BundleFinished {
+        format: "deb",
+        paths: vec!["app.deb".into()],
+        duration_ms: 10,
+      })
+      .unwrap(),
+      r#"{"event":"bundle_finished","format":"deb","paths":["app.deb"],"duration_ms":10}"#
+    );
+  }
+
+  #[test]
+  fn error_includes_command_stderr() {
+    let error = anyhow::Error::new(CommandError {
+      program: "makensis".into(),
+      stderr: "invalid script".into(),
+    })
+    .context("failed to bundle project");
+    let message = format!("{error:#}");
+    assert_eq!(
+      serde_json::to_string(&error_event(&message, &error)).unwrap(),
+      r#"{"event":"error","message":"failed to bundle project: failed to run makensis","stderr":"invalid script"}"#
+    );
+  }
+}
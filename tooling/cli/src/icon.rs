@@ -58,6 +58,77 @@ pub struct Options {
   /// The background color of the iOS icon - string as defined in the W3C's CSS Color Module Level 4 <https://www.w3.org/TR/css-color-4/>.
   #[clap(long, default_value = "#fff")]
   ios_color: String,
+
+  /// Path to the foreground layer (squared PNG or SVG file) of the Android adaptive icon.
+  /// Defaults to the source icon.
+  ///
+  /// Setting any of the `--android-*` options generates the Android adaptive icon.
+  #[clap(long)]
+  android_foreground: Option<PathBuf>,
+
+  /// The background layer of the Android adaptive icon,
+  /// either a path to a squared PNG or SVG file or a color as defined in the W3C's CSS Color Module Level 4 <https://www.w3.org/TR/css-color-4/>.
+  /// Defaults to white.
+  #[clap(long)]
+  android_background: Option<String>,
+
+  /// Path to the monochrome layer (squared PNG or SVG file) of the Android adaptive icon, used for themed icons on Android 13+.
+  #[clap(long)]
+  android_monochrome: Option<PathBuf>,
+}
+
+/// Minimum width and height of a raster source image.
+const MIN_SOURCE_SIZE: u32 = 64;
+
+/// The sizes of the layers of the `.ico` file, the first one being the default.
+const ICO_SIZES: [u32; 6] = [32, 16, 24, 48, 64, 256];
+
+struct AndroidDensity {
+  name: &'static str,
+  size: u32,
+  foreground_size: u32,
+}
+
+/// The `mipmap-*` density buckets, with the legacy icon and the adaptive icon layer sizes.
+const ANDROID_DENSITIES: [AndroidDensity; 5] = [
+  AndroidDensity {
+    name: "hdpi",
+    size: 72,
+    foreground_size: 162,
+  },
+  AndroidDensity {
+    name: "mdpi",
+    size: 48,
+    foreground_size: 108,
+  },
+  AndroidDensity {
+    name: "xhdpi",
+    size: 96,
+    foreground_size: 216,
+  },
+  AndroidDensity {
+    name: "xxhdpi",
+    size: 144,
+    foreground_size: 324,
+  },
+  AndroidDensity {
+    name: "xxxhdpi",
+    size: 192,
+    foreground_size: 432,
+  },
+];
+
+enum AndroidBackground {
+  Color(Rgba<u8>),
+  Image(Source),
+}
+
+/// The layers of the Android adaptive icon.
+struct AndroidAdaptiveIcon {
+  /// Uses the source icon when not set.
+  foreground: Option<Source>,
+  background: AndroidBackground,
+  monochrome: Option<Source>,
 }
 
 enum Source {
@@ -102,7 +173,89 @@ pub fn command(options: Options) -> Result<()> {
   let input = options.input;
   let out_dir = options.output.unwrap_or_else(|| tauri_dir().join("icons"));
   let png_icon_sizes = options.png.unwrap_or_default();
-  let ios_color = css_color::Srgb::from_str(&options.ios_color)
+  let ios_color = parse_color(&options.ios_color).context("failed to parse iOS color")?;
+
+  create_dir_all(&out_dir).context("Can't create output directory")?;
+
+  let source = read_source(&input)?;
+
+  let android_adaptive = if options.android_foreground.is_some()
+    || options.android_background.is_some()
+    || options.android_monochrome.is_some()
+  {
+    let foreground = options
+      .android_foreground
+      .as_deref()
+      .map(read_source)
+      .transpose()?;
+    let background = match options.android_background.as_deref() {
+      Some(background) if Path::new(background).exists() => {
+        AndroidBackground::Image(read_source(Path::new(background))?)
+      }
+      Some(background) => AndroidBackground::Color(parse_color(background).with_context(|| {
+        format!("Android background `{background}` is neither an existing image nor a color")
+      })?),
+      None => AndroidBackground::Color(Rgba([255, 255, 255, 255])),
+    };
+    let monochrome = options
+      .android_monochrome
+      .as_deref()
+      .map(read_source)
+      .transpose()?;
+    Some(AndroidAdaptiveIcon {
+      foreground,
+      background,
+      monochrome,
+    })
+  } else {
+    None
+  };
+
+  if png_icon_sizes.is_empty() {
+    appx(&source, &out_dir).context("Failed to generate appx icons")?;
+    icns(&source, &out_dir).context("Failed to generate .icns file")?;
+    ico(&source, &out_dir).context("Failed to generate .ico file")?;
+
+    png(&source, &out_dir, ios_color).context("Failed to generate png icons")?;
+
+    let android_out = out_dir
+      .parent()
+      .unwrap()
+      .join("gen/android/app/src/main/res/");
+    let android_out = if android_out.exists() {
+      android_out
+    } else {
+      let out = out_dir.join("android");
+      create_dir_all(&out).context("Can't create Android output directory")?;
+      out
+    };
+    android(&source, android_adaptive.as_ref(), &android_out)
+      .context("Failed to generate Android icons")?;
+  } else {
+    for target in png_icon_sizes
+      .into_iter()
+      .map(|size| {
+        let name = format!("{size}x{size}.png");
+        let out_path = out_dir.join(&name);
+        PngEntry {
+          name,
+          out_path,
+          size,
+        }
+      })
+      .collect::<Vec<PngEntry>>()
+    {
+      log::info!(action = "PNG"; "Creating {}", target.name);
+      resize_and_save_png(&source, target.size, &target.out_path, None)?;
+    }
+  }
+
+  Ok(())
+}
+
+// Parse a color as defined in the W3C's CSS Color Module Level 4.
+fn parse_color(color: &str) -> Result<Rgba<u8>> {
+  css_color::Srgb::from_str(color)
     .map(|color| {
       Rgba([
         (color.red * 255.) as u8,
@@ -111,73 +264,80 @@ pub fn command(options: Options) -> Result<()> {
         (color.alpha * 255.) as u8,
       ])
     })
-    .map_err(|_| anyhow::anyhow!("failed to parse iOS color"))?;
-
-  create_dir_all(&out_dir).context("Can't create output directory")?;
+    .map_err(|_| anyhow::anyhow!("`{color}` is not a valid CSS color"))
+}
 
-  let source = if let Some(extension) = input.extension() {
-    if extension == "svg" {
+// Read a PNG or SVG source image, ensuring it can be used to generate icons.
+fn read_source(path: &Path) -> Result<Source> {
+  let source = match path.extension() {
+    Some(extension) if extension == "svg" => {
       let rtree = {
         let mut fontdb = usvg::fontdb::Database::new();
         fontdb.load_system_fonts();
 
         let opt = usvg::Options {
           // Get file's absolute directory.
-          resources_dir: std::fs::canonicalize(&input)
+          resources_dir: std::fs::canonicalize(path)
             .ok()
             .and_then(|p| p.parent().map(|p| p.to_path_buf())),
           fontdb: Arc::new(fontdb),
           ..Default::default()
         };
 
-        let svg_data = std::fs::read(&input).unwrap();
-        usvg::Tree::from_data(&svg_data, &opt).unwrap()
+        let svg_data = std::fs::read(path)
+          .with_context(|| format!("Can't read source image {}", path.display()))?;
+        usvg::Tree::from_data(&svg_data, &opt)
+          .with_context(|| format!("Can't parse SVG source image {}", path.display()))?
       };
 
       Source::Svg(rtree)
-    } else {
-      Source::DynamicImage(DynamicImage::ImageRgba8(
-        open(&input)
-          .context("Can't read and decode source image")?
-          .into_rgba8(),
-      ))
     }
-  } else {
-    anyhow::bail!("Error loading image");
+    Some(_) => Source::DynamicImage(DynamicImage::ImageRgba8(
+      open(path)
+        .with_context(|| format!("Can't read and decode source image {}", path.display()))?
+        .into_rgba8(),
+    )),
+    None => anyhow::bail!("Error loading image {}", path.display()),
   };
 
+  validate_source(&source).with_context(|| format!("Invalid source image {}", path.display()))?;
+
+  Ok(source)
+}
+
+fn validate_source(source: &Source) -> Result<()> {
   if source.height() != source.width() {
-    anyhow::bail!("Source image must be square");
+    anyhow::bail!(
+      "Source image must be square, found {}x{}",
+      source.width(),
+      source.height()
+    );
   }
 
-  if png_icon_sizes.is_empty() {
-    appx(&source, &out_dir).context("Failed to generate appx icons")?;
-    icns(&source, &out_dir).context("Failed to generate .icns file")?;
-    ico(&source, &out_dir).context("Failed to generate .ico file")?;
-
-    png(&source, &out_dir, ios_color).context("Failed to generate png icons")?;
-  } else {
-    for target in png_icon_sizes
-      .into_iter()
-      .map(|size| {
-        let name = format!("{size}x{size}.png");
-        let out_path = out_dir.join(&name);
-        PngEntry {
-          name,
-          out_path,
-          size,
-        }
-      })
-      .collect::<Vec<PngEntry>>()
-    {
-      log::info!(action = "PNG"; "Creating {}", target.name);
-      resize_and_save_png(&source, target.size, &target.out_path, None)?;
-    }
+  // SVG images are rasterized at each size so only raster images have a minimum size
+  if matches!(source, Source::DynamicImage(_)) && source.width() < MIN_SOURCE_SIZE {
+    anyhow::bail!(
+      "Source image must be at least {MIN_SOURCE_SIZE}x{MIN_SOURCE_SIZE}, found {}x{}",
+      source.width(),
+      source.height()
+    );
   }
 
   Ok(())
 }
 
+/// Warns when the largest layer of `file_name` is larger than the raster source image,
+/// the layers larger than the source being upscaled from it.
+fn warn_upscale(source: &Source, file_name: &str, size: u32) {
+  if matches!(source, Source::DynamicImage(_)) && source.width() < size {
+    log::warn!(
+      "The source image is {}x{}, the {file_name} layers larger than that are upscaled and may look blurry. Use a source image of at least {size}x{size}",
+      source.width(),
+      source.height()
+    );
+  }
+}
+
 fn appx(source: &Source, out_dir: &Path) -> Result<()> {
   log::info!(action = "Appx"; "Creating StoreLogo.png");
   resize_and_save_png(source, 50, &out_dir.join("StoreLogo.png"), None)?;
@@ -197,6 +357,15 @@ fn icns(source: &Source, out_dir: &Path) -> Result<()> {
   log::info!(action = "ICNS"; "Creating icon.icns");
   let entries: HashMap<String, IcnsEntry> =
     serde_json::from_slice(include_bytes!("helpers/icns.json")).unwrap();
+  warn_upscale(
+    source,
+    "icon.icns",
+    entries
+      .values()
+      .map(|entry| entry.size)
+      .max()
+      .unwrap_or_default(),
+  );
 
   let mut family = IconFamily::new();
 
@@ -230,8 +399,9 @@ fn icns(source: &Source, out_dir: &Path) -> Result<()> {
 fn ico(source: &Source, out_dir: &Path) -> Result<()> {
   log::info!(action = "ICO"; "Creating icon.ico");
   let mut frames = Vec::new();
+  warn_upscale(source, "icon.ico", *ICO_SIZES.iter().max().unwrap());
 
-  for size in [32, 16, 24, 48, 64, 256] {
+  for size in ICO_SIZES {
     let image = source.resize_exact(size)?;
 
     // Only the 256px layer can be compressed according to the ico specs.
@@ -282,69 +452,6 @@ fn png(source: &Source, out_dir: &Path, ios_color: Rgba<u8>) -> Result<()> {
     entries
   }
 
-  fn android_entries(out_dir: &Path) -> Result<Vec<PngEntry>> {
-    struct AndroidEntry {
-      name: &'static str,
-      size: u32,
-      foreground_size: u32,
-    }
-
-    let mut entries = Vec::new();
-
-    let targets = vec![
-      AndroidEntry {
-        name: "hdpi",
-        size: 49,
-        foreground_size: 162,
-      },
-      AndroidEntry {
-        name: "mdpi",
-        size: 48,
-        foreground_size: 108,
-      },
-      AndroidEntry {
-        name: "xhdpi",
-        size: 96,
-        foreground_size: 216,
-      },
-      AndroidEntry {
-        name: "xxhdpi",
-        size: 144,
-        foreground_size: 324,
-      },
-      AndroidEntry {
-        name: "xxxhdpi",
-        size: 192,
-        foreground_size: 432,
-      },
-    ];
-
-    for target in targets {
-      let folder_name = format!("mipmap-{}", target.name);
-      let out_folder = out_dir.join(&folder_name);
-
-      create_dir_all(&out_folder).context("Can't create Android mipmap output directory")?;
-
-      entries.push(PngEntry {
-        name: format!("{}/{}", folder_name, "ic_launcher_foreground.png"),
-        out_path: out_folder.join("ic_launcher_foreground.png"),
-        size: target.foreground_size,
-      });
-      entries.push(PngEntry {
-        name: format!("{}/{}", folder_name, "ic_launcher_round.png"),
-        out_path: out_folder.join("ic_launcher_round.png"),
-        size: target.size,
-      });
-      entries.push(PngEntry {
-        name: format!("{}/{}", folder_name, "ic_launcher.png"),
-        out_path: out_folder.join("ic_launcher.png"),
-        size: target.size,
-      });
-    }
-
-    Ok(entries)
-  }
-
   fn ios_entries(out_dir: &Path) -> Result<Vec<PngEntry>> {
     struct IosEntry {
       size: f32,
@@ -419,20 +526,7 @@ fn png(source: &Source, out_dir: &Path, ios_color: Rgba<u8>) -> Result<()> {
     Ok(entries)
   }
 
-  let mut entries = desktop_entries(out_dir);
-
-  let android_out = out_dir
-    .parent()
-    .unwrap()
-    .join("gen/android/app/src/main/res/");
-  let out = if android_out.exists() {
-    android_out
-  } else {
-    let out = out_dir.join("android");
-    create_dir_all(&out).context("Can't create Android output directory")?;
-    out
-  };
-  entries.extend(android_entries(&out)?);
+  let entries = desktop_entries(out_dir);
 
   let ios_out = out_dir
     .parent()
@@ -459,6 +553,95 @@ fn png(source: &Source, out_dir: &Path, ios_color: Rgba<u8>) -> Result<()> {
   Ok(())
 }
 
+// Generate the `mipmap-*` launcher icons and, if requested, the adaptive icon layers and definitions.
+// Main target: Android
+fn android(source: &Source, adaptive: Option<&AndroidAdaptiveIcon>, out_dir: &Path) -> Result<()> {
+  for density in &ANDROID_DENSITIES {
+    let folder_name = format!("mipmap-{}", density.name);
+    let out_folder = out_dir.join(&folder_name);
+
+    create_dir_all(&out_folder).context("Can't create Android mipmap output directory")?;
+
+    let foreground = adaptive
+      .and_then(|adaptive| adaptive.foreground.as_ref())
+      .unwrap_or(source);
+    let mut entries = vec![
+      (
+        "ic_launcher_foreground.png",
+        foreground,
+        density.foreground_size,
+      ),
+      ("ic_launcher_round.png", source, density.size),
+      ("ic_launcher.png", source, density.size),
+    ];
+    if let Some(adaptive) = adaptive {
+      if let AndroidBackground::Image(background) = &adaptive.background {
+        entries.push((
+          "ic_launcher_background.png",
+          background,
+          density.foreground_size,
+        ));
+      }
+      if let Some(monochrome) = &adaptive.monochrome {
+        entries.push((
+          "ic_launcher_monochrome.png",
+          monochrome,
+          density.foreground_size,
+        ));
+      }
+    }
+
+    for (file_name, source, size) in entries {
+      log::info!(action = "Android"; "Creating {}/{}", folder_name, file_name);
+      resize_and_save_png(source, size, &out_folder.join(file_name), None)?;
+    }
+  }
+
+  if let Some(adaptive) = adaptive {
+    let background = match adaptive.background {
+      AndroidBackground::Color(Rgba([r, g, b, a])) => {
+        let values_folder = out_dir.join("values");
+        create_dir_all(&values_folder).context("Can't create Android values output directory")?;
+        log::info!(action = "Android"; "Creating values/ic_launcher_background.xml");
+        std::fs::write(
+          values_folder.join("ic_launcher_background.xml"),
+          format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <color name="ic_launcher_background">#{a:02X}{r:02X}{g:02X}{b:02X}</color>
+</resources>
+"#
+          ),
+        )?;
+        "@color/ic_launcher_background"
+      }
+      AndroidBackground::Image(_) => "@mipmap/ic_launcher_background",
+    };
+    let monochrome = if adaptive.monochrome.is_some() {
+      "\n    <monochrome android:drawable=\"@mipmap/ic_launcher_monochrome\"/>"
+    } else {
+      ""
+    };
+    let adaptive_icon = format!(
+      r#"<?xml version="1.0" encoding="utf-8"?>
+<adaptive-icon xmlns:android="http://schemas.android.com/apk/res/android">
+    <background android:drawable="{background}"/>
+    <foreground android:drawable="@mipmap/ic_launcher_foreground"/>{monochrome}
+</adaptive-icon>
+"#
+    );
+
+    let anydpi_folder = out_dir.join("mipmap-anydpi-v26");
+    create_dir_all(&anydpi_folder).context("Can't create Android mipmap output directory")?;
+    for file_name in ["ic_launcher.xml", "ic_launcher_round.xml"] {
+      log::info!(action = "Android"; "Creating mipmap-anydpi-v26/{}", file_name);
+      std::fs::write(anydpi_folder.join(file_name), &adaptive_icon)?;
+    }
+  }
+
+  Ok(())
+}
+
 // Resize image and save it to disk.
 fn resize_and_save_png(
   source: &Source,
@@ -485,3 +668,150 @@ fn write_png<W: Write>(image_data: &[u8], w: W, size: u32) -> Result<()> {
   encoder.write_image(image_data, size, size, ColorType::Rgba8)?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn source(size: u32) -> Source {
+    Source::DynamicImage(DynamicImage::ImageRgba8(ImageBuffer::from_pixel(
+      size,
+      size,
+      Rgba([255, 0, 0, 255]),
+    )))
+  }
+
+  #[test]
+  fn rejects_non_square_and_tiny_sources() {
+    let non_square = Source::DynamicImage(DynamicImage::ImageRgba8(ImageBuffer::from_pixel(
+      512,
+      256,
+      Rgba([255, 0, 0, 255]),
+    )));
+    assert!(validate_source(&non_square).is_err());
+    assert!(validate_source(&source(MIN_SOURCE_SIZE - 1)).is_err());
+    assert!(validate_source(&source(MIN_SOURCE_SIZE)).is_ok());
+  }
+
+  #[test]
+  fn icns_and_ico_sizes() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let source = source(1024);
+    icns(&source, out_dir.path()).unwrap();
+    ico(&source, out_dir.path()).unwrap();
+
+    let entries: HashMap<String, IcnsEntry> =
+      serde_json::from_slice(include_bytes!("helpers/icns.json")).unwrap();
+    let family = IconFamily::read(File::open(out_dir.path().join("icon.icns")).unwrap()).unwrap();
+    for (name, entry) in entries {
+      let icon_type = IconType::from_ostype(entry.ostype.parse().unwrap()).unwrap();
+      let image = family
+        .get_icon_with_type(icon_type)
+        .unwrap_or_else(|_| panic!("missing {name}"));
+      assert_eq!((image.width(), image.height()), (entry.size, entry.size));
+    }
+
+    // the ICONDIR header is followed by a 16 bytes entry per layer, starting with its width and height
+    let ico = std::fs::read(out_dir.path().join("icon.ico")).unwrap();
+    let count = u16::from_le_bytes([ico[4], ico[5]]) as usize;
+    let sizes = ico[6..6 + count * 16]
+      .chunks(16)
+      .map(|entry| match entry[0] {
+        // 0 means 256
+        0 => 256,
+        width => width as u32,
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(sizes, ICO_SIZES);
+  }
+
+  #[test]
+  fn android_icon_sizes() {
+    let out_dir = tempfile::tempdir().unwrap();
+    android(&source(1024), None, out_dir.path()).unwrap();
+
+    for density in &ANDROID_DENSITIES {
+      let folder = out_dir.path().join(format!("mipmap-{}", density.name));
+      for (file_name, size) in [
+        ("ic_launcher.png", density.size),
+        ("ic_launcher_round.png", density.size),
+        ("ic_launcher_foreground.png", density.foreground_size),
+      ] {
+        assert_eq!(
+          image::image_dimensions(folder.join(file_name)).unwrap(),
+          (size, size),
+          "mipmap-{}/{file_name}",
+          density.name
+        );
+      }
+      assert!(!folder.join("ic_launcher_monochrome.png").exists());
+    }
+    assert!(!out_dir.path().join("mipmap-anydpi-v26").exists());
+  }
+
+  #[test]
+  fn android_adaptive_icon_sizes() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let adaptive = AndroidAdaptiveIcon {
+      foreground: Some(source(512)),
+      background: AndroidBackground::Image(source(256)),
+      monochrome: Some(source(128)),
+    };
+    android(&source(1024), Some(&adaptive), out_dir.path()).unwrap();
+
+    for density in &ANDROID_DENSITIES {
+      let folder = out_dir.path().join(format!("mipmap-{}", density.name));
+      for (file_name, size) in [
+        ("ic_launcher.png", density.size),
+        ("ic_launcher_round.png", density.size),
+        ("ic_launcher_foreground.png", density.foreground_size),
+        ("ic_launcher_background.png", density.foreground_size),
+        ("ic_launcher_monochrome.png", density.foreground_size),
+      ] {
+        assert_eq!(
+          image::image_dimensions(folder.join(file_name)).unwrap(),
+          (size, size),
+          "mipmap-{}/{file_name}",
+          density.name
+        );
+      }
+    }
+
+    for file_name in ["ic_launcher.xml", "ic_launcher_round.xml"] {
+      let adaptive_icon =
+        std::fs::read_to_string(out_dir.path().join("mipmap-anydpi-v26").join(file_name)).unwrap();
+      assert!(adaptive_icon
+        .contains(r#"<background android:drawable="@mipmap/ic_launcher_background"/>"#));
+      assert!(adaptive_icon
+        .contains(r#"<foreground android:drawable="@mipmap/ic_launcher_foreground"/>"#));
+      assert!(adaptive_icon
+        .contains(r#"<monochrome android:drawable="@mipmap/ic_launcher_monochrome"/>"#));
+    }
+  }
+
+  #[test]
+  fn android_adaptive_icon_color_background() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let adaptive = AndroidAdaptiveIcon {
+      foreground: None,
+      background: AndroidBackground::Color(parse_color("#3366ff").unwrap()),
+      monochrome: None,
+    };
+    android(&source(1024), Some(&adaptive), out_dir.path()).unwrap();
+
+    let colors =
+      std::fs::read_to_string(out_dir.path().join("values/ic_launcher_background.xml")).unwrap();
+    assert!(colors.contains(r#"<color name="ic_launcher_background">#FF3366FF</color>"#));
+
+    let adaptive_icon =
+      std::fs::read_to_string(out_dir.path().join("mipmap-anydpi-v26/ic_launcher.xml")).unwrap();
+    assert!(
+      adaptive_icon.contains(r#"<background android:drawable="@color/ic_launcher_background"/>"#)
+    );
+    assert!(!adaptive_icon.contains("monochrome"));
+    assert!(!out_dir
+      .path()
+      .join("mipmap-mdpi/ic_launcher_background.png")
+      .exists());
+  }
+}
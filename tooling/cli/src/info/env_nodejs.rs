@@ -27,7 +27,7 @@ pub fn items(metadata: &VersionMetadata) -> Vec<SectionItem> {
   let node_target_ver = metadata.js_cli.node.replace(">= ", "");
 
   vec![
-    SectionItem::new().action(move || {
+    SectionItem::new().command("node").action(move || {
       cross_command("node")
         .arg("-v")
         .output()
@@ -62,17 +62,21 @@ pub fn items(metadata: &VersionMetadata) -> Vec<SectionItem> {
         .ok()
         .unwrap_or_default()
     }),
-    SectionItem::new().action(|| {
+    SectionItem::new().command("pnpm").action(|| {
       manager_version("pnpm")
         .map(|v| format!("pnpm: {}", v))
         .into()
     }),
-    SectionItem::new().action(|| {
+    SectionItem::new().command("yarn").action(|| {
       manager_version("yarn")
         .map(|v| format!("yarn: {}", v))
         .into()
     }),
-    SectionItem::new().action(|| manager_version("npm").map(|v| format!("npm: {}", v)).into()),
-    SectionItem::new().action(|| manager_version("bun").map(|v| format!("bun: {}", v)).into()),
+    SectionItem::new()
+      .command("npm")
+      .action(|| manager_version("npm").map(|v| format!("npm: {}", v)).into()),
+    SectionItem::new()
+      .command("bun")
+      .action(|| manager_version("bun").map(|v| format!("bun: {}", v)).into()),
   ]
 }
@@ -28,7 +28,7 @@ fn component_version(component: &str) -> Option<(String, Status)> {
 
 pub fn items() -> Vec<SectionItem> {
   vec![
-    SectionItem::new().action(|| {
+    SectionItem::new().command("rustc").action(|| {
        component_version("rustc")
           .unwrap_or_else(|| {
             (
@@ -41,7 +41,7 @@ pub fn items() -> Vec<SectionItem> {
             )
           }).into()
     }),
-    SectionItem::new().action(|| {
+    SectionItem::new().command("cargo").action(|| {
         component_version("cargo")
           .unwrap_or_else(|| {
             (
@@ -54,7 +54,7 @@ pub fn items() -> Vec<SectionItem> {
             )
           }).into()
     }),
-    SectionItem::new().action(|| {
+    SectionItem::new().command("rustup").action(|| {
         component_version("rustup")
             .unwrap_or_else(|| {
               (
@@ -67,7 +67,7 @@ pub fn items() -> Vec<SectionItem> {
               )
             }).into()
     }),
-    SectionItem::new().action(|| {
+    SectionItem::new().command("rustup").action(|| {
           Command::new("rustup")
             .args(["show", "active-toolchain"])
             .output()
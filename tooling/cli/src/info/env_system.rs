@@ -234,7 +234,7 @@ pub fn items() -> Vec<SectionItem> {
       target_os = "openbsd",
       target_os = "netbsd"
     ))]
-    SectionItem::new().action(|| {
+    SectionItem::new().command("pkg-config").action(|| {
           webkit2gtk_ver()
             .map(|v| (format!("webkit2gtk-4.1: {v}"), Status::Success))
             .unwrap_or_else(|| {
@@ -256,7 +256,7 @@ pub fn items() -> Vec<SectionItem> {
       target_os = "openbsd",
       target_os = "netbsd"
     ))]
-    SectionItem::new().action(|| {
+    SectionItem::new().command("pkg-config").action(|| {
           rsvg2_ver()
             .map(|v| (format!("rsvg2: {v}"), Status::Success))
             .unwrap_or_else(|| {
@@ -272,7 +272,7 @@ pub fn items() -> Vec<SectionItem> {
       },
     ),
     #[cfg(target_os = "macos")]
-    SectionItem::new().action(|| {
+    SectionItem::new().command("xcode-select").action(|| {
         if is_xcode_command_line_tools_installed() {
           (
             "Xcode Command Line Tools: installed".into(),
@@ -6,10 +6,11 @@ use crate::Result;
 use clap::Parser;
 use colored::{ColoredString, Colorize};
 use dialoguer::{theme::ColorfulTheme, Confirm};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 use std::{
   fmt::{self, Display, Formatter},
   panic,
+  path::{Path, PathBuf},
 };
 
 mod app;
@@ -60,6 +61,16 @@ impl Status {
   }
 }
 
+impl Serialize for Status {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(match self {
+      Status::Neutral | Status::Success => "ok",
+      Status::Warning => "warning",
+      Status::Error => "error",
+    })
+  }
+}
+
 impl Display for Status {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     write!(
@@ -122,6 +133,8 @@ pub struct SectionItem {
   /// If description is none, the item is skipped
   description: Option<String>,
   status: Status,
+  /// The external command the item runs, reported in the JSON output.
+  command: Option<&'static str>,
   action: Option<Box<dyn FnMut() -> ActionResult>>,
   action_if_err: Option<Box<dyn FnMut() -> ActionResult>>,
 }
@@ -145,9 +158,15 @@ impl SectionItem {
       action_if_err: None,
       description: None,
       status: Status::Neutral,
+      command: None,
     }
   }
 
+  fn command(mut self, command: &'static str) -> Self {
+    self.command = Some(command);
+    self
+  }
+
   fn action<F: FnMut() -> ActionResult + 'static>(mut self, action: F) -> Self {
     self.action = Some(Box::new(action));
     self
@@ -215,6 +234,74 @@ impl SectionItem {
 
     self.status
   }
+
+  /// The item in the JSON output, `None` if it is skipped.
+  fn to_json(&self) -> Option<JsonItem> {
+    let command = self.command.map(|name| JsonCommand {
+      name,
+      found: command_found(name),
+    });
+    let (key, value) = match (&self.description, &command) {
+      (Some(description), _) => match description.split_once(':') {
+        Some((key, value)) => (key.to_string(), Some(value.trim().to_string())),
+        None => (description.clone(), None),
+      },
+      // a command that could not run
+      (None, Some(command)) => (command.name.to_string(), None),
+      (None, None) => return None,
+    };
+    Some(JsonItem {
+      key,
+      value,
+      status: self.status,
+      command,
+    })
+  }
+}
+
+/// Whether the command is found in the `PATH`.
+fn command_found(command: &str) -> bool {
+  let Some(paths) = std::env::var_os("PATH") else {
+    return false;
+  };
+  std::env::split_paths(&paths).any(|dir| {
+    let path = dir.join(command);
+    if cfg!(windows) {
+      ["exe", "cmd", "bat"]
+        .iter()
+        .any(|ext| path.with_extension(ext).is_file())
+    } else {
+      path.is_file()
+    }
+  })
+}
+
+/// The `tauri info --json` output.
+#[derive(Serialize)]
+struct JsonReport {
+  sections: Vec<JsonSection>,
+}
+
+#[derive(Serialize)]
+struct JsonSection {
+  label: String,
+  status: Status,
+  items: Vec<JsonItem>,
+}
+
+#[derive(Serialize)]
+struct JsonItem {
+  key: String,
+  value: Option<String>,
+  status: Status,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  command: Option<JsonCommand>,
+}
+
+#[derive(Serialize)]
+struct JsonCommand {
+  name: &'static str,
+  found: bool,
 }
 
 struct Section<'a> {
@@ -224,7 +311,8 @@ struct Section<'a> {
 }
 
 impl Section<'_> {
-  fn display(&mut self) {
+  /// Runs the items, returning the most severe status.
+  fn run(&mut self) -> Status {
     let mut status = Status::Neutral;
 
     for item in &mut self.items {
@@ -234,6 +322,12 @@ impl Section<'_> {
       }
     }
 
+    status
+  }
+
+  fn display(&mut self) {
+    let status = self.run();
+
     let status_str = format!("[{status}]");
     let status = status.color(status_str);
 
@@ -245,6 +339,15 @@ impl Section<'_> {
       }
     }
   }
+
+  fn to_json(&mut self) -> JsonSection {
+    let status = self.run();
+    JsonSection {
+      label: self.label.to_string(),
+      status,
+      items: self.items.iter().filter_map(SectionItem::to_json).collect(),
+    }
+  }
 }
 
 #[derive(Debug, Parser)]
@@ -253,12 +356,27 @@ impl Section<'_> {
 )]
 pub struct Options {
   /// Interactive mode to apply automatic fixes.
-  #[clap(long)]
+  #[clap(long, conflicts_with = "json")]
   pub interactive: bool,
+  /// Print the information as JSON, with the status of each item and whether its command was found.
+  #[clap(long)]
+  pub json: bool,
+  /// Write the JSON output to the given file instead of stdout.
+  #[clap(short, long, requires = "json")]
+  pub output: Option<PathBuf>,
 }
 
 pub fn command(options: Options) -> Result<()> {
-  let Options { interactive } = options;
+  let Options {
+    interactive,
+    json,
+    output,
+  } = options;
+  if json {
+    // keep the descriptions free of color codes
+    colored::control::set_override(false);
+  }
+
   let hook = panic::take_hook();
   panic::set_hook(Box::new(|_info| {
     // do nothing
@@ -301,9 +419,8 @@ pub fn command(options: Options) -> Result<()> {
   };
   app.items.extend(app::items(app_dir, tauri_dir.as_deref()));
 
-  environment.display();
-  packages.display();
-  app.display();
+  #[allow(unused_mut)]
+  let mut sections = vec![environment, packages, app];
 
   // iOS
   #[cfg(target_os = "macos")]
@@ -316,10 +433,101 @@ pub fn command(options: Options) -> Result<()> {
           items: Vec::new(),
         };
         ios.items.extend(ios::items());
-        ios.display();
+        sections.push(ios);
       }
     }
   }
 
+  if json {
+    write_json(&mut sections, output.as_deref())
+  } else {
+    for section in &mut sections {
+      section.display();
+    }
+    Ok(())
+  }
+}
+
+fn write_json(sections: &mut [Section<'_>], output: Option<&Path>) -> Result<()> {
+  let report = JsonReport {
+    sections: sections.iter_mut().map(Section::to_json).collect(),
+  };
+  let json = serde_json::to_string_pretty(&report)?;
+  match output {
+    Some(output) => std::fs::write(output, json)?,
+    None => println!("{json}"),
+  }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn json_report() {
+    let mut sections = vec![
+      Section {
+        label: "Environment",
+        interactive: false,
+        items: vec![
+          SectionItem::new().action(|| ("rustc: 1.80.0".to_string(), Status::Success).into()),
+          SectionItem::new()
+            .command("tauri-info-missing-package-manager")
+            .action(|| ActionResult::None),
+          SectionItem::new().action(|| {
+            (
+              "rustup: not installed!\nVisit https://rustup.rs/".to_string(),
+              Status::Warning,
+            )
+              .into()
+          }),
+        ],
+      },
+      Section {
+        label: "App",
+        interactive: false,
+        items: vec![
+          SectionItem::new().description("build-type: bundle"),
+          SectionItem::new(),
+        ],
+      },
+    ];
+
+    let report = JsonReport {
+      sections: sections.iter_mut().map(Section::to_json).collect(),
+    };
+    assert_eq!(
+      serde_json::to_value(report).unwrap(),
+      serde_json::json!({
+        "sections": [
+          {
+            "label": "Environment",
+            "status": "warning",
+            "items": [
+              { "key": "rustc", "value": "1.80.0", "status": "ok" },
+              {
+                "key": "tauri-info-missing-package-manager",
+                "value": null,
+                "status": "ok",
+                "command": { "name": "tauri-info-missing-package-manager", "found": false }
+              },
+              {
+                "key": "rustup",
+                "value": "not installed!\nVisit https://rustup.rs/",
+                "status": "warning"
+              }
+            ]
+          },
+          {
+            "label": "App",
+            "status": "ok",
+            "items": [
+              { "key": "build-type", "value": "bundle", "status": "ok" }
+            ]
+          }
+        ]
+      })
+    );
+  }
+}
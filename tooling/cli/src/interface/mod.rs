@@ -34,6 +34,7 @@ pub trait AppSettings {
   fn get_binaries(&self, target: &str) -> crate::Result<Vec<tauri_bundler::BundleBinary>>;
   fn app_name(&self) -> Option<String>;
   fn lib_name(&self) -> Option<String>;
+  fn target_triple(&self) -> &str;
 
   fn get_bundler_settings(
     &self,
@@ -48,11 +49,11 @@ pub trait AppSettings {
       enabled_features.push("default".into());
     }
 
-    let target: String = if let Some(target) = options.target.clone() {
-      target
-    } else {
-      tauri_utils::platform::target_triple()?
-    };
+    // the bundles describe the architecture of the target, which may not be the host's
+    let target = options
+      .target
+      .clone()
+      .unwrap_or_else(|| self.target_triple().to_string());
 
     SettingsBuilder::new()
       .package_settings(self.get_package_settings())
@@ -21,10 +21,15 @@ use notify::RecursiveMode;
 use notify_debouncer_mini::new_debouncer;
 use serde::{Deserialize, Deserializer};
 use tauri_bundler::{
-  AppCategory, AppImageSettings, BundleBinary, BundleSettings, DebianSettings, DmgSettings,
-  MacOsSettings, PackageSettings, Position, RpmSettings, Size, UpdaterSettings, WindowsSettings,
+  AppCategory, AppImageSettings, ArchiveSettings, BundleBinary, BundleSettings,
+  CustomSignCommandSettings, DebianSettings, DmgSettings, FlatpakSettings, MacOsSettings,
+  PackageSettings, PacmanSettings, PkgSettings, Position, RpmSettings, Size, SnapSettings,
+  UpdaterSettings, WindowsSettings,
+};
+use tauri_utils::config::{
+  parse::is_configuration_file, CustomSignCommandConfig, DeepLinkProtocol, FrontendDist, Updater,
+  WatchConfig,
 };
-use tauri_utils::config::{parse::is_configuration_file, DeepLinkProtocol, Updater};
 
 use super::{AppSettings, DevProcess, ExitReason, Interface};
 use crate::{
@@ -52,6 +57,10 @@ pub struct Options {
   pub args: Vec<String>,
   pub config: Option<ConfigValue>,
   pub no_watch: bool,
+  /// Additional paths to watch, on top of `build > watch > paths`.
+  pub watch_paths: Vec<PathBuf>,
+  /// Additional ignore patterns, on top of `build > watch > ignore`.
+  pub watch_ignore: Vec<String>,
 }
 
 impl From<crate::build::Options> for Options {
@@ -64,6 +73,7 @@ impl From<crate::build::Options> for Options {
       args: options.args,
       config: options.config,
       no_watch: true,
+      ..Default::default()
     }
   }
 }
@@ -91,6 +101,8 @@ impl From<crate::dev::Options> for Options {
       args: options.args,
       config: options.config,
       no_watch: options.no_watch,
+      watch_paths: options.watch,
+      watch_ignore: options.ignore,
     }
   }
 }
@@ -113,6 +125,7 @@ pub struct RustupTarget {
 pub struct Rust {
   app_settings: Arc<RustAppSettings>,
   config_features: Vec<String>,
+  config_watch: WatchConfig,
   available_targets: Option<Vec<RustupTarget>>,
 }
 
@@ -159,6 +172,7 @@ impl Interface for Rust {
     Ok(Self {
       app_settings: Arc::new(app_settings),
       config_features: config.build.features.clone().unwrap_or_default(),
+      config_watch: config.build.watch.clone().unwrap_or_default(),
       available_targets: None,
     })
   }
@@ -204,13 +218,16 @@ impl Interface for Rust {
       Ok(())
     } else {
       let config = options.config.clone().map(|c| c.0);
+      let mut watch = self.config_watch.clone();
+      watch.paths.extend(options.watch_paths.iter().cloned());
+      watch.ignore.extend(options.watch_ignore.iter().cloned());
       let run = Arc::new(|rust: &mut Rust| {
         let on_exit = on_exit.clone();
         rust.run_dev(options.clone(), run_args.clone(), move |status, reason| {
           on_exit(status, reason)
         })
       });
-      self.run_dev_watcher(config, run)
+      self.run_dev_watcher(config, watch, run)
     }
   }
 
@@ -233,8 +250,9 @@ impl Interface for Rust {
       Ok(())
     } else {
       let config = options.config.clone().map(|c| c.0);
+      let watch = self.config_watch.clone();
       let run = Arc::new(|_rust: &mut Rust| runner(options.clone()));
-      self.run_dev_watcher(config, run)
+      self.run_dev_watcher(config, watch, run)
     }
   }
 
@@ -289,7 +307,7 @@ impl IgnoreMatcher {
   }
 }
 
-fn build_ignore_matcher(dir: &Path) -> IgnoreMatcher {
+fn build_ignore_matcher(dir: &Path, watch_ignore: &[String]) -> IgnoreMatcher {
   let mut matchers = Vec::new();
 
   // ignore crate doesn't expose an API to build `ignore::gitignore::GitIgnore`
@@ -329,9 +347,68 @@ fn build_ignore_matcher(dir: &Path) -> IgnoreMatcher {
     }
   }
 
+  if !watch_ignore.is_empty() {
+    matchers.push(watch_ignore_matcher(dir, tauri_dir(), watch_ignore));
+  }
+
   IgnoreMatcher(matchers)
 }
 
+/// Builds the matcher for the `build > watch > ignore` patterns, which are relative to `config_dir`.
+fn watch_ignore_matcher(root: &Path, config_dir: &Path, patterns: &[String]) -> Gitignore {
+  let config_dir = config_dir.strip_prefix(root).unwrap_or(Path::new(""));
+  let mut ignore_builder = GitignoreBuilder::new(root);
+  for pattern in patterns {
+    match rebase_ignore_pattern(pattern, config_dir) {
+      Some(line) => {
+        if let Err(e) = ignore_builder.add_line(None, &line) {
+          log::warn!("Invalid watch ignore pattern `{pattern}`: {e}");
+        }
+      }
+      None => log::warn!("Watch ignore pattern `{pattern}` is outside of the watched directories"),
+    }
+  }
+  ignore_builder
+    .build()
+    .unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Rewrites an ignore pattern relative to `config_dir` to a pattern relative to the ignore matcher root.
+///
+/// Patterns without a `/` (other than a trailing one) match at any level and are kept as is.
+fn rebase_ignore_pattern(pattern: &str, config_dir: &Path) -> Option<String> {
+  let (negation, pattern) = match pattern.strip_prefix('!') {
+    Some(pattern) => ("!", pattern),
+    None => ("", pattern),
+  };
+  if !pattern.trim_end_matches('/').contains('/') {
+    return Some(format!("{negation}{pattern}"));
+  }
+
+  let mut components = config_dir
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+    .collect::<Vec<_>>();
+  for component in pattern.split('/') {
+    match component {
+      "" | "." => {}
+      ".." => {
+        components.pop()?;
+      }
+      component => components.push(component.to_string()),
+    }
+  }
+  if components.is_empty() {
+    return None;
+  }
+
+  let trailing_slash = if pattern.ends_with('/') { "/" } else { "" };
+  Some(format!(
+    "{negation}/{}{trailing_slash}",
+    components.join("/")
+  ))
+}
+
 fn lookup<F: FnMut(FileType, PathBuf)>(dir: &Path, mut f: F) {
   let mut default_gitignore = std::env::temp_dir();
   default_gitignore.push(".tauri");
@@ -438,7 +515,7 @@ fn expand_member_path(path: &Path) -> crate::Result<Vec<PathBuf>> {
   Ok(res)
 }
 
-fn get_watch_folders() -> crate::Result<Vec<PathBuf>> {
+fn get_watch_folders(additional_watch_folders: &[PathBuf]) -> crate::Result<Vec<PathBuf>> {
   let tauri_path = tauri_dir();
   let workspace_path = get_workspace_dir()?;
 
@@ -469,7 +546,28 @@ fn get_watch_folders() -> crate::Result<Vec<PathBuf>> {
     }
   }
 
-  Ok(watch_folders)
+  // Additional paths from `build > watch > paths` and `--watch`, relative to the configuration file.
+  for p in additional_watch_folders {
+    match dunce::canonicalize(tauri_path.join(p)) {
+      Ok(p) => watch_folders.push(p),
+      Err(err) => log::warn!("Unable to watch {}: {}", p.display(), err),
+    }
+  }
+
+  Ok(dedup_watch_folders(watch_folders))
+}
+
+/// Removes the folders that are already watched through one of their parents.
+fn dedup_watch_folders(watch_folders: Vec<PathBuf>) -> Vec<PathBuf> {
+  let mut deduped: Vec<PathBuf> = Vec::new();
+  for folder in watch_folders {
+    if deduped.iter().any(|p| folder.starts_with(p)) {
+      continue;
+    }
+    deduped.retain(|p| !p.starts_with(&folder));
+    deduped.push(folder);
+  }
+  deduped
 }
 
 impl Rust {
@@ -505,6 +603,7 @@ impl Rust {
   fn run_dev_watcher<F: Fn(&mut Rust) -> crate::Result<Box<dyn DevProcess + Send>>>(
     &mut self,
     config: Option<serde_json::Value>,
+    watch: WatchConfig,
     run: Arc<F>,
   ) -> crate::Result<()> {
     let child = run(self)?;
@@ -513,13 +612,14 @@ impl Rust {
     let (tx, rx) = sync_channel(1);
     let app_path = app_dir();
 
-    let watch_folders = get_watch_folders()?;
+    let watch_folders = get_watch_folders(&watch.paths)?;
 
     let common_ancestor = common_path::common_path_all(watch_folders.iter().map(Path::new))
       .expect("watch_folders should not be empty");
-    let ignore_matcher = build_ignore_matcher(&common_ancestor);
+    let ignore_matcher = build_ignore_matcher(&common_ancestor, &watch.ignore);
 
-    let mut watcher = new_debouncer(Duration::from_secs(1), move |r| {
+    let debounce = Duration::from_millis(watch.debounce.unwrap_or(1000));
+    let mut watcher = new_debouncer(debounce, move |r| {
       if let Ok(events) = r {
         tx.send(events).unwrap()
       }
@@ -700,6 +800,15 @@ struct CargoSettings {
   workspace: Option<WorkspaceSettings>,
   /// the binary targets configuration.
   bin: Option<Vec<BinarySettings>>,
+  /// the custom profiles, only read from the workspace root.
+  profile: Option<HashMap<String, CargoProfile>>,
+}
+
+/// A cargo profile (`[profile.<name>]`).
+#[derive(Clone, Debug, Deserialize)]
+struct CargoProfile {
+  /// the profile this one inherits from, required for custom profiles.
+  inherits: Option<String>,
 }
 
 impl CargoSettings {
@@ -843,6 +952,10 @@ impl AppSettings for RustAppSettings {
       arch64bits,
     )?;
 
+    if let Some(FrontendDist::Directory(frontend_dist)) = &config.build.frontend_dist {
+      settings.frontend_dist = Some(tauri_dir().join(frontend_dist));
+    }
+
     if let Some(plugin_config) = config
       .plugins
       .0
@@ -967,6 +1080,10 @@ impl AppSettings for RustAppSettings {
       .and_then(|n| n.as_str())
       .map(|n| n.to_string())
   }
+
+  fn target_triple(&self) -> &str {
+    &self.target_triple
+  }
 }
 
 impl RustAppSettings {
@@ -1179,7 +1296,94 @@ pub fn get_profile_dir(options: &Options) -> &str {
   }
 }
 
+/// Adds the cargo profile selected with `--profile`, or in the configuration, to the runner arguments.
+///
+/// A profile already passed in the runner arguments takes precedence over the configuration.
+/// Returns whether the selected profile builds in debug mode, or `None` if no profile is selected.
+pub fn apply_profile(
+  args: &mut Vec<String>,
+  profile: Option<String>,
+  config_profile: Option<&str>,
+) -> crate::Result<Option<bool>> {
+  let runner_args_end = args.iter().position(|a| a == "--").unwrap_or(args.len());
+  let runner_profile = get_cargo_option(&args[..runner_args_end], "--profile").map(String::from);
+
+  let profile = match (profile, runner_profile) {
+    (Some(profile), Some(runner_profile)) if profile != runner_profile => {
+      anyhow::bail!(
+        "`--profile {profile}` conflicts with the `--profile {runner_profile}` runner argument"
+      );
+    }
+    (_, Some(runner_profile)) => runner_profile,
+    (profile, None) => {
+      let Some(profile) = profile.or_else(|| config_profile.map(String::from)) else {
+        return Ok(None);
+      };
+      args.splice(0..0, ["--profile".to_string(), profile.clone()]);
+      profile
+    }
+  };
+
+  let profiles = CargoSettings::load(&get_workspace_dir()?)?
+    .profile
+    .unwrap_or_default();
+  profile_is_debug(&profiles, &profile).map(Some)
+}
+
+/// Checks that the profile is defined and whether it is the `dev` profile or inherits from it.
+fn profile_is_debug(
+  profiles: &HashMap<String, CargoProfile>,
+  profile: &str,
+) -> crate::Result<bool> {
+  let mut current = profile;
+  let mut visited = Vec::new();
+  loop {
+    match current {
+      "dev" | "test" => return Ok(true),
+      "release" | "bench" => return Ok(false),
+      _ => {}
+    }
+
+    if visited.contains(&current) {
+      anyhow::bail!("the `{profile}` cargo profile has an `inherits` cycle");
+    }
+    visited.push(current);
+
+    current = profiles
+      .get(current)
+      .with_context(|| {
+        format!(
+          "the `{current}` cargo profile is not defined, add a `[profile.{current}]` section to the workspace Cargo.toml"
+        )
+      })?
+      .inherits
+      .as_deref()
+      .with_context(|| format!("the `{current}` cargo profile must set `inherits`"))?;
+  }
+}
+
 #[allow(unused_variables)]
+fn custom_sign_command_settings(
+  command: CustomSignCommandConfig,
+) -> crate::Result<CustomSignCommandSettings> {
+  let mut args = match command {
+    CustomSignCommandConfig::Command(command) => command
+      .split_whitespace()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>(),
+    CustomSignCommandConfig::CommandWithArgs(args) => args,
+  }
+  .into_iter();
+  let cmd = args
+    .next()
+    .filter(|cmd| !cmd.is_empty())
+    .context("`bundle > windows > signCommand` must not be empty")?;
+  Ok(CustomSignCommandSettings {
+    cmd,
+    args: args.collect(),
+  })
+}
+
 fn tauri_config_to_bundle_settings(
   settings: &RustAppSettings,
   features: &[String],
@@ -1210,6 +1414,15 @@ fn tauri_config_to_bundle_settings(
   let mut resources = config
     .resources
     .unwrap_or(BundleResources::List(Vec::new()));
+  // the pacman dependencies default to the user defined Debian dependencies
+  #[allow(unused_mut)]
+  let mut depends_pacman = config
+    .linux
+    .pacman
+    .depends
+    .or_else(|| config.linux.deb.depends.clone())
+    .unwrap_or_default();
+
   #[allow(unused_mut)]
   let mut depends_deb = config.linux.deb.depends.unwrap_or_default();
 
@@ -1224,46 +1437,69 @@ fn tauri_config_to_bundle_settings(
     if enabled_features.contains(&"tray-icon".into())
       || enabled_features.contains(&"tauri/tray-icon".into())
     {
-      let (tray_kind, path) = std::env::var("TAURI_LINUX_AYATANA_APPINDICATOR")
-        .map(|ayatana| {
-          if ayatana == "true" || ayatana == "1" {
-            (
-              pkgconfig_utils::TrayKind::Ayatana,
-              format!(
-                "{}/libayatana-appindicator3.so.1",
-                pkgconfig_utils::get_library_path("ayatana-appindicator3-0.1")
-                  .expect("failed to get ayatana-appindicator library path using pkg-config.")
-              ),
-            )
-          } else {
-            (
-              pkgconfig_utils::TrayKind::Libappindicator,
-              format!(
-                "{}/libappindicator3.so.1",
-                pkgconfig_utils::get_library_path("appindicator3-0.1")
-                  .expect("failed to get libappindicator-gtk library path using pkg-config.")
-              ),
-            )
+      let (tray_kind, path) = if is_cross_arch(&settings.target_triple) {
+        // pkg-config finds the host libraries, so the library must be provided for the target
+        log::warn!(
+          "Skipping the appindicator library detection when cross compiling for {}, set `TAURI_LINUX_AYATANA_APPINDICATOR` to select the library and `TAURI_TRAY_LIBRARY_PATH` to bundle it in the AppImage",
+          settings.target_triple
+        );
+        let tray_kind = match std::env::var("TAURI_LINUX_AYATANA_APPINDICATOR") {
+          Ok(ayatana) if ayatana != "true" && ayatana != "1" => {
+            pkgconfig_utils::TrayKind::Libappindicator
           }
-        })
-        .unwrap_or_else(|_| pkgconfig_utils::get_appindicator_library_path());
+          _ => pkgconfig_utils::TrayKind::Ayatana,
+        };
+        (tray_kind, std::env::var("TAURI_TRAY_LIBRARY_PATH").ok())
+      } else {
+        let (tray_kind, path) = std::env::var("TAURI_LINUX_AYATANA_APPINDICATOR")
+          .map(|ayatana| {
+            if ayatana == "true" || ayatana == "1" {
+              (
+                pkgconfig_utils::TrayKind::Ayatana,
+                format!(
+                  "{}/libayatana-appindicator3.so.1",
+                  pkgconfig_utils::get_library_path("ayatana-appindicator3-0.1")
+                    .expect("failed to get ayatana-appindicator library path using pkg-config.")
+                ),
+              )
+            } else {
+              (
+                pkgconfig_utils::TrayKind::Libappindicator,
+                format!(
+                  "{}/libappindicator3.so.1",
+                  pkgconfig_utils::get_library_path("appindicator3-0.1")
+                    .expect("failed to get libappindicator-gtk library path using pkg-config.")
+                ),
+              )
+            }
+          })
+          .unwrap_or_else(|_| pkgconfig_utils::get_appindicator_library_path());
+        (tray_kind, Some(path))
+      };
       match tray_kind {
         pkgconfig_utils::TrayKind::Ayatana => {
           depends_deb.push("libayatana-appindicator3-1".into());
+          depends_pacman.push("libayatana-appindicator".into());
           libs.push("libayatana-appindicator3.so.1".into());
         }
         pkgconfig_utils::TrayKind::Libappindicator => {
           depends_deb.push("libappindicator3-1".into());
+          depends_pacman.push("libappindicator-gtk3".into());
           libs.push("libappindicator3.so.1".into());
         }
       }
 
-      std::env::set_var("TAURI_TRAY_LIBRARY_PATH", path);
+      if let Some(path) = path {
+        std::env::set_var("TAURI_TRAY_LIBRARY_PATH", path);
+      }
     }
 
     depends_deb.push("libwebkit2gtk-4.1-0".to_string());
     depends_deb.push("libgtk-3-0".to_string());
 
+    depends_pacman.push("webkit2gtk-4.1".to_string());
+    depends_pacman.push("gtk3".to_string());
+
     libs.push("libwebkit2gtk-4.1.so.0".into());
     libs.push("libgtk-3.so.0".into());
 
@@ -1319,6 +1555,7 @@ fn tauri_config_to_bundle_settings(
     icon: Some(config.icon),
     resources,
     resources_map,
+    resource_collision: config.resource_collision,
     copyright: config.copyright,
     category: match config.category {
       Some(category) => Some(AppCategory::from_str(&category).map_err(|e| match e {
@@ -1330,7 +1567,18 @@ fn tauri_config_to_bundle_settings(
     file_associations: config.file_associations,
     short_description: config.short_description,
     long_description: config.long_description,
+    display_names: config.display_names,
     external_bin: config.external_bin,
+    frontend_dist: None,
+    output_dir: config.output_dir,
+    artifact_name: config.artifact_name,
+    checksums: config.checksums,
+    reproducible: config.reproducible,
+    strip: config.strip,
+    archive: ArchiveSettings {
+      formats: config.archive.formats,
+      compression_level: config.archive.compression_level,
+    },
     deb: DebianSettings {
       depends: if depends_deb.is_empty() {
         None
@@ -1345,13 +1593,18 @@ fn tauri_config_to_bundle_settings(
       section: config.linux.deb.section,
       priority: config.linux.deb.priority,
       changelog: config.linux.deb.changelog,
+      skip_conffiles: config.linux.deb.skip_conffiles,
       pre_install_script: config.linux.deb.pre_install_script,
       post_install_script: config.linux.deb.post_install_script,
       pre_remove_script: config.linux.deb.pre_remove_script,
       post_remove_script: config.linux.deb.post_remove_script,
+      maintainer_scripts: config.linux.deb.maintainer_scripts,
+      compression: config.linux.deb.compression,
+      sign_key: config.linux.deb.sign_key,
     },
     appimage: AppImageSettings {
       files: config.linux.appimage.files,
+      update_information: config.linux.appimage.update_information,
     },
     rpm: RpmSettings {
       depends: if depends_rpm.is_empty() {
@@ -1370,7 +1623,46 @@ fn tauri_config_to_bundle_settings(
       post_install_script: config.linux.rpm.post_install_script,
       pre_remove_script: config.linux.rpm.pre_remove_script,
       post_remove_script: config.linux.rpm.post_remove_script,
+      config_files: config.linux.rpm.config_files,
+      systemd_units: config.linux.rpm.systemd_units,
+      sign_key: config.linux.rpm.sign_key,
+    },
+    flatpak: FlatpakSettings {
+      app_id: config.linux.flatpak.app_id,
+      runtime: config.linux.flatpak.runtime,
+      runtime_version: config.linux.flatpak.runtime_version,
+      sdk: config.linux.flatpak.sdk,
+      finish_args: config.linux.flatpak.finish_args,
+      modules: config.linux.flatpak.modules,
+      files: config.linux.flatpak.files,
+      desktop_template: config.linux.flatpak.desktop_template,
+    },
+    snap: SnapSettings {
+      confinement: config.linux.snap.confinement,
+      grade: config.linux.snap.grade,
+      base: config.linux.snap.base,
+      plugs: config.linux.snap.plugs,
+      files: config.linux.snap.files,
+      desktop_template: config.linux.snap.desktop_template,
+    },
+    pacman: PacmanSettings {
+      depends: if depends_pacman.is_empty() {
+        None
+      } else {
+        Some(depends_pacman)
+      },
+      provides: config.linux.pacman.provides,
+      conflicts: config.linux.pacman.conflicts,
+      replaces: config.linux.pacman.replaces,
+      release: config.linux.pacman.release,
+      files: config.linux.pacman.files,
+      desktop_template: config.linux.pacman.desktop_template,
+      pre_install_script: config.linux.pacman.pre_install_script,
+      post_install_script: config.linux.pacman.post_install_script,
+      pre_remove_script: config.linux.pacman.pre_remove_script,
+      post_remove_script: config.linux.pacman.post_remove_script,
     },
+    allow_empty_file_globs: config.linux.allow_empty_file_globs,
     dmg: DmgSettings {
       background: config.macos.dmg.background,
       window_position: config
@@ -1393,6 +1685,15 @@ fn tauri_config_to_bundle_settings(
         x: config.macos.dmg.application_folder_position.x,
         y: config.macos.dmg.application_folder_position.y,
       },
+      license_path: config.macos.dmg.license_path,
+      volume_icon: config.macos.dmg.volume_icon,
+    },
+    pkg: PkgSettings {
+      install_location: config.macos.pkg.install_location,
+      identifier: config.macos.pkg.identifier,
+      preinstall_script: config.macos.pkg.preinstall_script,
+      postinstall_script: config.macos.pkg.postinstall_script,
+      signing_identity: config.macos.pkg.signing_identity,
     },
     macos: MacOsSettings {
       frameworks: config.macos.frameworks,
@@ -1401,6 +1702,7 @@ fn tauri_config_to_bundle_settings(
       exception_domain: config.macos.exception_domain,
       signing_identity,
       hardened_runtime: config.macos.hardened_runtime,
+      signing_backend: config.macos.signing_backend,
       provider_short_name,
       entitlements: config.macos.entitlements,
       info_plist_path: {
@@ -1411,19 +1713,30 @@ fn tauri_config_to_bundle_settings(
           None
         }
       },
+      universal_binary: None,
+      updater_artifact: config.macos.updater_artifact,
     },
     windows: WindowsSettings {
       timestamp_url: config.windows.timestamp_url,
       tsp: config.windows.tsp,
-      digest_algorithm: config.windows.digest_algorithm,
+      digest_algorithm: config
+        .windows
+        .digest_algorithm
+        .map(|algorithms| algorithms.to_vec())
+        .unwrap_or_default(),
       certificate_thumbprint: config.windows.certificate_thumbprint,
       wix: config.windows.wix.map(wix_settings),
       nsis: config.windows.nsis.map(nsis_settings),
+      files: config.windows.files,
       icon_path: windows_icon_path,
       webview_install_mode: config.windows.webview_install_mode,
       webview_fixed_runtime_path: config.windows.webview_fixed_runtime_path,
       allow_downgrades: config.windows.allow_downgrades,
-      sign_command: config.windows.sign_command,
+      sign_command: config
+        .windows
+        .sign_command
+        .map(custom_sign_command_settings)
+        .transpose()?,
     },
     license: config.license.or_else(|| {
       settings
@@ -1450,6 +1763,18 @@ fn tauri_config_to_bundle_settings(
   })
 }
 
+/// Whether the target architecture differs from the host's,
+/// in which case the host libraries cannot be bundled with the app.
+#[cfg(target_os = "linux")]
+fn is_cross_arch(target_triple: &str) -> bool {
+  let arch = match target_triple.split('-').next().unwrap_or_default() {
+    "i586" | "i686" => "x86",
+    arch if arch.starts_with("arm") => "arm",
+    arch => arch,
+  };
+  arch != std::env::consts::ARCH
+}
+
 #[cfg(target_os = "linux")]
 mod pkgconfig_utils {
   use std::process::Command;
@@ -1518,6 +1843,30 @@ mod tests {
     assert_eq!(get_cargo_option(&args, "--non-existent"), None);
   }
 
+  #[test]
+  fn custom_sign_command() {
+    let command = custom_sign_command_settings(CustomSignCommandConfig::Command(
+      " sign-cli  --arg %1 ".into(),
+    ))
+    .unwrap();
+    assert_eq!(command.cmd, "sign-cli");
+    assert_eq!(command.args, ["--arg", "%1"]);
+
+    let command = custom_sign_command_settings(CustomSignCommandConfig::CommandWithArgs(vec![
+      "C:\\Program Files\\sign.exe".into(),
+      "--description=My App".into(),
+      "%1".into(),
+    ]))
+    .unwrap();
+    assert_eq!(command.cmd, "C:\\Program Files\\sign.exe");
+    assert_eq!(command.args, ["--description=My App", "%1"]);
+
+    assert!(custom_sign_command_settings(CustomSignCommandConfig::Command(" ".into())).is_err());
+    assert!(
+      custom_sign_command_settings(CustomSignCommandConfig::CommandWithArgs(vec![])).is_err()
+    );
+  }
+
   #[test]
   fn parse_profile_from_opts() {
     let options = Options {
@@ -1651,4 +2000,106 @@ mod tests {
       );
     }
   }
+
+  #[test]
+  fn profile_inheritance() {
+    let profiles: HashMap<String, CargoProfile> = toml::from_str(
+      r#"
+release = { lto = true }
+release-lto = { inherits = "release" }
+dist = { inherits = "release-lto" }
+dev-opt = { inherits = "dev" }
+no-inherits = {}
+cycle-a = { inherits = "cycle-b" }
+cycle-b = { inherits = "cycle-a" }
+"#,
+    )
+    .unwrap();
+
+    for (profile, debug) in [
+      ("dev", true),
+      ("release", false),
+      ("release-lto", false),
+      ("dist", false),
+      ("dev-opt", true),
+    ] {
+      assert_eq!(
+        profile_is_debug(&profiles, profile).unwrap(),
+        debug,
+        "{profile}"
+      );
+    }
+
+    let error = profile_is_debug(&profiles, "missing").unwrap_err();
+    assert!(error.to_string().contains("`[profile.missing]`"));
+    assert!(profile_is_debug(&profiles, "no-inherits").is_err());
+    assert!(profile_is_debug(&profiles, "cycle-a").is_err());
+  }
+
+  #[test]
+  fn dedup_watch_folders_by_parent() {
+    let folders = ["/ws/app", "/ws/shared", "/ws/app/src", "/ws/shared", "/ws"]
+      .map(PathBuf::from)
+      .to_vec();
+    assert_eq!(dedup_watch_folders(folders), vec![PathBuf::from("/ws")]);
+
+    let folders = ["/ws/app", "/ws/config", "/ws/app/src"]
+      .map(PathBuf::from)
+      .to_vec();
+    assert_eq!(
+      dedup_watch_folders(folders),
+      vec![PathBuf::from("/ws/app"), PathBuf::from("/ws/config")]
+    );
+  }
+
+  #[test]
+  fn rebase_ignore_patterns() {
+    let config_dir = Path::new("app/src-tauri");
+    assert_eq!(
+      rebase_ignore_pattern("*.sqlite", config_dir).as_deref(),
+      Some("*.sqlite")
+    );
+    assert_eq!(
+      rebase_ignore_pattern("gen/", config_dir).as_deref(),
+      Some("gen/")
+    );
+    assert_eq!(
+      rebase_ignore_pattern("db/dev.sqlite", config_dir).as_deref(),
+      Some("/app/src-tauri/db/dev.sqlite")
+    );
+    assert_eq!(
+      rebase_ignore_pattern("!../../shared/generated/", config_dir).as_deref(),
+      Some("!/shared/generated/")
+    );
+    assert_eq!(rebase_ignore_pattern("../../../other/*", config_dir), None);
+  }
+
+  #[test]
+  fn watch_ignore_patterns() {
+    let root = Path::new("/ws");
+    let matcher = IgnoreMatcher(vec![watch_ignore_matcher(
+      root,
+      Path::new("/ws/app/src-tauri"),
+      &[
+        "*.sqlite".into(),
+        "../../shared/generated/".into(),
+        "capabilities/*.json".into(),
+        "!capabilities/default.json".into(),
+      ],
+    )]);
+
+    assert!(matcher.is_ignore(Path::new("/ws/app/src-tauri/dev.sqlite"), false));
+    assert!(matcher.is_ignore(Path::new("/ws/shared/data.sqlite"), false));
+    assert!(matcher.is_ignore(Path::new("/ws/shared/generated/mod.rs"), false));
+    assert!(matcher.is_ignore(
+      Path::new("/ws/app/src-tauri/capabilities/extra.json"),
+      false
+    ));
+    assert!(!matcher.is_ignore(
+      Path::new("/ws/app/src-tauri/capabilities/default.json"),
+      false
+    ));
+    assert!(!matcher.is_ignore(Path::new("/ws/shared/src/lib.rs"), false));
+    assert!(!matcher.is_ignore(Path::new("/ws/app/src-tauri/src/main.rs"), false));
+  }
 }
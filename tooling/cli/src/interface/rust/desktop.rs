@@ -3,12 +3,12 @@
 // SPDX-License-Identifier: MIT
 
 use super::{AppSettings, DevProcess, ExitReason, Options, RustAppSettings, RustupTarget};
-use crate::CommandExt;
+use crate::{helpers::progress, CommandExt};
 
 use anyhow::Context;
 use shared_child::SharedChild;
 use std::{
-  io::{BufReader, ErrorKind, Write},
+  io::{BufRead, BufReader, ErrorKind, Write},
   process::{Command, ExitStatus, Stdio},
   sync::{
     atomic::{AtomicBool, Ordering},
@@ -262,7 +262,12 @@ fn build_production_app(
 ) -> crate::Result<()> {
   let mut build_cmd = build_command(options, available_targets, config_features)?;
   let runner = build_cmd.get_program().to_string_lossy().into_owned();
-  match build_cmd.piped() {
+  let status = if progress::is_json() {
+    piped_json(&mut build_cmd)
+  } else {
+    build_cmd.piped()
+  };
+  match status {
     Ok(status) if status.success() => Ok(()),
     Ok(_) => Err(anyhow::anyhow!("failed to build app")),
     Err(e) if e.kind() == ErrorKind::NotFound => Err(anyhow::anyhow!(
@@ -278,6 +283,26 @@ fn build_production_app(
   }
 }
 
+/// Runs the build with `--message-format json-render-diagnostics`,
+/// forwarding the JSON messages of cargo as progress events while the diagnostics are rendered to stderr.
+fn piped_json(build_cmd: &mut Command) -> std::io::Result<ExitStatus> {
+  build_cmd.args(["--message-format", "json-render-diagnostics"]);
+  build_cmd.stdout(Stdio::piped());
+  build_cmd.stderr(os_pipe::dup_stderr()?);
+
+  let mut child = build_cmd.spawn()?;
+  let stdout = BufReader::new(child.stdout.take().unwrap());
+  for line in stdout.lines() {
+    let line = line?;
+    match serde_json::from_str(&line) {
+      Ok(message) => progress::emit(progress::Event::CargoMessage { message }),
+      // not a cargo message, e.g. printed by a build script or a custom runner
+      Err(_) => eprintln!("{line}"),
+    }
+  }
+  child.wait()
+}
+
 fn build_command(
   options: Options,
   available_targets: &mut Option<Vec<RustupTarget>>,
@@ -306,7 +331,7 @@ fn build_command(
     args.push(features.join(","));
   }
 
-  if !options.debug && !args.contains(&"--profile".to_string()) {
+  if !options.debug && super::get_cargo_option(&args, "--profile").is_none() {
     args.push("--release".into());
   }
 
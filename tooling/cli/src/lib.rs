@@ -140,8 +140,7 @@ enum Commands {
   Android(mobile::android::Cli),
   #[cfg(target_os = "macos")]
   Ios(mobile::ios::Cli),
-  /// Migrate from v1 to v2
-  Migrate,
+  Migrate(migrate::Options),
   Info(info::Options),
   Add(add::Options),
   Plugin(plugin::Cli),
@@ -188,6 +187,7 @@ where
       }
     });
     log::error!("{message}");
+    helpers::progress::error(&message, &e);
     exit(1);
   }
 }
@@ -266,7 +266,7 @@ where
     Commands::Android(c) => mobile::android::command(c, cli.verbose)?,
     #[cfg(target_os = "macos")]
     Commands::Ios(c) => mobile::ios::command(c, cli.verbose)?,
-    Commands::Migrate => migrate::command()?,
+    Commands::Migrate(options) => migrate::command(options)?,
   }
 
   Ok(())
@@ -301,7 +301,12 @@ pub trait CommandExt {
 
 impl CommandExt for Command {
   fn piped(&mut self) -> std::io::Result<ExitStatus> {
-    self.stdout(os_pipe::dup_stdout()?);
+    // stdout is reserved to the JSON progress events
+    if helpers::progress::is_json() {
+      self.stdout(os_pipe::dup_stderr()?);
+    } else {
+      self.stdout(os_pipe::dup_stdout()?);
+    }
     self.stderr(os_pipe::dup_stderr()?);
     let program = self.get_program().to_string_lossy().into_owned();
     log::debug!(action = "Running"; "Command `{} {}`", program, self.get_args().map(|arg| arg.to_string_lossy()).fold(String::new(), |acc, arg| format!("{acc} {arg}")));
@@ -367,7 +372,29 @@ impl CommandExt for Command {
     if output.status.success() {
       Ok(output)
     } else {
-      Err(anyhow::anyhow!("failed to run {}", program))
+      Err(
+        CommandError {
+          program,
+          stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+        .into(),
+      )
     }
   }
 }
+
+/// The error returned by [`CommandExt::output_ok`] when the command fails.
+#[derive(Debug)]
+pub(crate) struct CommandError {
+  pub(crate) program: String,
+  /// The captured stderr, reported by the JSON progress events.
+  pub(crate) stderr: String,
+}
+
+impl Display for CommandError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "failed to run {}", self.program)
+  }
+}
+
+impl std::error::Error for CommandError {}
@@ -0,0 +1,158 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::Result;
+
+use super::Step;
+
+use anyhow::Context;
+use similar::TextDiff;
+
+use std::{
+  collections::BTreeMap,
+  fmt::Display,
+  fs,
+  path::{Path, PathBuf},
+};
+
+/// Applies the changes of the migration steps, or records them as a diff on dry runs.
+pub struct Migration {
+  dry_run: bool,
+  only: Option<Step>,
+  root: PathBuf,
+  // keyed by the displayed path so the diff output is sorted
+  diffs: BTreeMap<String, String>,
+}
+
+impl Migration {
+  /// Creates a migration of the project at `root`, running all steps unless `only` is set.
+  pub fn new(dry_run: bool, only: Option<Step>, root: impl Into<PathBuf>) -> Self {
+    Self {
+      dry_run,
+      only,
+      root: root.into(),
+      diffs: Default::default(),
+    }
+  }
+
+  /// Whether the given step is part of this migration.
+  pub fn runs(&self, step: Step) -> bool {
+    self.only.map_or(true, |only| only == step)
+  }
+
+  /// Writes the file of a migration step if its contents changed.
+  pub fn write(&mut self, step: Step, path: &Path, contents: impl AsRef<str>) -> Result<()> {
+    if !self.runs(step) {
+      return Ok(());
+    }
+
+    let contents = contents.as_ref();
+    let current = if path.exists() {
+      Some(fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?)
+    } else {
+      None
+    };
+    if current.as_deref() == Some(contents) {
+      return Ok(());
+    }
+
+    if self.dry_run {
+      let display_path = self.display_path(path);
+      let old_header = if current.is_some() {
+        format!("a/{display_path}")
+      } else {
+        "/dev/null".into()
+      };
+      let diff = TextDiff::from_lines(current.as_deref().unwrap_or_default(), contents)
+        .unified_diff()
+        .header(&old_header, &format!("b/{display_path}"))
+        .to_string();
+      self.diffs.insert(display_path, diff);
+    } else {
+      if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    Ok(())
+  }
+
+  /// Whether an action of a migration step, such as installing packages, must be run.
+  ///
+  /// On dry runs the action is only logged.
+  pub fn should_run(&self, step: Step, action: impl Display) -> bool {
+    if !self.runs(step) {
+      return false;
+    }
+    if self.dry_run {
+      log::info!(action = "Skipping"; "{action} (dry run)");
+      return false;
+    }
+    true
+  }
+
+  /// The unified diff of every file changed by the migration, sorted by path.
+  pub fn diff(&self) -> String {
+    self.diffs.values().map(String::as_str).collect()
+  }
+
+  fn display_path(&self, path: &Path) -> String {
+    let path = path.strip_prefix(&self.root).unwrap_or(path);
+    path
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Migration, Step};
+
+  #[test]
+  fn dry_run_records_sorted_diffs() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("b.txt"), "one\ntwo\n").unwrap();
+
+    let mut migration = Migration::new(true, None, dir.path());
+    migration
+      .write(Step::Config, &dir.path().join("b.txt"), "one\nthree\n")
+      .unwrap();
+    migration
+      .write(Step::Config, &dir.path().join("nested/a.txt"), "new\n")
+      .unwrap();
+    migration
+      .write(Step::Config, &dir.path().join("b.txt"), "one\nthree\n")
+      .unwrap();
+
+    assert_eq!(
+      migration.diff(),
+      "--- a/b.txt\n+++ b/b.txt\n@@ -1,2 +1,2 @@\n one\n-two\n+three\n--- /dev/null\n+++ b/nested/a.txt\n@@ -0,0 +1 @@\n+new\n"
+    );
+    assert_eq!(
+      std::fs::read_to_string(dir.path().join("b.txt")).unwrap(),
+      "one\ntwo\n"
+    );
+    assert!(!dir.path().join("nested").exists());
+  }
+
+  #[test]
+  fn only_runs_the_selected_step() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("file.txt");
+
+    let mut migration = Migration::new(false, Some(Step::Frontend), dir.path());
+    assert!(!migration.should_run(Step::Deps, "install packages"));
+    migration.write(Step::Config, &path, "config").unwrap();
+    assert!(!path.exists());
+    migration.write(Step::Frontend, &path, "frontend").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "frontend");
+    assert!(migration.diff().is_empty());
+
+    let migration = Migration::new(true, None, dir.path());
+    assert!(!migration.should_run(Step::Deps, "install packages"));
+  }
+}
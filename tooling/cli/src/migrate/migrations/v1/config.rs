@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::Result;
+use crate::{
+  migrate::{Migration, Step},
+  Result,
+};
 
 use serde_json::{Map, Value};
 use tauri_utils::acl::{
@@ -12,39 +15,46 @@ use tauri_utils::acl::{
 
 use std::{
   collections::{BTreeMap, HashSet},
-  fs,
   path::Path,
 };
 
-pub fn migrate(tauri_dir: &Path) -> Result<MigratedConfig> {
+pub fn migrate(migration: &mut Migration, tauri_dir: &Path) -> Result<MigratedConfig> {
   if let Ok((mut config, config_path)) =
     tauri_utils_v1::config::parse::parse_value(tauri_dir.join("tauri.conf.json"))
   {
+    // the capabilities are generated from the v1 allowlist, skip them if the config is already migrated
+    let is_v1 = config.get("tauri").is_some();
     let migrated = migrate_config(&mut config)?;
-    fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
-
-    let mut permissions: Vec<PermissionEntry> = vec!["core:default"]
-      .into_iter()
-      .map(|p| PermissionEntry::PermissionRef(p.to_string().try_into().unwrap()))
-      .collect();
-    permissions.extend(migrated.permissions.clone());
-
-    let capabilities_path = config_path.parent().unwrap().join("capabilities");
-    fs::create_dir_all(&capabilities_path)?;
-    fs::write(
-      capabilities_path.join("migrated.json"),
-      serde_json::to_string_pretty(&Capability {
-        identifier: "migrated".to_string(),
-        description: "permissions that were migrated from v1".into(),
-        local: true,
-        remote: None,
-        windows: vec!["main".into()],
-        webviews: vec![],
-        permissions,
-        platforms: None,
-      })?,
+    migration.write(
+      Step::Config,
+      &config_path,
+      serde_json::to_string_pretty(&config)?,
     )?;
 
+    if is_v1 {
+      let mut permissions: Vec<PermissionEntry> = vec!["core:default"]
+        .into_iter()
+        .map(|p| PermissionEntry::PermissionRef(p.to_string().try_into().unwrap()))
+        .collect();
+      permissions.extend(migrated.permissions.clone());
+
+      let capabilities_path = config_path.parent().unwrap().join("capabilities");
+      migration.write(
+        Step::Capabilities,
+        &capabilities_path.join("migrated.json"),
+        serde_json::to_string_pretty(&Capability {
+          identifier: "migrated".to_string(),
+          description: "permissions that were migrated from v1".into(),
+          local: true,
+          remote: None,
+          windows: vec!["main".into()],
+          webviews: vec![],
+          permissions,
+          platforms: None,
+        })?,
+      )?;
+    }
+
     return Ok(migrated);
   }
 
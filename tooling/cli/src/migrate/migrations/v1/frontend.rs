@@ -4,6 +4,7 @@
 
 use crate::{
   helpers::{app_paths::walk_builder, cargo, npm::PackageManager},
+  migrate::{Migration, Step},
   Result,
 };
 use anyhow::Context;
@@ -14,7 +15,7 @@ use oxc_ast::ast::*;
 use oxc_parser::Parser;
 use oxc_span::SourceType;
 
-use std::{fs, path::Path};
+use std::path::Path;
 
 const RENAMED_MODULES: phf::Map<&str, &str> = phf::phf_map! {
   "tauri" => "core",
@@ -54,7 +55,7 @@ const MODULES_MAP: phf::Map<&str, &str> = phf::phf_map! {
 const JS_EXTENSIONS: &[&str] = &["js", "mjs", "jsx", "ts", "mts", "tsx"];
 
 /// Returns a list of paths that could not be migrated
-pub fn migrate(app_dir: &Path, tauri_dir: &Path) -> Result<()> {
+pub fn migrate(migration: &mut Migration, app_dir: &Path, tauri_dir: &Path) -> Result<()> {
   let mut new_npm_packages = Vec::new();
   let mut new_cargo_packages = Vec::new();
 
@@ -76,46 +77,66 @@ pub fn migrate(app_dir: &Path, tauri_dir: &Path) -> Result<()> {
     .next()
     .unwrap_or(PackageManager::Npm);
 
-  for pkg in ["@tauri-apps/cli", "@tauri-apps/api"] {
-    let version = pm
-      .current_package_version(pkg, app_dir)
-      .unwrap_or_default()
-      .unwrap_or_default();
-    if version.starts_with('1') {
-      new_npm_packages.push(format!("{pkg}@^{npm_version}"));
+  if migration.runs(Step::Deps) {
+    for pkg in ["@tauri-apps/cli", "@tauri-apps/api"] {
+      let version = pm
+        .current_package_version(pkg, app_dir)
+        .unwrap_or_default()
+        .unwrap_or_default();
+      if version.starts_with('1') {
+        new_npm_packages.push(format!("{pkg}@^{npm_version}"));
+      }
     }
   }
 
-  for entry in walk_builder(app_dir).build().flatten() {
-    if entry.file_type().map(|t| t.is_file()).unwrap_or_default() {
-      let path = entry.path();
+  let mut js_paths = walk_builder(app_dir)
+    .build()
+    .flatten()
+    .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or_default())
+    .map(|entry| entry.into_path())
+    .filter(|path| {
       let ext = path.extension().unwrap_or_default();
-      if JS_EXTENSIONS.iter().any(|e| e == &ext) {
-        let js_contents = std::fs::read_to_string(path)?;
-        let new_contents = migrate_imports(
-          path,
-          &js_contents,
-          &mut new_cargo_packages,
-          &mut new_npm_packages,
-        )?;
-        if new_contents != js_contents {
-          fs::write(path, new_contents)
-            .with_context(|| format!("Error writing {}", path.display()))?;
-        }
-      }
-    }
+      JS_EXTENSIONS.iter().any(|e| e == &ext)
+    })
+    .collect::<Vec<_>>();
+  js_paths.sort();
+
+  for path in js_paths {
+    let js_contents = std::fs::read_to_string(&path)?;
+    let new_contents = migrate_imports(
+      &path,
+      &js_contents,
+      &mut new_cargo_packages,
+      &mut new_npm_packages,
+    )?;
+    migration
+      .write(Step::Frontend, &path, new_contents)
+      .with_context(|| format!("Error writing {}", path.display()))?;
   }
 
   new_npm_packages.sort();
   new_npm_packages.dedup();
-  if !new_npm_packages.is_empty() {
+  if !new_npm_packages.is_empty()
+    && migration.should_run(
+      Step::Deps,
+      format_args!("installing npm packages {}", new_npm_packages.join(", ")),
+    )
+  {
     pm.install(&new_npm_packages, app_dir)
       .context("Error installing new npm packages")?;
   }
 
   new_cargo_packages.sort();
   new_cargo_packages.dedup();
-  if !new_cargo_packages.is_empty() {
+  if !new_cargo_packages.is_empty()
+    && migration.should_run(
+      Step::Deps,
+      format_args!(
+        "installing Cargo packages {}",
+        new_cargo_packages.join(", ")
+      ),
+    )
+  {
     cargo::install(&new_cargo_packages, Some(tauri_dir))
       .context("Error installing new Cargo packages")?;
   }
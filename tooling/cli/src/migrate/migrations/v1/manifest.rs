@@ -4,6 +4,7 @@
 
 use crate::{
   interface::rust::manifest::{read_manifest, serialize_manifest},
+  migrate::{Migration, Step},
   Result,
 };
 
@@ -16,12 +17,13 @@ use std::path::Path;
 
 const CRATE_TYPES: [&str; 3] = ["lib", "staticlib", "cdylib"];
 
-pub fn migrate(tauri_dir: &Path) -> Result<()> {
+pub fn migrate(migration: &mut Migration, tauri_dir: &Path) -> Result<()> {
   let manifest_path = tauri_dir.join("Cargo.toml");
   let (mut manifest, _) = read_manifest(&manifest_path)?;
   migrate_manifest(&mut manifest)?;
 
-  std::fs::write(&manifest_path, serialize_manifest(&manifest))
+  migration
+    .write(Step::Deps, &manifest_path, serialize_manifest(&manifest))
     .context("failed to rewrite Cargo manifest")?;
 
   Ok(())
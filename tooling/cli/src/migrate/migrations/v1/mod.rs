@@ -4,25 +4,29 @@
 
 use crate::{
   helpers::app_paths::{app_dir, tauri_dir},
+  migrate::{Migration, Step},
   Result,
 };
 
 use anyhow::Context;
 
+use std::path::Path;
+
 mod config;
 mod frontend;
 mod manifest;
 
-pub fn run() -> Result<()> {
+pub fn run(migration: &mut Migration) -> Result<()> {
   let tauri_dir = tauri_dir();
   let app_dir = app_dir();
 
-  let migrated = config::migrate(&tauri_dir).context("Could not migrate config")?;
-  manifest::migrate(&tauri_dir).context("Could not migrate manifest")?;
-  frontend::migrate(app_dir, &tauri_dir)?;
+  let plugins = migrate(migration, app_dir, &tauri_dir)?;
 
   // Add plugins
-  for plugin in migrated.plugins {
+  for plugin in plugins {
+    if !migration.should_run(Step::Deps, format_args!("adding the `{plugin}` plugin")) {
+      continue;
+    }
     crate::add::command(crate::add::Options {
       plugin: plugin.clone(),
       branch: None,
@@ -34,3 +38,172 @@ pub fn run() -> Result<()> {
 
   Ok(())
 }
+
+/// Migrates the project files, returning the plugins that must be added to the project.
+fn migrate(migration: &mut Migration, app_dir: &Path, tauri_dir: &Path) -> Result<Vec<String>> {
+  let migrated = config::migrate(migration, tauri_dir).context("Could not migrate config")?;
+  manifest::migrate(migration, tauri_dir).context("Could not migrate manifest")?;
+  frontend::migrate(migration, app_dir, tauri_dir)?;
+
+  let mut plugins = migrated.plugins.into_iter().collect::<Vec<_>>();
+  plugins.sort();
+  Ok(plugins)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::migrate::{Migration, Step};
+
+  use std::{fs, path::Path};
+
+  const CONFIG: &str = r#"{
+  "package": {
+    "productName": "app",
+    "version": "0.1.0"
+  },
+  "tauri": {
+    "allowlist": {
+      "fs": {
+        "readFile": true
+      }
+    },
+    "bundle": {
+      "identifier": "com.tauri.test"
+    }
+  }
+}"#;
+
+  const MANIFEST: &str = r#"[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[build-dependencies]
+tauri-build = { version = "1", features = [] }
+
+[dependencies]
+tauri = { version = "1", features = ["fs-read-file"] }
+"#;
+
+  const MAIN_JS: &str = r#"import { invoke } from "@tauri-apps/api/tauri";
+
+invoke("greet");
+"#;
+
+  fn fixture() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let tauri_dir = dir.path().join("src-tauri");
+    fs::create_dir_all(&tauri_dir).unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    fs::write(tauri_dir.join("tauri.conf.json"), CONFIG).unwrap();
+    fs::write(tauri_dir.join("Cargo.toml"), MANIFEST).unwrap();
+    fs::write(dir.path().join("src/main.js"), MAIN_JS).unwrap();
+    dir
+  }
+
+  fn migrate(app_dir: &Path, dry_run: bool, only: Option<Step>) -> String {
+    let mut migration = Migration::new(dry_run, only, app_dir);
+    super::migrate(&mut migration, app_dir, &app_dir.join("src-tauri")).unwrap();
+    migration.diff()
+  }
+
+  fn changed_files(diff: &str) -> Vec<&str> {
+    diff
+      .lines()
+      .filter_map(|line| line.strip_prefix("+++ b/"))
+      .collect()
+  }
+
+  fn assert_step(step: Step, files: &[&str], expected: &[&str]) {
+    let project = fixture();
+
+    let diff = migrate(project.path(), true, Some(step));
+    assert_eq!(changed_files(&diff), files);
+    for expected in expected {
+      assert!(
+        diff.contains(expected),
+        "`{expected}` not found in:\n{diff}"
+      );
+    }
+    // dry runs do not write anything and always print the same diff
+    assert_eq!(migrate(project.path(), true, Some(step)), diff);
+
+    assert!(migrate(project.path(), false, Some(step)).is_empty());
+    // running the step again does not change anything
+    assert!(migrate(project.path(), true, Some(step)).is_empty());
+  }
+
+  #[test]
+  fn config_step() {
+    assert_step(
+      Step::Config,
+      &["src-tauri/tauri.conf.json"],
+      &[
+        "--- a/src-tauri/tauri.conf.json\n",
+        "-  \"package\": {\n",
+        "+  \"productName\": \"app\"",
+        "+  \"identifier\": \"com.tauri.test\"",
+      ],
+    );
+  }
+
+  #[test]
+  fn deps_step() {
+    assert_step(
+      Step::Deps,
+      &["src-tauri/Cargo.toml"],
+      &[
+        "--- a/src-tauri/Cargo.toml\n",
+        "-tauri = { version = \"1\", features = [\"fs-read-file\"] }\n",
+        "-tauri-build = { version = \"1\", features = [] }\n",
+        "+tauri = { version = \"2",
+      ],
+    );
+  }
+
+  #[test]
+  fn frontend_step() {
+    assert_step(
+      Step::Frontend,
+      &["src/main.js"],
+      &["--- a/src/main.js\n+++ b/src/main.js\n@@ -1,3 +1,3 @@\n-import { invoke } from \"@tauri-apps/api/tauri\";\n+import { invoke } from \"@tauri-apps/api/core\";\n \n invoke(\"greet\");\n"],
+    );
+  }
+
+  #[test]
+  fn capabilities_step() {
+    assert_step(
+      Step::Capabilities,
+      &["src-tauri/capabilities/migrated.json"],
+      &[
+        "--- /dev/null\n+++ b/src-tauri/capabilities/migrated.json\n",
+        "+  \"identifier\": \"migrated\",\n",
+        "\"core:default\"",
+        "\"fs:allow-read-file\"",
+      ],
+    );
+  }
+
+  #[test]
+  fn full_migration_is_idempotent() {
+    let project = fixture();
+
+    let diff = migrate(project.path(), true, None);
+    assert_eq!(
+      changed_files(&diff),
+      [
+        "src-tauri/Cargo.toml",
+        "src-tauri/capabilities/migrated.json",
+        "src-tauri/tauri.conf.json",
+        "src/main.js",
+      ]
+    );
+
+    migrate(project.path(), false, None);
+    assert!(migrate(project.path(), true, None).is_empty());
+    assert!(project
+      .path()
+      .join("src-tauri/capabilities/migrated.json")
+      .exists());
+  }
+}
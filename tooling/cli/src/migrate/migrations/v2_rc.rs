@@ -8,6 +8,7 @@ use crate::{
     npm::PackageManager,
   },
   interface::rust::manifest::{read_manifest, serialize_manifest},
+  migrate::{Migration, Step},
   Result,
 };
 
@@ -16,7 +17,7 @@ use std::{fs::read_to_string, path::Path};
 use anyhow::Context;
 use toml_edit::{Document, Item, Table, TableLike, Value};
 
-pub fn run() -> Result<()> {
+pub fn run(migration: &mut Migration) -> Result<()> {
   let app_dir = app_dir();
   let tauri_dir = tauri_dir();
 
@@ -24,17 +25,20 @@ pub fn run() -> Result<()> {
   let (mut manifest, _) = read_manifest(&manifest_path)?;
   migrate_manifest(&mut manifest)?;
 
-  migrate_permissions(&tauri_dir)?;
+  migrate_permissions(migration, &tauri_dir)?;
 
-  migrate_npm_dependencies(app_dir)?;
+  if migration.runs(Step::Deps) {
+    migrate_npm_dependencies(migration, app_dir)?;
+  }
 
-  std::fs::write(&manifest_path, serialize_manifest(&manifest))
+  migration
+    .write(Step::Deps, &manifest_path, serialize_manifest(&manifest))
     .context("failed to rewrite Cargo manifest")?;
 
   Ok(())
 }
 
-fn migrate_npm_dependencies(app_dir: &Path) -> Result<()> {
+fn migrate_npm_dependencies(migration: &Migration, app_dir: &Path) -> Result<()> {
   let pm = PackageManager::from_project(app_dir)
     .into_iter()
     .next()
@@ -79,14 +83,19 @@ fn migrate_npm_dependencies(app_dir: &Path) -> Result<()> {
     }
   }
 
-  if !install_deps.is_empty() {
+  if !install_deps.is_empty()
+    && migration.should_run(
+      Step::Deps,
+      format_args!("installing npm packages {}", install_deps.join(", ")),
+    )
+  {
     pm.install(&install_deps, app_dir)?;
   }
 
   Ok(())
 }
 
-fn migrate_permissions(tauri_dir: &Path) -> Result<()> {
+fn migrate_permissions(migration: &mut Migration, tauri_dir: &Path) -> Result<()> {
   let core_plugins = [
     "app",
     "event",
@@ -107,7 +116,9 @@ fn migrate_permissions(tauri_dir: &Path) -> Result<()> {
       for plugin in core_plugins {
         capability = capability.replace(&format!("\"{plugin}:"), &format!("\"core:{plugin}:"));
       }
-      std::fs::write(path, capability).context("failed to rewrite capability")?;
+      migration
+        .write(Step::Capabilities, path, capability)
+        .context("failed to rewrite capability")?;
     }
   }
   Ok(())
@@ -194,3 +205,33 @@ fn migrate_dependency(dependencies: &mut Table, name: &str, version: &str) {
 fn migrate_dependency_table<D: TableLike>(dep: &mut D, version: &str) {
   *dep.entry("version").or_insert(Item::None) = Item::Value(version.into());
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::migrate::{Migration, Step};
+
+  #[test]
+  fn migrate_permissions_is_idempotent() {
+    let dir = tempfile::tempdir().unwrap();
+    let capabilities_dir = dir.path().join("capabilities");
+    std::fs::create_dir_all(&capabilities_dir).unwrap();
+    std::fs::write(
+      capabilities_dir.join("default.json"),
+      "{ \"permissions\": [\"window:default\", \"fs:default\"] }\n",
+    )
+    .unwrap();
+
+    let mut migration = Migration::new(true, Some(Step::Capabilities), dir.path());
+    super::migrate_permissions(&mut migration, dir.path()).unwrap();
+    assert_eq!(
+      migration.diff(),
+      "--- a/capabilities/default.json\n+++ b/capabilities/default.json\n@@ -1 +1 @@\n-{ \"permissions\": [\"window:default\", \"fs:default\"] }\n+{ \"permissions\": [\"core:window:default\", \"fs:default\"] }\n"
+    );
+
+    let mut migration = Migration::new(false, Some(Step::Capabilities), dir.path());
+    super::migrate_permissions(&mut migration, dir.path()).unwrap();
+    let mut migration = Migration::new(true, Some(Step::Capabilities), dir.path());
+    super::migrate_permissions(&mut migration, dir.path()).unwrap();
+    assert!(migration.diff().is_empty());
+  }
+}
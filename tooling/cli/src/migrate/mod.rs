@@ -4,7 +4,7 @@
 
 use crate::{
   helpers::{
-    app_paths::tauri_dir,
+    app_paths::{app_dir, tauri_dir},
     cargo_manifest::{crate_version, CargoLock, CargoManifest},
   },
   interface::rust::get_workspace_dir,
@@ -14,11 +14,48 @@ use crate::{
 use std::{fs::read_to_string, str::FromStr};
 
 use anyhow::Context;
+use clap::{Parser, ValueEnum};
 
+mod migration;
 mod migrations;
 
-pub fn command() -> Result<()> {
+pub use migration::Migration;
+
+#[derive(Debug, Parser)]
+#[clap(about = "Migrate from v1 to v2")]
+pub struct Options {
+  /// Print a diff of every file the migration would change without writing anything.
+  ///
+  /// Package installs and plugin additions are listed instead of being run.
+  #[clap(long)]
+  pub dry_run: bool,
+  /// Only run the given migration step.
+  #[clap(long, value_enum)]
+  pub only: Option<Step>,
+}
+
+/// A step of the migration.
+///
+/// Every step can be run again on an already migrated project without changing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Step {
+  /// Migrate the Tauri configuration file.
+  Config,
+  /// Migrate the Cargo manifest, install the new npm and Cargo packages and add the plugins replacing the v1 APIs.
+  ///
+  /// The plugins are read from the v1 allowlist, so this must run before or along with the `config` step.
+  Deps,
+  /// Migrate the `@tauri-apps/api` imports of the frontend source files.
+  Frontend,
+  /// Migrate the capabilities, generating them from the v1 allowlist.
+  ///
+  /// This must run before or along with the `config` step.
+  Capabilities,
+}
+
+pub fn command(options: Options) -> Result<()> {
   let tauri_dir = tauri_dir();
+  let mut migration = Migration::new(options.dry_run, options.only, app_dir());
 
   let manifest_contents =
     read_to_string(tauri_dir.join("Cargo.toml")).context("failed to read Cargo manifest")?;
@@ -40,14 +77,18 @@ pub fn command() -> Result<()> {
   let tauri_version = semver::Version::from_str(&tauri_version)?;
 
   if tauri_version.major == 1 {
-    migrations::v1::run().context("failed to migrate from v1")?;
+    migrations::v1::run(&mut migration).context("failed to migrate from v1")?;
   } else if tauri_version.major == 2 {
     if let Some((pre, _number)) = tauri_version.pre.as_str().split_once('.') {
       if pre == "beta" {
-        migrations::v2_rc::run().context("failed to migrate from v2 beta to rc")?;
+        migrations::v2_rc::run(&mut migration).context("failed to migrate from v2 beta to rc")?;
       }
     }
   }
 
+  if options.dry_run {
+    print!("{}", migration.diff());
+  }
+
   Ok(())
 }
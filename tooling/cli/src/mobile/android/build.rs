@@ -19,14 +19,22 @@ use crate::{
 };
 use clap::{ArgAction, Parser};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use cargo_mobile2::{
   android::{aab, apk, config::Config as AndroidConfig, env::Env, target::Target},
   opts::{NoiseLevel, Profile},
   target::TargetTrait,
 };
 
-use std::env::set_current_dir;
+use tauri_utils::config::AndroidSigningConfig;
+
+use std::{env::set_current_dir, ffi::OsString, path::Path};
+
+/// The Gradle project properties read by the `signingConfigs` block of the app `build.gradle.kts`.
+const STORE_FILE_PROPERTY: &str = "tauriSigningStoreFile";
+const STORE_PASSWORD_PROPERTY: &str = "tauriSigningStorePassword";
+const KEY_ALIAS_PROPERTY: &str = "tauriSigningKeyAlias";
+const KEY_PASSWORD_PROPERTY: &str = "tauriSigningKeyPassword";
 
 #[derive(Debug, Clone, Parser)]
 #[clap(
@@ -74,10 +82,17 @@ impl From<Options> for BuildOptions {
     Self {
       runner: None,
       debug: options.debug,
+      profile: None,
+      targets: Vec::new(),
       target: None,
+      fail_fast: false,
       features: options.features,
       bundles: None,
       no_bundle: false,
+      bundle_jobs: None,
+      offline: false,
+      print_artifacts: false,
+      format: Default::default(),
       config: options.config,
       args: Vec::new(),
       ci: options.ci,
@@ -129,7 +144,7 @@ pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
   };
 
   let tauri_path = tauri_dir();
-  set_current_dir(tauri_path).with_context(|| "failed to change current working directory")?;
+  set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
 
   ensure_init(
     &tauri_config,
@@ -141,6 +156,18 @@ pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
   let mut env = env()?;
   configure_cargo(&app, Some((&mut env, &config)))?;
 
+  if !options.debug {
+    let tauri_config_guard = tauri_config.lock().unwrap();
+    if let Some(signing) = &tauri_config_guard.as_ref().unwrap().bundle.android.signing {
+      // the properties are passed as environment variables so the passwords are never written to the project
+      for (property, value) in signing_properties(signing, &tauri_path)? {
+        env
+          .base
+          .insert_env_var(format!("ORG_GRADLE_PROJECT_{property}"), value);
+      }
+    }
+  }
+
   crate::build::setup(&interface, &mut build_options, tauri_config.clone(), true)?;
 
   // run an initial build to initialize plugins
@@ -239,6 +266,42 @@ fn run_build(
   Ok(handle)
 }
 
+/// Resolves the keystore and reads its passwords from the environment.
+fn signing_properties(
+  signing: &AndroidSigningConfig,
+  tauri_dir: &Path,
+) -> Result<Vec<(&'static str, OsString)>> {
+  let keystore_path = tauri_dir.join(&signing.keystore_path);
+  if !keystore_path.is_file() {
+    bail!(
+      "the Android keystore `{}` configured in `bundle > android > signing > keystorePath` does not exist",
+      keystore_path.display()
+    );
+  }
+  let keystore_path = dunce::canonicalize(&keystore_path).unwrap_or(keystore_path);
+
+  let password = |var: &str, field: &str| {
+    std::env::var_os(var).filter(|v| !v.is_empty()).with_context(|| {
+      format!(
+        "the `{var}` environment variable must be set to the Android {field} password to sign the release build"
+      )
+    })
+  };
+
+  Ok(vec![
+    (STORE_FILE_PROPERTY, keystore_path.into_os_string()),
+    (
+      STORE_PASSWORD_PROPERTY,
+      password(&signing.store_password_env, "keystore")?,
+    ),
+    (KEY_ALIAS_PROPERTY, signing.key_alias.clone().into()),
+    (
+      KEY_PASSWORD_PROPERTY,
+      password(&signing.key_password_env, "key")?,
+    ),
+  ])
+}
+
 fn get_targets_or_all<'a>(targets: Vec<String>) -> Result<Vec<&'a Target<'a>>> {
   if targets.is_empty() {
     Ok(Target::all().iter().map(|t| t.1).collect())
@@ -264,3 +327,40 @@ fn get_targets_or_all<'a>(targets: Vec<String>) -> Result<Vec<&'a Target<'a>>> {
     Ok(outs)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn signing_requires_keystore_and_passwords() {
+    let dir = tempfile::tempdir().unwrap();
+    let signing = AndroidSigningConfig {
+      keystore_path: "release.jks".into(),
+      key_alias: "upload".into(),
+      store_password_env: "TAURI_TEST_SIGNING_STORE_PASSWORD".into(),
+      key_password_env: "TAURI_TEST_SIGNING_KEY_PASSWORD".into(),
+    };
+
+    let err = signing_properties(&signing, dir.path()).unwrap_err();
+    assert!(err.to_string().contains("does not exist"), "{err}");
+
+    std::fs::write(dir.path().join("release.jks"), []).unwrap();
+    let err = signing_properties(&signing, dir.path()).unwrap_err();
+    assert!(
+      err
+        .to_string()
+        .contains("TAURI_TEST_SIGNING_STORE_PASSWORD"),
+      "{err}"
+    );
+
+    std::env::set_var("TAURI_TEST_SIGNING_STORE_PASSWORD", "store");
+    std::env::set_var("TAURI_TEST_SIGNING_KEY_PASSWORD", "key");
+    let properties = signing_properties(&signing, dir.path()).unwrap();
+    assert_eq!(properties[0].0, STORE_FILE_PROPERTY);
+    assert!(Path::new(&properties[0].1).is_absolute());
+    assert_eq!(properties[1], (STORE_PASSWORD_PROPERTY, "store".into()));
+    assert_eq!(properties[2], (KEY_ALIAS_PROPERTY, "upload".into()));
+    assert_eq!(properties[3], (KEY_PASSWORD_PROPERTY, "key".into()));
+  }
+}
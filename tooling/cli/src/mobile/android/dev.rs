@@ -81,10 +81,13 @@ impl From<Options> for DevOptions {
       config: options.config,
       args: Vec::new(),
       no_watch: options.no_watch,
+      watch: Vec::new(),
+      ignore: Vec::new(),
       no_dev_server_wait: options.no_dev_server_wait,
       no_dev_server: options.no_dev_server,
       port: options.port,
       release_mode: options.release_mode,
+      profile: None,
       host: None,
     }
   }
@@ -105,10 +105,17 @@ impl From<Options> for BuildOptions {
     Self {
       runner: None,
       debug: options.debug,
+      profile: None,
+      targets: Vec::new(),
       target: None,
+      fail_fast: false,
       features: options.features,
       bundles: None,
       no_bundle: false,
+      bundle_jobs: None,
+      offline: false,
+      print_artifacts: false,
+      format: Default::default(),
       config: options.config,
       args: Vec::new(),
       ci: options.ci,
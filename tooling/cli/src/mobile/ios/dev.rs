@@ -106,8 +106,11 @@ impl From<Options> for DevOptions {
       exit_on_panic: options.exit_on_panic,
       config: options.config,
       release_mode: options.release_mode,
+      profile: None,
       args: Vec::new(),
       no_watch: options.no_watch,
+      watch: Vec::new(),
+      ignore: Vec::new(),
       no_dev_server: options.no_dev_server,
       no_dev_server_wait: options.no_dev_server_wait,
       port: options.port,
@@ -114,6 +114,23 @@ pub fn get_config(
       .extend_from_slice(features);
   }
 
+  // the crate version, inheriting the workspace version, if the config does not set one
+  let app_version = tauri_config.version.clone().or_else(|| {
+    tauri_utils::config::version::cargo_package_version(&tauri_dir().join("Cargo.toml")).ok()
+  });
+  let (bundle_version_short, bundle_version) = match app_version {
+    Some(app_version) => {
+      let (short_version, bundle_version) =
+        tauri_utils::config::version::apple_bundle_versions(&app_version, "iOS")
+          .unwrap_or_else(|e| panic!("{e}"));
+      (
+        Some(short_version.clone()),
+        Some(bundle_version.unwrap_or(short_version)),
+      )
+    }
+    None => (None, None),
+  };
+
   let raw = RawAppleConfig {
     development_team: std::env::var(APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME)
         .ok()
@@ -133,8 +150,8 @@ pub fn get_config(
           }
         }),
     ios_features: ios_options.features.clone(),
-    bundle_version: tauri_config.version.clone(),
-    bundle_version_short: tauri_config.version.clone(),
+    bundle_version,
+    bundle_version_short,
     ios_version: Some(tauri_config.bundle.ios.minimum_system_version.clone()),
     ..Default::default()
   };
@@ -2,10 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{
-  helpers::{prompts, template},
-  Result,
-};
+use crate::{helpers::template, Result};
 use clap::{Parser, Subcommand};
 use handlebars::Handlebars;
 
@@ -59,13 +56,7 @@ pub fn command(cli: Cli) -> Result<()> {
         return Err(anyhow::anyhow!("android folder already exists"));
       }
 
-      let plugin_id = prompts::input(
-        "What should be the Android Package ID for your plugin?",
-        Some(format!("com.plugin.{}", plugin_name)),
-        false,
-        false,
-      )?
-      .unwrap();
+      let plugin_id = super::android_package_id(&plugin_name)?;
 
       let handlebars = Handlebars::new();
 
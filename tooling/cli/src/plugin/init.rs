@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::helpers::prompts;
 use crate::Result;
 use crate::{
   helpers::{resolve_tauri_path, template},
@@ -152,15 +151,12 @@ pub fn command(mut options: Options) -> Result<()> {
       );
     }
 
-    let plugin_id = if options.android || options.mobile {
-      let plugin_id = prompts::input(
-        "What should be the Android Package ID for your plugin?",
-        Some(format!("com.plugin.{}", plugin_name)),
-        false,
-        false,
-      )?
-      .unwrap();
+    let android = options.android || options.mobile;
+    let ios = options.ios || options.mobile;
+    platform_data(&mut data, android, ios);
 
+    let plugin_id = if android {
+      let plugin_id = super::android_package_id(&plugin_name)?;
       data.insert("android_package_id", to_json(&plugin_id));
       Some(plugin_id)
     } else {
@@ -176,6 +172,11 @@ pub fn command(mut options: Options) -> Result<()> {
       &TEMPLATE_DIR,
       &template_target_path,
       &mut |mut path| {
+        // without Android and iOS projects the plugin is implemented in Rust on every platform
+        if !(android || ios) && path == Path::new("src").join("mobile.rs") {
+          return Ok(None);
+        }
+
         let mut components = path.components();
         let root = components.next().unwrap();
 
@@ -196,7 +197,7 @@ pub fn command(mut options: Options) -> Result<()> {
               }
             }
             "android" => {
-              if options.android || options.mobile {
+              if android {
                 return generate_android_out_file(
                   &path,
                   &template_target_path,
@@ -207,7 +208,7 @@ pub fn command(mut options: Options) -> Result<()> {
                 return Ok(None);
               }
             }
-            "ios-spm" | "ios-xcode" if !(options.ios || options.mobile) => return Ok(None),
+            "ios-spm" | "ios-xcode" if !ios => return Ok(None),
             "ios-spm" if !matches!(ios_framework, IosFrameworkKind::Spm) => return Ok(None),
             "ios-xcode" if !matches!(ios_framework, IosFrameworkKind::Xcode) => return Ok(None),
             "ios-spm" | "ios-xcode" => {
@@ -258,6 +259,22 @@ permissions = ["allow-ping"]
   Ok(())
 }
 
+/// Sets the template data selecting the native (Kotlin or Swift) or Rust implementation of the plugin on each platform.
+fn platform_data(data: &mut BTreeMap<&'static str, serde_json::Value>, android: bool, ios: bool) {
+  data.insert("android", to_json(android));
+  data.insert("ios", to_json(ios));
+  let cfg = match (android, ios) {
+    (true, true) => Some(("mobile", "desktop")),
+    (true, false) => Some((r#"target_os = "android""#, r#"not(target_os = "android")"#)),
+    (false, true) => Some((r#"target_os = "ios""#, r#"not(target_os = "ios")"#)),
+    (false, false) => None,
+  };
+  if let Some((mobile_cfg, desktop_cfg)) = cfg {
+    data.insert("mobile_cfg", to_json(mobile_cfg));
+    data.insert("desktop_cfg", to_json(desktop_cfg));
+  }
+}
+
 pub fn plugin_name_data(data: &mut BTreeMap<&'static str, serde_json::Value>, plugin_name: &str) {
   data.insert("plugin_name_original", to_json(plugin_name));
   data.insert("plugin_name", to_json(plugin_name.to_kebab_case()));
@@ -315,3 +332,67 @@ pub fn generate_android_out_file(
     Ok(None)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{platform_data, plugin_name_data, TEMPLATE_DIR};
+  use handlebars::{to_json, Handlebars};
+  use std::collections::BTreeMap;
+
+  fn render(path: &str, android: bool, ios: bool) -> String {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+
+    let mut data = BTreeMap::new();
+    plugin_name_data(&mut data, "my-plugin");
+    platform_data(&mut data, android, ios);
+    data.insert("android_package_id", to_json("com.plugin.my_plugin"));
+
+    let template = TEMPLATE_DIR
+      .get_file(path)
+      .and_then(|file| file.contents_utf8())
+      .unwrap();
+    handlebars.render_template(template, &data).unwrap()
+  }
+
+  #[test]
+  fn renders_mobile_plugin() {
+    let lib = render("src/lib.rs", true, true);
+    assert!(lib.contains("#[cfg(desktop)]\nmod desktop;\n#[cfg(mobile)]\nmod mobile;"));
+
+    let mobile = render("src/mobile.rs", true, true);
+    assert!(mobile.contains(r#"const PLUGIN_IDENTIFIER: &str = "com.plugin.my_plugin";"#));
+    assert!(mobile.contains("tauri::ios_plugin_binding!(init_plugin_my_plugin);"));
+    assert!(mobile.contains("api.register_ios_plugin(init_plugin_my_plugin)"));
+
+    let build = render("build.rs", true, true);
+    assert!(build.contains(".android_path(\"android\")"));
+    assert!(build.contains(".ios_path(\"ios\")"));
+  }
+
+  #[test]
+  fn renders_single_platform_plugin() {
+    let lib = render("src/lib.rs", true, false);
+    assert!(lib.contains("#[cfg(not(target_os = \"android\"))]\nmod desktop;"));
+    assert!(lib.contains("#[cfg(target_os = \"android\")]\nmod mobile;"));
+
+    let mobile = render("src/mobile.rs", true, false);
+    assert!(mobile.contains("register_android_plugin"));
+    assert!(!mobile.contains("ios"));
+
+    let build = render("build.rs", true, false);
+    assert!(build.contains(".android_path(\"android\")"));
+    assert!(!build.contains(".ios_path"));
+  }
+
+  #[test]
+  fn renders_desktop_plugin() {
+    let lib = render("src/lib.rs", false, false);
+    assert!(lib.contains("\nmod desktop;"));
+    assert!(!lib.contains("mobile"));
+    assert!(!lib.contains("#[cfg("));
+
+    let build = render("build.rs", false, false);
+    assert!(!build.contains("_path"));
+  }
+}
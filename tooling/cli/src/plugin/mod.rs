@@ -5,8 +5,9 @@
 use std::path::Path;
 
 use clap::{Parser, Subcommand};
+use heck::ToSnakeCase;
 
-use crate::Result;
+use crate::{helpers::prompts, Result};
 
 mod android;
 mod init;
@@ -71,3 +72,59 @@ fn infer_plugin_name<P: AsRef<Path>>(directory: P) -> Result<String> {
       .to_string(),
   )
 }
+
+/// Prompts for the Android package ID of the plugin, defaulting to `com.plugin.<plugin_name>`.
+fn android_package_id(plugin_name: &str) -> Result<String> {
+  let package_id = prompts::input(
+    "What should be the Android Package ID for your plugin?",
+    Some(format!("com.plugin.{}", plugin_name.to_snake_case())),
+    false,
+    false,
+  )?
+  .unwrap();
+  validate_android_package_id(&package_id)?;
+  Ok(package_id)
+}
+
+/// Checks that the package ID is a valid Java package name, used by the generated Kotlin sources.
+fn validate_android_package_id(package_id: &str) -> Result<()> {
+  let is_identifier = |segment: &str| {
+    let mut chars = segment.chars();
+    chars
+      .next()
+      .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+      && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+  };
+  if package_id.contains('.') && package_id.split('.').all(is_identifier) {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!(
+      "invalid Android package ID `{package_id}`, expected dot-separated identifiers such as `com.plugin.example`"
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::validate_android_package_id;
+
+  #[test]
+  fn validates_android_package_id() {
+    for valid in [
+      "com.plugin.example",
+      "com.plugin.my_plugin",
+      "app.tauri._internal2",
+    ] {
+      assert!(validate_android_package_id(valid).is_ok(), "{valid}");
+    }
+    for invalid in [
+      "example",
+      "com.plugin.my-plugin",
+      "com.plugin.2fa",
+      "com..plugin",
+      "com.plugin.",
+    ] {
+      assert!(validate_android_package_id(invalid).is_err(), "{invalid}");
+    }
+  }
+}
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::path::{Path, PathBuf};
+use std::{
+  io::BufRead,
+  path::{Path, PathBuf},
+};
 
 use crate::{
   helpers::updater_signature::{secret_key, sign_file},
@@ -13,8 +16,19 @@ use base64::Engine;
 use clap::Parser;
 use tauri_utils::display_path;
 
+/// Environment variables the private key password is read from, in order of precedence,
+/// when it is not passed with `--password`, `--password-file` or `--password-stdin`.
+const PASSWORD_ENV_VARS: [&str; 2] = [
+  "TAURI_SIGNING_PRIVATE_KEY_PASSWORD",
+  "TAURI_PRIVATE_KEY_PASSWORD",
+];
+
+/// Environment variable the private key, or the path to it, is read from
+/// when it is not passed with `--private-key` or `--private-key-path`.
+const PRIVATE_KEY_ENV_VAR: &str = "TAURI_SIGNING_PRIVATE_KEY";
+
 #[derive(Debug, Parser)]
-#[clap(about = "Sign a file")]
+#[clap(about = "Sign files")]
 pub struct Options {
   /// Load the private key from a string
   #[clap(
@@ -32,40 +46,267 @@ pub struct Options {
     env = "TAURI_PRIVATE_KEY_PATH"
   )]
   private_key_path: Option<PathBuf>,
-  /// Set private key password when signing
-  #[clap(short, long, env = "TAURI_PRIVATE_KEY_PASSWORD")]
+  /// Set private key password when signing.
+  ///
+  /// Prefer `--password-file`, `--password-stdin` or the `TAURI_SIGNING_PRIVATE_KEY_PASSWORD` environment variable
+  /// so the password does not end up in the shell history.
+  #[clap(short, long, conflicts_with_all(["password_file", "password_stdin"]))]
   password: Option<String>,
-  /// Sign the specified file
-  file: PathBuf,
+  /// Read the private key password from the first line of a file
+  #[clap(long, conflicts_with_all(["password", "password_stdin"]))]
+  password_file: Option<PathBuf>,
+  /// Read the private key password from the first line of the standard input
+  #[clap(long, conflicts_with_all(["password", "password_file"]))]
+  password_stdin: bool,
+  /// Print a JSON object mapping each signed file to its signature
+  #[clap(long)]
+  json: bool,
+  /// The files to sign, glob patterns are expanded. A `.sig` file is written next to each of them.
+  #[clap(required = true)]
+  files: Vec<String>,
 }
 
-pub fn command(mut options: Options) -> Result<()> {
-  options.private_key = if let Some(private_key) = options.private_key_path {
-    Some(std::fs::read_to_string(Path::new(&private_key)).expect("Unable to extract private key"))
-  } else {
-    options.private_key
-  };
-  let private_key = if let Some(pk) = options.private_key {
-    pk
-  } else {
-    return Err(anyhow::anyhow!(
-      "Key generation aborted: Unable to find the private key".to_string(),
-    ));
-  };
-
-  if options.password.is_none() {
+pub fn command(options: Options) -> Result<()> {
+  let private_key = private_key(&options, |key| std::env::var(key).ok())?;
+  let password = password(
+    &options,
+    |key| std::env::var(key).ok(),
+    std::io::stdin().lock(),
+  )?;
+
+  if password.is_none() && !options.json {
     println!("Signing without password.");
   }
 
-  let (manifest_dir, signature) =
-    sign_file(&secret_key(private_key, options.password)?, options.file)
-      .with_context(|| "failed to sign file")?;
+  let files = expand_files(&options.files)?;
+  // the key is only decrypted once for all the files
+  let secret_key = secret_key(private_key, password)?;
+
+  let mut signatures = serde_json::Map::new();
+  for file in files {
+    let (signature_path, signature) = sign_file(&secret_key, &file)
+      .with_context(|| format!("failed to sign {}", file.display()))?;
+    let signature = base64::engine::general_purpose::STANDARD.encode(signature.to_string());
 
-  println!(
+    if options.json {
+      signatures.insert(
+        file.display().to_string(),
+        serde_json::json!({
+          "path": signature_path,
+          "signature": signature,
+        }),
+      );
+    } else {
+      println!(
            "\nYour file was signed successfully, You can find the signature here:\n{}\n\nPublic signature:\n{}\n\nMake sure to include this into the signature field of your update server.",
-           display_path(manifest_dir),
-           base64::engine::general_purpose::STANDARD.encode(signature.to_string())
+           display_path(signature_path),
+           signature
          );
+    }
+  }
+
+  if options.json {
+    println!("{}", serde_json::to_string_pretty(&signatures)?);
+  }
 
   Ok(())
 }
+
+/// Reads the private key from `--private-key`, `--private-key-path` or the [`PRIVATE_KEY_ENV_VAR`] environment variable.
+fn private_key(options: &Options, env: impl Fn(&str) -> Option<String>) -> Result<String> {
+  if let Some(path) = &options.private_key_path {
+    return std::fs::read_to_string(path)
+      .with_context(|| format!("failed to read the private key file {}", path.display()));
+  }
+  if let Some(private_key) = &options.private_key {
+    return Ok(private_key.clone());
+  }
+  match env(PRIVATE_KEY_ENV_VAR) {
+    // the environment variable is either the key or the path to it
+    Some(private_key) if Path::new(&private_key).is_file() => std::fs::read_to_string(&private_key)
+      .with_context(|| format!("failed to read the private key file {private_key}")),
+    Some(private_key) => Ok(private_key),
+    None => Err(anyhow::anyhow!(
+      "Unable to find the private key, set it with `--private-key`, `--private-key-path` or the `{PRIVATE_KEY_ENV_VAR}` environment variable"
+    )),
+  }
+}
+
+/// Reads the private key password, in order of precedence, from `--password`, `--password-file`, `--password-stdin`
+/// or the [`PASSWORD_ENV_VARS`] environment variables.
+///
+/// Returns `None` when no password is set, in which case it is prompted for if the key is encrypted.
+fn password(
+  options: &Options,
+  env: impl Fn(&str) -> Option<String>,
+  stdin: impl BufRead,
+) -> Result<Option<String>> {
+  if let Some(password) = &options.password {
+    return Ok(Some(password.clone()));
+  }
+
+  if let Some(path) = &options.password_file {
+    let file = std::fs::File::open(path)
+      .with_context(|| format!("failed to open the password file {}", path.display()))?;
+    return first_line(std::io::BufReader::new(file))
+      .with_context(|| format!("failed to read the password file {}", path.display()))
+      .map(Some);
+  }
+
+  if options.password_stdin {
+    return first_line(stdin)
+      .context("failed to read the password from the standard input")
+      .map(Some);
+  }
+
+  Ok(PASSWORD_ENV_VARS.iter().find_map(|key| env(key)))
+}
+
+fn first_line(mut reader: impl BufRead) -> std::io::Result<String> {
+  let mut line = String::new();
+  reader.read_line(&mut line)?;
+  if line.ends_with('\n') {
+    line.pop();
+    if line.ends_with('\r') {
+      line.pop();
+    }
+  }
+  Ok(line)
+}
+
+/// Expands the glob patterns of the files to sign, skipping existing signatures.
+fn expand_files(patterns: &[String]) -> Result<Vec<PathBuf>> {
+  let mut files = Vec::new();
+  for pattern in patterns {
+    let path = Path::new(pattern);
+    if path.is_file() {
+      files.push(path.to_path_buf());
+      continue;
+    }
+
+    let matches = glob::glob(pattern)
+      .with_context(|| format!("invalid file pattern `{pattern}`"))?
+      .filter_map(|entry| entry.ok())
+      .filter(|path| path.is_file() && path.extension().map_or(true, |ext| ext != "sig"))
+      .collect::<Vec<_>>();
+    if matches.is_empty() {
+      anyhow::bail!("no file to sign matches `{pattern}`");
+    }
+    files.extend(matches);
+  }
+  Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{expand_files, password, private_key, Options};
+  use clap::Parser;
+
+  fn options(args: &[&str]) -> Options {
+    Options::try_parse_from(["sign"].iter().chain(args)).unwrap()
+  }
+
+  fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    move |key| {
+      vars
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.to_string())
+    }
+  }
+
+  #[test]
+  fn password_precedence() {
+    let tmp = tempfile::tempdir().unwrap();
+    let password_file = tmp.path().join("password");
+    std::fs::write(&password_file, "from file\r\nsecond line").unwrap();
+    let password_file = password_file.to_str().unwrap();
+
+    let vars = [
+      ("TAURI_SIGNING_PRIVATE_KEY_PASSWORD", "from env"),
+      ("TAURI_PRIVATE_KEY_PASSWORD", "from legacy env"),
+    ];
+    let stdin = "from stdin\n".as_bytes();
+
+    for (args, vars, expected) in [
+      (
+        &["--password", "from flag", "file"][..],
+        &vars[..],
+        Some("from flag"),
+      ),
+      (
+        &["--password-file", password_file, "file"][..],
+        &vars[..],
+        Some("from file"),
+      ),
+      (
+        &["--password-stdin", "file"][..],
+        &vars[..],
+        Some("from stdin"),
+      ),
+      (&["file"][..], &vars[..], Some("from env")),
+      (&["file"][..], &vars[1..], Some("from legacy env")),
+      (&["file"][..], &[][..], None),
+    ] {
+      assert_eq!(
+        password(&options(args), env(vars), stdin)
+          .unwrap()
+          .as_deref(),
+        expected,
+        "{args:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn password_sources_are_exclusive() {
+    for args in [
+      &["--password", "a", "--password-stdin", "file"][..],
+      &["--password", "a", "--password-file", "p", "file"][..],
+      &["--password-file", "p", "--password-stdin", "file"][..],
+    ] {
+      assert!(Options::try_parse_from(["sign"].iter().chain(args)).is_err());
+    }
+  }
+
+  #[test]
+  fn missing_private_key() {
+    let error = private_key(&options(&["file"]), env(&[])).unwrap_err();
+    assert!(error
+      .to_string()
+      .starts_with("Unable to find the private key"));
+    assert_eq!(
+      private_key(
+        &options(&["file"]),
+        env(&[("TAURI_SIGNING_PRIVATE_KEY", "key")])
+      )
+      .unwrap(),
+      "key"
+    );
+  }
+
+  #[test]
+  fn expands_file_patterns() {
+    let tmp = tempfile::tempdir().unwrap();
+    for file in ["app.tar.gz", "app.tar.gz.sig", "app.msi", "other.txt"] {
+      std::fs::write(tmp.path().join(file), file).unwrap();
+    }
+
+    let mut files = expand_files(&[
+      tmp.path().join("app.*").to_string_lossy().into_owned(),
+      tmp.path().join("other.txt").to_string_lossy().into_owned(),
+    ])
+    .unwrap();
+    files.sort();
+    assert_eq!(
+      files,
+      vec![
+        tmp.path().join("app.msi"),
+        tmp.path().join("app.tar.gz"),
+        tmp.path().join("other.txt"),
+      ]
+    );
+
+    assert!(expand_files(&[tmp.path().join("*.dmg").to_string_lossy().into_owned()]).is_err());
+  }
+}
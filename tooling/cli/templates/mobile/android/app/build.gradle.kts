@@ -26,6 +26,18 @@ android {
         versionCode = tauriProperties.getProperty("tauri.android.versionCode", "1").toInt()
         versionName = tauriProperties.getProperty("tauri.android.versionName", "1.0")
     }
+    signingConfigs {
+        // set by `tauri android build` from `bundle > android > signing`
+        val keystorePath = findProperty("tauriSigningStoreFile") as String?
+        if (keystorePath != null) {
+            create("release") {
+                storeFile = file(keystorePath)
+                storePassword = findProperty("tauriSigningStorePassword") as String?
+                keyAlias = findProperty("tauriSigningKeyAlias") as String?
+                keyPassword = findProperty("tauriSigningKeyPassword") as String?
+            }
+        }
+    }
     buildTypes {
         getByName("debug") {
             manifestPlaceholders["usesCleartextTraffic"] = "true"
@@ -39,6 +51,7 @@ android {
             }
         }
         getByName("release") {
+            signingConfig = signingConfigs.findByName("release")
             isMinifyEnabled = true
             proguardFiles(
                 *fileTree(".") { include("**/*.pro") }
@@ -1,5 +1,7 @@
 import com.android.build.api.dsl.ApplicationExtension
+import com.android.build.api.variant.ApplicationAndroidComponentsExtension
 import org.gradle.api.DefaultTask
+import org.gradle.api.GradleException
 import org.gradle.api.Plugin
 import org.gradle.api.Project
 import org.gradle.kotlin.dsl.configure
@@ -46,6 +48,22 @@ open class RustPlugin : Plugin<Project> {
             }
         }
 
+        // the per-ABI artifacts of `--split-per-abi` need distinct version codes to be published together
+        extensions.configure<ApplicationAndroidComponentsExtension> {
+            onVariants { variant ->
+                val abiIndex = defaultArchList.indexOf(variant.flavorName)
+                if (abiIndex >= 0) {
+                    variant.outputs.forEach { output ->
+                        val versionCode = (output.versionCode.orNull ?: 1) * 10 + abiIndex + 1
+                        if (versionCode > 2100000000) {
+                            throw GradleException("The version code $versionCode of the ${variant.flavorName} APK exceeds 2100000000. Lower the `bundle > android > versionCode` in tauri.conf.json.")
+                        }
+                        output.versionCode.set(versionCode)
+                    }
+                }
+            }
+        }
+
         afterEvaluate {
             for (profile in listOf("debug", "release")) {
                 val profileCapitalized = profile.replaceFirstChar { it.uppercase() }
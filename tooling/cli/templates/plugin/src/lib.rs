@@ -8,10 +8,14 @@ use tauri::{
 
 pub use models::*;
 
-#[cfg(desktop)]
+{{#if mobile_cfg}}
+#[cfg({{ desktop_cfg }})]
 mod desktop;
-#[cfg(mobile)]
+#[cfg({{ mobile_cfg }})]
 mod mobile;
+{{else}}
+mod desktop;
+{{/if}}
 
 mod commands;
 mod error;
@@ -19,10 +23,14 @@ mod models;
 
 pub use error::{Error, Result};
 
-#[cfg(desktop)]
+{{#if mobile_cfg}}
+#[cfg({{ desktop_cfg }})]
 use desktop::{{ plugin_name_pascal_case }};
-#[cfg(mobile)]
+#[cfg({{ mobile_cfg }})]
 use mobile::{{ plugin_name_pascal_case }};
+{{else}}
+use desktop::{{ plugin_name_pascal_case }};
+{{/if}}
 
 /// Extensions to [`tauri::App`], [`tauri::AppHandle`] and [`tauri::Window`] to access the {{ plugin_name }} APIs.
 pub trait {{ plugin_name_pascal_case }}Ext<R: Runtime> {
@@ -40,10 +48,14 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
   Builder::new("{{ plugin_name }}")
     .invoke_handler(tauri::generate_handler![commands::ping])
     .setup(|app, api| {
-      #[cfg(mobile)]
+{{#if mobile_cfg}}
+      #[cfg({{ mobile_cfg }})]
       let {{ plugin_name_snake_case }} = mobile::init(app, api)?;
-      #[cfg(desktop)]
+      #[cfg({{ desktop_cfg }})]
+      let {{ plugin_name_snake_case }} = desktop::init(app, api)?;
+{{else}}
       let {{ plugin_name_snake_case }} = desktop::init(app, api)?;
+{{/if}}
       app.manage({{ plugin_name_snake_case }});
       Ok(())
     })
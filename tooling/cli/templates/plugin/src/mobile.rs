@@ -9,21 +9,29 @@ use tauri::{
 
 use crate::models::*;
 
+{{#if android}}
 #[cfg(target_os = "android")]
 const PLUGIN_IDENTIFIER: &str = "{{ android_package_id }}";
 
+{{/if}}
+{{#if ios}}
 #[cfg(target_os = "ios")]
-tauri::ios_plugin_binding!(init_plugin_{{ plugin_name }});
+tauri::ios_plugin_binding!(init_plugin_{{ plugin_name_snake_case }});
 
+{{/if}}
 // initializes the Kotlin or Swift plugin classes
 pub fn init<R: Runtime, C: DeserializeOwned>(
   _app: &AppHandle<R>,
   api: PluginApi<R, C>,
 ) -> crate::Result<{{ plugin_name_pascal_case }}<R>> {
+{{#if android}}
   #[cfg(target_os = "android")]
   let handle = api.register_android_plugin(PLUGIN_IDENTIFIER, "ExamplePlugin")?;
+{{/if}}
+{{#if ios}}
   #[cfg(target_os = "ios")]
-  let handle = api.register_ios_plugin(init_plugin_{{ plugin_name }})?;
+  let handle = api.register_ios_plugin(init_plugin_{{ plugin_name_snake_case }})?;
+{{/if}}
   Ok({{ plugin_name_pascal_case }}(handle))
 }
 